@@ -34,6 +34,10 @@ typedef enum
   unfulfilled,
   expired,
   revoked,
+  redirected,
+  rejected,
+  proposal_received = 10,
+  proposal_sent = 11,
 } vcx_state_t;
 
 typedef enum
@@ -110,6 +114,195 @@ vcx_error_t vcx_connection_create(vcx_command_handle_t command_handle,
                                const char *source_id,
                                void (*cb)(vcx_command_handle_t, vcx_error_t, vcx_connection_handle_t));
 
+// Creates a connection object which invitation can be used by many identity owners.
+// Every connection request received on the invitation creates a new connection object.
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// source_id: institution's personal identification for the connection
+//
+// cb: Callback that provides connection handle and error status of request
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_connection_create_multi_use(vcx_command_handle_t command_handle,
+                                         const char *source_id,
+                                         void (*cb)(vcx_command_handle_t, vcx_error_t, vcx_connection_handle_t));
+
+// Answers connection requests received on the multi-use invitation since the last call.
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// connection_handle: handle of the connection created by vcx_connection_create_multi_use
+//
+// cb: Callback that provides JSON array of new connection handles and error status of request
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_connection_get_new_connections(vcx_command_handle_t command_handle,
+                                            vcx_connection_handle_t connection_handle,
+                                            void (*cb)(vcx_command_handle_t, vcx_error_t, const char*));
+
+// Requests mediation (Aries RFC 0211) over an established connection.
+// Connections created afterwards are routed through the mediator.
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// connection_handle: completed connection to the mediator
+//
+// cb: Callback that provides mediator json, it can be passed as `mediator` field of vcx_init config
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_mediator_request(vcx_command_handle_t command_handle,
+                                 vcx_connection_handle_t connection_handle,
+                                 void (*cb)(vcx_command_handle_t, vcx_error_t, const char*));
+
+// Retrieves the number of messages queued by the mediator.
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// cb: Callback that provides the number of queued messages
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_mediator_get_message_count(vcx_command_handle_t command_handle,
+                                           void (*cb)(vcx_command_handle_t, vcx_error_t, vcx_u32_t));
+
+// Picks up messages queued by the mediator (Aries RFC 0212) and updates the connections and objects they belong to.
+// Only processed messages are removed from the mediator's queue.
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// batch_size: maximum number of messages to pick up
+//
+// cb: Callback that provides JSON array of picked up messages with their recipient keys and connection handles
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_mediator_pickup(vcx_command_handle_t command_handle,
+                                vcx_u32_t batch_size,
+                                void (*cb)(vcx_command_handle_t, vcx_error_t, const char*));
+
+// Creates a router which mediates for other agents (Aries RFC 0211, 0212).
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// source_id: institution's personal identification for the router
+//
+// endpoint: URL the router receives messages on
+//
+// cb: Callback that provides router handle and error status of request
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_router_create(vcx_command_handle_t command_handle,
+                              const char *source_id,
+                              const char *endpoint,
+                              void (*cb)(vcx_command_handle_t, vcx_error_t, vcx_u32_t));
+
+// Allows the counterparty of the connection to request mediation from the router.
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// router_handle: router handle that was provided during creation
+//
+// connection_handle: completed connection to the client
+//
+// cb: Callback that provides error status of request
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_router_add_connection(vcx_command_handle_t command_handle,
+                                      vcx_u32_t router_handle,
+                                      vcx_connection_handle_t connection_handle,
+                                      void (*cb)(vcx_command_handle_t, vcx_error_t));
+
+// Processes the packed message received on the router's endpoint.
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// router_handle: router handle that was provided during creation
+//
+// message_raw: raw packed message as it was received
+//
+// message_len: length of message buffer
+//
+// cb: Callback that provides the packed answer to send back in the response, empty if there is nothing to return
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_router_receive_message(vcx_command_handle_t command_handle,
+                                       vcx_u32_t router_handle,
+                                       const unsigned char *message_raw,
+                                       vcx_u32_t message_len,
+                                       void (*cb)(vcx_command_handle_t, vcx_error_t, const unsigned char*, vcx_u32_t));
+
+// Takes the router object and returns a json string of all its attributes including queued messages
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// router_handle: router handle that was provided during creation
+//
+// cb: Callback that provides json string of the router's attributes
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_router_serialize(vcx_command_handle_t command_handle,
+                                 vcx_u32_t router_handle,
+                                 void (*cb)(vcx_command_handle_t, vcx_error_t, const char*));
+
+// Takes a json string representing a router object and recreates an object matching the json
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// router_data: json string representing a router object, output of vcx_router_serialize
+//
+// cb: Callback that provides router handle and error status of request
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_router_deserialize(vcx_command_handle_t command_handle,
+                                   const char *router_data,
+                                   void (*cb)(vcx_command_handle_t, vcx_error_t, vcx_u32_t));
+
+// Releases the router object by de-allocating memory
+//
+// #Params
+// router_handle: router handle that was provided during creation
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_router_release(vcx_u32_t router_handle);
+
+// Creates a connection object to a specific identity owner using the selected handshake protocol.
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// source_id: institution's personal identification for the user
+//
+// handshake_protocol: "connections/1.0" or "didexchange/1.0"
+//
+// cb: Callback that provides connection handle and error status of request
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_connection_create_with_handshake_protocol(vcx_command_handle_t command_handle,
+                                                          const char *source_id,
+                                                          const char *handshake_protocol,
+                                                          void (*cb)(vcx_command_handle_t, vcx_error_t, vcx_connection_handle_t));
+
 // Create a Connection object from the given invite_details that provides a pairwise connection.
 //
 // #Params
@@ -117,7 +310,8 @@ vcx_error_t vcx_connection_create(vcx_command_handle_t command_handle,
 //
 // source_id: institution's personal identification for the user
 //
-// invite_details: Provided via the other end of the connection calling "vcx_connection_connect" or "vcx_connection_invite_details"
+// invite_details: Provided via the other end of the connection calling "vcx_connection_connect" or "vcx_connection_invite_details".
+//     May also be a public DID invitation referencing only the inviter's "did", its verkey and endpoint are resolved from the ledger.
 //
 // cb: Callback that provides connection handle and error status of request
 //
@@ -190,6 +384,46 @@ vcx_error_t vcx_connection_invite_details(vcx_command_handle_t command_handle,
                                        vcx_bool_t abbreviated,
                                        void (*cb)(vcx_command_handle_t, vcx_error_t, const char*));
 
+// Gets Out-of-Band invitation for Inviter connection in OfferSent state
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// connection_handle: was provided during creation. Used to identify connection object
+//
+// requests: (Optional) JSON array of aries messages to attach to the invitation
+//
+// goal_code: (Optional) code the receiver may use in automatically deciding what to do
+//
+// goal: (Optional) string the receiver may want to display to the user
+//
+// cb: Callback that provides the json string of Out-of-Band invitation
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_connection_get_out_of_band_invite_details(vcx_command_handle_t command_handle,
+                                                          vcx_connection_handle_t connection_handle,
+                                                          const char* requests,
+                                                          const char* goal_code,
+                                                          const char* goal,
+                                                          void (*cb)(vcx_command_handle_t, vcx_error_t, const char*));
+
+// Gets the messages attached to Out-of-Band invitation (`requests~attach`), e.g. credential offer or presentation request.
+// Invitation without handshake protocols carries only requests, connection created from it answers them directly.
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// invite_details: Out-of-Band invitation received from the inviter
+//
+// cb: Callback that provides JSON array of the attached aries messages
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_connection_get_out_of_band_requests(vcx_command_handle_t command_handle,
+                                                    const char* invite_details,
+                                                    void (*cb)(vcx_command_handle_t, vcx_error_t, const char*));
+
 /// Get the information about the connection state.
 ///
 /// Note: This method can be used for `aries` communication method only.
@@ -313,6 +547,86 @@ vcx_error_t vcx_connection_send_discovery_features(vcx_u32_t command_handle,
                                                    const char* comment,
                                                    void (*cb)(vcx_command_handle_t, vcx_error_t));
 
+/// Sign the field of the message with connection pairwise key.
+/// The field is replaced by `<field>~sig` signature decorator (`signature/1.0/ed25519Sha512_single`).
+///
+/// #params
+///
+/// command_handle: command handle to map callback to user context.
+///
+/// connection_handle: connection to use to sign the field
+///
+/// message: JSON message containing the field to sign
+///
+/// field: name of the field to sign
+///
+/// cb: Callback that provides the message with signed field
+///
+/// #Returns
+/// Error code as a u32
+vcx_error_t vcx_connection_sign_message_field(vcx_command_handle_t command_handle,
+                                              vcx_connection_handle_t connection_handle,
+                                              const char* message,
+                                              const char* field,
+                                              void (*cb)(vcx_command_handle_t, vcx_error_t, const char*));
+
+/// Verify all `~sig` decorated fields of the message with the key of connection counterparty.
+/// The signed fields are replaced by the values they contain.
+///
+/// #params
+///
+/// command_handle: command handle to map callback to user context.
+///
+/// connection_handle: connection to use to verify the fields
+///
+/// message: JSON message containing signed fields
+///
+/// cb: Callback that provides the message with verified field values
+///
+/// #Returns
+/// Error code as a u32
+vcx_error_t vcx_connection_verify_message_fields(vcx_command_handle_t command_handle,
+                                                 vcx_connection_handle_t connection_handle,
+                                                 const char* message,
+                                                 void (*cb)(vcx_command_handle_t, vcx_error_t, const char*));
+
+/// Get the messages sent to the connection with `~please_ack` decorator and status of their acknowledgement.
+///
+/// #params
+///
+/// command_handle: command handle to map callback to user context.
+///
+/// connection_handle: was provided during creation. Used to identify connection object
+///
+/// cb: Callback that provides JSON array of the messages:
+///     [{"id": <str>, "thread_id": <str>, "sent": <str>, "status": Optional<str>, "acknowledged": Optional<str>}]
+///
+/// #Returns
+/// Error code as a u32
+vcx_error_t vcx_connection_get_ack_requests(vcx_command_handle_t command_handle,
+                                            vcx_connection_handle_t connection_handle,
+                                            void (*cb)(vcx_command_handle_t, vcx_error_t, const char*));
+
+/// Enables tracing of the messages exchanged on the connection with `~trace` decorator,
+/// or disables it if no target is given.
+///
+/// #params
+///
+/// command_handle: command handle to map callback to user context.
+///
+/// connection_handle: was provided during creation. Used to identify connection object
+///
+/// target: (Optional) target of trace reports: "log", "file://<absolute path>" or "http(s)://<url>"
+///
+/// cb: Callback that provides error status of the request
+///
+/// #Returns
+/// Error code as a u32
+vcx_error_t vcx_connection_set_trace_target(vcx_command_handle_t command_handle,
+                                            vcx_connection_handle_t connection_handle,
+                                            const char* target,
+                                            void (*cb)(vcx_command_handle_t, vcx_error_t));
+
 // Takes the Connection object and returns callers pw_did associated with this connection
 //
 // #Params
@@ -376,6 +690,51 @@ vcx_error_t vcx_credential_create_with_offer(vcx_command_handle_t command_handle
                                           const char *offer,
                                           void (*cb)(vcx_command_handle_t, vcx_error_t, vcx_credential_handle_t));
 
+// Create a Credential object which starts the issuance by proposing a credential to the issuer.
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// source_id: Institution's personal identification for the credential, should be unique.
+//
+// schema_id: id of the schema the proposed credential is based on
+//
+// cred_def_id: id of the credential definition the credential is expected to be issued with
+//
+// credential_data: proposed attribute values, e.g. {"name": "Alice", "age": "25"}
+//
+// comment: (Optional) human-friendly comment attached to the proposal
+//
+// cb: Callback that provides credential handle or error status
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_credential_create_with_proposal(vcx_command_handle_t command_handle,
+                                             const char *source_id,
+                                             const char *schema_id,
+                                             const char *cred_def_id,
+                                             const char *credential_data,
+                                             const char *comment,
+                                             void (*cb)(vcx_command_handle_t, vcx_error_t, vcx_credential_handle_t));
+
+// Sends the credential proposal of the Credential object created with `vcx_credential_create_with_proposal`.
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// credential_handle: credential handle that was provided during creation. Used to identify credential object
+//
+// connection_handle: Connection handle that identifies pairwise connection
+//
+// cb: Callback that provides error status of sending the proposal
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_credential_send_proposal(vcx_command_handle_t command_handle,
+                                      vcx_credential_handle_t credential_handle,
+                                      vcx_connection_handle_t connection_handle,
+                                      void (*cb)(vcx_command_handle_t, vcx_error_t));
+
 // Takes a json string representing an credential object and recreates an object matching the json
 //
 // #Params
@@ -916,6 +1275,45 @@ vcx_error_t vcx_disclosed_proof_retrieve_credentials(vcx_command_handle_t comman
                                                   vcx_disclosed_proof_handle_t proof_handle,
                                                   void (*cb)(vcx_command_handle_t, vcx_error_t, const char*));
 
+// Create a Proof object which starts the exchange by proposing a presentation to the verifier
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// source_id: Institution's identification for the proof, should be unique.
+//
+// proposal: presentation preview describing attributes and predicates the prover is going to disclose
+//
+// comment: (Optional) human-readable comment attached to the proposal
+//
+// cb: Callback that provides proof handle or error status
+//
+// #Returns
+// Error code as u32
+vcx_error_t vcx_disclosed_proof_create_with_proposal(vcx_command_handle_t command_handle,
+                                                  const char *source_id,
+                                                  const char *proposal,
+                                                  const char *comment,
+                                                  void (*cb)(vcx_command_handle_t, vcx_error_t, vcx_disclosed_proof_handle_t));
+
+// Send the presentation proposal of the proof object created with `vcx_disclosed_proof_create_with_proposal`.
+//
+// #params
+// command_handle: command handle to map callback to API user context.
+//
+// proof_handle: proof handle that was provided duration creation.  Used to identify proof object.
+//
+// connection_handle: Connection handle that identifies pairwise connection
+//
+// cb: Callback that provides error status of proposal send request
+//
+// #Returns
+// Error code as u32
+vcx_error_t vcx_disclosed_proof_send_proposal(vcx_command_handle_t command_handle,
+                                           vcx_disclosed_proof_handle_t proof_handle,
+                                           vcx_connection_handle_t connection_handle,
+                                           void (*cb)(vcx_command_handle_t, vcx_error_t));
+
 // Send a proof to the connection, called after having received a proof request
 //
 // #params
@@ -1145,6 +1543,32 @@ vcx_error_t vcx_issuer_create_credential(vcx_command_handle_t command_handle,
                                       vcx_payment_handle_t price,
                                       void (*cb)(vcx_command_handle_t, vcx_error_t, vcx_issuer_credential_handle_t));
 
+// Create a Issuer Credential object for Credential Proposal the holder sent without receiving an offer first.
+// The object is in proposal_received state, proposal can be answered with `vcx_issuer_send_counter_offer`
+// or declined with `vcx_issuer_decline_credential_proposal`.
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// source_id: Enterprise's personal identification for the user.
+//
+// cred_def_handle: handle of credential definition the proposal refers to
+//
+// connection_handle: Connection handle that identifies pairwise connection the proposal was received on
+//
+// proposal: received Credential Proposal message
+//
+// cb: Callback that provides credential handle and error status of request
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_issuer_create_credential_from_proposal(vcx_command_handle_t command_handle,
+                                                    const char *source_id,
+                                                    vcx_credentialdef_handle_t cred_def_handle,
+                                                    vcx_connection_handle_t connection_handle,
+                                                    const char *proposal,
+                                                    void (*cb)(vcx_command_handle_t, vcx_error_t, vcx_issuer_credential_handle_t));
+
 // Takes a json string representing an issuer credential object and recreates an object matching the json
 //
 // #Params
@@ -1288,6 +1712,63 @@ vcx_error_t vcx_issuer_get_credential_offer_msg(vcx_command_handle_t command_han
                                                 vcx_issuer_credential_handle_t credential_handle,
                                                 void (*cb)(vcx_command_handle_t, vcx_error_t, const char*));
 
+// Gets the Credential Proposal received from the holder (in VcxStateProposalReceived state).
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// credential_handle: Credential handle that was provided during creation. Used to identify credential object
+//
+// cb: Callback that provides the proposal message json and error status
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_issuer_get_credential_proposal(vcx_command_handle_t command_handle,
+                                               vcx_issuer_credential_handle_t credential_handle,
+                                               void (*cb)(vcx_command_handle_t, vcx_error_t, const char*));
+
+// Answers the received Credential Proposal with a new Credential Offer on the same thread.
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// credential_handle: Credential handle that was provided during creation. Used to identify credential object
+//
+// connection_handle: Connection handle that identifies pairwise connection
+//
+// comment: (Optional) human-friendly comment attached to the offer
+//
+// cb: Callback that provides error status of sending the offer
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_issuer_send_counter_offer(vcx_command_handle_t command_handle,
+                                          vcx_issuer_credential_handle_t credential_handle,
+                                          vcx_connection_handle_t connection_handle,
+                                          const char *comment,
+                                          void (*cb)(vcx_command_handle_t, vcx_error_t));
+
+// Declines the received Credential Proposal by sending a Problem Report with the given reason.
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// credential_handle: Credential handle that was provided during creation. Used to identify credential object
+//
+// connection_handle: Connection handle that identifies pairwise connection
+//
+// reason: human-readable reason of declining
+//
+// cb: Callback that provides error status of declining the proposal
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_issuer_decline_credential_proposal(vcx_command_handle_t command_handle,
+                                                   vcx_issuer_credential_handle_t credential_handle,
+                                                   vcx_connection_handle_t connection_handle,
+                                                   const char *reason,
+                                                   void (*cb)(vcx_command_handle_t, vcx_error_t));
+
 // Takes the credential object and returns a credential (For sending purposes)
 //
 // #Params
@@ -1375,6 +1856,169 @@ vcx_error_t vcx_messages_update_status(vcx_command_handle_t command_handle,
                                     const char *msg_json,
                                     void (*cb)(vcx_command_handle_t, vcx_error_t));
 
+// Updates state of all connections, credentials and proofs at once.
+// Messages are routed to the objects by their thread, new credential and proof objects are created
+// for received credential offers and presentation requests.
+//
+// #params
+//
+// command_handle: command handle to map callback to user context.
+//
+// cb: Callback that provides array of updated objects: [{"type":"credential","handle":1,"connection_handle":2,"created":true}]
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_update_all(vcx_command_handle_t command_handle,
+                           void (*cb)(vcx_command_handle_t, vcx_error_t, const char*));
+
+// Loads connection, credential or proof object persisted in the wallet (see `persist_objects` config option).
+//
+// #params
+//
+// command_handle: command handle to map callback to user context.
+//
+// record_id: id of the wallet record: "<type>:<pairwise did>" for connections, "<type>:<thread id>" for others.
+//
+// cb: Callback that provides the loaded object: {"id":"credential:<thread id>","type":"credential","handle":1}
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_persisted_object_load(vcx_command_handle_t command_handle,
+                                      const char *record_id,
+                                      void (*cb)(vcx_command_handle_t, vcx_error_t, const char*));
+
+// Loads all connections, credentials and proofs persisted in the wallet matching the query.
+//
+// #params
+//
+// command_handle: command handle to map callback to user context.
+//
+// query_json: WQL query over record tags: "type", "source_id", "thread_id" and "state".
+//
+// cb: Callback that provides array of loaded objects: [{"id":"connection:<pairwise did>","type":"connection","handle":1}]
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_persisted_objects_load(vcx_command_handle_t command_handle,
+                                       const char *query_json,
+                                       void (*cb)(vcx_command_handle_t, vcx_error_t, const char*));
+
+// Retrieves the history of state transitions of connection, credential or proof.
+//
+// #params
+//
+// command_handle: command handle to map callback to user context.
+//
+// object_type: "connection" | "credential" | "disclosed_proof" | "issuer_credential" | "proof"
+//
+// handle: handle of the object
+//
+// cb: Callback that provides json array of transitions ordered from the oldest:
+//     [{"timestamp":"<RFC 3339>","old_state":1,"new_state":2,"message_type":"CredentialInit","outcome":"in_progress"}]
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_get_state_history(vcx_command_handle_t command_handle,
+                                  const char *object_type,
+                                  vcx_u32_t handle,
+                                  void (*cb)(vcx_command_handle_t, vcx_error_t, const char*));
+
+// Abandons credential issuances and proof requests which expired without an answer of the counterparty
+// (see `protocol_timeouts` config option). A problem report is sent to the counterparty of every abandoned exchange.
+//
+// #params
+//
+// command_handle: command handle to map callback to user context.
+//
+// cb: Callback that provides array of abandoned objects: [{"type":"issuer_credential","handle":1}]
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_sweep_expired(vcx_command_handle_t command_handle,
+                              void (*cb)(vcx_command_handle_t, vcx_error_t, const char*));
+
+// Retrieves messages queued in the outbox because they could not be delivered to the counterparty
+// (the outbox is enabled by `outbox` config option set to "true").
+//
+// #params
+//
+// command_handle: command handle to map callback to user context.
+//
+// cb: Callback that provides array of queued messages:
+//     [{"id":"<@id>","endpoint":"<url>","payload":"<base64>","status":"pending","attempts":1,"created":"<RFC 3339>","next_attempt":"<RFC 3339>"}]
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_outbox_get_messages(vcx_command_handle_t command_handle,
+                                    void (*cb)(vcx_command_handle_t, vcx_error_t, const char*));
+
+// Removes the message from the outbox, so its delivery is not retried anymore.
+//
+// #params
+//
+// command_handle: command handle to map callback to user context.
+//
+// message_id: @id of the queued message.
+//
+// cb: Callback that provides error status of the cancellation
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_outbox_cancel(vcx_command_handle_t command_handle,
+                              const char *message_id,
+                              void (*cb)(vcx_command_handle_t, vcx_error_t));
+
+// Retries delivery of the queued messages whose backoff elapsed.
+//
+// #params
+//
+// command_handle: command handle to map callback to user context.
+//
+// cb: Callback that provides array of ids of the delivered messages
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_outbox_flush(vcx_command_handle_t command_handle,
+                             void (*cb)(vcx_command_handle_t, vcx_error_t, const char*));
+
+// Registers the application's transport delivering messages to endpoints of the given URL scheme.
+// It replaces the transport registered before for the scheme, including built-in HTTP(S) and WebSocket ones.
+//
+// #params
+//
+// command_handle: command handle to map callback to user context, passed to every send_cb call.
+//
+// scheme: URL scheme of the endpoints, e.g. "https" or "didcomm"
+//
+// send_cb: Callback called from a library thread with the endpoint and the packed message to deliver.
+//     The library waits until the application completes the send with vcx_transport_set_response
+//     using the provided send_handle.
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_register_transport(vcx_command_handle_t command_handle,
+                                   const char *scheme,
+                                   void (*send_cb)(vcx_command_handle_t, vcx_command_handle_t send_handle, const char *endpoint, const unsigned char *message, vcx_u32_t message_len));
+
+// Completes the send started by the transport registered with vcx_register_transport.
+//
+// #params
+//
+// send_handle: handle passed to the transport's send_cb
+//
+// err: 0 if the message was delivered, error code otherwise
+//
+// response: (Optional) answer of the endpoint, e.g. when the message asked for return route
+//
+// response_len: length of the answer
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_transport_set_response(vcx_command_handle_t send_handle,
+                                       vcx_error_t err,
+                                       const unsigned char *response,
+                                       vcx_u32_t response_len);
+
 // Create a new Proof object that requests a proof for an enterprise
 //
 // #Params
@@ -1402,6 +2046,29 @@ vcx_error_t vcx_proof_create(vcx_command_handle_t command_handle,
                           const char *name,
                           void (*cb)(vcx_command_handle_t, vcx_error_t, vcx_proof_handle_t));
 
+// Create a new Proof object for Presentation Proposal the prover sent without receiving a request first.
+// The object is in proposal_received state, proposal can be answered with `vcx_proof_accept_proposal`,
+// `vcx_proof_send_counter_request` or rejected with `vcx_proof_reject_proposal`.
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// source_id: Enterprise's personal identification for the user, also used as the name of the later request.
+//
+// connection_handle: Connection handle that identifies pairwise connection the proposal was received on
+//
+// presentation_proposal: received Presentation Proposal message
+//
+// cb: Callback that provides proof handle and error status of request.
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_proof_create_from_proposal(vcx_command_handle_t command_handle,
+                                        const char *source_id,
+                                        vcx_connection_handle_t connection_handle,
+                                        const char *presentation_proposal,
+                                        void (*cb)(vcx_command_handle_t, vcx_error_t, vcx_proof_handle_t));
+
 // Takes a json string representing a proof object and recreates an object matching the json
 //
 // #Params
@@ -1459,6 +2126,90 @@ vcx_error_t vcx_proof_send_request(vcx_command_handle_t command_handle,
                                 vcx_connection_handle_t connection_handle,
                                 void (*cb)(vcx_command_handle_t, vcx_error_t));
 
+// Gets the Presentation Proposal received from the prover (in VcxStateProposalReceived state).
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// proof_handle: Proof handle that was provided during creation. Used to access proof object
+//
+// cb: Callback that provides the proposal message json and error status
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_proof_get_proposal(vcx_command_handle_t command_handle,
+                                vcx_proof_handle_t proof_handle,
+                                void (*cb)(vcx_command_handle_t, vcx_error_t, const char*));
+
+// Answers the received Presentation Proposal with a proof request asking for the proposed attributes and predicates.
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// proof_handle: Proof handle that was provided during creation. Used to access proof object
+//
+// connection_handle: Connection handle that identifies pairwise connection
+//
+// cb: provides any error status of the proof_request
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_proof_accept_proposal(vcx_command_handle_t command_handle,
+                                   vcx_proof_handle_t proof_handle,
+                                   vcx_connection_handle_t connection_handle,
+                                   void (*cb)(vcx_command_handle_t, vcx_error_t));
+
+// Answers the received Presentation Proposal with a different proof request on the proposal thread.
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// proof_handle: Proof handle that was provided during creation. Used to access proof object
+//
+// connection_handle: Connection handle that identifies pairwise connection
+//
+// requested_attrs: Describes requested attribute (the same format as in `vcx_proof_create`)
+//
+// requested_predicates: predicate specifications prover must provide claim for (the same format as in `vcx_proof_create`)
+//
+// revocation_interval: revocation interval (the same format as in `vcx_proof_create`)
+//
+// name: Name of the proof request
+//
+// cb: provides any error status of the proof_request
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_proof_send_counter_request(vcx_command_handle_t command_handle,
+                                        vcx_proof_handle_t proof_handle,
+                                        vcx_connection_handle_t connection_handle,
+                                        const char *requested_attrs,
+                                        const char *requested_predicates,
+                                        const char *revocation_interval,
+                                        const char *name,
+                                        void (*cb)(vcx_command_handle_t, vcx_error_t));
+
+// Rejects the received Presentation Proposal by sending a Problem Report with the given reason.
+//
+// #Params
+// command_handle: command handle to map callback to user context.
+//
+// proof_handle: Proof handle that was provided during creation. Used to access proof object
+//
+// connection_handle: Connection handle that identifies pairwise connection
+//
+// reason: human-readable reason of rejection
+//
+// cb: provides any error status of the rejection
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_proof_reject_proposal(vcx_command_handle_t command_handle,
+                                   vcx_proof_handle_t proof_handle,
+                                   vcx_connection_handle_t connection_handle,
+                                   const char *reason,
+                                   void (*cb)(vcx_command_handle_t, vcx_error_t));
+
 // Takes the proof object and returns a json string of all its attributes
 //
 // #Params
@@ -2120,6 +2871,25 @@ vcx_error_t vcx_get_logger(const void*  vcx_get_logger,
                                           vcx_u32_t line),
                            void (**flushFn)(const void*  context) );
 
+// Subscribes for state transitions of connections, credentials and proofs.
+//
+// #Params
+// command_handle: command handle to map callback to user context, passed to every eventFn call.
+//
+// eventFn: handler called from a library thread on every state transition with the event json:
+//     {"type":"issuer_credential","handle":1,"source_id":"1","thread_id":"...","old_state":1,"new_state":2,"message_type":"CredentialInit"}
+//
+// subscription_handle: reference that will contain the subscription handle
+//
+// #Returns
+// Error code as a u32
+vcx_error_t vcx_subscribe_state_events(vcx_command_handle_t command_handle,
+                                       void (*eventFn)(vcx_command_handle_t xcommand_handle, const char* event),
+                                       vcx_u32_t *subscription_handle);
+
+// Stops delivering state transitions to the subscriber.
+vcx_error_t vcx_unsubscribe_state_events(vcx_u32_t subscription_handle);
+
 /// Get details for last occurred error.
 ///
 /// This function should be called in two places to handle both cases of error occurrence:
@@ -0,0 +1,333 @@
+use chrono::Utc;
+use indy::SearchHandle;
+use serde_json::{self, Value};
+
+use aries::messages::a2a::A2AMessage;
+use aries::messages::ack::{Ack, AckStatus};
+use error::prelude::*;
+use replay;
+use utils::libindy::wallet;
+
+/*
+    Acknowledgement requests (`~please_ack` decorator) of messages exchanged over a connection.
+
+    A message sent with `~please_ack` is tracked by a `vcx_ack_request` wallet record tagged by pairwise DID
+    of the connection and thread of the message, until an `ack` of the thread comes back.
+
+    An inbound message asking for acknowledgement is answered by an `ack` automatically once the object owning it
+    processed it, unless its protocol defines own acknowledgement (credential, presentation, connection response).
+    A `vcx_ack_sent` wallet record ensures the message is acknowledged once even if it is handled again.
+
+    Outbound messages ask for acknowledgement by the typed `~please_ack` decorator (`ask_for_ack`).
+*/
+
+pub const ACK_REQUEST_RECORD_TYPE: &str = "vcx_ack_request";
+pub const ACK_SENT_RECORD_TYPE: &str = "vcx_ack_sent";
+
+const PLEASE_ACK: &str = "~please_ack";
+const SEARCH_BATCH_SIZE: usize = 100;
+
+/// Message sent with `~please_ack` decorator.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct AckRequest {
+    /// `@id` of the message.
+    pub id: String,
+    pub thread_id: String,
+    /// RFC 3339 time when the message was sent.
+    pub sent: String,
+    /// Status of the last received ack, not set until the message is acknowledged.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub status: Option<AckStatus>,
+    /// RFC 3339 time when the last ack was received.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub acknowledged: Option<String>,
+}
+
+impl AckRequest {
+    fn tags(&self, pw_did: &str) -> String {
+        let acknowledged = match self.status {
+            Some(AckStatus::Ok) | Some(AckStatus::Fail) => true,
+            Some(AckStatus::Pending) | None => false
+        };
+
+        json!({"connection": pw_did, "thread_id": self.thread_id, "acknowledged": acknowledged.to_string()}).to_string()
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct AckRequestRecord {
+    id: String,
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchRecords {
+    records: Option<Vec<AckRequestRecord>>,
+}
+
+/// Whether the message contains `~please_ack` decorator.
+pub fn requests_ack(message: &A2AMessage) -> bool {
+    json!(message).get(PLEASE_ACK).map(Value::is_object).unwrap_or(false)
+}
+
+/**
+Builds `ack` answering the message if it asks for acknowledgement not covered by its protocol.
+ */
+pub fn build_ack(message: &A2AMessage) -> Option<Ack> {
+    match message {
+        A2AMessage::Ack(_) |
+        A2AMessage::ConnectionResponse(_) |
+        A2AMessage::Credential(_) | A2AMessage::CredentialV2(_) |
+        A2AMessage::Presentation(_) | A2AMessage::PresentationV2(_) => None,
+        _ if requests_ack(message) => _thread_id(message).map(|thread_id| Ack::create().set_thread_id(&thread_id)),
+        _ => None
+    }
+}
+
+/**
+Records that `ack` of the message received on the connection with the given pairwise DID is sent.
+Returns false if the message was acknowledged before.
+ */
+pub fn record_ack_sent(pw_did: &str, message: &A2AMessage) -> VcxResult<bool> {
+    trace!("acks::record_ack_sent >>> pw_did: {}", pw_did);
+
+    let message_id = replay::message_id(message)
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, "Message does not contain @id"))?;
+
+    match wallet::add_record(ACK_SENT_RECORD_TYPE, &_record_id(pw_did, &message_id), &Utc::now().to_rfc3339(), None) {
+        Ok(()) => Ok(true),
+        Err(ref err) if err.kind() == VcxErrorKind::DuplicationWalletRecord => Ok(false),
+        Err(err) => Err(err)
+    }
+}
+
+/// Starts tracking acknowledgement of the message sent on the connection with the given pairwise DID.
+pub fn track(pw_did: &str, message: &A2AMessage) -> VcxResult<()> {
+    trace!("acks::track >>> pw_did: {}", pw_did);
+
+    let id = replay::message_id(message)
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, "Message does not contain @id"))?;
+
+    let request = AckRequest {
+        thread_id: _thread_id(message).unwrap_or(id.clone()),
+        id,
+        sent: Utc::now().to_rfc3339(),
+        status: None,
+        acknowledged: None,
+    };
+
+    wallet::add_record(ACK_REQUEST_RECORD_TYPE, &_record_id(pw_did, &request.id), &_to_string(&request)?, Some(&request.tags(pw_did)))
+}
+
+/**
+Marks messages sent on the connection in the thread of the ack as acknowledged.
+Returns false if the ack does not answer any tracked message.
+ */
+pub fn acknowledge(pw_did: &str, ack: &Ack) -> VcxResult<bool> {
+    trace!("acks::acknowledge >>> pw_did: {}, ack: {:?}", pw_did, ack);
+
+    let thread_id = match ack.thread.thid {
+        Some(ref thread_id) => thread_id,
+        None => return Ok(false)
+    };
+
+    let records = _search(&json!({"connection": pw_did, "thread_id": thread_id, "acknowledged": "false"}).to_string())?;
+
+    for (record_id, mut request) in records.iter().cloned() {
+        request.status = Some(ack.status.clone());
+        request.acknowledged = Some(Utc::now().to_rfc3339());
+
+        wallet::update_record_value(ACK_REQUEST_RECORD_TYPE, &record_id, &_to_string(&request)?)?;
+        wallet::update_record_tags(ACK_REQUEST_RECORD_TYPE, &record_id, &request.tags(pw_did))?;
+    }
+
+    Ok(!records.is_empty())
+}
+
+/// Returns JSON array of messages sent with `~please_ack` on the connection with the given pairwise DID.
+pub fn get_requests(pw_did: &str) -> VcxResult<String> {
+    trace!("acks::get_requests >>> pw_did: {}", pw_did);
+
+    let mut requests: Vec<AckRequest> = _search(&json!({"connection": pw_did}).to_string())?
+        .into_iter()
+        .map(|(_, request)| request)
+        .collect();
+
+    requests.sort_by(|a, b| a.sent.cmp(&b.sent));
+
+    serde_json::to_string(&requests)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize ack requests: {:?}", err)))
+}
+
+fn _thread_id(message: &A2AMessage) -> Option<String> {
+    let message = json!(message);
+
+    message["~thread"]["thid"].as_str()
+        .or(message["@id"].as_str())
+        .map(String::from)
+}
+
+fn _record_id(pw_did: &str, message_id: &str) -> String {
+    format!("{}:{}", pw_did, message_id)
+}
+
+fn _search(query: &str) -> VcxResult<Vec<(String, AckRequest)>> {
+    let search_handle = wallet::open_search(ACK_REQUEST_RECORD_TYPE, query, &json!({"retrieveRecords": true}).to_string())?;
+
+    let result = _fetch_all(search_handle);
+
+    wallet::close_search(search_handle)?;
+
+    result?.into_iter()
+        .map(|record| serde_json::from_str::<AckRequest>(&record.value)
+            .map(|request| (record.id, request))
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize ack request: {:?}", err))))
+        .collect()
+}
+
+fn _fetch_all(search_handle: SearchHandle) -> VcxResult<Vec<AckRequestRecord>> {
+    let mut records = Vec::new();
+
+    loop {
+        let batch = wallet::fetch_next_records(search_handle, SEARCH_BATCH_SIZE)?;
+
+        let batch: SearchRecords = serde_json::from_str(&batch)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize ack request records: {:?}", err)))?;
+
+        match batch.records {
+            Some(ref batch) if batch.is_empty() => break,
+            Some(batch) => records.extend(batch),
+            None => break
+        }
+    }
+
+    Ok(records)
+}
+
+fn _to_string(request: &AckRequest) -> VcxResult<String> {
+    serde_json::to_string(request)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize ack request: {:?}", err)))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::ack::tests::_ack;
+    use aries::messages::basic_message::message::BasicMessage;
+    use aries::messages::issuance::credential::tests::_credential;
+    use utils::devsetup::{SetupAriesMocks, SetupLibraryWallet};
+
+    use super::*;
+
+    fn _basic_message() -> BasicMessage {
+        BasicMessage::create()
+            .set_content(String::from("Hello"))
+            .set_time()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_requests_ack() {
+        assert!(!requests_ack(&_basic_message().to_a2a_message()));
+
+        let message = _basic_message().ask_for_ack().to_a2a_message();
+        assert!(requests_ack(&message));
+
+        let message: A2AMessage = serde_json::from_value(json!(message)).unwrap();
+        assert!(requests_ack(&message));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_build_ack() {
+        assert!(build_ack(&_basic_message().to_a2a_message()).is_none());
+
+        let ack = build_ack(&_basic_message().ask_for_ack().to_a2a_message()).unwrap();
+        assert_eq!(Some(String::from("testid")), ack.thread.thid);
+        assert_eq!(AckStatus::Ok, ack.status);
+
+        let credential = _credential().ask_for_ack().to_a2a_message();
+        assert!(requests_ack(&credential));
+        assert!(build_ack(&credential).is_none());
+
+        assert!(build_ack(&_ack().to_a2a_message()).is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_ack_request_tags() {
+        let mut request = AckRequest {
+            id: String::from("id"),
+            thread_id: String::from("thread"),
+            sent: Utc::now().to_rfc3339(),
+            status: None,
+            acknowledged: None,
+        };
+        assert_eq!(json!({"connection": "pw_did", "thread_id": "thread", "acknowledged": "false"}).to_string(), request.tags("pw_did"));
+
+        request.status = Some(AckStatus::Pending);
+        assert_eq!(json!({"connection": "pw_did", "thread_id": "thread", "acknowledged": "false"}).to_string(), request.tags("pw_did"));
+
+        request.status = Some(AckStatus::Fail);
+        assert_eq!(json!({"connection": "pw_did", "thread_id": "thread", "acknowledged": "true"}).to_string(), request.tags("pw_did"));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_track_and_acknowledge_without_records() {
+        let _setup = SetupAriesMocks::init();
+
+        let message = _basic_message().ask_for_ack().to_a2a_message();
+        track("pw_did", &message).unwrap();
+
+        assert!(!acknowledge("pw_did", &_ack()).unwrap());
+        assert_eq!("[]", get_requests("pw_did").unwrap());
+        assert!(record_ack_sent("pw_did", &message).unwrap());
+    }
+
+    fn _requests(pw_did: &str) -> Vec<AckRequest> {
+        serde_json::from_str(&get_requests(pw_did).unwrap()).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_track_and_acknowledge() {
+        let _setup = SetupLibraryWallet::init();
+
+        track("pw_did", &_basic_message().ask_for_ack().to_a2a_message()).unwrap();
+
+        let requests = _requests("pw_did");
+        assert_eq!(1, requests.len());
+        assert_eq!("testid", requests[0].id);
+        assert_eq!("testid", requests[0].thread_id);
+        assert_eq!(None, requests[0].status);
+        assert_eq!(None, requests[0].acknowledged);
+        assert!(_requests("other_pw_did").is_empty());
+
+        // ack of another thread or received on another connection
+        assert!(!acknowledge("pw_did", &_ack()).unwrap());
+        let ack = Ack::create().set_thread_id("testid");
+        assert!(!acknowledge("other_pw_did", &ack).unwrap());
+        assert_eq!(None, _requests("pw_did")[0].status);
+
+        assert!(acknowledge("pw_did", &ack).unwrap());
+
+        let requests = _requests("pw_did");
+        assert_eq!(Some(AckStatus::Ok), requests[0].status);
+        assert!(requests[0].acknowledged.is_some());
+
+        // acknowledged message does not wait for ack anymore
+        assert!(!acknowledge("pw_did", &ack).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_record_ack_sent_once_per_message() {
+        let _setup = SetupLibraryWallet::init();
+
+        let message = _basic_message().ask_for_ack().to_a2a_message();
+
+        assert!(record_ack_sent("pw_did", &message).unwrap());
+        assert!(!record_ack_sent("pw_did", &message).unwrap());
+        assert!(record_ack_sent("other_pw_did", &message).unwrap());
+    }
+}
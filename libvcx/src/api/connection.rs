@@ -37,6 +37,14 @@ use aries::messages::a2a::A2AMessage;
                                             OR
                                         `ConnectionProblemReport` messages is received on state updates.
 
+        Multi-use Inviter:
+            VcxStateType::VcxStateInitialized - once `vcx_connection_create_multi_use` (create Connection object) is called.
+
+            VcxStateType::VcxStateOfferSent - once `vcx_connection_connect` (prepared Connection invite) is called.
+                                              the object stays in this state, every `ConnectionRequest` received on the invitation
+                                              creates a new Inviter connection in VcxStateRequestReceived state.
+                                              use `vcx_connection_get_new_connections` to get them.
+
         Invitee:
             VcxStateType::VcxStateOfferSent - once `vcx_connection_create_with_invite` (create Connection object with invite) is called.
 
@@ -68,6 +76,13 @@ use aries::messages::a2a::A2AMessage;
 
             any state - `vcx_connection_delete_connection` - VcxStateType::VcxStateNone
 
+        Multi-use Inviter:
+            VcxStateType::None - `vcx_connection_create_multi_use` - VcxStateType::VcxStateInitialized
+
+            VcxStateType::VcxStateInitialized - `vcx_connection_connect` - VcxStateType::VcxStateOfferSent
+
+            VcxStateType::VcxStateOfferSent - `vcx_connection_get_new_connections` - VcxStateType::VcxStateOfferSent
+
 
         Invitee:
             VcxStateType::None - `vcx_connection_create_with_invite` - VcxStateType::VcxStateOfferSent
@@ -185,6 +200,152 @@ pub extern fn vcx_connection_create(command_handle: CommandHandle,
     error::SUCCESS.code_num
 }
 
+/// Create a Connection object which invitation can be used by many users (e.g. published on a website).
+/// The object itself never becomes connected: every `ConnectionRequest` received on its invitation
+/// produces a new Connection object with own pairwise DID (see `vcx_connection_get_new_connections`).
+///
+/// # Params
+/// command_handle: command handle to map callback to user context.
+///
+/// source_id: institution's personal identification for the connection
+///
+/// cb: Callback that provides connection handle and error status of request
+///
+/// # Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_connection_create_multi_use(command_handle: CommandHandle,
+                                              source_id: *const c_char,
+                                              cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, connection_handle: u32)>) -> u32 {
+    info!("vcx_connection_create_multi_use >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(source_id, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_connection_create_multi_use(command_handle: {}, source_id: {})", command_handle, source_id);
+
+    spawn(move || {
+        match create_connection_multi_use(&source_id) {
+            Ok(handle) => {
+                trace!("vcx_connection_create_multi_use_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                       command_handle, error::SUCCESS.message, handle, source_id);
+                cb(command_handle, error::SUCCESS.code_num, handle);
+            }
+            Err(x) => {
+                warn!("vcx_connection_create_multi_use_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                      command_handle, x, 0, source_id);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Answers `ConnectionRequest` messages received on the multi-use invitation since the last call
+/// and returns handles of Connection objects created for them.
+/// New connections are in VcxStateRequestReceived state, use `vcx_connection_update_state` to complete them.
+///
+/// # Params
+/// command_handle: command handle to map callback to user context.
+///
+/// connection_handle: handle of the Connection object created by `vcx_connection_create_multi_use`
+///
+/// cb: Callback that provides JSON array of new connection handles (e.g. [1, 2]) and error status of request
+///
+/// # Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_connection_get_new_connections(command_handle: CommandHandle,
+                                                 connection_handle: u32,
+                                                 cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, connection_handles: *const c_char)>) -> u32 {
+    info!("vcx_connection_get_new_connections >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    let source_id = get_source_id(connection_handle).unwrap_or_default();
+    trace!("vcx_connection_get_new_connections(command_handle: {}, connection_handle: {}), source_id: {:?}",
+           command_handle, connection_handle, source_id);
+
+    if !is_valid_handle(connection_handle) {
+        error!("vcx_connection_get_new_connections - invalid handle");
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    spawn(move || {
+        match get_new_connections(connection_handle) {
+            Ok(handles) => {
+                let handles = json!(handles).to_string();
+                trace!("vcx_connection_get_new_connections_cb(command_handle: {}, connection_handle: {}, rc: {}, connection_handles: {}), source_id: {:?}",
+                       command_handle, connection_handle, error::SUCCESS.message, handles, source_id);
+                let handles = CStringUtils::string_to_cstring(handles);
+                cb(command_handle, error::SUCCESS.code_num, handles.as_ptr());
+            }
+            Err(x) => {
+                warn!("vcx_connection_get_new_connections_cb(command_handle: {}, connection_handle: {}, rc: {}), source_id: {:?}",
+                      command_handle, connection_handle, x, source_id);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Create a Connection object that provides a pairwise connection for an institution's user
+/// using the selected handshake protocol.
+///
+/// # Params
+/// command_handle: command handle to map callback to user context.
+///
+/// source_id: institution's personal identification for the connection
+///
+/// handshake_protocol: protocol used to establish the connection. One of:
+///     "connections/1.0" - Aries RFC 0160 (same as `vcx_connection_create`)
+///     "didexchange/1.0" - Aries RFC 0023, the invitation is an Out-of-Band invitation (Aries RFC 0434)
+///
+/// cb: Callback that provides connection handle and error status of request
+///
+/// # Returns
+/// Error code as a u32
+#[no_mangle]
+#[allow(unused_assignments)]
+pub extern fn vcx_connection_create_with_handshake_protocol(command_handle: CommandHandle,
+                                                            source_id: *const c_char,
+                                                            handshake_protocol: *const c_char,
+                                                            cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, connection_handle: u32)>) -> u32 {
+    info!("vcx_connection_create_with_handshake_protocol >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(source_id, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(handshake_protocol, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_connection_create_with_handshake_protocol(command_handle: {}, source_id: {}, handshake_protocol: {})", command_handle, source_id, handshake_protocol);
+
+    spawn(move || {
+        match create_connection_with_handshake_protocol(&source_id, &handshake_protocol) {
+            Ok(handle) => {
+                trace!("vcx_connection_create_with_handshake_protocol_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                       command_handle, error::SUCCESS.message, handle, source_id);
+                cb(command_handle, error::SUCCESS.code_num, handle);
+            }
+            Err(x) => {
+                warn!("vcx_connection_create_with_handshake_protocol_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                      command_handle, x, 0, source_id);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
 /// Create a Connection object from the given invite_details that provides a pairwise connection.
 ///
 /// # Params
@@ -206,6 +367,26 @@ pub extern fn vcx_connection_create(command_handle: CommandHandle,
 ///         "serviceEndpoint": "https://example.com/endpoint",
 ///         "routingKeys": ["8HH5gYEeNc3z7PYXmd54d4x6qAfCNrqQqEB3nS7Zfu7K"]
 ///      }
+///     OR public DID invitation, the verkey and endpoint (`endpoint` ATTRIB) of the DID are read from the ledger:
+///      {
+///         "@type": "https://didcomm.org/connections/1.0/invitation",
+///         "@id": "69212a3a-d068-4f9d-a2dd-4741bca89af3",
+///         "label": "Alice",
+///         "did": "did:sov:QmWbsNYhMrjHiqZDTUTEJs"
+///      }
+///     OR Out-of-Band invitation offering `connections/1.0` handshake protocol: https://github.com/hyperledger/aries-rfcs/tree/master/features/0434-outofband
+///      {
+///         "@type": "https://didcomm.org/out-of-band/1.0/invitation",
+///         "@id": "69212a3a-d068-4f9d-a2dd-4741bca89af3",
+///         "label": "Alice",
+///         "handshake_protocols": ["https://didcomm.org/connections/1.0"],
+///         "service": [{
+///             "id": "#inline",
+///             "type": "did-communication",
+///             "recipientKeys": ["8HH5gYEeNc3z7PYXmd54d4x6qAfCNrqQqEB3nS7Zfu7K"],
+///             "serviceEndpoint": "https://example.com/endpoint"
+///         }]
+///      }
 ///
 /// # Returns
 /// Error code as a u32
@@ -684,6 +865,131 @@ pub extern fn vcx_connection_invite_details(command_handle: CommandHandle,
     error::SUCCESS.code_num
 }
 
+/// Get the Out-of-Band invitation (https://github.com/hyperledger/aries-rfcs/tree/master/features/0434-outofband)
+/// for an Inviter connection in `VcxStateOfferSent` state.
+/// The invitation offers `connections/1.0` handshake protocol and can carry messages attached to `requests~attach`.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// connection_handle: was provided during creation. Used to identify connection object
+///
+/// requests: (Optional) JSON array of aries messages to attach (e.g. credential offer or presentation request)
+///
+/// goal_code: (Optional) self-attested code the receiver may want to display to the user or use in automatically deciding what to do
+///
+/// goal: (Optional) self-attested string the receiver may want to display to the user
+///
+/// cb: Callback that provides the json string of Out-of-Band invitation
+///
+/// # Example
+/// details ->
+///      {
+///         "@type": "did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/out-of-band/1.0/invitation",
+///         "@id": "69212a3a-d068-4f9d-a2dd-4741bca89af3",
+///         "label": "Faber",
+///         "handshake_protocols": ["did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/connections/1.0"],
+///         "requests~attach": [],
+///         "service": [{
+///             "id": "#inline",
+///             "type": "did-communication",
+///             "recipientKeys": ["8HH5gYEeNc3z7PYXmd54d4x6qAfCNrqQqEB3nS7Zfu7K"],
+///             "routingKeys": ["8HH5gYEeNc3z7PYXmd54d4x6qAfCNrqQqEB3nS7Zfu7K"],
+///             "serviceEndpoint": "https://example.com/endpoint"
+///         }]
+///      }
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_connection_get_out_of_band_invite_details(command_handle: CommandHandle,
+                                                            connection_handle: u32,
+                                                            requests: *const c_char,
+                                                            goal_code: *const c_char,
+                                                            goal: *const c_char,
+                                                            cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, details: *const c_char)>) -> u32 {
+    info!("vcx_connection_get_out_of_band_invite_details >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_opt_c_str!(requests, VcxErrorKind::InvalidOption);
+    check_useful_opt_c_str!(goal_code, VcxErrorKind::InvalidOption);
+    check_useful_opt_c_str!(goal, VcxErrorKind::InvalidOption);
+
+    let source_id = get_source_id(connection_handle).unwrap_or_default();
+    trace!("vcx_connection_get_out_of_band_invite_details(command_handle: {}, connection_handle: {}, requests: {:?}, goal_code: {:?}, goal: {:?}), source_id: {:?}",
+           command_handle, connection_handle, requests, goal_code, goal, source_id);
+
+    if !is_valid_handle(connection_handle) {
+        error!("vcx_connection_get_out_of_band_invite_details - invalid handle");
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    spawn(move || {
+        match get_out_of_band_invite_details(connection_handle, requests.as_ref().map(String::as_str), goal_code, goal) {
+            Ok(details) => {
+                trace!("vcx_connection_get_out_of_band_invite_details_cb(command_handle: {}, connection_handle: {}, rc: {}, details: {}), source_id: {:?}",
+                       command_handle, connection_handle, error::SUCCESS.message, details, source_id);
+                let details = CStringUtils::string_to_cstring(details);
+                cb(command_handle, error::SUCCESS.code_num, details.as_ptr());
+            }
+            Err(x) => {
+                warn!("vcx_connection_get_out_of_band_invite_details_cb(command_handle: {}, connection_handle: {}, rc: {}, details: {}, source_id: {:?})",
+                      command_handle, connection_handle, x, "null", source_id);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Gets the messages attached to Out-of-Band invitation (`requests~attach`), e.g. credential offer or presentation request.
+/// Invitation without handshake protocols carries only requests, connection created from it answers them directly.
+///
+/// #params
+/// command_handle: command handle to map callback to user context.
+///
+/// invite_details: Out-of-Band invitation received from the inviter
+///
+/// cb: Callback that provides JSON array of the attached aries messages
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_connection_get_out_of_band_requests(command_handle: CommandHandle,
+                                                      invite_details: *const c_char,
+                                                      cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, requests: *const c_char)>) -> u32 {
+    info!("vcx_connection_get_out_of_band_requests >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(invite_details, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_connection_get_out_of_band_requests(command_handle: {}, invite_details: {})",
+           command_handle, invite_details);
+
+    spawn(move || {
+        match get_out_of_band_requests(&invite_details) {
+            Ok(requests) => {
+                trace!("vcx_connection_get_out_of_band_requests_cb(command_handle: {}, rc: {}, requests: {})",
+                       command_handle, error::SUCCESS.message, requests);
+                let requests = CStringUtils::string_to_cstring(requests);
+                cb(command_handle, error::SUCCESS.code_num, requests.as_ptr());
+            }
+            Err(x) => {
+                warn!("vcx_connection_get_out_of_band_requests_cb(command_handle: {}, rc: {}, requests: {})",
+                      command_handle, x, "null");
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
 /// Send a message to the specified connection
 ///
 /// #params
@@ -696,9 +1002,17 @@ pub extern fn vcx_connection_invite_details(command_handle: CommandHandle,
 ///
 /// msg: actual message to send
 ///
-/// send_msg_options: deprecated, has not effect
+/// send_msg_options: (Optional) JSON with sending options, other fields are deprecated and have no effect
+///     {
+///         "please_ack": Optional<bool> - add `~please_ack` decorator and track acknowledgement of the message (false by default),
+///                                        supported for basic messages (plain text message is sent as basic message)
+///         "trace": Optional<bool> - add `~trace` decorator and report hops of the message to the trace target of the connection,
+///                                   or to the one set by `trace_target` config option (false by default)
+///     }
 ///
-/// cb: Callback that provides id of retrieved response message
+/// cb: Callback that provides `@id` of the sent message if acknowledgement is requested, otherwise empty string.
+///     With the outbox enabled, a message which could not be delivered is queued for retries
+///     and the callback gets PostMessageFailed error.
 ///
 /// #Returns
 /// Error code as a u32
@@ -706,18 +1020,27 @@ pub extern fn vcx_connection_invite_details(command_handle: CommandHandle,
 pub extern fn vcx_connection_send_message(command_handle: CommandHandle,
                                           connection_handle: u32,
                                           msg: *const c_char,
-                                          _send_msg_options: *const c_char,
+                                          send_msg_options: *const c_char,
                                           cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, msg_id: *const c_char)>) -> u32 {
     info!("vcx_connection_send_message >>>");
 
     check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
     check_useful_c_str!(msg, VcxErrorKind::InvalidOption);
+    check_useful_opt_c_str!(send_msg_options, VcxErrorKind::InvalidOption);
 
-    trace!("vcx_connection_send_message(command_handle: {}, connection_handle: {}, msg: {})",
-           command_handle, connection_handle, msg);
+    trace!("vcx_connection_send_message(command_handle: {}, connection_handle: {}, msg: {}, send_msg_options: {:?})",
+           command_handle, connection_handle, msg, send_msg_options);
+
+    let (please_ack, trace) = match send_msg_options {
+        Some(options) => match serde_json::from_str::<serde_json::Value>(&options) {
+            Ok(options) => (options["please_ack"].as_bool().unwrap_or(false), options["trace"].as_bool().unwrap_or(false)),
+            Err(err) => return VcxError::from_msg(VcxErrorKind::InvalidOption, format!("Cannot deserialize send message options: {:?}", err)).into()
+        },
+        None => (false, false)
+    };
 
     spawn(move || {
-        match send_generic_message(connection_handle, &msg) {
+        match send_generic_message_with_options(connection_handle, &msg, please_ack, trace) {
             Ok(x) => {
                 trace!("vcx_connection_send_message_cb(command_handle: {}, rc: {}, msg_id: {})",
                        command_handle, error::SUCCESS.message, x);
@@ -943,6 +1266,133 @@ pub extern fn vcx_connection_verify_signature(command_handle: CommandHandle,
     error::SUCCESS.code_num
 }
 
+/// Sign the field of the message with connection pairwise key.
+/// The field is replaced by `<field>~sig` signature decorator (`signature/1.0/ed25519Sha512_single`).
+///
+/// #params
+///
+/// command_handle: command handle to map callback to user context.
+///
+/// connection_handle: connection to use to sign the field.
+///                    Was provided during creation. Used to identify connection object.
+///
+/// message: JSON message containing the field to sign
+///
+/// field: name of the field to sign
+///
+/// cb: Callback that provides the message with signed field
+///
+/// # Example
+/// message -> {"@type": "https://didcomm.org/basicmessage/1.0/message", "@id": "1", "content": "Hello"}
+/// field -> "content"
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_connection_sign_message_field(command_handle: CommandHandle,
+                                                connection_handle: u32,
+                                                message: *const c_char,
+                                                field: *const c_char,
+                                                cb: Option<extern fn(command_handle_: CommandHandle,
+                                                                     err: u32,
+                                                                     signed_message: *const c_char)>) -> u32 {
+    info!("vcx_connection_sign_message_field >>>");
+
+    check_useful_c_str!(message, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(field, VcxErrorKind::InvalidOption);
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_connection_sign_message_field(command_handle: {}, connection_handle: {}, field: {})",
+           command_handle, connection_handle, field);
+
+    if !is_valid_handle(connection_handle) {
+        error!("vcx_connection_sign_message_field - invalid handle");
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    spawn(move || {
+        match sign_message_field(connection_handle, &message, &field) {
+            Ok(signed_message) => {
+                trace!("vcx_connection_sign_message_field_cb(command_handle: {}, rc: {}, signed_message: {})",
+                       command_handle, error::SUCCESS.message, signed_message);
+
+                let signed_message = CStringUtils::string_to_cstring(signed_message);
+                cb(command_handle, error::SUCCESS.code_num, signed_message.as_ptr());
+            }
+            Err(e) => {
+                warn!("vcx_connection_sign_message_field_cb(command_handle: {}, rc: {}, signed_message: null)",
+                      command_handle, e);
+
+                cb(command_handle, e.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Verify all `~sig` decorated fields of the message with the key of connection counterparty.
+/// The signed fields are replaced by the values they contain.
+///
+/// #params
+///
+/// command_handle: command handle to map callback to user context.
+///
+/// connection_handle: connection to use to verify the fields.
+///                    Was provided during creation. Used to identify connection object.
+///
+/// message: JSON message containing signed fields
+///
+/// cb: Callback that provides the message with verified field values.
+///     An error is returned if the message does not contain signed field or some signature is invalid.
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_connection_verify_message_fields(command_handle: CommandHandle,
+                                                   connection_handle: u32,
+                                                   message: *const c_char,
+                                                   cb: Option<extern fn(command_handle_: CommandHandle,
+                                                                        err: u32,
+                                                                        message: *const c_char)>) -> u32 {
+    info!("vcx_connection_verify_message_fields >>>");
+
+    check_useful_c_str!(message, VcxErrorKind::InvalidOption);
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_connection_verify_message_fields(command_handle: {}, connection_handle: {}, message: {})",
+           command_handle, connection_handle, message);
+
+    if !is_valid_handle(connection_handle) {
+        error!("vcx_connection_verify_message_fields - invalid handle");
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    spawn(move || {
+        match verify_message_fields(connection_handle, &message) {
+            Ok(message) => {
+                trace!("vcx_connection_verify_message_fields_cb(command_handle: {}, rc: {}, message: {})",
+                       command_handle, error::SUCCESS.message, message);
+
+                let message = CStringUtils::string_to_cstring(message);
+                cb(command_handle, error::SUCCESS.code_num, message.as_ptr());
+            }
+            Err(e) => {
+                warn!("vcx_connection_verify_message_fields_cb(command_handle: {}, rc: {}, message: null)",
+                      command_handle, e);
+
+                cb(command_handle, e.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
 /// Releases the connection object by de-allocating memory
 ///
 /// #Params
@@ -1102,6 +1552,125 @@ pub extern fn vcx_connection_info(command_handle: CommandHandle,
     error::SUCCESS.code_num
 }
 
+/// Get the messages sent to the connection with `~please_ack` decorator and status of their acknowledgement.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// connection_handle: was provided during creation. Used to identify connection object
+///
+/// cb: Callback that provides JSON array of the messages
+///
+/// # Example
+/// requests ->
+///     [
+///         {
+///             "id": <str> - `@id` of the sent message,
+///             "thread_id": <str> - thread of the sent message,
+///             "sent": <str> - RFC 3339 time of sending,
+///             "status": Optional<str> - status of the received ack (OK, FAIL or PENDING), missing until the message is acknowledged,
+///             "acknowledged": Optional<str> - RFC 3339 time when the ack was received
+///         }
+///     ]
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_connection_get_ack_requests(command_handle: CommandHandle,
+                                              connection_handle: u32,
+                                              cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, requests: *const c_char)>) -> u32 {
+    info!("vcx_connection_get_ack_requests >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    let source_id = get_source_id(connection_handle).unwrap_or_default();
+    trace!("vcx_connection_get_ack_requests(command_handle: {}, connection_handle: {}), source_id: {:?}",
+           command_handle, connection_handle, source_id);
+
+    if !is_valid_handle(connection_handle) {
+        error!("vcx_connection_get_ack_requests - invalid handle");
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    spawn(move || {
+        match get_ack_requests(connection_handle) {
+            Ok(requests) => {
+                trace!("vcx_connection_get_ack_requests_cb(command_handle: {}, connection_handle: {}, rc: {}, requests: {}), source_id: {:?}",
+                       command_handle, connection_handle, error::SUCCESS.message, requests, source_id);
+                let requests = CStringUtils::string_to_cstring(requests);
+                cb(command_handle, error::SUCCESS.code_num, requests.as_ptr());
+            }
+            Err(x) => {
+                warn!("vcx_connection_get_ack_requests_cb(command_handle: {}, connection_handle: {}, rc: {}, requests: {}, source_id: {:?})",
+                      command_handle, connection_handle, x, "null", source_id);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Enables tracing of the messages exchanged on the connection (RFC 0034 `~trace` decorator).
+/// Every hop of the messages (pack, forward wrap, send, receive and state transition) is reported to the target.
+/// Sent messages are decorated with `~trace`, so the counterparty can trace them too.
+///
+/// #params
+///
+/// command_handle: command handle to map callback to user context.
+///
+/// connection_handle: was provided during creation. Used to identify connection object
+///
+/// target: (Optional) target of trace reports, tracing is disabled if not set:
+///     "log" - reports are logged with info level
+///     "file://<absolute path>" - reports are appended to the file, one JSON per line
+///     "http(s)://<url>" - reports are posted to the URL as JSON
+///
+/// cb: Callback that provides error status of the request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_connection_set_trace_target(command_handle: CommandHandle,
+                                              connection_handle: u32,
+                                              target: *const c_char,
+                                              cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_connection_set_trace_target >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_opt_c_str!(target, VcxErrorKind::InvalidOption);
+
+    let source_id = get_source_id(connection_handle).unwrap_or_default();
+    trace!("vcx_connection_set_trace_target(command_handle: {}, connection_handle: {}, target: {:?}), source_id: {:?}",
+           command_handle, connection_handle, target, source_id);
+
+    if !is_valid_handle(connection_handle) {
+        error!("vcx_connection_set_trace_target - invalid handle");
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    spawn(move || {
+        match set_trace_target(connection_handle, target) {
+            Ok(()) => {
+                trace!("vcx_connection_set_trace_target_cb(command_handle: {}, connection_handle: {}, rc: {}), source_id: {:?}",
+                       command_handle, connection_handle, error::SUCCESS.message, source_id);
+                cb(command_handle, error::SUCCESS.code_num);
+            }
+            Err(x) => {
+                warn!("vcx_connection_set_trace_target_cb(command_handle: {}, connection_handle: {}, rc: {}), source_id: {:?}",
+                      command_handle, connection_handle, x, source_id);
+                cb(command_handle, x.into());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
 /// Retrieves pw_did from Connection object
 ///
 /// #Params
@@ -1231,6 +1800,53 @@ mod tests {
         assert!(cb.receive(TimeoutUtils::some_medium()).unwrap() > 0);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_connection_multi_use() {
+        let _setup = SetupAriesMocks::init();
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        let rc = vcx_connection_create_multi_use(cb.command_handle,
+                                                 CString::new("test_create").unwrap().into_raw(),
+                                                 Some(cb.get_callback()));
+        assert_eq!(rc, error::SUCCESS.code_num);
+        let handle = cb.receive(TimeoutUtils::some_medium()).unwrap();
+        connect(handle).unwrap();
+
+        AgencyMockDecrypted::set_next_decrypted_response(GET_MESSAGES_DECRYPTED_RESPONSE);
+        AgencyMockDecrypted::set_next_decrypted_message(ARIES_CONNECTION_REQUEST);
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        let rc = vcx_connection_get_new_connections(cb.command_handle, handle, Some(cb.get_callback()));
+        assert_eq!(rc, error::SUCCESS.code_num);
+        let handles: Vec<u32> = serde_json::from_str(&cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap()).unwrap();
+        assert_eq!(1, handles.len());
+        assert_eq!(get_state(handles[0]), VcxStateType::VcxStateRequestReceived as u32);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_connection_create_with_handshake_protocol() {
+        let _setup = SetupAriesMocks::init();
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        let rc = vcx_connection_create_with_handshake_protocol(cb.command_handle,
+                                                               CString::new("test_create").unwrap().into_raw(),
+                                                               CString::new("didexchange/1.0").unwrap().into_raw(),
+                                                               Some(cb.get_callback()));
+        assert_eq!(rc, error::SUCCESS.code_num);
+        let handle = cb.receive(TimeoutUtils::some_medium()).unwrap();
+        assert!(handle > 0);
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        let rc = vcx_connection_create_with_handshake_protocol(cb.command_handle,
+                                                               CString::new("test_create").unwrap().into_raw(),
+                                                               CString::new("unknown").unwrap().into_raw(),
+                                                               Some(cb.get_callback()));
+        assert_eq!(rc, error::SUCCESS.code_num);
+        assert_eq!(cb.receive(TimeoutUtils::some_medium()).unwrap_err(), error::INVALID_OPTION.code_num);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_vcx_connection_create_fails() {
@@ -1334,6 +1950,49 @@ mod tests {
         assert_eq!(rc, error::INVALID_OPTION.code_num);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_connection_get_out_of_band_invite_details() {
+        let _setup = SetupAriesMocks::init();
+
+        let handle = build_test_connection_inviter_null();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        let rc = vcx_connection_get_out_of_band_invite_details(cb.command_handle, handle, ptr::null(), ptr::null(), ptr::null(), Some(cb.get_callback()));
+        assert_eq!(rc, error::SUCCESS.code_num);
+        assert_eq!(cb.receive(TimeoutUtils::some_medium()).unwrap_err(), error::NOT_READY.code_num);
+
+        let handle = build_test_connection_inviter_invited();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        let rc = vcx_connection_get_out_of_band_invite_details(cb.command_handle, handle, ptr::null(), ptr::null(), ptr::null(), Some(cb.get_callback()));
+        assert_eq!(rc, error::SUCCESS.code_num);
+        let details = cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap();
+        assert!(details.contains("out-of-band/1.0/invitation"));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_connection_get_out_of_band_requests() {
+        let _setup = SetupAriesMocks::init();
+
+        let handle = build_test_connection_inviter_invited();
+        let offer = ::aries::messages::issuance::credential_offer::tests::_credential_offer().to_a2a_message();
+        let details = get_out_of_band_invite_details(handle, Some(&json!([offer]).to_string()), None, None).unwrap();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        let rc = vcx_connection_get_out_of_band_requests(cb.command_handle, CString::new(details).unwrap().into_raw(), Some(cb.get_callback()));
+        assert_eq!(rc, error::SUCCESS.code_num);
+        let requests = cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap();
+        let requests: Vec<A2AMessage> = serde_json::from_str(&requests).unwrap();
+        assert_eq!(vec![offer], requests);
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        let rc = vcx_connection_get_out_of_band_requests(cb.command_handle, CString::new("{}").unwrap().into_raw(), Some(cb.get_callback()));
+        assert_eq!(rc, error::SUCCESS.code_num);
+        assert_eq!(cb.receive(TimeoutUtils::some_medium()).unwrap_err(), error::INVALID_JSON.code_num);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_vcx_connection_serialize() {
@@ -1431,6 +2090,57 @@ mod tests {
         cb.receive(TimeoutUtils::some_medium()).unwrap();
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_send_message_with_please_ack() {
+        let _setup = SetupAriesMocks::init();
+
+        let connection_handle = build_test_connection_inviter_requested();
+
+        let msg = CString::new("MESSAGE").unwrap().into_raw();
+        let send_msg_options = CString::new(json!({"please_ack": true}).to_string()).unwrap().into_raw();
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_connection_send_message(cb.command_handle, connection_handle, msg, send_msg_options, Some(cb.get_callback())), error::SUCCESS.code_num);
+        assert_eq!(Some(String::from("testid")), cb.receive(TimeoutUtils::some_medium()).unwrap());
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_connection_get_ack_requests(cb.command_handle, connection_handle, Some(cb.get_callback())), error::SUCCESS.code_num);
+        assert_eq!(Some(String::from("[]")), cb.receive(TimeoutUtils::some_medium()).unwrap());
+
+        let msg = CString::new("MESSAGE").unwrap().into_raw();
+        let send_msg_options = CString::new("not json").unwrap().into_raw();
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_connection_send_message(cb.command_handle, connection_handle, msg, send_msg_options, Some(cb.get_callback())), error::INVALID_OPTION.code_num);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_set_trace_target_and_send_traced_message() {
+        let _setup = SetupAriesMocks::init();
+
+        let connection_handle = build_test_connection_inviter_requested();
+
+        let target = CString::new("log").unwrap().into_raw();
+        let cb = return_types_u32::Return_U32::new().unwrap();
+        assert_eq!(vcx_connection_set_trace_target(cb.command_handle, connection_handle, target, Some(cb.get_callback())), error::SUCCESS.code_num);
+        cb.receive(TimeoutUtils::some_medium()).unwrap();
+
+        let msg = CString::new("MESSAGE").unwrap().into_raw();
+        let send_msg_options = CString::new(json!({"trace": true}).to_string()).unwrap().into_raw();
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_connection_send_message(cb.command_handle, connection_handle, msg, send_msg_options, Some(cb.get_callback())), error::SUCCESS.code_num);
+        assert_eq!(Some(String::new()), cb.receive(TimeoutUtils::some_medium()).unwrap());
+
+        let target = CString::new("console").unwrap().into_raw();
+        let cb = return_types_u32::Return_U32::new().unwrap();
+        assert_eq!(vcx_connection_set_trace_target(cb.command_handle, connection_handle, target, Some(cb.get_callback())), error::SUCCESS.code_num);
+        assert_eq!(cb.receive(TimeoutUtils::some_medium()).unwrap_err(), error::INVALID_OPTION.code_num);
+
+        let cb = return_types_u32::Return_U32::new().unwrap();
+        assert_eq!(vcx_connection_set_trace_target(cb.command_handle, connection_handle, ptr::null(), Some(cb.get_callback())), error::SUCCESS.code_num);
+        cb.receive(TimeoutUtils::some_medium()).unwrap();
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_sign() {
@@ -1473,4 +2183,30 @@ mod tests {
                                                    Some(cb.get_callback())), error::SUCCESS.code_num);
         cb.receive(TimeoutUtils::some_medium()).unwrap();
     }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_sign_and_verify_message_field() {
+        let _setup = SetupAriesMocks::init();
+
+        let connection_handle = build_test_connection_inviter_requested();
+
+        let message = json!({"@type": "https://didcomm.org/basicmessage/1.0/message", "@id": "testid", "content": "Hello"}).to_string();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_connection_sign_message_field(cb.command_handle,
+                                                     connection_handle,
+                                                     CString::new(message.clone()).unwrap().into_raw(),
+                                                     CString::new("content").unwrap().into_raw(),
+                                                     Some(cb.get_callback())), error::SUCCESS.code_num);
+        let signed_message = cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_connection_verify_message_fields(cb.command_handle,
+                                                        connection_handle,
+                                                        CString::new(signed_message).unwrap().into_raw(),
+                                                        Some(cb.get_callback())), error::SUCCESS.code_num);
+        let verified_message = cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&message).unwrap(), serde_json::from_str::<Value>(&verified_message).unwrap());
+    }
 }
@@ -18,7 +18,12 @@ use utils::threadpool::spawn;
 
     The set of object states, messages and transitions depends on the communication method is used.
     The communication method can be specified as a config option on one of *_init functions.
-        VcxStateType::VcxStateRequestReceived - once `vcx_credential_create_with_offer` (create Credential object) is called.
+        VcxStateType::VcxStateInitialized - once `vcx_credential_create_with_proposal` (create Credential object) is called.
+
+        VcxStateType::VcxStateProposalSent - once `vcx_credential_send_proposal` (send `CredentialProposal` message) is called.
+
+        VcxStateType::VcxStateRequestReceived - once `vcx_credential_create_with_offer` (create Credential object) is called
+                                                or `CredentialOffer` message is received in reply to the proposal.
 
         VcxStateType::VcxStateOfferSent - once `vcx_credential_send_request` (send `CredentialRequest` message) is called.
 
@@ -29,6 +34,12 @@ use utils::threadpool::spawn;
     # Transitions
     RFC - https://github.com/hyperledger/aries-rfcs/tree/7b6b93acbaf9611d3c892c4bada142fe2613de6e/features/0036-issue-credential
         VcxStateType::None - `vcx_credential_create_with_offer` - VcxStateType::VcxStateRequestReceived
+        VcxStateType::None - `vcx_credential_create_with_proposal` - VcxStateType::VcxStateInitialized
+
+        VcxStateType::VcxStateInitialized - `vcx_credential_send_proposal` - VcxStateType::VcxStateProposalSent
+
+        VcxStateType::VcxStateProposalSent - received `CredentialOffer` - VcxStateType::VcxStateRequestReceived
+        VcxStateType::VcxStateProposalSent - received `ProblemReport` - VcxStateType::None
 
         VcxStateType::VcxStateRequestReceived - `vcx_issuer_send_credential_offer` - VcxStateType::VcxStateOfferSent
 
@@ -136,6 +147,123 @@ pub extern fn vcx_credential_create_with_offer(command_handle: CommandHandle,
     error::SUCCESS.code_num
 }
 
+/// Create a Credential object which starts the issuance by proposing a credential to the issuer.
+/// Once the proposal is sent, the issuer answers with a credential offer on the same thread
+/// and the exchange continues as if it was created with `vcx_credential_create_with_offer`.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// source_id: Institution's personal identification for the credential, should be unique.
+///
+/// schema_id: id of the schema the proposed credential is based on
+///
+/// cred_def_id: id of the credential definition the credential is expected to be issued with
+///
+/// credential_data: proposed attribute values
+///
+/// comment: (Optional) human-friendly comment attached to the proposal
+///
+/// # Example
+/// credential_data -> {"name": "Alice", "age": "25"}
+///
+/// cb: Callback that provides credential handle or error status
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_credential_create_with_proposal(command_handle: CommandHandle,
+                                                  source_id: *const c_char,
+                                                  schema_id: *const c_char,
+                                                  cred_def_id: *const c_char,
+                                                  credential_data: *const c_char,
+                                                  comment: *const c_char,
+                                                  cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credential_handle: u32)>) -> u32 {
+    info!("vcx_credential_create_with_proposal >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(source_id, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(schema_id, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(cred_def_id, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(credential_data, VcxErrorKind::InvalidOption);
+    check_useful_opt_c_str!(comment, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_credential_create_with_proposal(command_handle: {}, source_id: {}, schema_id: {}, cred_def_id: {}, credential_data: {}, comment: {:?})",
+           command_handle, source_id, schema_id, cred_def_id, secret!(&credential_data), comment);
+
+    spawn(move || {
+        match credential::credential_create_with_proposal(&source_id, &schema_id, &cred_def_id, &credential_data, comment) {
+            Ok(x) => {
+                trace!("vcx_credential_create_with_proposal_cb(command_handle: {}, source_id: {}, rc: {}, handle: {})",
+                       command_handle, source_id, error::SUCCESS.message, x);
+                cb(command_handle, error::SUCCESS.code_num, x)
+            }
+            Err(x) => {
+                warn!("vcx_credential_create_with_proposal_cb(command_handle: {}, source_id: {}, rc: {}, handle: {})",
+                      command_handle, source_id, x, 0);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Sends the credential proposal of the Credential object created with `vcx_credential_create_with_proposal`.
+///
+/// #params
+/// command_handle: command handle to map callback to user context
+///
+/// credential_handle: credential handle that was provided during creation. Used to identify credential object
+///
+/// connection_handle: Connection handle that identifies pairwise connection
+///
+/// cb: Callback that provides error status of sending the proposal
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_credential_send_proposal(command_handle: CommandHandle,
+                                           credential_handle: u32,
+                                           connection_handle: u32,
+                                           cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_credential_send_proposal >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    if !credential::is_valid_handle(credential_handle) {
+        return VcxError::from(VcxErrorKind::InvalidCredentialHandle).into();
+    }
+
+    if !connection::is_valid_handle(connection_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    let source_id = credential::get_source_id(credential_handle).unwrap_or_default();
+    trace!("vcx_credential_send_proposal(command_handle: {}, credential_handle: {}, connection_handle: {}), source_id: {:?}",
+           command_handle, credential_handle, connection_handle, source_id);
+
+    spawn(move || {
+        match credential::send_credential_proposal(credential_handle, connection_handle) {
+            Ok(x) => {
+                trace!("vcx_credential_send_proposal_cb(command_handle: {}, rc: {}) source_id: {}",
+                       command_handle, x.to_string(), source_id);
+                cb(command_handle, x);
+            }
+            Err(e) => {
+                warn!("vcx_credential_send_proposal_cb(command_handle: {}, rc: {}) source_id: {}",
+                      command_handle, e, source_id);
+                cb(command_handle, e.into());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
 
 /// Retrieve information about a stored credential in user's wallet, including credential id and the credential itself.
 ///
@@ -861,6 +989,32 @@ mod tests {
         assert_eq!(err, error::INVALID_JSON.code_num);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_credential_create_with_proposal_and_send() {
+        let _setup = SetupStrictAriesMocks::init();
+
+        let connection_handle = ::connection::tests::build_test_connection_inviter_requested();
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        assert_eq!(vcx_credential_create_with_proposal(cb.command_handle,
+                                                       CString::new("test_create").unwrap().into_raw(),
+                                                       CString::new("schema:id").unwrap().into_raw(),
+                                                       CString::new("cred_def_id:id").unwrap().into_raw(),
+                                                       CString::new(json!({"name": "Alice"}).to_string()).unwrap().into_raw(),
+                                                       ptr::null(),
+                                                       Some(cb.get_callback())),
+                   error::SUCCESS.code_num);
+        let handle = cb.receive(TimeoutUtils::some_medium()).unwrap();
+
+        let cb = return_types_u32::Return_U32::new().unwrap();
+        assert_eq!(vcx_credential_send_proposal(cb.command_handle, handle, connection_handle, Some(cb.get_callback())),
+                   error::SUCCESS.code_num);
+        cb.receive(TimeoutUtils::some_medium()).unwrap();
+
+        assert_eq!(::credential::get_state(handle).unwrap(), VcxStateType::VcxStateProposalSent as u32);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_vcx_credential_serialize_and_deserialize() {
@@ -20,6 +20,11 @@ use utils::threadpool::spawn;
     The communication method can be specified as a config option on one of *_init functions.
 
     aries:
+        VcxStateType::VcxStateInitialized - once `vcx_disclosed_proof_create_with_proposal` (create DisclosedProof object) is called.
+
+        VcxStateType::VcxStateProposalSent - once `vcx_disclosed_proof_send_proposal` (send `PresentationProposal` message) is called.
+
+        VcxStateType::VcxStateRequestReceived - once `PresentationRequest` messages answering the proposal is received.
         VcxStateType::VcxStateRequestReceived - once `vcx_disclosed_proof_create_with_request` (create DisclosedProof object) is called.
 
         VcxStateType::VcxStateRequestReceived - once `vcx_disclosed_proof_generate_proof` is called.
@@ -33,8 +38,14 @@ use utils::threadpool::spawn;
     # Transitions
 
     aries: RFC - https://github.com/hyperledger/aries-rfcs/tree/7b6b93acbaf9611d3c892c4bada142fe2613de6e/features/0037-present-proof#propose-presentation
+        VcxStateType::None - `vcx_disclosed_proof_create_with_proposal` - VcxStateType::VcxStateInitialized
         VcxStateType::None - `vcx_disclosed_proof_create_with_request` - VcxStateType::VcxStateRequestReceived
 
+        VcxStateType::VcxStateInitialized - `vcx_disclosed_proof_send_proposal` - VcxStateType::VcxStateProposalSent
+
+        VcxStateType::VcxStateProposalSent - received `PresentationRequest` - VcxStateType::VcxStateRequestReceived
+        VcxStateType::VcxStateProposalSent - received `ProblemReport` - VcxStateType::None
+
         VcxStateType::VcxStateRequestReceived - `vcx_disclosed_proof_generate_proof` - VcxStateType::VcxStateRequestReceived
 
         VcxStateType::VcxStateRequestReceived - `vcx_disclosed_proof_send_proof` - VcxStateType::VcxStateAccepted
@@ -102,6 +113,112 @@ pub extern fn vcx_disclosed_proof_create_with_request(command_handle: CommandHan
     error::SUCCESS.code_num
 }
 
+/// Create a Proof object which starts the exchange by proposing a presentation to the verifier
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// source_id: Institution's identification for the proof, should be unique.
+///
+/// proposal: presentation preview describing attributes and predicates the prover is going to disclose
+///     (the same format as `proposal` of `vcx_disclosed_proof_decline_presentation_request`)
+///
+/// comment: (Optional) human-readable comment attached to the proposal
+///
+/// cb: Callback that provides proof handle or error status
+///
+/// #Returns
+/// Error code as u32
+#[no_mangle]
+pub extern fn vcx_disclosed_proof_create_with_proposal(command_handle: CommandHandle,
+                                                       source_id: *const c_char,
+                                                       proposal: *const c_char,
+                                                       comment: *const c_char,
+                                                       cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, handle: u32)>) -> u32 {
+    info!("vcx_disclosed_proof_create_with_proposal >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(source_id, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(proposal, VcxErrorKind::InvalidOption);
+    check_useful_opt_c_str!(comment, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_disclosed_proof_create_with_proposal(command_handle: {}, source_id: {}, proposal: {}, comment: {:?})",
+           command_handle, source_id, proposal, comment);
+
+    spawn(move || {
+        match disclosed_proof::create_proof_with_proposal(&source_id, &proposal, comment) {
+            Ok(x) => {
+                trace!("vcx_disclosed_proof_create_with_proposal_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                       command_handle, error::SUCCESS.message, x, source_id);
+                cb(command_handle, 0, x);
+            }
+            Err(x) => {
+                error!("vcx_disclosed_proof_create_with_proposal_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                       command_handle, x, 0, source_id);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Send the presentation proposal of the proof object created with `vcx_disclosed_proof_create_with_proposal`.
+/// Presentation Request answering the proposal is picked up by `vcx_disclosed_proof_update_state`.
+///
+/// #params
+/// command_handle: command handle to map callback to API user context.
+///
+/// proof_handle: proof handle that was provided duration creation.  Used to identify proof object.
+///
+/// connection_handle: Connection handle that identifies pairwise connection
+///
+/// cb: Callback that provides error status of proposal send request
+///
+/// #Returns
+/// Error code as u32
+#[no_mangle]
+pub extern fn vcx_disclosed_proof_send_proposal(command_handle: CommandHandle,
+                                                proof_handle: u32,
+                                                connection_handle: u32,
+                                                cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_disclosed_proof_send_proposal >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    if !disclosed_proof::is_valid_handle(proof_handle) {
+        return VcxError::from(VcxErrorKind::InvalidDisclosedProofHandle).into();
+    }
+
+    if !connection::is_valid_handle(connection_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    let source_id = disclosed_proof::get_source_id(proof_handle).unwrap_or_default();
+    trace!("vcx_disclosed_proof_send_proposal(command_handle: {}, proof_handle: {}, connection_handle: {}) source_id: {}",
+           command_handle, proof_handle, connection_handle, source_id);
+
+    spawn(move || {
+        match disclosed_proof::send_proposal(proof_handle, connection_handle) {
+            Ok(_) => {
+                trace!("vcx_disclosed_proof_send_proposal_cb(command_handle: {}, rc: {}) source_id: {}",
+                       command_handle, error::SUCCESS.message, source_id);
+                cb(command_handle, error::SUCCESS.code_num);
+            }
+            Err(x) => {
+                error!("vcx_disclosed_proof_send_proposal_cb(command_handle: {}, rc: {}) source_id: {}",
+                       command_handle, x, source_id);
+                cb(command_handle, x.into());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
 
 /// Create a proof based off of a known message id for a given connection.
 ///
@@ -1097,6 +1214,31 @@ mod tests {
         cb.receive(TimeoutUtils::some_medium()).unwrap();
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_send_proposal() {
+        let _setup = SetupAriesMocks::init();
+
+        let proposal = json!({"attributes": [{"name": "first name"}], "predicates": []}).to_string();
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        assert_eq!(vcx_disclosed_proof_create_with_proposal(cb.command_handle,
+                                                            CString::new("test_create").unwrap().into_raw(),
+                                                            CString::new(proposal).unwrap().into_raw(),
+                                                            ptr::null(),
+                                                            Some(cb.get_callback())),
+                   error::SUCCESS.code_num);
+        let handle_proof = cb.receive(TimeoutUtils::some_medium()).unwrap();
+        assert_eq!(disclosed_proof::get_state(handle_proof).unwrap(), VcxStateType::VcxStateInitialized as u32);
+
+        let handle_conn = connection::tests::build_test_connection_inviter_requested();
+
+        let cb = return_types_u32::Return_U32::new().unwrap();
+        assert_eq!(vcx_disclosed_proof_send_proposal(cb.command_handle, handle_proof, handle_conn, Some(cb.get_callback())), error::SUCCESS.code_num);
+        cb.receive(TimeoutUtils::some_medium()).unwrap();
+        assert_eq!(disclosed_proof::get_state(handle_proof).unwrap(), VcxStateType::VcxStateProposalSent as u32);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_vcx_reject_proof_request() {
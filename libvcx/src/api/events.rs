@@ -0,0 +1,127 @@
+use std::sync::Arc;
+
+use indy_sys::CommandHandle;
+use libc::c_char;
+
+use error::prelude::*;
+use events::{self, StateEvent, StateSubscriber};
+use utils::cstring::CStringUtils;
+use utils::error;
+use utils::threadpool::spawn;
+
+pub type StateEventCB = extern fn(xcommand_handle: CommandHandle, event: *const c_char);
+
+struct CallbackSubscriber {
+    command_handle: CommandHandle,
+    cb: StateEventCB,
+}
+
+impl StateSubscriber for CallbackSubscriber {
+    fn on_state_changed(&self, event: &StateEvent) {
+        let command_handle = self.command_handle;
+        let cb = self.cb;
+        let event = json!(event).to_string();
+
+        spawn(move || {
+            trace!("vcx_subscribe_state_events_cb(command_handle: {}, event: {})", command_handle, event);
+
+            let event = CStringUtils::string_to_cstring(event);
+            cb(command_handle, event.as_ptr());
+
+            Ok(())
+        });
+    }
+}
+
+/// Subscribes for state transitions of connections, credentials and proofs.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context, passed to every `event_cb` call.
+///
+/// event_cb: handler called on every message handled by the objects with the event json:
+///     {
+///         "type": "connection" | "credential" | "disclosed_proof" | "issuer_credential" | "proof",
+///         "handle": handle of the object,
+///         "source_id": string,
+///         "thread_id": Optional<string>,
+///         "old_state": state before the transition,
+///         "new_state": state after the transition, equal to "old_state" when the message left the state unchanged,
+///         "message_type": name of the message which triggered the transition, e.g. "CredentialOffer"
+///     }
+///     The handler is called from a library thread, it can call library functions.
+///
+/// subscription_handle_p: reference that will contain the subscription handle
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_subscribe_state_events(command_handle: CommandHandle,
+                                         event_cb: Option<StateEventCB>,
+                                         subscription_handle_p: *mut u32) -> u32 {
+    info!("vcx_subscribe_state_events >>>");
+
+    check_useful_c_callback!(event_cb, VcxErrorKind::InvalidOption);
+
+    if subscription_handle_p.is_null() {
+        return VcxError::from_msg(VcxErrorKind::InvalidOption, "Subscription handle reference is null").into();
+    }
+
+    trace!("vcx_subscribe_state_events(command_handle: {})", command_handle);
+
+    let subscription_handle = events::subscribe(Arc::new(CallbackSubscriber { command_handle, cb: event_cb }));
+
+    unsafe { *subscription_handle_p = subscription_handle; }
+
+    trace!("vcx_subscribe_state_events <<< subscription_handle: {}", subscription_handle);
+
+    error::SUCCESS.code_num
+}
+
+/// Stops delivering state transitions to the subscriber.
+///
+/// #Params
+/// subscription_handle: subscription handle returned by vcx_subscribe_state_events
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_unsubscribe_state_events(subscription_handle: u32) -> u32 {
+    info!("vcx_unsubscribe_state_events >>>");
+
+    trace!("vcx_unsubscribe_state_events(subscription_handle: {})", subscription_handle);
+
+    match events::unsubscribe(subscription_handle) {
+        Ok(()) => error::SUCCESS.code_num,
+        Err(err) => {
+            warn!("vcx_unsubscribe_state_events: {}", err);
+            err.into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ptr;
+
+    use utils::devsetup::SetupAriesMocks;
+
+    use super::*;
+
+    extern fn _event_cb(_command_handle: CommandHandle, _event: *const c_char) {}
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_subscribe_state_events() {
+        let _setup = SetupAriesMocks::init();
+
+        let mut subscription_handle = 0;
+        assert_eq!(vcx_subscribe_state_events(0, Some(_event_cb), &mut subscription_handle), error::SUCCESS.code_num);
+        assert!(subscription_handle > 0);
+
+        assert_eq!(vcx_subscribe_state_events(0, None, &mut subscription_handle), error::INVALID_OPTION.code_num);
+        assert_eq!(vcx_subscribe_state_events(0, Some(_event_cb), ptr::null_mut()), error::INVALID_OPTION.code_num);
+
+        assert_eq!(vcx_unsubscribe_state_events(subscription_handle), error::SUCCESS.code_num);
+        assert_eq!(vcx_unsubscribe_state_events(subscription_handle), error::INVALID_OBJ_HANDLE.code_num);
+    }
+}
@@ -22,9 +22,12 @@ use utils::threadpool::spawn;
 
         VcxStateType::VcxStateInitialized - once `vcx_issuer_create_credential` (create IssuerCredential object) is called.
 
+        VcxStateType::VcxStateProposalReceived - once `vcx_issuer_create_credential_from_proposal` (create IssuerCredential object for unsolicited `CredentialProposal`) is called.
+
         VcxStateType::VcxStateOfferSent - once `vcx_issuer_send_credential_offer` (send `CredentialOffer` message) is called.
 
         VcxStateType::VcxStateRequestReceived - once `CredentialRequest` messages is received.
+        VcxStateType::VcxStateProposalReceived - once `CredentialProposal` messages is received.
         VcxStateType::None - once `ProblemReport` messages is received.
                                                 use `vcx_issuer_credential_update_state` or `vcx_issuer_credential_update_state_with_message` functions for state updates.
 
@@ -33,12 +36,17 @@ use utils::threadpool::spawn;
     # Transitions
     RFC - https://github.com/hyperledger/aries-rfcs/tree/7b6b93acbaf9611d3c892c4bada142fe2613de6e/features/0036-issue-credential
         VcxStateType::None - `vcx_issuer_create_credential` - VcxStateType::VcxStateInitialized
+        VcxStateType::None - `vcx_issuer_create_credential_from_proposal` - VcxStateType::VcxStateProposalReceived
 
         VcxStateType::VcxStateInitialized - `vcx_issuer_send_credential_offer` - VcxStateType::VcxStateOfferSent
 
         VcxStateType::VcxStateOfferSent - received `CredentialRequest` - VcxStateType::VcxStateRequestReceived
+        VcxStateType::VcxStateOfferSent - received `CredentialProposal` - VcxStateType::VcxStateProposalReceived
         VcxStateType::VcxStateOfferSent - received `ProblemReport` - VcxStateType::None
 
+        VcxStateType::VcxStateProposalReceived - `vcx_issuer_send_counter_offer` - VcxStateType::VcxStateOfferSent
+        VcxStateType::VcxStateProposalReceived - `vcx_issuer_decline_credential_proposal` - VcxStateType::None
+
         VcxStateType::VcxStateRequestReceived - vcx_issuer_send_credential` - VcxStateType::VcxStateAccepted
 
         VcxStateType::VcxStateAccepted - received `Ack` - VcxStateType::VcxStateAccepted
@@ -149,6 +157,71 @@ pub extern fn vcx_issuer_create_credential(command_handle: CommandHandle,
     error::SUCCESS.code_num
 }
 
+/// Create a Issuer Credential object for Credential Proposal the holder sent without receiving an offer first.
+/// The object is in VcxStateProposalReceived state, proposal can be answered with `vcx_issuer_send_counter_offer`
+/// or declined with `vcx_issuer_decline_credential_proposal`.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// source_id: Enterprise's personal identification for the user.
+///
+/// cred_def_handle: handle of credential definition the proposal refers to
+///
+/// connection_handle: Connection handle that identifies pairwise connection the proposal was received on
+///
+/// proposal: received Credential Proposal message
+///
+/// cb: Callback that provides credential handle and error status of request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_issuer_create_credential_from_proposal(command_handle: CommandHandle,
+                                                         source_id: *const c_char,
+                                                         cred_def_handle: u32,
+                                                         connection_handle: u32,
+                                                         proposal: *const c_char,
+                                                         cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, credential_handle: u32)>) -> u32 {
+    info!("vcx_issuer_create_credential_from_proposal >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(source_id, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(proposal, VcxErrorKind::InvalidOption);
+
+    if !::credential_def::is_valid_handle(cred_def_handle) {
+        return VcxError::from(VcxErrorKind::InvalidCredDefHandle).into();
+    }
+
+    if !connection::is_valid_handle(connection_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    trace!("vcx_issuer_create_credential_from_proposal(command_handle: {}, source_id: {}, cred_def_handle: {}, connection_handle: {}, proposal: {})",
+           command_handle, source_id, cred_def_handle, connection_handle, secret!(&proposal));
+
+    spawn(move || {
+        let (rc, handle) = match issuer_credential::issuer_credential_create_from_proposal(&source_id, cred_def_handle, connection_handle, &proposal) {
+            Ok(x) => {
+                trace!("vcx_issuer_create_credential_from_proposal_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                       command_handle, error::SUCCESS.message, x, source_id);
+                (error::SUCCESS.code_num, x)
+            }
+            Err(x) => {
+                warn!("vcx_issuer_create_credential_from_proposal_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                      command_handle, x, 0, source_id);
+                (x.into(), 0)
+            }
+        };
+
+        cb(command_handle, rc, handle);
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
 /// Send a credential offer to user showing what will be included in the actual credential
 ///
 /// #Params
@@ -253,6 +326,175 @@ pub extern fn vcx_issuer_get_credential_offer_msg(command_handle: CommandHandle,
     error::SUCCESS.code_num
 }
 
+/// Gets the Credential Proposal received from the holder (in VcxStateProposalReceived state).
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// credential_handle: Credential handle that was provided during creation. Used to identify credential object
+///
+/// cb: Callback that provides the proposal message json and error status
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_issuer_get_credential_proposal(command_handle: CommandHandle,
+                                                 credential_handle: u32,
+                                                 cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, proposal: *const c_char)>) -> u32 {
+    info!("vcx_issuer_get_credential_proposal >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    let source_id = issuer_credential::get_source_id(credential_handle).unwrap_or_default();
+    trace!("vcx_issuer_get_credential_proposal(command_handle: {}, credential_handle: {}) source_id: {}",
+           command_handle, credential_handle, source_id);
+
+    if !issuer_credential::is_valid_handle(credential_handle) {
+        return VcxError::from(VcxErrorKind::InvalidIssuerCredentialHandle).into();
+    }
+
+    spawn(move || {
+        match issuer_credential::get_credential_proposal(credential_handle) {
+            Ok(proposal) => {
+                trace!("vcx_issuer_get_credential_proposal_cb(command_handle: {}, credential_handle: {}, rc: {}, proposal: {}) source_id: {}",
+                       command_handle, credential_handle, error::SUCCESS.message, proposal, source_id);
+                let proposal = CStringUtils::string_to_cstring(proposal);
+                cb(command_handle, error::SUCCESS.code_num, proposal.as_ptr());
+            }
+            Err(x) => {
+                warn!("vcx_issuer_get_credential_proposal_cb(command_handle: {}, credential_handle: {}, rc: {}) source_id: {}",
+                      command_handle, credential_handle, x, source_id);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Answers the received Credential Proposal with a new Credential Offer on the same thread.
+/// The offer contains attribute values taken from the proposal preview.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// credential_handle: Credential handle that was provided during creation. Used to identify credential object
+///
+/// connection_handle: Connection handle that identifies pairwise connection
+///
+/// comment: (Optional) human-friendly comment attached to the offer
+///
+/// cb: Callback that provides error status of sending the offer
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_issuer_send_counter_offer(command_handle: CommandHandle,
+                                            credential_handle: u32,
+                                            connection_handle: u32,
+                                            comment: *const c_char,
+                                            cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_issuer_send_counter_offer >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_opt_c_str!(comment, VcxErrorKind::InvalidOption);
+
+    let source_id = issuer_credential::get_source_id(credential_handle).unwrap_or_default();
+    trace!("vcx_issuer_send_counter_offer(command_handle: {}, credential_handle: {}, connection_handle: {}, comment: {:?}) source_id: {}",
+           command_handle, credential_handle, connection_handle, comment, source_id);
+
+    if !issuer_credential::is_valid_handle(credential_handle) {
+        return VcxError::from(VcxErrorKind::InvalidIssuerCredentialHandle).into();
+    }
+
+    if !connection::is_valid_handle(connection_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    spawn(move || {
+        let err = match issuer_credential::send_counter_offer(credential_handle, connection_handle, comment) {
+            Ok(x) => {
+                trace!("vcx_issuer_send_counter_offer_cb(command_handle: {}, credential_handle: {}, rc: {}) source_id: {}",
+                       command_handle, credential_handle, error::SUCCESS.message, source_id);
+                x
+            }
+            Err(x) => {
+                warn!("vcx_issuer_send_counter_offer_cb(command_handle: {}, credential_handle: {}, rc: {}) source_id: {}",
+                      command_handle, credential_handle, x, source_id);
+                x.into()
+            }
+        };
+
+        cb(command_handle, err);
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Declines the received Credential Proposal by sending a Problem Report with the given reason.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// credential_handle: Credential handle that was provided during creation. Used to identify credential object
+///
+/// connection_handle: Connection handle that identifies pairwise connection
+///
+/// reason: human-readable reason of declining
+///
+/// cb: Callback that provides error status of declining the proposal
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_issuer_decline_credential_proposal(command_handle: CommandHandle,
+                                                     credential_handle: u32,
+                                                     connection_handle: u32,
+                                                     reason: *const c_char,
+                                                     cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_issuer_decline_credential_proposal >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(reason, VcxErrorKind::InvalidOption);
+
+    let source_id = issuer_credential::get_source_id(credential_handle).unwrap_or_default();
+    trace!("vcx_issuer_decline_credential_proposal(command_handle: {}, credential_handle: {}, connection_handle: {}, reason: {}) source_id: {}",
+           command_handle, credential_handle, connection_handle, reason, source_id);
+
+    if !issuer_credential::is_valid_handle(credential_handle) {
+        return VcxError::from(VcxErrorKind::InvalidIssuerCredentialHandle).into();
+    }
+
+    if !connection::is_valid_handle(connection_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    spawn(move || {
+        let err = match issuer_credential::decline_credential_proposal(credential_handle, connection_handle, &reason) {
+            Ok(x) => {
+                trace!("vcx_issuer_decline_credential_proposal_cb(command_handle: {}, credential_handle: {}, rc: {}) source_id: {}",
+                       command_handle, credential_handle, error::SUCCESS.message, source_id);
+                x
+            }
+            Err(x) => {
+                warn!("vcx_issuer_decline_credential_proposal_cb(command_handle: {}, credential_handle: {}, rc: {}) source_id: {}",
+                      command_handle, credential_handle, x, source_id);
+                x.into()
+            }
+        };
+
+        cb(command_handle, err);
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
 /// Query the agency for the received messages.
 /// Checks for any messages changing state in the object and updates the state attribute.
 ///
@@ -909,6 +1151,30 @@ pub mod tests {
         assert!(handle > 0);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_issuer_create_credential_from_proposal() {
+        let _setup = SetupStrictAriesMocks::init();
+
+        let connection_handle = ::connection::tests::build_test_connection_inviter_requested();
+        let cred_def_handle = ::credential_def::tests::create_cred_def_fake();
+
+        let mut proposal = ::aries::messages::issuance::credential_proposal::tests::_credential_proposal();
+        proposal.cred_def_id = ::credential_def::get_cred_def_id(cred_def_handle).unwrap();
+        let proposal = json!(proposal.to_a2a_message()).to_string();
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        assert_eq!(vcx_issuer_create_credential_from_proposal(cb.command_handle,
+                                                              CString::new(DEFAULT_CREDENTIAL_NAME).unwrap().into_raw(),
+                                                              cred_def_handle,
+                                                              connection_handle,
+                                                              CString::new(proposal).unwrap().into_raw(),
+                                                              Some(cb.get_callback())),
+                   error::SUCCESS.code_num);
+        let handle = cb.receive(TimeoutUtils::some_medium()).unwrap();
+        assert_eq!(VcxStateType::VcxStateProposalReceived as u32, issuer_credential::get_state(handle).unwrap());
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_vcx_issuer_create_credential_fails() {
@@ -1029,6 +1295,21 @@ pub mod tests {
         let _msg = cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap();
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_issuer_get_credential_proposal_fails_without_proposal() {
+        let _setup = SetupAriesMocks::init();
+
+        let handle = _vcx_issuer_create_credential_c_closure().unwrap();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_issuer_get_credential_proposal(cb.command_handle,
+                                                      handle,
+                                                      Some(cb.get_callback())),
+                   error::SUCCESS.code_num);
+        assert_eq!(cb.receive(TimeoutUtils::some_medium()).unwrap_err(), error::NOT_READY.code_num);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     #[cfg(feature = "to_restore")] // easyfix after merge of v2_update
@@ -0,0 +1,186 @@
+use std::ptr;
+
+use indy_sys::CommandHandle;
+use libc::c_char;
+
+use connection;
+use error::prelude::*;
+use mediation::*;
+use utils::cstring::CStringUtils;
+use utils::error;
+use utils::threadpool::spawn;
+
+/*
+    The API represents a mediator (Aries RFC 0211 Coordinate Mediation) the library is using instead of the agency.
+
+    Once mediation is granted, every newly created connection registers its pairwise key with the mediator
+    and puts mediator's endpoint and routing keys into its DIDDoc.
+    Messages sent to mediated connections are queued by the mediator and must be picked up (Aries RFC 0212 Pickup)
+    using `vcx_mediator_pickup`, which passes them to their connections and the objects owning their threads.
+*/
+
+/// Requests mediation over an established connection.
+///
+/// # Params
+/// command_handle: command handle to map callback to user context.
+///
+/// connection_handle: connection to the mediator. The connection must be completed.
+///
+/// cb: Callback that provides mediator json. It can be passed as `mediator` field of `vcx_init` config
+///     to keep using the mediator in the next session.
+///
+/// # Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_mediator_request(command_handle: CommandHandle,
+                                   connection_handle: u32,
+                                   cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, mediator: *const c_char)>) -> u32 {
+    info!("vcx_mediator_request >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_mediator_request(command_handle: {}, connection_handle: {})", command_handle, connection_handle);
+
+    if !connection::is_valid_handle(connection_handle) {
+        error!("vcx_mediator_request - invalid handle");
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    spawn(move || {
+        match request_mediation(connection_handle) {
+            Ok(mediator) => {
+                trace!("vcx_mediator_request_cb(command_handle: {}, rc: {}, mediator: {})",
+                       command_handle, error::SUCCESS.message, mediator);
+                let mediator = CStringUtils::string_to_cstring(mediator);
+                cb(command_handle, error::SUCCESS.code_num, mediator.as_ptr());
+            }
+            Err(x) => {
+                warn!("vcx_mediator_request_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Retrieves the number of messages queued by the mediator.
+///
+/// # Params
+/// command_handle: command handle to map callback to user context.
+///
+/// cb: Callback that provides the number of queued messages
+///
+/// # Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_mediator_get_message_count(command_handle: CommandHandle,
+                                             cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, count: u32)>) -> u32 {
+    info!("vcx_mediator_get_message_count >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_mediator_get_message_count(command_handle: {})", command_handle);
+
+    spawn(move || {
+        match get_message_count() {
+            Ok(count) => {
+                trace!("vcx_mediator_get_message_count_cb(command_handle: {}, rc: {}, count: {})",
+                       command_handle, error::SUCCESS.message, count);
+                cb(command_handle, error::SUCCESS.code_num, count);
+            }
+            Err(x) => {
+                warn!("vcx_mediator_get_message_count_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Picks up messages queued by the mediator and updates the connections and objects they belong to,
+/// as `vcx_update_all` does. Only processed messages are removed from the mediator's queue.
+///
+/// # Params
+/// command_handle: command handle to map callback to user context.
+///
+/// batch_size: maximum number of messages to pick up
+///
+/// cb: Callback that provides json array of picked up messages:
+///     [{"recipient_verkey": string, "connection_handle": Optional<int>, "message": {...}}]
+///
+/// # Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_mediator_pickup(command_handle: CommandHandle,
+                                  batch_size: u32,
+                                  cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, messages: *const c_char)>) -> u32 {
+    info!("vcx_mediator_pickup >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_mediator_pickup(command_handle: {}, batch_size: {})", command_handle, batch_size);
+
+    spawn(move || {
+        match pickup_messages(batch_size) {
+            Ok(messages) => {
+                trace!("vcx_mediator_pickup_cb(command_handle: {}, rc: {}, messages: {})",
+                       command_handle, error::SUCCESS.message, messages);
+                let messages = CStringUtils::string_to_cstring(messages);
+                cb(command_handle, error::SUCCESS.code_num, messages.as_ptr());
+            }
+            Err(x) => {
+                warn!("vcx_mediator_pickup_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+#[cfg(test)]
+mod tests {
+    use api::return_types_u32;
+    use aries::messages::pickup::batch::Batch;
+    use mediation::tests::set_test_mediator;
+    use utils::devsetup::*;
+    use utils::httpclient::AgencyMockDecrypted;
+    use utils::timeout::TimeoutUtils;
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_mediator_request_fails_for_invalid_handle() {
+        let _setup = SetupAriesMocks::init();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        let rc = vcx_mediator_request(cb.command_handle, 0, Some(cb.get_callback()));
+        assert_eq!(rc, error::INVALID_CONNECTION_HANDLE.code_num);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_mediator_pickup_works() {
+        let _setup = SetupAriesMocks::init();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_mediator_pickup(cb.command_handle, 10, Some(cb.get_callback())), error::SUCCESS.code_num);
+        assert_eq!(cb.receive(TimeoutUtils::some_medium()).unwrap_err(), error::NOT_READY.code_num);
+
+        set_test_mediator();
+        AgencyMockDecrypted::set_next_decrypted_message(&json!(Batch::create().to_a2a_message()).to_string());
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_mediator_pickup(cb.command_handle, 10, Some(cb.get_callback())), error::SUCCESS.code_num);
+        assert_eq!("[]", cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap());
+    }
+}
@@ -7,6 +7,9 @@ pub mod credential_def;
 pub mod schema;
 pub mod credential;
 pub mod disclosed_proof;
+pub mod mediation;
+pub mod router;
+pub mod events;
 pub mod wallet;
 pub mod logger;
 pub mod return_types_u32;
@@ -79,6 +82,8 @@ enum_number!(VcxStateType
     VcxStateRevoked = 7,
     VcxStateRedirected = 8,
     VcxStateRejected = 9,
+    VcxStateProposalReceived = 10,
+    VcxStateProposalSent = 11,
 });
 
 impl VcxStateType {
@@ -92,6 +97,8 @@ impl VcxStateType {
             5 => VcxStateType::VcxStateUnfulfilled,
             6 => VcxStateType::VcxStateExpired,
             7 => VcxStateType::VcxStateRevoked,
+            10 => VcxStateType::VcxStateProposalReceived,
+            11 => VcxStateType::VcxStateProposalSent,
             _ => VcxStateType::VcxStateNone,
         }
     }
@@ -22,11 +22,14 @@ use utils::threadpool::spawn;
     aries:
         VcxStateType::VcxStateInitialized - once `vcx_proof_create` (create Proof object) is called.
 
+        VcxStateType::VcxStateProposalReceived - once `vcx_proof_create_from_proposal` (create Proof object for unsolicited `PresentationProposal`) is called.
+
         VcxStateType::VcxStateOfferSent - once `vcx_credential_send_request` (send `PresentationRequest` message) is called.
 
+        VcxStateType::VcxStateProposalReceived - once `PresentationProposal` messages is received.
+
         VcxStateType::VcxStateAccepted - once `Presentation` messages is received.
         VcxStateType::None - once `ProblemReport` messages is received.
-        VcxStateType::None - once `PresentationProposal` messages is received.
         VcxStateType::None - on `Presentation` validation failed.
                                                 use `vcx_proof_update_state` or `vcx_proof_update_state_with_message` functions for state updates.
 
@@ -34,13 +37,18 @@ use utils::threadpool::spawn;
 
     aries: RFC - https://github.com/hyperledger/aries-rfcs/tree/7b6b93acbaf9611d3c892c4bada142fe2613de6e/features/0037-present-proof#propose-presentation
         VcxStateType::None - `vcx_proof_create` - VcxStateType::VcxStateInitialized
+        VcxStateType::None - `vcx_proof_create_from_proposal` - VcxStateType::VcxStateProposalReceived
 
         VcxStateType::VcxStateInitialized - `vcx_credential_send_request` - VcxStateType::VcxStateOfferSent
 
         VcxStateType::VcxStateOfferSent - received `Presentation` - VcxStateType::VcxStateAccepted
-        VcxStateType::VcxStateOfferSent - received `PresentationProposal` - VcxStateType::None
+        VcxStateType::VcxStateOfferSent - received `PresentationProposal` - VcxStateType::VcxStateProposalReceived
         VcxStateType::VcxStateOfferSent - received `ProblemReport` - VcxStateType::None
 
+        VcxStateType::VcxStateProposalReceived - `vcx_proof_accept_proposal` - VcxStateType::VcxStateOfferSent
+        VcxStateType::VcxStateProposalReceived - `vcx_proof_send_counter_request` - VcxStateType::VcxStateOfferSent
+        VcxStateType::VcxStateProposalReceived - `vcx_proof_reject_proposal` - VcxStateType::None
+
     # Messages
 
     aries:
@@ -154,6 +162,63 @@ pub extern fn vcx_proof_create(command_handle: CommandHandle,
     error::SUCCESS.code_num
 }
 
+/// Create a new Proof object for Presentation Proposal the prover sent without receiving a request first.
+/// The object is in VcxStateProposalReceived state, proposal can be answered with `vcx_proof_accept_proposal`,
+/// `vcx_proof_send_counter_request` or rejected with `vcx_proof_reject_proposal`.
+///
+/// #params
+/// command_handle: command handle to map callback to user context.
+///
+/// source_id: Enterprise's personal identification for the user, also used as the name of the later request.
+///
+/// connection_handle: Connection handle that identifies pairwise connection the proposal was received on
+///
+/// presentation_proposal: received Presentation Proposal message
+///
+/// cb: Callback that provides proof handle and error status of request.
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_proof_create_from_proposal(command_handle: CommandHandle,
+                                             source_id: *const c_char,
+                                             connection_handle: u32,
+                                             presentation_proposal: *const c_char,
+                                             cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, proof_handle: u32)>) -> u32 {
+    info!("vcx_proof_create_from_proposal >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(source_id, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(presentation_proposal, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_proof_create_from_proposal(command_handle: {}, source_id: {}, connection_handle: {}, presentation_proposal: {})",
+           command_handle, source_id, connection_handle, presentation_proposal);
+
+    if !connection::is_valid_handle(connection_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    spawn(move || {
+        let (rc, handle) = match proof::create_proof_from_proposal(&source_id, connection_handle, &presentation_proposal) {
+            Ok(x) => {
+                trace!("vcx_proof_create_from_proposal_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                       command_handle, error::SUCCESS.message, x, source_id);
+                (error::SUCCESS.code_num, x)
+            }
+            Err(x) => {
+                warn!("vcx_proof_create_from_proposal_cb(command_handle: {}, rc: {}, handle: {}) source_id: {}",
+                      command_handle, x, 0, source_id);
+                (x.into(), 0)
+            }
+        };
+        cb(command_handle, rc, handle);
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
 /// Query the agency for the received messages.
 /// Checks for any messages changing state in the object and updates the state attribute.
 ///
@@ -579,6 +644,238 @@ pub extern fn vcx_proof_get_request_msg(command_handle: CommandHandle,
     error::SUCCESS.code_num
 }
 
+/// Gets the Presentation Proposal received from the prover (in VcxStateProposalReceived state).
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// proof_handle: Proof handle that was provided during creation. Used to access proof object
+///
+/// cb: Callback that provides the proposal message json and error status
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_proof_get_proposal(command_handle: CommandHandle,
+                                     proof_handle: u32,
+                                     cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, proposal: *const c_char)>) -> u32 {
+    info!("vcx_proof_get_proposal >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    let source_id = proof::get_source_id(proof_handle).unwrap_or_default();
+    trace!("vcx_proof_get_proposal(command_handle: {}, proof_handle: {}) source_id: {}",
+           command_handle, proof_handle, source_id);
+    if !proof::is_valid_handle(proof_handle) {
+        return VcxError::from(VcxErrorKind::InvalidProofHandle).into();
+    }
+
+    spawn(move || {
+        match proof::get_proof_proposal(proof_handle) {
+            Ok(proposal) => {
+                trace!("vcx_proof_get_proposal_cb(command_handle: {}, rc: {}, proof_handle: {}) source_id: {}",
+                       command_handle, error::SUCCESS.code_num, proof_handle, source_id);
+                let proposal = CStringUtils::string_to_cstring(proposal);
+                cb(command_handle, error::SUCCESS.code_num, proposal.as_ptr());
+            }
+            Err(x) => {
+                warn!("vcx_proof_get_proposal_cb(command_handle: {}, rc: {}, proof_handle: {}) source_id: {}",
+                      command_handle, x, proof_handle, source_id);
+                cb(command_handle, x.into(), ptr::null_mut())
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Answers the received Presentation Proposal with a proof request asking for the proposed attributes and predicates.
+/// Proposed credential definitions are used as restrictions. The request continues the proposal thread.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// proof_handle: Proof handle that was provided during creation. Used to access proof object
+///
+/// connection_handle: Connection handle that identifies pairwise connection
+///
+/// cb: provides any error status of the proof_request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_proof_accept_proposal(command_handle: CommandHandle,
+                                        proof_handle: u32,
+                                        connection_handle: u32,
+                                        cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_proof_accept_proposal >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    let source_id = proof::get_source_id(proof_handle).unwrap_or_default();
+    trace!("vcx_proof_accept_proposal(command_handle: {}, proof_handle: {}, connection_handle: {}) source_id: {}",
+           command_handle, proof_handle, connection_handle, source_id);
+    if !proof::is_valid_handle(proof_handle) {
+        return VcxError::from(VcxErrorKind::InvalidProofHandle).into();
+    }
+
+    if !connection::is_valid_handle(connection_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    spawn(move || {
+        let err = match proof::accept_proof_proposal(proof_handle, connection_handle) {
+            Ok(x) => {
+                trace!("vcx_proof_accept_proposal_cb(command_handle: {}, rc: {}, proof_handle: {}) source_id: {}",
+                       command_handle, 0, proof_handle, source_id);
+                x
+            }
+            Err(x) => {
+                warn!("vcx_proof_accept_proposal_cb(command_handle: {}, rc: {}, proof_handle: {}) source_id: {}",
+                      command_handle, x, proof_handle, source_id);
+                x.into()
+            }
+        };
+
+        cb(command_handle, err);
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Answers the received Presentation Proposal with a different proof request on the proposal thread.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// proof_handle: Proof handle that was provided during creation. Used to access proof object
+///
+/// connection_handle: Connection handle that identifies pairwise connection
+///
+/// requested_attrs: Describes requested attribute (the same format as in `vcx_proof_create`)
+///
+/// requested_predicates: predicate specifications prover must provide claim for (the same format as in `vcx_proof_create`)
+///
+/// revocation_interval: revocation interval (the same format as in `vcx_proof_create`)
+///
+/// name: Name of the proof request
+///
+/// cb: provides any error status of the proof_request
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_proof_send_counter_request(command_handle: CommandHandle,
+                                             proof_handle: u32,
+                                             connection_handle: u32,
+                                             requested_attrs: *const c_char,
+                                             requested_predicates: *const c_char,
+                                             revocation_interval: *const c_char,
+                                             name: *const c_char,
+                                             cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_proof_send_counter_request >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(requested_attrs, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(requested_predicates, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(revocation_interval, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(name, VcxErrorKind::InvalidOption);
+
+    let source_id = proof::get_source_id(proof_handle).unwrap_or_default();
+    trace!("vcx_proof_send_counter_request(command_handle: {}, proof_handle: {}, connection_handle: {}, requested_attrs: {}, requested_predicates: {}, revocation_interval: {}, name: {}) source_id: {}",
+           command_handle, proof_handle, connection_handle, requested_attrs, requested_predicates, revocation_interval, name, source_id);
+    if !proof::is_valid_handle(proof_handle) {
+        return VcxError::from(VcxErrorKind::InvalidProofHandle).into();
+    }
+
+    if !connection::is_valid_handle(connection_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    spawn(move || {
+        let err = match proof::send_counter_proof_request(proof_handle, connection_handle, requested_attrs, requested_predicates, revocation_interval, name) {
+            Ok(x) => {
+                trace!("vcx_proof_send_counter_request_cb(command_handle: {}, rc: {}, proof_handle: {}) source_id: {}",
+                       command_handle, 0, proof_handle, source_id);
+                x
+            }
+            Err(x) => {
+                warn!("vcx_proof_send_counter_request_cb(command_handle: {}, rc: {}, proof_handle: {}) source_id: {}",
+                      command_handle, x, proof_handle, source_id);
+                x.into()
+            }
+        };
+
+        cb(command_handle, err);
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Rejects the received Presentation Proposal by sending a Problem Report with the given reason.
+///
+/// #Params
+/// command_handle: command handle to map callback to user context.
+///
+/// proof_handle: Proof handle that was provided during creation. Used to access proof object
+///
+/// connection_handle: Connection handle that identifies pairwise connection
+///
+/// reason: human-readable reason of rejection
+///
+/// cb: provides any error status of the rejection
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_proof_reject_proposal(command_handle: CommandHandle,
+                                        proof_handle: u32,
+                                        connection_handle: u32,
+                                        reason: *const c_char,
+                                        cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_proof_reject_proposal >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(reason, VcxErrorKind::InvalidOption);
+
+    let source_id = proof::get_source_id(proof_handle).unwrap_or_default();
+    trace!("vcx_proof_reject_proposal(command_handle: {}, proof_handle: {}, connection_handle: {}, reason: {}) source_id: {}",
+           command_handle, proof_handle, connection_handle, reason, source_id);
+    if !proof::is_valid_handle(proof_handle) {
+        return VcxError::from(VcxErrorKind::InvalidProofHandle).into();
+    }
+
+    if !connection::is_valid_handle(connection_handle) {
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    spawn(move || {
+        let err = match proof::reject_proof_proposal(proof_handle, connection_handle, &reason) {
+            Ok(x) => {
+                trace!("vcx_proof_reject_proposal_cb(command_handle: {}, rc: {}, proof_handle: {}) source_id: {}",
+                       command_handle, 0, proof_handle, source_id);
+                x
+            }
+            Err(x) => {
+                warn!("vcx_proof_reject_proposal_cb(command_handle: {}, rc: {}, proof_handle: {}) source_id: {}",
+                      command_handle, x, proof_handle, source_id);
+                x.into()
+            }
+        };
+
+        cb(command_handle, err);
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
 
 /// #Params
 /// command_handle: command handle to map callback to user context.
@@ -725,6 +1022,25 @@ mod tests {
         assert!(handle > 0);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_create_proof_from_proposal() {
+        let _setup = SetupAriesMocks::init();
+
+        let connection_handle = build_test_connection_inviter_requested();
+        let proposal = ::aries::messages::proof_presentation::presentation_proposal::tests::_presentation_proposal();
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        assert_eq!(vcx_proof_create_from_proposal(cb.command_handle,
+                                                  CString::new(DEFAULT_PROOF_NAME).unwrap().into_raw(),
+                                                  connection_handle,
+                                                  CString::new(json!(proposal.to_a2a_message()).to_string()).unwrap().into_raw(),
+                                                  Some(cb.get_callback())),
+                   error::SUCCESS.code_num);
+        let handle = cb.receive(TimeoutUtils::some_medium()).unwrap();
+        assert_eq!(proof::get_state(handle).unwrap(), VcxStateType::VcxStateProposalReceived as u32);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_vcx_create_proof_fails() {
@@ -754,6 +1070,19 @@ mod tests {
         let _msg = cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap();
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_proof_get_proposal_fails_without_proposal() {
+        let _setup = SetupAriesMocks::init();
+
+        let proof_handle = create_proof_util().unwrap();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_proof_get_proposal(cb.command_handle, proof_handle, Some(cb.get_callback())),
+                   error::SUCCESS.code_num);
+        assert_eq!(cb.receive(TimeoutUtils::some_medium()).unwrap_err(), error::NOT_READY.code_num);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_vcx_proof_serialize() {
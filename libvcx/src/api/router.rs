@@ -0,0 +1,356 @@
+use std::ptr;
+
+use indy_sys::CommandHandle;
+use libc::c_char;
+
+use connection;
+use error::prelude::*;
+use router::*;
+use utils::cstring::CStringUtils;
+use utils::error;
+use utils::threadpool::spawn;
+
+/*
+    The API represents a router acting as a mediator (Aries RFC 0211 Coordinate Mediation) for other agents.
+
+    The router only accepts mediation requests sent over connections registered with `vcx_router_add_connection`.
+    Once mediation is granted, the client registers its keys and gets the router's endpoint and routing key.
+    Forward messages (Aries RFC 0094) addressed to registered keys are queued until the client picks them up
+    (Aries RFC 0212 Pickup), or posted to the client's endpoint if the client did not ask for return route.
+
+    The application is responsible for receiving messages on the router's endpoint
+    and passing them to `vcx_router_receive_message`.
+    Queued messages are part of the serialized router, the application has to store it to keep them across restarts.
+    The number of messages queued per key is limited by `router_queue_limit` config option (100 by default).
+*/
+
+/// Creates a router.
+///
+/// # Params
+/// command_handle: command handle to map callback to user context.
+///
+/// source_id: institution's personal identification for the router
+///
+/// endpoint: URL the router receives messages on, it is given to the mediated clients
+///
+/// cb: Callback that provides router handle and error status of request
+///
+/// # Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_router_create(command_handle: CommandHandle,
+                                source_id: *const c_char,
+                                endpoint: *const c_char,
+                                cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, router_handle: u32)>) -> u32 {
+    info!("vcx_router_create >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(source_id, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(endpoint, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_router_create(command_handle: {}, source_id: {}, endpoint: {})", command_handle, source_id, endpoint);
+
+    spawn(move || {
+        match create_router(&source_id, &endpoint) {
+            Ok(handle) => {
+                trace!("vcx_router_create_cb(command_handle: {}, rc: {}, handle: {}), source_id: {:?}",
+                       command_handle, error::SUCCESS.message, handle, source_id);
+                cb(command_handle, error::SUCCESS.code_num, handle);
+            }
+            Err(x) => {
+                warn!("vcx_router_create_cb(command_handle: {}, rc: {}, handle: {}), source_id: {:?}",
+                      command_handle, x, 0, source_id);
+                cb(command_handle, x.into(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Allows the counterparty of the connection to request mediation from the router.
+///
+/// # Params
+/// command_handle: command handle to map callback to user context.
+///
+/// router_handle: router handle that was provided during creation.
+///
+/// connection_handle: completed connection to the client
+///
+/// cb: Callback that provides error status of request
+///
+/// # Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_router_add_connection(command_handle: CommandHandle,
+                                        router_handle: u32,
+                                        connection_handle: u32,
+                                        cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_router_add_connection >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_router_add_connection(command_handle: {}, router_handle: {}, connection_handle: {})",
+           command_handle, router_handle, connection_handle);
+
+    if !is_valid_handle(router_handle) {
+        error!("vcx_router_add_connection - invalid router handle");
+        return VcxError::from(VcxErrorKind::InvalidHandle).into();
+    }
+
+    if !connection::is_valid_handle(connection_handle) {
+        error!("vcx_router_add_connection - invalid connection handle");
+        return VcxError::from(VcxErrorKind::InvalidConnectionHandle).into();
+    }
+
+    spawn(move || {
+        match add_connection(router_handle, connection_handle) {
+            Ok(()) => {
+                trace!("vcx_router_add_connection_cb(command_handle: {}, rc: {})", command_handle, error::SUCCESS.message);
+                cb(command_handle, error::SUCCESS.code_num);
+            }
+            Err(x) => {
+                warn!("vcx_router_add_connection_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Processes the packed message received on the router's endpoint.
+///
+/// # Params
+/// command_handle: command handle to map callback to user context.
+///
+/// router_handle: router handle that was provided during creation.
+///
+/// message_raw: raw packed message as it was received
+///
+/// message_len: length of message buffer
+///
+/// cb: Callback that provides the packed answer which must be sent back in the response to the sender.
+///     Empty if there is nothing to return.
+///
+/// # Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_router_receive_message(command_handle: CommandHandle,
+                                         router_handle: u32,
+                                         message_raw: *const u8,
+                                         message_len: u32,
+                                         cb: Option<extern fn(xcommand_handle: CommandHandle,
+                                                              err: u32,
+                                                              answer_raw: *const u8,
+                                                              answer_len: u32)>) -> u32 {
+    info!("vcx_router_receive_message >>>");
+
+    check_useful_c_byte_array!(message_raw, message_len, VcxErrorKind::InvalidOption, VcxErrorKind::InvalidOption);
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_router_receive_message(command_handle: {}, router_handle: {}, message_len: {})",
+           command_handle, router_handle, message_len);
+
+    if !is_valid_handle(router_handle) {
+        error!("vcx_router_receive_message - invalid handle");
+        return VcxError::from(VcxErrorKind::InvalidHandle).into();
+    }
+
+    spawn(move || {
+        match receive_message(router_handle, &message_raw) {
+            Ok(answer) => {
+                trace!("vcx_router_receive_message_cb(command_handle: {}, rc: {}, answer_len: {})",
+                       command_handle, error::SUCCESS.message, answer.len());
+                let (answer_raw, answer_len) = ::utils::cstring::vec_to_pointer(&answer);
+                cb(command_handle, error::SUCCESS.code_num, answer_raw, answer_len);
+            }
+            Err(x) => {
+                warn!("vcx_router_receive_message_cb(command_handle: {}, rc: {})", command_handle, x);
+                cb(command_handle, x.into(), ptr::null_mut(), 0);
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Takes the router object and returns a json string of all its attributes including queued messages
+///
+/// # Params
+/// command_handle: command handle to map callback to user context.
+///
+/// router_handle: router handle that was provided during creation.
+///
+/// cb: Callback that provides json string of the router's attributes and provides error status
+///
+/// # Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_router_serialize(command_handle: CommandHandle,
+                                   router_handle: u32,
+                                   cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, serialized_data: *const c_char)>) -> u32 {
+    info!("vcx_router_serialize >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_router_serialize(command_handle: {}, router_handle: {})", command_handle, router_handle);
+
+    if !is_valid_handle(router_handle) {
+        error!("vcx_router_serialize - invalid handle");
+        return VcxError::from(VcxErrorKind::InvalidHandle).into();
+    }
+
+    spawn(move || {
+        match to_string(router_handle) {
+            Ok(json) => {
+                trace!("vcx_router_serialize_cb(command_handle: {}, router_handle: {}, rc: {}, state: {})",
+                       command_handle, router_handle, error::SUCCESS.message, json);
+                let msg = CStringUtils::string_to_cstring(json);
+                cb(command_handle, error::SUCCESS.code_num, msg.as_ptr());
+            }
+            Err(x) => {
+                warn!("vcx_router_serialize_cb(command_handle: {}, router_handle: {}, rc: {})",
+                      command_handle, router_handle, x);
+                cb(command_handle, x.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Takes a json string representing a router object and recreates an object matching the json
+///
+/// # Params
+/// command_handle: command handle to map callback to user context.
+///
+/// router_data: json string representing a router object. Is an output of `vcx_router_serialize` function.
+///
+/// cb: Callback that provides router handle and provides error status
+///
+/// # Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_router_deserialize(command_handle: CommandHandle,
+                                     router_data: *const c_char,
+                                     cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, router_handle: u32)>) -> u32 {
+    info!("vcx_router_deserialize >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(router_data, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_router_deserialize(command_handle: {}, router_data: {})", command_handle, router_data);
+
+    spawn(move || {
+        let (rc, handle) = match from_string(&router_data) {
+            Ok(x) => {
+                trace!("vcx_router_deserialize_cb(command_handle: {}, rc: {}, handle: {})",
+                       command_handle, error::SUCCESS.message, x);
+                (error::SUCCESS.code_num, x)
+            }
+            Err(x) => {
+                warn!("vcx_router_deserialize_cb(command_handle: {}, rc: {}, handle: {})",
+                      command_handle, x, 0);
+                (x.into(), 0)
+            }
+        };
+
+        cb(command_handle, rc, handle);
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Releases the router object by de-allocating memory
+///
+/// # Params
+/// router_handle: router handle that was provided during creation.
+///
+/// # Returns
+/// Success
+#[no_mangle]
+pub extern fn vcx_router_release(router_handle: u32) -> u32 {
+    info!("vcx_router_release >>>");
+
+    match release(router_handle) {
+        Ok(()) => {
+            trace!("vcx_router_release(router_handle: {}, rc: {})", router_handle, error::SUCCESS.message);
+            error::SUCCESS.code_num
+        }
+        Err(e) => {
+            warn!("vcx_router_release(router_handle: {}), rc: {})", router_handle, e);
+            e.into()
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use std::ffi::CString;
+
+    use api::return_types_u32;
+    use utils::devsetup::*;
+    use utils::timeout::TimeoutUtils;
+
+    use super::*;
+
+    fn _vcx_router_create() -> u32 {
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        let rc = vcx_router_create(cb.command_handle,
+                                   CString::new("router").unwrap().into_raw(),
+                                   CString::new("https://router.example.com").unwrap().into_raw(),
+                                   Some(cb.get_callback()));
+        assert_eq!(rc, error::SUCCESS.code_num);
+        cb.receive(TimeoutUtils::some_medium()).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_router_create_serialize_release() {
+        let _setup = SetupAriesMocks::init();
+
+        let handle = _vcx_router_create();
+        assert!(handle > 0);
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_router_serialize(cb.command_handle, handle, Some(cb.get_callback())), error::SUCCESS.code_num);
+        let serialized = cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap();
+
+        assert_eq!(vcx_router_release(handle), error::SUCCESS.code_num);
+        assert_eq!(vcx_router_release(handle), error::INVALID_OBJ_HANDLE.code_num);
+
+        let cb = return_types_u32::Return_U32_U32::new().unwrap();
+        assert_eq!(vcx_router_deserialize(cb.command_handle, CString::new(serialized).unwrap().into_raw(), Some(cb.get_callback())), error::SUCCESS.code_num);
+        assert!(cb.receive(TimeoutUtils::some_medium()).unwrap() > 0);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_router_receive_message_fails_for_invalid_message() {
+        let _setup = SetupAriesMocks::init();
+
+        let handle = _vcx_router_create();
+
+        let message = b"{\"message\": \"not a json\", \"recipient_verkey\": \"key\"}".to_vec();
+
+        let cb = return_types_u32::Return_U32_BIN::new().unwrap();
+        assert_eq!(vcx_router_receive_message(cb.command_handle, handle, message.as_ptr(), message.len() as u32, Some(cb.get_callback())),
+                   error::SUCCESS.code_num);
+        assert_eq!(cb.receive(TimeoutUtils::some_medium()).unwrap_err(), error::INVALID_JSON.code_num);
+
+        let cb = return_types_u32::Return_U32_BIN::new().unwrap();
+        assert_eq!(vcx_router_receive_message(cb.command_handle, 0, message.as_ptr(), message.len() as u32, Some(cb.get_callback())),
+                   error::INVALID_OBJ_HANDLE.code_num);
+    }
+}
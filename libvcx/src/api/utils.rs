@@ -1,18 +1,22 @@
 use std::ptr;
+use std::sync::Arc;
 use std::thread;
 
 use indy_sys::CommandHandle;
 use libc::c_char;
 use serde_json;
 
+use dispatcher::ObjectType;
 use error::prelude::*;
 use messages;
 use utils::constants::*;
 use utils::cstring::CStringUtils;
 use utils::error;
 use utils::httpclient::AgencyMock;
+use utils::libindy::callback::build_buf;
 use utils::libindy::payments;
 use utils::threadpool::spawn;
+use utils::transport::callback::{CallbackTransport, set_response, TransportSendCB};
 
 #[derive(Deserialize, Debug, Clone)]
 pub struct UpdateAgentInfo {
@@ -417,6 +421,472 @@ pub extern fn vcx_messages_update_status(command_handle: CommandHandle,
     error::SUCCESS.code_num
 }
 
+/// Updates state of all connections, credentials and proofs at once.
+/// Pending messages of completed connections are downloaded with a single request and routed
+/// to the objects by their thread. New credential and proof objects are created for received
+/// credential offers and presentation requests starting a new thread.
+///
+/// #params
+///
+/// command_handle: command handle to map callback to user context.
+///
+/// cb: Callback that provides array of updated objects
+///
+/// # Example updated -> "[{"type":"credential","handle":1832947214,"connection_handle":2931938324,"created":true},{"type":"proof","handle":4049258744,"connection_handle":2931938324}]"
+///     Types: connection, credential, disclosed_proof, issuer_credential, proof
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_update_all(command_handle: CommandHandle,
+                             cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, updated: *const c_char)>) -> u32 {
+    info!("vcx_update_all >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_update_all(command_handle: {})", command_handle);
+
+    spawn(move || {
+        match ::dispatcher::update_all() {
+            Ok(updated) => {
+                trace!("vcx_update_all_cb(command_handle: {}, rc: {}, updated: {})",
+                       command_handle, error::SUCCESS.message, updated);
+
+                let updated = CStringUtils::string_to_cstring(updated);
+                cb(command_handle, error::SUCCESS.code_num, updated.as_ptr());
+            }
+            Err(e) => {
+                warn!("vcx_update_all_cb(command_handle: {}, rc: {}, updated: {})",
+                      command_handle, e, "null");
+
+                cb(command_handle, e.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Loads connection, credential or proof object persisted in the wallet (see `persist_objects` config option).
+///
+/// #params
+///
+/// command_handle: command handle to map callback to user context.
+///
+/// record_id: id of the wallet record: "<type>:<pairwise did>" for connections, "<type>:<thread id>" for others.
+///
+/// cb: Callback that provides the loaded object
+///
+/// # Example object -> "{"id":"credential:b1b31e42-9f04-4dd4-bd1f-3c3e2d4d0c4b","type":"credential","handle":1832947214}"
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_persisted_object_load(command_handle: CommandHandle,
+                                        record_id: *const c_char,
+                                        cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, object: *const c_char)>) -> u32 {
+    info!("vcx_persisted_object_load >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(record_id, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_persisted_object_load(command_handle: {}, record_id: {})", command_handle, record_id);
+
+    spawn(move || {
+        match ::persistence::load(&record_id) {
+            Ok(object) => {
+                let object = json!(object).to_string();
+                trace!("vcx_persisted_object_load_cb(command_handle: {}, rc: {}, object: {})",
+                       command_handle, error::SUCCESS.message, object);
+
+                let object = CStringUtils::string_to_cstring(object);
+                cb(command_handle, error::SUCCESS.code_num, object.as_ptr());
+            }
+            Err(e) => {
+                warn!("vcx_persisted_object_load_cb(command_handle: {}, rc: {}, object: {})",
+                      command_handle, e, "null");
+
+                cb(command_handle, e.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Loads all connections, credentials and proofs persisted in the wallet matching the query.
+///
+/// #params
+///
+/// command_handle: command handle to map callback to user context.
+///
+/// query_json: WQL query over record tags: "type", "source_id", "thread_id" and "state",
+///     e.g. {"type": "credential", "state": "4"}. Use {} to load all the objects.
+///
+/// cb: Callback that provides array of loaded objects
+///
+/// # Example objects -> "[{"id":"connection:V4SGRU86Z58d6TV7PBUe6f","type":"connection","handle":2931938324}]"
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_persisted_objects_load(command_handle: CommandHandle,
+                                         query_json: *const c_char,
+                                         cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, objects: *const c_char)>) -> u32 {
+    info!("vcx_persisted_objects_load >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(query_json, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_persisted_objects_load(command_handle: {}, query_json: {})", command_handle, query_json);
+
+    spawn(move || {
+        match ::persistence::load_all(&query_json) {
+            Ok(objects) => {
+                let objects = json!(objects).to_string();
+                trace!("vcx_persisted_objects_load_cb(command_handle: {}, rc: {}, objects: {})",
+                       command_handle, error::SUCCESS.message, objects);
+
+                let objects = CStringUtils::string_to_cstring(objects);
+                cb(command_handle, error::SUCCESS.code_num, objects.as_ptr());
+            }
+            Err(e) => {
+                warn!("vcx_persisted_objects_load_cb(command_handle: {}, rc: {}, objects: {})",
+                      command_handle, e, "null");
+
+                cb(command_handle, e.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Retrieves the history of state transitions of connection, credential or proof.
+///
+/// #params
+///
+/// command_handle: command handle to map callback to user context.
+///
+/// object_type: "connection" | "credential" | "disclosed_proof" | "issuer_credential" | "proof"
+///
+/// handle: handle of the object
+///
+/// cb: Callback that provides array of transitions ordered from the oldest:
+///     {
+///         "timestamp": RFC 3339 time of the transition,
+///         "old_state": state before the transition,
+///         "new_state": state after the transition,
+///         "message_type": name of the message which triggered the transition, e.g. "CredentialRequest",
+///         "message_id": Optional<string> - id of the received message,
+///         "outcome": "in_progress" | "success" | "failed" | "declined",
+///         "problem_report": Optional<{"code": Optional<string>, "comment": Optional<string>}>
+///     }
+///
+/// # Example history -> "[{"timestamp":"2020-09-08T11:43:17.532+00:00","old_state":1,"new_state":2,"message_type":"CredentialInit","outcome":"in_progress"}]"
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_get_state_history(command_handle: CommandHandle,
+                                    object_type: *const c_char,
+                                    handle: u32,
+                                    cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, history: *const c_char)>) -> u32 {
+    info!("vcx_get_state_history >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(object_type, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_get_state_history(command_handle: {}, object_type: {}, handle: {})", command_handle, object_type, handle);
+
+    let object_type: ObjectType = match serde_json::from_value(json!(object_type)) {
+        Ok(object_type) => object_type,
+        Err(_) => return VcxError::from_msg(VcxErrorKind::InvalidOption, format!("Unknown object type: {}", object_type)).into()
+    };
+
+    spawn(move || {
+        match ::history::get(object_type, handle) {
+            Ok(history) => {
+                trace!("vcx_get_state_history_cb(command_handle: {}, rc: {}, history: {})",
+                       command_handle, error::SUCCESS.message, history);
+
+                let history = CStringUtils::string_to_cstring(history);
+                cb(command_handle, error::SUCCESS.code_num, history.as_ptr());
+            }
+            Err(e) => {
+                warn!("vcx_get_state_history_cb(command_handle: {}, rc: {}, history: {})",
+                      command_handle, e, "null");
+
+                cb(command_handle, e.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Abandons credential issuances and proof requests which expired without an answer of the counterparty.
+/// Expiry of exchanges is set by `protocol_timeouts` config option, e.g. {"issue-credential": 86400, "present-proof": 3600}.
+/// A problem report is sent to the counterparty of every abandoned exchange.
+///
+/// #params
+///
+/// command_handle: command handle to map callback to user context.
+///
+/// cb: Callback that provides array of abandoned objects
+///
+/// # Example expired -> "[{"type":"issuer_credential","handle":1832947214},{"type":"proof","handle":4049258744}]"
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_sweep_expired(command_handle: CommandHandle,
+                                cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, expired: *const c_char)>) -> u32 {
+    info!("vcx_sweep_expired >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_sweep_expired(command_handle: {})", command_handle);
+
+    spawn(move || {
+        match ::expiry::sweep() {
+            Ok(expired) => {
+                trace!("vcx_sweep_expired_cb(command_handle: {}, rc: {}, expired: {})",
+                       command_handle, error::SUCCESS.message, expired);
+
+                let expired = CStringUtils::string_to_cstring(expired);
+                cb(command_handle, error::SUCCESS.code_num, expired.as_ptr());
+            }
+            Err(e) => {
+                warn!("vcx_sweep_expired_cb(command_handle: {}, rc: {}, expired: {})",
+                      command_handle, e, "null");
+
+                cb(command_handle, e.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Retrieves messages queued in the outbox because they could not be delivered to the counterparty.
+/// The outbox is enabled by `outbox` config option set to "true".
+///
+/// #params
+///
+/// command_handle: command handle to map callback to user context.
+///
+/// cb: Callback that provides array of queued messages
+///
+/// # Example messages -> "[{"id":"d5e6a4f4-...","endpoint":"http://localhost:8080","payload":"<base64>","status":"pending","attempts":1,"created":"<RFC 3339>","next_attempt":"<RFC 3339>","last_error":"..."}]"
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_outbox_get_messages(command_handle: CommandHandle,
+                                      cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, messages: *const c_char)>) -> u32 {
+    info!("vcx_outbox_get_messages >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_outbox_get_messages(command_handle: {})", command_handle);
+
+    spawn(move || {
+        match ::outbox::get_messages() {
+            Ok(messages) => {
+                trace!("vcx_outbox_get_messages_cb(command_handle: {}, rc: {}, messages: {})",
+                       command_handle, error::SUCCESS.message, messages);
+
+                let messages = CStringUtils::string_to_cstring(messages);
+                cb(command_handle, error::SUCCESS.code_num, messages.as_ptr());
+            }
+            Err(e) => {
+                warn!("vcx_outbox_get_messages_cb(command_handle: {}, rc: {}, messages: {})",
+                      command_handle, e, "null");
+
+                cb(command_handle, e.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Removes the message from the outbox, so its delivery is not retried anymore.
+///
+/// #params
+///
+/// command_handle: command handle to map callback to user context.
+///
+/// message_id: `@id` of the queued message
+///
+/// cb: Callback that provides error status of the cancellation
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_outbox_cancel(command_handle: CommandHandle,
+                                message_id: *const c_char,
+                                cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32)>) -> u32 {
+    info!("vcx_outbox_cancel >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+    check_useful_c_str!(message_id, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_outbox_cancel(command_handle: {}, message_id: {})", command_handle, message_id);
+
+    spawn(move || {
+        match ::outbox::cancel(&message_id) {
+            Ok(()) => {
+                trace!("vcx_outbox_cancel_cb(command_handle: {}, rc: {})",
+                       command_handle, error::SUCCESS.message);
+
+                cb(command_handle, error::SUCCESS.code_num);
+            }
+            Err(e) => {
+                warn!("vcx_outbox_cancel_cb(command_handle: {}, rc: {})",
+                      command_handle, e);
+
+                cb(command_handle, e.into());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Retries delivery of the queued messages whose backoff elapsed.
+/// It is also done by `vcx_update_all` when the outbox is enabled.
+///
+/// #params
+///
+/// command_handle: command handle to map callback to user context.
+///
+/// cb: Callback that provides array of ids of the delivered messages
+///
+/// # Example delivered -> "["d5e6a4f4-8c3b-4c35-9a4b-6f1f1b5d1a0e"]"
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_outbox_flush(command_handle: CommandHandle,
+                               cb: Option<extern fn(xcommand_handle: CommandHandle, err: u32, delivered: *const c_char)>) -> u32 {
+    info!("vcx_outbox_flush >>>");
+
+    check_useful_c_callback!(cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_outbox_flush(command_handle: {})", command_handle);
+
+    spawn(move || {
+        match ::outbox::flush() {
+            Ok(delivered) => {
+                let delivered = json!(delivered).to_string();
+
+                trace!("vcx_outbox_flush_cb(command_handle: {}, rc: {}, delivered: {})",
+                       command_handle, error::SUCCESS.message, delivered);
+
+                let delivered = CStringUtils::string_to_cstring(delivered);
+                cb(command_handle, error::SUCCESS.code_num, delivered.as_ptr());
+            }
+            Err(e) => {
+                warn!("vcx_outbox_flush_cb(command_handle: {}, rc: {}, delivered: {})",
+                      command_handle, e, "null");
+
+                cb(command_handle, e.into(), ptr::null_mut());
+            }
+        };
+
+        Ok(())
+    });
+
+    error::SUCCESS.code_num
+}
+
+/// Registers the application's transport delivering messages to endpoints of the given URL scheme.
+/// It replaces the transport registered before for the scheme, including built-in HTTP(S) and WebSocket ones.
+///
+/// #params
+///
+/// command_handle: command handle to map callback to user context, passed to every `send_cb` call.
+///
+/// scheme: URL scheme of the endpoints, e.g. "https" or "didcomm"
+///
+/// send_cb: Callback called from a library thread with the endpoint and the packed message to deliver.
+///     The library waits until the application completes the send with `vcx_transport_set_response`
+///     using the provided `send_handle`.
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_register_transport(command_handle: CommandHandle,
+                                     scheme: *const c_char,
+                                     send_cb: Option<TransportSendCB>) -> u32 {
+    info!("vcx_register_transport >>>");
+
+    check_useful_c_str!(scheme, VcxErrorKind::InvalidOption);
+    check_useful_c_callback!(send_cb, VcxErrorKind::InvalidOption);
+
+    trace!("vcx_register_transport(command_handle: {}, scheme: {})", command_handle, scheme);
+
+    ::utils::transport::register_transport(Arc::new(CallbackTransport::new(&scheme, command_handle, send_cb)));
+
+    error::SUCCESS.code_num
+}
+
+/// Completes the send started by the transport registered with `vcx_register_transport`.
+///
+/// #params
+///
+/// send_handle: handle passed to the transport's `send_cb`
+///
+/// err: 0 if the message was delivered, error code otherwise
+///
+/// response: (Optional) answer of the endpoint, e.g. when the message asked for return route
+///
+/// response_len: length of the answer
+///
+/// #Returns
+/// Error code as a u32
+#[no_mangle]
+pub extern fn vcx_transport_set_response(send_handle: CommandHandle,
+                                         err: u32,
+                                         response: *const u8,
+                                         response_len: u32) -> u32 {
+    info!("vcx_transport_set_response >>>");
+
+    trace!("vcx_transport_set_response(send_handle: {}, err: {}, response_len: {})", send_handle, err, response_len);
+
+    let response = if err != error::SUCCESS.code_num {
+        Err(VcxError::from_msg(VcxErrorKind::PostMessageFailed, format!("Transport failed to send message with error: {}", err)))
+    } else if response.is_null() {
+        Ok(Vec::new())
+    } else {
+        Ok(build_buf(response, response_len))
+    };
+
+    match set_response(send_handle, response) {
+        Ok(()) => error::SUCCESS.code_num,
+        Err(err) => {
+            warn!("vcx_transport_set_response: {}", err);
+            err.into()
+        }
+    }
+}
+
 /// Set the pool handle before calling vcx_init_minimal
 ///
 /// #params
@@ -661,5 +1131,99 @@ mod tests {
                    error::SUCCESS.code_num);
         cb.receive(TimeoutUtils::some_medium()).unwrap();
     }
-}
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_update_all() {
+        let _setup = SetupAriesMocks::init();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_update_all(cb.command_handle, Some(cb.get_callback())), error::SUCCESS.code_num);
+        let updated = cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap();
+        ::serde_json::from_str::<Vec<::serde_json::Value>>(&updated).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_persisted_objects_load() {
+        let _setup = SetupAriesMocks::init();
+
+        let query = CStringUtils::string_to_cstring("{}".to_string());
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_persisted_objects_load(cb.command_handle, query.as_ptr(), Some(cb.get_callback())), error::SUCCESS.code_num);
+        let objects = cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap();
+        assert_eq!("[]", objects);
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_persisted_objects_load(cb.command_handle, ptr::null(), Some(cb.get_callback())), error::INVALID_OPTION.code_num);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_get_state_history() {
+        let _setup = SetupAriesMocks::init();
+
+        let handle = ::connection::create_connection("test_vcx_get_state_history").unwrap();
+
+        let object_type = CStringUtils::string_to_cstring("connection".to_string());
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_get_state_history(cb.command_handle, object_type.as_ptr(), handle, Some(cb.get_callback())), error::SUCCESS.code_num);
+        let history = cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap();
+        assert_eq!("[]", history);
+
+        let object_type = CStringUtils::string_to_cstring("schema".to_string());
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_get_state_history(cb.command_handle, object_type.as_ptr(), handle, Some(cb.get_callback())), error::INVALID_OPTION.code_num);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_sweep_expired() {
+        let _setup = SetupAriesMocks::init();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_sweep_expired(cb.command_handle, Some(cb.get_callback())), error::SUCCESS.code_num);
+        let expired = cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap();
+        assert_eq!("[]", expired);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_outbox() {
+        let _setup = SetupAriesMocks::init();
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_outbox_get_messages(cb.command_handle, Some(cb.get_callback())), error::SUCCESS.code_num);
+        assert_eq!("[]", cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap());
+
+        let cb = return_types_u32::Return_U32_STR::new().unwrap();
+        assert_eq!(vcx_outbox_flush(cb.command_handle, Some(cb.get_callback())), error::SUCCESS.code_num);
+        assert_eq!("[]", cb.receive(TimeoutUtils::some_medium()).unwrap().unwrap());
+
+        let message_id = CString::new("testid").unwrap().into_raw();
+        let cb = return_types_u32::Return_U32::new().unwrap();
+        assert_eq!(vcx_outbox_cancel(cb.command_handle, message_id, Some(cb.get_callback())), error::SUCCESS.code_num);
+        cb.receive(TimeoutUtils::some_medium()).unwrap();
+    }
+
+    extern fn _echo_send_cb(_command_handle: CommandHandle, send_handle: CommandHandle, _endpoint: *const c_char, message: *const u8, message_len: u32) {
+        let message = build_buf(message, message_len);
+        thread::spawn(move || {
+            assert_eq!(vcx_transport_set_response(send_handle, error::SUCCESS.code_num, message.as_ptr(), message.len() as u32), error::SUCCESS.code_num);
+        });
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_vcx_register_transport() {
+        let _setup = SetupEmpty::init();
+
+        let scheme = CStringUtils::string_to_cstring("vcx-ffi".to_string());
+        assert_eq!(vcx_register_transport(0, scheme.as_ptr(), None), error::INVALID_OPTION.code_num);
+        assert_eq!(vcx_register_transport(0, ptr::null(), Some(_echo_send_cb)), error::INVALID_OPTION.code_num);
+        assert_eq!(vcx_register_transport(0, scheme.as_ptr(), Some(_echo_send_cb)), error::SUCCESS.code_num);
+
+        assert_eq!(b"message".to_vec(), ::utils::transport::send(b"message", "vcx-ffi://endpoint").unwrap());
+        assert_eq!(vcx_transport_set_response(0, error::SUCCESS.code_num, ptr::null(), 0), error::INVALID_OBJ_HANDLE.code_num);
+    }
+}
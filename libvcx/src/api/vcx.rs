@@ -93,6 +93,12 @@ pub extern fn vcx_init_core(config: *const c_char) -> u32 {
 
     settings::log_settings();
     ::utils::threadpool::init();
+    ::utils::transport::init();
+    if let Err(err) = ::inbound::init() {
+        error!("Cannot start inbound endpoint: {}", err);
+        return err.into();
+    }
+    ::persistence::init();
     error::SUCCESS.code_num
 }
 
@@ -246,6 +252,12 @@ pub extern fn vcx_init(command_handle: CommandHandle,
 fn _finish_init(command_handle: CommandHandle, cb: extern fn(xcommand_handle: CommandHandle, err: u32)) -> u32 {
     info!("_finish_init: Going to finish VCX Init.");
     ::utils::threadpool::init();
+    ::utils::transport::init();
+    if let Err(err) = ::inbound::init() {
+        error!("Cannot start inbound endpoint: {}", err);
+        return err.into();
+    }
+    ::persistence::init();
 
     settings::log_settings();
 
@@ -359,6 +371,12 @@ pub extern fn vcx_init_minimal(config: *const c_char) -> u32 {
     }
 
     ::utils::threadpool::init();
+    ::utils::transport::init();
+    if let Err(err) = ::inbound::init() {
+        error!("Cannot start inbound endpoint: {}", err);
+        return err.into();
+    }
+    ::persistence::init();
 
     settings::log_settings();
 
@@ -408,6 +426,11 @@ pub extern fn vcx_shutdown(delete: bool) -> u32 {
     ::proof::release_all();
     ::disclosed_proof::release_all();
     ::credential::release_all();
+    ::router::release_all();
+    ::inbound::stop();
+    ::inbound::clear();
+    ::persistence::stop();
+    ::events::unsubscribe_all();
 
     if delete {
         let pool_name = settings::get_config_value(settings::CONFIG_POOL_NAME)
@@ -2,24 +2,43 @@ use std::collections::HashMap;
 
 use connection::create_agent_keys;
 use error::prelude::*;
+use inbound;
+use mediation;
 use messages::get_message::{get_connection_messages, Message};
 use messages::MessageStatusCode;
 use messages::update_connection::send_delete_connection_message;
 use messages::update_message::{UIDsByConn, update_messages as update_messages_status};
 use settings;
 use settings::ProtocolTypes;
-use utils::httpclient;
+use outbox::{self, Delivery};
+use replay;
+use tracing::{self, Hop};
 use utils::libindy::signus::create_and_store_my_did;
 use aries::messages::a2a::A2AMessage;
 use aries::messages::connection::did_doc::DidDoc;
+use aries::messages::connection::service::Service;
 use aries::utils::encryption_envelope::EncryptionEnvelope;
 
+const SERVICE_DECORATOR: &str = "~service";
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct AgentInfo {
     pub pw_did: String,
     pub pw_vk: String,
     pub agent_did: String,
     pub agent_vk: String,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "::std::ops::Not::not")]
+    pub mediated: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "::std::ops::Not::not")]
+    pub inbound: bool,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trace_target: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "::std::ops::Not::not")]
+    pub connectionless: bool,
 }
 
 impl Default for AgentInfo {
@@ -29,6 +48,10 @@ impl Default for AgentInfo {
             pw_vk: String::new(),
             agent_did: String::new(),
             agent_vk: String::new(),
+            mediated: false,
+            inbound: false,
+            trace_target: None,
+            connectionless: false,
         }
     }
 }
@@ -43,35 +66,88 @@ impl AgentInfo {
         let method_name = settings::get_config_value(settings::CONFIG_DID_METHOD).ok();
         let (pw_did, pw_vk) = create_and_store_my_did(None, method_name.as_ref().map(String::as_str))?;
 
+        /*
+            With a mediator there is no connection agent, the key is only registered for routing.
+        */
+        if mediation::is_mediation_active() {
+            mediation::get_mediator()?.add_key(&pw_vk)?;
+            return Ok(AgentInfo { pw_did, pw_vk, mediated: true, trace_target: self.trace_target.clone(), connectionless: self.connectionless, ..AgentInfo::default() });
+        }
+
+        /*
+            Messages are received on our own inbound endpoint, no agent is needed either.
+        */
+        if inbound::is_enabled() {
+            return Ok(AgentInfo { pw_did, pw_vk, inbound: true, trace_target: self.trace_target.clone(), connectionless: self.connectionless, ..AgentInfo::default() });
+        }
+
         /*
             Create User Pairwise Agent in old way.
             Send Messages corresponding to V2 Protocol to avoid code changes on Agency side.
         */
         let (agent_did, agent_vk) = create_agent_keys("", &pw_did, &pw_vk)?;
 
-        Ok(AgentInfo { pw_did, pw_vk, agent_did, agent_vk })
+        Ok(AgentInfo { pw_did, pw_vk, agent_did, agent_vk, trace_target: self.trace_target.clone(), connectionless: self.connectionless, ..AgentInfo::default() })
     }
 
     /**
-    Builds one's agency's URL endpoint
+    Builds one's agency's URL endpoint, or returns mediator's or inbound one for connections not using the agency
      */
     pub fn agency_endpoint(&self) -> VcxResult<String> {
+        if self.mediated {
+            return mediation::get_mediator().map(|mediator| mediator.endpoint());
+        }
+
+        if self.inbound {
+            return inbound::get_endpoint();
+        }
+
         settings::get_config_value(settings::CONFIG_AGENCY_ENDPOINT)
             .map(|str| format!("{}/agency/msg", str))
     }
 
     pub fn routing_keys(&self) -> VcxResult<Vec<String>> {
+        if self.mediated {
+            return mediation::get_mediator().map(|mediator| mediator.routing_keys());
+        }
+
+        if self.inbound {
+            return Ok(vec![]);
+        }
+
         let agency_vk = settings::get_config_value(settings::CONFIG_AGENCY_VERKEY)?;
         Ok(vec![self.agent_vk.to_string(), agency_vk])
     }
 
+    /**
+    Returns whether messages of the connection are stored by one's agency.
+     */
+    pub fn uses_agency(&self) -> bool {
+        !self.mediated && !self.inbound
+    }
+
     pub fn recipient_keys(&self) -> Vec<String> {
         vec![self.pw_vk.to_string()]
     }
 
+    /**
+    Builds DIDDoc describing one's connection agent
+     */
+    pub fn did_doc(&self) -> VcxResult<DidDoc> {
+        let mut did_doc = DidDoc::default();
+        did_doc.set_id(self.pw_did.to_string());
+        did_doc.set_service_endpoint(self.agency_endpoint()?);
+        did_doc.set_keys(self.recipient_keys(), self.routing_keys()?);
+        Ok(did_doc)
+    }
+
     pub fn update_message_status(&self, uid: String) -> VcxResult<()> {
         trace!("Agent::update_message_status >>> uid: {:?}", uid);
 
+        if self.mediated || self.inbound {
+            return inbound::remove_message(&self.pw_vk, &uid);
+        }
+
         let messages_to_update = vec![UIDsByConn {
             pairwise_did: self.pw_did.clone(),
             uids: vec![uid],
@@ -83,6 +159,11 @@ impl AgentInfo {
     pub fn get_messages(&self) -> VcxResult<HashMap<String, A2AMessage>> {
         trace!("Agent::get_messages >>>");
 
+        // messages of mediated connection are picked up from the mediator into the inbox
+        if self.mediated || self.inbound {
+            return self.filter_processed(inbound::get_messages(&self.pw_vk)?);
+        }
+
         let messages = get_connection_messages(&self.pw_did,
                                                &self.pw_vk,
                                                &self.agent_did,
@@ -106,12 +187,39 @@ impl AgentInfo {
                 warn!("Fetched decrypted connection messages:\n{}", serialized_msg);
             }
         }
-        Ok(a2a_messages)
+        self.filter_processed(a2a_messages)
+    }
+
+    /**
+    Drops messages which were already processed on the connection and marks them as reviewed, so they are not fetched again.
+     */
+    fn filter_processed(&self, messages: HashMap<String, A2AMessage>) -> VcxResult<HashMap<String, A2AMessage>> {
+        let (messages, duplicates) = replay::filter_processed(&self.pw_did, messages)?;
+
+        for uid in duplicates {
+            self.update_message_status(uid)?;
+        }
+
+        Ok(messages)
+    }
+
+    /**
+    Records that the message received from connection counterparty was handled.
+     */
+    pub fn record_processed(&self, message: &A2AMessage) -> VcxResult<()> {
+        trace!("Agent::record_processed >>> message: {:?}", message);
+        replay::record_processed(&self.pw_did, message)?;
+        tracing::processed(self.trace_target(), message);
+        Ok(())
     }
 
     pub fn get_message_by_id(&self, msg_id: &str) -> VcxResult<A2AMessage> {
         trace!("Agent::get_message_by_id >>> msg_id: {:?}", msg_id);
 
+        if self.mediated || self.inbound {
+            return inbound::get_message(&self.pw_vk, msg_id);
+        }
+
         let mut messages = get_connection_messages(&self.pw_did,
                                                    &self.pw_vk,
                                                    &self.agent_did,
@@ -133,17 +241,33 @@ impl AgentInfo {
     pub fn decode_message(&self, message: &Message) -> VcxResult<A2AMessage> {
         trace!("Agent::decode_message >>> message = {:?}", json!(&message).to_string());
 
-        EncryptionEnvelope::open(message.payload()?)
+        EncryptionEnvelope::open_traced(message.payload()?, self.trace_target())
+    }
+
+    pub fn trace_target(&self) -> Option<&str> {
+        self.trace_target.as_ref().map(String::as_str)
     }
 
     /**
-    Sends authenticated message to connection counterparty
+    Sends authenticated message to connection counterparty.
+    A message queued in outbox is considered sent as its delivery is retried.
      */
     pub fn send_message(&self, message: &A2AMessage, did_dod: &DidDoc) -> VcxResult<()> {
-        trace!("Agent::send_message >>> message: {:?}, did_doc: {:?}", message, did_dod);
-        let envelope = EncryptionEnvelope::create(&message, Some(&self.pw_vk), &did_dod)?;
-        httpclient::post_message(&envelope.0, &did_dod.get_endpoint())?;
-        Ok(())
+        self.deliver_message(message, did_dod).map(|_| ())
+    }
+
+    /**
+    Sends authenticated message to connection counterparty and returns the outcome of the delivery
+     */
+    pub fn deliver_message(&self, message: &A2AMessage, did_dod: &DidDoc) -> VcxResult<Delivery> {
+        trace!("Agent::deliver_message >>> message: {:?}, did_doc: {:?}", message, did_dod);
+
+        if self.connectionless {
+            let message = self.decorate_service(message)?;
+            return AgentInfo::send_traced(&message, Some(&self.pw_vk), did_dod, self.trace_target());
+        }
+
+        AgentInfo::send_traced(message, Some(&self.pw_vk), did_dod, self.trace_target())
     }
 
     /**
@@ -151,16 +275,67 @@ impl AgentInfo {
      */
     pub fn send_message_anonymously(message: &A2AMessage, did_dod: &DidDoc) -> VcxResult<()> {
         trace!("Agent::send_message_anonymously >>> message: {:?}, did_doc: {:?}", message, did_dod);
-        let envelope = EncryptionEnvelope::create(&message, None, &did_dod)?;
-        httpclient::post_message(&envelope.0, &did_dod.get_endpoint())?;
-        Ok(())
+        AgentInfo::send_traced(message, None, did_dod, None).map(|_| ())
+    }
+
+    /**
+    Connectionless exchange has no did doc on the other side, so `~service` tells the counterparty where to reply.
+     */
+    fn decorate_service(&self, message: &A2AMessage) -> VcxResult<A2AMessage> {
+        let service = Service {
+            recipient_keys: self.recipient_keys(),
+            routing_keys: Some(self.routing_keys()?),
+            service_endpoint: self.agency_endpoint()?,
+        };
+
+        let mut message = json!(message);
+
+        message.as_object_mut()
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, "Message is not JSON object"))?
+            .entry(SERVICE_DECORATOR)
+            .or_insert(json!(service));
+
+        Ok(A2AMessage::Generic(message))
+    }
+
+    /**
+    Decorates the message with `~trace` if the connection is traced and reports the hops of the traced message.
+     */
+    fn send_traced(message: &A2AMessage, pw_verkey: Option<&str>, did_dod: &DidDoc, connection_target: Option<&str>) -> VcxResult<Delivery> {
+        let trace_target = tracing::target(connection_target, &json!(message));
+
+        let message = match trace_target {
+            Some(ref target) => tracing::decorate(message, target)?,
+            None => message.clone()
+        };
+        let trace_target = trace_target.as_ref().map(String::as_str);
+
+        let envelope = EncryptionEnvelope::create_traced(&message, pw_verkey, did_dod, trace_target)?;
+        tracing::traced_with_outcome(trace_target, &message, Hop::Send,
+                                     || outbox::send(&message, envelope, &did_dod.get_endpoint()),
+                                     |delivery| match delivery {
+                                         Delivery::Delivered => String::from("OK"),
+                                         Delivery::Queued => String::from("QUEUED: not delivered, queued in outbox for retries"),
+                                         Delivery::Duplicate => String::from("SKIPPED: already sent"),
+                                     })
     }
 
     /**
     Sends message to one's agency signalling resources related to this connection agent can be deleted.
+    For mediated connection the key is removed from mediator's keylist and its picked up messages are dropped.
+    For connection using the inbound endpoint its pending messages are dropped.
      */
     pub fn delete(&self) -> VcxResult<()> {
         trace!("Agent::delete >>>");
+
+        if self.mediated {
+            inbound::remove_messages(&self.pw_vk)?;
+            return mediation::get_mediator()?.remove_key(&self.pw_vk);
+        }
+
+        if self.inbound {
+            return inbound::remove_messages(&self.pw_vk);
+        }
         send_delete_connection_message(&self.pw_did, &self.pw_vk, &self.agent_did, &self.agent_vk)
     }
 }
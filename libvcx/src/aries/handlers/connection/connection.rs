@@ -1,32 +1,50 @@
 use std::collections::HashMap;
+use std::convert::TryInto;
 
+use acks;
+use api::VcxStateType;
 use error::prelude::*;
+use history::{History, Outcome, Problem, Transition};
 use messages::get_message::Message;
+use outbox::Delivery;
+use replay;
+use settings;
+use tracing::{self, TraceTarget};
 use aries::handlers::connection::agent_info::AgentInfo;
 use aries::handlers::connection::invitee::state_machine::{InviteeState, SmConnectionInvitee};
 use aries::handlers::connection::inviter::state_machine::{InviterState, SmConnectionInviter};
 use aries::handlers::connection::messages::DidExchangeMessages;
+use aries::handlers::connection::requester::state_machine::{RequesterState, SmDidExchangeRequester};
+use aries::handlers::connection::responder::state_machine::{ResponderState, SmDidExchangeResponder};
 use aries::messages::a2a::A2AMessage;
+use aries::messages::a2a::message_family::MessageFamilies;
 use aries::messages::basic_message::message::BasicMessage;
 use aries::messages::connection::did_doc::DidDoc;
 use aries::messages::connection::invite::Invitation;
 use aries::messages::discovery::disclose::ProtocolDescriptor;
+use aries::messages::out_of_band::invitation::OutOfBandInvitation;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Connection {
-    connection_sm: SmConnection
+    connection_sm: SmConnection,
+    #[serde(default)]
+    history: History,
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SmConnection {
     Inviter(SmConnectionInviter),
     Invitee(SmConnectionInvitee),
+    Requester(SmDidExchangeRequester),
+    Responder(SmDidExchangeResponder),
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub enum SmConnectionState {
     Inviter(InviterState),
     Invitee(InviteeState),
+    Requester(RequesterState),
+    Responder(ResponderState),
 }
 
 #[derive(Debug, Serialize)]
@@ -46,6 +64,40 @@ struct SideConnectionInfo {
     protocols: Option<Vec<ProtocolDescriptor>>,
 }
 
+/// Protocol used to establish a connection.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum HandshakeProtocol {
+    #[serde(rename = "connections/1.0")]
+    Connections,
+    #[serde(rename = "didexchange/1.0")]
+    DidExchange,
+}
+
+impl Default for HandshakeProtocol {
+    fn default() -> HandshakeProtocol {
+        HandshakeProtocol::Connections
+    }
+}
+
+impl HandshakeProtocol {
+    pub fn family(&self) -> MessageFamilies {
+        match self {
+            HandshakeProtocol::Connections => MessageFamilies::Connections,
+            HandshakeProtocol::DidExchange => MessageFamilies::DidExchange,
+        }
+    }
+
+    /// Matches handshake protocol identifier listed in Out-of-Band invitation (e.g. `https://didcomm.org/didexchange/1.0`).
+    pub fn from_protocol_id(protocol: &str) -> Option<HandshakeProtocol> {
+        [HandshakeProtocol::Connections, HandshakeProtocol::DidExchange].iter()
+            .find(|supported| {
+                let family = supported.family();
+                protocol.ends_with(&format!("/{}/{}", family.to_string(), family.version()))
+            })
+            .cloned()
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum Actor {
     Inviter,
@@ -60,17 +112,52 @@ impl Connection {
         trace!("Connection::create >>> source_id: {}", source_id);
 
         Connection {
-            connection_sm: SmConnection::Inviter(SmConnectionInviter::new(source_id))
+            connection_sm: SmConnection::Inviter(SmConnectionInviter::new(source_id)),
+            history: History::default(),
+        }
+    }
+
+    /**
+    Create Inviter connection state machine which invitation can be used by many invitees.
+     */
+    pub fn create_multi_use(source_id: &str) -> Connection {
+        trace!("Connection::create_multi_use >>> source_id: {}", source_id);
+
+        Connection {
+            connection_sm: SmConnection::Inviter(SmConnectionInviter::new_multi_use(source_id)),
+            history: History::default(),
+        }
+    }
+
+    /**
+    Create Inviter connection state machine using given handshake protocol.
+    For `didexchange/1.0` the invitation is an Out-of-Band invitation.
+     */
+    pub fn create_with_handshake_protocol(source_id: &str, protocol: HandshakeProtocol) -> Connection {
+        trace!("Connection::create_with_handshake_protocol >>> source_id: {}, protocol: {:?}", source_id, protocol);
+
+        match protocol {
+            HandshakeProtocol::Connections => Connection::create(source_id),
+            HandshakeProtocol::DidExchange => Connection {
+                connection_sm: SmConnection::Responder(SmDidExchangeResponder::new(source_id)),
+                history: History::default(),
+            }
         }
     }
 
-    pub fn from_parts(source_id: String, agent_info: AgentInfo, state: SmConnectionState) -> Connection {
+    pub fn from_parts(source_id: String, agent_info: AgentInfo, state: SmConnectionState, history: History) -> Connection {
         match state {
             SmConnectionState::Inviter(state) => {
-                Connection { connection_sm: SmConnection::Inviter(SmConnectionInviter::from(source_id, agent_info, state)) }
+                Connection { connection_sm: SmConnection::Inviter(SmConnectionInviter::from(source_id, agent_info, state)), history }
             }
             SmConnectionState::Invitee(state) => {
-                Connection { connection_sm: SmConnection::Invitee(SmConnectionInvitee::from(source_id, agent_info, state)) }
+                Connection { connection_sm: SmConnection::Invitee(SmConnectionInvitee::from(source_id, agent_info, state)), history }
+            }
+            SmConnectionState::Requester(state) => {
+                Connection { connection_sm: SmConnection::Requester(SmDidExchangeRequester::from(source_id, agent_info, state)), history }
+            }
+            SmConnectionState::Responder(state) => {
+                Connection { connection_sm: SmConnection::Responder(SmDidExchangeResponder::from(source_id, agent_info, state)), history }
             }
         }
     }
@@ -82,7 +169,8 @@ impl Connection {
         trace!("Connection::create_with_invite >>> source_id: {}", source_id);
 
         let mut connection = Connection {
-            connection_sm: SmConnection::Invitee(SmConnectionInvitee::new(source_id))
+            connection_sm: SmConnection::Invitee(SmConnectionInvitee::new(source_id)),
+            history: History::default(),
         };
 
         connection.process_invite(invitation)?;
@@ -90,6 +178,75 @@ impl Connection {
         Ok(connection)
     }
 
+    /**
+    Create Invitee connection state machine from Out-of-Band invitation.
+    The first handshake protocol listed in the invitation which is supported by VCX is used.
+     */
+    pub fn create_with_out_of_band_invite(source_id: &str, invitation: OutOfBandInvitation) -> VcxResult<Connection> {
+        trace!("Connection::create_with_out_of_band_invite >>> source_id: {}", source_id);
+
+        if invitation.is_connectionless() {
+            return Connection::create_connectionless(source_id, invitation);
+        }
+
+        let protocol = invitation.handshake_protocols.iter()
+            .filter_map(|protocol| HandshakeProtocol::from_protocol_id(protocol))
+            .next()
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Out-of-Band invitation does not offer supported handshake protocol: {:?}", invitation.handshake_protocols)))?;
+
+        match protocol {
+            HandshakeProtocol::Connections => {
+                let invitation: Invitation = invitation.try_into()?;
+                Connection::create_with_invite(source_id, invitation)
+            }
+            HandshakeProtocol::DidExchange => {
+                // fail early if the invitation can not be used to reach the inviter
+                invitation.get_service()?;
+
+                let mut connection = Connection {
+                    connection_sm: SmConnection::Requester(SmDidExchangeRequester::new(source_id)),
+                    history: History::default(),
+                };
+
+                connection.step(DidExchangeMessages::OutOfBandInvitationReceived(invitation))?;
+
+                Ok(connection)
+            }
+        }
+    }
+
+    /**
+    Creates connection for Out-of-Band invitation without handshake protocols.
+    Attached requests are answered directly to the service of the invitation.
+     */
+    fn create_connectionless(source_id: &str, invitation: OutOfBandInvitation) -> VcxResult<Connection> {
+        trace!("Connection::create_connectionless >>> source_id: {}", source_id);
+
+        let service = invitation.get_service()?;
+
+        let mut did_doc = DidDoc::default();
+        did_doc.set_id(invitation.id.0.clone());
+        did_doc.set_service_endpoint(service.service_endpoint);
+        did_doc.set_keys(service.recipient_keys, service.routing_keys);
+
+        let agent_info = AgentInfo { connectionless: true, ..AgentInfo::default() }.create_agent()?;
+
+        Ok(Connection {
+            connection_sm: SmConnection::Invitee(SmConnectionInvitee::connectionless(source_id, agent_info, did_doc)),
+            history: History::default(),
+        })
+    }
+
+    /**
+    Returns handshake protocol used by the connection.
+     */
+    pub fn handshake_protocol(&self) -> HandshakeProtocol {
+        match &self.connection_sm {
+            SmConnection::Inviter(_) | SmConnection::Invitee(_) => HandshakeProtocol::Connections,
+            SmConnection::Requester(_) | SmConnection::Responder(_) => HandshakeProtocol::DidExchange,
+        }
+    }
+
     pub fn source_id(&self) -> String {
         match &self.connection_sm {
             SmConnection::Inviter(sm_inviter) => {
@@ -98,9 +255,19 @@ impl Connection {
             SmConnection::Invitee(sm_invitee) => {
                 sm_invitee.source_id()
             }
+            SmConnection::Requester(sm_requester) => {
+                sm_requester.source_id()
+            }
+            SmConnection::Responder(sm_responder) => {
+                sm_responder.source_id()
+            }
         }.into()
     }
 
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
     pub fn state(&self) -> u32 {
         match &self.connection_sm {
             SmConnection::Inviter(sm_inviter) => {
@@ -109,6 +276,12 @@ impl Connection {
             SmConnection::Invitee(sm_invitee) => {
                 sm_invitee.state()
             }
+            SmConnection::Requester(sm_requester) => {
+                sm_requester.state()
+            }
+            SmConnection::Responder(sm_responder) => {
+                sm_responder.state()
+            }
         }
     }
 
@@ -120,6 +293,12 @@ impl Connection {
             SmConnection::Invitee(sm_invitee) => {
                 sm_invitee.agent_info()
             }
+            SmConnection::Requester(sm_requester) => {
+                sm_requester.agent_info()
+            }
+            SmConnection::Responder(sm_responder) => {
+                sm_responder.agent_info()
+            }
         }
     }
 
@@ -131,6 +310,12 @@ impl Connection {
             SmConnection::Invitee(sm_invitee) => {
                 sm_invitee.remote_did()
             }
+            SmConnection::Requester(sm_requester) => {
+                sm_requester.remote_did()
+            }
+            SmConnection::Responder(sm_responder) => {
+                sm_responder.remote_did()
+            }
         }
     }
 
@@ -142,6 +327,12 @@ impl Connection {
             SmConnection::Invitee(sm_invitee) => {
                 sm_invitee.remote_vk()
             }
+            SmConnection::Requester(sm_requester) => {
+                sm_requester.remote_vk()
+            }
+            SmConnection::Responder(sm_responder) => {
+                sm_responder.remote_vk()
+            }
         }
     }
 
@@ -153,6 +344,12 @@ impl Connection {
             SmConnection::Invitee(sm_invitee) => {
                 SmConnectionState::Invitee(sm_invitee.state_object().clone())
             }
+            SmConnection::Requester(sm_requester) => {
+                SmConnectionState::Requester(sm_requester.state_object().clone())
+            }
+            SmConnection::Responder(sm_responder) => {
+                SmConnectionState::Responder(sm_responder.state_object().clone())
+            }
         }
     }
 
@@ -164,6 +361,12 @@ impl Connection {
             SmConnection::Invitee(sm_invitee) => {
                 sm_invitee.source_id()
             }
+            SmConnection::Requester(sm_requester) => {
+                sm_requester.source_id()
+            }
+            SmConnection::Responder(sm_responder) => {
+                sm_responder.source_id()
+            }
         }.to_string()
     }
 
@@ -175,6 +378,12 @@ impl Connection {
             SmConnection::Invitee(sm_invitee) => {
                 sm_invitee.get_protocols()
             }
+            SmConnection::Requester(sm_requester) => {
+                sm_requester.get_protocols()
+            }
+            SmConnection::Responder(sm_responder) => {
+                sm_responder.get_protocols()
+            }
         }
     }
 
@@ -186,9 +395,22 @@ impl Connection {
             SmConnection::Invitee(sm_invitee) => {
                 sm_invitee.get_remote_protocols()
             }
+            SmConnection::Requester(sm_requester) => {
+                sm_requester.get_remote_protocols()
+            }
+            SmConnection::Responder(sm_responder) => {
+                sm_responder.get_remote_protocols()
+            }
         }
     }
 
+    /// Checks whether the other side disclosed support of the protocol via discover-features.
+    pub fn remote_supports_protocol(&self, family: &MessageFamilies) -> bool {
+        let suffix = format!("/{}/{}", family.to_string(), family.version());
+        self.get_remote_protocols().unwrap_or_default().iter()
+            .any(|protocol| protocol.pid.ends_with(&suffix))
+    }
+
     pub fn is_in_null_state(&self) -> bool {
         match &self.connection_sm {
             SmConnection::Inviter(sm_inviter) => {
@@ -197,6 +419,12 @@ impl Connection {
             SmConnection::Invitee(sm_invitee) => {
                 sm_invitee.is_in_null_state()
             }
+            SmConnection::Requester(sm_requester) => {
+                sm_requester.is_in_null_state()
+            }
+            SmConnection::Responder(sm_responder) => {
+                sm_responder.is_in_null_state()
+            }
         }
     }
 
@@ -208,20 +436,29 @@ impl Connection {
             SmConnection::Invitee(sm_invitee) => {
                 sm_invitee.their_did_doc()
             }
+            SmConnection::Requester(sm_requester) => {
+                sm_requester.their_did_doc()
+            }
+            SmConnection::Responder(sm_responder) => {
+                sm_responder.their_did_doc()
+            }
         }
     }
 
     /**
-    Invitee operation
+    Invitee operation.
+    Public DID invitation is resolved from the ledger before it is stored in the state.
      */
     pub fn process_invite(&mut self, invitation: Invitation) -> VcxResult<()> {
         trace!("Connection::process_invite >>> invitation: {:?}", invitation);
+        let invitation = invitation.resolve()?;
         self.step(DidExchangeMessages::InvitationReceived(invitation))
     }
 
     /**
     If called on Inviter in Invited state returns invitation to connect with him. Returns error in other states.
-    If called on Invitee, returns error
+    If called on Responder in Invited state returns Out-of-Band invitation offering `didexchange` handshake protocol.
+    If called on Invitee or Requester, returns error
      */
     pub fn get_invite_details(&self) -> Option<String> {
         trace!("Connection::get_invite_details >>>");
@@ -231,12 +468,73 @@ impl Connection {
                     json!(invitation.to_a2a_message()).to_string()
                 })
             }
-            SmConnection::Invitee(_sm_invitee) => {
+            SmConnection::Responder(sm_responder) => {
+                sm_responder.get_invitation().map(|invitation| {
+                    json!(invitation.to_a2a_message()).to_string()
+                })
+            }
+            SmConnection::Invitee(_) | SmConnection::Requester(_) => {
                 None
             }
         }
     }
 
+    /**
+    If called on Inviter or Responder in Invited state returns Out-of-Band invitation built on top of the connection invitation.
+    Passed messages (e.g. credential offer or presentation request) are attached to `requests~attach`.
+    Returns error in other states and if called on Invitee or Requester.
+     */
+    pub fn get_out_of_band_invite_details(&self, requests: Vec<A2AMessage>, goal_code: Option<String>, goal: Option<String>) -> VcxResult<String> {
+        trace!("Connection::get_out_of_band_invite_details >>> requests: {:?}, goal_code: {:?}, goal: {:?}", requests, goal_code, goal);
+        let invitation = match &self.connection_sm {
+            SmConnection::Inviter(sm_inviter) => {
+                sm_inviter.get_invitation().cloned()
+                    .map(|invitation| OutOfBandInvitation::from(invitation).set_handshake_protocols(vec![MessageFamilies::Connections]))
+                    .ok_or(VcxError::from_msg(VcxErrorKind::NotReady, "Connection invitation is not prepared yet"))?
+            }
+            SmConnection::Responder(sm_responder) => {
+                sm_responder.get_invitation().cloned()
+                    .ok_or(VcxError::from_msg(VcxErrorKind::NotReady, "Connection invitation is not prepared yet"))?
+            }
+            SmConnection::Invitee(_) | SmConnection::Requester(_) => {
+                return Err(VcxError::from_msg(VcxErrorKind::ActionNotSupported, "Out-of-Band invitation can be prepared only by Inviter"));
+            }
+        };
+
+        let invitation = invitation
+            .set_goal_code(goal_code)
+            .set_goal(goal)
+            .set_requests_attach(requests)?;
+
+        Ok(json!(invitation.to_a2a_message()).to_string())
+    }
+
+    /**
+    If called on multi-use Inviter in Invited state, answers Connection Requests received since the last call
+    and returns a new connection for every request, with the handled request recorded in its history.
+    Returns error in other cases.
+     */
+    pub fn accept_multi_use_requests(&self) -> VcxResult<Vec<Connection>> {
+        trace!("Connection::accept_multi_use_requests >>>");
+        match &self.connection_sm {
+            SmConnection::Inviter(sm_inviter) => {
+                let inviters = sm_inviter.accept_multi_use_requests()?;
+                Ok(inviters.into_iter().map(|(inviter, request)| {
+                    let message = DidExchangeMessages::ExchangeRequestReceived(request);
+
+                    let mut history = History::default();
+                    history.record(Transition::new(self.state(), inviter.state(), message.as_ref())
+                        .set_message_id(message.message_id()));
+
+                    Connection { connection_sm: SmConnection::Inviter(inviter), history }
+                }).collect())
+            }
+            SmConnection::Invitee(_) | SmConnection::Requester(_) | SmConnection::Responder(_) => {
+                Err(VcxError::from_msg(VcxErrorKind::ActionNotSupported, "Only Inviter can publish multi-use invitation"))
+            }
+        }
+    }
+
     fn find_message_to_handle(&self, messages: HashMap<String, A2AMessage>) -> Option<(String, A2AMessage)> {
         match &self.connection_sm {
             SmConnection::Inviter(sm_inviter) => {
@@ -245,12 +543,20 @@ impl Connection {
             SmConnection::Invitee(sm_invitee) => {
                 sm_invitee.find_message_to_handle(messages)
             }
+            SmConnection::Requester(sm_requester) => {
+                sm_requester.find_message_to_handle(messages)
+            }
+            SmConnection::Responder(sm_responder) => {
+                sm_responder.find_message_to_handle(messages)
+            }
         }
     }
 
     /**
     If called on Inviter, creates initial connection agent and generates invitation
     If called on Invitee, creates connection agent and send connection request using info from connection invitation
+    If called on Responder, creates initial connection agent and generates Out-of-Band invitation
+    If called on Requester, creates connection agent and send DID Exchange request using info from Out-of-Band invitation
      */
     pub fn connect(&mut self) -> VcxResult<()> {
         trace!("Connection::connect >>> source_id: {}", self.source_id());
@@ -258,10 +564,11 @@ impl Connection {
     }
 
     /**
-    Tries to update state of connection state machine in 3 steps:
+    Tries to update state of connection state machine in 4 steps:
       1. find relevant message in agency,
       2. use it to update connection state and possibly send response over network,
-      3. update state of used message in agency to "Reviewed".
+      3. update state of used message in agency to "Reviewed",
+      4. consume acks of the messages sent with `~please_ack`.
      */
     pub fn update_state(&mut self) -> VcxResult<()> {
         trace!("Connection::update_state >>>");
@@ -271,16 +578,22 @@ impl Connection {
             return Ok(());
         }
 
-        let messages = self.get_messages()?;
+        let mut messages = self.get_messages()?;
         trace!("Connection::update_state >>> retrieved messages {:?}", messages);
 
-        if let Some((uid, message)) = self.find_message_to_handle(messages) {
+        if let Some((uid, message)) = self.find_message_to_handle(messages.clone()) {
             trace!("Connection::update_state >>> handling message uid: {:?}", uid);
+            messages.remove(&uid);
             self.update_state_with_message(&message)?;
             self.agent_info().clone().update_message_status(uid)?;
-        } else if let SmConnection::Inviter(sm_inviter) = &self.connection_sm {
-            trace!("Connection::update_state >>> Inviter found no message to handel on main connection agent. Will check bootstrap agent.");
-            if let Some((messages, bootstrap_agent_info)) = sm_inviter.get_bootstrap_agent_messages()? {
+        } else {
+            let bootstrap_agent_messages = match &self.connection_sm {
+                SmConnection::Inviter(sm_inviter) => sm_inviter.get_bootstrap_agent_messages()?,
+                SmConnection::Responder(sm_responder) => sm_responder.get_bootstrap_agent_messages()?,
+                SmConnection::Invitee(_) | SmConnection::Requester(_) => None,
+            };
+            trace!("Connection::update_state >>> found no message to handel on main connection agent. Will check bootstrap agent.");
+            if let Some((messages, bootstrap_agent_info)) = bootstrap_agent_messages {
                 if let Some((uid, message)) = self.find_message_to_handle(messages) {
                     trace!("Connection::update_state >>> handling message found on bootstrap agent uid: {:?}", uid);
                     self.update_state_with_message(&message)?;
@@ -289,12 +602,17 @@ impl Connection {
             }
         }
 
+        for uid in self.consume_acks(&messages)? {
+            self.update_message_status(uid)?;
+        }
+
         trace!("Connection::update_state >>> done");
         Ok(())
     }
 
     /**
-    Perform state machine transition using supplied message.
+    Perform state machine transition using supplied message and record the message as processed,
+    which acknowledges it if asked for.
      */
     pub fn update_state_with_message(&mut self, message: &A2AMessage) -> VcxResult<()> {
         trace!("Connection: update_state_with_message: {:?}", message);
@@ -304,6 +622,78 @@ impl Connection {
         }
 
         self.handle_message(message.clone().into())?;
+        self.consume_ack(message)?;
+        self.record_processed(message)?;
+
+        Ok(())
+    }
+
+    /**
+    Handles the messages fetched by the caller one by one while the state machine finds some relevant,
+    then consumes acks of the messages sent with `~please_ack`.
+    Messages left to other objects are acknowledged only once they are processed (see `record_processed`).
+    Returns uids of the handled messages, updating their status is left to the caller.
+     */
+    pub fn update_state_with_messages(&mut self, mut messages: HashMap<String, A2AMessage>) -> VcxResult<Vec<String>> {
+        trace!("Connection::update_state_with_messages >>> messages: {:?}", messages);
+
+        let mut handled = Vec::new();
+
+        while let Some((uid, message)) = self.find_message_to_handle(messages.clone()) {
+            messages.remove(&uid);
+            self.update_state_with_message(&message)?;
+            handled.push(uid);
+        }
+
+        handled.extend(self.consume_acks(&messages)?);
+
+        Ok(handled)
+    }
+
+    /**
+    Consumes acks of the messages sent with `~please_ack` received on the completed connection.
+    Returns uids of the consumed acks.
+     */
+    fn consume_acks(&self, messages: &HashMap<String, A2AMessage>) -> VcxResult<Vec<String>> {
+        let mut consumed = Vec::new();
+
+        for (uid, message) in messages {
+            if self.consume_ack(message)? {
+                consumed.push(uid.clone());
+            }
+        }
+
+        Ok(consumed)
+    }
+
+    /**
+    Marks the messages acknowledged by the received `ack`.
+    Returns true if the message is an `ack` of some message sent with `~please_ack`.
+     */
+    fn consume_ack(&self, message: &A2AMessage) -> VcxResult<bool> {
+        if self.state() != VcxStateType::VcxStateAccepted as u32 {
+            return Ok(false);
+        }
+
+        match message {
+            A2AMessage::Ack(ack) => acks::acknowledge(&self.agent_info().pw_did, ack),
+            _ => Ok(false)
+        }
+    }
+
+    /**
+    Sends `ack` of the processed message if it asks for acknowledgement, once per message.
+     */
+    fn send_ack(&self, message: &A2AMessage) -> VcxResult<()> {
+        if self.state() != VcxStateType::VcxStateAccepted as u32 {
+            return Ok(());
+        }
+
+        if let Some(ack) = acks::build_ack(message) {
+            if acks::record_ack_sent(&self.agent_info().pw_did, message)? {
+                self.send_message(&ack.to_a2a_message())?;
+            }
+        }
 
         Ok(())
     }
@@ -325,7 +715,26 @@ impl Connection {
     }
 
     /**
-    Get messages received from connection counterparty.
+    Records that the message received from connection counterparty was handled, so its replays are dropped,
+    and acknowledges it if it asks for acknowledgement.
+     */
+    pub fn record_processed(&self, message: &A2AMessage) -> VcxResult<()> {
+        trace!("Connection::record_processed >>> message: {:?}", message);
+        self.agent_info().record_processed(message)?;
+        self.send_ack(message)
+    }
+
+    /**
+    Records that the message received from connection counterparty failed to be handled, so its replays are dropped.
+    The message is not acknowledged.
+     */
+    pub fn record_rejected(&self, message: &A2AMessage) -> VcxResult<()> {
+        trace!("Connection::record_rejected >>> message: {:?}", message);
+        self.agent_info().record_processed(message)
+    }
+
+    /**
+    Get messages received from connection counterparty, except of the already processed ones.
      */
     pub fn get_messages(&self) -> VcxResult<HashMap<String, A2AMessage>> {
         trace!("Connection: get_messages >>>");
@@ -338,6 +747,14 @@ impl Connection {
                 let messages = sm_invitee.agent_info().get_messages()?;
                 Ok(messages)
             }
+            SmConnection::Requester(sm_requester) => {
+                let messages = sm_requester.agent_info().get_messages()?;
+                Ok(messages)
+            }
+            SmConnection::Responder(sm_responder) => {
+                let messages = sm_responder.agent_info().get_messages()?;
+                Ok(messages)
+            }
         }
     }
 
@@ -369,13 +786,20 @@ impl Connection {
     Sends authenticated message to connection counterparty
      */
     pub fn send_message(&self, message: &A2AMessage) -> VcxResult<()> {
-        trace!("Connection::send_message >>> message: {:?}", message);
+        self.deliver_message(message).map(|_| ())
+    }
+
+    /**
+    Sends authenticated message to connection counterparty and returns the outcome of the delivery
+     */
+    pub fn deliver_message(&self, message: &A2AMessage) -> VcxResult<Delivery> {
+        trace!("Connection::deliver_message >>> message: {:?}", message);
 
         let did_doc = self.their_did_doc()
             .ok_or(VcxError::from_msg(VcxErrorKind::NotReady, "Cannot send message: Remote Connection information is not set"))?;
 
         warn!("Connection resolved did_doc = {:?}", did_doc);
-        self.agent_info().send_message(message, &did_doc)
+        self.agent_info().deliver_message(message, &did_doc)
     }
 
     pub fn send_message_to_self_endpoint(message: &A2AMessage, did_doc: &DidDoc) -> VcxResult<()> {
@@ -398,9 +822,83 @@ impl Connection {
 
     pub fn send_generic_message(&self, message: &str) -> VcxResult<String> {
         trace!("Connection::send_generic_message >>> message: {:?}", message);
+        self.send_generic_message_with_options(message, false, false)
+    }
 
-        let message = Connection::parse_generic_message(message);
-        self.send_message(&message).map(|_| String::new())
+    /**
+    Sends the message with `~please_ack` decorator and tracks its acknowledgement.
+    Returns `@id` of the sent message.
+     */
+    pub fn send_generic_message_with_ack(&self, message: &str) -> VcxResult<String> {
+        trace!("Connection::send_generic_message_with_ack >>> message: {:?}", message);
+        self.send_generic_message_with_options(message, true, false)
+    }
+
+    /**
+    Sends the message optionally asking for acknowledgement, which is tracked,
+    and optionally traced even if tracing is not enabled on the connection.
+    Returns `@id` of the sent message if acknowledgement is requested, otherwise empty string.
+    Fails with `PostMessageFailed` if the message was not delivered but queued in outbox for retries.
+     */
+    pub fn send_generic_message_with_options(&self, message: &str, please_ack: bool, trace: bool) -> VcxResult<String> {
+        trace!("Connection::send_generic_message_with_options >>> message: {:?}, please_ack: {}, trace: {}", message, please_ack, trace);
+
+        let mut message = Connection::parse_generic_message(message);
+
+        if please_ack {
+            message = match message {
+                A2AMessage::BasicMessage(basic_message) => basic_message.ask_for_ack().to_a2a_message(),
+                _ => return Err(VcxError::from_msg(VcxErrorKind::InvalidOption, "Only basic message can ask for acknowledgement"))
+            };
+        }
+
+        if trace {
+            let target = self.agent_info().trace_target.clone().unwrap_or_else(settings::get_trace_target);
+            message = tracing::decorate(&message, &target)?;
+        }
+
+        let delivery = self.deliver_message(&message)?;
+
+        if please_ack {
+            // a queued message is still delivered later, so its acknowledgement is awaited
+            acks::track(&self.agent_info().pw_did, &message)?;
+        }
+
+        let message_id = replay::message_id(&message).unwrap_or_default();
+
+        if delivery == Delivery::Queued {
+            return Err(VcxError::from_msg(VcxErrorKind::PostMessageFailed,
+                                          format!("Message {} was not delivered, it is queued in outbox for retries", message_id)));
+        }
+
+        Ok(if please_ack { message_id } else { String::new() })
+    }
+
+    /**
+    Enables tracing of the messages exchanged on the connection with reports sent to the target,
+    or disables it if no target is given.
+     */
+    pub fn set_trace_target(&mut self, target: Option<String>) -> VcxResult<()> {
+        trace!("Connection::set_trace_target >>> target: {:?}", target);
+
+        if let Some(ref target) = target {
+            TraceTarget::parse(target)?;
+        }
+
+        let mut agent_info = self.agent_info().clone();
+        agent_info.trace_target = target;
+
+        *self = Connection::from_parts(self.source_id(), agent_info, self.state_object(), self.history.clone());
+
+        Ok(())
+    }
+
+    /**
+    Returns JSON array of the messages sent with `~please_ack` and status of their acknowledgement.
+     */
+    pub fn get_ack_requests(&self) -> VcxResult<String> {
+        trace!("Connection::get_ack_requests >>>");
+        acks::get_requests(&self.agent_info().pw_did)
     }
 
     pub fn send_ping(&mut self, comment: Option<String>) -> VcxResult<()> {
@@ -414,6 +912,15 @@ impl Connection {
     }
 
     fn step(&mut self, message: DidExchangeMessages) -> VcxResult<()> {
+        let old_state = self.state();
+        let message_type = message.as_ref().to_string();
+        let message_id = message.message_id();
+        let problem_report: Option<Problem> = match message {
+            DidExchangeMessages::ProblemReportReceived(ref problem_report) => Some(problem_report.into()),
+            DidExchangeMessages::DidExchangeProblemReportReceived(ref problem_report) => Some(problem_report.into()),
+            _ => None
+        };
+
         self.connection_sm = match &self.connection_sm {
             SmConnection::Inviter(sm_inviter) => {
                 SmConnection::Inviter(sm_inviter.clone().step(message)?)
@@ -421,7 +928,25 @@ impl Connection {
             SmConnection::Invitee(sm_invitee) => {
                 SmConnection::Invitee(sm_invitee.clone().step(message)?)
             }
+            SmConnection::Requester(sm_requester) => {
+                SmConnection::Requester(sm_requester.clone().step(message)?)
+            }
+            SmConnection::Responder(sm_responder) => {
+                SmConnection::Responder(sm_responder.clone().step(message)?)
+            }
         };
+
+        let new_state = self.state();
+        let outcome = match problem_report {
+            Some(_) => Outcome::Failed,
+            None if new_state == VcxStateType::VcxStateAccepted as u32 => Outcome::Success,
+            None => Outcome::InProgress
+        };
+        self.history.record(Transition::new(old_state, new_state, &message_type)
+            .set_message_id(message_id)
+            .set_outcome(outcome)
+            .set_problem_report(problem_report));
+
         Ok(())
     }
 
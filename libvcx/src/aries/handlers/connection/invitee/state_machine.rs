@@ -70,6 +70,15 @@ impl SmConnectionInvitee {
         }
     }
 
+    /// Connectionless exchange started by Out-of-Band invitation carrying only requests. There is no handshake, invitation's service is used right away.
+    pub fn connectionless(source_id: &str, agent_info: AgentInfo, did_doc: DidDoc) -> Self {
+        SmConnectionInvitee {
+            source_id: source_id.to_string(),
+            agent_info,
+            state: InviteeState::Completed(CompleteState { did_doc, protocols: None }),
+        }
+    }
+
     pub fn agent_info(&self) -> &AgentInfo {
         &self.agent_info
     }
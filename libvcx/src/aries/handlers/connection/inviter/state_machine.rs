@@ -13,6 +13,7 @@ use aries::messages::a2a::protocol_registry::ProtocolRegistry;
 use aries::messages::connection::did_doc::DidDoc;
 use aries::messages::connection::invite::Invitation;
 use aries::messages::connection::problem_report::{ProblemCode, ProblemReport};
+use aries::messages::connection::request::Request;
 use aries::messages::discovery::disclose::ProtocolDescriptor;
 use aries::messages::trust_ping::ping::Ping;
 
@@ -46,11 +47,26 @@ impl SmConnectionInviter {
     pub fn _build_inviter(source_id: &str) -> Self {
         SmConnectionInviter {
             source_id: source_id.to_string(),
-            state: InviterState::Null(NullState {}),
+            state: InviterState::Null(NullState::default()),
             agent_info: AgentInfo::default(),
         }
     }
 
+    pub fn new_multi_use(source_id: &str) -> Self {
+        SmConnectionInviter {
+            state: InviterState::Null(NullState { multi_use: true }),
+            ..SmConnectionInviter::_build_inviter(source_id)
+        }
+    }
+
+    pub fn is_multi_use(&self) -> bool {
+        match self.state {
+            InviterState::Null(ref state) => state.multi_use,
+            InviterState::Invited(ref state) => state.multi_use,
+            _ => false
+        }
+    }
+
     pub fn is_in_null_state(&self) -> bool {
         match self.state {
             InviterState::Null(_) => true,
@@ -119,6 +135,41 @@ impl SmConnectionInviter {
         None
     }
 
+    /// Answers Connection Requests received on the multi-use invitation since the last call.
+    /// Every request is handled by a separate inviter which provisions own pairwise agent, the invitation stays in Invited state.
+    /// Returns the inviters together with the requests they answered.
+    pub fn accept_multi_use_requests(&self) -> VcxResult<Vec<(SmConnectionInviter, Request)>> {
+        let state = match self.state {
+            InviterState::Invited(ref state) if state.multi_use => state,
+            _ => return Err(VcxError::from_msg(VcxErrorKind::ActionNotSupported, "Connection is not a published multi-use invitation"))
+        };
+
+        let mut inviters = Vec::new();
+
+        // a failing request must not drop connections already created for the other ones,
+        // it is reported and marked as reviewed so it is not answered twice
+        for (uid, message) in self.agent_info.get_messages()? {
+            if let A2AMessage::ConnectionRequest(request) = message {
+                let invited = InvitedState { invitation: state.invitation.clone(), multi_use: false };
+
+                match SmConnectionInviter::from(self.source_id.clone(), self.agent_info.clone(), InviterState::Invited(invited))
+                    .step(DidExchangeMessages::ExchangeRequestReceived(request.clone())) {
+                    Ok(inviter) => match inviter.state {
+                        InviterState::Responded(_) => inviters.push((inviter, request)),
+                        _ => warn!("SmConnectionInviter::accept_multi_use_requests >>> Connection Request {} was rejected", uid)
+                    },
+                    Err(err) => warn!("SmConnectionInviter::accept_multi_use_requests >>> cannot answer Connection Request {}: {}", uid, err)
+                }
+
+                if let Err(err) = self.agent_info.update_message_status(uid.clone()) {
+                    warn!("SmConnectionInviter::accept_multi_use_requests >>> cannot mark Connection Request {} as reviewed: {}", uid, err);
+                }
+            }
+        }
+
+        Ok(inviters)
+    }
+
     pub fn get_bootstrap_agent_messages(&self) -> VcxResult<Option<(HashMap<String, A2AMessage>, AgentInfo)>> {
         if let Some(prev_agent_info) = self.prev_agent_info() {
             let messages = prev_agent_info.get_messages()?;
@@ -163,11 +214,11 @@ impl SmConnectionInviter {
 
     pub fn can_handle_message(&self, message: &A2AMessage) -> bool {
         match self.state {
-            InviterState::Invited(_) => {
+            InviterState::Invited(ref state) => {
                 match message {
                     A2AMessage::ConnectionRequest(_) => {
                         debug!("Inviter received ConnectionRequest message");
-                        true
+                        !state.multi_use
                     }
                     A2AMessage::ConnectionProblemReport(_) => {
                         debug!("Inviter received ProblemReport message");
@@ -256,6 +307,10 @@ impl SmConnectionInviter {
             }
             InviterState::Invited(state) => {
                 match message {
+                    DidExchangeMessages::ExchangeRequestReceived(_) if state.multi_use => {
+                        warn!("Multi-use invitation is not consumed by Connection Request, use `accept_multi_use_requests` instead");
+                        InviterState::Invited(state)
+                    }
                     DidExchangeMessages::ExchangeRequestReceived(request) => {
                         match state.handle_connection_request(&request, &agent_info) {
                             Ok((response, new_agent_info)) => {
@@ -377,6 +432,18 @@ pub mod test {
                 assert_match!(InviterState::Null(_), inviter_sm.state);
                 assert_eq!(source_id(), inviter_sm.source_id());
             }
+
+            #[test]
+            #[cfg(feature = "general_test")]
+            fn test_inviter_new_multi_use() {
+                let _setup = SetupAriesMocks::init();
+
+                let inviter_sm = SmConnectionInviter::new_multi_use(&source_id());
+
+                assert_match!(InviterState::Null(_), inviter_sm.state);
+                assert!(inviter_sm.is_multi_use());
+                assert!(!inviter_sm().is_multi_use());
+            }
         }
 
         mod step {
@@ -469,6 +536,29 @@ pub mod test {
                 assert_match!(InviterState::Invited(_), did_exchange_sm.state);
             }
 
+            #[test]
+            #[cfg(feature = "general_test")]
+            fn test_did_exchange_multi_use_invitation_is_not_consumed_by_exchange_request() {
+                let _setup = AgencyModeSetup::init();
+
+                let mut did_exchange_sm = SmConnectionInviter::new_multi_use(&source_id()).to_inviter_invited_state();
+                assert!(did_exchange_sm.is_multi_use());
+
+                did_exchange_sm = did_exchange_sm.step(DidExchangeMessages::ExchangeRequestReceived(_request())).unwrap();
+                assert_match!(InviterState::Invited(_), did_exchange_sm.state);
+                assert!(!did_exchange_sm.can_handle_message(&A2AMessage::ConnectionRequest(_request())));
+            }
+
+            #[test]
+            #[cfg(feature = "general_test")]
+            fn test_did_exchange_accept_multi_use_requests_fails_for_single_use_invitation() {
+                let _setup = AgencyModeSetup::init();
+
+                let did_exchange_sm = inviter_sm().to_inviter_invited_state();
+
+                assert_eq!(VcxErrorKind::ActionNotSupported, did_exchange_sm.accept_multi_use_requests().unwrap_err().kind());
+            }
+
             #[test]
             #[cfg(feature = "general_test")]
             fn test_did_exchange_handle_ack_message_from_responded_state() {
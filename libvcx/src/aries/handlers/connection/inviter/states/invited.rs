@@ -9,13 +9,16 @@ use aries::messages::connection::response::{Response, SignedResponse};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct InvitedState {
-    pub invitation: Invitation
+    pub invitation: Invitation,
+    /// Multi-use invitation is never consumed by a request, every request spawns a separate connection.
+    #[serde(default)]
+    pub multi_use: bool,
 }
 
 impl From<(InvitedState, ProblemReport)> for NullState {
     fn from((_state, _error): (InvitedState, ProblemReport)) -> NullState {
         trace!("ConnectionInviter: transit state from InvitedState to NullState");
-        NullState {}
+        NullState::default()
     }
 }
 
@@ -1,12 +1,15 @@
 use aries::handlers::connection::inviter::states::invited::InvitedState;
 use aries::messages::connection::invite::Invitation;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct NullState {}
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct NullState {
+    #[serde(default)]
+    pub multi_use: bool,
+}
 
 impl From<(NullState, Invitation)> for InvitedState {
-    fn from((_state, invitation): (NullState, Invitation)) -> InvitedState {
+    fn from((state, invitation): (NullState, Invitation)) -> InvitedState {
         trace!("ConnectionInviter: transit state from NullState to InvitedState");
-        InvitedState { invitation }
+        InvitedState { invitation, multi_use: state.multi_use }
     }
 }
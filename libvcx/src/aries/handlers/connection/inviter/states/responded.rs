@@ -21,7 +21,7 @@ pub struct RespondedState {
 impl From<(RespondedState, ProblemReport)> for NullState {
     fn from((_state, _error): (RespondedState, ProblemReport)) -> NullState {
         trace!("ConnectionInviter: transit state from RespondedState to NullState");
-        NullState {}
+        NullState::default()
     }
 }
 
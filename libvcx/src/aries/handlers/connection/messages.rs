@@ -4,12 +4,17 @@ use aries::messages::connection::invite::Invitation;
 use aries::messages::connection::problem_report::ProblemReport;
 use aries::messages::connection::request::Request;
 use aries::messages::connection::response::SignedResponse;
+use aries::messages::did_exchange::complete::Complete as DidExchangeComplete;
+use aries::messages::did_exchange::problem_report::ProblemReport as DidExchangeProblemReport;
+use aries::messages::did_exchange::request::Request as DidExchangeRequest;
+use aries::messages::did_exchange::response::Response as DidExchangeResponse;
 use aries::messages::discovery::disclose::Disclose;
 use aries::messages::discovery::query::Query;
 use aries::messages::trust_ping::ping::Ping;
+use aries::messages::out_of_band::invitation::OutOfBandInvitation;
 use aries::messages::trust_ping::ping_response::PingResponse;
 
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, AsRefStr)]
 pub enum DidExchangeMessages {
     Connect(),
     InvitationReceived(Invitation),
@@ -17,6 +22,11 @@ pub enum DidExchangeMessages {
     ExchangeResponseReceived(SignedResponse),
     AckReceived(Ack),
     ProblemReportReceived(ProblemReport),
+    OutOfBandInvitationReceived(OutOfBandInvitation),
+    DidExchangeRequestReceived(DidExchangeRequest),
+    DidExchangeResponseReceived(DidExchangeResponse),
+    DidExchangeCompleteReceived(DidExchangeComplete),
+    DidExchangeProblemReportReceived(DidExchangeProblemReport),
     SendPing(Option<String>),
     PingReceived(Ping),
     PingResponseReceived(PingResponse),
@@ -56,9 +66,47 @@ impl From<A2AMessage> for DidExchangeMessages {
             A2AMessage::ConnectionProblemReport(report) => {
                 DidExchangeMessages::ProblemReportReceived(report)
             }
+            A2AMessage::OutOfBandInvitation(invite) => {
+                DidExchangeMessages::OutOfBandInvitationReceived(invite)
+            }
+            A2AMessage::DidExchangeRequest(request) => {
+                DidExchangeMessages::DidExchangeRequestReceived(request)
+            }
+            A2AMessage::DidExchangeResponse(response) => {
+                DidExchangeMessages::DidExchangeResponseReceived(response)
+            }
+            A2AMessage::DidExchangeComplete(complete) => {
+                DidExchangeMessages::DidExchangeCompleteReceived(complete)
+            }
+            A2AMessage::DidExchangeProblemReport(report) => {
+                DidExchangeMessages::DidExchangeProblemReportReceived(report)
+            }
             _ => {
                 DidExchangeMessages::Unknown
             }
         }
     }
-}
\ No newline at end of file
+}
+
+impl DidExchangeMessages {
+    /// Id of the received message.
+    pub fn message_id(&self) -> Option<String> {
+        match self {
+            DidExchangeMessages::InvitationReceived(invitation) => Some(invitation.id.0.clone()),
+            DidExchangeMessages::ExchangeRequestReceived(request) => Some(request.id.0.clone()),
+            DidExchangeMessages::ExchangeResponseReceived(response) => Some(response.id.0.clone()),
+            DidExchangeMessages::AckReceived(ack) => Some(ack.id.0.clone()),
+            DidExchangeMessages::ProblemReportReceived(problem_report) => Some(problem_report.id.0.clone()),
+            DidExchangeMessages::OutOfBandInvitationReceived(invitation) => Some(invitation.id.0.clone()),
+            DidExchangeMessages::DidExchangeRequestReceived(request) => Some(request.id.0.clone()),
+            DidExchangeMessages::DidExchangeResponseReceived(response) => Some(response.id.0.clone()),
+            DidExchangeMessages::DidExchangeCompleteReceived(complete) => Some(complete.id.0.clone()),
+            DidExchangeMessages::DidExchangeProblemReportReceived(problem_report) => Some(problem_report.id.0.clone()),
+            DidExchangeMessages::PingReceived(ping) => Some(ping.id.0.clone()),
+            DidExchangeMessages::PingResponseReceived(ping_response) => Some(ping_response.id.0.clone()),
+            DidExchangeMessages::QueryReceived(query) => Some(query.id.0.clone()),
+            DidExchangeMessages::DiscloseReceived(disclose) => Some(disclose.id.0.clone()),
+            _ => None
+        }
+    }
+}
@@ -3,6 +3,8 @@ pub mod connection;
 pub mod messages;
 mod invitee;
 mod inviter;
+mod requester;
+mod responder;
 mod util;
 
 #[cfg(test)]
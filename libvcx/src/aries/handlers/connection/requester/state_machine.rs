@@ -0,0 +1,401 @@
+use std::collections::HashMap;
+
+use api::VcxStateType;
+use error::prelude::*;
+use aries::handlers::connection::agent_info::AgentInfo;
+use aries::handlers::connection::messages::DidExchangeMessages;
+use aries::handlers::connection::requester::states::complete::CompleteState;
+use aries::handlers::connection::requester::states::invited::InvitedState;
+use aries::handlers::connection::requester::states::null::NullState;
+use aries::handlers::connection::requester::states::requested::RequestedState;
+use aries::messages::a2a::A2AMessage;
+use aries::messages::a2a::protocol_registry::ProtocolRegistry;
+use aries::messages::connection::did_doc::DidDoc;
+use aries::messages::did_exchange::problem_report::{ProblemCode, ProblemReport};
+use aries::messages::did_exchange::request::Request;
+use aries::messages::discovery::disclose::ProtocolDescriptor;
+
+/// Requester (invitee) side of DID Exchange 1.0 protocol (RFC 0023).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmDidExchangeRequester {
+    source_id: String,
+    agent_info: AgentInfo,
+    state: RequesterState,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum RequesterState {
+    Null(NullState),
+    Invited(InvitedState),
+    Requested(RequestedState),
+    Completed(CompleteState),
+}
+
+impl RequesterState {
+    pub fn code(&self) -> u32 {
+        match self {
+            RequesterState::Null(_) => VcxStateType::VcxStateInitialized as u32,
+            RequesterState::Invited(_) => VcxStateType::VcxStateOfferSent as u32,
+            RequesterState::Requested(_) => VcxStateType::VcxStateRequestReceived as u32,
+            RequesterState::Completed(_) => VcxStateType::VcxStateAccepted as u32,
+        }
+    }
+}
+
+impl SmDidExchangeRequester {
+    pub fn new(source_id: &str) -> Self {
+        SmDidExchangeRequester {
+            source_id: source_id.to_string(),
+            state: RequesterState::Null(NullState {}),
+            agent_info: AgentInfo::default(),
+        }
+    }
+
+    pub fn from(source_id: String, agent_info: AgentInfo, state: RequesterState) -> Self {
+        SmDidExchangeRequester {
+            source_id,
+            agent_info,
+            state,
+        }
+    }
+
+    pub fn is_in_null_state(&self) -> bool {
+        match self.state {
+            RequesterState::Null(_) => true,
+            _ => false
+        }
+    }
+
+    pub fn agent_info(&self) -> &AgentInfo {
+        &self.agent_info
+    }
+
+    pub fn source_id(&self) -> &str {
+        &self.source_id
+    }
+
+    pub fn state(&self) -> u32 {
+        self.state.code()
+    }
+
+    pub fn state_object(&self) -> &RequesterState {
+        &self.state
+    }
+
+    pub fn step(self, message: DidExchangeMessages) -> VcxResult<SmDidExchangeRequester> {
+        trace!("SmDidExchangeRequester::step >>> message: {:?}", message);
+        let SmDidExchangeRequester { source_id, agent_info, state } = self;
+
+        let (new_state, agent_info) =
+            SmDidExchangeRequester::requester_step(state, message, &source_id, agent_info)?;
+
+        Ok(SmDidExchangeRequester { source_id, agent_info, state: new_state })
+    }
+
+    pub fn their_did_doc(&self) -> Option<DidDoc> {
+        match self.state {
+            RequesterState::Null(_) => None,
+            RequesterState::Invited(ref state) => state.did_doc().ok(),
+            RequesterState::Requested(ref state) => Some(state.did_doc.clone()),
+            RequesterState::Completed(ref state) => Some(state.did_doc.clone()),
+        }
+    }
+
+    pub fn find_message_to_handle(&self, messages: HashMap<String, A2AMessage>) -> Option<(String, A2AMessage)> {
+        for (uid, message) in messages {
+            if self.can_handle_message(&message) {
+                return Some((uid, message));
+            }
+        }
+        None
+    }
+
+    pub fn get_protocols(&self) -> Vec<ProtocolDescriptor> {
+        ProtocolRegistry::init().protocols()
+    }
+
+    pub fn get_remote_protocols(&self) -> Option<Vec<ProtocolDescriptor>> {
+        match self.state {
+            RequesterState::Completed(ref state) => state.protocols.clone(),
+            _ => None
+        }
+    }
+
+    pub fn remote_did(&self) -> VcxResult<String> {
+        self.their_did_doc()
+            .map(|did_doc: DidDoc| did_doc.id.clone())
+            .ok_or(VcxError::from_msg(VcxErrorKind::NotReady, "Remote Connection DID is not set"))
+    }
+
+    pub fn remote_vk(&self) -> VcxResult<String> {
+        self.their_did_doc()
+            .and_then(|did_doc| did_doc.recipient_keys().get(0).cloned())
+            .ok_or(VcxError::from_msg(VcxErrorKind::NotReady, "Remote Connection Verkey is not set"))
+    }
+
+    pub fn can_handle_message(&self, message: &A2AMessage) -> bool {
+        match self.state {
+            RequesterState::Requested(_) => {
+                match message {
+                    A2AMessage::DidExchangeResponse(_) => {
+                        debug!("Requester received DidExchangeResponse message");
+                        true
+                    }
+                    A2AMessage::DidExchangeProblemReport(_) => {
+                        debug!("Requester received ProblemReport message");
+                        true
+                    }
+                    _ => {
+                        debug!("Requester received unexpected message: {:?}", message);
+                        false
+                    }
+                }
+            }
+            RequesterState::Completed(_) => {
+                match message {
+                    A2AMessage::Ping(_) => {
+                        debug!("Ping message received");
+                        true
+                    }
+                    A2AMessage::PingResponse(_) => {
+                        debug!("PingResponse message received");
+                        true
+                    }
+                    A2AMessage::Query(_) => {
+                        debug!("Query message received");
+                        true
+                    }
+                    A2AMessage::Disclose(_) => {
+                        debug!("Disclose message received");
+                        true
+                    }
+                    _ => {
+                        debug!("Unexpected message received in Completed state: {:?}", message);
+                        false
+                    }
+                }
+            }
+            _ => {
+                debug!("Unexpected message received: message: {:?}", message);
+                false
+            }
+        }
+    }
+
+    pub fn requester_step(requester_state: RequesterState, message: DidExchangeMessages, source_id: &str, mut agent_info: AgentInfo) -> VcxResult<(RequesterState, AgentInfo)> {
+        let new_state = match requester_state {
+            RequesterState::Null(state) => {
+                match message {
+                    DidExchangeMessages::OutOfBandInvitationReceived(invitation) => {
+                        RequesterState::Invited((state, invitation).into())
+                    }
+                    _ => {
+                        RequesterState::Null(state)
+                    }
+                }
+            }
+            RequesterState::Invited(state) => {
+                match message {
+                    DidExchangeMessages::Connect() => {
+                        let did_doc = state.did_doc()?;
+
+                        agent_info = agent_info.create_agent()?;
+                        let request = Request::create()
+                            .set_label(source_id.to_string())
+                            .set_did(agent_info.pw_did.to_string())
+                            .set_parent_thread_id(&state.invitation.id.0)
+                            .set_did_doc(&agent_info.did_doc()?)?;
+
+                        agent_info.send_message(&request.to_a2a_message(), &did_doc)?;
+                        RequesterState::Requested((state, request, did_doc).into())
+                    }
+                    DidExchangeMessages::DidExchangeProblemReportReceived(problem_report) => {
+                        RequesterState::Null((state, problem_report).into())
+                    }
+                    _ => {
+                        RequesterState::Invited(state)
+                    }
+                }
+            }
+            RequesterState::Requested(state) => {
+                match message {
+                    DidExchangeMessages::DidExchangeResponseReceived(response) => {
+                        match state.handle_exchange_response(response, &agent_info) {
+                            Ok(did_doc) => {
+                                RequesterState::Completed((state, did_doc).into())
+                            }
+                            Err(err) => {
+                                let problem_report = ProblemReport::create()
+                                    .set_problem_code(ProblemCode::ResponseProcessingError)
+                                    .set_explain(err.to_string())
+                                    .set_thread_id(&state.request.id.0);
+                                agent_info.send_message(&problem_report.to_a2a_message(), &state.did_doc).ok();
+                                RequesterState::Null((state, problem_report).into())
+                            }
+                        }
+                    }
+                    DidExchangeMessages::DidExchangeProblemReportReceived(problem_report) => {
+                        RequesterState::Null((state, problem_report).into())
+                    }
+                    _ => {
+                        RequesterState::Requested(state)
+                    }
+                }
+            }
+            RequesterState::Completed(state) => {
+                state.handle_message(message, &agent_info)?
+            }
+        };
+        Ok((new_state, agent_info))
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use utils::devsetup::SetupAriesMocks;
+    use aries::messages::a2a::message_family::MessageFamilies;
+    use aries::messages::connection::did_doc::tests::{_did_doc, _key_1};
+    use aries::messages::did_exchange::problem_report::tests::_problem_report;
+    use aries::messages::did_exchange::response::Response;
+    use aries::messages::out_of_band::invitation::OutOfBandInvitation;
+    use aries::messages::out_of_band::invitation::tests::_out_of_band_invitation;
+    use aries::messages::trust_ping::ping::tests::_ping;
+    use aries::test::setup::AgencyModeSetup;
+    use aries::test::source_id;
+
+    use super::*;
+
+    pub fn requester_sm() -> SmDidExchangeRequester {
+        SmDidExchangeRequester::new(&source_id())
+    }
+
+    fn _invitation() -> OutOfBandInvitation {
+        let mut invitation = _out_of_band_invitation();
+        invitation.handshake_protocols = vec![MessageFamilies::DidExchange.id()];
+        invitation
+    }
+
+    fn _response(thread_id: &str) -> Response {
+        Response::create()
+            .set_did(_did_doc().id)
+            .set_thread_id(thread_id)
+            .set_signed_did_doc(&_did_doc(), &_key_1()).unwrap()
+    }
+
+    impl SmDidExchangeRequester {
+        fn to_requester_invited_state(mut self) -> SmDidExchangeRequester {
+            self = self.step(DidExchangeMessages::OutOfBandInvitationReceived(_invitation())).unwrap();
+            self
+        }
+
+        fn to_requester_requested_state(mut self) -> SmDidExchangeRequester {
+            self = self.step(DidExchangeMessages::OutOfBandInvitationReceived(_invitation())).unwrap();
+            self = self.step(DidExchangeMessages::Connect()).unwrap();
+            self
+        }
+
+        fn request(&self) -> Request {
+            match self.state {
+                RequesterState::Requested(ref state) => state.request.clone(),
+                _ => panic!("Requester is not in Requested state")
+            }
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_requester_new() {
+        let _setup = SetupAriesMocks::init();
+
+        let requester_sm = requester_sm();
+
+        assert_match!(RequesterState::Null(_), requester_sm.state);
+        assert_eq!(source_id(), requester_sm.source_id());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_requester_handle_invitation_message_from_null_state() {
+        let _setup = AgencyModeSetup::init();
+
+        let requester_sm = requester_sm().to_requester_invited_state();
+
+        assert_match!(RequesterState::Invited(_), requester_sm.state);
+        assert_eq!(_invitation().get_service().unwrap().service_endpoint, requester_sm.their_did_doc().unwrap().get_endpoint());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_requester_handle_connect_message_from_invited_state() {
+        let _setup = AgencyModeSetup::init();
+
+        let requester_sm = requester_sm().to_requester_requested_state();
+
+        assert_match!(RequesterState::Requested(_), requester_sm.state);
+        let request = requester_sm.request();
+        assert_eq!(Some(_invitation().id.0), request.thread.pthid);
+        assert_eq!(requester_sm.agent_info().pw_did, request.did);
+        assert_eq!(requester_sm.agent_info().recipient_keys(), request.did_doc().unwrap().recipient_keys());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_requester_handle_problem_report_from_invited_state() {
+        let _setup = AgencyModeSetup::init();
+
+        let requester_sm = requester_sm().to_requester_invited_state()
+            .step(DidExchangeMessages::DidExchangeProblemReportReceived(_problem_report())).unwrap();
+
+        assert_match!(RequesterState::Null(_), requester_sm.state);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_requester_handle_response_message_from_requested_state() {
+        let _setup = AgencyModeSetup::init();
+
+        let requester_sm = requester_sm().to_requester_requested_state();
+        let response = _response(&requester_sm.request().id.0);
+
+        let requester_sm = requester_sm.step(DidExchangeMessages::DidExchangeResponseReceived(response)).unwrap();
+
+        assert_match!(RequesterState::Completed(_), requester_sm.state);
+        assert_eq!(_did_doc(), requester_sm.their_did_doc().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_requester_handle_response_message_with_other_thread_from_requested_state() {
+        let _setup = AgencyModeSetup::init();
+
+        let requester_sm = requester_sm().to_requester_requested_state()
+            .step(DidExchangeMessages::DidExchangeResponseReceived(_response("other"))).unwrap();
+
+        assert_match!(RequesterState::Null(_), requester_sm.state);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_requester_handle_unsigned_response_message_from_requested_state() {
+        let _setup = AgencyModeSetup::init();
+
+        let requester_sm = requester_sm().to_requester_requested_state();
+        let mut response = _response(&requester_sm.request().id.0);
+        response.did_doc_attach.data.jws = None;
+
+        let requester_sm = requester_sm.step(DidExchangeMessages::DidExchangeResponseReceived(response)).unwrap();
+
+        assert_match!(RequesterState::Null(_), requester_sm.state);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_requester_handle_other_messages_from_requested_state() {
+        let _setup = AgencyModeSetup::init();
+
+        let requester_sm = requester_sm().to_requester_requested_state()
+            .step(DidExchangeMessages::PingReceived(_ping())).unwrap();
+
+        assert_match!(RequesterState::Requested(_), requester_sm.state);
+    }
+}
@@ -0,0 +1,38 @@
+use error::prelude::*;
+use aries::handlers::connection::requester::states::null::NullState;
+use aries::handlers::connection::requester::states::requested::RequestedState;
+use aries::messages::connection::did_doc::DidDoc;
+use aries::messages::did_exchange::problem_report::ProblemReport;
+use aries::messages::did_exchange::request::Request;
+use aries::messages::out_of_band::invitation::OutOfBandInvitation;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvitedState {
+    pub invitation: OutOfBandInvitation
+}
+
+impl From<(InvitedState, ProblemReport)> for NullState {
+    fn from((_state, _error): (InvitedState, ProblemReport)) -> NullState {
+        trace!("DidExchangeRequester: transit state from InvitedState to NullState");
+        NullState {}
+    }
+}
+
+impl From<(InvitedState, Request, DidDoc)> for RequestedState {
+    fn from((_state, request, did_doc): (InvitedState, Request, DidDoc)) -> RequestedState {
+        trace!("DidExchangeRequester: transit state from InvitedState to RequestedState");
+        RequestedState { request, did_doc }
+    }
+}
+
+impl InvitedState {
+    pub fn did_doc(&self) -> VcxResult<DidDoc> {
+        let service = self.invitation.get_service()?;
+
+        let mut did_doc: DidDoc = DidDoc::default();
+        did_doc.set_id(self.invitation.id.0.clone());
+        did_doc.set_service_endpoint(service.service_endpoint);
+        did_doc.set_keys(service.recipient_keys, service.routing_keys);
+        Ok(did_doc)
+    }
+}
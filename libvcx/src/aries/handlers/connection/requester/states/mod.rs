@@ -0,0 +1,4 @@
+pub(super) mod null;
+pub(super) mod invited;
+pub(super) mod requested;
+pub(super) mod complete;
\ No newline at end of file
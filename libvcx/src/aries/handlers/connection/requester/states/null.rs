@@ -0,0 +1,12 @@
+use aries::handlers::connection::requester::states::invited::InvitedState;
+use aries::messages::out_of_band::invitation::OutOfBandInvitation;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct NullState {}
+
+impl From<(NullState, OutOfBandInvitation)> for InvitedState {
+    fn from((_state, invitation): (NullState, OutOfBandInvitation)) -> InvitedState {
+        trace!("DidExchangeRequester: transit state from NullState to InvitedState");
+        InvitedState { invitation }
+    }
+}
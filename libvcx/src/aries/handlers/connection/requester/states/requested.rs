@@ -0,0 +1,56 @@
+use error::prelude::*;
+use aries::handlers::connection::agent_info::AgentInfo;
+use aries::handlers::connection::requester::states::complete::CompleteState;
+use aries::handlers::connection::requester::states::null::NullState;
+use aries::messages::connection::did_doc::DidDoc;
+use aries::messages::did_exchange::complete::Complete;
+use aries::messages::did_exchange::problem_report::ProblemReport;
+use aries::messages::did_exchange::request::Request;
+use aries::messages::did_exchange::response::Response;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RequestedState {
+    pub request: Request,
+    pub did_doc: DidDoc,
+}
+
+impl From<(RequestedState, ProblemReport)> for NullState {
+    fn from((_state, _error): (RequestedState, ProblemReport)) -> NullState {
+        trace!("DidExchangeRequester: transit state from RequestedState to NullState");
+        NullState {}
+    }
+}
+
+impl From<(RequestedState, DidDoc)> for CompleteState {
+    fn from((_state, did_doc): (RequestedState, DidDoc)) -> CompleteState {
+        trace!("DidExchangeRequester: transit state from RequestedState to CompleteState");
+        CompleteState { did_doc, protocols: None }
+    }
+}
+
+impl RequestedState {
+    pub fn handle_exchange_response(&self, response: Response, agent_info: &AgentInfo) -> VcxResult<DidDoc> {
+        trace!("DidExchangeRequester:handle_exchange_response >>> response: {:?}, agent_info: {:?}", response, agent_info);
+
+        if !response.from_thread(&self.request.id.0) {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot handle Response: thread id does not match: {:?}", response.thread)));
+        }
+
+        let remote_vk: String = self.did_doc.recipient_keys().get(0).cloned()
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidState, "Cannot handle Response: Remote Verkey not found"))?;
+
+        let did_doc = response.verify_did_doc(&remote_vk)?;
+        did_doc.validate()?;
+
+        let mut complete = Complete::create()
+            .set_thread_id(&self.request.id.0);
+
+        if let Some(ref pthid) = self.request.thread.pthid {
+            complete = complete.set_parent_thread_id(pthid);
+        }
+
+        agent_info.send_message(&complete.to_a2a_message(), &did_doc)?;
+
+        Ok(did_doc)
+    }
+}
@@ -0,0 +1,2 @@
+mod states;
+pub mod state_machine;
\ No newline at end of file
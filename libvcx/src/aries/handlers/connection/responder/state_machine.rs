@@ -0,0 +1,464 @@
+use std::collections::HashMap;
+
+use api::VcxStateType;
+use error::prelude::*;
+use aries::handlers::connection::agent_info::AgentInfo;
+use aries::handlers::connection::messages::DidExchangeMessages;
+use aries::handlers::connection::responder::states::complete::CompleteState;
+use aries::handlers::connection::responder::states::invited::InvitedState;
+use aries::handlers::connection::responder::states::null::NullState;
+use aries::handlers::connection::responder::states::responded::RespondedState;
+use aries::messages::a2a::A2AMessage;
+use aries::messages::a2a::message_family::MessageFamilies;
+use aries::messages::a2a::protocol_registry::ProtocolRegistry;
+use aries::messages::connection::did_doc::DidDoc;
+use aries::messages::did_exchange::problem_report::{ProblemCode, ProblemReport};
+use aries::messages::discovery::disclose::ProtocolDescriptor;
+use aries::messages::out_of_band::invitation::{OutOfBandInvitation, OutOfBandService};
+use aries::messages::trust_ping::ping::Ping;
+
+/// Responder (inviter) side of DID Exchange 1.0 protocol (RFC 0023).
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SmDidExchangeResponder {
+    source_id: String,
+    agent_info: AgentInfo,
+    state: ResponderState,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum ResponderState {
+    Null(NullState),
+    Invited(InvitedState),
+    Responded(RespondedState),
+    Completed(CompleteState),
+}
+
+impl ResponderState {
+    pub fn code(&self) -> u32 {
+        match self {
+            ResponderState::Null(_) => VcxStateType::VcxStateInitialized as u32,
+            ResponderState::Invited(_) => VcxStateType::VcxStateOfferSent as u32,
+            ResponderState::Responded(_) => VcxStateType::VcxStateRequestReceived as u32,
+            ResponderState::Completed(_) => VcxStateType::VcxStateAccepted as u32,
+        }
+    }
+}
+
+impl SmDidExchangeResponder {
+    pub fn new(source_id: &str) -> Self {
+        SmDidExchangeResponder {
+            source_id: source_id.to_string(),
+            state: ResponderState::Null(NullState {}),
+            agent_info: AgentInfo::default(),
+        }
+    }
+
+    pub fn from(source_id: String, agent_info: AgentInfo, state: ResponderState) -> Self {
+        SmDidExchangeResponder {
+            source_id,
+            agent_info,
+            state,
+        }
+    }
+
+    pub fn is_in_null_state(&self) -> bool {
+        match self.state {
+            ResponderState::Null(_) => true,
+            _ => false
+        }
+    }
+
+    pub fn agent_info(&self) -> &AgentInfo {
+        &self.agent_info
+    }
+
+    pub fn source_id(&self) -> &str {
+        &self.source_id
+    }
+
+    pub fn state(&self) -> u32 {
+        self.state.code()
+    }
+
+    pub fn state_object(&self) -> &ResponderState {
+        &self.state
+    }
+
+    pub fn step(self, message: DidExchangeMessages) -> VcxResult<SmDidExchangeResponder> {
+        trace!("SmDidExchangeResponder::step >>> message: {:?}", message);
+        let SmDidExchangeResponder { source_id, agent_info, state } = self;
+
+        trace!("SmDidExchangeResponder::step :: current state = {:?}", &state);
+        let (new_state, agent_info) =
+            SmDidExchangeResponder::responder_step(state, message, &source_id, agent_info)?;
+
+        trace!("SmDidExchangeResponder::step :: new state = {:?}", &new_state);
+        Ok(SmDidExchangeResponder { source_id, agent_info, state: new_state })
+    }
+
+    pub fn their_did_doc(&self) -> Option<DidDoc> {
+        match self.state {
+            ResponderState::Null(_) => None,
+            ResponderState::Invited(ref _state) => None,
+            ResponderState::Responded(ref state) => Some(state.did_doc.clone()),
+            ResponderState::Completed(ref state) => Some(state.did_doc.clone()),
+        }
+    }
+
+    pub fn get_invitation(&self) -> Option<&OutOfBandInvitation> {
+        match self.state {
+            ResponderState::Invited(ref state) => Some(&state.invitation),
+            _ => None
+        }
+    }
+
+    pub fn find_message_to_handle(&self, messages: HashMap<String, A2AMessage>) -> Option<(String, A2AMessage)> {
+        for (uid, message) in messages {
+            if self.can_handle_message(&message) {
+                return Some((uid, message));
+            }
+        }
+        None
+    }
+
+    pub fn get_bootstrap_agent_messages(&self) -> VcxResult<Option<(HashMap<String, A2AMessage>, AgentInfo)>> {
+        if let Some(prev_agent_info) = self.prev_agent_info() {
+            let messages = prev_agent_info.get_messages()?;
+            return Ok(Some((messages, prev_agent_info.clone())));
+        }
+        Ok(None)
+    }
+
+    pub fn get_protocols(&self) -> Vec<ProtocolDescriptor> {
+        ProtocolRegistry::init().protocols()
+    }
+
+    pub fn get_remote_protocols(&self) -> Option<Vec<ProtocolDescriptor>> {
+        match self.state {
+            ResponderState::Completed(ref state) => state.protocols.clone(),
+            _ => None
+        }
+    }
+
+    pub fn remote_did(&self) -> VcxResult<String> {
+        self.their_did_doc()
+            .map(|did_doc: DidDoc| did_doc.id.clone())
+            .ok_or(VcxError::from_msg(VcxErrorKind::NotReady, "Remote Connection DID is not set"))
+    }
+
+    pub fn remote_vk(&self) -> VcxResult<String> {
+        self.their_did_doc()
+            .and_then(|did_doc| did_doc.recipient_keys().get(0).cloned())
+            .ok_or(VcxError::from_msg(VcxErrorKind::NotReady, "Remote Connection Verkey is not set"))
+    }
+
+    pub fn prev_agent_info(&self) -> Option<&AgentInfo> {
+        match self.state {
+            ResponderState::Responded(ref state) => Some(&state.prev_agent_info),
+            _ => None
+        }
+    }
+
+    pub fn can_handle_message(&self, message: &A2AMessage) -> bool {
+        match self.state {
+            ResponderState::Invited(_) => {
+                match message {
+                    A2AMessage::DidExchangeRequest(_) => {
+                        debug!("Responder received DidExchangeRequest message");
+                        true
+                    }
+                    A2AMessage::DidExchangeProblemReport(_) => {
+                        debug!("Responder received ProblemReport message");
+                        true
+                    }
+                    _ => {
+                        debug!("Responder received unexpected message: {:?}", message);
+                        false
+                    }
+                }
+            }
+            ResponderState::Responded(_) => {
+                match message {
+                    A2AMessage::DidExchangeComplete(_) => {
+                        debug!("DidExchangeComplete message received");
+                        true
+                    }
+                    A2AMessage::Ack(_) => {
+                        debug!("Ack message received");
+                        true
+                    }
+                    A2AMessage::Ping(_) => {
+                        debug!("Ping message received");
+                        true
+                    }
+                    A2AMessage::PingResponse(_) => {
+                        debug!("PingResponse message received");
+                        true
+                    }
+                    A2AMessage::DidExchangeProblemReport(_) => {
+                        debug!("ProblemReport message received");
+                        true
+                    }
+                    _ => {
+                        debug!("Unexpected message received in Responded state: {:?}", message);
+                        false
+                    }
+                }
+            }
+            ResponderState::Completed(_) => {
+                match message {
+                    A2AMessage::Ping(_) => {
+                        debug!("Ping message received");
+                        true
+                    }
+                    A2AMessage::PingResponse(_) => {
+                        debug!("PingResponse message received");
+                        true
+                    }
+                    A2AMessage::Query(_) => {
+                        debug!("Query message received");
+                        true
+                    }
+                    A2AMessage::Disclose(_) => {
+                        debug!("Disclose message received");
+                        true
+                    }
+                    _ => {
+                        debug!("Unexpected message received in Completed state: {:?}", message);
+                        false
+                    }
+                }
+            }
+            _ => {
+                debug!("Unexpected message received: message: {:?}", message);
+                false
+            }
+        }
+    }
+
+    pub fn responder_step(responder_state: ResponderState, message: DidExchangeMessages, source_id: &str, mut agent_info: AgentInfo) -> VcxResult<(ResponderState, AgentInfo)> {
+        let new_state = match responder_state {
+            ResponderState::Null(state) => {
+                match message {
+                    DidExchangeMessages::Connect() => {
+                        agent_info = agent_info.create_agent()?;
+
+                        let service = OutOfBandService {
+                            recipient_keys: agent_info.recipient_keys(),
+                            routing_keys: agent_info.routing_keys()?,
+                            service_endpoint: agent_info.agency_endpoint()?,
+                            ..OutOfBandService::default()
+                        };
+
+                        let invite: OutOfBandInvitation = OutOfBandInvitation::create()
+                            .set_label(source_id.to_string())
+                            .set_handshake_protocols(vec![MessageFamilies::DidExchange])
+                            .set_service(service);
+
+                        ResponderState::Invited((state, invite).into())
+                    }
+                    _ => {
+                        ResponderState::Null(state)
+                    }
+                }
+            }
+            ResponderState::Invited(state) => {
+                match message {
+                    DidExchangeMessages::DidExchangeRequestReceived(request) => {
+                        match state.handle_exchange_request(&request, &agent_info) {
+                            Ok((did_doc, response, new_agent_info)) => {
+                                let prev_agent_info = agent_info.clone();
+                                agent_info = new_agent_info;
+                                ResponderState::Responded((state, did_doc, response, prev_agent_info).into())
+                            }
+                            Err(err) => {
+                                let problem_report = ProblemReport::create()
+                                    .set_problem_code(ProblemCode::RequestProcessingError)
+                                    .set_explain(err.to_string())
+                                    .set_thread_id(&request.id.0);
+
+                                if let Ok(did_doc) = request.did_doc() {
+                                    agent_info.send_message(&problem_report.to_a2a_message(), &did_doc).ok();
+                                }
+                                ResponderState::Null((state, problem_report).into())
+                            }
+                        }
+                    }
+                    DidExchangeMessages::DidExchangeProblemReportReceived(problem_report) => {
+                        ResponderState::Null((state, problem_report).into())
+                    }
+                    _ => {
+                        ResponderState::Invited(state)
+                    }
+                }
+            }
+            ResponderState::Responded(state) => {
+                match message {
+                    DidExchangeMessages::DidExchangeCompleteReceived(complete) => {
+                        ResponderState::Completed((state, complete).into())
+                    }
+                    DidExchangeMessages::AckReceived(ack) => {
+                        ResponderState::Completed((state, ack).into())
+                    }
+                    DidExchangeMessages::PingReceived(ping) => {
+                        state.handle_ping(&ping, &agent_info)?;
+                        ResponderState::Completed((state, ping).into())
+                    }
+                    DidExchangeMessages::DidExchangeProblemReportReceived(problem_report) => {
+                        ResponderState::Null((state, problem_report).into())
+                    }
+                    DidExchangeMessages::SendPing(comment) => {
+                        let ping =
+                            Ping::create()
+                                .request_response()
+                                .set_comment(comment);
+
+                        agent_info.send_message(&ping.to_a2a_message(), &state.did_doc).ok();
+                        ResponderState::Responded(state)
+                    }
+                    DidExchangeMessages::PingResponseReceived(ping_response) => {
+                        ResponderState::Completed((state, ping_response).into())
+                    }
+                    _ => {
+                        ResponderState::Responded(state)
+                    }
+                }
+            }
+            ResponderState::Completed(state) => {
+                state.handle_message(message, &agent_info)?
+            }
+        };
+        Ok((new_state, agent_info))
+    }
+}
+
+#[cfg(test)]
+pub mod test {
+    use utils::devsetup::SetupAriesMocks;
+    use aries::messages::ack::tests::_ack;
+    use aries::messages::did_exchange::complete::tests::_complete;
+    use aries::messages::did_exchange::problem_report::tests::_problem_report;
+    use aries::messages::did_exchange::request::tests::_request;
+    use aries::messages::trust_ping::ping::tests::_ping;
+    use aries::test::setup::AgencyModeSetup;
+    use aries::test::source_id;
+
+    use super::*;
+
+    pub fn responder_sm() -> SmDidExchangeResponder {
+        SmDidExchangeResponder::new(&source_id())
+    }
+
+    impl SmDidExchangeResponder {
+        fn to_responder_invited_state(mut self) -> SmDidExchangeResponder {
+            self = self.step(DidExchangeMessages::Connect()).unwrap();
+            self
+        }
+
+        fn to_responder_responded_state(mut self) -> SmDidExchangeResponder {
+            self = self.step(DidExchangeMessages::Connect()).unwrap();
+            self = self.step(DidExchangeMessages::DidExchangeRequestReceived(_request())).unwrap();
+            self
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_responder_new() {
+        let _setup = SetupAriesMocks::init();
+
+        let responder_sm = responder_sm();
+
+        assert_match!(ResponderState::Null(_), responder_sm.state);
+        assert_eq!(source_id(), responder_sm.source_id());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_responder_handle_connect_message_from_null_state() {
+        let _setup = AgencyModeSetup::init();
+
+        let responder_sm = responder_sm().to_responder_invited_state();
+
+        assert_match!(ResponderState::Invited(_), responder_sm.state);
+        let invitation = responder_sm.get_invitation().unwrap();
+        assert!(invitation.supports_handshake(&MessageFamilies::DidExchange));
+        assert!(!invitation.supports_handshake(&MessageFamilies::Connections));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_responder_handle_exchange_request_message_from_invited_state() {
+        let _setup = AgencyModeSetup::init();
+
+        let responder_sm = responder_sm().to_responder_responded_state();
+
+        assert_match!(ResponderState::Responded(_), responder_sm.state);
+        assert!(responder_sm.their_did_doc().is_some());
+        assert!(responder_sm.prev_agent_info().is_some());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_responder_handle_exchange_request_with_other_parent_thread_from_invited_state() {
+        let _setup = AgencyModeSetup::init();
+
+        let mut request = _request();
+        request.thread.pthid = Some(String::from("other"));
+
+        let responder_sm = responder_sm().to_responder_invited_state()
+            .step(DidExchangeMessages::DidExchangeRequestReceived(request)).unwrap();
+
+        assert_match!(ResponderState::Null(_), responder_sm.state);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_responder_handle_problem_report_from_invited_state() {
+        let _setup = AgencyModeSetup::init();
+
+        let responder_sm = responder_sm().to_responder_invited_state()
+            .step(DidExchangeMessages::DidExchangeProblemReportReceived(_problem_report())).unwrap();
+
+        assert_match!(ResponderState::Null(_), responder_sm.state);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_responder_handle_complete_message_from_responded_state() {
+        let _setup = AgencyModeSetup::init();
+
+        let responder_sm = responder_sm().to_responder_responded_state()
+            .step(DidExchangeMessages::DidExchangeCompleteReceived(_complete())).unwrap();
+
+        assert_match!(ResponderState::Completed(_), responder_sm.state);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_responder_handle_ack_and_ping_messages_from_responded_state() {
+        let _setup = AgencyModeSetup::init();
+
+        let responder_sm = responder_sm().to_responder_responded_state()
+            .step(DidExchangeMessages::AckReceived(_ack())).unwrap();
+        assert_match!(ResponderState::Completed(_), responder_sm.state);
+
+        let responder_sm = self::responder_sm().to_responder_responded_state()
+            .step(DidExchangeMessages::PingReceived(_ping())).unwrap();
+        assert_match!(ResponderState::Completed(_), responder_sm.state);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_responder_can_handle_message() {
+        let _setup = AgencyModeSetup::init();
+
+        let responder_sm = responder_sm().to_responder_invited_state();
+        assert!(responder_sm.can_handle_message(&_request().to_a2a_message()));
+        assert!(!responder_sm.can_handle_message(&_complete().to_a2a_message()));
+
+        let responder_sm = self::responder_sm().to_responder_responded_state();
+        assert!(responder_sm.can_handle_message(&_complete().to_a2a_message()));
+        assert!(!responder_sm.can_handle_message(&_request().to_a2a_message()));
+    }
+}
@@ -0,0 +1,56 @@
+use error::prelude::*;
+use aries::handlers::connection::agent_info::AgentInfo;
+use aries::handlers::connection::responder::states::null::NullState;
+use aries::handlers::connection::responder::states::responded::RespondedState;
+use aries::messages::connection::did_doc::DidDoc;
+use aries::messages::did_exchange::problem_report::ProblemReport;
+use aries::messages::did_exchange::request::Request;
+use aries::messages::did_exchange::response::Response;
+use aries::messages::out_of_band::invitation::OutOfBandInvitation;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct InvitedState {
+    pub invitation: OutOfBandInvitation
+}
+
+impl From<(InvitedState, ProblemReport)> for NullState {
+    fn from((_state, _error): (InvitedState, ProblemReport)) -> NullState {
+        trace!("DidExchangeResponder: transit state from InvitedState to NullState");
+        NullState {}
+    }
+}
+
+impl From<(InvitedState, DidDoc, Response, AgentInfo)> for RespondedState {
+    fn from((_state, did_doc, response, prev_agent_info): (InvitedState, DidDoc, Response, AgentInfo)) -> RespondedState {
+        trace!("DidExchangeResponder: transit state from InvitedState to RespondedState");
+        RespondedState { response, did_doc, prev_agent_info }
+    }
+}
+
+impl InvitedState {
+    pub fn handle_exchange_request(&self, request: &Request,
+                                   agent_info: &AgentInfo) -> VcxResult<(DidDoc, Response, AgentInfo)> {
+        trace!("DidExchangeResponder:handle_exchange_request >>> request: {:?}, agent_info: {:?}", request, agent_info);
+
+        if let Some(ref pthid) = request.thread.pthid {
+            if pthid != &self.invitation.id.0 {
+                return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot handle Request: parent thread id does not match invitation: {:?}", pthid)));
+            }
+        }
+
+        let did_doc = request.did_doc()?;
+        did_doc.validate()?;
+
+        // provision a new keys
+        let new_agent_info: AgentInfo = agent_info.create_agent()?;
+
+        let response = Response::create()
+            .set_did(new_agent_info.pw_did.to_string())
+            .set_thread_id(&request.id.0)
+            .set_signed_did_doc(&new_agent_info.did_doc()?, &agent_info.pw_vk)?;
+
+        new_agent_info.send_message(&response.to_a2a_message(), &did_doc)?;
+
+        Ok((did_doc, response, new_agent_info))
+    }
+}
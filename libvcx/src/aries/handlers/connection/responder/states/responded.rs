@@ -0,0 +1,60 @@
+use error::prelude::*;
+use aries::handlers::connection::agent_info::AgentInfo;
+use aries::handlers::connection::responder::states::complete::CompleteState;
+use aries::handlers::connection::responder::states::null::NullState;
+use aries::handlers::connection::util::handle_ping;
+use aries::messages::ack::Ack;
+use aries::messages::connection::did_doc::DidDoc;
+use aries::messages::did_exchange::complete::Complete;
+use aries::messages::did_exchange::problem_report::ProblemReport;
+use aries::messages::did_exchange::response::Response;
+use aries::messages::trust_ping::ping::Ping;
+use aries::messages::trust_ping::ping_response::PingResponse;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct RespondedState {
+    pub response: Response,
+    pub did_doc: DidDoc,
+    pub prev_agent_info: AgentInfo,
+}
+
+impl From<(RespondedState, ProblemReport)> for NullState {
+    fn from((_state, _error): (RespondedState, ProblemReport)) -> NullState {
+        trace!("DidExchangeResponder: transit state from RespondedState to NullState");
+        NullState {}
+    }
+}
+
+impl From<(RespondedState, Complete)> for CompleteState {
+    fn from((state, _complete): (RespondedState, Complete)) -> CompleteState {
+        trace!("DidExchangeResponder: transit state from RespondedState to CompleteState");
+        CompleteState { did_doc: state.did_doc, protocols: None }
+    }
+}
+
+impl From<(RespondedState, Ack)> for CompleteState {
+    fn from((state, _ack): (RespondedState, Ack)) -> CompleteState {
+        trace!("DidExchangeResponder: transit state from RespondedState to CompleteState");
+        CompleteState { did_doc: state.did_doc, protocols: None }
+    }
+}
+
+impl From<(RespondedState, Ping)> for CompleteState {
+    fn from((state, _ping): (RespondedState, Ping)) -> CompleteState {
+        trace!("DidExchangeResponder: transit state from RespondedState to CompleteState");
+        CompleteState { did_doc: state.did_doc, protocols: None }
+    }
+}
+
+impl From<(RespondedState, PingResponse)> for CompleteState {
+    fn from((state, _ping_response): (RespondedState, PingResponse)) -> CompleteState {
+        trace!("DidExchangeResponder: transit state from RespondedState to CompleteState");
+        CompleteState { did_doc: state.did_doc, protocols: None }
+    }
+}
+
+impl RespondedState {
+    pub fn handle_ping(&self, ping: &Ping, agent_info: &AgentInfo) -> VcxResult<()> {
+        handle_ping(ping, agent_info, &self.did_doc)
+    }
+}
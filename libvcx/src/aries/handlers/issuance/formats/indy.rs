@@ -0,0 +1,97 @@
+use error::prelude::*;
+use utils::libindy::anoncreds::{self, get_cred_def_json, libindy_issuer_create_credential_offer, libindy_prover_create_credential_req, libindy_prover_store_credential};
+use aries::handlers::issuance::formats::CredentialFormatHandler;
+use aries::handlers::issuance::issuer::utils::encode_attributes;
+
+/// Hyperledger Indy credentials (`hlindy/*@v2.0` attachments) backed by libindy anoncreds.
+pub struct IndyCredentialFormat;
+
+impl CredentialFormatHandler for IndyCredentialFormat {
+    fn create_offer(&self, cred_def_id: &str) -> VcxResult<String> {
+        libindy_issuer_create_credential_offer(cred_def_id)
+    }
+
+    fn create_credential(&self, offer: &str, request: &str, cred_data: &str,
+                         rev_reg_id: Option<String>, tails_file: Option<String>) -> VcxResult<(String, Option<String>)> {
+        trace!("IndyCredentialFormat::create_credential >>> offer: {:?}, request: {:?}, cred_data: {:?}, rev_reg_id: {:?}, tails_file: {:?}", offer, request, cred_data, rev_reg_id, tails_file);
+
+        let cred_data = encode_attributes(cred_data)?;
+
+        let (credential, cred_rev_id, _) = anoncreds::libindy_issuer_create_credential(offer, request, &cred_data, rev_reg_id, tails_file)?;
+
+        Ok((credential, cred_rev_id))
+    }
+
+    fn create_request(&self, prover_did: &str, offer: &str) -> VcxResult<(String, String, String)> {
+        trace!("IndyCredentialFormat::create_request >>> prover_did: {:?}, offer: {:?}", prover_did, offer);
+
+        let cred_def_id = _parse_cred_def_from_cred_offer(offer)?;
+        let (_, cred_def_json) = get_cred_def_json(&cred_def_id)?;
+
+        let (request, req_meta) = libindy_prover_create_credential_req(prover_did, offer, &cred_def_json)
+            .map_err(|err| err.extend("Cannot create credential request"))?;
+
+        Ok((request, req_meta, cred_def_json))
+    }
+
+    fn store_credential(&self, credential: &str, req_meta: &str, cred_def_json: &str) -> VcxResult<(String, Option<String>)> {
+        trace!("IndyCredentialFormat::store_credential >>>");
+
+        let rev_reg_id = _parse_rev_reg_id_from_credential(credential)?;
+        let rev_reg_def_json = if let Some(rev_reg_id) = rev_reg_id {
+            let (_, json) = anoncreds::get_rev_reg_def_json(&rev_reg_id)?;
+            Some(json)
+        } else {
+            None
+        };
+
+        let cred_id = libindy_prover_store_credential(None,
+                                                      req_meta,
+                                                      credential,
+                                                      cred_def_json,
+                                                      rev_reg_def_json.as_ref().map(String::as_str))?;
+        Ok((cred_id, rev_reg_def_json))
+    }
+}
+
+fn _parse_cred_def_from_cred_offer(cred_offer: &str) -> VcxResult<String> {
+    trace!("IndyCredentialFormat::_parse_cred_def_from_cred_offer >>> cred_offer: {:?}", cred_offer);
+
+    let parsed_offer: serde_json::Value = serde_json::from_str(cred_offer)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Invalid Credential Offer Json: {:?}", err)))?;
+
+    let cred_def_id = parsed_offer["cred_def_id"].as_str()
+        .ok_or_else(|| VcxError::from_msg(VcxErrorKind::InvalidJson, "Invalid Credential Offer Json: cred_def_id not found"))?;
+
+    Ok(cred_def_id.to_string())
+}
+
+fn _parse_rev_reg_id_from_credential(credential: &str) -> VcxResult<Option<String>> {
+    trace!("IndyCredentialFormat::_parse_rev_reg_id_from_credential >>>");
+
+    let parsed_credential: serde_json::Value = serde_json::from_str(credential)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Invalid Credential Json: {}, err: {:?}", credential, err)))?;
+
+    let rev_reg_id = parsed_credential["rev_reg_id"].as_str().map(String::from);
+
+    Ok(rev_reg_id)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_parse_cred_def_from_cred_offer_works() {
+        assert_eq!("V4SGRU86Z58d6TV7PBUe6f:3:CL:1281:tag1", _parse_cred_def_from_cred_offer(&json!({"cred_def_id": "V4SGRU86Z58d6TV7PBUe6f:3:CL:1281:tag1"}).to_string()).unwrap());
+        assert_eq!(VcxErrorKind::InvalidJson, _parse_cred_def_from_cred_offer(r#"{"credential offer": {}}"#).unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_parse_rev_reg_id_from_credential_works() {
+        assert_eq!(Some(String::from("rev_reg_id")), _parse_rev_reg_id_from_credential(&json!({"rev_reg_id": "rev_reg_id"}).to_string()).unwrap());
+        assert_eq!(None, _parse_rev_reg_id_from_credential(&json!({"rev_reg_id": null}).to_string()).unwrap());
+    }
+}
@@ -0,0 +1,32 @@
+use error::VcxResult;
+use aries::messages::issuance::v20::CredentialFormat;
+
+pub mod indy;
+
+/// Format specific part of credential issuance.
+///
+/// Issuance state machines only move attachment contents between messages and
+/// delegate everything related to their structure to the handler of negotiated format.
+pub trait CredentialFormatHandler {
+    /// Creates content of the offer attachment for the given credential definition.
+    fn create_offer(&self, cred_def_id: &str) -> VcxResult<String>;
+
+    /// Creates the credential for the request.
+    /// Returns the credential and its revocation id.
+    fn create_credential(&self, offer: &str, request: &str, cred_data: &str,
+                         rev_reg_id: Option<String>, tails_file: Option<String>) -> VcxResult<(String, Option<String>)>;
+
+    /// Creates the request for the offer.
+    /// Returns the request, its private metadata and credential definition needed to store the credential later.
+    fn create_request(&self, prover_did: &str, offer: &str) -> VcxResult<(String, String, String)>;
+
+    /// Stores the credential in the wallet.
+    /// Returns id of stored credential and revocation registry definition the credential refers to.
+    fn store_credential(&self, credential: &str, req_meta: &str, cred_def_json: &str) -> VcxResult<(String, Option<String>)>;
+}
+
+pub fn handler(format: &CredentialFormat) -> &'static dyn CredentialFormatHandler {
+    match format {
+        CredentialFormat::Indy => &indy::IndyCredentialFormat
+    }
+}
@@ -2,11 +2,15 @@
 
 use connection;
 use error::prelude::*;
+use history::History;
 use aries::handlers::issuance::holder::state_machine::HolderSM;
 use aries::handlers::issuance::messages::CredentialIssuanceMessage;
+use aries::handlers::issuance::protocol::IssuanceProtocol;
 use aries::messages::a2a::A2AMessage;
 use aries::messages::issuance::credential::Credential;
 use aries::messages::issuance::credential_offer::CredentialOffer;
+use aries::messages::issuance::credential_proposal::CredentialProposal;
+use aries::messages::issuance::v20::credential_offer::CredentialOfferV2;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Holder {
@@ -22,6 +26,29 @@ impl Holder {
         Ok(Holder { holder_sm })
     }
 
+    pub fn create_v2(credential_offer: CredentialOfferV2, source_id: &str) -> VcxResult<Holder> {
+        trace!("Holder::create_v2 >>> credential_offer: {:?}, source_id: {:?}", credential_offer, source_id);
+
+        let format = credential_offer.format()
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Credential Offer does not contain attachment of supported format: {:?}", credential_offer.formats)))?;
+
+        let holder_sm = HolderSM::new_with_protocol(credential_offer.into(), IssuanceProtocol::V2(format), source_id.to_string());
+
+        Ok(Holder { holder_sm })
+    }
+
+    pub fn create_with_proposal(credential_proposal: CredentialProposal, source_id: &str) -> VcxResult<Holder> {
+        trace!("Holder::create_with_proposal >>> credential_proposal: {:?}, source_id: {:?}", credential_proposal, source_id);
+
+        let holder_sm = HolderSM::new_with_proposal(credential_proposal, source_id.to_string());
+
+        Ok(Holder { holder_sm })
+    }
+
+    pub fn send_proposal(&mut self, connection_handle: u32) -> VcxResult<()> {
+        self.step(CredentialIssuanceMessage::CredentialProposalSend(connection_handle))
+    }
+
     pub fn send_request(&mut self, connection_handle: u32) -> VcxResult<()> {
         self.step(CredentialIssuanceMessage::CredentialRequestSend(connection_handle))
     }
@@ -49,6 +76,18 @@ impl Holder {
         self.holder_sm.get_source_id()
     }
 
+    pub fn get_thread_id(&self) -> String {
+        self.holder_sm.thread_id()
+    }
+
+    pub fn get_connection_handle(&self) -> Option<u32> {
+        self.holder_sm.connection_handle()
+    }
+
+    pub fn get_history(&self) -> &History {
+        self.holder_sm.history()
+    }
+
     pub fn get_credential(&self) -> VcxResult<(String, A2AMessage)> {
         self.holder_sm.get_credential()
     }
@@ -69,7 +108,7 @@ impl Holder {
     pub fn get_credential_offer_message(connection_handle: u32, msg_id: &str) -> VcxResult<A2AMessage> {
         match connection::get_message_by_id(connection_handle, msg_id.to_string()) {
             Ok(message) => match message {
-                A2AMessage::CredentialOffer(_) => Ok(message),
+                A2AMessage::CredentialOffer(_) | A2AMessage::CredentialOfferV2(_) => Ok(message),
                 msg => {
                     return Err(VcxError::from_msg(VcxErrorKind::InvalidMessages,
                                                   format!("Message of different type was received: {:?}", msg)));
@@ -85,7 +124,7 @@ impl Holder {
             .into_iter()
             .filter_map(|(_, a2a_message)| {
                 match a2a_message {
-                    A2AMessage::CredentialOffer(_) => Some(a2a_message),
+                    A2AMessage::CredentialOffer(_) | A2AMessage::CredentialOfferV2(_) => Some(a2a_message),
                     _ => None
                 }
             })
@@ -3,21 +3,28 @@ use std::collections::HashMap;
 use api::VcxStateType;
 use connection;
 use error::prelude::*;
-use utils::libindy::anoncreds::{self, libindy_prover_delete_credential, libindy_prover_store_credential, libindy_prover_create_credential_req, get_cred_def_json};
+use history::{History, Transition};
+use utils::libindy::anoncreds::libindy_prover_delete_credential;
 use aries::handlers::issuance::messages::CredentialIssuanceMessage;
+use aries::handlers::issuance::protocol::IssuanceProtocol;
 use aries::messages::a2a::A2AMessage;
 use aries::messages::error::ProblemReport;
 use aries::messages::issuance::credential::Credential;
 use aries::messages::issuance::credential_ack::CredentialAck;
 use aries::messages::issuance::credential_offer::CredentialOffer;
+use aries::messages::issuance::credential_proposal::CredentialProposal;
 use aries::messages::issuance::credential_request::CredentialRequest;
 use aries::messages::status::Status;
+use aries::handlers::issuance::holder::states::initial::InitialHolderState;
+use aries::handlers::issuance::holder::states::proposal_sent::ProposalSentState;
 use aries::handlers::issuance::holder::states::offer_received::OfferReceivedState;
 use aries::handlers::issuance::holder::states::request_sent::RequestSentState;
 use aries::handlers::issuance::holder::states::finished::FinishedHolderState;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum HolderState {
+    Initial(InitialHolderState),
+    ProposalSent(ProposalSentState),
     OfferReceived(OfferReceivedState),
     RequestSent(RequestSentState),
     Finished(FinishedHolderState),
@@ -26,6 +33,8 @@ pub enum HolderState {
 impl HolderState {
     pub fn get_connection_handle(&self) -> u32 {
         match self {
+            HolderState::Initial(_) => 0,
+            HolderState::ProposalSent(state) => state.connection_handle,
             HolderState::OfferReceived(_) => 0,
             HolderState::RequestSent(state) => state.connection_handle,
             HolderState::Finished(_) => 0
@@ -34,6 +43,9 @@ impl HolderState {
 
     pub fn set_connection_handle(&mut self, connection_handle: u32) {
         match self {
+            HolderState::ProposalSent(ref mut state) => {
+                state.connection_handle = connection_handle;
+            }
             HolderState::RequestSent(ref mut state) => {
                 state.connection_handle = connection_handle;
             }
@@ -47,14 +59,37 @@ pub struct HolderSM {
     state: HolderState,
     source_id: String,
     thread_id: String,
+    #[serde(default)]
+    protocol: IssuanceProtocol,
+    #[serde(default)]
+    history: History,
 }
 
 impl HolderSM {
     pub fn new(offer: CredentialOffer, source_id: String) -> Self {
+        HolderSM::new_with_protocol(offer, IssuanceProtocol::V1, source_id)
+    }
+
+    /// Creates the state machine for the offer received via the given version of issue-credential protocol.
+    pub fn new_with_protocol(offer: CredentialOffer, protocol: IssuanceProtocol, source_id: String) -> Self {
         HolderSM {
             thread_id: offer.id.0.clone(),
             state: HolderState::OfferReceived(OfferReceivedState::new(offer)),
             source_id,
+            protocol,
+            history: History::default(),
+        }
+    }
+
+    /// Creates the state machine for the holder-initiated exchange starting from the given proposal.
+    /// The protocol version is negotiated when the proposal is sent.
+    pub fn new_with_proposal(proposal: CredentialProposal, source_id: String) -> Self {
+        HolderSM {
+            thread_id: proposal.id.0.clone(),
+            state: HolderState::Initial(InitialHolderState::new(proposal)),
+            source_id,
+            protocol: IssuanceProtocol::default(),
+            history: History::default(),
         }
     }
 
@@ -62,8 +97,26 @@ impl HolderSM {
         self.source_id.clone()
     }
 
+    pub fn thread_id(&self) -> String {
+        self.thread_id.clone()
+    }
+
+    /// Connection the credential is exchanged over, not known before the holder sends a message.
+    pub fn connection_handle(&self) -> Option<u32> {
+        match self.state.get_connection_handle() {
+            0 => None,
+            connection_handle => Some(connection_handle)
+        }
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
     pub fn state(&self) -> u32 {
         match self.state {
+            HolderState::Initial(_) => VcxStateType::VcxStateInitialized as u32,
+            HolderState::ProposalSent(_) => VcxStateType::VcxStateProposalSent as u32,
             HolderState::OfferReceived(_) => VcxStateType::VcxStateRequestReceived as u32,
             HolderState::RequestSent(_) => VcxStateType::VcxStateOfferSent as u32,
             HolderState::Finished(ref status) => {
@@ -87,7 +140,8 @@ impl HolderSM {
 
         match self.find_message_to_handle(messages) {
             Some((uid, msg)) => {
-                let state = self.handle_message(msg.into())?;
+                let state = self.handle_message(msg.clone().into())?;
+                connection::record_processed(conn_handle, &msg)?;
                 connection::update_message_status(conn_handle, uid)?;
                 Ok(state)
             }
@@ -100,6 +154,33 @@ impl HolderSM {
 
         for (uid, message) in messages {
             match self.state {
+                HolderState::Initial(_) => {
+                    // do not process messages
+                }
+                HolderState::ProposalSent(_) => {
+                    match message {
+                        A2AMessage::CredentialOffer(offer) => {
+                            if let Some(ref thread) = offer.thread {
+                                if thread.is_reply(&self.thread_id) {
+                                    return Some((uid, A2AMessage::CredentialOffer(offer)));
+                                }
+                            }
+                        }
+                        A2AMessage::CredentialOfferV2(offer) => {
+                            if let Some(ref thread) = offer.thread {
+                                if thread.is_reply(&self.thread_id) {
+                                    return Some((uid, A2AMessage::CredentialOfferV2(offer)));
+                                }
+                            }
+                        }
+                        A2AMessage::CommonProblemReport(problem_report) => {
+                            if problem_report.from_thread(&self.thread_id) {
+                                return Some((uid, A2AMessage::CommonProblemReport(problem_report)));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
                 HolderState::OfferReceived(_) => {
                     // do not process messages
                 }
@@ -110,6 +191,11 @@ impl HolderSM {
                                 return Some((uid, A2AMessage::Credential(credential)));
                             }
                         }
+                        A2AMessage::CredentialV2(credential) => {
+                            if credential.from_thread(&self.thread_id) {
+                                return Some((uid, A2AMessage::CredentialV2(credential)));
+                            }
+                        }
                         A2AMessage::CommonProblemReport(problem_report) => {
                             if problem_report.from_thread(&self.thread_id) {
                                 return Some((uid, A2AMessage::CommonProblemReport(problem_report)));
@@ -127,23 +213,57 @@ impl HolderSM {
         None
     }
 
-    pub fn step(state: HolderState, source_id: String, thread_id: String) -> Self {
-        HolderSM { state, source_id, thread_id }
+    pub fn step(state: HolderState, source_id: String, thread_id: String, protocol: IssuanceProtocol, history: History) -> Self {
+        HolderSM { state, source_id, thread_id, protocol, history }
     }
 
     pub fn handle_message(self, cim: CredentialIssuanceMessage) -> VcxResult<HolderSM> {
         trace!("Holder::handle_message >>> cim: {:?}", cim);
 
-        let HolderSM { state, source_id, thread_id } = self;
+        let old_state = self.state();
+        let message_type = cim.as_ref().to_string();
+        let message_id = cim.message_id();
+
+        if let Some(ref message_id) = message_id {
+            if self.history.contains_message(&message_type, message_id) {
+                warn!("Holder::handle_message >>> message {} was already processed, it is ignored", message_id);
+                return Ok(self);
+            }
+        }
+
+        let HolderSM { state, source_id, thread_id, mut protocol, history } = self;
         let state = match state {
+            HolderState::Initial(state_data) => match cim {
+                CredentialIssuanceMessage::CredentialProposalSend(connection_handle) => {
+                    protocol = IssuanceProtocol::negotiate(connection_handle);
+                    connection::send_message(connection_handle, protocol.proposal_message(&state_data.proposal)?)?;
+                    HolderState::ProposalSent((state_data, connection_handle).into())
+                }
+                _ => {
+                    warn!("Holder-initiated Credential Issuance can only start with sending Credential Proposal");
+                    HolderState::Initial(state_data)
+                }
+            },
+            HolderState::ProposalSent(state_data) => match cim {
+                CredentialIssuanceMessage::CredentialOffer(offer) => {
+                    HolderState::OfferReceived((state_data, offer).into())
+                }
+                CredentialIssuanceMessage::ProblemReport(problem_report) => {
+                    HolderState::Finished((state_data, problem_report).into())
+                }
+                _ => {
+                    warn!("In this state Credential Issuance can accept only Credential Offer and Problem Report");
+                    HolderState::ProposalSent(state_data)
+                }
+            },
             HolderState::OfferReceived(state_data) => match cim {
                 CredentialIssuanceMessage::CredentialRequestSend(connection_handle) => {
-                    let request = _make_credential_request(connection_handle, &state_data.offer);
+                    let request = _make_credential_request(&protocol, connection_handle, &state_data.offer);
                     match request {
                         Ok((cred_request, req_meta, cred_def_json)) => {
                             let cred_request = cred_request
                                 .set_thread_id(&thread_id);
-                            connection::send_message(connection_handle, cred_request.to_a2a_message())?;
+                            connection::send_message(connection_handle, protocol.request_message(&cred_request)?)?;
                             HolderState::RequestSent((state_data, req_meta, cred_def_json, connection_handle).into())
                         }
                         Err(err) => {
@@ -162,12 +282,12 @@ impl HolderSM {
             },
             HolderState::RequestSent(state_data) => match cim {
                 CredentialIssuanceMessage::Credential(credential) => {
-                    let result = _store_credential(&credential, &state_data.req_meta, &state_data.cred_def_json);
+                    let result = _store_credential(&protocol, &credential, &state_data.req_meta, &state_data.cred_def_json);
                     match result {
                         Ok((cred_id, rev_reg_def_json)) => {
                             if credential.please_ack.is_some() {
                                 let ack = CredentialAck::create().set_thread_id(&thread_id);
-                                connection::send_message(state_data.connection_handle, A2AMessage::CredentialAck(ack))?;
+                                connection::send_message(state_data.connection_handle, protocol.ack_message(ack))?;
                             }
 
                             HolderState::Finished((state_data, cred_id, credential, rev_reg_def_json).into())
@@ -195,7 +315,12 @@ impl HolderSM {
                 HolderState::Finished(state_data)
             }
         };
-        Ok(HolderSM::step(state, source_id, thread_id))
+        let mut holder_sm = HolderSM::step(state, source_id, thread_id, protocol, history);
+        let transition = Transition::new(old_state, holder_sm.state(), &message_type)
+            .set_message_id(message_id)
+            .set_status(holder_sm.finished_status());
+        holder_sm.history.record(transition);
+        Ok(holder_sm)
     }
 
     pub fn credential_status(&self) -> u32 {
@@ -212,12 +337,19 @@ impl HolderSM {
         }
     }
 
+    fn finished_status(&self) -> Option<&Status> {
+        match self.state {
+            HolderState::Finished(ref state) => Some(&state.status),
+            _ => None
+        }
+    }
+
     pub fn get_credential(&self) -> VcxResult<(String, A2AMessage)> {
         match self.state {
             HolderState::Finished(ref state) => {
                 let cred_id = state.cred_id.clone().ok_or(VcxError::from_msg(VcxErrorKind::InvalidState, "Cannot get credential: Credential Id not found"))?;
                 let credential = state.credential.clone().ok_or(VcxError::from_msg(VcxErrorKind::InvalidState, "Cannot get credential: Credential not found"))?;
-                Ok((cred_id, credential.to_a2a_message()))
+                Ok((cred_id, self.protocol.credential_message(&credential)?))
             }
             _ => Err(VcxError::from_msg(VcxErrorKind::NotReady, "Cannot get credential: Credential Issuance is not finished yet"))
         }
@@ -236,48 +368,12 @@ impl HolderSM {
     }
 }
 
-fn _parse_cred_def_from_cred_offer(cred_offer: &str) -> VcxResult<String> {
-    trace!("Holder::_parse_cred_def_from_cred_offer >>> cred_offer: {:?}", cred_offer);
-
-    let parsed_offer: serde_json::Value = serde_json::from_str(cred_offer)
-        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Invalid Credential Offer Json: {:?}", err)))?;
-
-    let cred_def_id = parsed_offer["cred_def_id"].as_str()
-        .ok_or_else(|| VcxError::from_msg(VcxErrorKind::InvalidJson, "Invalid Credential Offer Json: cred_def_id not found"))?;
-
-    Ok(cred_def_id.to_string())
-}
-
-fn _parse_rev_reg_id_from_credential(credential: &str) -> VcxResult<Option<String>> {
-    trace!("Holder::_parse_rev_reg_id_from_credential >>>");
-
-    let parsed_credential: serde_json::Value = serde_json::from_str(credential)
-        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Invalid Credential Json: {}, err: {:?}", credential, err)))?;
-
-    let rev_reg_id = parsed_credential["rev_reg_id"].as_str().map(String::from);
-
-    Ok(rev_reg_id)
-}
-
-fn _store_credential(credential: &Credential,
+fn _store_credential(protocol: &IssuanceProtocol, credential: &Credential,
                      req_meta: &str, cred_def_json: &str) -> VcxResult<(String, Option<String>)> {
     trace!("Holder::_store_credential >>>");
 
     let credential_json = credential.credentials_attach.content()?;
-    let rev_reg_id = _parse_rev_reg_id_from_credential(&credential_json)?;
-    let rev_reg_def_json = if let Some(rev_reg_id) = rev_reg_id {
-        let (_, json) = anoncreds::get_rev_reg_def_json(&rev_reg_id)?;
-        Some(json)
-    } else {
-        None
-    };
-
-    let cred_id = libindy_prover_store_credential(None,
-                                                  req_meta,
-                                                  &credential_json,
-                                                  cred_def_json,
-                                                  rev_reg_def_json.as_ref().map(String::as_str))?;
-    Ok((cred_id, rev_reg_def_json))
+    protocol.handler().store_credential(&credential_json, req_meta, cred_def_json)
 }
 
 fn _delete_credential(cred_id: &str) -> VcxResult<()> {
@@ -286,22 +382,12 @@ fn _delete_credential(cred_id: &str) -> VcxResult<()> {
     libindy_prover_delete_credential(cred_id)
 }
 
-    pub fn create_credential_request(cred_def_id: &str, prover_did: &str, cred_offer: &str) -> VcxResult<(String, String, String, String)> {
-        let (cred_def_id, cred_def_json) = get_cred_def_json(&cred_def_id)?;
-
-        libindy_prover_create_credential_req(&prover_did,
-                                             &cred_offer,
-                                             &cred_def_json)
-            .map_err(|err| err.extend("Cannot create credential request")).map(|(s1, s2)| (s1, s2, cred_def_id, cred_def_json))
-    }
-
-fn _make_credential_request(conn_handle: u32, offer: &CredentialOffer) -> VcxResult<(CredentialRequest, String, String)> {
+fn _make_credential_request(protocol: &IssuanceProtocol, conn_handle: u32, offer: &CredentialOffer) -> VcxResult<(CredentialRequest, String, String)> {
     trace!("Holder::_make_credential_request >>> conn_handle: {:?}, offer: {:?}", conn_handle, offer);
 
     let my_did = connection::get_pw_did(conn_handle)?;
     let cred_offer = offer.offers_attach.content()?;
-    let cred_def_id = _parse_cred_def_from_cred_offer(&cred_offer)?;
-    let (req, req_meta, _cred_def_id, cred_def_json) = create_credential_request(&cred_def_id, &my_did, &cred_offer)?;
+    let (req, req_meta, cred_def_json) = protocol.handler().create_request(&my_did, &cred_offer)?;
     Ok((CredentialRequest::create().set_requests_attach(req)?, req_meta, cred_def_json))
 }
 
@@ -314,6 +400,9 @@ mod test {
     use aries::messages::issuance::credential_proposal::tests::_credential_proposal;
     use aries::messages::issuance::credential_request::tests::_credential_request;
     use aries::messages::issuance::test::{_ack, _problem_report};
+    use aries::messages::issuance::v20::CredentialFormat;
+    use aries::messages::issuance::v20::credential::CredentialV2;
+    use aries::messages::issuance::v20::credential_offer::CredentialOfferV2;
     use aries::test::source_id;
 
     use super::*;
@@ -322,7 +411,16 @@ mod test {
         HolderSM::new(_credential_offer(), source_id())
     }
 
+    fn _holder_sm_with_proposal() -> HolderSM {
+        HolderSM::new_with_proposal(_credential_proposal(), source_id())
+    }
+
     impl HolderSM {
+        fn to_proposal_sent_state(mut self) -> HolderSM {
+            self = self.handle_message(CredentialIssuanceMessage::CredentialProposalSend(mock_connection())).unwrap();
+            self
+        }
+
         fn to_request_sent_state(mut self) -> HolderSM {
             self = self.handle_message(CredentialIssuanceMessage::CredentialRequestSend(mock_connection())).unwrap();
             self
@@ -348,6 +446,18 @@ mod test {
             assert_match!(HolderState::OfferReceived(_), holder_sm.state);
             assert_eq!(source_id(), holder_sm.get_source_id());
         }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_holder_new_with_proposal() {
+            let _setup = SetupAriesMocks::init();
+
+            let proposal = _credential_proposal();
+            let holder_sm = HolderSM::new_with_proposal(proposal.clone(), source_id());
+
+            assert_match!(HolderState::Initial(_), holder_sm.state);
+            assert_eq!(proposal.id.0, holder_sm.thread_id);
+        }
     }
 
     mod step {
@@ -362,6 +472,75 @@ mod test {
             assert_match!(HolderState::OfferReceived(_), holder_sm.state);
         }
 
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_holder_handle_credential_proposal_send_message_from_initial_state() {
+            let _setup = SetupAriesMocks::init();
+
+            let mut holder_sm = _holder_sm_with_proposal();
+            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::CredentialProposalSend(mock_connection())).unwrap();
+
+            assert_match!(HolderState::ProposalSent(_), holder_sm.state);
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_holder_handle_other_messages_from_initial_state() {
+            let _setup = SetupAriesMocks::init();
+
+            let mut holder_sm = _holder_sm_with_proposal();
+
+            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::CredentialRequestSend(mock_connection())).unwrap();
+            assert_match!(HolderState::Initial(_), holder_sm.state);
+
+            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::CredentialOffer(_credential_offer())).unwrap();
+            assert_match!(HolderState::Initial(_), holder_sm.state);
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_holder_handle_credential_offer_message_from_proposal_sent_state() {
+            let _setup = SetupAriesMocks::init();
+
+            let mut holder_sm = _holder_sm_with_proposal().to_proposal_sent_state();
+            let offer = _credential_offer().set_thread_id(&holder_sm.thread_id);
+            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::CredentialOffer(offer)).unwrap();
+            assert_match!(HolderState::OfferReceived(_), holder_sm.state);
+
+            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::CredentialRequestSend(mock_connection())).unwrap();
+            assert_match!(HolderState::RequestSent(_), holder_sm.state);
+
+            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::Credential(_credential())).unwrap();
+            assert_match!(HolderState::Finished(_), holder_sm.state);
+            assert_eq!(Status::Success.code(), holder_sm.credential_status());
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_holder_handle_problem_report_from_proposal_sent_state() {
+            let _setup = SetupAriesMocks::init();
+
+            let mut holder_sm = _holder_sm_with_proposal().to_proposal_sent_state();
+            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::ProblemReport(_problem_report())).unwrap();
+
+            assert_match!(HolderState::Finished(_), holder_sm.state);
+            assert_eq!(Status::Failed(ProblemReport::default()).code(), holder_sm.credential_status());
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_holder_handle_other_messages_from_proposal_sent_state() {
+            let _setup = SetupAriesMocks::init();
+
+            let mut holder_sm = _holder_sm_with_proposal().to_proposal_sent_state();
+
+            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::Credential(_credential())).unwrap();
+            assert_match!(HolderState::ProposalSent(_), holder_sm.state);
+
+            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::CredentialRequestSend(mock_connection())).unwrap();
+            assert_match!(HolderState::ProposalSent(_), holder_sm.state);
+        }
+
         #[test]
         #[cfg(feature = "general_test")]
         fn test_issuer_handle_credential_request_sent_message_from_offer_received_state() {
@@ -414,6 +593,36 @@ mod test {
             assert_eq!(Status::Success.code(), holder_sm.credential_status());
         }
 
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_holder_ignores_already_processed_credential_message() {
+            let _setup = SetupAriesMocks::init();
+
+            let mut holder_sm = _holder_sm().to_request_sent_state();
+            holder_sm.history.record(Transition::new(VcxStateType::VcxStateOfferSent as u32, VcxStateType::VcxStateAccepted as u32, "Credential")
+                .set_message_id(Some(_credential().id.0)));
+
+            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::Credential(_credential())).unwrap();
+
+            assert_match!(HolderState::RequestSent(_), holder_sm.state);
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_issuer_handle_credential_message_from_request_sent_state_for_v2_protocol() {
+            let _setup = SetupAriesMocks::init();
+
+            let mut holder_sm = HolderSM::new_with_protocol(_credential_offer(), IssuanceProtocol::V2(CredentialFormat::Indy), source_id());
+            holder_sm = holder_sm.handle_message(CredentialIssuanceMessage::CredentialRequestSend(mock_connection())).unwrap();
+
+            let credential: A2AMessage = CredentialV2::from_v1(&_credential(), &CredentialFormat::Indy).unwrap().to_a2a_message();
+            holder_sm = holder_sm.handle_message(credential.into()).unwrap();
+
+            assert_match!(HolderState::Finished(_), holder_sm.state);
+            assert_eq!(Status::Success.code(), holder_sm.credential_status());
+            assert_match!(A2AMessage::CredentialV2(_), holder_sm.get_credential().unwrap().1);
+        }
+
         #[test]
         #[cfg(feature = "general_test")]
         fn test_issuer_handle_invalid_credential_message_from_request_sent_state() {
@@ -501,6 +710,60 @@ mod test {
             }
         }
 
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_holder_find_message_to_handle_from_proposal_sent_state() {
+            let _setup = SetupAriesMocks::init();
+
+            let holder = _holder_sm_with_proposal().to_proposal_sent_state();
+            let thread_id = holder.thread_id.clone();
+
+            // CredentialOffer
+            {
+                let messages = map!(
+                    "key_1".to_string() => A2AMessage::CredentialProposal(_credential_proposal()),
+                    "key_2".to_string() => A2AMessage::CredentialOffer(_credential_offer().set_thread_id(&thread_id))
+                );
+
+                let (uid, message) = holder.find_message_to_handle(messages).unwrap();
+                assert_eq!("key_2", uid);
+                assert_match!(A2AMessage::CredentialOffer(_), message);
+            }
+
+            // Credential Offer 2.0
+            {
+                let offer = CredentialOfferV2::from_v1(&_credential_offer().set_thread_id(&thread_id), &CredentialFormat::Indy).unwrap();
+                let messages = map!(
+                    "key_1".to_string() => A2AMessage::CredentialOfferV2(offer)
+                );
+
+                let (uid, message) = holder.find_message_to_handle(messages).unwrap();
+                assert_eq!("key_1", uid);
+                assert_match!(A2AMessage::CredentialOfferV2(_), message);
+            }
+
+            // Problem Report
+            {
+                let messages = map!(
+                    "key_1".to_string() => A2AMessage::CommonProblemReport(_problem_report().set_thread_id(&thread_id))
+                );
+
+                let (uid, message) = holder.find_message_to_handle(messages).unwrap();
+                assert_eq!("key_1", uid);
+                assert_match!(A2AMessage::CommonProblemReport(_), message);
+            }
+
+            // Offer from different thread
+            {
+                let messages = map!(
+                    "key_1".to_string() => A2AMessage::CredentialOffer(_credential_offer().set_thread_id("other")),
+                    "key_2".to_string() => A2AMessage::Credential(_credential())
+                );
+
+                assert!(holder.find_message_to_handle(messages).is_none());
+            }
+        }
+
         #[test]
         #[cfg(feature = "general_test")]
         fn test_holder_find_message_to_handle_from_request_sent_state() {
@@ -522,6 +785,18 @@ mod test {
                 assert_match!(A2AMessage::Credential(_), message);
             }
 
+            // Credential 2.0
+            {
+                let messages = map!(
+                    "key_1".to_string() => A2AMessage::CredentialOffer(_credential_offer()),
+                    "key_2".to_string() => CredentialV2::from_v1(&_credential(), &CredentialFormat::Indy).unwrap().to_a2a_message()
+                );
+
+                let (uid, message) = holder.find_message_to_handle(messages).unwrap();
+                assert_eq!("key_2", uid);
+                assert_match!(A2AMessage::CredentialV2(_), message);
+            }
+
             // Problem Report
             {
                 let messages = map!(
@@ -594,9 +869,31 @@ mod test {
         fn test_get_state() {
             let _setup = SetupAriesMocks::init();
 
+            assert_eq!(VcxStateType::VcxStateInitialized as u32, _holder_sm_with_proposal().state());
+            assert_eq!(VcxStateType::VcxStateProposalSent as u32, _holder_sm_with_proposal().to_proposal_sent_state().state());
             assert_eq!(VcxStateType::VcxStateRequestReceived as u32, _holder_sm().state());
             assert_eq!(VcxStateType::VcxStateOfferSent as u32, _holder_sm().to_request_sent_state().state());
             assert_eq!(VcxStateType::VcxStateAccepted as u32, _holder_sm().to_finished_state().state());
         }
     }
+    mod history {
+        use history::Outcome;
+
+        use super::*;
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_history_of_finished_exchange() {
+            let _setup = SetupAriesMocks::init();
+
+            let holder_sm = _holder_sm().to_finished_state();
+
+            let transitions = holder_sm.history().transitions();
+            assert_eq!(2, transitions.len());
+            assert_eq!("CredentialRequestSend", transitions[0].message_type);
+            assert_eq!("Credential", transitions[1].message_type);
+            assert_eq!(Some(_credential().id.0), transitions[1].message_id);
+            assert_eq!(Outcome::Success, transitions[1].outcome);
+        }
+    }
 }
@@ -0,0 +1,25 @@
+use aries::handlers::issuance::holder::states::proposal_sent::ProposalSentState;
+use aries::messages::issuance::credential_proposal::CredentialProposal;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct InitialHolderState {
+    pub proposal: CredentialProposal
+}
+
+impl InitialHolderState {
+    pub fn new(proposal: CredentialProposal) -> Self {
+        InitialHolderState {
+            proposal,
+        }
+    }
+}
+
+impl From<(InitialHolderState, u32)> for ProposalSentState {
+    fn from((state, connection_handle): (InitialHolderState, u32)) -> Self {
+        trace!("SM is now in ProposalSent state");
+        ProposalSentState {
+            proposal: state.proposal,
+            connection_handle,
+        }
+    }
+}
@@ -1,3 +1,5 @@
 pub(super) mod finished;
+pub(super) mod initial;
 pub(super) mod offer_received;
-pub(super) mod request_sent;
\ No newline at end of file
+pub(super) mod proposal_sent;
+pub(super) mod request_sent;
@@ -0,0 +1,31 @@
+use aries::handlers::issuance::holder::states::finished::FinishedHolderState;
+use aries::handlers::issuance::holder::states::offer_received::OfferReceivedState;
+use aries::messages::error::ProblemReport;
+use aries::messages::issuance::credential_offer::CredentialOffer;
+use aries::messages::issuance::credential_proposal::CredentialProposal;
+use aries::messages::status::Status;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProposalSentState {
+    pub proposal: CredentialProposal,
+    pub connection_handle: u32,
+}
+
+impl From<(ProposalSentState, CredentialOffer)> for OfferReceivedState {
+    fn from((_state, offer): (ProposalSentState, CredentialOffer)) -> Self {
+        trace!("SM is now in OfferReceived state");
+        OfferReceivedState::new(offer)
+    }
+}
+
+impl From<(ProposalSentState, ProblemReport)> for FinishedHolderState {
+    fn from((_state, problem_report): (ProposalSentState, ProblemReport)) -> Self {
+        trace!("SM is now in Finished state");
+        FinishedHolderState {
+            cred_id: None,
+            credential: None,
+            status: Status::Failed(problem_report),
+            rev_reg_def_json: None,
+        }
+    }
+}
@@ -1,6 +1,8 @@
 use error::prelude::*;
+use history::History;
 use aries::handlers::issuance::issuer::state_machine::IssuerSM;
 use aries::handlers::issuance::messages::CredentialIssuanceMessage;
+use aries::handlers::issuance::protocol::IssuanceProtocol;
 use aries::messages::a2a::A2AMessage;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -19,6 +21,31 @@ impl Issuer {
         Ok(Issuer { issuer_sm })
     }
 
+    pub fn create_from_proposal(cred_def_handle: u32, proposal: A2AMessage, connection_handle: u32, source_id: &str) -> VcxResult<Issuer> {
+        trace!("Issuer::create_from_proposal >>> cred_def_handle: {:?}, proposal: {:?}, source_id: {:?}", cred_def_handle, proposal, source_id);
+
+        let (proposal, protocol) = match proposal {
+            A2AMessage::CredentialProposal(proposal) => (proposal, IssuanceProtocol::V1),
+            A2AMessage::CredentialProposalV2(proposal) => {
+                let format = proposal.format()
+                    .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Credential Proposal does not contain attachment of supported format: {:?}", proposal.formats)))?;
+                (proposal.into(), IssuanceProtocol::V2(format))
+            }
+            message => return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Message is not a Credential Proposal: {:?}", message)))
+        };
+
+        let cred_def_id = ::credential_def::get_cred_def_id(cred_def_handle)?;
+        if !proposal.cred_def_id.is_empty() && proposal.cred_def_id != cred_def_id {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidCredDefHandle,
+                                          format!("Credential Proposal refers to Credential Definition {:?}, not {:?}", proposal.cred_def_id, cred_def_id)));
+        }
+
+        let rev_reg_id = ::credential_def::get_rev_reg_id(cred_def_handle)?;
+        let tails_file = ::credential_def::get_tails_file(cred_def_handle)?;
+        let issuer_sm = IssuerSM::from_proposal(&cred_def_id, rev_reg_id, tails_file, proposal, protocol, connection_handle, source_id)?;
+        Ok(Issuer { issuer_sm })
+    }
+
     pub fn send_credential_offer(&mut self, connection_handle: u32, comment: Option<String>) -> VcxResult<()> {
         self.step(CredentialIssuanceMessage::CredentialInit(connection_handle, comment))
     }
@@ -27,6 +54,18 @@ impl Issuer {
         self.step(CredentialIssuanceMessage::CredentialSend(connection_handle))
     }
 
+    pub fn get_proposal(&self) -> VcxResult<A2AMessage> {
+        self.issuer_sm.get_proposal()
+    }
+
+    pub fn send_counter_offer(&mut self, connection_handle: u32, comment: Option<String>) -> VcxResult<()> {
+        self.step(CredentialIssuanceMessage::CredentialCounterOfferSend(connection_handle, comment))
+    }
+
+    pub fn decline_proposal(&mut self, connection_handle: u32, reason: &str) -> VcxResult<()> {
+        self.step(CredentialIssuanceMessage::CredentialProposalDecline(connection_handle, reason.to_string()))
+    }
+
     pub fn get_state(&self) -> VcxResult<u32> {
         Ok(self.issuer_sm.state())
     }
@@ -35,6 +74,18 @@ impl Issuer {
         Ok(self.issuer_sm.get_source_id())
     }
 
+    pub fn get_thread_id(&self) -> String {
+        self.issuer_sm.thread_id()
+    }
+
+    pub fn get_connection_handle(&self) -> Option<u32> {
+        self.issuer_sm.connection_handle()
+    }
+
+    pub fn get_history(&self) -> &History {
+        self.issuer_sm.history()
+    }
+
     pub fn revoke_credential(&self, publish: bool) -> VcxResult<()> {
         self.issuer_sm.revoke(publish)
     }
@@ -54,6 +105,18 @@ impl Issuer {
         }
     }
 
+    pub fn is_expired(&self) -> bool {
+        self.issuer_sm.is_expired()
+    }
+
+    /// Abandons the exchange if the offer expired. Returns whether it was abandoned.
+    pub fn expire(&mut self) -> VcxResult<bool> {
+        if !self.issuer_sm.is_expired() { return Ok(false); }
+
+        self.step(CredentialIssuanceMessage::Expire)?;
+        Ok(true)
+    }
+
     pub fn get_credential_status(&self) -> VcxResult<u32> {
         Ok(self.issuer_sm.credential_status())
     }
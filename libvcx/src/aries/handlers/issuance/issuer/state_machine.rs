@@ -4,32 +4,44 @@ use api::VcxStateType;
 use connection::{get_messages, send_message};
 use connection;
 use error::{VcxError, VcxErrorKind, VcxResult};
-use utils::libindy::anoncreds::{self, libindy_issuer_create_credential_offer};
+use history::{History, Transition};
+use utils::libindy::anoncreds;
 use aries::handlers::issuance::messages::CredentialIssuanceMessage;
+use aries::handlers::issuance::protocol::IssuanceProtocol;
 use aries::messages::a2a::A2AMessage;
+use aries::messages::a2a::message_family::MessageFamilies;
 use aries::messages::error::ProblemReport;
+use aries::messages::issuance::CredentialPreviewData;
 use aries::messages::issuance::credential::Credential;
 use aries::messages::issuance::credential_offer::CredentialOffer;
+use aries::messages::issuance::credential_proposal::CredentialProposal;
 use aries::messages::issuance::credential_request::CredentialRequest;
 use aries::messages::mime_type::MimeType;
 use aries::messages::status::Status;
+use aries::messages::timing;
 use aries::handlers::issuance::issuer::states::initial::InitialState;
 use aries::handlers::issuance::issuer::states::offer_sent::OfferSentState;
+use aries::handlers::issuance::issuer::states::proposal_received::ProposalReceivedState;
 use aries::handlers::issuance::issuer::states::requested_received::RequestReceivedState;
 use aries::handlers::issuance::issuer::states::credential_sent::CredentialSentState;
 use aries::handlers::issuance::issuer::states::finished::FinishedState;
-use aries::handlers::issuance::issuer::utils::encode_attributes;
 
 // Possible Transitions:
+// (none) -> ProposalReceived (exchange started by the holder)
 // Initial -> OfferSent
 // Initial -> Finished
-// OfferSent -> CredentialSent
-// OfferSent -> Finished
+// OfferSent -> RequestReceived
+// OfferSent -> ProposalReceived
+// OfferSent -> Finished (also when the offer expires)
+// ProposalReceived -> OfferSent
+// ProposalReceived -> Finished
+// RequestReceived -> Finished
 // CredentialSent -> Finished
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub enum IssuerState {
     Initial(InitialState),
     OfferSent(OfferSentState),
+    ProposalReceived(ProposalReceivedState),
     RequestReceived(RequestReceivedState),
     CredentialSent(CredentialSentState),
     Finished(FinishedState),
@@ -47,6 +59,7 @@ impl IssuerState {
         match self {
             IssuerState::Initial(_) => 0,
             IssuerState::OfferSent(state) => state.connection_handle,
+            IssuerState::ProposalReceived(state) => state.connection_handle,
             IssuerState::RequestReceived(state) => state.connection_handle,
             IssuerState::CredentialSent(state) => state.connection_handle,
             IssuerState::Finished(_) => 0
@@ -56,6 +69,7 @@ impl IssuerState {
     pub fn set_connection_handle(&mut self, connection_handle: u32) {
         match self {
             IssuerState::OfferSent(state) => { state.connection_handle = connection_handle; },
+            IssuerState::ProposalReceived(state) => { state.connection_handle = connection_handle; },
             IssuerState::RequestReceived(state) => { state.connection_handle = connection_handle; },
             IssuerState::CredentialSent(state) => { state.connection_handle = connection_handle; },
             _ => {}
@@ -66,6 +80,7 @@ impl IssuerState {
         match self {
             IssuerState::Initial(_) => String::new(),
             IssuerState::OfferSent(state) => state.thread_id.clone(),
+            IssuerState::ProposalReceived(state) => state.thread_id.clone(),
             IssuerState::RequestReceived(state) => state.thread_id.clone(),
             IssuerState::CredentialSent(state) => state.thread_id.clone(),
             IssuerState::Finished(state) => state.thread_id.clone(),
@@ -77,6 +92,10 @@ impl IssuerState {
 pub struct IssuerSM {
     state: IssuerState,
     source_id: String,
+    #[serde(default)]
+    protocol: IssuanceProtocol,
+    #[serde(default)]
+    history: History,
 }
 
 impl IssuerSM {
@@ -84,17 +103,52 @@ impl IssuerSM {
         IssuerSM {
             state: IssuerState::Initial(InitialState::new(cred_def_id, credential_data, rev_reg_id, tails_file)),
             source_id: source_id.to_string(),
+            protocol: IssuanceProtocol::default(),
+            history: History::default(),
         }
     }
 
+    /// Starts the exchange from a Credential Proposal the holder sent without an offer.
+    pub fn from_proposal(cred_def_id: &str, rev_reg_id: Option<String>, tails_file: Option<String>, proposal: CredentialProposal,
+                         protocol: IssuanceProtocol, connection_handle: u32, source_id: &str) -> VcxResult<Self> {
+        let offer = protocol.handler().create_offer(cred_def_id)?;
+        let cred_data = _credential_data_from_preview(&proposal.credential_proposal)?;
+        let thread_id = proposal.thread.as_ref().and_then(|thread| thread.thid.clone())
+            .unwrap_or(proposal.id.0.clone());
+        let message_id = Some(proposal.id.0.clone());
+
+        let state = IssuerState::ProposalReceived(ProposalReceivedState { offer, cred_data, rev_reg_id, tails_file, connection_handle, proposal, thread_id });
+        let mut issuer_sm = IssuerSM::step(state, source_id.to_string(), protocol, History::default());
+        issuer_sm.history.record(Transition::new(VcxStateType::VcxStateNone as u32, issuer_sm.state(), "CredentialProposal").set_message_id(message_id));
+        Ok(issuer_sm)
+    }
+
     pub fn get_source_id(&self) -> String {
         self.source_id.clone()
     }
 
-    pub fn step(state: IssuerState, source_id: String) -> Self {
+    pub fn thread_id(&self) -> String {
+        self.state.thread_id()
+    }
+
+    /// Connection the credential is exchanged over, not known before the offer is sent or the proposal received.
+    pub fn connection_handle(&self) -> Option<u32> {
+        match self.state.get_connection_handle() {
+            0 => None,
+            connection_handle => Some(connection_handle)
+        }
+    }
+
+    pub fn history(&self) -> &History {
+        &self.history
+    }
+
+    pub fn step(state: IssuerState, source_id: String, protocol: IssuanceProtocol, history: History) -> Self {
         IssuerSM {
             state,
             source_id,
+            protocol,
+            history,
         }
     }
 
@@ -135,14 +189,24 @@ impl IssuerSM {
 
         match self.find_message_to_handle(messages) {
             Some((uid, msg)) => {
-                let state = self.handle_message(msg.into())?;
+                let state = self.handle_message(msg.clone().into())?;
+                connection::record_processed(conn_handle, &msg)?;
                 connection::update_message_status(conn_handle, uid)?;
                 Ok(state)
             }
+            None if self.is_expired() => self.handle_message(CredentialIssuanceMessage::Expire),
             None => Ok(self)
         }
     }
 
+    /// Whether the holder did not answer the offer before its `~timing.expires_time`.
+    pub fn is_expired(&self) -> bool {
+        match self.state {
+            IssuerState::OfferSent(ref state) => state.expires_time.as_ref().map(|expires_time| timing::is_expired(expires_time)).unwrap_or(false),
+            _ => false
+        }
+    }
+
     fn find_message_to_handle(&self, messages: HashMap<String, A2AMessage>) -> Option<(String, A2AMessage)> {
         trace!("Issuer::find_message_to_handle >>> messages: {:?}", messages);
 
@@ -158,6 +222,11 @@ impl IssuerSM {
                                 return Some((uid, A2AMessage::CredentialRequest(credential)));
                             }
                         }
+                        A2AMessage::CredentialRequestV2(credential) => {
+                            if credential.from_thread(&self.state.thread_id()) {
+                                return Some((uid, A2AMessage::CredentialRequestV2(credential)));
+                            }
+                        }
                         A2AMessage::CredentialProposal(credential_proposal) => {
                             if let Some(ref thread) = credential_proposal.thread {
                                 if thread.is_reply(&self.state.thread_id()) {
@@ -165,6 +234,13 @@ impl IssuerSM {
                                 }
                             }
                         }
+                        A2AMessage::CredentialProposalV2(credential_proposal) => {
+                            if let Some(ref thread) = credential_proposal.thread {
+                                if thread.is_reply(&self.state.thread_id()) {
+                                    return Some((uid, A2AMessage::CredentialProposalV2(credential_proposal)));
+                                }
+                            }
+                        }
                         A2AMessage::CommonProblemReport(problem_report) => {
                             if problem_report.from_thread(&self.state.thread_id()) {
                                 return Some((uid, A2AMessage::CommonProblemReport(problem_report)));
@@ -173,12 +249,15 @@ impl IssuerSM {
                         _ => {}
                     }
                 }
+                IssuerState::ProposalReceived(_) => {
+                    // do not process messages
+                }
                 IssuerState::RequestReceived(_) => {
                     // do not process messages
                 }
                 IssuerState::CredentialSent(_) => {
                     match message {
-                        A2AMessage::Ack(ack) | A2AMessage::CredentialAck(ack) => {
+                        A2AMessage::Ack(ack) | A2AMessage::CredentialAck(ack) | A2AMessage::CredentialAckV2(ack) => {
                             if ack.from_thread(&self.state.thread_id()) {
                                 return Some((uid, A2AMessage::CredentialAck(ack)));
                             }
@@ -204,6 +283,7 @@ impl IssuerSM {
         match self.state {
             IssuerState::Initial(_) => VcxStateType::VcxStateInitialized as u32,
             IssuerState::OfferSent(_) => VcxStateType::VcxStateOfferSent as u32,
+            IssuerState::ProposalReceived(_) => VcxStateType::VcxStateProposalReceived as u32,
             IssuerState::RequestReceived(_) => VcxStateType::VcxStateRequestReceived as u32,
             IssuerState::CredentialSent(_) => VcxStateType::VcxStateAccepted as u32,
             IssuerState::Finished(ref status) => {
@@ -218,17 +298,31 @@ impl IssuerSM {
     pub fn handle_message(self, cim: CredentialIssuanceMessage) -> VcxResult<IssuerSM> {
         trace!("IssuerSM::handle_message >>> cim: {:?}", cim);
 
-        let IssuerSM { state, source_id } = self;
+        let old_state = self.state();
+        let message_type = cim.as_ref().to_string();
+        let message_id = cim.message_id();
+
+        if let Some(ref message_id) = message_id {
+            if self.history.contains_message(&message_type, message_id) {
+                warn!("IssuerSM::handle_message >>> message {} was already processed, it is ignored", message_id);
+                return Ok(self);
+            }
+        }
+
+        let IssuerSM { state, source_id, mut protocol, history } = self;
         let state = match state {
             IssuerState::Initial(state_data) => match cim {
                 CredentialIssuanceMessage::CredentialInit(connection_handle, comment) => {
-                    let cred_offer = libindy_issuer_create_credential_offer(&state_data.cred_def_id)?;
+                    protocol = IssuanceProtocol::negotiate(connection_handle);
+                    let cred_offer = protocol.handler().create_offer(&state_data.cred_def_id)?;
                     let cred_offer_msg = CredentialOffer::create()
                         .set_offers_attach(&cred_offer)?
-                        .set_comment(comment);
+                        .set_comment(comment)
+                        .set_expires_time(timing::protocol_expires_time(&MessageFamilies::CredentialIssuance));
                     let cred_offer_msg = _append_credential_preview(cred_offer_msg, &state_data.credential_json)?;
-                    send_message(connection_handle, cred_offer_msg.to_a2a_message())?;
-                    IssuerState::OfferSent((state_data, cred_offer, connection_handle, cred_offer_msg.id).into())
+                    send_message(connection_handle, protocol.offer_message(&cred_offer_msg)?)?;
+                    let expires_time = cred_offer_msg.expires_time().map(String::from);
+                    IssuerState::OfferSent((state_data, cred_offer, connection_handle, cred_offer_msg.id, expires_time).into())
                 }
                 _ => {
                     warn!("Credential Issuance can only start on issuer side with init");
@@ -239,15 +333,21 @@ impl IssuerSM {
                 CredentialIssuanceMessage::CredentialRequest(request) => {
                     IssuerState::RequestReceived((state_data, request).into())
                 }
-                CredentialIssuanceMessage::CredentialProposal(_) => {
+                CredentialIssuanceMessage::CredentialProposal(proposal) => {
+                    IssuerState::ProposalReceived((state_data, proposal).into())
+                }
+                CredentialIssuanceMessage::ProblemReport(problem_report) => {
+                    IssuerState::Finished((state_data, problem_report).into())
+                }
+                CredentialIssuanceMessage::Expire => {
                     let problem_report = ProblemReport::create()
-                        .set_comment(String::from("CredentialProposal is not supported"))
+                        .set_comment(String::from("Credential Offer expired"))
                         .set_thread_id(&state_data.thread_id);
 
-                    send_message(state_data.connection_handle, problem_report.to_a2a_message())?;
-                    IssuerState::Finished((state_data, problem_report).into())
-                }
-                CredentialIssuanceMessage::ProblemReport(problem_report) => {
+                    // the exchange is abandoned even if the holder cannot be notified
+                    if let Err(err) = send_message(state_data.connection_handle, problem_report.to_a2a_message()) {
+                        warn!("Cannot notify holder about expired Credential Offer: {}", err);
+                    }
                     IssuerState::Finished((state_data, problem_report).into())
                 }
                 _ => {
@@ -255,13 +355,39 @@ impl IssuerSM {
                     IssuerState::OfferSent(state_data)
                 }
             },
+            IssuerState::ProposalReceived(state_data) => match cim {
+                CredentialIssuanceMessage::CredentialCounterOfferSend(connection_handle, comment) => {
+                    let cred_data = _credential_data_from_preview(&state_data.proposal.credential_proposal)?;
+                    let cred_offer_msg = CredentialOffer::create()
+                        .set_offers_attach(&state_data.offer)?
+                        .set_comment(comment)
+                        .set_thread_id(&state_data.thread_id)
+                        .set_expires_time(timing::protocol_expires_time(&MessageFamilies::CredentialIssuance));
+                    let cred_offer_msg = _append_credential_preview(cred_offer_msg, &cred_data)?;
+                    send_message(connection_handle, protocol.offer_message(&cred_offer_msg)?)?;
+                    let expires_time = cred_offer_msg.expires_time().map(String::from);
+                    IssuerState::OfferSent((state_data, cred_data, expires_time).into())
+                }
+                CredentialIssuanceMessage::CredentialProposalDecline(connection_handle, reason) => {
+                    let problem_report = ProblemReport::create()
+                        .set_comment(reason)
+                        .set_thread_id(&state_data.thread_id);
+
+                    send_message(connection_handle, problem_report.to_a2a_message())?;
+                    IssuerState::Finished((state_data, problem_report).into())
+                }
+                _ => {
+                    warn!("In this state Credential Issuance can only send Counter Offer or decline Proposal");
+                    IssuerState::ProposalReceived(state_data)
+                }
+            },
             IssuerState::RequestReceived(state_data) => match cim {
                 CredentialIssuanceMessage::CredentialSend(connection_handle) => {
-                    let credential_msg = _create_credential(&state_data.request, &state_data.rev_reg_id, &state_data.tails_file, &state_data.offer, &state_data.cred_data);
+                    let credential_msg = _create_credential(&protocol, &state_data.request, &state_data.rev_reg_id, &state_data.tails_file, &state_data.offer, &state_data.cred_data);
                     match credential_msg {
                         Ok((credential_msg, cred_rev_id)) => {
                             let credential_msg = credential_msg.set_thread_id(&state_data.thread_id);
-                            send_message(connection_handle, credential_msg.to_a2a_message())?;
+                            send_message(connection_handle, protocol.credential_message(&credential_msg)?)?;
                             IssuerState::Finished((state_data, cred_rev_id).into())
                         }
                         Err(err) => {
@@ -299,7 +425,19 @@ impl IssuerSM {
             }
         };
 
-        Ok(IssuerSM::step(state, source_id))
+        let mut issuer_sm = IssuerSM::step(state, source_id, protocol, history);
+        let transition = Transition::new(old_state, issuer_sm.state(), &message_type)
+            .set_message_id(message_id)
+            .set_status(issuer_sm.finished_status());
+        issuer_sm.history.record(transition);
+        Ok(issuer_sm)
+    }
+
+    pub fn get_proposal(&self) -> VcxResult<A2AMessage> {
+        match self.state {
+            IssuerState::ProposalReceived(ref state) => self.protocol.proposal_message(&state.proposal),
+            _ => Err(VcxError::from_msg(VcxErrorKind::NotReady, "Credential Proposal is not received"))
+        }
     }
 
     pub fn credential_status(&self) -> u32 {
@@ -317,6 +455,13 @@ impl IssuerSM {
             _ => false
         }
     }
+
+    fn finished_status(&self) -> Option<&Status> {
+        match self.state {
+            IssuerState::Finished(ref state) => Some(&state.status),
+            _ => None
+        }
+    }
 }
 
 
@@ -342,18 +487,29 @@ fn _append_credential_preview(cred_offer_msg: CredentialOffer, credential_json:
     Ok(new_offer)
 }
 
-fn _create_credential(request: &CredentialRequest, rev_reg_id: &Option<String>, tails_file: &Option<String>, offer: &str, cred_data: &str) -> VcxResult<(Credential, Option<String>)> {
+/// Converts proposed preview into the credential data json the offer is created from.
+fn _credential_data_from_preview(preview: &CredentialPreviewData) -> VcxResult<String> {
+    let cred_data: serde_json::Map<String, serde_json::Value> = preview.attributes.iter()
+        .map(|attribute| (attribute.name.clone(), serde_json::Value::String(attribute.value.clone())))
+        .collect();
+
+    if cred_data.is_empty() {
+        return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, "Credential Proposal does not contain any attribute"));
+    }
+
+    Ok(serde_json::Value::Object(cred_data).to_string())
+}
+
+fn _create_credential(protocol: &IssuanceProtocol, request: &CredentialRequest, rev_reg_id: &Option<String>, tails_file: &Option<String>, offer: &str, cred_data: &str) -> VcxResult<(Credential, Option<String>)> {
     trace!("Issuer::_create_credential >>> request: {:?}, rev_reg_id: {:?}, tails_file: {:?}, offer: {:?}, cred_data: {:?}", request, rev_reg_id, tails_file, offer, cred_data);
 
     let request = &request.requests_attach.content()?;
 
-    let cred_data = encode_attributes(cred_data)?;
-
-    let (ser_credential, cred_rev_id, _) = anoncreds::libindy_issuer_create_credential(offer,
-                                                                                       &request,
-                                                                                       &cred_data,
-                                                                                       rev_reg_id.clone(),
-                                                                                       tails_file.clone())?;
+    let (ser_credential, cred_rev_id) = protocol.handler().create_credential(offer,
+                                                                            &request,
+                                                                            cred_data,
+                                                                            rev_reg_id.clone(),
+                                                                            tails_file.clone())?;
     let credential = Credential::create().set_credential(ser_credential)?;
 
     Ok((credential, cred_rev_id))
@@ -364,10 +520,13 @@ pub mod test {
     use utils::devsetup::SetupAriesMocks;
     use aries::handlers::connection::tests::mock_connection;
     use aries::messages::issuance::credential::tests::_credential;
-    use aries::messages::issuance::credential_offer::tests::_credential_offer;
+    use aries::messages::issuance::credential_offer::tests::{_credential_offer, _value};
+    use aries::messages::issuance::credential_proposal::CredentialProposal;
     use aries::messages::issuance::credential_proposal::tests::_credential_proposal;
     use aries::messages::issuance::credential_request::tests::_credential_request;
     use aries::messages::issuance::test::{_ack, _problem_report};
+    use aries::messages::issuance::v20::CredentialFormat;
+    use aries::messages::issuance::v20::credential_request::CredentialRequestV2;
     use aries::test::source_id;
 
     use super::*;
@@ -382,6 +541,12 @@ pub mod test {
             self
         }
 
+        fn to_proposal_received_state(mut self) -> IssuerSM {
+            self = self.handle_message(CredentialIssuanceMessage::CredentialInit(mock_connection(), None)).unwrap();
+            self = self.handle_message(CredentialIssuanceMessage::CredentialProposal(_credential_proposal())).unwrap();
+            self
+        }
+
         fn to_request_received_state(mut self) -> IssuerSM {
             self = self.handle_message(CredentialIssuanceMessage::CredentialInit(mock_connection(), None)).unwrap();
             self = self.handle_message(CredentialIssuanceMessage::CredentialRequest(_credential_request())).unwrap();
@@ -410,6 +575,25 @@ pub mod test {
             assert_match!(IssuerState::Initial(_), issuer_sm.state);
             assert_eq!(source_id(), issuer_sm.get_source_id());
         }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_issuer_from_proposal() {
+            let _setup = SetupAriesMocks::init();
+
+            let proposal = _credential_proposal();
+            let mut issuer_sm = IssuerSM::from_proposal("test", None, None, proposal.clone(), IssuanceProtocol::V1, mock_connection(), &source_id()).unwrap();
+
+            assert_match!(IssuerState::ProposalReceived(_), issuer_sm.state);
+            assert_eq!(proposal.to_a2a_message(), issuer_sm.get_proposal().unwrap());
+            assert_eq!(proposal.thread.clone().unwrap().thid.unwrap(), issuer_sm.thread_id());
+
+            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialProposal(proposal)).unwrap();
+            assert_eq!(1, issuer_sm.history().transitions().len());
+
+            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialCounterOfferSend(mock_connection(), None)).unwrap();
+            assert_match!(IssuerState::OfferSent(_), issuer_sm.state);
+        }
     }
 
     mod handle_message {
@@ -471,10 +655,69 @@ pub mod test {
             issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialInit(mock_connection(), None)).unwrap();
             issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialProposal(_credential_proposal())).unwrap();
 
+            assert_match!(IssuerState::ProposalReceived(_), issuer_sm.state);
+            assert_eq!(VcxStateType::VcxStateProposalReceived as u32, issuer_sm.state());
+            assert_eq!(_credential_proposal().to_a2a_message(), issuer_sm.get_proposal().unwrap());
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_issuer_handle_counter_offer_send_message_from_proposal_received_state() {
+            let _setup = SetupAriesMocks::init();
+
+            let mut issuer_sm = _issuer_sm().to_proposal_received_state();
+            let thread_id = issuer_sm.state.thread_id();
+            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialCounterOfferSend(mock_connection(), None)).unwrap();
+
+            assert_match!(IssuerState::OfferSent(_), issuer_sm.state);
+            assert_eq!(thread_id, issuer_sm.state.thread_id());
+            if let IssuerState::OfferSent(ref state) = issuer_sm.state {
+                let (name, value) = _value();
+                assert_eq!(json!({name: value}).to_string(), state.cred_data);
+            }
+
+            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialRequest(_credential_request())).unwrap();
+            assert_match!(IssuerState::RequestReceived(_), issuer_sm.state);
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_issuer_handle_proposal_decline_message_from_proposal_received_state() {
+            let _setup = SetupAriesMocks::init();
+
+            let mut issuer_sm = _issuer_sm().to_proposal_received_state();
+            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialProposalDecline(mock_connection(), String::from("not issuing it"))).unwrap();
+
             assert_match!(IssuerState::Finished(_), issuer_sm.state);
             assert_eq!(Status::Failed(ProblemReport::default()).code(), issuer_sm.credential_status());
         }
 
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_issuer_handle_other_messages_from_proposal_received_state() {
+            let _setup = SetupAriesMocks::init();
+
+            let mut issuer_sm = _issuer_sm().to_proposal_received_state();
+
+            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialRequest(_credential_request())).unwrap();
+            assert_match!(IssuerState::ProposalReceived(_), issuer_sm.state);
+
+            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialSend(mock_connection())).unwrap();
+            assert_match!(IssuerState::ProposalReceived(_), issuer_sm.state);
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_issuer_counter_offer_fails_for_empty_proposal() {
+            let _setup = SetupAriesMocks::init();
+
+            let issuer_sm = _issuer_sm().to_offer_sent_state()
+                .handle_message(CredentialIssuanceMessage::CredentialProposal(CredentialProposal::create())).unwrap();
+
+            let res = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialCounterOfferSend(mock_connection(), None));
+            assert_eq!(VcxErrorKind::InvalidJson, res.unwrap_err().kind());
+        }
+
         #[test]
         #[cfg(feature = "general_test")]
         fn test_issuer_handle_problem_report_message_from_offer_sent_state() {
@@ -548,6 +791,26 @@ pub mod test {
             assert_match!(IssuerState::Finished(_), issuer_sm.state);
         }
 
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_issuer_handle_credential_send_message_from_request_received_state_for_v2_protocol() {
+            let _setup = SetupAriesMocks::init();
+
+            let mut issuer_sm = _issuer_sm();
+            let conn_handle = mock_connection();
+            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialInit(conn_handle, None)).unwrap();
+            issuer_sm.protocol = IssuanceProtocol::V2(CredentialFormat::Indy);
+
+            let request: A2AMessage = CredentialRequestV2::from_v1(&_credential_request(), &CredentialFormat::Indy).unwrap().to_a2a_message();
+            issuer_sm = issuer_sm.handle_message(request.into()).unwrap();
+            assert_match!(IssuerState::RequestReceived(_), issuer_sm.state);
+
+            issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::CredentialSend(conn_handle)).unwrap();
+            assert_match!(IssuerState::Finished(_), issuer_sm.state);
+            assert_eq!(Status::Success.code(), issuer_sm.credential_status());
+            assert_eq!(IssuanceProtocol::V2(CredentialFormat::Indy), issuer_sm.protocol);
+        }
+
         // TRANSITIONS TO/FROM CREDENTIAL SENT STATE AREN'T POSSIBLE NOW
 
         #[test]
@@ -617,6 +880,18 @@ pub mod test {
                 assert_match!(A2AMessage::CredentialRequest(_), message);
             }
 
+            // CredentialRequest 2.0
+            {
+                let messages = map!(
+                    "key_1".to_string() => A2AMessage::CredentialOffer(_credential_offer()),
+                    "key_2".to_string() => CredentialRequestV2::from_v1(&_credential_request(), &CredentialFormat::Indy).unwrap().to_a2a_message()
+                );
+
+                let (uid, message) = issuer.find_message_to_handle(messages).unwrap();
+                assert_eq!("key_2", uid);
+                assert_match!(A2AMessage::CredentialRequestV2(_), message);
+            }
+
             // CredentialProposal
             {
                 let messages = map!(
@@ -728,4 +1003,99 @@ pub mod test {
             assert_eq!(VcxStateType::VcxStateAccepted as u32, _issuer_sm().to_finished_state().state());
         }
     }
+    mod history {
+        use history::Outcome;
+
+        use super::*;
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_history_of_finished_exchange() {
+            let _setup = SetupAriesMocks::init();
+
+            let issuer_sm = _issuer_sm().to_finished_state();
+
+            let transitions = issuer_sm.history().transitions();
+            assert_eq!(vec!["CredentialInit", "CredentialRequest", "CredentialSend"],
+                       transitions.iter().map(|transition| transition.message_type.as_str()).collect::<Vec<&str>>());
+            assert_eq!(None, transitions[0].message_id);
+            assert_eq!(Some(_credential_request().id.0), transitions[1].message_id);
+            assert_eq!(Outcome::InProgress, transitions[1].outcome);
+            assert_eq!(VcxStateType::VcxStateRequestReceived as u32, transitions[2].old_state);
+            assert_eq!(Outcome::Success, transitions[2].outcome);
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_history_of_failed_exchange() {
+            let _setup = SetupAriesMocks::init();
+
+            let issuer_sm = _issuer_sm().to_offer_sent_state()
+                .handle_message(CredentialIssuanceMessage::ProblemReport(_problem_report())).unwrap();
+
+            let transition = issuer_sm.history().transitions().last().unwrap();
+            assert_eq!("ProblemReport", transition.message_type);
+            assert_eq!(Outcome::Failed, transition.outcome);
+            assert!(transition.problem_report.is_some());
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_history_guards_duplicate_of_message_leaving_state_unchanged() {
+            let _setup = SetupAriesMocks::init();
+
+            let issuer_sm = _issuer_sm().to_offer_sent_state()
+                .handle_message(CredentialIssuanceMessage::Credential(_credential())).unwrap();
+            assert_match!(IssuerState::OfferSent(_), issuer_sm.state);
+            assert!(issuer_sm.history().contains_message("Credential", &_credential().id.0));
+
+            let transitions = issuer_sm.history().transitions().len();
+            let issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::Credential(_credential())).unwrap();
+            assert_eq!(transitions, issuer_sm.history().transitions().len());
+        }
+    }
+
+    mod expiry {
+        use settings;
+
+        use super::*;
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_issuer_offer_does_not_expire_without_timeout() {
+            let _setup = SetupAriesMocks::init();
+
+            let issuer_sm = _issuer_sm().to_offer_sent_state();
+
+            assert!(!issuer_sm.is_expired());
+            assert_match!(IssuerState::OfferSent(OfferSentState { expires_time: None, .. }), issuer_sm.state);
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_issuer_handle_expire_message_from_offer_sent_state() {
+            let _setup = SetupAriesMocks::init();
+            settings::set_config_value(settings::CONFIG_PROTOCOL_TIMEOUTS, &json!({"issue-credential": 0}).to_string());
+
+            let issuer_sm = _issuer_sm().to_offer_sent_state();
+            assert!(issuer_sm.is_expired());
+
+            let issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::Expire).unwrap();
+
+            assert_match!(IssuerState::Finished(_), issuer_sm.state);
+            assert_eq!(Status::Failed(ProblemReport::default()).code(), issuer_sm.credential_status());
+            assert!(!issuer_sm.is_expired());
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_issuer_ignores_expire_message_from_other_states() {
+            let _setup = SetupAriesMocks::init();
+
+            let issuer_sm = _issuer_sm().to_request_received_state();
+            let issuer_sm = issuer_sm.handle_message(CredentialIssuanceMessage::Expire).unwrap();
+
+            assert_match!(IssuerState::RequestReceived(_), issuer_sm.state);
+        }
+    }
 }
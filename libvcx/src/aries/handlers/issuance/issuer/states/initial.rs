@@ -34,8 +34,8 @@ impl From<InitialState> for FinishedState {
     }
 }
 
-impl From<(InitialState, String, u32, MessageId)> for OfferSentState {
-    fn from((state, offer, connection_handle, sent_id): (InitialState, String, u32, MessageId)) -> Self {
+impl From<(InitialState, String, u32, MessageId, Option<String>)> for OfferSentState {
+    fn from((state, offer, connection_handle, sent_id, expires_time): (InitialState, String, u32, MessageId, Option<String>)) -> Self {
         trace!("SM is now in OfferSent state");
         OfferSentState {
             offer,
@@ -44,6 +44,7 @@ impl From<(InitialState, String, u32, MessageId)> for OfferSentState {
             tails_file: state.tails_file,
             connection_handle,
             thread_id: sent_id.0,
+            expires_time,
         }
     }
 }
\ No newline at end of file
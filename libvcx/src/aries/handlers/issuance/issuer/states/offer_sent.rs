@@ -1,7 +1,9 @@
 use aries::handlers::issuance::issuer::state_machine::RevocationInfoV1;
 use aries::handlers::issuance::issuer::states::finished::FinishedState;
+use aries::handlers::issuance::issuer::states::proposal_received::ProposalReceivedState;
 use aries::handlers::issuance::issuer::states::requested_received::RequestReceivedState;
 use aries::messages::error::ProblemReport;
+use aries::messages::issuance::credential_proposal::CredentialProposal;
 use aries::messages::issuance::credential_request::CredentialRequest;
 use aries::messages::status::Status;
 
@@ -13,6 +15,8 @@ pub struct OfferSentState {
     pub tails_file: Option<String>,
     pub connection_handle: u32,
     pub thread_id: String,
+    #[serde(default)]
+    pub expires_time: Option<String>,
 }
 
 impl From<OfferSentState> for FinishedState {
@@ -46,6 +50,21 @@ impl From<(OfferSentState, CredentialRequest)> for RequestReceivedState {
     }
 }
 
+impl From<(OfferSentState, CredentialProposal)> for ProposalReceivedState {
+    fn from((state, proposal): (OfferSentState, CredentialProposal)) -> Self {
+        trace!("SM is now in Proposal Received state");
+        ProposalReceivedState {
+            offer: state.offer,
+            cred_data: state.cred_data,
+            rev_reg_id: state.rev_reg_id,
+            tails_file: state.tails_file,
+            connection_handle: state.connection_handle,
+            proposal,
+            thread_id: state.thread_id,
+        }
+    }
+}
+
 impl From<(OfferSentState, ProblemReport)> for FinishedState {
     fn from((state, err): (OfferSentState, ProblemReport)) -> Self {
         trace!("SM is now in Finished state");
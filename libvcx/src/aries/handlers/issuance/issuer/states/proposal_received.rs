@@ -0,0 +1,48 @@
+use aries::handlers::issuance::issuer::state_machine::RevocationInfoV1;
+use aries::handlers::issuance::issuer::states::finished::FinishedState;
+use aries::handlers::issuance::issuer::states::offer_sent::OfferSentState;
+use aries::messages::error::ProblemReport;
+use aries::messages::issuance::credential_proposal::CredentialProposal;
+use aries::messages::status::Status;
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct ProposalReceivedState {
+    pub offer: String,
+    pub cred_data: String,
+    pub rev_reg_id: Option<String>,
+    pub tails_file: Option<String>,
+    pub connection_handle: u32,
+    pub proposal: CredentialProposal,
+    pub thread_id: String,
+}
+
+impl From<(ProposalReceivedState, String, Option<String>)> for OfferSentState {
+    fn from((state, cred_data, expires_time): (ProposalReceivedState, String, Option<String>)) -> Self {
+        trace!("SM is now in OfferSent state");
+        OfferSentState {
+            offer: state.offer,
+            cred_data,
+            rev_reg_id: state.rev_reg_id,
+            tails_file: state.tails_file,
+            connection_handle: state.connection_handle,
+            thread_id: state.thread_id,
+            expires_time,
+        }
+    }
+}
+
+impl From<(ProposalReceivedState, ProblemReport)> for FinishedState {
+    fn from((state, err): (ProposalReceivedState, ProblemReport)) -> Self {
+        trace!("SM is now in Finished state");
+        FinishedState {
+            cred_id: None,
+            thread_id: state.thread_id,
+            revocation_info_v1: Some(RevocationInfoV1 {
+                cred_rev_id: None,
+                rev_reg_id: state.rev_reg_id,
+                tails_file: state.tails_file,
+            }),
+            status: Status::Failed(err),
+        }
+    }
+}
@@ -6,17 +6,21 @@ use aries::messages::issuance::credential_offer::CredentialOffer;
 use aries::messages::issuance::credential_proposal::CredentialProposal;
 use aries::messages::issuance::credential_request::CredentialRequest;
 
-#[derive(Debug, Clone)]
+#[derive(Debug, Clone, AsRefStr)]
 pub enum CredentialIssuanceMessage {
     CredentialInit(u32, Option<String>),
     CredentialSend(u32),
+    CredentialProposalSend(u32),
     CredentialProposal(CredentialProposal),
+    CredentialCounterOfferSend(u32, Option<String>),
+    CredentialProposalDecline(u32, String),
     CredentialOffer(CredentialOffer),
     CredentialRequestSend(u32),
     CredentialRequest(CredentialRequest),
     Credential(Credential),
     CredentialAck(CredentialAck),
     ProblemReport(ProblemReport),
+    Expire,
     Unknown,
 }
 
@@ -35,7 +39,19 @@ impl From<A2AMessage> for CredentialIssuanceMessage {
             A2AMessage::Credential(credential) => {
                 CredentialIssuanceMessage::Credential(credential)
             }
-            A2AMessage::Ack(ack) | A2AMessage::CredentialAck(ack) => {
+            A2AMessage::CredentialProposalV2(proposal) => {
+                CredentialIssuanceMessage::CredentialProposal(proposal.into())
+            }
+            A2AMessage::CredentialOfferV2(offer) => {
+                CredentialIssuanceMessage::CredentialOffer(offer.into())
+            }
+            A2AMessage::CredentialRequestV2(request) => {
+                CredentialIssuanceMessage::CredentialRequest(request.into())
+            }
+            A2AMessage::CredentialV2(credential) => {
+                CredentialIssuanceMessage::Credential(credential.into())
+            }
+            A2AMessage::Ack(ack) | A2AMessage::CredentialAck(ack) | A2AMessage::CredentialAckV2(ack) => {
                 CredentialIssuanceMessage::CredentialAck(ack)
             }
             A2AMessage::CommonProblemReport(report) => {
@@ -47,3 +63,18 @@ impl From<A2AMessage> for CredentialIssuanceMessage {
         }
     }
 }
+
+impl CredentialIssuanceMessage {
+    /// Id of the received message.
+    pub fn message_id(&self) -> Option<String> {
+        match self {
+            CredentialIssuanceMessage::CredentialProposal(proposal) => Some(proposal.id.0.clone()),
+            CredentialIssuanceMessage::CredentialOffer(offer) => Some(offer.id.0.clone()),
+            CredentialIssuanceMessage::CredentialRequest(request) => Some(request.id.0.clone()),
+            CredentialIssuanceMessage::Credential(credential) => Some(credential.id.0.clone()),
+            CredentialIssuanceMessage::CredentialAck(ack) => Some(ack.id.0.clone()),
+            CredentialIssuanceMessage::ProblemReport(problem_report) => Some(problem_report.id.0.clone()),
+            _ => None
+        }
+    }
+}
@@ -1,3 +1,5 @@
 pub mod issuer;
 pub mod holder;
 pub mod messages;
+pub mod formats;
+pub mod protocol;
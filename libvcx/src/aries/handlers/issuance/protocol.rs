@@ -0,0 +1,130 @@
+use connection;
+use error::VcxResult;
+use aries::handlers::issuance::formats::{self, CredentialFormatHandler};
+use aries::messages::a2a::A2AMessage;
+use aries::messages::a2a::message_family::MessageFamilies;
+use aries::messages::issuance::credential::Credential;
+use aries::messages::issuance::credential_ack::CredentialAck;
+use aries::messages::issuance::credential_offer::CredentialOffer;
+use aries::messages::issuance::credential_proposal::CredentialProposal;
+use aries::messages::issuance::credential_request::CredentialRequest;
+use aries::messages::issuance::v20::CredentialFormat;
+use aries::messages::issuance::v20::credential::CredentialV2;
+use aries::messages::issuance::v20::credential_offer::CredentialOfferV2;
+use aries::messages::issuance::v20::credential_proposal::CredentialProposalV2;
+use aries::messages::issuance::v20::credential_request::CredentialRequestV2;
+
+/// Version of issue-credential protocol used for the exchange.
+///
+/// State machines keep working with 1.0 messages internally and convert them
+/// into the negotiated version only when they are sent.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum IssuanceProtocol {
+    V1,
+    V2(CredentialFormat),
+}
+
+impl Default for IssuanceProtocol {
+    fn default() -> IssuanceProtocol {
+        IssuanceProtocol::V1
+    }
+}
+
+impl IssuanceProtocol {
+    /// Uses issue-credential/2.0 if the other side disclosed it via discover-features, 1.0 otherwise.
+    pub fn negotiate(connection_handle: u32) -> IssuanceProtocol {
+        match connection::remote_supports_protocol(connection_handle, &MessageFamilies::CredentialIssuanceV2) {
+            Ok(true) => IssuanceProtocol::V2(CredentialFormat::default()),
+            _ => IssuanceProtocol::V1
+        }
+    }
+
+    pub fn format(&self) -> CredentialFormat {
+        match self {
+            IssuanceProtocol::V1 => CredentialFormat::Indy,
+            IssuanceProtocol::V2(format) => format.clone()
+        }
+    }
+
+    pub fn handler(&self) -> &'static dyn CredentialFormatHandler {
+        formats::handler(&self.format())
+    }
+
+    pub fn proposal_message(&self, proposal: &CredentialProposal) -> VcxResult<A2AMessage> {
+        match self {
+            IssuanceProtocol::V1 => Ok(proposal.to_a2a_message()),
+            IssuanceProtocol::V2(format) => Ok(CredentialProposalV2::from_v1(proposal, format)?.to_a2a_message())
+        }
+    }
+
+    pub fn offer_message(&self, offer: &CredentialOffer) -> VcxResult<A2AMessage> {
+        match self {
+            IssuanceProtocol::V1 => Ok(offer.to_a2a_message()),
+            IssuanceProtocol::V2(format) => Ok(CredentialOfferV2::from_v1(offer, format)?.to_a2a_message())
+        }
+    }
+
+    pub fn request_message(&self, request: &CredentialRequest) -> VcxResult<A2AMessage> {
+        match self {
+            IssuanceProtocol::V1 => Ok(request.to_a2a_message()),
+            IssuanceProtocol::V2(format) => Ok(CredentialRequestV2::from_v1(request, format)?.to_a2a_message())
+        }
+    }
+
+    pub fn credential_message(&self, credential: &Credential) -> VcxResult<A2AMessage> {
+        match self {
+            IssuanceProtocol::V1 => Ok(credential.to_a2a_message()),
+            IssuanceProtocol::V2(format) => Ok(CredentialV2::from_v1(credential, format)?.to_a2a_message())
+        }
+    }
+
+    pub fn ack_message(&self, ack: CredentialAck) -> A2AMessage {
+        match self {
+            IssuanceProtocol::V1 => A2AMessage::CredentialAck(ack),
+            IssuanceProtocol::V2(_) => A2AMessage::CredentialAckV2(ack)
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use utils::devsetup::SetupAriesMocks;
+    use aries::handlers::connection::tests::mock_connection;
+    use aries::messages::issuance::credential::tests::_credential;
+    use aries::messages::issuance::credential_offer::tests::_credential_offer;
+    use aries::messages::issuance::credential_request::tests::_credential_request;
+    use aries::messages::issuance::test::_ack;
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_issuance_protocol_negotiate_falls_back_to_v1() {
+        let _setup = SetupAriesMocks::init();
+
+        assert_eq!(IssuanceProtocol::V1, IssuanceProtocol::negotiate(mock_connection()));
+        assert_eq!(IssuanceProtocol::V1, IssuanceProtocol::negotiate(0));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_issuance_protocol_v1_messages_work() {
+        let protocol = IssuanceProtocol::V1;
+
+        assert_eq!(_credential_offer().to_a2a_message(), protocol.offer_message(&_credential_offer()).unwrap());
+        assert_eq!(_credential_request().to_a2a_message(), protocol.request_message(&_credential_request()).unwrap());
+        assert_eq!(_credential().to_a2a_message(), protocol.credential_message(&_credential()).unwrap());
+        assert_eq!(A2AMessage::CredentialAck(_ack()), protocol.ack_message(_ack()));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_issuance_protocol_v2_messages_work() {
+        let protocol = IssuanceProtocol::V2(CredentialFormat::Indy);
+
+        assert_match!(A2AMessage::CredentialOfferV2(_), protocol.offer_message(&_credential_offer()).unwrap());
+        assert_match!(A2AMessage::CredentialRequestV2(_), protocol.request_message(&_credential_request()).unwrap());
+        assert_match!(A2AMessage::CredentialV2(_), protocol.credential_message(&_credential()).unwrap());
+        assert_eq!(A2AMessage::CredentialAckV2(_ack()), protocol.ack_message(_ack()));
+    }
+}
@@ -0,0 +1,270 @@
+use error::prelude::*;
+use utils::transport;
+use aries::messages::a2a::A2AMessage;
+use aries::messages::connection::did_doc::DidDoc;
+use aries::messages::mediation::keylist_update::KeylistUpdate;
+use aries::messages::mediation::mediate_request::MediateRequest;
+use aries::messages::pickup::batch_pickup::BatchPickup;
+use aries::messages::pickup::messages_received::MessagesReceived;
+use aries::messages::pickup::status::Status;
+use aries::messages::pickup::status_request::StatusRequest;
+use aries::utils::encryption_envelope::EncryptionEnvelope;
+
+/// Recipient side of Coordinate Mediation (RFC 0211) and Pickup (RFC 0212) protocols.
+///
+/// The mediator is reached over an established connection. Every request asks for `return_route`,
+/// so the answer comes back in the HTTP response and no inbound endpoint is needed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct MediatorClient {
+    pw_vk: String,
+    did_doc: DidDoc,
+    endpoint: String,
+    routing_keys: Vec<String>,
+}
+
+/// Message picked up from the mediator along with the key it was packed for.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PickedUpMessage {
+    /// Id of the message in the mediator's queue, used to acknowledge it.
+    pub id: String,
+    pub recipient_verkey: String,
+    pub message: A2AMessage,
+}
+
+impl MediatorClient {
+    /**
+    Requests mediation from the counterparty of the connection defined by our verkey and their DIDDoc.
+    Fails if the mediator denies the request.
+     */
+    pub fn request(pw_vk: &str, did_doc: DidDoc) -> VcxResult<MediatorClient> {
+        trace!("MediatorClient::request >>> pw_vk: {:?}, did_doc: {:?}", pw_vk, did_doc);
+
+        let request = MediateRequest::create()
+            .request_return_route();
+
+        match MediatorClient::send_and_receive(pw_vk, &did_doc, &request.to_a2a_message())? {
+            A2AMessage::MediateGrant(grant) => {
+                if !grant.from_thread(&request.id.0) {
+                    return Err(VcxError::from_msg(VcxErrorKind::InvalidMessages, format!("Mediate grant is not related to the request: {:?}", grant.thread)));
+                }
+
+                Ok(MediatorClient {
+                    pw_vk: pw_vk.to_string(),
+                    did_doc,
+                    endpoint: grant.endpoint,
+                    routing_keys: grant.routing_keys,
+                })
+            }
+            A2AMessage::MediateDeny(_) => Err(VcxError::from_msg(VcxErrorKind::ActionNotSupported, "Mediator denied mediation request")),
+            message => Err(VcxError::from_msg(VcxErrorKind::InvalidMessages, format!("Unexpected answer to mediation request: {:?}", message)))
+        }
+    }
+
+    /**
+    Endpoint other agents have to use to reach us through the mediator
+     */
+    pub fn endpoint(&self) -> String {
+        self.endpoint.to_string()
+    }
+
+    pub fn routing_keys(&self) -> Vec<String> {
+        self.routing_keys.clone()
+    }
+
+    pub fn add_key(&self, key: &str) -> VcxResult<()> {
+        self.update_keylist(KeylistUpdate::create().add_key(key))
+    }
+
+    pub fn remove_key(&self, key: &str) -> VcxResult<()> {
+        self.update_keylist(KeylistUpdate::create().remove_key(key))
+    }
+
+    fn update_keylist(&self, update: KeylistUpdate) -> VcxResult<()> {
+        trace!("MediatorClient::update_keylist >>> update: {:?}", update);
+
+        let update = update.request_return_route();
+
+        match self.send(&update.to_a2a_message())? {
+            A2AMessage::KeylistUpdateResponse(response) => {
+                let failed = response.failed();
+                if !failed.is_empty() {
+                    return Err(VcxError::from_msg(VcxErrorKind::InvalidState, format!("Mediator could not update keys: {:?}", failed)));
+                }
+                Ok(())
+            }
+            message => Err(VcxError::from_msg(VcxErrorKind::InvalidMessages, format!("Unexpected answer to keylist update: {:?}", message)))
+        }
+    }
+
+    /**
+    Asks the mediator how many messages are queued for us
+     */
+    pub fn status(&self) -> VcxResult<Status> {
+        trace!("MediatorClient::status >>>");
+
+        let request = StatusRequest::create()
+            .request_return_route();
+
+        match self.send(&request.to_a2a_message())? {
+            A2AMessage::PickupStatus(status) => Ok(status),
+            message => Err(VcxError::from_msg(VcxErrorKind::InvalidMessages, format!("Unexpected answer to status request: {:?}", message)))
+        }
+    }
+
+    /**
+    Picks up at most `batch_size` queued messages and decrypts them.
+    The mediator keeps them queued until they are acknowledged with `acknowledge`.
+     */
+    pub fn pickup(&self, batch_size: u32) -> VcxResult<Vec<PickedUpMessage>> {
+        trace!("MediatorClient::pickup >>> batch_size: {}", batch_size);
+
+        let pickup = BatchPickup::create()
+            .set_batch_size(batch_size)
+            .request_return_route();
+
+        match self.send(&pickup.to_a2a_message())? {
+            A2AMessage::Batch(batch) => {
+                batch.messages.into_iter()
+                    .map(|message| {
+                        let payload = ::serde_json::to_vec(&message.message)
+                            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot serialize picked up message: {:?}", err)))?;
+
+                        let unpacked = EncryptionEnvelope::unpack(&payload)?;
+
+                        let a2a_message = ::serde_json::from_str(&unpacked.message)
+                            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize A2A message: {}", err)))?;
+
+                        Ok(PickedUpMessage { id: message.id, recipient_verkey: unpacked.recipient_verkey, message: a2a_message })
+                    })
+                    .collect()
+            }
+            message => Err(VcxError::from_msg(VcxErrorKind::InvalidMessages, format!("Unexpected answer to batch pickup: {:?}", message)))
+        }
+    }
+
+    /**
+    Lets the mediator remove processed messages from its queue.
+    Returns the number of messages still queued.
+     */
+    pub fn acknowledge(&self, message_ids: Vec<String>) -> VcxResult<u32> {
+        trace!("MediatorClient::acknowledge >>> message_ids: {:?}", message_ids);
+
+        let received = MessagesReceived::create()
+            .set_message_ids(message_ids)
+            .request_return_route();
+
+        match self.send(&received.to_a2a_message())? {
+            A2AMessage::PickupStatus(status) => Ok(status.message_count),
+            message => Err(VcxError::from_msg(VcxErrorKind::InvalidMessages, format!("Unexpected answer to messages received: {:?}", message)))
+        }
+    }
+
+    fn send(&self, message: &A2AMessage) -> VcxResult<A2AMessage> {
+        MediatorClient::send_and_receive(&self.pw_vk, &self.did_doc, message)
+    }
+
+    fn send_and_receive(pw_vk: &str, did_doc: &DidDoc, message: &A2AMessage) -> VcxResult<A2AMessage> {
+        let envelope = EncryptionEnvelope::create(message, Some(pw_vk), did_doc)?;
+        let response = transport::send(&envelope.0, &did_doc.get_endpoint())?;
+        EncryptionEnvelope::open(response)
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::a2a::MessageId;
+    use aries::messages::ack::tests::_ack;
+    use aries::messages::connection::did_doc::tests::*;
+    use aries::messages::mediation::keylist_update::KeylistAction;
+    use aries::messages::mediation::keylist_update_response::{KeylistUpdateResponse, KeylistUpdateResult};
+    use aries::messages::mediation::mediate_deny::MediateDeny;
+    use aries::messages::mediation::mediate_grant::tests::_mediate_grant;
+    use aries::messages::pickup::batch::Batch;
+    use aries::messages::pickup::status::tests::_status;
+    use utils::devsetup::SetupAriesMocks;
+    use utils::httpclient::AgencyMockDecrypted;
+
+    use super::*;
+
+    pub fn _mediator_client() -> MediatorClient {
+        MediatorClient {
+            pw_vk: _key_1(),
+            did_doc: _did_doc(),
+            endpoint: _service_endpoint(),
+            routing_keys: vec![_key_2()],
+        }
+    }
+
+    fn _set_next_answer(message: A2AMessage) {
+        AgencyMockDecrypted::set_next_decrypted_message(&json!(message).to_string());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_mediator_client_request_works() {
+        let _setup = SetupAriesMocks::init();
+
+        _set_next_answer(_mediate_grant().set_thread_id(&MessageId::id().0).to_a2a_message());
+
+        let client = MediatorClient::request(&_key_1(), _did_doc()).unwrap();
+        assert_eq!(_mediator_client(), client);
+        assert_eq!(_service_endpoint(), client.endpoint());
+        assert_eq!(vec![_key_2()], client.routing_keys());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_mediator_client_request_fails_when_denied() {
+        let _setup = SetupAriesMocks::init();
+
+        _set_next_answer(MediateDeny::create().set_thread_id(&MessageId::id().0).to_a2a_message());
+        assert_eq!(VcxErrorKind::ActionNotSupported, MediatorClient::request(&_key_1(), _did_doc()).unwrap_err().kind());
+
+        _set_next_answer(_ack().to_a2a_message());
+        assert_eq!(VcxErrorKind::InvalidMessages, MediatorClient::request(&_key_1(), _did_doc()).unwrap_err().kind());
+
+        _set_next_answer(_mediate_grant().to_a2a_message());
+        assert_eq!(VcxErrorKind::InvalidMessages, MediatorClient::request(&_key_1(), _did_doc()).unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_mediator_client_update_keylist_works() {
+        let _setup = SetupAriesMocks::init();
+
+        let client = _mediator_client();
+
+        _set_next_answer(KeylistUpdateResponse::create()
+            .add_updated(&_key_3(), KeylistAction::Add, KeylistUpdateResult::Success)
+            .to_a2a_message());
+        client.add_key(&_key_3()).unwrap();
+
+        _set_next_answer(KeylistUpdateResponse::create()
+            .add_updated(&_key_3(), KeylistAction::Remove, KeylistUpdateResult::ServerError)
+            .to_a2a_message());
+        assert_eq!(VcxErrorKind::InvalidState, client.remove_key(&_key_3()).unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_mediator_client_pickup_works() {
+        let _setup = SetupAriesMocks::init();
+
+        let client = _mediator_client();
+
+        _set_next_answer(_status().to_a2a_message());
+        assert_eq!(2, client.status().unwrap().message_count);
+
+        let packed = json!({"message": json!(_ack().to_a2a_message()).to_string(), "recipient_verkey": _key_3()});
+        _set_next_answer(Batch::create().add_message("1", packed).to_a2a_message());
+
+        let expected = PickedUpMessage { id: String::from("1"), recipient_verkey: _key_3(), message: _ack().to_a2a_message() };
+        assert_eq!(vec![expected], client.pickup(10).unwrap());
+
+        _set_next_answer(_status().to_a2a_message());
+        assert_eq!(2, client.acknowledge(vec![String::from("1")]).unwrap());
+
+        _set_next_answer(_ack().to_a2a_message());
+        assert_eq!(VcxErrorKind::InvalidMessages, client.acknowledge(vec![String::from("1")]).unwrap_err().kind());
+    }
+}
@@ -0,0 +1,521 @@
+use std::collections::{HashMap, VecDeque};
+
+use error::prelude::*;
+use settings;
+use utils::transport;
+use utils::libindy::crypto;
+use utils::uuid;
+use aries::messages::a2a::A2AMessage;
+use aries::messages::connection::did_doc::DidDoc;
+use aries::messages::forward::Forward;
+use aries::messages::mediation::keylist_update::{KeylistAction, KeylistUpdate};
+use aries::messages::mediation::keylist_update_response::{KeylistUpdateResponse, KeylistUpdateResult};
+use aries::messages::mediation::mediate_grant::MediateGrant;
+use aries::messages::pickup::batch::Batch;
+use aries::messages::pickup::status::Status;
+use aries::utils::encryption_envelope::{EncryptionEnvelope, UnpackedMessage};
+
+/// Mediator side of Coordinate Mediation (RFC 0211) and Pickup (RFC 0212) protocols.
+///
+/// The router owns a single routing key which is given to all the clients it mediates for.
+/// Forward messages (RFC 0094) packed for this key are unpacked and their inner messages
+/// are queued per recipient key until the client picks them up, or pushed to the client's endpoint.
+/// Picked up messages stay queued until the client acknowledges them with `messages-received`.
+/// At most `router_queue_limit` config value (100 by default) messages are queued per key, further ones are rejected.
+/// The queues are part of the serialized router, so it has to be serialized to keep them across restarts.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Router {
+    source_id: String,
+    endpoint: String,
+    routing_vk: String,
+    clients: Vec<RouterClient>,
+    queues: HashMap<String, VecDeque<QueuedMessage>>,
+}
+
+/// Connection registered with the router, mediation is only granted over registered connections.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RouterClient {
+    pw_vk: String,
+    did_doc: DidDoc,
+    granted: bool,
+    delivery: Delivery,
+    keys: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum Delivery {
+    /// Messages are kept until the client picks them up. Used for clients without own endpoint.
+    Pickup,
+    /// Messages are posted to the endpoint from client's DIDDoc, queued if it is not reachable.
+    Push,
+}
+
+const DEFAULT_QUEUE_LIMIT: usize = 100;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct QueuedMessage {
+    id: String,
+    message: ::serde_json::Value,
+}
+
+impl Router {
+    pub fn create(source_id: &str, endpoint: &str) -> VcxResult<Router> {
+        trace!("Router::create >>> source_id: {:?}, endpoint: {:?}", source_id, endpoint);
+
+        Ok(Router {
+            source_id: source_id.to_string(),
+            endpoint: endpoint.to_string(),
+            routing_vk: crypto::create_key(None)?,
+            clients: Vec::new(),
+            queues: HashMap::new(),
+        })
+    }
+
+    pub fn get_source_id(&self) -> String {
+        self.source_id.clone()
+    }
+
+    pub fn routing_key(&self) -> String {
+        self.routing_vk.clone()
+    }
+
+    /**
+    Registers the connection defined by our verkey and their DIDDoc.
+    Its counterparty is allowed to request mediation.
+     */
+    pub fn add_client(&mut self, pw_vk: &str, did_doc: DidDoc) -> VcxResult<()> {
+        trace!("Router::add_client >>> pw_vk: {:?}, did_doc: {:?}", pw_vk, did_doc);
+
+        if self.clients.iter().any(|client| client.pw_vk == pw_vk) {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidState, format!("Connection with key {} is already registered", pw_vk)));
+        }
+
+        self.clients.push(RouterClient {
+            pw_vk: pw_vk.to_string(),
+            did_doc,
+            granted: false,
+            delivery: Delivery::Pickup,
+            keys: Vec::new(),
+        });
+
+        Ok(())
+    }
+
+    /// Returns the number of messages queued for all the keys.
+    pub fn queued_count(&self) -> usize {
+        self.queues.values().map(VecDeque::len).sum()
+    }
+
+    /**
+    Processes the packed message received on the router's endpoint.
+    Returns the packed answer if the sender asked to return it over the same transport.
+     */
+    pub fn receive(&mut self, payload: &[u8]) -> VcxResult<Option<Vec<u8>>> {
+        trace!("Router::receive >>> payload: {:?}", payload);
+
+        let unpacked = EncryptionEnvelope::unpack(payload)?;
+
+        let message: A2AMessage = ::serde_json::from_str(&unpacked.message)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize A2A message: {}", err)))?;
+
+        match message {
+            A2AMessage::Forward(forward) => {
+                if unpacked.recipient_verkey != self.routing_vk {
+                    return Err(VcxError::from_msg(VcxErrorKind::InvalidMessages, format!("Forward message is not packed for routing key: {}", unpacked.recipient_verkey)));
+                }
+                self.route(forward)?;
+                Ok(None)
+            }
+            message => self.handle_client_message(&unpacked, message)
+        }
+    }
+
+    fn route(&mut self, forward: Forward) -> VcxResult<()> {
+        trace!("Router::route >>> to: {:?}", forward.to);
+
+        let delivery = self.clients.iter()
+            .find(|client| client.keys.contains(&forward.to))
+            .map(|client| (client.delivery.clone(), client.did_doc.get_endpoint()))
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidMessages, format!("Recipient key is not managed by the router: {}", forward.to)))?;
+
+        if let (Delivery::Push, endpoint) = delivery {
+            match Router::push(&forward.msg, &endpoint) {
+                Ok(()) => return Ok(()),
+                Err(err) => warn!("Router::route <<< cannot push message to {}, queueing it: {:?}", endpoint, err)
+            }
+        }
+
+        let queue = self.queues.entry(forward.to.clone())
+            .or_insert_with(VecDeque::new);
+
+        if queue.len() >= Router::queue_limit() {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidState, format!("Queue of recipient key {} is full", forward.to)));
+        }
+
+        queue.push_back(QueuedMessage { id: uuid::uuid(), message: forward.msg });
+
+        Ok(())
+    }
+
+    fn queue_limit() -> usize {
+        settings::get_config_value(settings::CONFIG_ROUTER_QUEUE_LIMIT)
+            .ok()
+            .and_then(|limit| limit.parse().ok())
+            .unwrap_or(DEFAULT_QUEUE_LIMIT)
+    }
+
+    fn push(message: &::serde_json::Value, endpoint: &str) -> VcxResult<()> {
+        let message = ::serde_json::to_vec(message)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize forwarded message: {:?}", err)))?;
+
+        transport::send(&message, endpoint).map(|_| ())
+    }
+
+    fn handle_client_message(&mut self, unpacked: &UnpackedMessage, message: A2AMessage) -> VcxResult<Option<Vec<u8>>> {
+        let sender_vk = unpacked.sender_verkey.as_ref()
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidMessages, "Anonymous messages are accepted by the router only inside Forward"))?;
+
+        let index = self.clients.iter()
+            .position(|client| client.pw_vk == unpacked.recipient_verkey && client.did_doc.recipient_keys().contains(sender_vk))
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidMessages, "Message is not sent over connection registered with the router"))?;
+
+        let (answer, return_route) = match message {
+            A2AMessage::MediateRequest(request) => {
+                let client = &mut self.clients[index];
+                client.granted = true;
+                client.delivery = if request.return_route_requested() { Delivery::Pickup } else { Delivery::Push };
+
+                let grant = MediateGrant::create()
+                    .set_endpoint(self.endpoint.clone())
+                    .set_routing_keys(vec![self.routing_vk.clone()])
+                    .set_thread_id(&request.id.0);
+
+                (grant.to_a2a_message(), request.return_route_requested())
+            }
+            A2AMessage::KeylistUpdate(update) => {
+                let return_route = update.return_route_requested();
+                (self.update_keylist(index, update)?.to_a2a_message(), return_route)
+            }
+            A2AMessage::PickupStatusRequest(request) => {
+                let client = self.granted_client(index)?;
+
+                let message_count = client.keys.iter()
+                    .filter_map(|key| self.queues.get(key))
+                    .map(VecDeque::len)
+                    .sum::<usize>();
+
+                let status = Status::create()
+                    .set_message_count(message_count as u32)
+                    .set_thread_id(&request.id.0);
+
+                (status.to_a2a_message(), request.return_route_requested())
+            }
+            A2AMessage::BatchPickup(pickup) => {
+                let client = self.granted_client(index)?;
+
+                let mut batch = Batch::create().set_thread_id(&pickup.id.0);
+
+                let queued = client.keys.iter()
+                    .filter_map(|key| self.queues.get(key))
+                    .flat_map(|queue| queue.iter())
+                    .take(pickup.batch_size as usize);
+
+                for message in queued {
+                    batch = batch.add_message(&message.id, message.message.clone());
+                }
+
+                (batch.to_a2a_message(), pickup.return_route_requested())
+            }
+            A2AMessage::MessagesReceived(received) => {
+                let keys = self.granted_client(index)?.keys.clone();
+
+                for key in keys.iter() {
+                    if let Some(queue) = self.queues.get_mut(key) {
+                        queue.retain(|queued| !received.message_id_list.contains(&queued.id));
+                    }
+                }
+
+                self.queues.retain(|_, queue| !queue.is_empty());
+
+                let message_count = keys.iter()
+                    .filter_map(|key| self.queues.get(key))
+                    .map(VecDeque::len)
+                    .sum::<usize>();
+
+                let status = Status::create()
+                    .set_message_count(message_count as u32)
+                    .set_thread_id(&received.id.0);
+
+                (status.to_a2a_message(), received.return_route_requested())
+            }
+            message => return Err(VcxError::from_msg(VcxErrorKind::ActionNotSupported, format!("Message is not supported by the router: {:?}", message)))
+        };
+
+        let client = &self.clients[index];
+
+        if return_route {
+            return Router::pack(&answer, &client.pw_vk, sender_vk).map(Some);
+        }
+
+        let envelope = EncryptionEnvelope::create(&answer, Some(&client.pw_vk), &client.did_doc)?;
+        transport::send(&envelope.0, &client.did_doc.get_endpoint())?;
+        Ok(None)
+    }
+
+    fn granted_client(&self, index: usize) -> VcxResult<&RouterClient> {
+        let client = &self.clients[index];
+
+        if !client.granted {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidState, "Mediation is not granted for the connection"));
+        }
+
+        Ok(client)
+    }
+
+    fn update_keylist(&mut self, index: usize, update: KeylistUpdate) -> VcxResult<KeylistUpdateResponse> {
+        self.granted_client(index)?;
+
+        let mut response = KeylistUpdateResponse::create()
+            .set_thread_id(&update.id.0);
+
+        for item in update.updates {
+            let result = match item.action {
+                KeylistAction::Add => {
+                    if self.clients[index].keys.contains(&item.recipient_key) {
+                        KeylistUpdateResult::NoChange
+                    } else if self.clients.iter().any(|client| client.keys.contains(&item.recipient_key)) {
+                        KeylistUpdateResult::ClientError
+                    } else {
+                        self.clients[index].keys.push(item.recipient_key.clone());
+                        KeylistUpdateResult::Success
+                    }
+                }
+                KeylistAction::Remove => {
+                    let keys = &mut self.clients[index].keys;
+                    match keys.iter().position(|key| key == &item.recipient_key) {
+                        Some(position) => {
+                            keys.remove(position);
+                            KeylistUpdateResult::Success
+                        }
+                        None => KeylistUpdateResult::NoChange
+                    }
+                }
+            };
+
+            response = response.add_updated(&item.recipient_key, item.action, result);
+        }
+
+        Ok(response)
+    }
+
+    fn pack(message: &A2AMessage, pw_vk: &str, their_vk: &str) -> VcxResult<Vec<u8>> {
+        let receiver_keys = json!(vec![their_vk]).to_string();
+        crypto::pack_message(Some(pw_vk), &receiver_keys, json!(message).to_string().as_bytes())
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::a2a::MessageId;
+    use aries::messages::ack::tests::_ack;
+    use aries::messages::connection::did_doc::tests::*;
+    use aries::messages::mediation::mediate_request::MediateRequest;
+    use aries::messages::pickup::batch_pickup::BatchPickup;
+    use aries::messages::pickup::messages_received::MessagesReceived;
+    use aries::messages::pickup::status_request::StatusRequest;
+    use utils::devsetup::SetupAriesMocks;
+
+    use super::*;
+
+    fn _endpoint() -> String {
+        String::from("https://router.example.com")
+    }
+
+    fn _pw_vk() -> String {
+        _key_2()
+    }
+
+    fn _unknown_key() -> String {
+        String::from("CnEDk9HrMnmiHXEV1WFgbVCRteYnPqsJwrTdcZaNhFVW")
+    }
+
+    // indy mocks return packed messages as is, so it is enough to provide unpacked structure
+    fn _packed(message: A2AMessage, sender_vk: Option<String>, recipient_vk: &str) -> Vec<u8> {
+        json!({
+            "message": json!(message).to_string(),
+            "sender_verkey": sender_vk,
+            "recipient_verkey": recipient_vk
+        }).to_string().into_bytes()
+    }
+
+    fn _from_client(message: A2AMessage) -> Vec<u8> {
+        _packed(message, Some(_key_1()), &_pw_vk())
+    }
+
+    fn _forward(router: &Router, to: &str) -> Vec<u8> {
+        let forward = Forward { to: to.to_string(), msg: json!(_ack()) };
+        _packed(A2AMessage::Forward(forward), None, &router.routing_key())
+    }
+
+    fn _answer(answer: Option<Vec<u8>>) -> A2AMessage {
+        ::serde_json::from_slice(&answer.unwrap()).unwrap()
+    }
+
+    pub fn _router() -> Router {
+        let mut router = Router::create("router", &_endpoint()).unwrap();
+        router.add_client(&_pw_vk(), _did_doc()).unwrap();
+        router
+    }
+
+    fn _granted_router() -> Router {
+        let mut router = _router();
+        router.receive(&_from_client(MediateRequest::create().request_return_route().to_a2a_message())).unwrap();
+        router.receive(&_from_client(KeylistUpdate::create().add_key(&_key_3()).request_return_route().to_a2a_message())).unwrap();
+        router
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_router_grants_mediation() {
+        let _setup = SetupAriesMocks::init();
+
+        let mut router = _router();
+
+        let answer = router.receive(&_from_client(MediateRequest::create().request_return_route().to_a2a_message())).unwrap();
+
+        let expected = MediateGrant::create()
+            .set_endpoint(_endpoint())
+            .set_routing_keys(vec![router.routing_key()])
+            .set_thread_id(&MessageId::id().0);
+
+        assert_eq!(expected.to_a2a_message(), _answer(answer));
+        assert_eq!(Delivery::Pickup, router.clients[0].delivery);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_router_rejects_unregistered_connection() {
+        let _setup = SetupAriesMocks::init();
+
+        let mut router = _router();
+
+        let request = _packed(MediateRequest::create().to_a2a_message(), Some(_key_3()), &_pw_vk());
+        assert_eq!(VcxErrorKind::InvalidMessages, router.receive(&request).unwrap_err().kind());
+
+        let request = _packed(MediateRequest::create().to_a2a_message(), None, &_pw_vk());
+        assert_eq!(VcxErrorKind::InvalidMessages, router.receive(&request).unwrap_err().kind());
+
+        assert!(!router.clients[0].granted);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_router_updates_keylist() {
+        let _setup = SetupAriesMocks::init();
+
+        let mut router = _router();
+
+        let update = KeylistUpdate::create().add_key(&_key_3()).request_return_route();
+        assert_eq!(VcxErrorKind::InvalidState, router.receive(&_from_client(update.to_a2a_message())).unwrap_err().kind());
+
+        router.receive(&_from_client(MediateRequest::create().request_return_route().to_a2a_message())).unwrap();
+
+        let update = KeylistUpdate::create().add_key(&_key_3()).remove_key(&_unknown_key()).request_return_route();
+        let answer = router.receive(&_from_client(update.to_a2a_message())).unwrap();
+
+        let expected = KeylistUpdateResponse::create()
+            .add_updated(&_key_3(), KeylistAction::Add, KeylistUpdateResult::Success)
+            .add_updated(&_unknown_key(), KeylistAction::Remove, KeylistUpdateResult::NoChange)
+            .set_thread_id(&MessageId::id().0);
+
+        assert_eq!(expected.to_a2a_message(), _answer(answer));
+        assert_eq!(vec![_key_3()], router.clients[0].keys);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_router_queues_forwarded_messages_until_received() {
+        let _setup = SetupAriesMocks::init();
+
+        let mut router = _granted_router();
+
+        assert_eq!(None, router.receive(&_forward(&router, &_key_3())).unwrap());
+        assert_eq!(None, router.receive(&_forward(&router, &_key_3())).unwrap());
+        assert_eq!(2, router.queued_count());
+
+        let answer = router.receive(&_from_client(StatusRequest::create().request_return_route().to_a2a_message())).unwrap();
+        match _answer(answer) {
+            A2AMessage::PickupStatus(status) => assert_eq!(2, status.message_count),
+            message => panic!("Unexpected message: {:?}", message)
+        }
+
+        let pickup = BatchPickup::create().set_batch_size(1).request_return_route();
+        let message_id = match _answer(router.receive(&_from_client(pickup.to_a2a_message())).unwrap()) {
+            A2AMessage::Batch(batch) => {
+                assert_eq!(1, batch.messages.len());
+                assert_eq!(json!(_ack()), batch.messages[0].message);
+                batch.messages[0].id.clone()
+            }
+            message => panic!("Unexpected message: {:?}", message)
+        };
+
+        assert_eq!(2, router.queued_count());
+
+        let received = MessagesReceived::create().set_message_ids(vec![message_id]).request_return_route();
+        match _answer(router.receive(&_from_client(received.to_a2a_message())).unwrap()) {
+            A2AMessage::PickupStatus(status) => assert_eq!(1, status.message_count),
+            message => panic!("Unexpected message: {:?}", message)
+        }
+
+        assert_eq!(1, router.queued_count());
+
+        // queued messages are kept in the serialized router
+        let restored: Router = ::serde_json::from_str(&::serde_json::to_string(&router).unwrap()).unwrap();
+        assert_eq!(1, restored.queued_count());
+        assert_eq!(router, restored);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_router_rejects_forward_to_full_queue() {
+        let _setup = SetupAriesMocks::init();
+
+        settings::set_config_value(settings::CONFIG_ROUTER_QUEUE_LIMIT, "1");
+
+        let mut router = _granted_router();
+
+        router.receive(&_forward(&router, &_key_3())).unwrap();
+        assert_eq!(VcxErrorKind::InvalidState, router.receive(&_forward(&router, &_key_3())).unwrap_err().kind());
+        assert_eq!(1, router.queued_count());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_router_rejects_forward_to_unknown_key() {
+        let _setup = SetupAriesMocks::init();
+
+        let mut router = _granted_router();
+
+        assert_eq!(VcxErrorKind::InvalidMessages, router.receive(&_forward(&router, &_unknown_key())).unwrap_err().kind());
+
+        let forward = Forward { to: _key_3(), msg: json!(_ack()) };
+        let forward = _packed(A2AMessage::Forward(forward), None, &_unknown_key());
+        assert_eq!(VcxErrorKind::InvalidMessages, router.receive(&forward).unwrap_err().kind());
+
+        assert_eq!(0, router.queued_count());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_router_pushes_messages_to_client_endpoint() {
+        let _setup = SetupAriesMocks::init();
+
+        let mut router = _router();
+        router.receive(&_from_client(MediateRequest::create().to_a2a_message())).unwrap();
+        router.receive(&_from_client(KeylistUpdate::create().add_key(&_key_3()).to_a2a_message())).unwrap();
+        assert_eq!(Delivery::Push, router.clients[0].delivery);
+
+        router.receive(&_forward(&router, &_key_3())).unwrap();
+        assert_eq!(0, router.queued_count());
+    }
+}
@@ -0,0 +1,25 @@
+use disclosed_proof_utils::generate_indy_proof;
+use error::VcxResult;
+use proof_utils::validate_indy_proof;
+use utils::libindy::anoncreds::libindy_prover_get_credentials_for_proof_req;
+use aries::handlers::proof_presentation::formats::PresentationFormatHandler;
+
+/// Hyperledger Indy proofs (`hlindy/*@v2.0` attachments) backed by libindy anoncreds.
+pub struct IndyPresentationFormat;
+
+impl PresentationFormatHandler for IndyPresentationFormat {
+    fn retrieve_credentials(&self, request: &str) -> VcxResult<String> {
+        trace!("IndyPresentationFormat::retrieve_credentials >>> request: {:?}", request);
+        libindy_prover_get_credentials_for_proof_req(request)
+    }
+
+    fn create_presentation(&self, request: &str, credentials: &str, self_attested_attrs: &str) -> VcxResult<String> {
+        trace!("IndyPresentationFormat::create_presentation >>> request: {:?}, credentials: {:?}, self_attested_attrs: {:?}", request, credentials, self_attested_attrs);
+        generate_indy_proof(credentials, self_attested_attrs, request)
+    }
+
+    fn verify_presentation(&self, presentation: &str, request: &str) -> VcxResult<bool> {
+        trace!("IndyPresentationFormat::verify_presentation >>> presentation: {:?}, request: {:?}", presentation, request);
+        validate_indy_proof(presentation, request)
+    }
+}
@@ -0,0 +1,25 @@
+use error::VcxResult;
+use aries::messages::proof_presentation::v20::PresentationFormat;
+
+pub mod indy;
+
+/// Format specific part of proof presentation.
+///
+/// Prover and verifier state machines pass attachment contents to the handler of
+/// negotiated format and never look inside them.
+pub trait PresentationFormatHandler {
+    /// Looks up credentials in the wallet which can satisfy the request.
+    fn retrieve_credentials(&self, request: &str) -> VcxResult<String>;
+
+    /// Creates the presentation for the request from selected credentials and self attested attributes.
+    fn create_presentation(&self, request: &str, credentials: &str, self_attested_attrs: &str) -> VcxResult<String>;
+
+    /// Checks that the presentation is valid and satisfies the request.
+    fn verify_presentation(&self, presentation: &str, request: &str) -> VcxResult<bool>;
+}
+
+pub fn handler(format: &PresentationFormat) -> &'static dyn PresentationFormatHandler {
+    match format {
+        PresentationFormat::Indy => &indy::IndyPresentationFormat
+    }
+}
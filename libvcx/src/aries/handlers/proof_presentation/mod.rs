@@ -1,2 +1,4 @@
+pub mod formats;
+pub mod protocol;
 pub mod prover;
-pub mod verifier;
\ No newline at end of file
+pub mod verifier;
@@ -0,0 +1,121 @@
+use connection;
+use error::VcxResult;
+use aries::handlers::proof_presentation::formats::{self, PresentationFormatHandler};
+use aries::messages::a2a::A2AMessage;
+use aries::messages::a2a::message_family::MessageFamilies;
+use aries::messages::proof_presentation::presentation::Presentation;
+use aries::messages::proof_presentation::presentation_ack::PresentationAck;
+use aries::messages::proof_presentation::presentation_proposal::PresentationProposal;
+use aries::messages::proof_presentation::presentation_request::PresentationRequest;
+use aries::messages::proof_presentation::v20::PresentationFormat;
+use aries::messages::proof_presentation::v20::presentation::PresentationV2;
+use aries::messages::proof_presentation::v20::presentation_proposal::PresentationProposalV2;
+use aries::messages::proof_presentation::v20::presentation_request::PresentationRequestV2;
+
+/// Version of present-proof protocol used for the exchange.
+///
+/// Prover and verifier hold 1.0 messages in their states, 2.0 messages are
+/// produced from them right before sending.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq)]
+pub enum PresentationProtocol {
+    V1,
+    V2(PresentationFormat),
+}
+
+impl Default for PresentationProtocol {
+    fn default() -> PresentationProtocol {
+        PresentationProtocol::V1
+    }
+}
+
+impl PresentationProtocol {
+    /// Uses present-proof/2.0 if the other side disclosed it via discover-features, 1.0 otherwise.
+    pub fn negotiate(connection_handle: u32) -> PresentationProtocol {
+        match connection::remote_supports_protocol(connection_handle, &MessageFamilies::PresentProofV2) {
+            Ok(true) => PresentationProtocol::V2(PresentationFormat::default()),
+            _ => PresentationProtocol::V1
+        }
+    }
+
+    pub fn format(&self) -> PresentationFormat {
+        match self {
+            PresentationProtocol::V1 => PresentationFormat::Indy,
+            PresentationProtocol::V2(format) => format.clone()
+        }
+    }
+
+    pub fn handler(&self) -> &'static dyn PresentationFormatHandler {
+        formats::handler(&self.format())
+    }
+
+    pub fn proposal_message(&self, proposal: &PresentationProposal) -> VcxResult<A2AMessage> {
+        match self {
+            PresentationProtocol::V1 => Ok(proposal.to_a2a_message()),
+            PresentationProtocol::V2(format) => Ok(PresentationProposalV2::from_v1(proposal, format)?.to_a2a_message())
+        }
+    }
+
+    pub fn request_message(&self, request: &PresentationRequest) -> VcxResult<A2AMessage> {
+        match self {
+            PresentationProtocol::V1 => Ok(request.to_a2a_message()),
+            PresentationProtocol::V2(format) => Ok(PresentationRequestV2::from_v1(request, format)?.to_a2a_message())
+        }
+    }
+
+    pub fn presentation_message(&self, presentation: &Presentation) -> VcxResult<A2AMessage> {
+        match self {
+            PresentationProtocol::V1 => Ok(presentation.to_a2a_message()),
+            PresentationProtocol::V2(format) => Ok(PresentationV2::from_v1(presentation, format)?.to_a2a_message())
+        }
+    }
+
+    pub fn ack_message(&self, ack: PresentationAck) -> A2AMessage {
+        match self {
+            PresentationProtocol::V1 => A2AMessage::PresentationAck(ack),
+            PresentationProtocol::V2(_) => A2AMessage::PresentationAckV2(ack)
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use utils::devsetup::SetupAriesMocks;
+    use aries::handlers::connection::tests::mock_connection;
+    use aries::messages::proof_presentation::presentation::tests::_presentation;
+    use aries::messages::proof_presentation::presentation_proposal::tests::_presentation_proposal;
+    use aries::messages::proof_presentation::presentation_request::tests::_presentation_request;
+    use aries::messages::proof_presentation::test::_ack;
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_presentation_protocol_negotiate_falls_back_to_v1() {
+        let _setup = SetupAriesMocks::init();
+
+        assert_eq!(PresentationProtocol::V1, PresentationProtocol::negotiate(mock_connection()));
+        assert_eq!(PresentationProtocol::V1, PresentationProtocol::negotiate(0));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_presentation_protocol_v1_messages_work() {
+        let protocol = PresentationProtocol::V1;
+
+        assert_eq!(_presentation_proposal().to_a2a_message(), protocol.proposal_message(&_presentation_proposal()).unwrap());
+        assert_eq!(_presentation_request().to_a2a_message(), protocol.request_message(&_presentation_request()).unwrap());
+        assert_eq!(_presentation().to_a2a_message(), protocol.presentation_message(&_presentation()).unwrap());
+        assert_eq!(A2AMessage::PresentationAck(_ack()), protocol.ack_message(_ack()));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_presentation_protocol_v2_messages_work() {
+        let protocol = PresentationProtocol::V2(PresentationFormat::Indy);
+
+        assert_match!(A2AMessage::PresentationProposalV2(_), protocol.proposal_message(&_presentation_proposal()).unwrap());
+        assert_match!(A2AMessage::PresentationRequestV2(_), protocol.request_message(&_presentation_request()).unwrap());
+        assert_match!(A2AMessage::PresentationV2(_), protocol.presentation_message(&_presentation()).unwrap());
+        assert_eq!(A2AMessage::PresentationAckV2(_ack()), protocol.ack_message(_ack()));
+    }
+}
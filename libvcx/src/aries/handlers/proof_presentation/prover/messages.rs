@@ -3,11 +3,11 @@ use aries::messages::error::ProblemReport;
 use aries::messages::proof_presentation::presentation::Presentation;
 use aries::messages::proof_presentation::presentation_ack::PresentationAck;
 use aries::messages::proof_presentation::presentation_proposal::PresentationPreview;
-use aries::messages::proof_presentation::presentation_request::PresentationRequestData;
+use aries::messages::proof_presentation::presentation_request::PresentationRequest;
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, AsRefStr)]
 pub enum ProverMessages {
-    PresentationRequestReceived(PresentationRequestData),
+    PresentationRequestReceived(PresentationRequest),
     RejectPresentationRequest((u32, String)),
     SetPresentation(Presentation),
     PreparePresentation((String, String)),
@@ -15,15 +15,22 @@ pub enum ProverMessages {
     PresentationAckReceived(PresentationAck),
     PresentationRejectReceived(ProblemReport),
     ProposePresentation((u32, PresentationPreview)),
+    SendPresentationProposal(u32),
     Unknown,
 }
 
 impl From<A2AMessage> for ProverMessages {
     fn from(msg: A2AMessage) -> Self {
         match msg {
-            A2AMessage::Ack(ack) | A2AMessage::PresentationAck(ack) => {
+            A2AMessage::Ack(ack) | A2AMessage::PresentationAck(ack) | A2AMessage::PresentationAckV2(ack) => {
                 ProverMessages::PresentationAckReceived(ack)
             }
+            A2AMessage::PresentationRequest(request) => {
+                ProverMessages::PresentationRequestReceived(request)
+            }
+            A2AMessage::PresentationRequestV2(request) => {
+                ProverMessages::PresentationRequestReceived(request.into())
+            }
             A2AMessage::CommonProblemReport(report) => {
                 ProverMessages::PresentationRejectReceived(report)
             }
@@ -32,4 +39,16 @@ impl From<A2AMessage> for ProverMessages {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+impl ProverMessages {
+    /// Id of the received message.
+    pub fn message_id(&self) -> Option<String> {
+        match self {
+            ProverMessages::PresentationRequestReceived(request) => Some(request.id.0.clone()),
+            ProverMessages::PresentationAckReceived(ack) => Some(ack.id.0.clone()),
+            ProverMessages::PresentationRejectReceived(problem_report) => Some(problem_report.id.0.clone()),
+            _ => None
+        }
+    }
+}
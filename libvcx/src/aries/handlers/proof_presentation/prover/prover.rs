@@ -2,13 +2,15 @@ use std::convert::TryInto;
 
 use ::{connection, settings};
 use error::prelude::*;
+use history::History;
 use messages::proofs::proof_message::ProofMessage;
-use utils::libindy::anoncreds;
+use aries::handlers::proof_presentation::protocol::PresentationProtocol;
 use aries::handlers::proof_presentation::prover::messages::ProverMessages;
 use aries::messages::a2a::A2AMessage;
 use aries::messages::proof_presentation::presentation::Presentation;
-use aries::messages::proof_presentation::presentation_proposal::PresentationPreview;
+use aries::messages::proof_presentation::presentation_proposal::{PresentationPreview, PresentationProposal};
 use aries::messages::proof_presentation::presentation_request::PresentationRequest;
+use aries::messages::proof_presentation::v20::presentation_request::PresentationRequestV2;
 use aries::handlers::proof_presentation::prover::state_machine::ProverSM;
 
 
@@ -25,6 +27,37 @@ impl Prover {
         })
     }
 
+    pub fn create_v2(source_id: &str, presentation_request: PresentationRequestV2) -> VcxResult<Prover> {
+        trace!("Prover::create_v2 >>> source_id: {}, presentation_request: {:?}", source_id, presentation_request);
+
+        let format = presentation_request.format()
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Presentation Request does not contain attachment of supported format: {:?}", presentation_request.formats)))?;
+
+        Ok(Prover {
+            prover_sm: ProverSM::new_with_protocol(presentation_request.into(), PresentationProtocol::V2(format), source_id.to_string()),
+        })
+    }
+
+    pub fn create_with_proposal(source_id: &str, presentation_preview: PresentationPreview, comment: Option<String>) -> VcxResult<Prover> {
+        trace!("Prover::create_with_proposal >>> source_id: {}, presentation_preview: {:?}", source_id, presentation_preview);
+
+        let mut presentation_proposal = PresentationProposal::create()
+            .set_presentation_preview(presentation_preview);
+
+        if let Some(comment) = comment {
+            presentation_proposal = presentation_proposal.set_comment(comment);
+        }
+
+        Ok(Prover {
+            prover_sm: ProverSM::new_with_proposal(presentation_proposal, source_id.to_string()),
+        })
+    }
+
+    pub fn send_proposal(&mut self, connection_handle: u32) -> VcxResult<()> {
+        trace!("Prover::send_proposal >>> connection_handle: {}", connection_handle);
+        self.step(ProverMessages::SendPresentationProposal(connection_handle))
+    }
+
     pub fn state(&self) -> u32 { self.prover_sm.state() }
 
     pub fn presentation_status(&self) -> u32 {
@@ -35,7 +68,7 @@ impl Prover {
     pub fn retrieve_credentials(&self) -> VcxResult<String> {
         trace!("Prover::retrieve_credentials >>>");
         let presentation_request = self.presentation_request_data()?;
-        anoncreds::libindy_prover_get_credentials_for_proof_req(&presentation_request)
+        self.prover_sm.protocol().handler().retrieve_credentials(&presentation_request)
     }
 
     pub fn generate_presentation(&mut self, credentials: String, self_attested_attrs: String) -> VcxResult<()> {
@@ -78,7 +111,8 @@ impl Prover {
         trace!("Prover::update_state >>> found messages: {:?}", messages);
 
         if let Some((uid, message)) = self.prover_sm.find_message_to_handle(messages) {
-            self.handle_message(message.into())?;
+            self.handle_message(message.clone().into())?;
+            connection::record_processed(connection_handle, &message)?;
             connection::update_message_status(connection_handle, uid)?;
         };
 
@@ -101,20 +135,18 @@ impl Prover {
         self.step(message)
     }
 
-    pub fn get_presentation_request(connection_handle: u32, msg_id: &str) -> VcxResult<PresentationRequest> {
+    pub fn get_presentation_request(connection_handle: u32, msg_id: &str) -> VcxResult<A2AMessage> {
         trace!("Prover::get_presentation_request >>> connection_handle: {:?}, msg_id: {:?}", connection_handle, msg_id);
 
         let message = connection::get_message_by_id(connection_handle, msg_id.to_string())?;
 
-        let presentation_request: PresentationRequest = match message {
-            A2AMessage::PresentationRequest(presentation_request) => presentation_request,
+        match message {
+            A2AMessage::PresentationRequest(_) | A2AMessage::PresentationRequestV2(_) => Ok(message),
             msg => {
-                return Err(VcxError::from_msg(VcxErrorKind::InvalidMessages,
-                                              format!("Message of different type was received: {:?}", msg)));
+                Err(VcxError::from_msg(VcxErrorKind::InvalidMessages,
+                                       format!("Message of different type was received: {:?}", msg)))
             }
-        };
-
-        Ok(presentation_request)
+        }
     }
 
     pub fn get_presentation_request_messages(connection_handle: u32) -> VcxResult<Vec<A2AMessage>> {
@@ -125,7 +157,7 @@ impl Prover {
                 .into_iter()
                 .filter_map(|(_, message)| {
                     match message {
-                        A2AMessage::PresentationRequest(_) => Some(message),
+                        A2AMessage::PresentationRequest(_) | A2AMessage::PresentationRequestV2(_) => Some(message),
                         _ => None
                     }
                 })
@@ -135,11 +167,17 @@ impl Prover {
     }
 
     pub fn presentation_request_data(&self) -> VcxResult<String> {
-        self.prover_sm.presentation_request().request_presentations_attach.content()
+        self.prover_sm.presentation_request()?.request_presentations_attach.content()
     }
 
     pub fn get_source_id(&self) -> String { self.prover_sm.source_id() }
 
+    pub fn get_thread_id(&self) -> String { self.prover_sm.thread_id() }
+
+    pub fn get_connection_handle(&self) -> Option<u32> { self.prover_sm.connection_handle().ok() }
+
+    pub fn get_history(&self) -> &History { self.prover_sm.history() }
+
     pub fn step(&mut self, message: ProverMessages) -> VcxResult<()> {
         self.prover_sm = self.prover_sm.clone().step(message)?;
         Ok(())
@@ -3,6 +3,8 @@ use std::collections::HashMap;
 use api::VcxStateType;
 use connection;
 use error::prelude::*;
+use history::{History, Transition};
+use aries::handlers::proof_presentation::protocol::PresentationProtocol;
 use aries::handlers::proof_presentation::prover::messages::ProverMessages;
 use aries::messages::a2a::A2AMessage;
 use aries::messages::error::ProblemReport;
@@ -11,6 +13,8 @@ use aries::messages::proof_presentation::presentation_proposal::{PresentationPre
 use aries::messages::proof_presentation::presentation_request::PresentationRequest;
 use aries::messages::status::Status;
 use aries::handlers::proof_presentation::prover::states::initial::InitialState;
+use aries::handlers::proof_presentation::prover::states::proposal_prepared::ProposalPreparedState;
+use aries::handlers::proof_presentation::prover::states::proposal_sent::ProposalSentState;
 use aries::handlers::proof_presentation::prover::states::presentation_prepared::PresentationPreparedState;
 use aries::handlers::proof_presentation::prover::states::presentation_prepared_failed::PresentationPreparationFailedState;
 use aries::handlers::proof_presentation::prover::states::presentation_sent::PresentationSentState;
@@ -23,22 +27,52 @@ pub struct ProverSM {
     source_id: String,
     thread_id: String,
     state: ProverState,
+    #[serde(default)]
+    protocol: PresentationProtocol,
+    #[serde(default)]
+    history: History,
 }
 
 impl ProverSM {
     pub fn new(presentation_request: PresentationRequest, source_id: String) -> ProverSM {
-        ProverSM { source_id, thread_id: presentation_request.id.0.clone(), state: ProverState::Initiated(InitialState { presentation_request }) }
+        ProverSM::new_with_protocol(presentation_request, PresentationProtocol::V1, source_id)
+    }
+
+    /// Creates the state machine for the request received via the given version of present-proof protocol.
+    pub fn new_with_protocol(presentation_request: PresentationRequest, protocol: PresentationProtocol, source_id: String) -> ProverSM {
+        ProverSM {
+            source_id,
+            thread_id: presentation_request.thread_id(),
+            state: ProverState::Initiated(InitialState { presentation_request }),
+            protocol,
+            history: History::default(),
+        }
+    }
+
+    /// Creates the state machine for the exchange started by the prover with the given proposal.
+    pub fn new_with_proposal(presentation_proposal: PresentationProposal, source_id: String) -> ProverSM {
+        ProverSM {
+            source_id,
+            thread_id: presentation_proposal.id.0.clone(),
+            state: ProverState::ProposalPrepared(ProposalPreparedState { presentation_proposal }),
+            protocol: PresentationProtocol::default(),
+            history: History::default(),
+        }
     }
 }
 
 // Possible Transitions:
 //
+// ProposalPrepared -> ProposalSent
+// ProposalSent -> Initial, Finished
 // Initial -> PresentationPrepared, PresentationPreparationFailedState, Finished
 // PresentationPrepared -> PresentationSent, Finished
 // PresentationPreparationFailedState -> Finished
 // PresentationSent -> Finished
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub enum ProverState {
+    ProposalPrepared(ProposalPreparedState),
+    ProposalSent(ProposalSentState),
     Initiated(InitialState),
     PresentationPrepared(PresentationPreparedState),
     PresentationPreparationFailed(PresentationPreparationFailedState),
@@ -52,6 +86,33 @@ impl ProverSM {
 
         for (uid, message) in messages {
             match self.state {
+                ProverState::ProposalPrepared(_) => {
+                    // do not process messages
+                }
+                ProverState::ProposalSent(_) => {
+                    match message {
+                        A2AMessage::PresentationRequest(request) => {
+                            if let Some(ref thread) = request.thread {
+                                if thread.is_reply(&self.thread_id) {
+                                    return Some((uid, A2AMessage::PresentationRequest(request)));
+                                }
+                            }
+                        }
+                        A2AMessage::PresentationRequestV2(request) => {
+                            if let Some(ref thread) = request.thread {
+                                if thread.is_reply(&self.thread_id) {
+                                    return Some((uid, A2AMessage::PresentationRequestV2(request)));
+                                }
+                            }
+                        }
+                        A2AMessage::CommonProblemReport(problem_report) => {
+                            if problem_report.from_thread(&self.thread_id) {
+                                return Some((uid, A2AMessage::CommonProblemReport(problem_report)));
+                            }
+                        }
+                        _ => {}
+                    }
+                }
                 ProverState::Initiated(_) => {
                     match message {
                         A2AMessage::PresentationRequest(_) => {
@@ -68,7 +129,7 @@ impl ProverSM {
                 }
                 ProverState::PresentationSent(_) => {
                     match message {
-                        A2AMessage::Ack(ack) | A2AMessage::PresentationAck(ack) => {
+                        A2AMessage::Ack(ack) | A2AMessage::PresentationAck(ack) | A2AMessage::PresentationAckV2(ack) => {
                             if ack.from_thread(&self.thread_id) {
                                 return Some((uid, A2AMessage::PresentationAck(ack)));
                             }
@@ -93,9 +154,45 @@ impl ProverSM {
     pub fn step(self, message: ProverMessages) -> VcxResult<ProverSM> {
         trace!("ProverSM::step >>> message: {:?}", message);
 
-        let ProverSM { source_id, state, thread_id } = self;
+        let old_state = self.state();
+        let message_type = message.as_ref().to_string();
+        let message_id = message.message_id();
+
+        if let Some(ref message_id) = message_id {
+            if self.history.contains_message(&message_type, message_id) {
+                warn!("ProverSM::step >>> message {} was already processed, it is ignored", message_id);
+                return Ok(self);
+            }
+        }
+
+        let ProverSM { source_id, state, thread_id, mut protocol, history } = self;
 
         let state = match state {
+            ProverState::ProposalPrepared(state) => {
+                match message {
+                    ProverMessages::SendPresentationProposal(connection_handle) => {
+                        protocol = PresentationProtocol::negotiate(connection_handle);
+                        connection::send_message(connection_handle, protocol.proposal_message(&state.presentation_proposal)?)?;
+                        ProverState::ProposalSent((state, connection_handle).into())
+                    }
+                    _ => {
+                        ProverState::ProposalPrepared(state)
+                    }
+                }
+            }
+            ProverState::ProposalSent(state) => {
+                match message {
+                    ProverMessages::PresentationRequestReceived(presentation_request) => {
+                        ProverState::Initiated((state, presentation_request).into())
+                    }
+                    ProverMessages::PresentationRejectReceived(problem_report) => {
+                        ProverState::Finished((state, problem_report).into())
+                    }
+                    _ => {
+                        ProverState::ProposalSent(state)
+                    }
+                }
+            }
             ProverState::Initiated(state) => {
                 match message {
                     ProverMessages::SetPresentation(presentation) => {
@@ -103,7 +200,7 @@ impl ProverSM {
                         ProverState::PresentationPrepared((state, presentation).into())
                     }
                     ProverMessages::PreparePresentation((credentials, self_attested_attrs)) => {
-                        match state.build_presentation(&credentials, &self_attested_attrs) {
+                        match state.build_presentation(protocol.handler(), &credentials, &self_attested_attrs) {
                             Ok(presentation) => {
                                 let presentation = Presentation::create()
                                     .ask_for_ack()
@@ -127,7 +224,7 @@ impl ProverSM {
                         ProverState::Finished(state.into())
                     }
                     ProverMessages::ProposePresentation((connection_handle, preview)) => {
-                        Self::_handle_presentation_proposal(connection_handle, preview, &state.presentation_request, &thread_id)?;
+                        Self::_handle_presentation_proposal(&protocol, connection_handle, preview, &state.presentation_request, &thread_id)?;
                         ProverState::Finished(state.into())
                     }
                     _ => {
@@ -140,11 +237,11 @@ impl ProverSM {
                     ProverMessages::SendPresentation(connection_handle) => {
                         match state.presentation_request.service.clone() {
                             None => {
-                                connection::send_message(connection_handle, state.presentation.to_a2a_message())?;
+                                connection::send_message(connection_handle, protocol.presentation_message(&state.presentation)?)?;
                                 ProverState::PresentationSent((state, connection_handle).into())
                             }
                             Some(service) => {
-                                connection::send_message_to_self_endpoint(state.presentation.to_a2a_message(), &service.into())?;
+                                connection::send_message_to_self_endpoint(protocol.presentation_message(&state.presentation)?, &service.into())?;
                                 ProverState::Finished(state.into())
                             }
                         }
@@ -154,7 +251,7 @@ impl ProverSM {
                         ProverState::Finished(state.into())
                     }
                     ProverMessages::ProposePresentation((connection_handle, preview)) => {
-                        Self::_handle_presentation_proposal(connection_handle, preview, &state.presentation_request, &thread_id)?;
+                        Self::_handle_presentation_proposal(&protocol, connection_handle, preview, &state.presentation_request, &thread_id)?;
                         ProverState::Finished(state.into())
                     }
                     _ => {
@@ -200,7 +297,12 @@ impl ProverSM {
             ProverState::Finished(state) => ProverState::Finished(state)
         };
 
-        Ok(ProverSM { source_id, state, thread_id })
+        let mut prover_sm = ProverSM { source_id, state, thread_id, protocol, history };
+        let transition = Transition::new(old_state, prover_sm.state(), &message_type)
+            .set_message_id(message_id)
+            .set_status(prover_sm.finished_status());
+        prover_sm.history.record(transition);
+        Ok(prover_sm)
     }
 
     fn _handle_reject_presentation_request(connection_handle: u32, reason: &str, presentation_request: &PresentationRequest, thread_id: &str) -> VcxResult<()> {
@@ -216,14 +318,14 @@ impl ProverSM {
         Ok(())
     }
 
-    fn _handle_presentation_proposal(connection_handle: u32, preview: PresentationPreview, presentation_request: &PresentationRequest, thread_id: &str) -> VcxResult<()> {
+    fn _handle_presentation_proposal(protocol: &PresentationProtocol, connection_handle: u32, preview: PresentationPreview, presentation_request: &PresentationRequest, thread_id: &str) -> VcxResult<()> {
         let proposal = PresentationProposal::create()
             .set_presentation_preview(preview)
             .set_thread_id(thread_id);
 
         match presentation_request.service.clone() {
-            None => connection::send_message(connection_handle, proposal.to_a2a_message())?,
-            Some(service) => connection::send_message_to_self_endpoint(proposal.to_a2a_message(), &service.into())?
+            None => connection::send_message(connection_handle, protocol.proposal_message(&proposal)?)?,
+            Some(service) => connection::send_message_to_self_endpoint(protocol.proposal_message(&proposal)?, &service.into())?
         }
 
         Ok(())
@@ -231,8 +333,16 @@ impl ProverSM {
 
     pub fn source_id(&self) -> String { self.source_id.clone() }
 
+    pub fn thread_id(&self) -> String { self.thread_id.clone() }
+
+    pub fn protocol(&self) -> &PresentationProtocol { &self.protocol }
+
+    pub fn history(&self) -> &History { &self.history }
+
     pub fn state(&self) -> u32 {
         match self.state {
+            ProverState::ProposalPrepared(_) => VcxStateType::VcxStateInitialized as u32,
+            ProverState::ProposalSent(_) => VcxStateType::VcxStateProposalSent as u32,
             ProverState::Initiated(_) => VcxStateType::VcxStateRequestReceived as u32,
             ProverState::PresentationPrepared(_) => VcxStateType::VcxStateRequestReceived as u32,
             ProverState::PresentationPreparationFailed(_) => VcxStateType::VcxStateRequestReceived as u32,
@@ -249,6 +359,8 @@ impl ProverSM {
     pub fn has_transitions(&self) -> bool {
         trace!("Prover::states::has_transitions >> state: {:?}", self.state);
         match self.state {
+            ProverState::ProposalPrepared(_) => false,
+            ProverState::ProposalSent(_) => true,
             ProverState::Initiated(_) => false,
             ProverState::PresentationPrepared(_) => true,
             ProverState::PresentationPreparationFailed(_) => true,
@@ -264,8 +376,17 @@ impl ProverSM {
         }
     }
 
+    fn finished_status(&self) -> Option<&Status> {
+        match self.state {
+            ProverState::Finished(ref state) => Some(&state.status),
+            _ => None
+        }
+    }
+
     pub fn connection_handle(&self) -> VcxResult<u32> {
         match self.state {
+            ProverState::ProposalPrepared(_) => Err(VcxError::from_msg(VcxErrorKind::NotReady, "Connection handle isn't set")),
+            ProverState::ProposalSent(ref state) => Ok(state.connection_handle),
             ProverState::Initiated(_) => Err(VcxError::from_msg(VcxErrorKind::NotReady, "Connection handle isn't set")),
             ProverState::PresentationPrepared(_) => Err(VcxError::from_msg(VcxErrorKind::NotReady, "Connection handle isn't set")),
             ProverState::PresentationPreparationFailed(_) => Err(VcxError::from_msg(VcxErrorKind::NotReady, "Connection handle isn't set")),
@@ -276,6 +397,9 @@ impl ProverSM {
 
     pub fn set_connection_handle(&mut self, connection_handle: u32) {
         match self.state {
+            ProverState::ProposalSent(ref mut state) => {
+                state.connection_handle = connection_handle;
+            },
             ProverState::PresentationSent(ref mut state) => {
                 state.connection_handle = connection_handle;
             },
@@ -287,18 +411,30 @@ impl ProverSM {
     }
 
 
-    pub fn presentation_request(&self) -> &PresentationRequest {
+    pub fn presentation_request(&self) -> VcxResult<&PresentationRequest> {
         match self.state {
-            ProverState::Initiated(ref state) => &state.presentation_request,
-            ProverState::PresentationPrepared(ref state) => &state.presentation_request,
-            ProverState::PresentationPreparationFailed(ref state) => &state.presentation_request,
-            ProverState::PresentationSent(ref state) => &state.presentation_request,
-            ProverState::Finished(ref state) => &state.presentation_request,
+            ProverState::ProposalPrepared(_) => Err(VcxError::from_msg(VcxErrorKind::NotReady, "Presentation Request is not received yet")),
+            ProverState::ProposalSent(_) => Err(VcxError::from_msg(VcxErrorKind::NotReady, "Presentation Request is not received yet")),
+            ProverState::Initiated(ref state) => Ok(&state.presentation_request),
+            ProverState::PresentationPrepared(ref state) => Ok(&state.presentation_request),
+            ProverState::PresentationPreparationFailed(ref state) => Ok(&state.presentation_request),
+            ProverState::PresentationSent(ref state) => Ok(&state.presentation_request),
+            ProverState::Finished(ref state) => Ok(&state.presentation_request),
+        }
+    }
+
+    pub fn presentation_proposal(&self) -> VcxResult<&PresentationProposal> {
+        match self.state {
+            ProverState::ProposalPrepared(ref state) => Ok(&state.presentation_proposal),
+            ProverState::ProposalSent(ref state) => Ok(&state.presentation_proposal),
+            _ => Err(VcxError::from_msg(VcxErrorKind::NotReady, "Presentation Proposal is not created"))
         }
     }
 
     pub fn presentation(&self) -> VcxResult<&Presentation> {
         match self.state {
+            ProverState::ProposalPrepared(_) => Err(VcxError::from_msg(VcxErrorKind::NotReady, "Presentation is not created yet")),
+            ProverState::ProposalSent(_) => Err(VcxError::from_msg(VcxErrorKind::NotReady, "Presentation is not created yet")),
             ProverState::Initiated(_) => Err(VcxError::from_msg(VcxErrorKind::NotReady, "Presentation is not created yet")),
             ProverState::PresentationPrepared(ref state) => Ok(&state.presentation),
             ProverState::PresentationPreparationFailed(_) => Err(VcxError::from_msg(VcxErrorKind::NotReady, "Presentation is not created yet")),
@@ -316,6 +452,8 @@ pub mod test {
     use aries::messages::proof_presentation::presentation_proposal::tests::{_presentation_preview, _presentation_proposal};
     use aries::messages::proof_presentation::presentation_request::tests::{_presentation_request, _presentation_request_with_service};
     use aries::messages::proof_presentation::test::{_ack, _problem_report};
+    use aries::messages::proof_presentation::v20::PresentationFormat;
+    use aries::messages::proof_presentation::v20::presentation_request::PresentationRequestV2;
     use aries::test::source_id;
 
     use super::*;
@@ -324,7 +462,16 @@ pub mod test {
         ProverSM::new(_presentation_request(), source_id())
     }
 
+    pub fn _prover_sm_with_proposal() -> ProverSM {
+        ProverSM::new_with_proposal(_presentation_proposal(), source_id())
+    }
+
     impl ProverSM {
+        fn to_proposal_sent_state(mut self) -> ProverSM {
+            self = self.step(ProverMessages::SendPresentationProposal(mock_connection())).unwrap();
+            self
+        }
+
         fn to_presentation_prepared_state(mut self) -> ProverSM {
             self = self.step(ProverMessages::PreparePresentation((_credentials(), _self_attested()))).unwrap();
             self
@@ -378,6 +525,19 @@ pub mod test {
             assert_match!(ProverState::Initiated(_), prover_sm.state);
             assert_eq!(source_id(), prover_sm.source_id());
         }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_prover_new_with_proposal() {
+            let _setup = SetupAriesMocks::init();
+
+            let prover_sm = _prover_sm_with_proposal();
+
+            assert_match!(ProverState::ProposalPrepared(_), prover_sm.state);
+            assert_eq!(source_id(), prover_sm.source_id());
+            assert_eq!(_presentation_proposal().id.0, prover_sm.thread_id);
+            assert_eq!(VcxErrorKind::NotReady, prover_sm.presentation_request().unwrap_err().kind());
+        }
     }
 
     mod step {
@@ -385,6 +545,78 @@ pub mod test {
         use utils::constants::CREDS_FROM_PROOF_REQ;
         use utils::mockdata::mock_settings::MockBuilder;
 
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_prover_handle_send_presentation_proposal_message_from_proposal_prepared_state() {
+            let _setup = SetupAriesMocks::init();
+
+            let mut prover_sm = _prover_sm_with_proposal();
+            prover_sm = prover_sm.step(ProverMessages::SendPresentationProposal(mock_connection())).unwrap();
+
+            assert_match!(ProverState::ProposalSent(_), prover_sm.state);
+            assert_eq!(mock_connection(), prover_sm.connection_handle().unwrap());
+            assert_eq!(&_presentation_proposal(), prover_sm.presentation_proposal().unwrap());
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_prover_handle_other_messages_from_proposal_prepared_state() {
+            let _setup = SetupAriesMocks::init();
+
+            let mut prover_sm = _prover_sm_with_proposal();
+
+            prover_sm = prover_sm.step(ProverMessages::PresentationRequestReceived(_presentation_request())).unwrap();
+            assert_match!(ProverState::ProposalPrepared(_), prover_sm.state);
+
+            prover_sm = prover_sm.step(ProverMessages::SendPresentation(mock_connection())).unwrap();
+            assert_match!(ProverState::ProposalPrepared(_), prover_sm.state);
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_prover_handle_presentation_request_received_message_from_proposal_sent_state() {
+            let _setup = SetupAriesMocks::init();
+
+            let mut prover_sm = _prover_sm_with_proposal().to_proposal_sent_state();
+            let thread_id = prover_sm.thread_id.clone();
+
+            let presentation_request = _presentation_request().set_thread_id(&thread_id);
+            prover_sm = prover_sm.step(ProverMessages::PresentationRequestReceived(presentation_request.clone())).unwrap();
+
+            assert_match!(ProverState::Initiated(_), prover_sm.state);
+            assert_eq!(thread_id, prover_sm.thread_id);
+            assert_eq!(&presentation_request, prover_sm.presentation_request().unwrap());
+
+            prover_sm = prover_sm.step(ProverMessages::PreparePresentation((_credentials(), _self_attested()))).unwrap();
+            assert_eq!(thread_id, prover_sm.presentation().unwrap().thread.thid.clone().unwrap());
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_prover_handle_presentation_reject_received_message_from_proposal_sent_state() {
+            let _setup = SetupAriesMocks::init();
+
+            let mut prover_sm = _prover_sm_with_proposal().to_proposal_sent_state();
+            prover_sm = prover_sm.step(ProverMessages::PresentationRejectReceived(_problem_report())).unwrap();
+
+            assert_match!(ProverState::Finished(_), prover_sm.state);
+            assert_eq!(Status::Failed(_problem_report()).code(), prover_sm.presentation_status());
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_prover_handle_other_messages_from_proposal_sent_state() {
+            let _setup = SetupAriesMocks::init();
+
+            let mut prover_sm = _prover_sm_with_proposal().to_proposal_sent_state();
+
+            prover_sm = prover_sm.step(ProverMessages::PresentationAckReceived(_ack())).unwrap();
+            assert_match!(ProverState::ProposalSent(_), prover_sm.state);
+
+            prover_sm = prover_sm.step(ProverMessages::SendPresentation(mock_connection())).unwrap();
+            assert_match!(ProverState::ProposalSent(_), prover_sm.state);
+        }
+
         #[test]
         #[cfg(feature = "general_test")]
         fn test_prover_init() {
@@ -466,6 +698,21 @@ pub mod test {
             assert_match!(ProverState::PresentationSent(_), prover_sm.state);
         }
 
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_prover_handle_send_presentation_message_from_presentation_prepared_state_for_v2_protocol() {
+            let _setup = SetupAriesMocks::init();
+
+            let mut prover_sm = ProverSM::new_with_protocol(_presentation_request(), PresentationProtocol::V2(PresentationFormat::Indy), source_id());
+            prover_sm = prover_sm.step(ProverMessages::PreparePresentation((_credentials(), _self_attested()))).unwrap();
+            prover_sm = prover_sm.step(ProverMessages::SendPresentation(mock_connection())).unwrap();
+            assert_match!(ProverState::PresentationSent(_), prover_sm.state);
+
+            prover_sm = prover_sm.step(A2AMessage::PresentationAckV2(_ack()).into()).unwrap();
+            assert_match!(ProverState::Finished(_), prover_sm.state);
+            assert_eq!(Status::Success.code(), prover_sm.presentation_status());
+        }
+
         #[test]
         #[cfg(feature = "general_test")]
         fn test_prover_handle_send_presentation_message_from_presentation_prepared_state_for_presentation_request_contains_service_decorator() {
@@ -622,6 +869,61 @@ pub mod test {
     mod find_message_to_handle {
         use super::*;
 
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_prover_find_message_to_handle_from_proposal_sent_state() {
+            let _setup = SetupAriesMocks::init();
+
+            let prover = _prover_sm_with_proposal().to_proposal_sent_state();
+            let thread_id = prover.thread_id.clone();
+
+            // Presentation Request
+            {
+                let messages = map!(
+                    "key_1".to_string() => A2AMessage::PresentationProposal(_presentation_proposal()),
+                    "key_2".to_string() => A2AMessage::PresentationRequest(_presentation_request().set_thread_id(&thread_id))
+                );
+
+                let (uid, message) = prover.find_message_to_handle(messages).unwrap();
+                assert_eq!("key_2", uid);
+                assert_match!(A2AMessage::PresentationRequest(_), message);
+            }
+
+            // Presentation Request of present-proof 2.0
+            {
+                let request = PresentationRequestV2::from_v1(&_presentation_request().set_thread_id(&thread_id), &PresentationFormat::Indy).unwrap();
+                let messages = map!(
+                    "key_1".to_string() => A2AMessage::PresentationRequestV2(request)
+                );
+
+                let (uid, message) = prover.find_message_to_handle(messages).unwrap();
+                assert_eq!("key_1", uid);
+                assert_match!(A2AMessage::PresentationRequestV2(_), message);
+            }
+
+            // Problem Report
+            {
+                let messages = map!(
+                    "key_1".to_string() => A2AMessage::Presentation(_presentation()),
+                    "key_2".to_string() => A2AMessage::CommonProblemReport(_problem_report().set_thread_id(&thread_id))
+                );
+
+                let (uid, message) = prover.find_message_to_handle(messages).unwrap();
+                assert_eq!("key_2", uid);
+                assert_match!(A2AMessage::CommonProblemReport(_), message);
+            }
+
+            // No messages for different Thread ID
+            {
+                let messages = map!(
+                    "key_1".to_string() => A2AMessage::PresentationRequest(_presentation_request()),
+                    "key_2".to_string() => A2AMessage::CommonProblemReport(_problem_report())
+                );
+
+                assert!(prover.find_message_to_handle(messages).is_none());
+            }
+        }
+
         #[test]
         #[cfg(feature = "general_test")]
         fn test_prover_find_message_to_handle_from_initiated_state() {
@@ -684,6 +986,18 @@ pub mod test {
                 assert_match!(A2AMessage::PresentationAck(_), message);
             }
 
+            // Ack of present-proof 2.0
+            {
+                let messages = map!(
+                    "key_1".to_string() => A2AMessage::PresentationProposal(_presentation_proposal()),
+                    "key_2".to_string() => A2AMessage::PresentationAckV2(_ack())
+                );
+
+                let (uid, message) = prover.find_message_to_handle(messages).unwrap();
+                assert_eq!("key_2", uid);
+                assert_match!(A2AMessage::PresentationAck(_), message);
+            }
+
             // Problem Report
             {
                 let messages = map!(
@@ -750,6 +1064,8 @@ pub mod test {
         fn test_get_state() {
             let _setup = SetupAriesMocks::init();
 
+            assert_eq!(VcxStateType::VcxStateInitialized as u32, _prover_sm_with_proposal().state());
+            assert_eq!(VcxStateType::VcxStateProposalSent as u32, _prover_sm_with_proposal().to_proposal_sent_state().state());
             assert_eq!(VcxStateType::VcxStateRequestReceived as u32, _prover_sm().state());
             assert_eq!(VcxStateType::VcxStateRequestReceived as u32, _prover_sm().to_presentation_prepared_state().state());
             assert_eq!(VcxStateType::VcxStateOfferSent as u32, _prover_sm().to_presentation_sent_state().state());
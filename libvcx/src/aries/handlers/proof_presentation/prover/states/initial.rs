@@ -1,5 +1,5 @@
-use disclosed_proof_utils::generate_indy_proof;
 use error::prelude::*;
+use aries::handlers::proof_presentation::formats::PresentationFormatHandler;
 use aries::handlers::proof_presentation::prover::states::presentation_prepared::PresentationPreparedState;
 use aries::handlers::proof_presentation::prover::states::presentation_prepared_failed::PresentationPreparationFailedState;
 use aries::messages::error::ProblemReport;
@@ -13,10 +13,10 @@ pub struct InitialState {
 
 
 impl InitialState {
-    pub fn build_presentation(&self, credentials: &str, self_attested_attrs: &str) -> VcxResult<String> {
-        generate_indy_proof(credentials,
-                                            self_attested_attrs,
-                                            &self.presentation_request.request_presentations_attach.content()?)
+    pub fn build_presentation(&self, handler: &dyn PresentationFormatHandler, credentials: &str, self_attested_attrs: &str) -> VcxResult<String> {
+        handler.create_presentation(&self.presentation_request.request_presentations_attach.content()?,
+                                    credentials,
+                                    self_attested_attrs)
     }
 }
 
@@ -1,4 +1,6 @@
 pub(super) mod initial;
+pub(super) mod proposal_prepared;
+pub(super) mod proposal_sent;
 pub(super) mod presentation_prepared;
 pub(super) mod presentation_sent;
 pub(super) mod presentation_prepared_failed;
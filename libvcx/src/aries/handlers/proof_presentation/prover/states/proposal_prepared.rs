@@ -0,0 +1,17 @@
+use aries::handlers::proof_presentation::prover::states::proposal_sent::ProposalSentState;
+use aries::messages::proof_presentation::presentation_proposal::PresentationProposal;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProposalPreparedState {
+    pub presentation_proposal: PresentationProposal,
+}
+
+impl From<(ProposalPreparedState, u32)> for ProposalSentState {
+    fn from((state, connection_handle): (ProposalPreparedState, u32)) -> Self {
+        trace!("transit state from ProposalPreparedState to ProposalSentState");
+        ProposalSentState {
+            presentation_proposal: state.presentation_proposal,
+            connection_handle,
+        }
+    }
+}
@@ -0,0 +1,33 @@
+use aries::handlers::proof_presentation::prover::states::finished::FinishedState;
+use aries::handlers::proof_presentation::prover::states::initial::InitialState;
+use aries::messages::error::ProblemReport;
+use aries::messages::proof_presentation::presentation_proposal::PresentationProposal;
+use aries::messages::proof_presentation::presentation_request::PresentationRequest;
+use aries::messages::status::Status;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProposalSentState {
+    pub presentation_proposal: PresentationProposal,
+    pub connection_handle: u32,
+}
+
+impl From<(ProposalSentState, PresentationRequest)> for InitialState {
+    fn from((_state, presentation_request): (ProposalSentState, PresentationRequest)) -> Self {
+        trace!("transit state from ProposalSentState to InitialState");
+        InitialState {
+            presentation_request,
+        }
+    }
+}
+
+impl From<(ProposalSentState, ProblemReport)> for FinishedState {
+    fn from((state, problem_report): (ProposalSentState, ProblemReport)) -> Self {
+        trace!("transit state from ProposalSentState to FinishedState");
+        FinishedState {
+            connection_handle: state.connection_handle,
+            presentation_request: PresentationRequest::create(),
+            presentation: Default::default(),
+            status: Status::Failed(problem_report),
+        }
+    }
+}
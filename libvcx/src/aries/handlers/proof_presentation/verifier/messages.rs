@@ -2,13 +2,17 @@ use aries::messages::a2a::A2AMessage;
 use aries::messages::error::ProblemReport;
 use aries::messages::proof_presentation::presentation::Presentation;
 use aries::messages::proof_presentation::presentation_proposal::PresentationProposal;
+use aries::messages::proof_presentation::presentation_request::PresentationRequestData;
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, AsRefStr)]
 pub enum VerifierMessages {
     SendPresentationRequest(u32),
     VerifyPresentation(Presentation),
     PresentationProposalReceived(PresentationProposal),
+    SendCounterPresentationRequest(u32, PresentationRequestData),
+    RejectPresentationProposal(u32, String),
     PresentationRejectReceived(ProblemReport),
+    Expire,
     Unknown,
 }
 
@@ -21,6 +25,12 @@ impl From<A2AMessage> for VerifierMessages {
             A2AMessage::PresentationProposal(presentation_proposal) => {
                 VerifierMessages::PresentationProposalReceived(presentation_proposal)
             }
+            A2AMessage::PresentationV2(presentation) => {
+                VerifierMessages::VerifyPresentation(presentation.into())
+            }
+            A2AMessage::PresentationProposalV2(presentation_proposal) => {
+                VerifierMessages::PresentationProposalReceived(presentation_proposal.into())
+            }
             A2AMessage::CommonProblemReport(report) => {
                 VerifierMessages::PresentationRejectReceived(report)
             }
@@ -29,4 +39,16 @@ impl From<A2AMessage> for VerifierMessages {
             }
         }
     }
-}
\ No newline at end of file
+}
+
+impl VerifierMessages {
+    /// Id of the received message.
+    pub fn message_id(&self) -> Option<String> {
+        match self {
+            VerifierMessages::VerifyPresentation(presentation) => Some(presentation.id.0.clone()),
+            VerifierMessages::PresentationProposalReceived(proposal) => Some(proposal.id.0.clone()),
+            VerifierMessages::PresentationRejectReceived(problem_report) => Some(problem_report.id.0.clone()),
+            _ => None
+        }
+    }
+}
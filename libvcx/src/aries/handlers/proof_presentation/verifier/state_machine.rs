@@ -4,25 +4,52 @@ use api::VcxStateType;
 use connection::{get_pw_did, get_their_pw_verkey};
 use connection;
 use error::prelude::*;
+use history::{History, Transition};
+use aries::handlers::proof_presentation::protocol::PresentationProtocol;
 use aries::handlers::proof_presentation::verifier::messages::VerifierMessages;
 use aries::messages::a2a::A2AMessage;
+use aries::messages::a2a::message_family::MessageFamilies;
 use aries::messages::error::ProblemReport;
 use aries::messages::proof_presentation::presentation::Presentation;
+use aries::messages::proof_presentation::presentation_proposal::PresentationProposal;
 use aries::messages::proof_presentation::presentation_request::{PresentationRequest, PresentationRequestData};
 use aries::messages::status::Status;
+use aries::messages::timing;
 use aries::handlers::proof_presentation::verifier::states::initial::InitialState;
 use aries::handlers::proof_presentation::verifier::states::presentation_request_sent::PresentationRequestSentState;
+use aries::handlers::proof_presentation::verifier::states::proposal_received::ProposalReceivedState;
 use aries::handlers::proof_presentation::verifier::states::finished::FinishedState;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct VerifierSM {
     source_id: String,
     state: VerifierState,
+    #[serde(default)]
+    protocol: PresentationProtocol,
+    #[serde(default)]
+    history: History,
 }
 
 impl VerifierSM {
     pub fn new(presentation_request: PresentationRequestData, source_id: String) -> VerifierSM {
-        VerifierSM { source_id, state: VerifierState::Initiated(InitialState { presentation_request_data: presentation_request }) }
+        VerifierSM { source_id, state: VerifierState::Initiated(InitialState { presentation_request_data: presentation_request }), protocol: PresentationProtocol::V1, history: History::default() }
+    }
+
+    /// Starts the exchange from a Presentation Proposal the prover sent without a request.
+    /// The request kept in the state only carries the name and the thread of the proposal, it is never sent.
+    pub fn from_proposal(presentation_proposal: PresentationProposal, protocol: PresentationProtocol, connection_handle: u32, source_id: String) -> VcxResult<VerifierSM> {
+        let thread_id = presentation_proposal.thread.thid.clone()
+            .unwrap_or(presentation_proposal.id.0.clone());
+        let message_id = Some(presentation_proposal.id.0.clone());
+
+        let presentation_request = PresentationRequest::create()
+            .set_request_presentations_attach(&PresentationRequestData::create().set_name(source_id.clone()))?
+            .set_thread_id(&thread_id);
+
+        let state = VerifierState::ProposalReceived(ProposalReceivedState { connection_handle, presentation_request, presentation_proposal });
+        let mut verifier_sm = VerifierSM { source_id, state, protocol, history: History::default() };
+        verifier_sm.history.record(Transition::new(VcxStateType::VcxStateNone as u32, verifier_sm.state(), "PresentationProposalReceived").set_message_id(message_id));
+        Ok(verifier_sm)
     }
 }
 
@@ -30,6 +57,7 @@ impl VerifierSM {
 pub enum VerifierState {
     Initiated(InitialState),
     PresentationRequestSent(PresentationRequestSentState),
+    ProposalReceived(ProposalReceivedState),
     Finished(FinishedState),
 }
 
@@ -61,6 +89,16 @@ impl VerifierSM {
                                 return Some((uid, A2AMessage::PresentationProposal(proposal)));
                             }
                         }
+                        A2AMessage::PresentationV2(presentation) => {
+                            if presentation.from_thread(&self.thread_id()) {
+                                return Some((uid, A2AMessage::PresentationV2(presentation)));
+                            }
+                        }
+                        A2AMessage::PresentationProposalV2(proposal) => {
+                            if proposal.from_thread(&self.thread_id()) {
+                                return Some((uid, A2AMessage::PresentationProposalV2(proposal)));
+                            }
+                        }
                         A2AMessage::CommonProblemReport(problem_report) => {
                             if problem_report.from_thread(&self.thread_id()) {
                                 return Some((uid, A2AMessage::CommonProblemReport(problem_report)));
@@ -69,6 +107,9 @@ impl VerifierSM {
                         _ => {}
                     }
                 }
+                VerifierState::ProposalReceived(_) => {
+                    // do not process message
+                }
                 VerifierState::Finished(_) => {
                     // do not process message
                 }
@@ -81,28 +122,28 @@ impl VerifierSM {
     pub fn step(self, message: VerifierMessages) -> VcxResult<VerifierSM> {
         trace!("VerifierSM::step >>> message: {:?}", message);
 
-        let VerifierSM { source_id, state } = self;
+        let old_state = self.state();
+        let message_type = message.as_ref().to_string();
+        let message_id = message.message_id();
+
+        if let Some(ref message_id) = message_id {
+            if self.history.contains_message(&message_type, message_id) {
+                warn!("VerifierSM::step >>> message {} was already processed, it is ignored", message_id);
+                return Ok(self);
+            }
+        }
+
+        let VerifierSM { source_id, state, mut protocol, history } = self;
 
         let state = match state {
             VerifierState::Initiated(state) => {
                 match message {
                     VerifierMessages::SendPresentationRequest(connection_handle) => {
-                        let my_did = get_pw_did(connection_handle)?;
-                        let remote_did = get_their_pw_verkey(connection_handle)?;
+                        let presentation_request = _build_presentation_request(connection_handle, state.presentation_request_data.clone())?;
 
-                        let presentation_request: PresentationRequestData =
-                            state.presentation_request_data.clone()
-                                .set_format_version_for_did(&my_did, &remote_did)?;
+                        protocol = PresentationProtocol::negotiate(connection_handle);
 
-                        let title = format!("{} wants you to share {}",
-                                            ::settings::get_config_value(::settings::CONFIG_INSTITUTION_NAME)?, presentation_request.name);
-
-                        let presentation_request =
-                            PresentationRequest::create()
-                                .set_comment(title)
-                                .set_request_presentations_attach(&presentation_request)?;
-
-                        connection::send_message(connection_handle, presentation_request.to_a2a_message())?;
+                        connection::send_message(connection_handle, protocol.request_message(&presentation_request)?)?;
                         VerifierState::PresentationRequestSent((state, presentation_request, connection_handle).into())
                     }
                     _ => {
@@ -113,7 +154,7 @@ impl VerifierSM {
             VerifierState::PresentationRequestSent(state) => {
                 match message {
                     VerifierMessages::VerifyPresentation(presentation) => {
-                        match state.verify_presentation(&presentation) {
+                        match state.verify_presentation(&protocol, &presentation) {
                             Ok(()) => {
                                 VerifierState::Finished((state, presentation, RevocationStatus::NonRevoked).into())
                             }
@@ -121,7 +162,7 @@ impl VerifierSM {
                                 let problem_report =
                                     ProblemReport::create()
                                         .set_comment(err.to_string())
-                                        .set_thread_id(&state.presentation_request.id.0);
+                                        .set_thread_id(&state.presentation_request.thread_id());
 
                                 connection::send_message(state.connection_handle, problem_report.to_a2a_message())?;
                                 match err.kind() {
@@ -136,13 +177,19 @@ impl VerifierSM {
                     VerifierMessages::PresentationRejectReceived(problem_report) => {
                         VerifierState::Finished((state, problem_report).into())
                     }
-                    VerifierMessages::PresentationProposalReceived(_) => { // TODO: handle Presentation Proposal
+                    VerifierMessages::PresentationProposalReceived(presentation_proposal) => {
+                        VerifierState::ProposalReceived((state, presentation_proposal).into())
+                    }
+                    VerifierMessages::Expire => {
                         let problem_report =
                             ProblemReport::create()
-                                .set_comment(String::from("PresentationProposal is not supported"))
-                                .set_thread_id(&state.presentation_request.id.0);
+                                .set_comment(String::from("Presentation Request expired"))
+                                .set_thread_id(&state.presentation_request.thread_id());
 
-                        connection::send_message(state.connection_handle, problem_report.to_a2a_message())?;
+                        // the exchange is abandoned even if the prover cannot be notified
+                        if let Err(err) = connection::send_message(state.connection_handle, problem_report.to_a2a_message()) {
+                            warn!("Cannot notify prover about expired Presentation Request: {}", err);
+                        }
                         VerifierState::Finished((state, problem_report).into())
                     }
                     _ => {
@@ -150,20 +197,53 @@ impl VerifierSM {
                     }
                 }
             }
+            VerifierState::ProposalReceived(state) => {
+                match message {
+                    VerifierMessages::SendCounterPresentationRequest(connection_handle, presentation_request_data) => {
+                        let presentation_request = _build_presentation_request(connection_handle, presentation_request_data)?
+                            .set_thread_id(&state.presentation_request.thread_id());
+
+                        connection::send_message(connection_handle, protocol.request_message(&presentation_request)?)?;
+                        VerifierState::PresentationRequestSent((state, presentation_request, connection_handle).into())
+                    }
+                    VerifierMessages::RejectPresentationProposal(connection_handle, reason) => {
+                        let problem_report =
+                            ProblemReport::create()
+                                .set_comment(reason)
+                                .set_thread_id(&state.presentation_request.thread_id());
+
+                        connection::send_message(connection_handle, problem_report.to_a2a_message())?;
+                        VerifierState::Finished((state, problem_report).into())
+                    }
+                    _ => {
+                        VerifierState::ProposalReceived(state)
+                    }
+                }
+            }
             VerifierState::Finished(state) => VerifierState::Finished(state)
         };
 
-        Ok(VerifierSM { source_id, state })
+        let mut verifier_sm = VerifierSM { source_id, state, protocol, history };
+        let transition = Transition::new(old_state, verifier_sm.state(), &message_type)
+            .set_message_id(message_id)
+            .set_status(verifier_sm.finished_status().as_ref());
+        verifier_sm.history.record(transition);
+        Ok(verifier_sm)
     }
 
     pub fn source_id(&self) -> String { self.source_id.clone() }
 
-    pub fn thread_id(&self) -> String { self.presentation_request().map(|request| request.id.0.clone()).unwrap_or_default() }
+    pub fn protocol(&self) -> &PresentationProtocol { &self.protocol }
+
+    pub fn history(&self) -> &History { &self.history }
+
+    pub fn thread_id(&self) -> String { self.presentation_request().map(|request| request.thread_id()).unwrap_or_default() }
 
     pub fn state(&self) -> u32 {
         match self.state {
             VerifierState::Initiated(_) => VcxStateType::VcxStateInitialized as u32,
             VerifierState::PresentationRequestSent(_) => VcxStateType::VcxStateOfferSent as u32,
+            VerifierState::ProposalReceived(_) => VcxStateType::VcxStateProposalReceived as u32,
             VerifierState::Finished(ref status) => {
                 match status.status {
                     Status::Success => VcxStateType::VcxStateAccepted as u32,
@@ -173,10 +253,19 @@ impl VerifierSM {
         }
     }
 
+    /// Whether the prover did not answer the request before its `~timing.expires_time`.
+    pub fn is_expired(&self) -> bool {
+        match self.state {
+            VerifierState::PresentationRequestSent(ref state) => state.presentation_request.expires_time().map(timing::is_expired).unwrap_or(false),
+            _ => false
+        }
+    }
+
     pub fn has_transitions(&self) -> bool {
         match self.state {
             VerifierState::Initiated(_) => false,
             VerifierState::PresentationRequestSent(_) => true,
+            VerifierState::ProposalReceived(_) => false,
             VerifierState::Finished(_) => false,
         }
     }
@@ -202,10 +291,21 @@ impl VerifierSM {
         }
     }
 
+    fn finished_status(&self) -> Option<Status> {
+        match self.state {
+            VerifierState::Finished(ref state) => match state.revocation_status {
+                Some(RevocationStatus::Revoked) => Some(Status::Failed(ProblemReport::create().set_comment(String::from("Revoked credential was used.")))),
+                _ => Some(state.status.clone())
+            },
+            _ => None
+        }
+    }
+
     pub fn connection_handle(&self) -> VcxResult<u32> {
         match self.state {
             VerifierState::Initiated(_) => Err(VcxError::from_msg(VcxErrorKind::NotReady, "Connection handle isn't set")),
             VerifierState::PresentationRequestSent(ref state) => Ok(state.connection_handle),
+            VerifierState::ProposalReceived(ref state) => Ok(state.connection_handle),
             VerifierState::Finished(ref state) => Ok(state.connection_handle),
         }
     }
@@ -214,6 +314,7 @@ impl VerifierSM {
         match self.state {
             VerifierState::Initiated(_) => {},
             VerifierState::PresentationRequestSent(ref mut state) => { state.connection_handle = connection_handle; },
+            VerifierState::ProposalReceived(ref mut state) => { state.connection_handle = connection_handle; },
             VerifierState::Finished(ref mut state) => { state.connection_handle = connection_handle; },
         }
     }
@@ -224,10 +325,18 @@ impl VerifierSM {
                 PresentationRequest::create().set_request_presentations_attach(&state.presentation_request_data)
             }
             VerifierState::PresentationRequestSent(ref state) => Ok(state.presentation_request.clone()),
+            VerifierState::ProposalReceived(ref state) => Ok(state.presentation_request.clone()),
             VerifierState::Finished(ref state) => Ok(state.presentation_request.clone()),
         }
     }
 
+    pub fn presentation_proposal(&self) -> VcxResult<PresentationProposal> {
+        match self.state {
+            VerifierState::ProposalReceived(ref state) => Ok(state.presentation_proposal.clone()),
+            _ => Err(VcxError::from_msg(VcxErrorKind::NotReady, "Presentation Proposal is not received"))
+        }
+    }
+
     pub fn presentation(&self) -> VcxResult<Presentation> {
         match self.state {
             VerifierState::Finished(ref state) => {
@@ -239,6 +348,23 @@ impl VerifierSM {
     }
 }
 
+fn _build_presentation_request(connection_handle: u32, presentation_request_data: PresentationRequestData) -> VcxResult<PresentationRequest> {
+    let my_did = get_pw_did(connection_handle)?;
+    let remote_did = get_their_pw_verkey(connection_handle)?;
+
+    let presentation_request_data = presentation_request_data.set_format_version_for_did(&my_did, &remote_did)?;
+
+    let title = format!("{} wants you to share {}",
+                        ::settings::get_config_value(::settings::CONFIG_INSTITUTION_NAME)?, presentation_request_data.name);
+
+    let presentation_request = PresentationRequest::create()
+        .set_comment(title)
+        .set_request_presentations_attach(&presentation_request_data)?
+        .set_expires_time(timing::protocol_expires_time(&MessageFamilies::PresentProof));
+
+    Ok(presentation_request)
+}
+
 #[cfg(test)]
 pub mod test {
     use utils::devsetup::SetupAriesMocks;
@@ -248,6 +374,8 @@ pub mod test {
     use aries::messages::proof_presentation::presentation_request::tests::_presentation_request;
     use aries::messages::proof_presentation::presentation_request::tests::_presentation_request_data;
     use aries::messages::proof_presentation::test::{_ack, _problem_report};
+    use aries::messages::proof_presentation::v20::PresentationFormat;
+    use aries::messages::proof_presentation::v20::presentation::tests::_presentation_v2;
     use aries::test::source_id;
 
     use super::*;
@@ -262,6 +390,12 @@ pub mod test {
             self
         }
 
+        fn to_proposal_received_state(mut self) -> VerifierSM {
+            self = self.step(VerifierMessages::SendPresentationRequest(mock_connection())).unwrap();
+            self = self.step(VerifierMessages::PresentationProposalReceived(_presentation_proposal())).unwrap();
+            self
+        }
+
         fn to_finished_state(mut self) -> VerifierSM {
             self = self.step(VerifierMessages::SendPresentationRequest(mock_connection())).unwrap();
             self = self.step(VerifierMessages::VerifyPresentation(_presentation())).unwrap();
@@ -282,6 +416,25 @@ pub mod test {
             assert_match!(VerifierState::Initiated(_), verifier_sm.state);
             assert_eq!(source_id(), verifier_sm.source_id());
         }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_verifier_from_proposal() {
+            let _setup = SetupAriesMocks::init();
+
+            let proposal = _presentation_proposal();
+            let mut verifier_sm = VerifierSM::from_proposal(proposal.clone(), PresentationProtocol::V1, mock_connection(), source_id()).unwrap();
+
+            assert_match!(VerifierState::ProposalReceived(_), verifier_sm.state);
+            assert_eq!(proposal, verifier_sm.presentation_proposal().unwrap());
+            assert_eq!(proposal.thread.thid.clone().unwrap(), verifier_sm.thread_id());
+
+            let presentation_request_data = proposal.presentation_proposal.to_proof_request_data("proposal").unwrap();
+            verifier_sm = verifier_sm.step(VerifierMessages::SendCounterPresentationRequest(mock_connection(), presentation_request_data)).unwrap();
+
+            assert_match!(VerifierState::PresentationRequestSent(_), verifier_sm.state);
+            assert_eq!(proposal.thread.thid.unwrap(), verifier_sm.thread_id());
+        }
     }
 
     mod step {
@@ -340,6 +493,38 @@ pub mod test {
             assert_eq!(Status::Success.code(), verifier_sm.presentation_status());
         }
 
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_verifier_ignores_already_processed_presentation_message() {
+            let _setup = SetupAriesMocks::init();
+
+            let mut verifier_sm = _verifier_sm();
+            verifier_sm = verifier_sm.step(VerifierMessages::SendPresentationRequest(mock_connection())).unwrap();
+            verifier_sm.history.record(Transition::new(VcxStateType::VcxStateOfferSent as u32, VcxStateType::VcxStateAccepted as u32, "VerifyPresentation")
+                .set_message_id(Some(_presentation().id.0)));
+
+            verifier_sm = verifier_sm.step(VerifierMessages::VerifyPresentation(_presentation())).unwrap();
+
+            assert_match!(VerifierState::PresentationRequestSent(_), verifier_sm.state);
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_prover_handle_verify_presentation_message_from_presentation_request_sent_state_for_v2_protocol() {
+            let _setup = SetupAriesMocks::init();
+            let _mock_builder = MockBuilder::init().
+                set_mock_result_for_validate_indy_proof(Ok(true));
+
+            let mut verifier_sm = _verifier_sm();
+            verifier_sm = verifier_sm.step(VerifierMessages::SendPresentationRequest(mock_connection())).unwrap();
+            verifier_sm.protocol = PresentationProtocol::V2(PresentationFormat::Indy);
+            verifier_sm = verifier_sm.step(A2AMessage::PresentationV2(_presentation_v2()).into()).unwrap();
+
+            assert_match!(VerifierState::Finished(_), verifier_sm.state);
+            assert_eq!(Status::Success.code(), verifier_sm.presentation_status());
+            assert_match!(A2AMessage::PresentationV2(_), verifier_sm.protocol().presentation_message(&verifier_sm.presentation().unwrap()).unwrap());
+        }
+
         #[test]
         #[cfg(feature = "general_test")]
         fn test_prover_handle_invalid_presentation_message() {
@@ -377,10 +562,52 @@ pub mod test {
             verifier_sm = verifier_sm.step(VerifierMessages::SendPresentationRequest(mock_connection())).unwrap();
             verifier_sm = verifier_sm.step(VerifierMessages::PresentationProposalReceived(_presentation_proposal())).unwrap();
 
+            assert_match!(VerifierState::ProposalReceived(_), verifier_sm.state);
+            assert_eq!(_presentation_proposal(), verifier_sm.presentation_proposal().unwrap());
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_verifier_handle_send_counter_presentation_request_message_from_proposal_received_state() {
+            let _setup = SetupAriesMocks::init();
+
+            let mut verifier_sm = _verifier_sm().to_proposal_received_state();
+            let thread_id = verifier_sm.thread_id();
+
+            let presentation_request_data = _presentation_proposal().presentation_proposal.to_proof_request_data("proposal").unwrap();
+            verifier_sm = verifier_sm.step(VerifierMessages::SendCounterPresentationRequest(mock_connection(), presentation_request_data)).unwrap();
+
+            assert_match!(VerifierState::PresentationRequestSent(_), verifier_sm.state);
+            assert_eq!(thread_id, verifier_sm.thread_id());
+            assert_ne!(thread_id, verifier_sm.presentation_request().unwrap().id.0);
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_verifier_handle_reject_presentation_proposal_message_from_proposal_received_state() {
+            let _setup = SetupAriesMocks::init();
+
+            let mut verifier_sm = _verifier_sm().to_proposal_received_state();
+            verifier_sm = verifier_sm.step(VerifierMessages::RejectPresentationProposal(mock_connection(), String::from("reason"))).unwrap();
+
             assert_match!(VerifierState::Finished(_), verifier_sm.state);
             assert_eq!(Status::Failed(_problem_report()).code(), verifier_sm.presentation_status());
         }
 
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_verifier_handle_other_messages_from_proposal_received_state() {
+            let _setup = SetupAriesMocks::init();
+
+            let mut verifier_sm = _verifier_sm().to_proposal_received_state();
+
+            verifier_sm = verifier_sm.step(VerifierMessages::VerifyPresentation(_presentation())).unwrap();
+            assert_match!(VerifierState::ProposalReceived(_), verifier_sm.state);
+
+            verifier_sm = verifier_sm.step(VerifierMessages::SendPresentationRequest(mock_connection())).unwrap();
+            assert_match!(VerifierState::ProposalReceived(_), verifier_sm.state);
+        }
+
         #[test]
         #[cfg(feature = "general_test")]
         fn test_prover_handle_presentation_reject_message_from_presentation_request_sent_state() {
@@ -406,6 +633,34 @@ pub mod test {
             assert_match!(VerifierState::PresentationRequestSent(_), verifier_sm.state);
         }
 
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_verifier_handle_expire_message_from_presentation_request_sent_state() {
+            let _setup = SetupAriesMocks::init();
+            set_config_value(settings::CONFIG_PROTOCOL_TIMEOUTS, &json!({"present-proof": 0}).to_string());
+
+            let mut verifier_sm = _verifier_sm().to_presentation_request_sent_state();
+            assert!(verifier_sm.presentation_request().unwrap().expires_time().is_some());
+            assert!(verifier_sm.is_expired());
+
+            verifier_sm = verifier_sm.step(VerifierMessages::Expire).unwrap();
+
+            assert_match!(VerifierState::Finished(_), verifier_sm.state);
+            assert_eq!(Status::Failed(_problem_report()).code(), verifier_sm.presentation_status());
+            assert!(!verifier_sm.is_expired());
+        }
+
+        #[test]
+        #[cfg(feature = "general_test")]
+        fn test_verifier_presentation_request_does_not_expire_without_timeout() {
+            let _setup = SetupAriesMocks::init();
+
+            let verifier_sm = _verifier_sm().to_presentation_request_sent_state();
+
+            assert_eq!(None, verifier_sm.presentation_request().unwrap().expires_time());
+            assert!(!verifier_sm.is_expired());
+        }
+
         #[test]
         #[cfg(feature = "general_test")]
         fn test_prover_handle_messages_from_presentation_finished_state() {
@@ -467,6 +722,19 @@ pub mod test {
                 assert_match!(A2AMessage::Presentation(_), message);
             }
 
+            // Presentation of present-proof 2.0
+            {
+                let messages = map!(
+                    "key_1".to_string() => A2AMessage::PresentationRequest(_presentation_request()),
+                    "key_2".to_string() => A2AMessage::PresentationV2(_presentation_v2()),
+                    "key_3".to_string() => A2AMessage::PresentationAck(_ack())
+                );
+
+                let (uid, message) = verifier.find_message_to_handle(messages).unwrap();
+                assert_eq!("key_2", uid);
+                assert_match!(A2AMessage::PresentationV2(_), message);
+            }
+
             // Presentation Proposal
             {
                 let messages = map!(
@@ -550,6 +818,7 @@ pub mod test {
 
             assert_eq!(VcxStateType::VcxStateInitialized as u32, _verifier_sm().state());
             assert_eq!(VcxStateType::VcxStateOfferSent as u32, _verifier_sm().to_presentation_request_sent_state().state());
+            assert_eq!(VcxStateType::VcxStateProposalReceived as u32, _verifier_sm().to_proposal_received_state().state());
             assert_eq!(VcxStateType::VcxStateAccepted as u32, _verifier_sm().to_finished_state().state());
         }
     }
@@ -1,3 +1,4 @@
 pub(super) mod initial;
 pub(super) mod finished;
-pub(super) mod presentation_request_sent;
\ No newline at end of file
+pub(super) mod presentation_request_sent;
+pub(super) mod proposal_received;
@@ -1,14 +1,15 @@
 use connection;
 use error::{VcxError, VcxErrorKind, VcxResult};
+use aries::handlers::proof_presentation::protocol::PresentationProtocol;
 use aries::handlers::proof_presentation::verifier::states::finished::FinishedState;
+use aries::handlers::proof_presentation::verifier::states::proposal_received::ProposalReceivedState;
 use aries::handlers::proof_presentation::verifier::state_machine::RevocationStatus;
-use aries::messages::a2a::A2AMessage;
 use aries::messages::error::ProblemReport;
 use aries::messages::proof_presentation::presentation::Presentation;
 use aries::messages::proof_presentation::presentation_ack::PresentationAck;
+use aries::messages::proof_presentation::presentation_proposal::PresentationProposal;
 use aries::messages::proof_presentation::presentation_request::PresentationRequest;
 use aries::messages::status::Status;
-use proof_utils::validate_indy_proof;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 pub struct PresentationRequestSentState {
@@ -18,17 +19,17 @@ pub struct PresentationRequestSentState {
 
 
 impl PresentationRequestSentState {
-    pub fn verify_presentation(&self, presentation: &Presentation) -> VcxResult<()> {
-        let valid = validate_indy_proof(&presentation.presentations_attach.content()?,
-                                               &self.presentation_request.request_presentations_attach.content()?)?;
+    pub fn verify_presentation(&self, protocol: &PresentationProtocol, presentation: &Presentation) -> VcxResult<()> {
+        let valid = protocol.handler().verify_presentation(&presentation.presentations_attach.content()?,
+                                                           &self.presentation_request.request_presentations_attach.content()?)?;
 
         if !valid {
             return Err(VcxError::from_msg(VcxErrorKind::InvalidProof, "Presentation verification failed"));
         }
 
         if presentation.please_ack.is_some() {
-            let ack = PresentationAck::create().set_thread_id(&self.presentation_request.id.0);
-            connection::send_message(self.connection_handle, A2AMessage::PresentationAck(ack))?;
+            let ack = PresentationAck::create().set_thread_id(&self.presentation_request.thread_id());
+            connection::send_message(self.connection_handle, protocol.ack_message(ack))?;
         }
 
         Ok(())
@@ -49,6 +50,17 @@ impl From<(PresentationRequestSentState, Presentation, RevocationStatus)> for Fi
     }
 }
 
+impl From<(PresentationRequestSentState, PresentationProposal)> for ProposalReceivedState {
+    fn from((state, presentation_proposal): (PresentationRequestSentState, PresentationProposal)) -> Self {
+        trace!("transit state from PresentationRequestSentState to ProposalReceivedState");
+        ProposalReceivedState {
+            connection_handle: state.connection_handle,
+            presentation_request: state.presentation_request,
+            presentation_proposal,
+        }
+    }
+}
+
 impl From<(PresentationRequestSentState, ProblemReport)> for FinishedState {
     fn from((state, problem_report): (PresentationRequestSentState, ProblemReport)) -> Self {
         trace!("transit state from PresentationRequestSentState to FinishedState");
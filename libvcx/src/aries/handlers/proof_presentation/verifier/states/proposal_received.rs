@@ -0,0 +1,33 @@
+use aries::handlers::proof_presentation::verifier::states::finished::FinishedState;
+use aries::handlers::proof_presentation::verifier::states::presentation_request_sent::PresentationRequestSentState;
+use aries::messages::error::ProblemReport;
+use aries::messages::proof_presentation::presentation_proposal::PresentationProposal;
+use aries::messages::proof_presentation::presentation_request::PresentationRequest;
+use aries::messages::status::Status;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct ProposalReceivedState {
+    pub connection_handle: u32,
+    pub presentation_request: PresentationRequest,
+    pub presentation_proposal: PresentationProposal,
+}
+
+impl From<(ProposalReceivedState, PresentationRequest, u32)> for PresentationRequestSentState {
+    fn from((_state, presentation_request, connection_handle): (ProposalReceivedState, PresentationRequest, u32)) -> Self {
+        trace!("transit state from ProposalReceivedState to PresentationRequestSentState");
+        PresentationRequestSentState { connection_handle, presentation_request }
+    }
+}
+
+impl From<(ProposalReceivedState, ProblemReport)> for FinishedState {
+    fn from((state, problem_report): (ProposalReceivedState, ProblemReport)) -> Self {
+        trace!("transit state from ProposalReceivedState to FinishedState");
+        FinishedState {
+            connection_handle: state.connection_handle,
+            presentation_request: state.presentation_request,
+            presentation: None,
+            status: Status::Failed(problem_report),
+            revocation_status: None,
+        }
+    }
+}
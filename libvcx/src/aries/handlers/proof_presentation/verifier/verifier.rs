@@ -1,5 +1,7 @@
 use ::{connection};
 use error::prelude::*;
+use history::History;
+use aries::handlers::proof_presentation::protocol::PresentationProtocol;
 use aries::handlers::proof_presentation::verifier::messages::VerifierMessages;
 use aries::handlers::proof_presentation::verifier::state_machine::VerifierSM;
 use aries::messages::a2a::A2AMessage;
@@ -33,8 +35,33 @@ impl Verifier {
         })
     }
 
+    pub fn create_from_proposal(source_id: String, presentation_proposal: A2AMessage, connection_handle: u32) -> VcxResult<Verifier> {
+        trace!("Verifier::create_from_proposal >>> source_id: {:?}, presentation_proposal: {:?}, connection_handle: {:?}",
+               source_id, presentation_proposal, connection_handle);
+
+        let (presentation_proposal, protocol) = match presentation_proposal {
+            A2AMessage::PresentationProposal(proposal) => (proposal, PresentationProtocol::V1),
+            A2AMessage::PresentationProposalV2(proposal) => {
+                let format = proposal.format()
+                    .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Presentation Proposal does not contain attachment of supported format: {:?}", proposal.formats)))?;
+                (proposal.into(), PresentationProtocol::V2(format))
+            }
+            message => return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Message is not a Presentation Proposal: {:?}", message)))
+        };
+
+        Ok(Verifier {
+            verifier_sm: VerifierSM::from_proposal(presentation_proposal, protocol, connection_handle, source_id)?,
+        })
+    }
+
     pub fn get_source_id(&self) -> String { self.verifier_sm.source_id() }
 
+    pub fn get_thread_id(&self) -> String { self.verifier_sm.thread_id() }
+
+    pub fn get_connection_handle(&self) -> Option<u32> { self.verifier_sm.connection_handle().ok() }
+
+    pub fn get_history(&self) -> &History { self.verifier_sm.history() }
+
     pub fn state(&self) -> u32 {
         trace!("Verifier::state >>>");
         self.verifier_sm.state()
@@ -60,13 +87,30 @@ impl Verifier {
         let messages = connection::get_messages(connection_handle)?;
 
         if let Some((uid, message)) = self.verifier_sm.find_message_to_handle(messages) {
-            self.handle_message(message.into())?;
+            self.handle_message(message.clone().into())?;
+            connection::record_processed(connection_handle, &message)?;
             connection::update_message_status(connection_handle, uid)?;
+        } else if self.verifier_sm.is_expired() {
+            self.step(VerifierMessages::Expire)?;
         };
 
         Ok(())
     }
 
+    pub fn is_expired(&self) -> bool {
+        self.verifier_sm.is_expired()
+    }
+
+    /// Abandons the exchange if the presentation request expired. Returns whether it was abandoned.
+    pub fn expire(&mut self) -> VcxResult<bool> {
+        trace!("Verifier::expire >>>");
+
+        if !self.verifier_sm.is_expired() { return Ok(false); }
+
+        self.step(VerifierMessages::Expire)?;
+        Ok(true)
+    }
+
     pub fn update_state_with_message(&mut self, message: &str) -> VcxResult<()> {
         trace!("Verifier::update_state_with_message >>> message: {:?}", message);
 
@@ -93,6 +137,53 @@ impl Verifier {
         self.step(VerifierMessages::SendPresentationRequest(connection_handle))
     }
 
+    pub fn get_presentation_proposal(&self) -> VcxResult<String> {
+        trace!("Verifier::get_presentation_proposal >>>");
+
+        let proposal = self.verifier_sm.protocol().proposal_message(&self.verifier_sm.presentation_proposal()?)?;
+        Ok(json!(proposal).to_string())
+    }
+
+    /// Answers the received proposal with the presentation request built from the proposed preview.
+    pub fn accept_presentation_proposal(&mut self, connection_handle: u32) -> VcxResult<()> {
+        trace!("Verifier::accept_presentation_proposal >>> connection_handle: {:?}", connection_handle);
+
+        let presentation_request: PresentationRequestData = ::serde_json::from_str(&self.verifier_sm.presentation_request()?.request_presentations_attach.content()?)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize Presentation Request: {:?}", err)))?;
+
+        let presentation_request_data =
+            self.verifier_sm.presentation_proposal()?.presentation_proposal
+                .to_proof_request_data(&presentation_request.name)?
+                .set_nonce()?;
+
+        self.step(VerifierMessages::SendCounterPresentationRequest(connection_handle, presentation_request_data))
+    }
+
+    pub fn send_counter_presentation_request(&mut self,
+                                             connection_handle: u32,
+                                             requested_attrs: String,
+                                             requested_predicates: String,
+                                             revocation_details: String,
+                                             name: String) -> VcxResult<()> {
+        trace!("Verifier::send_counter_presentation_request >>> connection_handle: {:?}, requested_attrs: {:?}, requested_predicates: {:?}, revocation_details: {:?}, name: {:?}",
+               connection_handle, requested_attrs, requested_predicates, revocation_details, name);
+
+        let presentation_request_data =
+            PresentationRequestData::create()
+                .set_name(name)
+                .set_requested_attributes(requested_attrs)?
+                .set_requested_predicates(requested_predicates)?
+                .set_not_revoked_interval(revocation_details)?
+                .set_nonce()?;
+
+        self.step(VerifierMessages::SendCounterPresentationRequest(connection_handle, presentation_request_data))
+    }
+
+    pub fn reject_presentation_proposal(&mut self, connection_handle: u32, reason: &str) -> VcxResult<()> {
+        trace!("Verifier::reject_presentation_proposal >>> connection_handle: {:?}, reason: {:?}", connection_handle, reason);
+        self.step(VerifierMessages::RejectPresentationProposal(connection_handle, reason.to_string()))
+    }
+
     pub fn generate_presentation_request_msg(&self) -> VcxResult<String> {
         trace!("Verifier::generate_presentation_request_msg >>>");
 
@@ -104,7 +195,7 @@ impl Verifier {
     pub fn get_presentation(&self) -> VcxResult<String> {
         trace!("Verifier::get_presentation >>>");
 
-        let proof = self.verifier_sm.presentation()?.to_a2a_message();
+        let proof = self.verifier_sm.protocol().presentation_message(&self.verifier_sm.presentation()?)?;
         Ok(json!(proof).to_string())
     }
 
@@ -125,6 +216,7 @@ pub mod tests {
     use super::*;
     use utils::mockdata::mockdata_proof::ARIES_PROOF_PRESENTATION;
     use utils::mockdata::mock_settings::MockBuilder;
+    use aries::messages::proof_presentation::presentation_proposal::tests::_presentation_proposal;
 
     #[test]
     #[cfg(feature = "general_test")]
@@ -168,6 +260,55 @@ pub mod tests {
         assert_eq!(proof.state(), VcxStateType::VcxStateOfferSent as u32);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_presentation_proposal_can_be_accepted() {
+        let _setup = SetupAriesMocks::init();
+
+        let connection_handle = build_test_connection_inviter_requested();
+
+        let mut proof = Verifier::create("1".to_string(),
+                                         REQUESTED_ATTRS.to_owned(),
+                                         REQUESTED_PREDICATES.to_owned(),
+                                         r#"{"support_revocation":false}"#.to_string(),
+                                         "Optional".to_owned()).unwrap();
+
+        proof.send_presentation_request(connection_handle).unwrap();
+        assert_eq!(VcxErrorKind::NotReady, proof.get_presentation_proposal().unwrap_err().kind());
+
+        let proposal = json!(_presentation_proposal().to_a2a_message()).to_string();
+        proof.update_state(Some(&proposal), Some(connection_handle)).unwrap();
+        assert_eq!(proof.state(), VcxStateType::VcxStateProposalReceived as u32);
+
+        let received: A2AMessage = ::serde_json::from_str(&proof.get_presentation_proposal().unwrap()).unwrap();
+        assert_eq!(_presentation_proposal().to_a2a_message(), received);
+
+        proof.accept_presentation_proposal(connection_handle).unwrap();
+        assert_eq!(proof.state(), VcxStateType::VcxStateOfferSent as u32);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_presentation_proposal_can_be_rejected() {
+        let _setup = SetupAriesMocks::init();
+
+        let connection_handle = build_test_connection_inviter_requested();
+
+        let mut proof = Verifier::create("1".to_string(),
+                                         REQUESTED_ATTRS.to_owned(),
+                                         REQUESTED_PREDICATES.to_owned(),
+                                         r#"{"support_revocation":false}"#.to_string(),
+                                         "Optional".to_owned()).unwrap();
+
+        proof.send_presentation_request(connection_handle).unwrap();
+
+        let proposal = json!(_presentation_proposal().to_a2a_message()).to_string();
+        proof.update_state(Some(&proposal), Some(connection_handle)).unwrap();
+
+        proof.reject_presentation_proposal(connection_handle, "reason").unwrap();
+        assert_eq!(proof.state(), VcxStateType::VcxStateNone as u32);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_update_state_with_reject_message() {
@@ -1,7 +1,7 @@
 use serde::{de, Deserialize, Deserializer, Serialize, Serializer};
 use serde_json::Value;
 
-use messages::message_type::parse_message_type;
+use messages::message_type::{format_message_type, parse_message_type};
 use aries::messages::a2a::message_family::MessageFamilies;
 
 #[derive(Debug, Clone, PartialEq, Default)]
@@ -33,7 +33,7 @@ impl<'de> Deserialize<'de> for MessageType {
                 let (did, family, version, type_) = parse_message_type(type_).map_err(de::Error::custom)?;
                 Ok(MessageType {
                     did,
-                    family: MessageFamilies::from(family),
+                    family: MessageFamilies::from(family).with_version(&version),
                     version,
                     type_,
                 })
@@ -52,6 +52,6 @@ impl Serialize for MessageType {
 
 impl ::std::string::ToString for MessageType {
     fn to_string(&self) -> String {
-        format!("{};spec/{}/{}/{}", self.did, self.family.to_string(), self.version, self.type_)
+        format_message_type(&self.did, &self.family.to_string(), &self.version, &self.type_)
     }
 }
\ No newline at end of file
@@ -13,6 +13,10 @@ use aries::messages::connection::invite::Invitation;
 use aries::messages::connection::request::Request;
 use aries::messages::connection::response::SignedResponse;
 use aries::messages::connection::problem_report::ProblemReport as ConnectionProblemReport;
+use aries::messages::did_exchange::request::Request as DidExchangeRequest;
+use aries::messages::did_exchange::response::Response as DidExchangeResponse;
+use aries::messages::did_exchange::complete::Complete as DidExchangeComplete;
+use aries::messages::did_exchange::problem_report::ProblemReport as DidExchangeProblemReport;
 use aries::messages::trust_ping::ping::Ping;
 use aries::messages::trust_ping::ping_response::PingResponse;
 use aries::messages::forward::Forward;
@@ -23,15 +27,37 @@ use aries::messages::ack::Ack;
 use aries::messages::issuance::credential_offer::CredentialOffer;
 use aries::messages::issuance::credential_request::CredentialRequest;
 use aries::messages::issuance::credential::Credential;
+use aries::messages::issuance::v20::credential_proposal::CredentialProposalV2;
+use aries::messages::issuance::v20::credential_offer::CredentialOfferV2;
+use aries::messages::issuance::v20::credential_request::CredentialRequestV2;
+use aries::messages::issuance::v20::credential::CredentialV2;
 
 use aries::messages::proof_presentation::presentation_proposal::PresentationProposal;
 use aries::messages::proof_presentation::presentation_request::PresentationRequest;
 use aries::messages::proof_presentation::presentation::Presentation;
+use aries::messages::proof_presentation::v20::presentation_proposal::PresentationProposalV2;
+use aries::messages::proof_presentation::v20::presentation_request::PresentationRequestV2;
+use aries::messages::proof_presentation::v20::presentation::PresentationV2;
 
 use aries::messages::discovery::query::Query;
 use aries::messages::discovery::disclose::Disclose;
 
 use aries::messages::basic_message::message::BasicMessage;
+use aries::messages::signature;
+
+use aries::messages::out_of_band::invitation::OutOfBandInvitation;
+
+use aries::messages::mediation::mediate_request::MediateRequest;
+use aries::messages::mediation::mediate_grant::MediateGrant;
+use aries::messages::mediation::mediate_deny::MediateDeny;
+use aries::messages::mediation::keylist_update::KeylistUpdate;
+use aries::messages::mediation::keylist_update_response::KeylistUpdateResponse;
+
+use aries::messages::pickup::status_request::StatusRequest;
+use aries::messages::pickup::status::Status;
+use aries::messages::pickup::batch_pickup::BatchPickup;
+use aries::messages::pickup::batch::Batch;
+use aries::messages::pickup::messages_received::MessagesReceived;
 
 #[derive(Debug, PartialEq, Clone)]
 pub enum A2AMessage {
@@ -44,6 +70,12 @@ pub enum A2AMessage {
     ConnectionResponse(SignedResponse),
     ConnectionProblemReport(ConnectionProblemReport),
 
+    /// DID Exchange 1.0
+    DidExchangeRequest(DidExchangeRequest),
+    DidExchangeResponse(DidExchangeResponse),
+    DidExchangeComplete(DidExchangeComplete),
+    DidExchangeProblemReport(DidExchangeProblemReport),
+
     /// trust ping
     Ping(Ping),
     PingResponse(PingResponse),
@@ -59,12 +91,25 @@ pub enum A2AMessage {
     Credential(Credential),
     CredentialAck(Ack),
 
+    /// credential issuance 2.0
+    CredentialProposalV2(CredentialProposalV2),
+    CredentialOfferV2(CredentialOfferV2),
+    CredentialRequestV2(CredentialRequestV2),
+    CredentialV2(CredentialV2),
+    CredentialAckV2(Ack),
+
     /// proof presentation
     PresentationProposal(PresentationProposal),
     PresentationRequest(PresentationRequest),
     Presentation(Presentation),
     PresentationAck(Ack),
 
+    /// proof presentation 2.0
+    PresentationProposalV2(PresentationProposalV2),
+    PresentationRequestV2(PresentationRequestV2),
+    PresentationV2(PresentationV2),
+    PresentationAckV2(Ack),
+
     /// discovery features
     Query(Query),
     Disclose(Disclose),
@@ -72,6 +117,23 @@ pub enum A2AMessage {
     /// basic message
     BasicMessage(BasicMessage),
 
+    /// out-of-band
+    OutOfBandInvitation(OutOfBandInvitation),
+
+    /// coordinate mediation
+    MediateRequest(MediateRequest),
+    MediateGrant(MediateGrant),
+    MediateDeny(MediateDeny),
+    KeylistUpdate(KeylistUpdate),
+    KeylistUpdateResponse(KeylistUpdateResponse),
+
+    /// message pickup
+    PickupStatusRequest(StatusRequest),
+    PickupStatus(Status),
+    BatchPickup(BatchPickup),
+    Batch(Batch),
+    MessagesReceived(MessagesReceived),
+
     /// Any Raw Message
     Generic(Value),
 }
@@ -93,7 +155,11 @@ impl<'de> Deserialize<'de> for A2AMessage {
             Err(_) => return Ok(A2AMessage::Generic(value))
         };
 
-        match (message_type.family, message_type.type_.as_str()) {
+        // messages which cannot be parsed because of fields signed by `~sig` decorator are kept as they are,
+        // their fields are unwrapped by `signature::verify_fields`
+        let signed = if signature::has_signed_fields(&value) { Some(value.clone()) } else { None };
+
+        let message: Result<A2AMessage, D::Error> = match (message_type.family, message_type.type_.as_str()) {
             (MessageFamilies::Routing, A2AMessage::FORWARD) => {
                 Forward::deserialize(value)
                     .map(|msg| A2AMessage::Forward(msg))
@@ -114,6 +180,26 @@ impl<'de> Deserialize<'de> for A2AMessage {
                     .map(|msg| A2AMessage::ConnectionResponse(msg))
                     .map_err(de::Error::custom)
             }
+            (MessageFamilies::DidExchange, A2AMessage::DID_EXCHANGE_REQUEST) => {
+                DidExchangeRequest::deserialize(value)
+                    .map(|msg| A2AMessage::DidExchangeRequest(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::DidExchange, A2AMessage::DID_EXCHANGE_RESPONSE) => {
+                DidExchangeResponse::deserialize(value)
+                    .map(|msg| A2AMessage::DidExchangeResponse(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::DidExchange, A2AMessage::DID_EXCHANGE_COMPLETE) => {
+                DidExchangeComplete::deserialize(value)
+                    .map(|msg| A2AMessage::DidExchangeComplete(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::DidExchange, A2AMessage::DID_EXCHANGE_PROBLEM_REPORT) => {
+                DidExchangeProblemReport::deserialize(value)
+                    .map(|msg| A2AMessage::DidExchangeProblemReport(msg))
+                    .map_err(de::Error::custom)
+            }
             (MessageFamilies::TrustPing, A2AMessage::PING) => {
                 Ping::deserialize(value)
                     .map(|msg| A2AMessage::Ping(msg))
@@ -164,6 +250,36 @@ impl<'de> Deserialize<'de> for A2AMessage {
                     .map(|msg| A2AMessage::CredentialAck(msg))
                     .map_err(de::Error::custom)
             }
+            (MessageFamilies::CredentialIssuanceV2, A2AMessage::PROPOSE_CREDENTIAL) => {
+                CredentialProposalV2::deserialize(value)
+                    .map(|msg| A2AMessage::CredentialProposalV2(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::CredentialIssuanceV2, A2AMessage::CREDENTIAL_OFFER) => {
+                CredentialOfferV2::deserialize(value)
+                    .map(|msg| A2AMessage::CredentialOfferV2(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::CredentialIssuanceV2, A2AMessage::REQUEST_CREDENTIAL) => {
+                CredentialRequestV2::deserialize(value)
+                    .map(|msg| A2AMessage::CredentialRequestV2(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::CredentialIssuanceV2, A2AMessage::CREDENTIAL) => {
+                CredentialV2::deserialize(value)
+                    .map(|msg| A2AMessage::CredentialV2(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::CredentialIssuanceV2, A2AMessage::ACK) => {
+                Ack::deserialize(value)
+                    .map(|msg| A2AMessage::CredentialAckV2(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::CredentialIssuanceV2, A2AMessage::PROBLEM_REPORT) => {
+                CommonProblemReport::deserialize(value)
+                    .map(|msg| A2AMessage::CommonProblemReport(msg))
+                    .map_err(de::Error::custom)
+            }
             (MessageFamilies::PresentProof, A2AMessage::PROPOSE_PRESENTATION) => {
                 PresentationProposal::deserialize(value)
                     .map(|msg| A2AMessage::PresentationProposal(msg))
@@ -184,6 +300,31 @@ impl<'de> Deserialize<'de> for A2AMessage {
                     .map(|msg| A2AMessage::PresentationAck(msg))
                     .map_err(de::Error::custom)
             }
+            (MessageFamilies::PresentProofV2, A2AMessage::PROPOSE_PRESENTATION) => {
+                PresentationProposalV2::deserialize(value)
+                    .map(|msg| A2AMessage::PresentationProposalV2(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::PresentProofV2, A2AMessage::REQUEST_PRESENTATION) => {
+                PresentationRequestV2::deserialize(value)
+                    .map(|msg| A2AMessage::PresentationRequestV2(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::PresentProofV2, A2AMessage::PRESENTATION) => {
+                PresentationV2::deserialize(value)
+                    .map(|msg| A2AMessage::PresentationV2(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::PresentProofV2, A2AMessage::ACK) => {
+                Ack::deserialize(value)
+                    .map(|msg| A2AMessage::PresentationAckV2(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::PresentProofV2, A2AMessage::PROBLEM_REPORT) => {
+                CommonProblemReport::deserialize(value)
+                    .map(|msg| A2AMessage::CommonProblemReport(msg))
+                    .map_err(de::Error::custom)
+            }
             (MessageFamilies::DiscoveryFeatures, A2AMessage::QUERY) => {
                 Query::deserialize(value)
                     .map(|msg| A2AMessage::Query(msg))
@@ -199,10 +340,70 @@ impl<'de> Deserialize<'de> for A2AMessage {
                     .map(|msg| A2AMessage::BasicMessage(msg))
                     .map_err(de::Error::custom)
             }
+            (MessageFamilies::OutOfBand, A2AMessage::OUT_OF_BAND_INVITATION) => {
+                OutOfBandInvitation::deserialize(value)
+                    .map(|msg| A2AMessage::OutOfBandInvitation(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::CoordinateMediation, A2AMessage::MEDIATE_REQUEST) => {
+                MediateRequest::deserialize(value)
+                    .map(|msg| A2AMessage::MediateRequest(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::CoordinateMediation, A2AMessage::MEDIATE_GRANT) => {
+                MediateGrant::deserialize(value)
+                    .map(|msg| A2AMessage::MediateGrant(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::CoordinateMediation, A2AMessage::MEDIATE_DENY) => {
+                MediateDeny::deserialize(value)
+                    .map(|msg| A2AMessage::MediateDeny(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::CoordinateMediation, A2AMessage::KEYLIST_UPDATE) => {
+                KeylistUpdate::deserialize(value)
+                    .map(|msg| A2AMessage::KeylistUpdate(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::CoordinateMediation, A2AMessage::KEYLIST_UPDATE_RESPONSE) => {
+                KeylistUpdateResponse::deserialize(value)
+                    .map(|msg| A2AMessage::KeylistUpdateResponse(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::MessagePickup, A2AMessage::PICKUP_STATUS_REQUEST) => {
+                StatusRequest::deserialize(value)
+                    .map(|msg| A2AMessage::PickupStatusRequest(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::MessagePickup, A2AMessage::PICKUP_STATUS) => {
+                Status::deserialize(value)
+                    .map(|msg| A2AMessage::PickupStatus(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::MessagePickup, A2AMessage::BATCH_PICKUP) => {
+                BatchPickup::deserialize(value)
+                    .map(|msg| A2AMessage::BatchPickup(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::MessagePickup, A2AMessage::BATCH) => {
+                Batch::deserialize(value)
+                    .map(|msg| A2AMessage::Batch(msg))
+                    .map_err(de::Error::custom)
+            }
+            (MessageFamilies::MessagePickup, A2AMessage::MESSAGES_RECEIVED) => {
+                MessagesReceived::deserialize(value)
+                    .map(|msg| A2AMessage::MessagesReceived(msg))
+                    .map_err(de::Error::custom)
+            }
             (_, other_type) => {
                 warn!("Unexpected @type field structure: {}", other_type);
                 Ok(A2AMessage::Generic(value))
             }
+        };
+
+        match (message, signed) {
+            (Err(_), Some(signed)) => Ok(A2AMessage::Generic(signed)),
+            (message, _) => message
         }
     }
 }
@@ -222,6 +423,10 @@ impl Serialize for A2AMessage {
             A2AMessage::ConnectionRequest(msg) => set_a2a_message_type(msg, MessageFamilies::Connections, A2AMessage::CONNECTION_REQUEST),
             A2AMessage::ConnectionResponse(msg) => set_a2a_message_type(msg, MessageFamilies::Connections, A2AMessage::CONNECTION_RESPONSE),
             A2AMessage::ConnectionProblemReport(msg) => set_a2a_message_type(msg, MessageFamilies::Connections, A2AMessage::CONNECTION_PROBLEM_REPORT),
+            A2AMessage::DidExchangeRequest(msg) => set_a2a_message_type(msg, MessageFamilies::DidExchange, A2AMessage::DID_EXCHANGE_REQUEST),
+            A2AMessage::DidExchangeResponse(msg) => set_a2a_message_type(msg, MessageFamilies::DidExchange, A2AMessage::DID_EXCHANGE_RESPONSE),
+            A2AMessage::DidExchangeComplete(msg) => set_a2a_message_type(msg, MessageFamilies::DidExchange, A2AMessage::DID_EXCHANGE_COMPLETE),
+            A2AMessage::DidExchangeProblemReport(msg) => set_a2a_message_type(msg, MessageFamilies::DidExchange, A2AMessage::DID_EXCHANGE_PROBLEM_REPORT),
             A2AMessage::Ping(msg) => set_a2a_message_type(msg, MessageFamilies::TrustPing, A2AMessage::PING),
             A2AMessage::PingResponse(msg) => set_a2a_message_type(msg, MessageFamilies::TrustPing, A2AMessage::PING_RESPONSE),
             A2AMessage::Ack(msg) => set_a2a_message_type(msg, MessageFamilies::Notification, A2AMessage::ACK),
@@ -231,13 +436,33 @@ impl Serialize for A2AMessage {
             A2AMessage::CredentialProposal(msg) => set_a2a_message_type(msg, MessageFamilies::CredentialIssuance, A2AMessage::PROPOSE_CREDENTIAL),
             A2AMessage::CredentialRequest(msg) => set_a2a_message_type(msg, MessageFamilies::CredentialIssuance, A2AMessage::REQUEST_CREDENTIAL),
             A2AMessage::CredentialAck(msg) => set_a2a_message_type(msg, MessageFamilies::CredentialIssuance, A2AMessage::ACK),
+            A2AMessage::CredentialProposalV2(msg) => set_a2a_message_type(msg, MessageFamilies::CredentialIssuanceV2, A2AMessage::PROPOSE_CREDENTIAL),
+            A2AMessage::CredentialOfferV2(msg) => set_a2a_message_type(msg, MessageFamilies::CredentialIssuanceV2, A2AMessage::CREDENTIAL_OFFER),
+            A2AMessage::CredentialRequestV2(msg) => set_a2a_message_type(msg, MessageFamilies::CredentialIssuanceV2, A2AMessage::REQUEST_CREDENTIAL),
+            A2AMessage::CredentialV2(msg) => set_a2a_message_type(msg, MessageFamilies::CredentialIssuanceV2, A2AMessage::CREDENTIAL),
+            A2AMessage::CredentialAckV2(msg) => set_a2a_message_type(msg, MessageFamilies::CredentialIssuanceV2, A2AMessage::ACK),
             A2AMessage::PresentationProposal(msg) => set_a2a_message_type(msg, MessageFamilies::PresentProof, A2AMessage::PROPOSE_PRESENTATION),
             A2AMessage::PresentationRequest(msg) => set_a2a_message_type(msg, MessageFamilies::PresentProof, A2AMessage::REQUEST_PRESENTATION),
             A2AMessage::Presentation(msg) => set_a2a_message_type(msg, MessageFamilies::PresentProof, A2AMessage::PRESENTATION),
             A2AMessage::PresentationAck(msg) => set_a2a_message_type(msg, MessageFamilies::PresentProof, A2AMessage::ACK),
+            A2AMessage::PresentationProposalV2(msg) => set_a2a_message_type(msg, MessageFamilies::PresentProofV2, A2AMessage::PROPOSE_PRESENTATION),
+            A2AMessage::PresentationRequestV2(msg) => set_a2a_message_type(msg, MessageFamilies::PresentProofV2, A2AMessage::REQUEST_PRESENTATION),
+            A2AMessage::PresentationV2(msg) => set_a2a_message_type(msg, MessageFamilies::PresentProofV2, A2AMessage::PRESENTATION),
+            A2AMessage::PresentationAckV2(msg) => set_a2a_message_type(msg, MessageFamilies::PresentProofV2, A2AMessage::ACK),
             A2AMessage::Query(msg) => set_a2a_message_type(msg, MessageFamilies::DiscoveryFeatures, A2AMessage::QUERY),
             A2AMessage::Disclose(msg) => set_a2a_message_type(msg, MessageFamilies::DiscoveryFeatures, A2AMessage::DISCLOSE),
             A2AMessage::BasicMessage(msg) => set_a2a_message_type(msg, MessageFamilies::Basicmessage, A2AMessage::BASIC_MESSAGE),
+            A2AMessage::OutOfBandInvitation(msg) => set_a2a_message_type(msg, MessageFamilies::OutOfBand, A2AMessage::OUT_OF_BAND_INVITATION),
+            A2AMessage::MediateRequest(msg) => set_a2a_message_type(msg, MessageFamilies::CoordinateMediation, A2AMessage::MEDIATE_REQUEST),
+            A2AMessage::MediateGrant(msg) => set_a2a_message_type(msg, MessageFamilies::CoordinateMediation, A2AMessage::MEDIATE_GRANT),
+            A2AMessage::MediateDeny(msg) => set_a2a_message_type(msg, MessageFamilies::CoordinateMediation, A2AMessage::MEDIATE_DENY),
+            A2AMessage::KeylistUpdate(msg) => set_a2a_message_type(msg, MessageFamilies::CoordinateMediation, A2AMessage::KEYLIST_UPDATE),
+            A2AMessage::KeylistUpdateResponse(msg) => set_a2a_message_type(msg, MessageFamilies::CoordinateMediation, A2AMessage::KEYLIST_UPDATE_RESPONSE),
+            A2AMessage::PickupStatusRequest(msg) => set_a2a_message_type(msg, MessageFamilies::MessagePickup, A2AMessage::PICKUP_STATUS_REQUEST),
+            A2AMessage::PickupStatus(msg) => set_a2a_message_type(msg, MessageFamilies::MessagePickup, A2AMessage::PICKUP_STATUS),
+            A2AMessage::BatchPickup(msg) => set_a2a_message_type(msg, MessageFamilies::MessagePickup, A2AMessage::BATCH_PICKUP),
+            A2AMessage::Batch(msg) => set_a2a_message_type(msg, MessageFamilies::MessagePickup, A2AMessage::BATCH),
+            A2AMessage::MessagesReceived(msg) => set_a2a_message_type(msg, MessageFamilies::MessagePickup, A2AMessage::MESSAGES_RECEIVED),
             A2AMessage::Generic(msg) => Ok(msg.clone())
         }.map_err(ser::Error::custom)?;
 
@@ -278,6 +503,10 @@ impl A2AMessage {
     const CONNECTION_REQUEST: &'static str = "request";
     const CONNECTION_RESPONSE: &'static str = "response";
     const CONNECTION_PROBLEM_REPORT: &'static str = "problem_report";
+    const DID_EXCHANGE_REQUEST: &'static str = "request";
+    const DID_EXCHANGE_RESPONSE: &'static str = "response";
+    const DID_EXCHANGE_COMPLETE: &'static str = "complete";
+    const DID_EXCHANGE_PROBLEM_REPORT: &'static str = "problem_report";
     const PING: &'static str = "ping";
     const PING_RESPONSE: &'static str = "ping_response";
     const ACK: &'static str = "ack";
@@ -292,6 +521,17 @@ impl A2AMessage {
     const QUERY: &'static str = "query";
     const DISCLOSE: &'static str = "disclose";
     const BASIC_MESSAGE: &'static str = "message";
+    const OUT_OF_BAND_INVITATION: &'static str = "invitation";
+    const MEDIATE_REQUEST: &'static str = "mediate-request";
+    const MEDIATE_GRANT: &'static str = "mediate-grant";
+    const MEDIATE_DENY: &'static str = "mediate-deny";
+    const KEYLIST_UPDATE: &'static str = "keylist-update";
+    const KEYLIST_UPDATE_RESPONSE: &'static str = "keylist-update-response";
+    const PICKUP_STATUS_REQUEST: &'static str = "status-request";
+    const PICKUP_STATUS: &'static str = "status";
+    const BATCH_PICKUP: &'static str = "batch-pickup";
+    const BATCH: &'static str = "batch";
+    const MESSAGES_RECEIVED: &'static str = "messages-received";
 }
 
 #[macro_export]
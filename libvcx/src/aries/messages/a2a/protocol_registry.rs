@@ -21,11 +21,18 @@ impl ProtocolRegistry {
                 family @ MessageFamilies::Notification |
                 family @ MessageFamilies::Connections |
                 family @ MessageFamilies::CredentialIssuance |
+                family @ MessageFamilies::CredentialIssuanceV2 |
                 family @ MessageFamilies::PresentProof |
+                family @ MessageFamilies::PresentProofV2 |
                 family @ MessageFamilies::TrustPing |
                 family @ MessageFamilies::Basicmessage |
-                family @ MessageFamilies::DiscoveryFeatures => registry.add_protocol(&actors, family),
-                MessageFamilies::Signature => {}
+                family @ MessageFamilies::OutOfBand |
+                family @ MessageFamilies::DidExchange |
+                family @ MessageFamilies::CoordinateMediation |
+                family @ MessageFamilies::MessagePickup |
+                family @ MessageFamilies::DiscoveryFeatures |
+                family @ MessageFamilies::Signature |
+                family @ MessageFamilies::Tracing => registry.add_protocol(&actors, family),
                 MessageFamilies::Unknown(_) => {}
             }
         }
@@ -187,6 +194,17 @@ pub mod tests {
         assert_eq!(expected_protocols, protocols);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_protocol_registry_contains_signature_decorator() {
+        let _setup = SetupEmpty::init();
+
+        let registry: ProtocolRegistry = ProtocolRegistry::init();
+
+        let protocols = registry.get_protocols_for_query(Some("did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/signature"));
+        assert_eq!(vec![ProtocolDescriptor { pid: MessageFamilies::Signature.id(), roles: None }], protocols);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_get_protocols_for_query_works_for_limited_actors() {
@@ -5,7 +5,7 @@ use aries::messages::a2a::{A2AMessage, MessageId};
 pub struct Ack {
     #[serde(rename = "@id")]
     pub id: MessageId,
-    status: AckStatus,
+    pub status: AckStatus,
     #[serde(rename = "~thread")]
     pub thread: Thread,
 }
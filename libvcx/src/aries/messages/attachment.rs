@@ -1,5 +1,6 @@
 use std::str::from_utf8;
 
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
 use serde_json;
 
 use error::{VcxError, VcxErrorKind, VcxResult};
@@ -36,6 +37,27 @@ impl Attachments {
             _ => return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, "Unsupported Attachment type"))
         }
     }
+
+    pub fn content_by_id(&self, id: &str) -> VcxResult<String> {
+        let attachment = self.0.iter()
+            .find(|attachment| match attachment {
+                Attachment::JSON(ref attach) => attach.id.as_str() == id,
+                Attachment::Blank => false
+            })
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Attachment not found: {}", id)))?;
+
+        match attachment {
+            Attachment::JSON(ref attach) => attach.get_data(),
+            Attachment::Blank => Err(VcxError::from_msg(VcxErrorKind::InvalidJson, "Unsupported Attachment type"))
+        }
+    }
+}
+
+/// Entry of `formats` array of 2.0 protocols linking an attachment to the format of its content.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub struct AttachmentFormat {
+    pub attach_id: String,
+    pub format: String,
 }
 
 #[serde(tag = "mime-type")]
@@ -53,18 +75,58 @@ pub struct Json {
     data: AttachmentData,
 }
 
-#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[derive(Debug, Clone, PartialEq)]
 pub enum AttachmentId {
-    #[serde(rename = "libindy-cred-offer-0")]
     CredentialOffer,
-    #[serde(rename = "libindy-cred-request-0")]
     CredentialRequest,
-    #[serde(rename = "libindy-cred-0")]
     Credential,
-    #[serde(rename = "libindy-request-presentation-0")]
     PresentationRequest,
-    #[serde(rename = "libindy-presentation-0")]
     Presentation,
+    /// Id referenced from `formats` of 2.0 protocols
+    Other(String),
+}
+
+impl AttachmentId {
+    /// Id of the request attached to Out-of-Band invitation at the given position.
+    pub fn out_of_band_request(index: usize) -> AttachmentId {
+        AttachmentId::Other(format!("request-{}", index))
+    }
+
+    pub fn as_str(&self) -> &str {
+        match self {
+            AttachmentId::CredentialOffer => "libindy-cred-offer-0",
+            AttachmentId::CredentialRequest => "libindy-cred-request-0",
+            AttachmentId::Credential => "libindy-cred-0",
+            AttachmentId::PresentationRequest => "libindy-request-presentation-0",
+            AttachmentId::Presentation => "libindy-presentation-0",
+            AttachmentId::Other(id) => id.as_str(),
+        }
+    }
+}
+
+impl From<String> for AttachmentId {
+    fn from(id: String) -> AttachmentId {
+        match id.as_str() {
+            "libindy-cred-offer-0" => AttachmentId::CredentialOffer,
+            "libindy-cred-request-0" => AttachmentId::CredentialRequest,
+            "libindy-cred-0" => AttachmentId::Credential,
+            "libindy-request-presentation-0" => AttachmentId::PresentationRequest,
+            "libindy-presentation-0" => AttachmentId::Presentation,
+            _ => AttachmentId::Other(id)
+        }
+    }
+}
+
+impl Serialize for AttachmentId {
+    fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
+        serializer.serialize_str(self.as_str())
+    }
+}
+
+impl<'de> Deserialize<'de> for AttachmentId {
+    fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
+        String::deserialize(deserializer).map(AttachmentId::from)
+    }
 }
 
 impl Json {
@@ -156,4 +218,25 @@ pub mod tests {
             assert_eq!(_json().to_string(), attachments.content().unwrap());
         }
     }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_attachment_id_serialization_works() {
+        assert_eq!(json!("libindy-cred-0"), json!(AttachmentId::Credential));
+        assert_eq!(AttachmentId::Credential, ::serde_json::from_value(json!("libindy-cred-0")).unwrap());
+
+        assert_eq!(json!("indy"), json!(AttachmentId::Other("indy".to_string())));
+        assert_eq!(AttachmentId::Other("indy".to_string()), ::serde_json::from_value(json!("indy")).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_attachments_content_by_id_works() {
+        let mut attachments = Attachments::new();
+        attachments.add_base64_encoded_json_attachment(AttachmentId::Credential, json!({"field": "other"})).unwrap();
+        attachments.add_base64_encoded_json_attachment(AttachmentId::Other("indy".to_string()), _json()).unwrap();
+
+        assert_eq!(_json().to_string(), attachments.content_by_id("indy").unwrap());
+        assert_eq!(VcxErrorKind::InvalidJson, attachments.content_by_id("unknown").unwrap_err().kind());
+    }
 }
\ No newline at end of file
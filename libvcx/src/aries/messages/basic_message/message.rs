@@ -1,6 +1,7 @@
 use chrono::prelude::*;
 
 use aries::messages::a2a::{A2AMessage, MessageId};
+use aries::messages::ack::PleaseAck;
 use aries::messages::localization::Localization;
 
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
@@ -12,6 +13,9 @@ pub struct BasicMessage {
     #[serde(rename = "~l10n")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub l10n: Option<Localization>,
+    #[serde(rename = "~please_ack")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub please_ack: Option<PleaseAck>,
 }
 
 impl BasicMessage {
@@ -39,6 +43,8 @@ impl BasicMessage {
     }
 }
 
+please_ack!(BasicMessage);
+
 #[cfg(test)]
 pub mod tests {
     use super::*;
@@ -56,4 +62,19 @@ pub mod tests {
             .set_default_localization();
         assert_eq!(_content(), basic_message.content);
     }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_basic_message_with_please_ack() {
+        let basic_message: BasicMessage = BasicMessage::default()
+            .set_content(_content())
+            .ask_for_ack();
+
+        let json = json!(basic_message);
+        assert_eq!(json!({}), json["~please_ack"]);
+        assert_eq!(basic_message, ::serde_json::from_value::<BasicMessage>(json).unwrap());
+
+        let json = json!(BasicMessage::default());
+        assert!(json.get("~please_ack").is_none());
+    }
 }
@@ -3,6 +3,7 @@ use aries::messages::connection::invite::Invitation;
 use error::prelude::*;
 use url::Url;
 use messages::validation::validate_verkey;
+use utils::libindy::ledger;
 
 pub const CONTEXT: &str = "https://w3id.org/did/v1";
 pub const KEY_TYPE: &str = "Ed25519VerificationKey2018";
@@ -142,6 +143,18 @@ impl DidDoc {
             });
     }
 
+    /// Builds DIDDoc of a public `did:sov` DID from the verkey and endpoint it published on the ledger.
+    pub fn resolve_public_did(did: &str) -> VcxResult<DidDoc> {
+        let verkey = ledger::get_verkey(did)?;
+        let (service_endpoint, routing_keys) = ledger::get_service_endpoint(did)?;
+
+        let mut did_doc: DidDoc = DidDoc::default();
+        did_doc.set_id(did.to_string());
+        did_doc.set_service_endpoint(service_endpoint);
+        did_doc.set_keys(vec![verkey], routing_keys);
+        Ok(did_doc)
+    }
+
     pub fn validate(&self) -> VcxResult<()> {
         if self.context != CONTEXT {
             return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("DIDDoc validation failed: Unsupported @context value: {:?}", self.context)));
@@ -316,6 +329,7 @@ pub mod tests {
     use super::*;
     use aries::messages::a2a::MessageId;
     use aries::messages::connection::invite::tests::_invitation;
+    use utils::devsetup::SetupMocks;
 
     pub fn _key_1() -> String {
         String::from("GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL")
@@ -357,6 +371,10 @@ pub mod tests {
         DidDoc::_build_key_reference(&_id(), "3")
     }
 
+    pub fn _public_did() -> String {
+        String::from("did:sov:V4SGRU86Z58d6TV7PBUe6f")
+    }
+
     pub fn _label() -> String {
         String::from("test")
     }
@@ -522,4 +540,18 @@ pub mod tests {
 
         assert_eq!(did_doc, DidDoc::from(_invitation()))
     }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_did_doc_resolve_public_did_works() {
+        let _setup = SetupMocks::init();
+
+        let did_doc = DidDoc::resolve_public_did(&_public_did()).unwrap();
+        did_doc.validate().unwrap();
+
+        assert_eq!(_public_did(), did_doc.id);
+        assert_eq!(_service_endpoint(), did_doc.get_endpoint());
+        assert_eq!(_recipient_keys(), did_doc.recipient_keys());
+        assert!(did_doc.routing_keys().is_empty());
+    }
 }
@@ -1,17 +1,23 @@
+use error::prelude::*;
 use aries::messages::a2a::{A2AMessage, MessageId};
+use aries::messages::connection::did_doc::DidDoc;
 
 #[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
 pub struct Invitation {
     #[serde(rename = "@id")]
     pub id: MessageId,
     pub label: String,
+    #[serde(default)]
     #[serde(rename = "recipientKeys")]
     pub recipient_keys: Vec<String>,
     #[serde(default)]
     #[serde(rename = "routingKeys")]
     pub routing_keys: Vec<String>,
+    #[serde(default)]
     #[serde(rename = "serviceEndpoint")]
     pub service_endpoint: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub did: Option<String>,
 }
 
 impl Invitation {
@@ -43,6 +49,30 @@ impl Invitation {
         self.routing_keys = routing_keys;
         self
     }
+
+    pub fn set_did(mut self, did: String) -> Invitation {
+        self.did = Some(did);
+        self
+    }
+
+    /// Public DID (implicit) invitation carries only the inviter's `did:sov` DID.
+    /// Fills its recipient keys and service endpoint with the ones published on the ledger.
+    pub fn resolve(self) -> VcxResult<Invitation> {
+        match self.did.clone() {
+            Some(did) => {
+                let did_doc = DidDoc::resolve_public_did(&did)?;
+                let (recipient_keys, routing_keys) = did_doc.resolve_keys();
+
+                Ok(self
+                    .set_service_endpoint(did_doc.get_endpoint())
+                    .set_recipient_keys(recipient_keys)
+                    .set_routing_keys(routing_keys))
+            }
+            None if self.recipient_keys.is_empty() || self.service_endpoint.is_empty() =>
+                Err(VcxError::from_msg(VcxErrorKind::InvalidJson, "Invitation must contain either recipientKeys and serviceEndpoint or public DID")),
+            None => Ok(self)
+        }
+    }
 }
 
 a2a_message!(Invitation, ConnectionInvitation);
@@ -51,6 +81,7 @@ a2a_message!(Invitation, ConnectionInvitation);
 pub mod tests {
     use super::*;
     use aries::messages::connection::did_doc::tests::*;
+    use utils::devsetup::SetupMocks;
 
     pub fn _invitation() -> Invitation {
         Invitation {
@@ -59,9 +90,16 @@ pub mod tests {
             recipient_keys: _recipient_keys(),
             routing_keys: _routing_keys(),
             service_endpoint: _service_endpoint(),
+            did: None,
         }
     }
 
+    pub fn _public_did_invitation() -> Invitation {
+        Invitation::create()
+            .set_label(_label())
+            .set_did(_public_did())
+    }
+
     pub fn _invitation_json() -> String {
         ::serde_json::to_string(&_invitation().to_a2a_message()).unwrap()
     }
@@ -77,4 +115,39 @@ pub mod tests {
 
         assert_eq!(_invitation(), invitation);
     }
-}
\ No newline at end of file
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_public_did_invitation_resolve_works() {
+        let _setup = SetupMocks::init();
+
+        let invitation = _public_did_invitation().resolve().unwrap();
+
+        assert_eq!(Some(_public_did()), invitation.did);
+        assert_eq!(_recipient_keys(), invitation.recipient_keys);
+        assert_eq!(_service_endpoint(), invitation.service_endpoint);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_invitation_resolve_fails_without_keys_and_did() {
+        let invitation = Invitation::create().set_label(_label());
+        assert_eq!(VcxErrorKind::InvalidJson, invitation.resolve().unwrap_err().kind());
+
+        assert_eq!(_invitation(), _invitation().resolve().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_public_did_invitation_serialization_works() {
+        let invitation: Invitation = ::serde_json::from_value(json!({
+            "@type": "https://didcomm.org/connections/1.0/invitation",
+            "@id": "testid",
+            "label": _label(),
+            "did": _public_did()
+        })).unwrap();
+        assert_eq!(Some(_public_did()), invitation.did);
+
+        assert!(!::serde_json::to_string(&_invitation()).unwrap().contains("\"did\""));
+    }
+}
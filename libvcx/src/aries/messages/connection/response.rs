@@ -1,14 +1,9 @@
-use base64;
-use time;
-
 use error::prelude::*;
 use messages::thread::Thread;
-use utils::libindy::crypto;
 use aries::messages::a2a::{A2AMessage, MessageId};
-use aries::messages::a2a::message_family::MessageFamilies;
-use aries::messages::a2a::message_type::MessageType;
 use aries::messages::ack::PleaseAck;
 use aries::messages::connection::did_doc::*;
+use aries::messages::signature::FieldSignature;
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
 pub struct Response {
@@ -43,14 +38,7 @@ pub struct SignedResponse {
     pub please_ack: Option<PleaseAck>,
 }
 
-#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
-pub struct ConnectionSignature {
-    #[serde(rename = "@type")]
-    pub msg_type: MessageType,
-    pub signature: String,
-    pub sig_data: String,
-    pub signer: String,
-}
+pub type ConnectionSignature = FieldSignature;
 
 impl Response {
     pub fn create() -> Response {
@@ -74,26 +62,7 @@ impl Response {
     }
 
     pub fn encode(&self, key: &str) -> VcxResult<SignedResponse> {
-        let connection_data = json!(self.connection).to_string();
-
-        let now: u64 = time::get_time().sec as u64;
-
-        let mut sig_data = now.to_be_bytes().to_vec();
-
-        sig_data.extend(connection_data.as_bytes());
-
-        let signature = crypto::sign(key, &sig_data)?;
-
-        let sig_data = base64::encode_config(&sig_data, base64::URL_SAFE);
-
-        let signature = base64::encode_config(&signature, base64::URL_SAFE);
-
-        let connection_sig = ConnectionSignature {
-            signature,
-            sig_data,
-            signer: key.to_string(),
-            ..Default::default()
-        };
+        let connection_sig = FieldSignature::sign(&json!(self.connection), key)?;
 
         let signed_response = SignedResponse {
             id: self.id.clone(),
@@ -111,21 +80,11 @@ threadlike!(Response);
 
 impl SignedResponse {
     pub fn decode(self, key: &str) -> VcxResult<Response> {
-        let signature = base64::decode_config(&self.connection_sig.signature.as_bytes(), base64::URL_SAFE)
-            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot decode ConnectionResponse: {:?}", err)))?;
+        // the signature is verified with original Invite recipient key regardless of the signer
+        let connection_data = self.connection_sig.verify(key)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot decode ConnectionResponse: {}", err)))?;
 
-        let sig_data = base64::decode_config(&self.connection_sig.sig_data.as_bytes(), base64::URL_SAFE)
-            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot decode ConnectionResponse: {:?}", err)))?;
-
-        if !crypto::verify(&key, &sig_data, &signature)? {
-            return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, "ConnectionResponse signature is invalid for original Invite recipient key"));
-        }
-
-        //TODO check sig_data.signer
-
-        let sig_data = &sig_data[8..];
-
-        let connection: ConnectionData = ::serde_json::from_slice(&sig_data)
+        let connection: ConnectionData = ::serde_json::from_value(connection_data)
             .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, err.to_string()))?;
 
         Ok(Response {
@@ -139,17 +98,6 @@ impl SignedResponse {
 
 a2a_message!(SignedResponse, ConnectionResponse);
 
-impl Default for ConnectionSignature {
-    fn default() -> ConnectionSignature {
-        ConnectionSignature {
-            msg_type: MessageType::build(MessageFamilies::Signature, "ed25519Sha512_single"),
-            signature: String::new(),
-            sig_data: String::new(),
-            signer: String::new(),
-        }
-    }
-}
-
 #[cfg(test)]
 pub mod tests {
     use utils::libindy::tests::test_setup;
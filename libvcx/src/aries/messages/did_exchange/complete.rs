@@ -0,0 +1,48 @@
+use messages::thread::Thread;
+use aries::messages::a2a::{A2AMessage, MessageId};
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+pub struct Complete {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+}
+
+impl Complete {
+    pub fn create() -> Complete {
+        Complete::default()
+    }
+
+    pub fn set_parent_thread_id(mut self, id: &str) -> Complete {
+        self.thread = self.thread.set_pthid(id.to_string());
+        self
+    }
+}
+
+threadlike!(Complete);
+a2a_message!(Complete, DidExchangeComplete);
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::did_exchange::request::tests::{_pthid, _request};
+
+    use super::*;
+
+    pub fn _complete() -> Complete {
+        Complete {
+            id: MessageId::id(),
+            thread: Thread::new().set_thid(_request().id.0).set_pthid(_pthid()),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_complete_build_works() {
+        let complete: Complete = Complete::default()
+            .set_thread_id(&_request().id.0)
+            .set_parent_thread_id(&_pthid());
+
+        assert_eq!(_complete(), complete);
+    }
+}
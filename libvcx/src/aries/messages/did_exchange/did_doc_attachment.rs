@@ -0,0 +1,202 @@
+extern crate rust_base58;
+
+use base64;
+
+use self::rust_base58::{FromBase58, ToBase58};
+
+use error::prelude::*;
+use utils::libindy::crypto;
+use aries::messages::connection::did_doc::DidDoc;
+
+const ATTACHMENT_ID: &str = "did-doc-0";
+const MIME_TYPE: &str = "application/json";
+const JWS_ALG: &str = "EdDSA";
+const ED25519_MULTICODEC_PREFIX: [u8; 2] = [0xed, 0x01];
+
+/// `did_doc~attach` decorator used by DID Exchange messages.
+/// Unlike generic `Attachments` it is a single object and its data may carry a detached JWS.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DidDocAttachment {
+    #[serde(rename = "@id")]
+    pub id: String,
+    #[serde(rename = "mime-type")]
+    pub mime_type: String,
+    pub data: DidDocAttachmentData,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct DidDocAttachmentData {
+    pub base64: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jws: Option<Jws>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Jws {
+    pub header: JwsHeader,
+    pub protected: String,
+    pub signature: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct JwsHeader {
+    pub kid: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct JwsProtectedHeader {
+    alg: String,
+    kid: String,
+    jwk: Jwk,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+struct Jwk {
+    kty: String,
+    crv: String,
+    x: String,
+    kid: String,
+}
+
+impl Default for DidDocAttachment {
+    fn default() -> DidDocAttachment {
+        DidDocAttachment {
+            id: String::from(ATTACHMENT_ID),
+            mime_type: String::from(MIME_TYPE),
+            data: DidDocAttachmentData { base64: String::new(), jws: None },
+        }
+    }
+}
+
+impl DidDocAttachment {
+    pub fn create(did_doc: &DidDoc) -> VcxResult<DidDocAttachment> {
+        let did_doc = ::serde_json::to_string(did_doc)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot serialize DIDDoc: {:?}", err)))?;
+
+        Ok(DidDocAttachment {
+            data: DidDocAttachmentData { base64: encode_base64_url(did_doc.as_bytes()), jws: None },
+            ..DidDocAttachment::default()
+        })
+    }
+
+    pub fn did_doc(&self) -> VcxResult<DidDoc> {
+        let did_doc = decode_base64_url(&self.data.base64)?;
+
+        ::serde_json::from_slice(&did_doc)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize attached DIDDoc: {:?}", err)))
+    }
+
+    /// Signs the attached data with the given verkey producing detached JWS (RFC 0017).
+    pub fn sign(mut self, key: &str) -> VcxResult<DidDocAttachment> {
+        let kid = verkey_to_did_key(key)?;
+
+        let protected = JwsProtectedHeader {
+            alg: String::from(JWS_ALG),
+            kid: kid.clone(),
+            jwk: Jwk {
+                kty: String::from("OKP"),
+                crv: String::from("Ed25519"),
+                x: encode_base64_url(&decode_verkey(key)?),
+                kid: kid.clone(),
+            },
+        };
+
+        let protected = encode_base64_url(json!(protected).to_string().as_bytes());
+
+        let signature = crypto::sign(key, _signing_input(&protected, &self.data.base64).as_bytes())?;
+
+        self.data.jws = Some(Jws {
+            header: JwsHeader { kid },
+            protected,
+            signature: encode_base64_url(&signature),
+        });
+
+        Ok(self)
+    }
+
+    /// Verifies that attached data is signed with the given verkey.
+    pub fn verify(&self, key: &str) -> VcxResult<()> {
+        let jws = self.data.jws.as_ref()
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, "Attached DIDDoc is not signed"))?;
+
+        if jws.header.kid != verkey_to_did_key(key)? {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Attached DIDDoc is signed by unexpected key: {}", jws.header.kid)));
+        }
+
+        let signature = decode_base64_url(&jws.signature)?;
+
+        if !crypto::verify(key, _signing_input(&jws.protected, &self.data.base64).as_bytes(), &signature)? {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, "Attached DIDDoc signature is invalid for original Invite recipient key"));
+        }
+
+        Ok(())
+    }
+}
+
+fn _signing_input(protected: &str, payload: &str) -> String {
+    format!("{}.{}", protected, payload)
+}
+
+fn encode_base64_url(data: &[u8]) -> String {
+    base64::encode_config(data, base64::URL_SAFE).trim_end_matches('=').to_string()
+}
+
+fn decode_base64_url(data: &str) -> VcxResult<Vec<u8>> {
+    let mut data = data.to_string();
+    while data.len() % 4 != 0 {
+        data.push('=');
+    }
+
+    base64::decode_config(&data, base64::URL_SAFE)
+        .or_else(|_| base64::decode(&data))
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot decode base64 data: {:?}", err)))
+}
+
+fn decode_verkey(key: &str) -> VcxResult<Vec<u8>> {
+    key.from_base58()
+        .map_err(|_| VcxError::from_msg(VcxErrorKind::NotBase58, format!("Invalid verkey: {}", key)))
+}
+
+pub fn verkey_to_did_key(key: &str) -> VcxResult<String> {
+    let mut bytes = ED25519_MULTICODEC_PREFIX.to_vec();
+    bytes.extend(decode_verkey(key)?);
+    Ok(format!("did:key:z{}", bytes.to_base58()))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use aries::messages::connection::did_doc::tests::{_did_doc, _key_1};
+    use utils::devsetup::SetupAriesMocks;
+
+    pub fn _did_doc_attachment() -> DidDocAttachment {
+        DidDocAttachment::create(&_did_doc()).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_did_doc_attachment_works() {
+        let attachment = _did_doc_attachment();
+        assert!(!attachment.data.base64.contains('='));
+        assert_eq!(_did_doc(), attachment.did_doc().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_did_doc_attachment_sign_verify_works() {
+        let _setup = SetupAriesMocks::init();
+
+        let attachment = _did_doc_attachment();
+        assert_eq!(VcxErrorKind::InvalidJson, attachment.verify(&_key_1()).unwrap_err().kind());
+
+        let attachment = attachment.sign(&_key_1()).unwrap();
+        attachment.verify(&_key_1()).unwrap();
+        assert_eq!(_did_doc(), attachment.did_doc().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_verkey_to_did_key_works() {
+        assert_eq!("did:key:z6MkukGVb3mRvTu1msArDKY9UwxeZFGjmwnCKtdQttr4Fk6i", verkey_to_did_key("GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL").unwrap());
+    }
+}
@@ -0,0 +1,5 @@
+pub mod did_doc_attachment;
+pub mod request;
+pub mod response;
+pub mod complete;
+pub mod problem_report;
@@ -0,0 +1,85 @@
+use messages::thread::Thread;
+use aries::messages::a2a::{A2AMessage, MessageId};
+use aries::messages::localization::Localization;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct ProblemReport {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(rename = "problem-code")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub problem_code: Option<ProblemCode>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub explain: Option<String>,
+    #[serde(rename = "~l10n")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub localization: Option<Localization>,
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ProblemCode {
+    Empty,
+    #[serde(rename = "request_not_accepted")]
+    RequestNotAccepted,
+    #[serde(rename = "request_processing_error")]
+    RequestProcessingError,
+    #[serde(rename = "response_not_accepted")]
+    ResponseNotAccepted,
+    #[serde(rename = "response_processing_error")]
+    ResponseProcessingError,
+}
+
+impl ProblemReport {
+    pub fn create() -> ProblemReport {
+        ProblemReport::default()
+    }
+
+    pub fn set_problem_code(mut self, problem_code: ProblemCode) -> ProblemReport {
+        self.problem_code = Some(problem_code);
+        self
+    }
+
+    pub fn set_explain(mut self, explain: String) -> ProblemReport {
+        self.explain = Some(explain);
+        self
+    }
+}
+
+threadlike!(ProblemReport);
+a2a_message!(ProblemReport, DidExchangeProblemReport);
+
+impl Default for ProblemCode {
+    fn default() -> ProblemCode {
+        ProblemCode::Empty
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::did_exchange::request::tests::_request;
+
+    use super::*;
+
+    pub fn _problem_report() -> ProblemReport {
+        ProblemReport {
+            id: MessageId::id(),
+            problem_code: Some(ProblemCode::RequestProcessingError),
+            explain: Some(String::from("test explanation")),
+            localization: None,
+            thread: Thread::new().set_thid(_request().id.0),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_problem_report_build_works() {
+        let report: ProblemReport = ProblemReport::default()
+            .set_problem_code(ProblemCode::RequestProcessingError)
+            .set_explain(String::from("test explanation"))
+            .set_thread_id(&_request().id.0);
+
+        assert_eq!(_problem_report(), report);
+    }
+}
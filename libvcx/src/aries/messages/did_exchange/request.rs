@@ -0,0 +1,91 @@
+use error::prelude::*;
+use messages::thread::Thread;
+use aries::messages::a2a::{A2AMessage, MessageId};
+use aries::messages::connection::did_doc::DidDoc;
+use aries::messages::did_exchange::did_doc_attachment::DidDocAttachment;
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+pub struct Request {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(default)]
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+    pub label: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goal_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goal: Option<String>,
+    pub did: String,
+    #[serde(rename = "did_doc~attach")]
+    pub did_doc_attach: DidDocAttachment,
+}
+
+impl Request {
+    pub fn create() -> Request {
+        Request::default()
+    }
+
+    pub fn set_label(mut self, label: String) -> Request {
+        self.label = label;
+        self
+    }
+
+    pub fn set_did(mut self, did: String) -> Request {
+        self.did = did;
+        self
+    }
+
+    pub fn set_did_doc(mut self, did_doc: &DidDoc) -> VcxResult<Request> {
+        self.did_doc_attach = DidDocAttachment::create(did_doc)?;
+        Ok(self)
+    }
+
+    pub fn set_parent_thread_id(mut self, id: &str) -> Request {
+        self.thread = self.thread.set_pthid(id.to_string());
+        self
+    }
+
+    pub fn did_doc(&self) -> VcxResult<DidDoc> {
+        self.did_doc_attach.did_doc()
+    }
+}
+
+a2a_message!(Request, DidExchangeRequest);
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::connection::did_doc::tests::*;
+    use aries::messages::did_exchange::did_doc_attachment::tests::_did_doc_attachment;
+
+    use super::*;
+
+    pub fn _pthid() -> String {
+        String::from("testid")
+    }
+
+    pub fn _request() -> Request {
+        Request {
+            id: MessageId::id(),
+            thread: Thread::new().set_pthid(_pthid()),
+            label: _label(),
+            goal_code: None,
+            goal: None,
+            did: _id(),
+            did_doc_attach: _did_doc_attachment(),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_request_build_works() {
+        let request: Request = Request::default()
+            .set_label(_label())
+            .set_did(_id())
+            .set_parent_thread_id(&_pthid())
+            .set_did_doc(&_did_doc()).unwrap();
+
+        assert_eq!(_request(), request);
+        assert_eq!(_did_doc(), request.did_doc().unwrap());
+    }
+}
@@ -0,0 +1,82 @@
+use error::prelude::*;
+use messages::thread::Thread;
+use aries::messages::a2a::{A2AMessage, MessageId};
+use aries::messages::connection::did_doc::DidDoc;
+use aries::messages::did_exchange::did_doc_attachment::DidDocAttachment;
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+pub struct Response {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+    pub did: String,
+    #[serde(rename = "did_doc~attach")]
+    pub did_doc_attach: DidDocAttachment,
+}
+
+impl Response {
+    pub fn create() -> Response {
+        Response::default()
+    }
+
+    pub fn set_did(mut self, did: String) -> Response {
+        self.did = did;
+        self
+    }
+
+    /// Attaches DIDDoc signed with the key used in the invitation so that requester can check that the response comes from the inviter.
+    pub fn set_signed_did_doc(mut self, did_doc: &DidDoc, key: &str) -> VcxResult<Response> {
+        self.did_doc_attach = DidDocAttachment::create(did_doc)?.sign(key)?;
+        Ok(self)
+    }
+
+    pub fn verify_did_doc(&self, key: &str) -> VcxResult<DidDoc> {
+        self.did_doc_attach.verify(key)?;
+        self.did_doc_attach.did_doc()
+    }
+}
+
+threadlike!(Response);
+a2a_message!(Response, DidExchangeResponse);
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::connection::did_doc::tests::*;
+    use aries::messages::did_exchange::did_doc_attachment::tests::_did_doc_attachment;
+    use aries::messages::did_exchange::request::tests::_request;
+    use utils::devsetup::SetupAriesMocks;
+
+    use super::*;
+
+    pub fn _response() -> Response {
+        Response {
+            id: MessageId::id(),
+            thread: Thread::new().set_thid(_request().id.0),
+            did: _id(),
+            did_doc_attach: _did_doc_attachment().sign(&_key_1()).unwrap(),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_response_build_works() {
+        let _setup = SetupAriesMocks::init();
+
+        let response: Response = Response::default()
+            .set_did(_id())
+            .set_thread_id(&_request().id.0)
+            .set_signed_did_doc(&_did_doc(), &_key_1()).unwrap();
+
+        assert_eq!(_response(), response);
+        assert_eq!(_did_doc(), response.verify_did_doc(&_key_1()).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_response_verify_fails_for_other_key() {
+        let _setup = SetupAriesMocks::init();
+
+        assert_eq!(VcxErrorKind::InvalidJson, _response().verify_did_doc(&_key_2()).unwrap_err().kind());
+    }
+}
@@ -6,7 +6,7 @@ use aries::messages::a2a::{A2AMessage, MessageId};
 #[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
 pub struct ProblemReport {
     #[serde(rename = "@id")]
-    id: MessageId,
+    pub id: MessageId,
     #[serde(rename = "~thread")]
     pub thread: Thread,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -4,6 +4,7 @@ use aries::messages::a2a::{A2AMessage, MessageId};
 use aries::messages::attachment::{AttachmentId, Attachments};
 use aries::messages::issuance::CredentialPreviewData;
 use aries::messages::mime_type::MimeType;
+use aries::messages::timing::Timing;
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
 pub struct CredentialOffer {
@@ -17,6 +18,9 @@ pub struct CredentialOffer {
     #[serde(skip_serializing_if = "Option::is_none")]
     #[serde(rename = "~thread")]
     pub thread: Option<Thread>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "~timing")]
+    pub timing: Option<Timing>,
 }
 
 impl CredentialOffer {
@@ -55,6 +59,7 @@ impl CredentialOffer {
     }
 }
 
+timing!(CredentialOffer);
 a2a_message!(CredentialOffer);
 
 #[cfg(test)]
@@ -102,6 +107,7 @@ pub mod tests {
             credential_preview: _preview_data(),
             offers_attach: attachment,
             thread: Some(_thread()),
+            timing: None,
         }
     }
 
@@ -8,6 +8,7 @@ pub mod credential_offer;
 pub mod credential_proposal;
 pub mod credential_request;
 pub mod credential_ack;
+pub mod v20;
 
 #[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
 pub struct CredentialPreviewData {
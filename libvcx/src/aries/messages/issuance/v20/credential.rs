@@ -0,0 +1,104 @@
+use error::VcxResult;
+use messages::thread::Thread;
+use aries::messages::a2a::{A2AMessage, MessageId};
+use aries::messages::ack::PleaseAck;
+use aries::messages::attachment::{AttachmentFormat, AttachmentId, Attachments};
+use aries::messages::issuance::credential::Credential;
+use aries::messages::issuance::v20::{add_attachment, AttachmentKind, CredentialFormat, supported_attachment};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct CredentialV2 {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replacement_id: Option<String>,
+    pub formats: Vec<AttachmentFormat>,
+    #[serde(rename = "credentials~attach")]
+    pub credentials_attach: Attachments,
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+    #[serde(rename = "~please_ack")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub please_ack: Option<PleaseAck>,
+}
+
+impl CredentialV2 {
+    pub fn create() -> Self {
+        CredentialV2::default()
+    }
+
+    pub fn set_comment(mut self, comment: String) -> Self {
+        self.comment = Some(comment);
+        self
+    }
+
+    pub fn set_credential(mut self, format: &CredentialFormat, credential: String) -> VcxResult<CredentialV2> {
+        add_attachment(&mut self.formats, &mut self.credentials_attach, format, &AttachmentKind::Credential, credential)?;
+        Ok(self)
+    }
+
+    pub fn from_v1(credential: &Credential, format: &CredentialFormat) -> VcxResult<CredentialV2> {
+        let mut credential_v2 = CredentialV2::create()
+            .set_credential(format, credential.credentials_attach.content()?)?;
+        credential_v2.id = credential.id.clone();
+        credential_v2.comment = credential.comment.clone();
+        credential_v2.thread = credential.thread.clone();
+        credential_v2.please_ack = credential.please_ack.clone();
+        Ok(credential_v2)
+    }
+}
+
+please_ack!(CredentialV2);
+threadlike!(CredentialV2);
+a2a_message!(CredentialV2);
+
+impl From<CredentialV2> for Credential {
+    fn from(credential: CredentialV2) -> Credential {
+        let mut credentials_attach = Attachments::new();
+        if let Some((_, content)) = supported_attachment(&credential.formats, &credential.credentials_attach, &AttachmentKind::Credential) {
+            credentials_attach.add_base64_encoded_json_attachment(AttachmentId::Credential, ::serde_json::Value::String(content)).ok();
+        }
+
+        Credential {
+            id: credential.id,
+            comment: credential.comment,
+            credentials_attach,
+            thread: credential.thread,
+            please_ack: credential.please_ack,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::issuance::credential::tests::_credential;
+
+    use super::*;
+
+    pub fn _credential_v2() -> CredentialV2 {
+        CredentialV2::from_v1(&_credential(), &CredentialFormat::Indy).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_credential_v2_converts_from_and_into_v1() {
+        let credential = _credential_v2();
+        assert_eq!(vec![AttachmentFormat { attach_id: "indy".to_string(), format: "hlindy/cred@v2.0".to_string() }], credential.formats);
+
+        let credential: Credential = credential.into();
+        assert_eq!(_credential(), credential);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_credential_v2_serialization_works() {
+        let message = _credential_v2().ask_for_ack().to_a2a_message();
+
+        let json = ::serde_json::to_value(&message).unwrap();
+        assert_eq!("did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/issue-credential/2.0/issue-credential", json["@type"]);
+
+        assert_eq!(message, ::serde_json::from_value::<A2AMessage>(json).unwrap());
+    }
+}
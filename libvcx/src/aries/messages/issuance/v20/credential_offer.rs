@@ -0,0 +1,156 @@
+use error::VcxResult;
+use messages::thread::Thread;
+use aries::messages::a2a::{A2AMessage, MessageId};
+use aries::messages::attachment::{AttachmentFormat, AttachmentId, Attachments};
+use aries::messages::issuance::CredentialPreviewData;
+use aries::messages::issuance::credential_offer::CredentialOffer;
+use aries::messages::timing::Timing;
+use aries::messages::issuance::v20::{add_attachment, AttachmentKind, CredentialFormat, set_credential_preview_type, supported_attachment};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct CredentialOfferV2 {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replacement_id: Option<String>,
+    pub credential_preview: CredentialPreviewData,
+    pub formats: Vec<AttachmentFormat>,
+    #[serde(rename = "offers~attach")]
+    pub offers_attach: Attachments,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "~thread")]
+    pub thread: Option<Thread>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "~timing")]
+    pub timing: Option<Timing>,
+}
+
+impl CredentialOfferV2 {
+    pub fn create() -> Self {
+        CredentialOfferV2 {
+            credential_preview: set_credential_preview_type(CredentialPreviewData::new()),
+            ..CredentialOfferV2::default()
+        }
+    }
+
+    pub fn set_id(mut self, id: String) -> Self {
+        self.id = MessageId(id);
+        self
+    }
+
+    pub fn set_comment(mut self, comment: Option<String>) -> Self {
+        self.comment = comment;
+        self
+    }
+
+    pub fn set_offers_attach(mut self, format: &CredentialFormat, credential_offer: &str) -> VcxResult<CredentialOfferV2> {
+        add_attachment(&mut self.formats, &mut self.offers_attach, format, &AttachmentKind::Offer, credential_offer.to_string())?;
+        Ok(self)
+    }
+
+    pub fn set_credential_preview_data(mut self, credential_preview: CredentialPreviewData) -> VcxResult<CredentialOfferV2> {
+        self.credential_preview = set_credential_preview_type(credential_preview);
+        Ok(self)
+    }
+
+    pub fn set_thread_id(mut self, id: &str) -> Self {
+        self.thread = Some(Thread::new().set_thid(id.to_string()));
+        self
+    }
+
+    /// Returns the first offered credential format supported by VCX.
+    pub fn format(&self) -> Option<CredentialFormat> {
+        supported_attachment(&self.formats, &self.offers_attach, &AttachmentKind::Offer).map(|(format, _)| format)
+    }
+
+    pub fn from_v1(offer: &CredentialOffer, format: &CredentialFormat) -> VcxResult<CredentialOfferV2> {
+        let mut offer_v2 = CredentialOfferV2::create()
+            .set_id(offer.id.0.clone())
+            .set_comment(offer.comment.clone())
+            .set_credential_preview_data(offer.credential_preview.clone())?
+            .set_offers_attach(format, &offer.offers_attach.content()?)?;
+        offer_v2.thread = offer.thread.clone();
+        offer_v2.timing = offer.timing.clone();
+        Ok(offer_v2)
+    }
+}
+
+timing!(CredentialOfferV2);
+a2a_message!(CredentialOfferV2);
+
+impl From<CredentialOfferV2> for CredentialOffer {
+    fn from(offer: CredentialOfferV2) -> CredentialOffer {
+        let mut offers_attach = Attachments::new();
+        if let Some((_, content)) = supported_attachment(&offer.formats, &offer.offers_attach, &AttachmentKind::Offer) {
+            offers_attach.add_base64_encoded_json_attachment(AttachmentId::CredentialOffer, ::serde_json::Value::String(content)).ok();
+        }
+
+        CredentialOffer {
+            id: offer.id,
+            comment: offer.comment,
+            credential_preview: CredentialPreviewData { attributes: offer.credential_preview.attributes, ..CredentialPreviewData::new() },
+            offers_attach,
+            thread: offer.thread,
+            timing: offer.timing,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::issuance::credential_offer::tests::{_credential_offer, _preview_data};
+
+    use super::*;
+
+    fn _attachment() -> ::serde_json::Value {
+        json!({
+            "schema_id":"NcYxiDXkpYi6ov5FcYDi1e:2:gvt:1.0",
+            "cred_def_id":"NcYxiDXkpYi6ov5FcYDi1e:3:CL:NcYxiDXkpYi6ov5FcYDi1e:2:gvt:1.0:TAG1"
+        })
+    }
+
+    fn _comment() -> Option<String> {
+        Some(String::from("comment"))
+    }
+
+    pub fn _credential_offer_v2() -> CredentialOfferV2 {
+        CredentialOfferV2::from_v1(&_credential_offer(), &CredentialFormat::Indy).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_credential_offer_v2_build_works() {
+        let credential_offer: CredentialOfferV2 = CredentialOfferV2::create()
+            .set_comment(_comment())
+            .set_credential_preview_data(_preview_data()).unwrap()
+            .set_offers_attach(&CredentialFormat::Indy, &_attachment().to_string()).unwrap();
+
+        assert_eq!(vec![AttachmentFormat { attach_id: "indy".to_string(), format: "hlindy/cred-abstract@v2.0".to_string() }], credential_offer.formats);
+        assert_eq!(_attachment().to_string(), credential_offer.offers_attach.content_by_id("indy").unwrap());
+        assert_eq!(Some(CredentialFormat::Indy), credential_offer.format());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_credential_offer_v2_converts_from_and_into_v1() {
+        let offer = _credential_offer_v2();
+        assert_eq!(_credential_offer().id, offer.id);
+
+        let offer: CredentialOffer = offer.into();
+        assert_eq!(_credential_offer(), offer);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_credential_offer_v2_serialization_works() {
+        let message = _credential_offer_v2().to_a2a_message();
+
+        let json = ::serde_json::to_value(&message).unwrap();
+        assert_eq!("did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/issue-credential/2.0/offer-credential", json["@type"]);
+        assert_eq!("did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/issue-credential/2.0/credential-preview", json["credential_preview"]["@type"]);
+
+        assert_eq!(message, ::serde_json::from_value::<A2AMessage>(json).unwrap());
+    }
+}
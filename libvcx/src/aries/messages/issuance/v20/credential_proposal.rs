@@ -0,0 +1,121 @@
+use error::VcxResult;
+use messages::thread::Thread;
+use aries::messages::a2a::{A2AMessage, MessageId};
+use aries::messages::attachment::{AttachmentFormat, Attachments};
+use aries::messages::issuance::CredentialPreviewData;
+use aries::messages::issuance::credential_proposal::CredentialProposal;
+use aries::messages::issuance::v20::{add_attachment, AttachmentKind, CredentialFormat, set_credential_preview_type, supported_attachment};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct CredentialProposalV2 {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credential_preview: Option<CredentialPreviewData>,
+    pub formats: Vec<AttachmentFormat>,
+    #[serde(rename = "filters~attach")]
+    pub filters_attach: Attachments,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(rename = "~thread")]
+    pub thread: Option<Thread>,
+}
+
+impl CredentialProposalV2 {
+    pub fn create() -> Self {
+        CredentialProposalV2::default()
+    }
+
+    pub fn set_comment(mut self, comment: String) -> Self {
+        self.comment = Some(comment);
+        self
+    }
+
+    pub fn set_credential_preview_data(mut self, credential_preview: CredentialPreviewData) -> Self {
+        self.credential_preview = Some(set_credential_preview_type(credential_preview));
+        self
+    }
+
+    pub fn set_filters_attach(mut self, format: &CredentialFormat, filter: &str) -> VcxResult<CredentialProposalV2> {
+        add_attachment(&mut self.formats, &mut self.filters_attach, format, &AttachmentKind::Filter, filter.to_string())?;
+        Ok(self)
+    }
+
+    pub fn set_thread_id(mut self, id: &str) -> Self {
+        self.thread = Some(Thread::new().set_thid(id.to_string()));
+        self
+    }
+
+    pub fn format(&self) -> Option<CredentialFormat> {
+        supported_attachment(&self.formats, &self.filters_attach, &AttachmentKind::Filter).map(|(format, _)| format)
+    }
+
+    pub fn from_v1(proposal: &CredentialProposal, format: &CredentialFormat) -> VcxResult<CredentialProposalV2> {
+        let filter = json!({
+            "schema_id": proposal.schema_id,
+            "cred_def_id": proposal.cred_def_id,
+        });
+
+        let mut proposal_v2 = CredentialProposalV2::create()
+            .set_credential_preview_data(proposal.credential_proposal.clone())
+            .set_filters_attach(format, &filter.to_string())?;
+        proposal_v2.id = proposal.id.clone();
+        proposal_v2.comment = proposal.comment.clone();
+        proposal_v2.thread = proposal.thread.clone();
+        Ok(proposal_v2)
+    }
+}
+
+a2a_message!(CredentialProposalV2);
+
+impl From<CredentialProposalV2> for CredentialProposal {
+    fn from(proposal: CredentialProposalV2) -> CredentialProposal {
+        let filter: ::serde_json::Value = supported_attachment(&proposal.formats, &proposal.filters_attach, &AttachmentKind::Filter)
+            .and_then(|(_, content)| ::serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        let attributes = proposal.credential_preview.map(|preview| preview.attributes).unwrap_or_default();
+
+        CredentialProposal {
+            id: proposal.id,
+            comment: proposal.comment,
+            credential_proposal: CredentialPreviewData { attributes, ..CredentialPreviewData::new() },
+            schema_id: filter["schema_id"].as_str().unwrap_or_default().to_string(),
+            cred_def_id: filter["cred_def_id"].as_str().unwrap_or_default().to_string(),
+            thread: proposal.thread,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::issuance::credential_proposal::tests::_credential_proposal;
+
+    use super::*;
+
+    pub fn _credential_proposal_v2() -> CredentialProposalV2 {
+        CredentialProposalV2::from_v1(&_credential_proposal(), &CredentialFormat::Indy).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_credential_proposal_v2_converts_from_and_into_v1() {
+        let proposal = _credential_proposal_v2();
+        assert_eq!(vec![AttachmentFormat { attach_id: "indy".to_string(), format: "hlindy/cred-filter@v2.0".to_string() }], proposal.formats);
+
+        let proposal: CredentialProposal = proposal.into();
+        assert_eq!(_credential_proposal(), proposal);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_credential_proposal_v2_serialization_works() {
+        let message = _credential_proposal_v2().to_a2a_message();
+
+        let json = ::serde_json::to_value(&message).unwrap();
+        assert_eq!("did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/issue-credential/2.0/propose-credential", json["@type"]);
+
+        assert_eq!(message, ::serde_json::from_value::<A2AMessage>(json).unwrap());
+    }
+}
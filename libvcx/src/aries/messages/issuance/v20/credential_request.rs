@@ -0,0 +1,96 @@
+use error::VcxResult;
+use messages::thread::Thread;
+use aries::messages::a2a::{A2AMessage, MessageId};
+use aries::messages::attachment::{AttachmentFormat, AttachmentId, Attachments};
+use aries::messages::issuance::credential_request::CredentialRequest;
+use aries::messages::issuance::v20::{add_attachment, AttachmentKind, CredentialFormat, supported_attachment};
+
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone, Default)]
+pub struct CredentialRequestV2 {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    pub formats: Vec<AttachmentFormat>,
+    #[serde(rename = "requests~attach")]
+    pub requests_attach: Attachments,
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+}
+
+impl CredentialRequestV2 {
+    pub fn create() -> Self {
+        CredentialRequestV2::default()
+    }
+
+    pub fn set_comment(mut self, comment: String) -> Self {
+        self.comment = Some(comment);
+        self
+    }
+
+    pub fn set_requests_attach(mut self, format: &CredentialFormat, credential_request: String) -> VcxResult<CredentialRequestV2> {
+        add_attachment(&mut self.formats, &mut self.requests_attach, format, &AttachmentKind::Request, credential_request)?;
+        Ok(self)
+    }
+
+    pub fn from_v1(request: &CredentialRequest, format: &CredentialFormat) -> VcxResult<CredentialRequestV2> {
+        let mut request_v2 = CredentialRequestV2::create()
+            .set_requests_attach(format, request.requests_attach.content()?)?;
+        request_v2.id = request.id.clone();
+        request_v2.comment = request.comment.clone();
+        request_v2.thread = request.thread.clone();
+        Ok(request_v2)
+    }
+}
+
+threadlike!(CredentialRequestV2);
+a2a_message!(CredentialRequestV2);
+
+impl From<CredentialRequestV2> for CredentialRequest {
+    fn from(request: CredentialRequestV2) -> CredentialRequest {
+        let mut requests_attach = Attachments::new();
+        if let Some((_, content)) = supported_attachment(&request.formats, &request.requests_attach, &AttachmentKind::Request) {
+            requests_attach.add_base64_encoded_json_attachment(AttachmentId::CredentialRequest, ::serde_json::Value::String(content)).ok();
+        }
+
+        CredentialRequest {
+            id: request.id,
+            comment: request.comment,
+            requests_attach,
+            thread: request.thread,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::issuance::credential_offer::tests::thread_id;
+    use aries::messages::issuance::credential_request::tests::_credential_request;
+
+    use super::*;
+
+    pub fn _credential_request_v2() -> CredentialRequestV2 {
+        CredentialRequestV2::from_v1(&_credential_request(), &CredentialFormat::Indy).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_credential_request_v2_converts_from_and_into_v1() {
+        let request = _credential_request_v2();
+        assert!(request.from_thread(&thread_id()));
+        assert_eq!(vec![AttachmentFormat { attach_id: "indy".to_string(), format: "hlindy/cred-req@v2.0".to_string() }], request.formats);
+
+        let request: CredentialRequest = request.into();
+        assert_eq!(_credential_request(), request);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_credential_request_v2_without_supported_format_converts_to_empty_request() {
+        let mut request = _credential_request_v2();
+        request.formats[0].format = String::from("aries/ld-proof-vc-detail@v1.0");
+
+        let request: CredentialRequest = request.into();
+        assert!(request.requests_attach.content().is_err());
+    }
+}
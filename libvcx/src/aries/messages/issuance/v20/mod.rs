@@ -0,0 +1,113 @@
+use error::prelude::*;
+use aries::messages::a2a::message_family::MessageFamilies;
+use aries::messages::a2a::message_type::MessageType;
+use aries::messages::attachment::{AttachmentFormat, AttachmentId, Attachments};
+use aries::messages::issuance::CredentialPreviewData;
+
+pub mod credential;
+pub mod credential_offer;
+pub mod credential_proposal;
+pub mod credential_request;
+
+/// Credential formats which can be carried by issue-credential/2.0 messages.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub enum CredentialFormat {
+    #[serde(rename = "indy")]
+    Indy,
+}
+
+/// Kind of the attachment, each credential format uses own identifier for every kind.
+#[derive(Debug, PartialEq, Clone)]
+pub enum AttachmentKind {
+    Filter,
+    Offer,
+    Request,
+    Credential,
+}
+
+impl Default for CredentialFormat {
+    fn default() -> CredentialFormat {
+        CredentialFormat::Indy
+    }
+}
+
+impl CredentialFormat {
+    pub fn supported() -> Vec<CredentialFormat> {
+        vec![CredentialFormat::Indy]
+    }
+
+    pub fn identifier(&self, kind: &AttachmentKind) -> &'static str {
+        match (self, kind) {
+            (CredentialFormat::Indy, AttachmentKind::Filter) => "hlindy/cred-filter@v2.0",
+            (CredentialFormat::Indy, AttachmentKind::Offer) => "hlindy/cred-abstract@v2.0",
+            (CredentialFormat::Indy, AttachmentKind::Request) => "hlindy/cred-req@v2.0",
+            (CredentialFormat::Indy, AttachmentKind::Credential) => "hlindy/cred@v2.0",
+        }
+    }
+
+    pub fn from_identifier(identifier: &str, kind: &AttachmentKind) -> Option<CredentialFormat> {
+        CredentialFormat::supported().into_iter()
+            .find(|format| format.identifier(kind) == identifier)
+    }
+
+    fn attach_id(&self) -> String {
+        match self {
+            CredentialFormat::Indy => String::from("indy")
+        }
+    }
+}
+
+fn add_attachment(formats: &mut Vec<AttachmentFormat>, attachments: &mut Attachments,
+                  format: &CredentialFormat, kind: &AttachmentKind, content: String) -> VcxResult<()> {
+    let attach_id = format.attach_id();
+    attachments.add_base64_encoded_json_attachment(AttachmentId::Other(attach_id.clone()), ::serde_json::Value::String(content))?;
+    formats.push(AttachmentFormat { attach_id, format: format.identifier(kind).to_string() });
+    Ok(())
+}
+
+/// Returns the first attachment which format is supported.
+fn supported_attachment(formats: &[AttachmentFormat], attachments: &Attachments, kind: &AttachmentKind) -> Option<(CredentialFormat, String)> {
+    formats.iter()
+        .filter_map(|attachment_format| {
+            CredentialFormat::from_identifier(&attachment_format.format, kind)
+                .and_then(|format| attachments.content_by_id(&attachment_format.attach_id).ok().map(|content| (format, content)))
+        })
+        .next()
+}
+
+fn set_credential_preview_type(mut preview: CredentialPreviewData) -> CredentialPreviewData {
+    preview._type = MessageType::build(MessageFamilies::CredentialIssuanceV2, "credential-preview");
+    preview
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_credential_format_identifiers_work() {
+        for kind in vec![AttachmentKind::Filter, AttachmentKind::Offer, AttachmentKind::Request, AttachmentKind::Credential] {
+            let identifier = CredentialFormat::Indy.identifier(&kind);
+            assert_eq!(Some(CredentialFormat::Indy), CredentialFormat::from_identifier(identifier, &kind));
+        }
+
+        assert_eq!(None, CredentialFormat::from_identifier("hlindy/cred-req@v2.0", &AttachmentKind::Offer));
+        assert_eq!(None, CredentialFormat::from_identifier("aries/ld-proof-vc-detail@v1.0", &AttachmentKind::Offer));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_supported_attachment_skips_unknown_formats() {
+        let mut formats = vec![AttachmentFormat { attach_id: String::from("ld-proof"), format: String::from("aries/ld-proof-vc-detail@v1.0") }];
+        let mut attachments = Attachments::new();
+        attachments.add_base64_encoded_json_attachment(AttachmentId::Other(String::from("ld-proof")), json!({"credential": {}})).unwrap();
+        add_attachment(&mut formats, &mut attachments, &CredentialFormat::Indy, &AttachmentKind::Offer, json!({"cred_def_id": "id"}).to_string()).unwrap();
+
+        let (format, content) = supported_attachment(&formats, &attachments, &AttachmentKind::Offer).unwrap();
+        assert_eq!(CredentialFormat::Indy, format);
+        assert_eq!(json!({"cred_def_id": "id"}).to_string(), content);
+
+        assert!(supported_attachment(&formats, &attachments, &AttachmentKind::Request).is_none());
+    }
+}
@@ -0,0 +1,73 @@
+use aries::messages::a2a::{A2AMessage, MessageId};
+use aries::messages::transport::{ReturnRoute, Transport};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct KeylistUpdate {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub updates: Vec<KeylistUpdateItem>,
+    #[serde(rename = "~transport")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transport: Option<Transport>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KeylistUpdateItem {
+    pub recipient_key: String,
+    pub action: KeylistAction,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum KeylistAction {
+    #[serde(rename = "add")]
+    Add,
+    #[serde(rename = "remove")]
+    Remove,
+}
+
+impl KeylistUpdate {
+    pub fn create() -> KeylistUpdate {
+        KeylistUpdate::default()
+    }
+
+    pub fn add_key(mut self, key: &str) -> KeylistUpdate {
+        self.updates.push(KeylistUpdateItem { recipient_key: key.to_string(), action: KeylistAction::Add });
+        self
+    }
+
+    pub fn remove_key(mut self, key: &str) -> KeylistUpdate {
+        self.updates.push(KeylistUpdateItem { recipient_key: key.to_string(), action: KeylistAction::Remove });
+        self
+    }
+}
+
+return_route!(KeylistUpdate);
+a2a_message!(KeylistUpdate);
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::connection::did_doc::tests::*;
+
+    use super::*;
+
+    pub fn _keylist_update() -> KeylistUpdate {
+        KeylistUpdate {
+            id: MessageId::id(),
+            updates: vec![
+                KeylistUpdateItem { recipient_key: _key_1(), action: KeylistAction::Add },
+                KeylistUpdateItem { recipient_key: _key_2(), action: KeylistAction::Remove },
+            ],
+            transport: None,
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_keylist_update_build_works() {
+        let update: KeylistUpdate = KeylistUpdate::create()
+            .add_key(&_key_1())
+            .remove_key(&_key_2());
+
+        assert_eq!(_keylist_update(), update);
+    }
+}
@@ -0,0 +1,86 @@
+use messages::thread::Thread;
+use aries::messages::a2a::{A2AMessage, MessageId};
+use aries::messages::mediation::keylist_update::KeylistAction;
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct KeylistUpdateResponse {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub updated: Vec<KeylistUpdated>,
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct KeylistUpdated {
+    pub recipient_key: String,
+    pub action: KeylistAction,
+    pub result: KeylistUpdateResult,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum KeylistUpdateResult {
+    #[serde(rename = "client_error")]
+    ClientError,
+    #[serde(rename = "server_error")]
+    ServerError,
+    #[serde(rename = "no_change")]
+    NoChange,
+    #[serde(rename = "success")]
+    Success,
+}
+
+impl KeylistUpdateResponse {
+    pub fn create() -> KeylistUpdateResponse {
+        KeylistUpdateResponse::default()
+    }
+
+    pub fn add_updated(mut self, recipient_key: &str, action: KeylistAction, result: KeylistUpdateResult) -> KeylistUpdateResponse {
+        self.updated.push(KeylistUpdated { recipient_key: recipient_key.to_string(), action, result });
+        self
+    }
+
+    /// Returns the updates the mediator could not apply.
+    pub fn failed(&self) -> Vec<&KeylistUpdated> {
+        self.updated.iter()
+            .filter(|updated| updated.result == KeylistUpdateResult::ClientError || updated.result == KeylistUpdateResult::ServerError)
+            .collect()
+    }
+}
+
+threadlike!(KeylistUpdateResponse);
+a2a_message!(KeylistUpdateResponse);
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::connection::did_doc::tests::*;
+    use aries::messages::connection::response::tests::*;
+
+    use super::*;
+
+    pub fn _keylist_update_response() -> KeylistUpdateResponse {
+        KeylistUpdateResponse {
+            id: MessageId::id(),
+            updated: vec![
+                KeylistUpdated { recipient_key: _key_1(), action: KeylistAction::Add, result: KeylistUpdateResult::Success },
+                KeylistUpdated { recipient_key: _key_2(), action: KeylistAction::Remove, result: KeylistUpdateResult::NoChange },
+            ],
+            thread: _thread(),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_keylist_update_response_build_works() {
+        let response: KeylistUpdateResponse = KeylistUpdateResponse::create()
+            .add_updated(&_key_1(), KeylistAction::Add, KeylistUpdateResult::Success)
+            .add_updated(&_key_2(), KeylistAction::Remove, KeylistUpdateResult::NoChange)
+            .set_thread_id(&_thread_id());
+
+        assert_eq!(_keylist_update_response(), response);
+        assert!(response.failed().is_empty());
+
+        let response = response.add_updated(&_key_3(), KeylistAction::Add, KeylistUpdateResult::ServerError);
+        assert_eq!(vec![&response.updated[2]], response.failed());
+    }
+}
@@ -0,0 +1,42 @@
+use messages::thread::Thread;
+use aries::messages::a2a::{A2AMessage, MessageId};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct MediateDeny {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+}
+
+impl MediateDeny {
+    pub fn create() -> MediateDeny {
+        MediateDeny::default()
+    }
+}
+
+threadlike!(MediateDeny);
+a2a_message!(MediateDeny);
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::connection::response::tests::*;
+
+    use super::*;
+
+    pub fn _mediate_deny() -> MediateDeny {
+        MediateDeny {
+            id: MessageId::id(),
+            thread: _thread(),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_mediate_deny_build_works() {
+        let deny: MediateDeny = MediateDeny::create()
+            .set_thread_id(&_thread_id());
+
+        assert_eq!(_mediate_deny(), deny);
+    }
+}
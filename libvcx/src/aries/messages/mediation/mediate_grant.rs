@@ -0,0 +1,60 @@
+use messages::thread::Thread;
+use aries::messages::a2a::{A2AMessage, MessageId};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct MediateGrant {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub endpoint: String,
+    #[serde(default)]
+    pub routing_keys: Vec<String>,
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+}
+
+impl MediateGrant {
+    pub fn create() -> MediateGrant {
+        MediateGrant::default()
+    }
+
+    pub fn set_endpoint(mut self, endpoint: String) -> MediateGrant {
+        self.endpoint = endpoint;
+        self
+    }
+
+    pub fn set_routing_keys(mut self, routing_keys: Vec<String>) -> MediateGrant {
+        self.routing_keys = routing_keys;
+        self
+    }
+}
+
+threadlike!(MediateGrant);
+a2a_message!(MediateGrant);
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::connection::did_doc::tests::*;
+    use aries::messages::connection::response::tests::*;
+
+    use super::*;
+
+    pub fn _mediate_grant() -> MediateGrant {
+        MediateGrant {
+            id: MessageId::id(),
+            endpoint: _service_endpoint(),
+            routing_keys: vec![_key_2()],
+            thread: _thread(),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_mediate_grant_build_works() {
+        let grant: MediateGrant = MediateGrant::create()
+            .set_endpoint(_service_endpoint())
+            .set_routing_keys(vec![_key_2()])
+            .set_thread_id(&_thread_id());
+
+        assert_eq!(_mediate_grant(), grant);
+    }
+}
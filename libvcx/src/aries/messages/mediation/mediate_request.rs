@@ -0,0 +1,42 @@
+use aries::messages::a2a::{A2AMessage, MessageId};
+use aries::messages::transport::{ReturnRoute, Transport};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct MediateRequest {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(rename = "~transport")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transport: Option<Transport>,
+}
+
+impl MediateRequest {
+    pub fn create() -> MediateRequest {
+        MediateRequest::default()
+    }
+}
+
+return_route!(MediateRequest);
+a2a_message!(MediateRequest);
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    pub fn _mediate_request() -> MediateRequest {
+        MediateRequest {
+            id: MessageId::id(),
+            transport: Some(Transport { return_route: ReturnRoute::All }),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_mediate_request_build_works() {
+        let request: MediateRequest = MediateRequest::create()
+            .request_return_route();
+
+        assert_eq!(_mediate_request(), request);
+        assert_eq!(json!({"return_route": "all"}), json!(request.to_a2a_message())["~transport"]);
+    }
+}
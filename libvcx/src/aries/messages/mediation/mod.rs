@@ -0,0 +1,5 @@
+pub mod mediate_request;
+pub mod mediate_grant;
+pub mod mediate_deny;
+pub mod keylist_update;
+pub mod keylist_update_response;
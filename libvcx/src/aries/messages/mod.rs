@@ -2,7 +2,12 @@
 pub mod a2a;
 #[macro_use]
 pub mod ack;
+#[macro_use]
+pub mod transport;
+#[macro_use]
+pub mod timing;
 pub mod connection;
+pub mod did_exchange;
 pub mod error;
 pub mod forward;
 pub mod attachment;
@@ -13,4 +18,9 @@ pub mod proof_presentation;
 pub mod discovery;
 pub mod trust_ping;
 pub mod basic_message;
-pub mod localization;
\ No newline at end of file
+pub mod out_of_band;
+pub mod localization;
+pub mod mediation;
+pub mod pickup;
+pub mod signature;
+pub mod trace;
\ No newline at end of file
@@ -0,0 +1,324 @@
+use std::convert::TryInto;
+
+use error::prelude::*;
+use aries::messages::a2a::{A2AMessage, MessageId};
+use aries::messages::a2a::message_family::MessageFamilies;
+use aries::messages::attachment::{Attachment, AttachmentId, Attachments};
+use aries::messages::connection::did_doc::DidDoc;
+use aries::messages::connection::invite::Invitation;
+
+pub const SERVICE_TYPE: &str = "did-communication";
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq, Default)]
+pub struct OutOfBandInvitation {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goal_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub goal: Option<String>,
+    #[serde(default)]
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub handshake_protocols: Vec<String>,
+    #[serde(default)]
+    #[serde(rename = "requests~attach")]
+    pub requests_attach: Attachments,
+    #[serde(default)]
+    #[serde(rename = "service")]
+    pub services: Vec<ServiceResolvable>,
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+#[serde(untagged)]
+pub enum ServiceResolvable {
+    Service(OutOfBandService),
+    Did(String),
+}
+
+#[derive(Debug, Clone, Deserialize, Serialize, PartialEq)]
+pub struct OutOfBandService {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub type_: String,
+    #[serde(default)]
+    #[serde(rename = "recipientKeys")]
+    pub recipient_keys: Vec<String>,
+    #[serde(default)]
+    #[serde(rename = "routingKeys")]
+    pub routing_keys: Vec<String>,
+    #[serde(rename = "serviceEndpoint")]
+    pub service_endpoint: String,
+}
+
+impl Default for OutOfBandService {
+    fn default() -> OutOfBandService {
+        OutOfBandService {
+            id: String::from("#inline"),
+            type_: String::from(SERVICE_TYPE),
+            recipient_keys: Vec::new(),
+            routing_keys: Vec::new(),
+            service_endpoint: String::new(),
+        }
+    }
+}
+
+impl OutOfBandInvitation {
+    pub fn create() -> OutOfBandInvitation {
+        OutOfBandInvitation::default()
+    }
+
+    pub fn set_id(mut self, id: String) -> OutOfBandInvitation {
+        self.id = MessageId(id);
+        self
+    }
+
+    pub fn set_label(mut self, label: String) -> OutOfBandInvitation {
+        self.label = Some(label);
+        self
+    }
+
+    pub fn set_goal_code(mut self, goal_code: Option<String>) -> OutOfBandInvitation {
+        self.goal_code = goal_code;
+        self
+    }
+
+    pub fn set_goal(mut self, goal: Option<String>) -> OutOfBandInvitation {
+        self.goal = goal;
+        self
+    }
+
+    pub fn set_handshake_protocols(mut self, protocols: Vec<MessageFamilies>) -> OutOfBandInvitation {
+        self.handshake_protocols = protocols.iter().map(MessageFamilies::id).collect();
+        self
+    }
+
+    pub fn set_service(mut self, service: OutOfBandService) -> OutOfBandInvitation {
+        self.services.push(ServiceResolvable::Service(service));
+        self
+    }
+
+    pub fn set_requests_attach(mut self, requests: Vec<A2AMessage>) -> VcxResult<OutOfBandInvitation> {
+        for (index, request) in requests.into_iter().enumerate() {
+            self.requests_attach.add_base64_encoded_json_attachment(AttachmentId::out_of_band_request(index), json!(request))?;
+        }
+        Ok(self)
+    }
+
+    /// Checks whether the invitation offers the given handshake protocol.
+    /// Only the family name and version are compared, so both `did:sov:...;spec/` and `https://didcomm.org/` prefixes match.
+    pub fn supports_handshake(&self, family: &MessageFamilies) -> bool {
+        let suffix = format!("/{}/{}", family.to_string(), family.version());
+        self.handshake_protocols.iter().any(|protocol| protocol.ends_with(&suffix))
+    }
+
+    /// Invitation offering no handshake but carrying requests starts connectionless exchange.
+    pub fn is_connectionless(&self) -> bool {
+        self.handshake_protocols.is_empty() && !self.requests_attach.0.is_empty()
+    }
+
+    /// Returns the messages carried in `requests~attach` (e.g. a credential offer or a presentation request).
+    pub fn get_requests(&self) -> VcxResult<Vec<A2AMessage>> {
+        self.requests_attach.0.iter()
+            .map(|attachment| {
+                match attachment {
+                    Attachment::JSON(ref json) => {
+                        ::serde_json::from_str::<A2AMessage>(&json.get_data()?)
+                            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize attached request: {}", err)))
+                    }
+                    Attachment::Blank => Err(VcxError::from_msg(VcxErrorKind::InvalidJson, "Unsupported Attachment type"))
+                }
+            })
+            .collect()
+    }
+
+    /// Returns the first service of the invitation. Service referenced by a public DID is resolved from the ledger.
+    pub fn get_service(&self) -> VcxResult<OutOfBandService> {
+        match self.services.get(0) {
+            Some(ServiceResolvable::Service(service)) => Ok(service.clone()),
+            Some(ServiceResolvable::Did(did)) => {
+                let did_doc = DidDoc::resolve_public_did(did)?;
+                let (recipient_keys, routing_keys) = did_doc.resolve_keys();
+
+                Ok(OutOfBandService {
+                    id: did.to_string(),
+                    recipient_keys,
+                    routing_keys,
+                    service_endpoint: did_doc.get_endpoint(),
+                    ..OutOfBandService::default()
+                })
+            }
+            None => Err(VcxError::from_msg(VcxErrorKind::InvalidJson, "Out-of-Band invitation does not contain any service"))
+        }
+    }
+}
+
+a2a_message!(OutOfBandInvitation);
+
+impl From<Invitation> for OutOfBandInvitation {
+    fn from(invitation: Invitation) -> OutOfBandInvitation {
+        let service = OutOfBandService {
+            recipient_keys: invitation.recipient_keys,
+            routing_keys: invitation.routing_keys,
+            service_endpoint: invitation.service_endpoint,
+            ..OutOfBandService::default()
+        };
+
+        OutOfBandInvitation::create()
+            .set_id(invitation.id.0)
+            .set_label(invitation.label)
+            .set_service(service)
+    }
+}
+
+impl TryInto<Invitation> for OutOfBandInvitation {
+    type Error = VcxError;
+
+    fn try_into(self) -> Result<Invitation, Self::Error> {
+        if !self.supports_handshake(&MessageFamilies::Connections) {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Out-of-Band invitation does not offer supported handshake protocol: {:?}", self.handshake_protocols)));
+        }
+
+        let invitation = Invitation::create()
+            .set_id(self.id.0.clone())
+            .set_label(self.label.clone().unwrap_or_default());
+
+        // public DID is kept as is, it is resolved once the invitation is processed
+        if let Some(ServiceResolvable::Did(did)) = self.services.get(0) {
+            return Ok(invitation.set_did(did.to_string()));
+        }
+
+        let service = self.get_service()?;
+
+        Ok(invitation
+            .set_service_endpoint(service.service_endpoint)
+            .set_recipient_keys(service.recipient_keys)
+            .set_routing_keys(service.routing_keys))
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+    use aries::messages::connection::did_doc::tests::*;
+    use aries::messages::connection::invite::tests::_invitation;
+    use aries::messages::issuance::credential_offer::tests::_credential_offer;
+    use aries::messages::proof_presentation::presentation_request::tests::_presentation_request;
+    use utils::devsetup::SetupMocks;
+
+    pub fn _service() -> OutOfBandService {
+        OutOfBandService {
+            recipient_keys: _recipient_keys(),
+            routing_keys: _routing_keys(),
+            service_endpoint: _service_endpoint(),
+            ..OutOfBandService::default()
+        }
+    }
+
+    pub fn _out_of_band_invitation() -> OutOfBandInvitation {
+        OutOfBandInvitation {
+            id: MessageId::id(),
+            label: Some(_label()),
+            goal_code: None,
+            goal: None,
+            handshake_protocols: vec![MessageFamilies::Connections.id()],
+            requests_attach: Attachments::new(),
+            services: vec![ServiceResolvable::Service(_service())],
+        }
+    }
+
+    pub fn _out_of_band_invitation_json() -> String {
+        ::serde_json::to_string(&_out_of_band_invitation().to_a2a_message()).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_out_of_band_invitation_build_works() {
+        let invitation: OutOfBandInvitation = OutOfBandInvitation::default()
+            .set_label(_label())
+            .set_handshake_protocols(vec![MessageFamilies::Connections])
+            .set_service(_service());
+
+        assert_eq!(_out_of_band_invitation(), invitation);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_out_of_band_invitation_converts_from_and_into_connection_invitation() {
+        let mut invitation = OutOfBandInvitation::from(_invitation());
+        invitation.handshake_protocols = vec![MessageFamilies::Connections.id()];
+        assert_eq!(_out_of_band_invitation(), invitation);
+
+        let invitation: Invitation = invitation.try_into().unwrap();
+        assert_eq!(_invitation(), invitation);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_out_of_band_invitation_into_connection_invitation_fails_without_handshake() {
+        let mut invitation = _out_of_band_invitation();
+        invitation.handshake_protocols = vec![];
+
+        let res: VcxResult<Invitation> = invitation.try_into();
+        assert_eq!(VcxErrorKind::InvalidJson, res.unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_out_of_band_invitation_supports_didcomm_org_prefix() {
+        let mut invitation = _out_of_band_invitation();
+        invitation.handshake_protocols = vec!["https://didcomm.org/connections/1.0".to_string()];
+
+        assert!(invitation.supports_handshake(&MessageFamilies::Connections));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_out_of_band_invitation_requests_attach_works() {
+        let invitation = _out_of_band_invitation()
+            .set_requests_attach(vec![_credential_offer().to_a2a_message()]).unwrap();
+
+        let invitation: OutOfBandInvitation = ::serde_json::from_str(&::serde_json::to_string(&invitation).unwrap()).unwrap();
+        assert_eq!(vec![_credential_offer().to_a2a_message()], invitation.get_requests().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_out_of_band_invitation_numbers_attached_requests() {
+        let requests = vec![_credential_offer().to_a2a_message(), _presentation_request().to_a2a_message()];
+
+        let invitation = _out_of_band_invitation()
+            .set_requests_attach(requests.clone()).unwrap();
+
+        let invitation_json = json!(invitation);
+        assert_eq!("request-0", invitation_json["requests~attach"][0]["@id"]);
+        assert_eq!("request-1", invitation_json["requests~attach"][1]["@id"]);
+
+        let invitation: OutOfBandInvitation = ::serde_json::from_value(invitation_json).unwrap();
+        assert_eq!(requests, invitation.get_requests().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_out_of_band_invitation_resolves_did_service() {
+        let _setup = SetupMocks::init();
+
+        let invitation: OutOfBandInvitation = ::serde_json::from_value(json!({
+            "@id": "testid",
+            "handshake_protocols": ["https://didcomm.org/connections/1.0"],
+            "service": [_public_did()]
+        })).unwrap();
+
+        assert_eq!(vec![ServiceResolvable::Did(_public_did())], invitation.services);
+
+        let service = invitation.get_service().unwrap();
+        assert_eq!(_public_did(), service.id);
+        assert_eq!(_recipient_keys(), service.recipient_keys);
+        assert_eq!(_service_endpoint(), service.service_endpoint);
+
+        let invitation: Invitation = invitation.try_into().unwrap();
+        assert_eq!(Some(_public_did()), invitation.did);
+        assert!(invitation.recipient_keys.is_empty());
+    }
+}
@@ -0,0 +1,68 @@
+use messages::thread::Thread;
+use aries::messages::a2a::{A2AMessage, MessageId};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Batch {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(rename = "messages~attach")]
+    pub messages: Vec<BatchMessage>,
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+}
+
+/// Queued message as it was delivered to the mediator, i.e. still packed for the final recipient.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct BatchMessage {
+    #[serde(rename = "@id")]
+    pub id: String,
+    pub message: ::serde_json::Value,
+}
+
+impl Batch {
+    pub fn create() -> Batch {
+        Batch::default()
+    }
+
+    pub fn add_message(mut self, id: &str, message: ::serde_json::Value) -> Batch {
+        self.messages.push(BatchMessage { id: id.to_string(), message });
+        self
+    }
+
+    pub fn set_thread_id(mut self, id: &str) -> Batch {
+        self.thread = Some(Thread::new().set_thid(id.to_string()));
+        self
+    }
+}
+
+a2a_message!(Batch);
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::connection::response::tests::*;
+
+    use super::*;
+
+    pub fn _packed_message() -> ::serde_json::Value {
+        json!({"protected": "eyJlbmMiOiJ4Y2hhY2hhMjBwb2x5MTMwNV9pZXRmIn0", "iv": "iv", "ciphertext": "ciphertext", "tag": "tag"})
+    }
+
+    pub fn _batch() -> Batch {
+        Batch {
+            id: MessageId::id(),
+            messages: vec![BatchMessage { id: String::from("1"), message: _packed_message() }],
+            thread: Some(Thread::new().set_thid(_thread_id())),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_batch_build_works() {
+        let batch: Batch = Batch::create()
+            .add_message("1", _packed_message())
+            .set_thread_id(&_thread_id());
+
+        assert_eq!(_batch(), batch);
+    }
+}
@@ -0,0 +1,48 @@
+use aries::messages::a2a::{A2AMessage, MessageId};
+use aries::messages::transport::{ReturnRoute, Transport};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct BatchPickup {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub batch_size: u32,
+    #[serde(rename = "~transport")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transport: Option<Transport>,
+}
+
+impl BatchPickup {
+    pub fn create() -> BatchPickup {
+        BatchPickup::default()
+    }
+
+    pub fn set_batch_size(mut self, batch_size: u32) -> BatchPickup {
+        self.batch_size = batch_size;
+        self
+    }
+}
+
+return_route!(BatchPickup);
+a2a_message!(BatchPickup);
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    pub fn _batch_pickup() -> BatchPickup {
+        BatchPickup {
+            id: MessageId::id(),
+            batch_size: 10,
+            transport: None,
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_batch_pickup_build_works() {
+        let pickup: BatchPickup = BatchPickup::create()
+            .set_batch_size(10);
+
+        assert_eq!(_batch_pickup(), pickup);
+    }
+}
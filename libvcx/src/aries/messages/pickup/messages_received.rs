@@ -0,0 +1,50 @@
+use aries::messages::a2a::{A2AMessage, MessageId};
+use aries::messages::transport::{ReturnRoute, Transport};
+
+/// Acknowledges picked up messages, the mediator removes them from its queue only after receiving it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct MessagesReceived {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub message_id_list: Vec<String>,
+    #[serde(rename = "~transport")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transport: Option<Transport>,
+}
+
+impl MessagesReceived {
+    pub fn create() -> MessagesReceived {
+        MessagesReceived::default()
+    }
+
+    pub fn set_message_ids(mut self, message_id_list: Vec<String>) -> MessagesReceived {
+        self.message_id_list = message_id_list;
+        self
+    }
+}
+
+return_route!(MessagesReceived);
+a2a_message!(MessagesReceived);
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    pub fn _messages_received() -> MessagesReceived {
+        MessagesReceived {
+            id: MessageId::id(),
+            message_id_list: vec![String::from("1")],
+            transport: Some(Transport { return_route: ReturnRoute::All }),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_messages_received_build_works() {
+        let received: MessagesReceived = MessagesReceived::create()
+            .set_message_ids(vec![String::from("1")])
+            .request_return_route();
+
+        assert_eq!(_messages_received(), received);
+    }
+}
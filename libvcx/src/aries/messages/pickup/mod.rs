@@ -0,0 +1,5 @@
+pub mod status_request;
+pub mod status;
+pub mod batch_pickup;
+pub mod batch;
+pub mod messages_received;
@@ -0,0 +1,66 @@
+use messages::thread::Thread;
+use aries::messages::a2a::{A2AMessage, MessageId};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Status {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    pub message_count: u32,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_waited: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_added_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_delivered_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_removed_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_size: Option<u32>,
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+}
+
+impl Status {
+    pub fn create() -> Status {
+        Status::default()
+    }
+
+    pub fn set_message_count(mut self, message_count: u32) -> Status {
+        self.message_count = message_count;
+        self
+    }
+
+    pub fn set_thread_id(mut self, id: &str) -> Status {
+        self.thread = Some(Thread::new().set_thid(id.to_string()));
+        self
+    }
+}
+
+a2a_message!(Status, PickupStatus);
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::connection::response::tests::*;
+
+    use super::*;
+
+    pub fn _status() -> Status {
+        Status {
+            id: MessageId::id(),
+            message_count: 2,
+            thread: Some(Thread::new().set_thid(_thread_id())),
+            ..Status::default()
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_status_build_works() {
+        let status: Status = Status::create()
+            .set_message_count(2)
+            .set_thread_id(&_thread_id());
+
+        assert_eq!(_status(), status);
+    }
+}
@@ -0,0 +1,41 @@
+use aries::messages::a2a::{A2AMessage, MessageId};
+use aries::messages::transport::{ReturnRoute, Transport};
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct StatusRequest {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(rename = "~transport")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub transport: Option<Transport>,
+}
+
+impl StatusRequest {
+    pub fn create() -> StatusRequest {
+        StatusRequest::default()
+    }
+}
+
+return_route!(StatusRequest);
+a2a_message!(StatusRequest, PickupStatusRequest);
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    pub fn _status_request() -> StatusRequest {
+        StatusRequest {
+            id: MessageId::id(),
+            transport: Some(Transport { return_route: ReturnRoute::All }),
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_status_request_build_works() {
+        let request: StatusRequest = StatusRequest::create()
+            .request_return_route();
+
+        assert_eq!(_status_request(), request);
+    }
+}
@@ -1,3 +1,8 @@
+use std::collections::HashMap;
+use std::convert::TryFrom;
+
+use error::prelude::*;
+use messages::proofs::proof_request::{AttrInfo, Filter, PredicateInfo, ProofRequestData, Restrictions};
 use messages::thread::Thread;
 use aries::messages::a2a::{A2AMessage, MessageId};
 use aries::messages::a2a::message_family::MessageFamilies;
@@ -63,6 +68,53 @@ impl PresentationProposal {
     }
 }
 
+impl PresentationPreview {
+    /// Builds proof request data asking for the previewed attributes and predicates.
+    /// Items bound to a credential definition accept only credentials issued with it.
+    pub fn to_proof_request_data(&self, name: &str) -> VcxResult<ProofRequestData> {
+        let requested_attributes: HashMap<String, AttrInfo> = self.attributes.iter().enumerate()
+            .map(|(index, attribute)| (format!("attribute_{}", index), AttrInfo {
+                name: Some(attribute.name.clone()),
+                names: None,
+                restrictions: _cred_def_restrictions(&attribute.cred_def_id),
+                non_revoked: None,
+                self_attest_allowed: None,
+            }))
+            .collect();
+
+        let requested_predicates = self.predicates.iter().enumerate()
+            .map(|(index, predicate)| {
+                let p_value = i32::try_from(predicate.threshold)
+                    .map_err(|_| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Predicate threshold is out of range: {}", predicate.threshold)))?;
+
+                Ok((format!("predicate_{}", index), PredicateInfo {
+                    name: predicate.name.clone(),
+                    p_type: predicate.predicate.clone(),
+                    p_value,
+                    restrictions: _cred_def_restrictions(&predicate.cred_def_id),
+                    non_revoked: None,
+                }))
+            })
+            .collect::<VcxResult<HashMap<String, PredicateInfo>>>()?;
+
+        let mut proof_request_data = ProofRequestData::create().set_name(name.to_string());
+        proof_request_data.requested_attributes = requested_attributes;
+        proof_request_data.requested_predicates = requested_predicates;
+        Ok(proof_request_data)
+    }
+}
+
+fn _cred_def_restrictions(cred_def_id: &Option<String>) -> Option<Restrictions> {
+    cred_def_id.as_ref().map(|cred_def_id| Restrictions::V1(vec![Filter {
+        schema_id: None,
+        schema_issuer_did: None,
+        schema_name: None,
+        schema_version: None,
+        issuer_did: None,
+        cred_def_id: Some(cred_def_id.clone()),
+    }]))
+}
+
 threadlike!(PresentationProposal);
 a2a_message!(PresentationProposal);
 
@@ -113,4 +165,56 @@ pub mod tests {
 
         assert_eq!(_presentation_proposal(), presentation_proposal);
     }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_presentation_preview_to_proof_request_data_works() {
+        let preview = PresentationPreview {
+            attributes: vec![Attribute {
+                name: String::from("name"),
+                cred_def_id: Some(String::from("cred_def_id:id")),
+                mime_type: None,
+                value: None,
+                filter: None,
+            }],
+            predicates: vec![Predicate {
+                name: String::from("age"),
+                cred_def_id: None,
+                predicate: String::from(">="),
+                threshold: 18,
+                filter: None,
+            }],
+            ..Default::default()
+        };
+
+        let proof_request_data = preview.to_proof_request_data("proposal").unwrap();
+        assert_eq!("proposal", proof_request_data.name);
+
+        let attribute = &proof_request_data.requested_attributes["attribute_0"];
+        assert_eq!(Some(String::from("name")), attribute.name);
+        assert_eq!(json!("cred_def_id:id"), json!(attribute.restrictions)[0]["cred_def_id"]);
+
+        let predicate = &proof_request_data.requested_predicates["predicate_0"];
+        assert_eq!("age", predicate.name);
+        assert_eq!(18, predicate.p_value);
+        assert_eq!(None, predicate.restrictions);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_presentation_preview_to_proof_request_data_fails_for_huge_threshold() {
+        let preview = PresentationPreview {
+            attributes: vec![],
+            predicates: vec![Predicate {
+                name: String::from("age"),
+                cred_def_id: None,
+                predicate: String::from(">="),
+                threshold: i64::from(i32::max_value()) + 1,
+                filter: None,
+            }],
+            ..Default::default()
+        };
+
+        assert_eq!(VcxErrorKind::InvalidJson, preview.to_proof_request_data("proposal").unwrap_err().kind());
+    }
 }
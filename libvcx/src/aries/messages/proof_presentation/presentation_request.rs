@@ -1,10 +1,12 @@
 use std::convert::TryInto;
 
 use error::prelude::*;
+use messages::thread::Thread;
 pub use messages::proofs::proof_request::{ProofRequestData, ProofRequestMessage, ProofRequestVersion};
 use aries::messages::a2a::{A2AMessage, MessageId};
 use aries::messages::attachment::{AttachmentId, Attachments};
 use aries::messages::connection::service::Service;
+use aries::messages::timing::Timing;
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
 pub struct PresentationRequest {
@@ -17,6 +19,12 @@ pub struct PresentationRequest {
     #[serde(rename = "~service")]
     #[serde(skip_serializing_if = "Option::is_none")]
     pub service: Option<Service>,
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
 }
 
 impl PresentationRequest {
@@ -43,12 +51,27 @@ impl PresentationRequest {
         self.service = service;
         self
     }
+
+    pub fn set_thread_id(mut self, id: &str) -> Self {
+        self.thread = Some(Thread::new().set_thid(id.to_string()));
+        self
+    }
+
+    /// Requests sent in reply to a presentation proposal continue the proposal's thread,
+    /// otherwise the request starts a new thread identified by its own id.
+    pub fn thread_id(&self) -> String {
+        self.thread.as_ref()
+            .and_then(|thread| thread.thid.clone())
+            .unwrap_or(self.id.0.clone())
+    }
+
     pub fn to_json(&self) -> VcxResult<String> {
         serde_json::to_string(self)
             .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot serialize PresentationRequest: {}", err)))
     }
 }
 
+timing!(PresentationRequest);
 a2a_message!(PresentationRequest);
 
 impl TryInto<PresentationRequest> for ProofRequestMessage {
@@ -75,7 +98,7 @@ impl TryInto<ProofRequestMessage> for PresentationRequest {
             )?
             .type_version("1.0")?
             .proof_data_version("0.1")?
-            .set_thread_id(self.id.0.clone())?
+            .set_thread_id(self.thread_id())?
             .set_service(self.service)?
             .clone();
 
@@ -87,7 +110,6 @@ pub type PresentationRequestData = ProofRequestData;
 
 #[cfg(test)]
 pub mod tests {
-    use messages::thread::Thread;
     use aries::messages::connection::service::tests::_service;
 
     use super::*;
@@ -121,6 +143,8 @@ pub mod tests {
             comment: Some(_comment()),
             request_presentations_attach: _attachment(),
             service: None,
+            thread: None,
+            timing: None,
         }
     }
 
@@ -130,6 +154,8 @@ pub mod tests {
             comment: Some(_comment()),
             request_presentations_attach: _attachment(),
             service: Some(_service()),
+            thread: None,
+            timing: None,
         }
     }
 
@@ -153,4 +179,14 @@ pub mod tests {
 
         assert_eq!(_presentation_request_with_service(), presentation_request);
     }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_presentation_request_thread_id_works() {
+        let presentation_request = _presentation_request();
+        assert_eq!(presentation_request.id.0, presentation_request.thread_id());
+
+        let presentation_request = presentation_request.set_thread_id("proposal_thread");
+        assert_eq!("proposal_thread", presentation_request.thread_id());
+    }
 }
\ No newline at end of file
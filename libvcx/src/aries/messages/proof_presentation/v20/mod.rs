@@ -0,0 +1,85 @@
+use error::prelude::*;
+use aries::messages::attachment::{AttachmentFormat, AttachmentId, Attachments};
+
+pub mod presentation;
+pub mod presentation_proposal;
+pub mod presentation_request;
+
+/// Presentation formats which can be carried by present-proof/2.0 messages.
+#[derive(Debug, Serialize, Deserialize, PartialEq, Clone)]
+pub enum PresentationFormat {
+    #[serde(rename = "indy")]
+    Indy,
+}
+
+/// Kind of the attachment, each presentation format uses own identifier for every kind.
+#[derive(Debug, PartialEq, Clone)]
+pub enum AttachmentKind {
+    Proposal,
+    Request,
+    Presentation,
+}
+
+impl Default for PresentationFormat {
+    fn default() -> PresentationFormat {
+        PresentationFormat::Indy
+    }
+}
+
+impl PresentationFormat {
+    pub fn supported() -> Vec<PresentationFormat> {
+        vec![PresentationFormat::Indy]
+    }
+
+    pub fn identifier(&self, kind: &AttachmentKind) -> &'static str {
+        match (self, kind) {
+            (PresentationFormat::Indy, AttachmentKind::Proposal) => "hlindy/proof-req@v2.0",
+            (PresentationFormat::Indy, AttachmentKind::Request) => "hlindy/proof-req@v2.0",
+            (PresentationFormat::Indy, AttachmentKind::Presentation) => "hlindy/proof@v2.0",
+        }
+    }
+
+    pub fn from_identifier(identifier: &str, kind: &AttachmentKind) -> Option<PresentationFormat> {
+        PresentationFormat::supported().into_iter()
+            .find(|format| format.identifier(kind) == identifier)
+    }
+
+    fn attach_id(&self) -> String {
+        match self {
+            PresentationFormat::Indy => String::from("indy")
+        }
+    }
+}
+
+fn add_attachment(formats: &mut Vec<AttachmentFormat>, attachments: &mut Attachments,
+                  format: &PresentationFormat, kind: &AttachmentKind, content: String) -> VcxResult<()> {
+    let attach_id = format.attach_id();
+    attachments.add_base64_encoded_json_attachment(AttachmentId::Other(attach_id.clone()), ::serde_json::Value::String(content))?;
+    formats.push(AttachmentFormat { attach_id, format: format.identifier(kind).to_string() });
+    Ok(())
+}
+
+/// Returns the first attachment which format is supported.
+fn supported_attachment(formats: &[AttachmentFormat], attachments: &Attachments, kind: &AttachmentKind) -> Option<(PresentationFormat, String)> {
+    formats.iter()
+        .filter_map(|attachment_format| {
+            PresentationFormat::from_identifier(&attachment_format.format, kind)
+                .and_then(|format| attachments.content_by_id(&attachment_format.attach_id).ok().map(|content| (format, content)))
+        })
+        .next()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_presentation_format_identifiers_work() {
+        assert_eq!(Some(PresentationFormat::Indy), PresentationFormat::from_identifier("hlindy/proof-req@v2.0", &AttachmentKind::Request));
+        assert_eq!(Some(PresentationFormat::Indy), PresentationFormat::from_identifier("hlindy/proof@v2.0", &AttachmentKind::Presentation));
+
+        assert_eq!(None, PresentationFormat::from_identifier("hlindy/proof@v2.0", &AttachmentKind::Request));
+        assert_eq!(None, PresentationFormat::from_identifier("dif/presentation-exchange/definitions@v1.0", &AttachmentKind::Request));
+    }
+}
@@ -0,0 +1,104 @@
+use error::prelude::*;
+use messages::thread::Thread;
+use aries::messages::a2a::{A2AMessage, MessageId};
+use aries::messages::ack::PleaseAck;
+use aries::messages::attachment::{AttachmentFormat, AttachmentId, Attachments};
+use aries::messages::proof_presentation::presentation::Presentation;
+use aries::messages::proof_presentation::v20::{add_attachment, AttachmentKind, PresentationFormat, supported_attachment};
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+pub struct PresentationV2 {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    pub formats: Vec<AttachmentFormat>,
+    #[serde(rename = "presentations~attach")]
+    pub presentations_attach: Attachments,
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+    #[serde(rename = "~please_ack")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub please_ack: Option<PleaseAck>,
+}
+
+impl PresentationV2 {
+    pub fn create() -> Self {
+        PresentationV2::default()
+    }
+
+    pub fn set_comment(mut self, comment: String) -> Self {
+        self.comment = Some(comment);
+        self
+    }
+
+    pub fn set_presentations_attach(mut self, format: &PresentationFormat, presentations: String) -> VcxResult<PresentationV2> {
+        add_attachment(&mut self.formats, &mut self.presentations_attach, format, &AttachmentKind::Presentation, presentations)?;
+        Ok(self)
+    }
+
+    pub fn from_v1(presentation: &Presentation, format: &PresentationFormat) -> VcxResult<PresentationV2> {
+        let mut presentation_v2 = PresentationV2::create()
+            .set_presentations_attach(format, presentation.presentations_attach.content()?)?;
+        presentation_v2.id = presentation.id.clone();
+        presentation_v2.comment = presentation.comment.clone();
+        presentation_v2.thread = presentation.thread.clone();
+        presentation_v2.please_ack = presentation.please_ack.clone();
+        Ok(presentation_v2)
+    }
+}
+
+please_ack!(PresentationV2);
+threadlike!(PresentationV2);
+a2a_message!(PresentationV2);
+
+impl From<PresentationV2> for Presentation {
+    fn from(presentation: PresentationV2) -> Presentation {
+        let mut presentations_attach = Attachments::new();
+        if let Some((_, content)) = supported_attachment(&presentation.formats, &presentation.presentations_attach, &AttachmentKind::Presentation) {
+            presentations_attach.add_base64_encoded_json_attachment(AttachmentId::Presentation, ::serde_json::Value::String(content)).ok();
+        }
+
+        Presentation {
+            id: presentation.id,
+            comment: presentation.comment,
+            presentations_attach,
+            thread: presentation.thread,
+            please_ack: presentation.please_ack,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::proof_presentation::presentation::tests::_presentation;
+    use aries::messages::proof_presentation::presentation_request::tests::thread_id;
+
+    use super::*;
+
+    pub fn _presentation_v2() -> PresentationV2 {
+        PresentationV2::from_v1(&_presentation(), &PresentationFormat::Indy).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_presentation_v2_converts_from_and_into_v1() {
+        let presentation = _presentation_v2();
+        assert!(presentation.from_thread(&thread_id()));
+        assert_eq!(vec![AttachmentFormat { attach_id: "indy".to_string(), format: "hlindy/proof@v2.0".to_string() }], presentation.formats);
+
+        let presentation: Presentation = presentation.into();
+        assert_eq!(_presentation(), presentation);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_presentation_v2_serialization_works() {
+        let message = _presentation_v2().to_a2a_message();
+
+        let json = ::serde_json::to_value(&message).unwrap();
+        assert_eq!("did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/present-proof/2.0/presentation", json["@type"]);
+
+        assert_eq!(message, ::serde_json::from_value::<A2AMessage>(json).unwrap());
+    }
+}
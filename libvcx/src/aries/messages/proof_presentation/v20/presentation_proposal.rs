@@ -0,0 +1,170 @@
+use error::prelude::*;
+use messages::thread::Thread;
+use aries::messages::a2a::{A2AMessage, MessageId};
+use aries::messages::attachment::{AttachmentFormat, Attachments};
+use aries::messages::proof_presentation::presentation_proposal::{Attribute, PresentationPreview, PresentationProposal, Predicate};
+use aries::messages::proof_presentation::v20::{add_attachment, AttachmentKind, PresentationFormat, supported_attachment};
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+pub struct PresentationProposalV2 {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    pub formats: Vec<AttachmentFormat>,
+    #[serde(rename = "proposals~attach")]
+    pub proposals_attach: Attachments,
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+}
+
+impl PresentationProposalV2 {
+    pub fn create() -> Self {
+        PresentationProposalV2::default()
+    }
+
+    pub fn set_comment(mut self, comment: String) -> Self {
+        self.comment = Some(comment);
+        self
+    }
+
+    pub fn set_proposals_attach(mut self, format: &PresentationFormat, proposal: String) -> VcxResult<PresentationProposalV2> {
+        add_attachment(&mut self.formats, &mut self.proposals_attach, format, &AttachmentKind::Proposal, proposal)?;
+        Ok(self)
+    }
+
+    pub fn format(&self) -> Option<PresentationFormat> {
+        supported_attachment(&self.formats, &self.proposals_attach, &AttachmentKind::Proposal).map(|(format, _)| format)
+    }
+
+    pub fn from_v1(proposal: &PresentationProposal, format: &PresentationFormat) -> VcxResult<PresentationProposalV2> {
+        let proof_request = _proof_request_from_preview(&proposal.presentation_proposal);
+
+        let mut proposal_v2 = PresentationProposalV2::create()
+            .set_proposals_attach(format, proof_request.to_string())?;
+        proposal_v2.id = proposal.id.clone();
+        proposal_v2.comment = proposal.comment.clone();
+        proposal_v2.thread = proposal.thread.clone();
+        Ok(proposal_v2)
+    }
+}
+
+threadlike!(PresentationProposalV2);
+a2a_message!(PresentationProposalV2);
+
+impl From<PresentationProposalV2> for PresentationProposal {
+    fn from(proposal: PresentationProposalV2) -> PresentationProposal {
+        let proof_request: ::serde_json::Value = supported_attachment(&proposal.formats, &proposal.proposals_attach, &AttachmentKind::Proposal)
+            .and_then(|(_, content)| ::serde_json::from_str(&content).ok())
+            .unwrap_or_default();
+
+        PresentationProposal {
+            id: proposal.id,
+            comment: proposal.comment,
+            presentation_proposal: _preview_from_proof_request(&proof_request),
+            thread: proposal.thread,
+        }
+    }
+}
+
+/// Indy format proposes a presentation in form of a proof request.
+fn _proof_request_from_preview(preview: &PresentationPreview) -> ::serde_json::Value {
+    let requested_attributes: ::serde_json::Map<String, ::serde_json::Value> = preview.attributes.iter().enumerate()
+        .map(|(i, attribute)| (format!("attribute_{}", i), _add_restrictions(json!({"name": attribute.name}), &attribute.cred_def_id)))
+        .collect();
+
+    let requested_predicates: ::serde_json::Map<String, ::serde_json::Value> = preview.predicates.iter().enumerate()
+        .map(|(i, predicate)| {
+            let predicate_info = json!({"name": predicate.name, "p_type": predicate.predicate, "p_value": predicate.threshold});
+            (format!("predicate_{}", i), _add_restrictions(predicate_info, &predicate.cred_def_id))
+        })
+        .collect();
+
+    json!({
+        "name": "proposal",
+        "version": "1.0",
+        "requested_attributes": requested_attributes,
+        "requested_predicates": requested_predicates,
+    })
+}
+
+fn _add_restrictions(mut info: ::serde_json::Value, cred_def_id: &Option<String>) -> ::serde_json::Value {
+    if let Some(cred_def_id) = cred_def_id {
+        info["restrictions"] = json!([{"cred_def_id": cred_def_id}]);
+    }
+    info
+}
+
+fn _preview_from_proof_request(proof_request: &::serde_json::Value) -> PresentationPreview {
+    let cred_def_id = |info: &::serde_json::Value| info["restrictions"][0]["cred_def_id"].as_str().map(String::from);
+
+    let attributes = proof_request["requested_attributes"].as_object().map(|attributes| {
+        attributes.values()
+            .filter_map(|info| info["name"].as_str().map(|name| Attribute {
+                name: name.to_string(),
+                cred_def_id: cred_def_id(info),
+                mime_type: None,
+                value: None,
+                filter: None,
+            }))
+            .collect()
+    }).unwrap_or_default();
+
+    let predicates = proof_request["requested_predicates"].as_object().map(|predicates| {
+        predicates.values()
+            .filter_map(|info| match (info["name"].as_str(), info["p_type"].as_str(), info["p_value"].as_i64()) {
+                (Some(name), Some(p_type), Some(p_value)) => Some(Predicate {
+                    name: name.to_string(),
+                    cred_def_id: cred_def_id(info),
+                    predicate: p_type.to_string(),
+                    threshold: p_value,
+                    filter: None,
+                }),
+                _ => None
+            })
+            .collect()
+    }).unwrap_or_default();
+
+    PresentationPreview { attributes, predicates, ..PresentationPreview::default() }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::proof_presentation::presentation_proposal::tests::{_presentation_preview, _presentation_proposal};
+
+    use super::*;
+
+    pub fn _presentation_proposal_v2() -> PresentationProposalV2 {
+        PresentationProposalV2::from_v1(&_presentation_proposal(), &PresentationFormat::Indy).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_presentation_proposal_v2_converts_from_and_into_v1() {
+        let proposal = _presentation_proposal_v2();
+        assert_eq!(vec![AttachmentFormat { attach_id: "indy".to_string(), format: "hlindy/proof-req@v2.0".to_string() }], proposal.formats);
+
+        let proposal: PresentationProposal = proposal.into();
+        assert_eq!(_presentation_proposal(), proposal);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_proof_request_from_preview_keeps_predicates_and_restrictions() {
+        let mut preview = _presentation_preview();
+        preview.attributes[0].cred_def_id = Some(String::from("cred_def_id"));
+        preview.predicates.push(Predicate {
+            name: String::from("age"),
+            cred_def_id: None,
+            predicate: String::from(">="),
+            threshold: 18,
+            filter: None,
+        });
+
+        let proof_request = _proof_request_from_preview(&preview);
+        assert_eq!(json!({"name": "name", "restrictions": [{"cred_def_id": "cred_def_id"}]}), proof_request["requested_attributes"]["attribute_0"]);
+        assert_eq!(json!({"name": "age", "p_type": ">=", "p_value": 18}), proof_request["requested_predicates"]["predicate_0"]);
+
+        assert_eq!(preview, _preview_from_proof_request(&proof_request));
+    }
+}
@@ -0,0 +1,128 @@
+use error::prelude::*;
+use messages::thread::Thread;
+use aries::messages::a2a::{A2AMessage, MessageId};
+use aries::messages::attachment::{AttachmentFormat, AttachmentId, Attachments};
+use aries::messages::connection::service::Service;
+use aries::messages::timing::Timing;
+use aries::messages::proof_presentation::presentation_request::PresentationRequest;
+use aries::messages::proof_presentation::v20::{add_attachment, AttachmentKind, PresentationFormat, supported_attachment};
+
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq, Default)]
+pub struct PresentationRequestV2 {
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+    pub formats: Vec<AttachmentFormat>,
+    #[serde(rename = "request_presentations~attach")]
+    pub request_presentations_attach: Attachments,
+    #[serde(rename = "~service")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub service: Option<Service>,
+    #[serde(rename = "~thread")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread: Option<Thread>,
+    #[serde(rename = "~timing")]
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timing: Option<Timing>,
+}
+
+impl PresentationRequestV2 {
+    pub fn create() -> Self {
+        PresentationRequestV2::default()
+    }
+
+    pub fn set_id(mut self, id: String) -> Self {
+        self.id = MessageId(id);
+        self
+    }
+
+    pub fn set_comment(mut self, comment: String) -> Self {
+        self.comment = Some(comment);
+        self
+    }
+
+    pub fn set_request_presentations_attach(mut self, format: &PresentationFormat, request_presentations: String) -> VcxResult<PresentationRequestV2> {
+        add_attachment(&mut self.formats, &mut self.request_presentations_attach, format, &AttachmentKind::Request, request_presentations)?;
+        Ok(self)
+    }
+
+    pub fn set_service(mut self, service: Option<Service>) -> Self {
+        self.service = service;
+        self
+    }
+
+    /// Returns the first requested presentation format supported by VCX.
+    pub fn format(&self) -> Option<PresentationFormat> {
+        supported_attachment(&self.formats, &self.request_presentations_attach, &AttachmentKind::Request).map(|(format, _)| format)
+    }
+
+    pub fn from_v1(request: &PresentationRequest, format: &PresentationFormat) -> VcxResult<PresentationRequestV2> {
+        let mut request_v2 = PresentationRequestV2::create()
+            .set_id(request.id.0.clone())
+            .set_request_presentations_attach(format, request.request_presentations_attach.content()?)?
+            .set_service(request.service.clone());
+        request_v2.comment = request.comment.clone();
+        request_v2.thread = request.thread.clone();
+        request_v2.timing = request.timing.clone();
+        Ok(request_v2)
+    }
+}
+
+timing!(PresentationRequestV2);
+a2a_message!(PresentationRequestV2);
+
+impl From<PresentationRequestV2> for PresentationRequest {
+    fn from(request: PresentationRequestV2) -> PresentationRequest {
+        let mut request_presentations_attach = Attachments::new();
+        if let Some((_, content)) = supported_attachment(&request.formats, &request.request_presentations_attach, &AttachmentKind::Request) {
+            request_presentations_attach.add_base64_encoded_json_attachment(AttachmentId::PresentationRequest, ::serde_json::Value::String(content)).ok();
+        }
+
+        PresentationRequest {
+            id: request.id,
+            comment: request.comment,
+            request_presentations_attach,
+            service: request.service,
+            thread: request.thread,
+            timing: request.timing,
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::proof_presentation::presentation_request::tests::{_presentation_request, _presentation_request_with_service};
+
+    use super::*;
+
+    pub fn _presentation_request_v2() -> PresentationRequestV2 {
+        PresentationRequestV2::from_v1(&_presentation_request(), &PresentationFormat::Indy).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_presentation_request_v2_converts_from_and_into_v1() {
+        let request = _presentation_request_v2();
+        assert_eq!(vec![AttachmentFormat { attach_id: "indy".to_string(), format: "hlindy/proof-req@v2.0".to_string() }], request.formats);
+        assert_eq!(Some(PresentationFormat::Indy), request.format());
+
+        let request: PresentationRequest = request.into();
+        assert_eq!(_presentation_request(), request);
+
+        let request = PresentationRequestV2::from_v1(&_presentation_request_with_service(), &PresentationFormat::Indy).unwrap();
+        let request: PresentationRequest = request.into();
+        assert_eq!(_presentation_request_with_service(), request);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_presentation_request_v2_serialization_works() {
+        let message = _presentation_request_v2().to_a2a_message();
+
+        let json = ::serde_json::to_value(&message).unwrap();
+        assert_eq!("did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/present-proof/2.0/request-presentation", json["@type"]);
+
+        assert_eq!(message, ::serde_json::from_value::<A2AMessage>(json).unwrap());
+    }
+}
@@ -0,0 +1,196 @@
+use base64;
+use serde_json::{self, Value};
+use time;
+
+use error::prelude::*;
+use utils::libindy::crypto;
+use aries::messages::a2a::message_family::MessageFamilies;
+use aries::messages::a2a::message_type::MessageType;
+
+pub const SIGNATURE_SUFFIX: &str = "~sig";
+pub const ED25519_SHA512_SINGLE: &str = "ed25519Sha512_single";
+
+/// Field signature decorator (RFC 0234) replacing `<field>` of a message by `<field>~sig`.
+/// The signed value is serialized into `sig_data` after 8 bytes of big-endian timestamp of signing.
+#[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
+pub struct FieldSignature {
+    #[serde(rename = "@type")]
+    pub msg_type: MessageType,
+    pub signature: String,
+    pub sig_data: String,
+    pub signer: String,
+}
+
+impl FieldSignature {
+    pub fn sign(value: &Value, key: &str) -> VcxResult<FieldSignature> {
+        let now: u64 = time::get_time().sec as u64;
+
+        let mut sig_data = now.to_be_bytes().to_vec();
+        sig_data.extend(value.to_string().as_bytes());
+
+        let signature = crypto::sign(key, &sig_data)?;
+
+        Ok(FieldSignature {
+            signature: base64::encode_config(&signature, base64::URL_SAFE),
+            sig_data: base64::encode_config(&sig_data, base64::URL_SAFE),
+            signer: key.to_string(),
+            ..Default::default()
+        })
+    }
+
+    /// Verifies the signature with the key and returns the signed value.
+    pub fn verify(&self, key: &str) -> VcxResult<Value> {
+        let signature = base64::decode_config(&self.signature.as_bytes(), base64::URL_SAFE)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot decode signature: {:?}", err)))?;
+
+        let sig_data = base64::decode_config(&self.sig_data.as_bytes(), base64::URL_SAFE)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot decode signed data: {:?}", err)))?;
+
+        if sig_data.len() < 8 {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, "Signed data does not contain timestamp"));
+        }
+
+        if !crypto::verify(key, &sig_data, &signature)? {
+            return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Signature is invalid for key {}", key)));
+        }
+
+        serde_json::from_slice(&sig_data[8..])
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize signed data: {}", err)))
+    }
+}
+
+impl Default for FieldSignature {
+    fn default() -> FieldSignature {
+        FieldSignature {
+            msg_type: MessageType::build(MessageFamilies::Signature, ED25519_SHA512_SINGLE),
+            signature: String::new(),
+            sig_data: String::new(),
+            signer: String::new(),
+        }
+    }
+}
+
+/// Whether the message contains some `~sig` decorated field.
+pub fn has_signed_fields(message: &Value) -> bool {
+    message.as_object()
+        .map(|object| object.keys().any(|name| name.ends_with(SIGNATURE_SUFFIX)))
+        .unwrap_or(false)
+}
+
+/// Replaces the field of the message by its signature made with the key.
+pub fn sign_field(message: &mut Value, field: &str, key: &str) -> VcxResult<()> {
+    let object = message.as_object_mut()
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, "Message is not JSON object"))?;
+
+    let value = object.remove(field)
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidOption, format!("Message does not contain field {:?}", field)))?;
+
+    let signature = FieldSignature::sign(&value, key)?;
+    object.insert(format!("{}{}", field, SIGNATURE_SUFFIX), json!(signature));
+
+    Ok(())
+}
+
+/// Verifies all signed fields of the message with the key and replaces them by the signed values.
+pub fn verify_fields(message: &mut Value, key: &str) -> VcxResult<()> {
+    let object = message.as_object_mut()
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, "Message is not JSON object"))?;
+
+    let signed_fields: Vec<String> = object.keys()
+        .filter(|name| name.ends_with(SIGNATURE_SUFFIX))
+        .cloned()
+        .collect();
+
+    if signed_fields.is_empty() {
+        return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, "Message does not contain signed fields"));
+    }
+
+    for signed_field in signed_fields {
+        let signature = object.remove(&signed_field).unwrap_or(Value::Null);
+
+        let signature: FieldSignature = serde_json::from_value(signature)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize signature of {:?}: {}", signed_field, err)))?;
+
+        let value = signature.verify(key)?;
+        object.insert(signed_field.trim_end_matches(SIGNATURE_SUFFIX).to_string(), value);
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::a2a::A2AMessage;
+    use utils::devsetup::SetupAriesMocks;
+
+    use super::*;
+
+    fn _key() -> String {
+        String::from("CnEDk9HrMnmiHXEV1WFgbVCRteYnPqsJwrTdcZaNhFVW")
+    }
+
+    fn _message() -> Value {
+        json!({
+            "@type": "did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/basicmessage/1.0/message",
+            "@id": "testid",
+            "sent_time": "2020-01-01T00:00:00Z",
+            "content": "Your hovercraft is full of eels."
+        })
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_field_signature_sign_and_verify() {
+        let _setup = SetupAriesMocks::init();
+
+        let value = json!({"DID": "VsKV7grR1BUE29mG2Fm2kX"});
+
+        let signature = FieldSignature::sign(&value, &_key()).unwrap();
+        assert_eq!(_key(), signature.signer);
+        assert_eq!(MessageType::build(MessageFamilies::Signature, ED25519_SHA512_SINGLE), signature.msg_type);
+
+        assert_eq!(value, signature.verify(&_key()).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_sign_and_verify_message_field() {
+        let _setup = SetupAriesMocks::init();
+
+        let mut message = _message();
+
+        sign_field(&mut message, "content", &_key()).unwrap();
+        assert!(message.get("content").is_none());
+        assert!(message.get("content~sig").is_some());
+        assert!(has_signed_fields(&message));
+
+        verify_fields(&mut message, &_key()).unwrap();
+        assert_eq!(_message(), message);
+        assert!(!has_signed_fields(&message));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_sign_and_verify_errors() {
+        let _setup = SetupAriesMocks::init();
+
+        let mut message = _message();
+        assert_eq!(VcxErrorKind::InvalidOption, sign_field(&mut message, "unknown", &_key()).unwrap_err().kind());
+        assert_eq!(VcxErrorKind::InvalidJson, verify_fields(&mut message, &_key()).unwrap_err().kind());
+
+        let mut message = json!({"content~sig": {"signature": "", "sig_data": "", "signer": ""}});
+        assert_eq!(VcxErrorKind::InvalidJson, verify_fields(&mut message, &_key()).unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_message_with_signed_field_is_deserialized_as_generic() {
+        let _setup = SetupAriesMocks::init();
+
+        let mut message = _message();
+        sign_field(&mut message, "content", &_key()).unwrap();
+
+        let a2a_message: A2AMessage = serde_json::from_value(message.clone()).unwrap();
+        assert_eq!(A2AMessage::Generic(message), a2a_message);
+    }
+}
@@ -0,0 +1,98 @@
+use chrono::{DateTime, Duration, Utc};
+
+use settings;
+use aries::messages::a2a::message_family::MessageFamilies;
+
+/// `~timing` decorator (RFC 0032).
+/// Times are RFC 3339 strings, VCX sets `out_time` and `expires_time` on offers and requests it sends.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Default)]
+pub struct Timing {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub in_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub out_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stale_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_time: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay_milli: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub wait_until: Option<String>,
+}
+
+impl Timing {
+    pub fn create() -> Timing {
+        Timing::default()
+    }
+
+    pub fn set_out_time(mut self, out_time: String) -> Timing {
+        self.out_time = Some(out_time);
+        self
+    }
+
+    pub fn set_expires_time(mut self, expires_time: String) -> Timing {
+        self.expires_time = Some(expires_time);
+        self
+    }
+}
+
+/// Time which is `timeout` seconds from now.
+pub fn expires_time(timeout: u64) -> String {
+    (Utc::now() + Duration::seconds(timeout as i64)).to_rfc3339()
+}
+
+/// Expiry of an exchange of the protocol family starting now, if a timeout is configured for the family.
+pub fn protocol_expires_time(family: &MessageFamilies) -> Option<String> {
+    settings::get_protocol_timeout(&family.to_string()).map(expires_time)
+}
+
+/// Whether the time has passed. Times which cannot be parsed are considered to never expire.
+pub fn is_expired(expires_time: &str) -> bool {
+    match DateTime::parse_from_rfc3339(expires_time) {
+        Ok(expires_time) => expires_time.with_timezone(&Utc) <= Utc::now(),
+        Err(err) => {
+            warn!("Cannot parse expires_time {:?}: {}", expires_time, err);
+            false
+        }
+    }
+}
+
+#[macro_export]
+macro_rules! timing (($type:ident) => (
+    impl $type {
+        pub fn set_expires_time(mut self, expires_time: Option<String>) -> $type {
+            self.timing = expires_time.map(|expires_time|
+                Timing::create()
+                    .set_out_time(::chrono::Utc::now().to_rfc3339())
+                    .set_expires_time(expires_time)
+            );
+            self
+        }
+
+        pub fn expires_time(&self) -> Option<&str> {
+            self.timing.as_ref().and_then(|timing| timing.expires_time.as_ref().map(String::as_str))
+        }
+    }
+));
+
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_timing_is_expired() {
+        assert!(!is_expired(&expires_time(60)));
+        assert!(is_expired(&expires_time(0)));
+        assert!(is_expired("2020-01-01T00:00:00+00:00"));
+        assert!(!is_expired("invalid"));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_timing_serialization() {
+        let timing = Timing::create().set_expires_time(String::from("2020-01-01T00:00:00+00:00"));
+        assert_eq!(json!({"expires_time": "2020-01-01T00:00:00+00:00"}), json!(timing));
+    }
+}
@@ -0,0 +1,65 @@
+use aries::messages::a2a::MessageId;
+use aries::messages::a2a::message_family::MessageFamilies;
+use aries::messages::a2a::message_type::MessageType;
+use messages::thread::Thread;
+
+pub const TRACE_REPORT: &str = "trace_report";
+
+/// `~trace` decorator (RFC 0034) asking agents handling the message to report to the target.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Trace {
+    pub target: String,
+    #[serde(default)]
+    pub full_thread: bool,
+}
+
+impl Trace {
+    pub fn create(target: &str) -> Trace {
+        Trace {
+            target: target.to_string(),
+            full_thread: true,
+        }
+    }
+}
+
+/// Report of a single hop the traced message went through.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct TraceReport {
+    #[serde(rename = "@type")]
+    pub msg_type: MessageType,
+    #[serde(rename = "@id")]
+    pub id: MessageId,
+    #[serde(rename = "~thread")]
+    pub thread: Thread,
+    pub msg_id: String,
+    pub thread_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub traced_type: Option<String>,
+    pub handler: String,
+    pub hop: String,
+    pub ellapsed_milli: u64,
+    /// Unix time in milliseconds when the hop started.
+    pub timestamp: String,
+    /// RFC 3339 time when the hop started.
+    pub str_time: String,
+    pub outcome: String,
+}
+
+impl TraceReport {
+    pub fn create(msg_id: &str, thread_id: &str) -> TraceReport {
+        TraceReport {
+            msg_type: MessageType::build(MessageFamilies::Tracing, TRACE_REPORT),
+            id: MessageId::new(),
+            thread: Thread::new().set_thid(msg_id.to_string()),
+            msg_id: msg_id.to_string(),
+            thread_id: thread_id.to_string(),
+            traced_type: None,
+            handler: String::new(),
+            hop: String::new(),
+            ellapsed_milli: 0,
+            timestamp: String::new(),
+            str_time: String::new(),
+            outcome: String::new(),
+        }
+    }
+}
@@ -0,0 +1,32 @@
+/// `~transport` decorator (RFC 0092).
+/// Lets an agent without its own endpoint receive replies over the same connection it used to send the message.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct Transport {
+    pub return_route: ReturnRoute,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub enum ReturnRoute {
+    #[serde(rename = "none")]
+    None,
+    #[serde(rename = "all")]
+    All,
+    #[serde(rename = "thread")]
+    Thread,
+}
+
+#[macro_export]
+macro_rules! return_route (($type:ident) => (
+    impl $type {
+        pub fn request_return_route(mut self) -> $type {
+            self.transport = Some(Transport { return_route: ReturnRoute::All });
+            self
+        }
+
+        pub fn return_route_requested(&self) -> bool {
+            self.transport.as_ref()
+                .map(|transport| transport.return_route != ReturnRoute::None)
+                .unwrap_or(false)
+        }
+    }
+));
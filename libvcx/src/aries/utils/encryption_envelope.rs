@@ -1,23 +1,46 @@
+use chrono::Utc;
+use serde_json::Value;
+
 use aries::messages::a2a::A2AMessage;
 use aries::messages::connection::did_doc::DidDoc;
 use aries::messages::forward::Forward;
 use error::prelude::*;
+use tracing::{self, Hop};
 use utils::httpclient::AgencyMockDecrypted;
 use utils::libindy::crypto;
 
 #[derive(Debug)]
 pub struct EncryptionEnvelope(pub Vec<u8>);
 
+/// Result of unpacking a message which keeps the keys it was packed with.
+#[derive(Debug, Deserialize)]
+pub struct UnpackedMessage {
+    pub message: String,
+    #[serde(default)]
+    pub sender_verkey: Option<String>,
+    pub recipient_verkey: String,
+}
+
 impl EncryptionEnvelope {
     pub fn create(message: &A2AMessage,
                   pw_verkey: Option<&str>,
                   did_doc: &DidDoc) -> VcxResult<EncryptionEnvelope> {
-        trace!("EncryptionEnvelope::create >>> message: {:?}, pw_verkey: {:?}, did_doc: {:?}", message, pw_verkey, did_doc);
+        EncryptionEnvelope::create_traced(message, pw_verkey, did_doc, None)
+    }
+
+    /**
+    Packs the message reporting packing and wrapping into forward messages to the trace target, if any.
+     */
+    pub fn create_traced(message: &A2AMessage,
+                         pw_verkey: Option<&str>,
+                         did_doc: &DidDoc,
+                         trace_target: Option<&str>) -> VcxResult<EncryptionEnvelope> {
+        trace!("EncryptionEnvelope::create >>> message: {:?}, pw_verkey: {:?}, did_doc: {:?}, trace_target: {:?}", message, pw_verkey, did_doc, trace_target);
 
         if ::settings::indy_mocks_enabled() { return Ok(EncryptionEnvelope(vec![])); }
 
-        EncryptionEnvelope::encrypt_for_pairwise(message, pw_verkey, did_doc)
-            .and_then(|message| EncryptionEnvelope::wrap_into_forward_messages(message, did_doc))
+        tracing::traced(trace_target, message, Hop::Pack, || EncryptionEnvelope::encrypt_for_pairwise(message, pw_verkey, did_doc))
+            .and_then(|packed| tracing::traced(trace_target, message, Hop::Forward, || EncryptionEnvelope::wrap_into_forward_messages(packed, did_doc)))
             .map(|message| EncryptionEnvelope(message))
     }
 
@@ -61,8 +84,30 @@ impl EncryptionEnvelope {
         crypto::pack_message(None, &receiver_keys, message.as_bytes())
     }
 
+    /**
+    Unpacks the message addressed to one of our keys without looking inside it.
+    Used by the router which needs to know the keys to decide what to do with the message.
+     */
+    pub fn unpack(payload: &[u8]) -> VcxResult<UnpackedMessage> {
+        trace!("EncryptionEnvelope::unpack >>> payload: {:?}", payload);
+
+        let unpacked_msg = crypto::unpack_message(payload)?;
+
+        ::serde_json::from_slice(unpacked_msg.as_slice())
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize unpacked message: {}", err)))
+    }
+
     pub fn open(payload: Vec<u8>) -> VcxResult<A2AMessage> {
-        trace!("EncryptionEnvelope::open >>> payload: {:?}", payload);
+        EncryptionEnvelope::open_traced(payload, None)
+    }
+
+    /**
+    Unpacks the message reporting its receipt if it is traced, either by its connection or by `~trace` decorator.
+     */
+    pub fn open_traced(payload: Vec<u8>, trace_target: Option<&str>) -> VcxResult<A2AMessage> {
+        trace!("EncryptionEnvelope::open >>> payload: {:?}, trace_target: {:?}", payload, trace_target);
+
+        let started = Utc::now();
 
         let message = if AgencyMockDecrypted::has_decrypted_mock_messages() {
             trace!("EncryptionEnvelope::open >>> returning decrypted mock message");
@@ -81,9 +126,16 @@ impl EncryptionEnvelope {
         //     warn!("Raw decrypted message: {}", message);
         // }
 
-        Ok(::serde_json::from_str(&message)
-            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize A2A message: {}", err)))?
-        )
+        let message: Value = ::serde_json::from_str(&message)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize A2A message: {}", err)))?;
+
+        if let Some(target) = tracing::target(trace_target, &message) {
+            tracing::report(&target, &message, Hop::Receive, started, "OK");
+            tracing::received(&target, &message, started);
+        }
+
+        ::serde_json::from_value(message)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize A2A message: {}", err)))
     }
 }
 
@@ -3,14 +3,22 @@ use std::collections::HashMap;
 use serde_json;
 
 use aries::handlers::connection::agent_info::AgentInfo;
-use aries::handlers::connection::connection::{Connection, SmConnectionState};
+use aries::handlers::connection::connection::{Connection, HandshakeProtocol, SmConnectionState};
 use aries::messages::a2a::A2AMessage;
+use aries::messages::a2a::message_family::MessageFamilies;
 use aries::messages::connection::did_doc::DidDoc;
 use aries::messages::connection::invite::Invitation as InvitationV3;
+use aries::messages::out_of_band::invitation::OutOfBandInvitation;
+use aries::messages::signature;
+use dispatcher::ObjectType;
 use error::prelude::*;
+use events::{self, Observable};
+use history::History;
 use messages;
 use messages::get_message::Message;
 use messages::SerializableObjectWithState;
+use persistence;
+use serialization;
 use settings;
 use settings::ProtocolTypes;
 use utils::error;
@@ -20,6 +28,28 @@ lazy_static! {
     static ref CONNECTION_MAP: ObjectCache<Connection> = ObjectCache::<Connection>::new("connections-cache");
 }
 
+impl Observable for Connection {
+    fn source_id(&self) -> String {
+        Connection::source_id(self)
+    }
+
+    fn thread_id(&self) -> Option<String> {
+        None
+    }
+
+    fn history(&self) -> &History {
+        Connection::history(self)
+    }
+
+    fn serialize(&self) -> VcxResult<String> {
+        let (state, data, source_id, history) = self.to_owned().into();
+        let object = SerializableObjectWithState::V4 { data, state, source_id, history };
+
+        ::serde_json::to_string(&object)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidState, format!("Cannot serialize Connection: {:?}", err)))
+    }
+}
+
 pub fn create_agent_keys(source_id: &str, pw_did: &str, pw_verkey: &str) -> VcxResult<(String, String)> {
     debug!("creating pairwise keys on agent for connection {}", source_id);
 
@@ -77,6 +107,13 @@ pub fn get_pw_verkey(handle: u32) -> VcxResult<String> {
     })
 }
 
+pub fn get_their_did_doc(handle: u32) -> VcxResult<DidDoc> {
+    CONNECTION_MAP.get(handle, |connection| {
+        connection.their_did_doc()
+            .ok_or(VcxError::from_msg(VcxErrorKind::NotReady, "Remote Connection information is not set"))
+    })
+}
+
 pub fn get_state(handle: u32) -> u32 {
     trace!("get_state >>> handle = {:?}", handle);
     CONNECTION_MAP.get(handle, |connection| {
@@ -90,8 +127,14 @@ pub fn get_source_id(handle: u32) -> VcxResult<String> {
     })
 }
 
+pub fn get_history(handle: u32) -> VcxResult<History> {
+    CONNECTION_MAP.get(handle, |connection| {
+        Ok(connection.history().clone())
+    })
+}
+
 fn store_connection(connection: Connection) -> VcxResult<u32> {
-    CONNECTION_MAP.add(connection)
+    events::add(&CONNECTION_MAP, ObjectType::Connection, connection)
         .or(Err(VcxError::from(VcxErrorKind::CreateConnection)))
 }
 
@@ -101,9 +144,27 @@ pub fn create_connection(source_id: &str) -> VcxResult<u32> {
     return store_connection(connection);
 }
 
+pub fn create_connection_multi_use(source_id: &str) -> VcxResult<u32> {
+    trace!("create_connection_multi_use >>> source_id: {}", source_id);
+    let connection = Connection::create_multi_use(source_id);
+    return store_connection(connection);
+}
+
+pub fn create_connection_with_handshake_protocol(source_id: &str, protocol: &str) -> VcxResult<u32> {
+    trace!("create_connection_with_handshake_protocol >>> source_id: {}, protocol: {}", source_id, protocol);
+    let protocol: HandshakeProtocol = serde_json::from_value(json!(protocol))
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidOption, format!("Unsupported handshake protocol: {:?}", err)))?;
+    let connection = Connection::create_with_handshake_protocol(source_id, protocol);
+    return store_connection(connection);
+}
+
 pub fn create_connection_with_invite(source_id: &str, details: &str) -> VcxResult<u32> {
     debug!("create connection {} with invite {}", source_id, details);
-    if let Some(invitation) = serde_json::from_str::<InvitationV3>(details).ok() {
+    // Out-of-Band invitation is checked first as it would also match public DID form of connection invitation
+    if let Some(A2AMessage::OutOfBandInvitation(invitation)) = serde_json::from_str::<A2AMessage>(details).ok() {
+        let connection = Connection::create_with_out_of_band_invite(source_id, invitation)?;
+        store_connection(connection)
+    } else if let Some(invitation) = serde_json::from_str::<InvitationV3>(details).ok() {
         let connection = Connection::create_with_invite(source_id, invitation)?;
         store_connection(connection)
     } else {
@@ -111,28 +172,120 @@ pub fn create_connection_with_invite(source_id: &str, details: &str) -> VcxResul
     }
 }
 
+pub fn get_out_of_band_invite_details(handle: u32, requests: Option<&str>, goal_code: Option<String>, goal: Option<String>) -> VcxResult<String> {
+    let requests: Vec<A2AMessage> = match requests {
+        Some(requests) => serde_json::from_str(requests)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize attached requests: {:?}", err)))?,
+        None => vec![]
+    };
+
+    CONNECTION_MAP.get(handle, |connection| {
+        connection.get_out_of_band_invite_details(requests.clone(), goal_code.clone(), goal.clone())
+    })
+}
+
+pub fn get_out_of_band_requests(details: &str) -> VcxResult<String> {
+    let invitation: OutOfBandInvitation = match serde_json::from_str::<A2AMessage>(details) {
+        Ok(A2AMessage::OutOfBandInvitation(invitation)) => invitation,
+        _ => return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, "Used invite is not an Out-of-Band invitation"))
+    };
+
+    let requests = invitation.get_requests()?;
+
+    serde_json::to_string(&requests)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidState, format!("Cannot serialize attached requests: {:?}", err)))
+}
+
+pub fn get_new_connections(handle: u32) -> VcxResult<Vec<u32>> {
+    trace!("get_new_connections >>> handle: {}", handle);
+    let connections = CONNECTION_MAP.get(handle, |connection| {
+        connection.accept_multi_use_requests()
+    })?;
+
+    Ok(connections.into_iter()
+        .filter_map(|connection| match store_connection(connection) {
+            Ok(handle) => Some(handle),
+            Err(err) => {
+                warn!("get_new_connections >>> cannot store new connection: {}", err);
+                None
+            }
+        })
+        .collect())
+}
+
 pub fn send_generic_message(connection_handle: u32, msg: &str) -> VcxResult<String> {
     CONNECTION_MAP.get(connection_handle, |connection| {
         connection.send_generic_message(msg)
     })
 }
 
+pub fn send_generic_message_with_ack(connection_handle: u32, msg: &str) -> VcxResult<String> {
+    CONNECTION_MAP.get(connection_handle, |connection| {
+        connection.send_generic_message_with_ack(msg)
+    })
+}
+
+pub fn get_ack_requests(handle: u32) -> VcxResult<String> {
+    CONNECTION_MAP.get(handle, |connection| {
+        connection.get_ack_requests()
+    })
+}
+
+pub fn send_generic_message_with_options(connection_handle: u32, msg: &str, please_ack: bool, trace: bool) -> VcxResult<String> {
+    CONNECTION_MAP.get(connection_handle, |connection| {
+        connection.send_generic_message_with_options(msg, please_ack, trace)
+    })
+}
+
+/// Enables `~trace` reporting of the messages of the connection to the target, or disables it if the target is not set.
+pub fn set_trace_target(handle: u32, target: Option<String>) -> VcxResult<()> {
+    events::update(&CONNECTION_MAP, ObjectType::Connection, handle, |connection| {
+        connection.set_trace_target(target.clone())
+    })
+}
+
+/// Replaces the field of JSON message by `<field>~sig` decorator signed with pairwise verkey of the connection.
+pub fn sign_message_field(handle: u32, message: &str, field: &str) -> VcxResult<String> {
+    let mut message: serde_json::Value = serde_json::from_str(message)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize message: {:?}", err)))?;
+
+    signature::sign_field(&mut message, field, &get_pw_verkey(handle)?)?;
+
+    Ok(message.to_string())
+}
+
+/// Verifies `~sig` decorated fields of JSON message with verkey of the counterparty and replaces them by signed values.
+pub fn verify_message_fields(handle: u32, message: &str) -> VcxResult<String> {
+    let mut message: serde_json::Value = serde_json::from_str(message)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize message: {:?}", err)))?;
+
+    signature::verify_fields(&mut message, &get_their_pw_verkey(handle)?)?;
+
+    Ok(message.to_string())
+}
+
 pub fn update_state_with_message(handle: u32, message: A2AMessage) -> VcxResult<u32> {
-    CONNECTION_MAP.get_mut(handle, |connection| {
+    events::update(&CONNECTION_MAP, ObjectType::Connection, handle, |connection| {
         connection.update_state_with_message(&message)?;
         Ok(error::SUCCESS.code_num)
     })
 }
 
+pub fn update_state_with_messages(handle: u32, messages: HashMap<String, A2AMessage>) -> VcxResult<Vec<String>> {
+    events::update(&CONNECTION_MAP, ObjectType::Connection, handle, |connection| {
+        connection.update_state_with_messages(messages.clone())
+    })
+}
+
 pub fn update_state(handle: u32) -> VcxResult<u32> {
-    CONNECTION_MAP.get_mut(handle, |connection| {
+    events::update(&CONNECTION_MAP, ObjectType::Connection, handle, |connection| {
         connection.update_state()?;
         Ok(error::SUCCESS.code_num)
     })
 }
 
 pub fn delete_connection(handle: u32) -> VcxResult<u32> {
-    CONNECTION_MAP.get_mut(handle, |connection| {
+    events::update(&CONNECTION_MAP, ObjectType::Connection, handle, |connection| {
         connection.delete()?;
         Ok(error::SUCCESS.code_num)
     })
@@ -143,7 +296,7 @@ pub fn delete_connection(handle: u32) -> VcxResult<u32> {
 }
 
 pub fn connect(handle: u32) -> VcxResult<Option<String>> {
-    CONNECTION_MAP.get_mut(handle, |connection| {
+    events::update(&CONNECTION_MAP, ObjectType::Connection, handle, |connection| {
         connection.connect()?;
         Ok(connection.get_invite_details())
     })
@@ -151,21 +304,17 @@ pub fn connect(handle: u32) -> VcxResult<Option<String>> {
 
 pub fn to_string(handle: u32) -> VcxResult<String> {
     CONNECTION_MAP.get(handle, |connection| {
-        let (  state, data, source_id) = connection.to_owned().into();
-        let object = SerializableObjectWithState::V3 { data, state, source_id };
-
-        ::serde_json::to_string(&object)
-            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidState, format!("Cannot serialize Connection: {:?}", err)))
+        connection.serialize()
     })
 }
 
 pub fn from_string(connection_data: &str) -> VcxResult<u32> {
-    let object: SerializableObjectWithState<AgentInfo, SmConnectionState> = ::serde_json::from_str(connection_data)
+    let object: SerializableObjectWithState<AgentInfo, SmConnectionState> = ::serde_json::from_value(serialization::upgrade(ObjectType::Connection, connection_data)?)
         .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize Connection: {:?}", err)))?;
 
     let handle = match object {
-        SerializableObjectWithState::V3 { data, state, source_id } => {
-            CONNECTION_MAP.add((state, data, source_id).into())?
+        SerializableObjectWithState::V4 { data, state, source_id, history } => {
+            CONNECTION_MAP.add((state, data, source_id, history).into())?
         }
         _ => return Err(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Unexpected format of serialized connection: {:?}", object)))
     };
@@ -174,11 +323,33 @@ pub fn from_string(connection_data: &str) -> VcxResult<u32> {
 
 pub fn release(handle: u32) -> VcxResult<()> {
     CONNECTION_MAP.release(handle)
-        .or(Err(VcxError::from(VcxErrorKind::InvalidConnectionHandle)))
+        .or(Err(VcxError::from(VcxErrorKind::InvalidConnectionHandle)))?;
+
+    persistence::remove(ObjectType::Connection, handle)
 }
 
 pub fn release_all() {
     CONNECTION_MAP.drain().ok();
+    persistence::forget_all(ObjectType::Connection);
+}
+
+pub fn get_handles() -> VcxResult<Vec<u32>> {
+    CONNECTION_MAP.get_all(|_| ())
+        .map(|connections| connections.into_iter().map(|(handle, _)| handle).collect())
+}
+
+/**
+Finds the connection using the given pairwise verkey.
+ */
+pub fn find_by_pw_verkey(pw_vk: &str) -> VcxResult<Option<u32>> {
+    CONNECTION_MAP.get_all(|connection| connection.agent_info().pw_vk == pw_vk)
+        .map(|connections| connections.into_iter().find(|(_, found)| *found).map(|(handle, _)| handle))
+}
+
+pub fn uses_agency(handle: u32) -> VcxResult<bool> {
+    CONNECTION_MAP.get(handle, |connection| {
+        Ok(connection.agent_info().uses_agency())
+    }).or(Err(VcxError::from(VcxErrorKind::InvalidConnectionHandle)))
 }
 
 pub fn get_invite_details(handle: u32) -> VcxResult<String> {
@@ -188,45 +359,57 @@ pub fn get_invite_details(handle: u32) -> VcxResult<String> {
     }).or(Err(VcxError::from(VcxErrorKind::InvalidConnectionHandle)))
 }
 
-impl Into<(SmConnectionState, AgentInfo, String)> for Connection {
-    fn into(self) -> (SmConnectionState, AgentInfo, String) {
-        (self.state_object(), self.agent_info().to_owned(), self.source_id())
+impl Into<(SmConnectionState, AgentInfo, String, History)> for Connection {
+    fn into(self) -> (SmConnectionState, AgentInfo, String, History) {
+        (self.state_object(), self.agent_info().to_owned(), self.source_id(), self.history().to_owned())
     }
 }
 
-impl From<(SmConnectionState, AgentInfo, String)> for Connection {
-    fn from((state, agent_info, source_id): (SmConnectionState, AgentInfo, String)) -> Connection {
-        Connection::from_parts(source_id, agent_info, state)
+impl From<(SmConnectionState, AgentInfo, String, History)> for Connection {
+    fn from((state, agent_info, source_id, history): (SmConnectionState, AgentInfo, String, History)) -> Connection {
+        Connection::from_parts(source_id, agent_info, state, history)
     }
 }
 
 pub fn get_messages(handle: u32) -> VcxResult<HashMap<String, A2AMessage>> {
-    CONNECTION_MAP.get_mut(handle, |connection| {
+    events::update(&CONNECTION_MAP, ObjectType::Connection, handle, |connection| {
         connection.get_messages()
     })
 }
 
 pub fn update_message_status(handle: u32, uid: String) -> VcxResult<()> {
-    CONNECTION_MAP.get_mut(handle, |connection| {
+    events::update(&CONNECTION_MAP, ObjectType::Connection, handle, |connection| {
         connection.update_message_status(uid.clone())
     })
 }
 
+pub fn record_processed(handle: u32, message: &A2AMessage) -> VcxResult<()> {
+    CONNECTION_MAP.get(handle, |connection| {
+        connection.record_processed(message)
+    })
+}
+
+pub fn record_rejected(handle: u32, message: &A2AMessage) -> VcxResult<()> {
+    CONNECTION_MAP.get(handle, |connection| {
+        connection.record_rejected(message)
+    })
+}
+
 pub fn get_message_by_id(handle: u32, msg_id: String) -> VcxResult<A2AMessage> {
-    CONNECTION_MAP.get_mut(handle, |connection| {
+    events::update(&CONNECTION_MAP, ObjectType::Connection, handle, |connection| {
         connection.get_message_by_id(&msg_id)
     })
 }
 
 pub fn decode_message(handle: u32, message: Message) -> VcxResult<A2AMessage> {
-    CONNECTION_MAP.get_mut(handle, |connection| {
+    events::update(&CONNECTION_MAP, ObjectType::Connection, handle, |connection| {
         connection.decode_message(&message)
     })
 }
 
 pub fn send_message(handle: u32, message: A2AMessage) -> VcxResult<()> {
     trace!("connection::send_message >>>");
-    CONNECTION_MAP.get_mut(handle, |connection| {
+    events::update(&CONNECTION_MAP, ObjectType::Connection, handle, |connection| {
         connection.send_message(&message)
     })
 }
@@ -242,17 +425,23 @@ pub fn is_v3_connection(connection_handle: u32) -> VcxResult<bool> {
 }
 
 pub fn send_ping(connection_handle: u32, comment: Option<String>) -> VcxResult<()> {
-    CONNECTION_MAP.get_mut(connection_handle, |connection| {
+    events::update(&CONNECTION_MAP, ObjectType::Connection, connection_handle, |connection| {
         connection.send_ping(comment.clone())
     })
 }
 
 pub fn send_discovery_features(connection_handle: u32, query: Option<String>, comment: Option<String>) -> VcxResult<()> {
-    CONNECTION_MAP.get_mut(connection_handle, |connection| {
+    events::update(&CONNECTION_MAP, ObjectType::Connection, connection_handle, |connection| {
         connection.send_discovery_features(query.clone(), comment.clone())
     })
 }
 
+pub fn remote_supports_protocol(connection_handle: u32, family: &MessageFamilies) -> VcxResult<bool> {
+    CONNECTION_MAP.get(connection_handle, |connection| {
+        Ok(connection.remote_supports_protocol(family))
+    })
+}
+
 pub fn get_connection_info(handle: u32) -> VcxResult<String> {
     CONNECTION_MAP.get(handle, |connection| {
         connection.get_connection_info()
@@ -261,19 +450,28 @@ pub fn get_connection_info(handle: u32) -> VcxResult<String> {
 
 #[cfg(test)]
 pub mod tests {
+    use std::sync::{Arc, Mutex};
+    use std::sync::mpsc::channel;
     use std::thread;
     use std::time::Duration;
 
     use serde_json::Value;
 
     use api::VcxStateType;
+    use aries::messages::a2a::MessageId;
+    use aries::messages::ack::Ack;
+    use aries::messages::basic_message::message::BasicMessage;
+    use events::tests::ChannelSubscriber;
     use messages::get_message::download_messages;
     use messages::MessageStatusCode;
+    use replay;
     use utils::constants::*;
     use utils::constants;
     use utils::devsetup::*;
     use utils::httpclient::AgencyMockDecrypted;
     use utils::mockdata::mockdata_connection::{ARIES_CONNECTION_ACK, ARIES_CONNECTION_INVITATION, ARIES_CONNECTION_REQUEST, CONNECTION_SM_INVITEE_COMPLETED, CONNECTION_SM_INVITEE_INVITED, CONNECTION_SM_INVITEE_REQUESTED, CONNECTION_SM_INVITER_COMPLETED};
+    use utils::timeout::TimeoutUtils;
+    use utils::transport::memory::MemoryTransport;
 
     use super::*;
 
@@ -295,6 +493,20 @@ pub mod tests {
         handle
     }
 
+    pub fn build_test_connection_inviter_completed_with_wallet() -> u32 {
+        // messages go over memory transport and the connection is provisioned without agency agent
+        settings::set_config_value(settings::CONFIG_ENABLE_TEST_MODE, "agency");
+        settings::set_config_value(settings::CONFIG_INBOUND_ENDPOINT, "http://localhost:8080/inbound");
+
+        let handle = build_test_connection_inviter_requested();
+        let msg: A2AMessage = serde_json::from_str(ARIES_CONNECTION_ACK).unwrap();
+        update_state_with_message(handle, msg).unwrap();
+        assert_eq!(VcxStateType::VcxStateAccepted as u32, get_state(handle));
+
+        MemoryTransport::take_sent_messages();
+        handle
+    }
+
     pub fn create_connected_connections(consumer_handle: Option<u32>, institution_handle: Option<u32>) -> (u32, u32) {
         debug!("Institution is going to create connection.");
         ::utils::devsetup::set_institution(institution_handle);
@@ -359,6 +571,59 @@ pub mod tests {
         assert!(release(handle).is_err());
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_multi_use_connection_spawns_connection_per_request() {
+        let _setup = SetupAriesMocks::init();
+
+        let handle = create_connection_multi_use("test_multi_use_connection").unwrap();
+        connect(handle).unwrap();
+        assert_eq!(get_state(handle), VcxStateType::VcxStateOfferSent as u32);
+
+        AgencyMockDecrypted::set_next_decrypted_response(constants::GET_MESSAGES_DECRYPTED_RESPONSE);
+        AgencyMockDecrypted::set_next_decrypted_message(ARIES_CONNECTION_REQUEST);
+        update_state(handle).unwrap();
+        assert_eq!(get_state(handle), VcxStateType::VcxStateOfferSent as u32);
+
+        let (sender, receiver) = channel();
+        let subscription = events::subscribe(Arc::new(ChannelSubscriber(Mutex::new(sender))));
+
+        AgencyMockDecrypted::set_next_decrypted_response(constants::GET_MESSAGES_DECRYPTED_RESPONSE);
+        AgencyMockDecrypted::set_next_decrypted_message(ARIES_CONNECTION_REQUEST);
+        let connections = get_new_connections(handle).unwrap();
+        assert_eq!(1, connections.len());
+        assert_ne!(handle, connections[0]);
+        assert_eq!(get_state(connections[0]), VcxStateType::VcxStateRequestReceived as u32);
+        assert_eq!(get_source_id(connections[0]).unwrap(), "test_multi_use_connection");
+
+        let request: A2AMessage = serde_json::from_str(ARIES_CONNECTION_REQUEST).unwrap();
+        let history = get_history(connections[0]).unwrap();
+        let transition = history.transitions().last().unwrap();
+        assert_eq!(VcxStateType::VcxStateOfferSent as u32, transition.old_state);
+        assert_eq!(VcxStateType::VcxStateRequestReceived as u32, transition.new_state);
+        assert_eq!(replay::message_id(&request), transition.message_id);
+
+        // subscribers are shared by tests running in parallel
+        let event = ::std::iter::repeat_with(|| receiver.recv_timeout(TimeoutUtils::medium_timeout()).unwrap())
+            .find(|event| event.type_ == ObjectType::Connection && event.handle == connections[0])
+            .unwrap();
+        assert_eq!(VcxStateType::VcxStateRequestReceived as u32, event.new_state);
+        assert_eq!(to_string(connections[0]).unwrap(), event.object);
+        events::unsubscribe(subscription).unwrap();
+
+        assert_eq!(get_state(handle), VcxStateType::VcxStateOfferSent as u32);
+        assert!(get_invite_details(handle).is_ok());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_get_new_connections_fails_for_single_use_connection() {
+        let _setup = SetupAriesMocks::init();
+
+        let handle = build_test_connection_inviter_invited();
+        assert_eq!(get_new_connections(handle).unwrap_err().kind(), VcxErrorKind::ActionNotSupported);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_create_drop_create() {
@@ -539,6 +804,137 @@ pub mod tests {
         connect(handle_2).unwrap();
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_create_with_public_did_invite_details() {
+        let _setup = SetupAriesMocks::init();
+
+        let details = json!({
+            "@id": "28b39b79-f5db-4478-879a-15bb12632d00",
+            "@type": "https://didcomm.org/connections/1.0/invitation",
+            "label": "faber",
+            "did": "did:sov:V4SGRU86Z58d6TV7PBUe6f"
+        }).to_string();
+
+        let handle = create_connection_with_invite("alice", &details).unwrap();
+        assert_eq!(get_state(handle), VcxStateType::VcxStateOfferSent as u32);
+        connect(handle).unwrap();
+        assert_eq!(get_state(handle), VcxStateType::VcxStateRequestReceived as u32);
+
+        let details = json!({"@id": "28b39b79-f5db-4478-879a-15bb12632d00", "label": "faber"}).to_string();
+        assert_eq!(create_connection_with_invite("alice", &details).unwrap_err().kind(), VcxErrorKind::InvalidJson);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_create_with_out_of_band_invite_details() {
+        let _setup = SetupAriesMocks::init();
+
+        let inviter = build_test_connection_inviter_invited();
+        let offer = ::aries::messages::issuance::credential_offer::tests::_credential_offer().to_a2a_message();
+        let requests = json!([offer]).to_string();
+
+        let details = get_out_of_band_invite_details(inviter, Some(&requests), None, Some("Issue credential".to_string())).unwrap();
+        assert!(details.contains("out-of-band/1.0/invitation"));
+
+        let invitee = create_connection_with_invite("alice", &details).unwrap();
+        assert_eq!(get_state(invitee), VcxStateType::VcxStateOfferSent as u32);
+        connect(invitee).unwrap();
+        assert_eq!(get_state(invitee), VcxStateType::VcxStateRequestReceived as u32);
+
+        let attached: Vec<A2AMessage> = serde_json::from_str(&get_out_of_band_requests(&details).unwrap()).unwrap();
+        assert_eq!(vec![offer], attached);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_create_with_didcomm_org_typed_out_of_band_invite() {
+        let _setup = SetupAriesMocks::init();
+
+        let inviter = build_test_connection_inviter_invited();
+
+        let details = get_out_of_band_invite_details(inviter, None, None, None).unwrap();
+        let mut details: serde_json::Value = serde_json::from_str(&details).unwrap();
+        details["@type"] = json!("https://didcomm.org/out-of-band/1.0/invitation");
+        details["handshake_protocols"] = json!(["https://didcomm.org/connections/1.0"]);
+        let details = details.to_string();
+
+        let invitee = create_connection_with_invite("alice", &details).unwrap();
+        assert_eq!(get_state(invitee), VcxStateType::VcxStateOfferSent as u32);
+        connect(invitee).unwrap();
+        assert_eq!(get_state(invitee), VcxStateType::VcxStateRequestReceived as u32);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_create_with_connectionless_out_of_band_invite() {
+        let _setup = SetupAriesMocks::init();
+
+        let inviter = build_test_connection_inviter_invited();
+        let offer = ::aries::messages::issuance::credential_offer::tests::_credential_offer().to_a2a_message();
+        let requests = json!([offer]).to_string();
+
+        let details = get_out_of_band_invite_details(inviter, Some(&requests), None, None).unwrap();
+        let mut details: serde_json::Value = serde_json::from_str(&details).unwrap();
+        details.as_object_mut().unwrap().remove("handshake_protocols");
+        let details = details.to_string();
+
+        let invitee = create_connection_with_invite("alice", &details).unwrap();
+        assert_eq!(get_state(invitee), VcxStateType::VcxStateAccepted as u32);
+
+        let attached: Vec<A2AMessage> = serde_json::from_str(&get_out_of_band_requests(&details).unwrap()).unwrap();
+        assert_eq!(vec![offer], attached);
+
+        let serialized = to_string(invitee).unwrap();
+        assert!(serialized.contains("connectionless"));
+        let deserialized = from_string(&serialized).unwrap();
+        assert_eq!(serialized, to_string(deserialized).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_create_with_did_exchange_handshake_protocol() {
+        let _setup = SetupAriesMocks::init();
+
+        let responder = create_connection_with_handshake_protocol("faber", "didexchange/1.0").unwrap();
+        assert_eq!(get_state(responder), VcxStateType::VcxStateInitialized as u32);
+
+        let details = connect(responder).unwrap().unwrap();
+        assert!(details.contains("didexchange/1.0"));
+        assert_eq!(get_state(responder), VcxStateType::VcxStateOfferSent as u32);
+
+        let requester = create_connection_with_invite("alice", &details).unwrap();
+        assert_eq!(get_state(requester), VcxStateType::VcxStateOfferSent as u32);
+        connect(requester).unwrap();
+        assert_eq!(get_state(requester), VcxStateType::VcxStateRequestReceived as u32);
+
+        let serialized = to_string(requester).unwrap();
+        let deserialized = from_string(&serialized).unwrap();
+        assert_eq!(serialized, to_string(deserialized).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_create_with_handshake_protocol_fails_for_unknown_protocol() {
+        let _setup = SetupAriesMocks::init();
+
+        assert_eq!(create_connection_with_handshake_protocol("faber", "didexchange/2.0").unwrap_err().kind(), VcxErrorKind::InvalidOption);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_get_out_of_band_invite_details_fails_for_invalid_state() {
+        let _setup = SetupAriesMocks::init();
+
+        let inviter = build_test_connection_inviter_null();
+        assert_eq!(get_out_of_band_invite_details(inviter, None, None, None).unwrap_err().kind(), VcxErrorKind::NotReady);
+
+        let invitee = create_connection_with_invite("alice", ARIES_CONNECTION_INVITATION).unwrap();
+        assert_eq!(get_out_of_band_invite_details(invitee, None, None, None).unwrap_err().kind(), VcxErrorKind::ActionNotSupported);
+
+        assert_eq!(get_out_of_band_requests(ARIES_CONNECTION_INVITATION).unwrap_err().kind(), VcxErrorKind::InvalidJson);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_process_acceptance_message() {
@@ -571,6 +967,137 @@ pub mod tests {
         assert_eq!(err.kind(), VcxErrorKind::NotReady);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_update_state_with_message_asking_for_ack() {
+        let _setup = SetupAriesMocks::init();
+
+        let handle = from_string(CONNECTION_SM_INVITER_COMPLETED).unwrap();
+
+        let message = BasicMessage::create()
+            .set_content(String::from("Hello"))
+            .set_time()
+            .ask_for_ack()
+            .to_a2a_message();
+
+        update_state_with_message(handle, message).unwrap();
+        assert_eq!(VcxStateType::VcxStateAccepted as u32, get_state(handle));
+
+        assert_eq!(String::from("testid"), send_generic_message_with_ack(handle, "Hello").unwrap());
+        assert_eq!("[]", get_ack_requests(handle).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_only_basic_message_can_ask_for_ack() {
+        let _setup = SetupAriesMocks::init();
+
+        let handle = from_string(CONNECTION_SM_INVITER_COMPLETED).unwrap();
+
+        assert_eq!(VcxErrorKind::InvalidOption, send_generic_message_with_ack(handle, ARIES_CONNECTION_ACK).unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_ack_is_sent_once_after_message_is_processed() {
+        let _setup = SetupLibraryWallet::init();
+
+        let handle = build_test_connection_inviter_completed_with_wallet();
+
+        let message = BasicMessage::create()
+            .set_content(String::from("Hello"))
+            .set_time()
+            .ask_for_ack()
+            .to_a2a_message();
+
+        // basic message is left to the application, so it is not acknowledged until it is processed
+        assert!(update_state_with_messages(handle, map!("uid".to_string() => message.clone())).unwrap().is_empty());
+        assert!(MemoryTransport::take_sent_messages().is_empty());
+
+        let mut rejected = BasicMessage::create()
+            .set_content(String::from("Hello"))
+            .ask_for_ack();
+        rejected.id = MessageId(String::from("rejected"));
+        record_rejected(handle, &rejected.to_a2a_message()).unwrap();
+        assert!(MemoryTransport::take_sent_messages().is_empty());
+
+        record_processed(handle, &message).unwrap();
+        assert_eq!(1, MemoryTransport::take_sent_messages().len());
+
+        record_processed(handle, &message).unwrap();
+        update_state_with_message(handle, message).unwrap();
+        assert!(MemoryTransport::take_sent_messages().is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_ack_request_is_acknowledged_by_received_ack() {
+        let _setup = SetupLibraryWallet::init();
+
+        let handle = build_test_connection_inviter_completed_with_wallet();
+
+        let message_id = send_generic_message_with_ack(handle, "Hello").unwrap();
+        assert_eq!(1, MemoryTransport::take_sent_messages().len());
+
+        let requests: Value = serde_json::from_str(&get_ack_requests(handle).unwrap()).unwrap();
+        assert_eq!(1, requests.as_array().unwrap().len());
+        assert_eq!(message_id, requests[0]["id"].as_str().unwrap());
+        assert!(requests[0].get("status").is_none());
+
+        let ack = Ack::create().set_thread_id(&message_id).to_a2a_message();
+        assert_eq!(vec!["ack_uid".to_string()], update_state_with_messages(handle, map!("ack_uid".to_string() => ack)).unwrap());
+
+        let requests: Value = serde_json::from_str(&get_ack_requests(handle).unwrap()).unwrap();
+        assert_eq!("OK", requests[0]["status"].as_str().unwrap());
+        assert!(requests[0]["acknowledged"].is_string());
+
+        // ack is not acknowledged
+        assert!(MemoryTransport::take_sent_messages().is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_set_trace_target() {
+        let _setup = SetupAriesMocks::init();
+
+        let handle = from_string(CONNECTION_SM_INVITER_COMPLETED).unwrap();
+
+        assert_eq!(VcxErrorKind::InvalidOption, set_trace_target(handle, Some(String::from("console"))).unwrap_err().kind());
+
+        set_trace_target(handle, Some(String::from("log"))).unwrap();
+        assert_eq!(VcxStateType::VcxStateAccepted as u32, get_state(handle));
+
+        let serialized: Value = serde_json::from_str(&to_string(handle).unwrap()).unwrap();
+        assert_eq!("log", serialized["data"]["trace_target"].as_str().unwrap());
+
+        let handle = from_string(&serialized.to_string()).unwrap();
+        assert_eq!(String::new(), send_generic_message_with_options(handle, "Hello", false, true).unwrap());
+
+        set_trace_target(handle, None).unwrap();
+        let serialized: Value = serde_json::from_str(&to_string(handle).unwrap()).unwrap();
+        assert!(serialized["data"].get("trace_target").is_none());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_sign_and_verify_message_field() {
+        let _setup = SetupAriesMocks::init();
+
+        let handle = build_test_connection_inviter_requested();
+
+        let message = json!({"@type": "https://didcomm.org/basicmessage/1.0/message", "@id": "testid", "content": "Hello"}).to_string();
+
+        let signed: Value = serde_json::from_str(&sign_message_field(handle, &message, "content").unwrap()).unwrap();
+        assert!(signed.get("content").is_none());
+        assert_eq!(get_pw_verkey(handle).unwrap(), signed["content~sig"]["signer"].as_str().unwrap());
+
+        let verified: Value = serde_json::from_str(&verify_message_fields(handle, &signed.to_string()).unwrap()).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&message).unwrap(), verified);
+
+        assert_eq!(VcxErrorKind::InvalidJson, sign_message_field(handle, "not json", "content").unwrap_err().kind());
+        assert_eq!(VcxErrorKind::InvalidJson, verify_message_fields(handle, &message).unwrap_err().kind());
+    }
+
     #[cfg(feature = "agency_pool_tests")]
     #[test]
     fn test_send_and_download_messages() {
@@ -2,9 +2,16 @@ use serde_json;
 
 use aries::{
     handlers::issuance::holder::holder::Holder,
+    messages::a2a::A2AMessage,
     messages::issuance::credential_offer::CredentialOffer,
+    messages::issuance::credential_proposal::CredentialProposal,
+    messages::mime_type::MimeType,
 };
+use dispatcher::ObjectType;
 use error::prelude::*;
+use events::{self, Observable};
+use history::History;
+use serialization;
 use settings::indy_mocks_enabled;
 use utils::constants::GET_MESSAGES_DECRYPTED_RESPONSE;
 use utils::error;
@@ -20,10 +27,29 @@ lazy_static! {
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(tag = "version", content = "data")]
 enum Credentials {
-    #[serde(rename = "2.0")]
+    #[serde(rename = "4.0")]
     V3(Holder)
 }
 
+impl Observable for Holder {
+    fn source_id(&self) -> String {
+        self.get_source_id()
+    }
+
+    fn thread_id(&self) -> Option<String> {
+        Some(self.get_thread_id())
+    }
+
+    fn history(&self) -> &History {
+        self.get_history()
+    }
+
+    fn serialize(&self) -> VcxResult<String> {
+        serde_json::to_string(&Credentials::V3(self.clone()))
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidState, format!("cannot serialize Credential credentialect: {:?}", err)))
+    }
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, PartialEq)]
 pub struct Credential {}
 
@@ -46,6 +72,10 @@ fn create_credential(source_id: &str, offer: &str) -> VcxResult<Option<Holder>>
         offer => offer
     };
 
+    if let Ok(A2AMessage::CredentialOfferV2(cred_offer)) = serde_json::from_value::<A2AMessage>(offer_message.clone()) {
+        return Ok(Some(Holder::create_v2(cred_offer, source_id)?));
+    }
+
     if let Ok(cred_offer) = serde_json::from_value::<CredentialOffer>(offer_message) {
         return Ok(Some(Holder::create(cred_offer, source_id)?));
     }
@@ -57,12 +87,50 @@ fn create_credential(source_id: &str, offer: &str) -> VcxResult<Option<Holder>>
 pub fn credential_create_with_offer(source_id: &str, offer: &str) -> VcxResult<u32> {
     trace!("credential_create_with_offer >>> source_id: {}, offer: {}", source_id, secret!(&offer));
 
-    let cred_offer: CredentialOffer = serde_json::from_str(offer)
-        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson,
-                                          format!("Strict `aries` protocol is enabled. Can not parse `aries` formatted Credential Offer: {}", err)))?;
+    let holder = match serde_json::from_str::<A2AMessage>(offer) {
+        Ok(A2AMessage::CredentialOfferV2(cred_offer)) => Holder::create_v2(cred_offer, source_id)?,
+        _ => {
+            let cred_offer: CredentialOffer = serde_json::from_str(offer)
+                .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson,
+                                                  format!("Strict `aries` protocol is enabled. Can not parse `aries` formatted Credential Offer: {}", err)))?;
+
+            Holder::create(cred_offer, source_id)?
+        }
+    };
+    return events::add(&HANDLE_MAP, ObjectType::Credential, holder);
+}
+
+pub fn credential_create_with_proposal(source_id: &str, schema_id: &str, cred_def_id: &str, credential_data: &str, comment: Option<String>) -> VcxResult<u32> {
+    trace!("credential_create_with_proposal >>> source_id: {}, schema_id: {}, cred_def_id: {}, credential_data: {}", source_id, schema_id, cred_def_id, secret!(&credential_data));
 
-    let holder = Holder::create(cred_offer, source_id)?;
-    return HANDLE_MAP.add(holder);
+    let proposal = _build_credential_proposal(schema_id, cred_def_id, credential_data, comment)?;
+
+    let holder = Holder::create_with_proposal(proposal, source_id)?;
+    events::add(&HANDLE_MAP, ObjectType::Credential, holder)
+}
+
+fn _build_credential_proposal(schema_id: &str, cred_def_id: &str, credential_data: &str, comment: Option<String>) -> VcxResult<CredentialProposal> {
+    let credential_data: serde_json::Value = serde_json::from_str(credential_data)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize credential data: {:?}", err)))?;
+
+    let values = credential_data.as_object()
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Credential data is not an object: {:?}", credential_data)))?;
+
+    let mut proposal = CredentialProposal::create()
+        .set_schema_id(schema_id.to_string())
+        .set_cred_def_id(cred_def_id.to_string());
+
+    if let Some(comment) = comment {
+        proposal = proposal.set_comment(comment);
+    }
+
+    for (name, value) in values.iter() {
+        let value = value.as_str()
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Value of credential attribute {} is not a string: {:?}", name, value)))?;
+        proposal = proposal.add_credential_preview_data(name, value, MimeType::Plain)?;
+    }
+
+    Ok(proposal)
 }
 
 pub fn credential_create_with_msgid(source_id: &str, connection_handle: u32, msg_id: &str) -> VcxResult<(u32, String)> {
@@ -74,14 +142,14 @@ pub fn credential_create_with_msgid(source_id: &str, connection_handle: u32, msg
     let credential = create_credential(source_id, &offer)?
         .ok_or(VcxError::from_msg(VcxErrorKind::InvalidConnectionHandle, format!("Connection can not be used for Proprietary Issuance protocol")))?;
 
-    let handle = HANDLE_MAP.add(credential)?;
+    let handle = events::add(&HANDLE_MAP, ObjectType::Credential, credential)?;
 
     debug!("inserting credential {} into handle map", source_id);
     Ok((handle, offer))
 }
 
 pub fn update_state(handle: u32, message: Option<String>, connection_handle: Option<u32>) -> VcxResult<u32> {
-    HANDLE_MAP.get_mut(handle, |credential| {
+    events::update(&HANDLE_MAP, ObjectType::Credential, handle, |credential| {
         credential.update_state(message.clone(), connection_handle)?;
         Ok(error::SUCCESS.code_num)
     })
@@ -124,14 +192,14 @@ pub fn get_state(handle: u32) -> VcxResult<u32> {
 /// #Returns
 /// Credential request message serialized as String
 pub fn generate_credential_request_msg(handle: u32, _my_pw_did: &str, _their_pw_did: &str) -> VcxResult<String> {
-    HANDLE_MAP.get_mut(handle, |_credential| {
+    events::update(&HANDLE_MAP, ObjectType::Credential, handle, |_credential| {
         Err(VcxError::from_msg(VcxErrorKind::ActionNotSupported, "This action is not implemented yet")) // TODO: implement
     }).map_err(handle_err)
 }
 
 pub fn send_credential_request(handle: u32, connection_handle: u32) -> VcxResult<u32> {
     trace!("Credential::send_credential_request >>> credential_handle: {}, connection_handle: {}", handle, connection_handle);
-    HANDLE_MAP.get_mut(handle, |credential| {
+    events::update(&HANDLE_MAP, ObjectType::Credential, handle, |credential| {
         credential.send_request(connection_handle)?;
         let new_credential = credential.clone(); // TODO: Why are we doing this exactly?
         *credential = new_credential;
@@ -139,6 +207,14 @@ pub fn send_credential_request(handle: u32, connection_handle: u32) -> VcxResult
     }).map_err(handle_err)
 }
 
+pub fn send_credential_proposal(handle: u32, connection_handle: u32) -> VcxResult<u32> {
+    trace!("Credential::send_credential_proposal >>> credential_handle: {}, connection_handle: {}", handle, connection_handle);
+    events::update(&HANDLE_MAP, ObjectType::Credential, handle, |credential| {
+        credential.send_proposal(connection_handle)?;
+        Ok(error::SUCCESS.code_num)
+    }).map_err(handle_err)
+}
+
 fn get_credential_offer_msg(connection_handle: u32, msg_id: &str) -> VcxResult<String> {
     trace!("get_credential_offer_msg >>> connection_handle: {}, msg_id: {}", connection_handle, msg_id);
 
@@ -179,8 +255,7 @@ pub fn is_valid_handle(handle: u32) -> bool {
 
 pub fn to_string(handle: u32) -> VcxResult<String> {
     HANDLE_MAP.get(handle, |credential| {
-        serde_json::to_string(&Credentials::V3(credential.clone()))
-            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidState, format!("cannot serialize Credential credentialect: {:?}", err)))
+        credential.serialize()
     })
 }
 
@@ -190,8 +265,28 @@ pub fn get_source_id(handle: u32) -> VcxResult<String> {
     }).map_err(handle_err)
 }
 
+pub fn get_history(handle: u32) -> VcxResult<History> {
+    HANDLE_MAP.get(handle, |credential| {
+        Ok(credential.get_history().clone())
+    }).map_err(handle_err)
+}
+
+/**
+Returns thread ids of all holder credentials, they are used to route received messages.
+ */
+pub fn get_thread_ids() -> VcxResult<Vec<(u32, String)>> {
+    HANDLE_MAP.get_all(|credential| credential.get_thread_id())
+}
+
+/**
+Returns the connection the object exchanges messages over, if it is already known.
+ */
+pub fn get_connection_handle(handle: u32) -> VcxResult<Option<u32>> {
+    HANDLE_MAP.get(handle, |credential| Ok(credential.get_connection_handle()))
+}
+
 pub fn from_string(credential_data: &str) -> VcxResult<u32> {
-    let credential: Credentials = serde_json::from_str(credential_data)
+    let credential: Credentials = serde_json::from_value(serialization::upgrade(ObjectType::Credential, credential_data)?)
         .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize Credential: {:?}", err)))?;
 
     match credential {
@@ -215,6 +310,8 @@ pub fn get_credential_status(handle: u32) -> VcxResult<u32> {
 pub mod tests {
     use api::VcxStateType;
     use aries::messages::issuance::credential::Credential as Credential;
+    use aries::messages::issuance::v20::CredentialFormat;
+    use aries::messages::issuance::v20::credential_offer::CredentialOfferV2;
     use connection;
     use utils::devsetup::*;
     use utils::mockdata::mockdata_credex::{ARIES_CREDENTIAL_RESPONSE, CREDENTIAL_SM_FINISHED, CREDENTIAL_SM_OFFER_RECEIVED};
@@ -239,6 +336,18 @@ pub mod tests {
         assert!(handle > 0);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_credential_create_with_v2_offer() {
+        let _setup = SetupDefaults::init();
+
+        let offer: CredentialOffer = serde_json::from_str(ARIES_CREDENTIAL_OFFER).unwrap();
+        let offer = CredentialOfferV2::from_v1(&offer, &CredentialFormat::Indy).unwrap();
+
+        let handle = credential_create_with_offer("test_credential_create_with_v2_offer", &json!(offer.to_a2a_message()).to_string()).unwrap();
+        assert_eq!(VcxStateType::VcxStateRequestReceived as u32, get_state(handle).unwrap());
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_credential_create_with_bad_offer() {
@@ -248,6 +357,33 @@ pub mod tests {
         assert_eq!(err.kind(), VcxErrorKind::InvalidJson);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_credential_create_with_proposal() {
+        let _setup = SetupStrictAriesMocks::init();
+
+        let handle_conn = connection::tests::build_test_connection_inviter_requested();
+
+        let handle = credential_create_with_proposal("test_credential_create_with_proposal", "schema:id", "cred_def_id:id",
+                                                     &json!({"name": "Alice"}).to_string(), Some(String::from("comment"))).unwrap();
+        assert_eq!(VcxStateType::VcxStateInitialized as u32, get_state(handle).unwrap());
+
+        send_credential_proposal(handle, handle_conn).unwrap();
+        assert_eq!(VcxStateType::VcxStateProposalSent as u32, get_state(handle).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_credential_create_with_proposal_fails_for_invalid_credential_data() {
+        let _setup = SetupDefaults::init();
+
+        let err = credential_create_with_proposal("test_credential_create_with_proposal", "schema:id", "cred_def_id:id", "[\"Alice\"]", None).unwrap_err();
+        assert_eq!(err.kind(), VcxErrorKind::InvalidJson);
+
+        let err = credential_create_with_proposal("test_credential_create_with_proposal", "schema:id", "cred_def_id:id", &json!({"age": 20}).to_string(), None).unwrap_err();
+        assert_eq!(err.kind(), VcxErrorKind::InvalidJson);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_credential_serialize_deserialize() {
@@ -381,6 +381,15 @@ pub fn get_cred_def_id(handle: u32) -> VcxResult<String> {
     })
 }
 
+/// Finds loaded credential definition object with the given id.
+pub fn find_handle(cred_def_id: &str) -> VcxResult<Option<u32>> {
+    let cred_def_ids = CREDENTIALDEF_MAP.get_all(|c| c.get_cred_def_id().clone())?;
+
+    Ok(cred_def_ids.into_iter()
+        .find(|(_, id)| id == cred_def_id)
+        .map(|(handle, _)| handle))
+}
+
 pub fn get_rev_reg_id(handle: u32) -> VcxResult<Option<String>> {
     CREDENTIALDEF_MAP.get(handle, |c| {
         Ok(c.get_rev_reg_id().cloned())
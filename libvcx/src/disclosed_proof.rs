@@ -4,15 +4,21 @@ use serde_json;
 
 use aries::{
     handlers::proof_presentation::prover::prover::Prover,
+    messages::a2a::A2AMessage,
+    messages::proof_presentation::presentation_proposal::PresentationPreview,
     messages::proof_presentation::presentation_request::PresentationRequest,
 };
 use connection;
+use dispatcher::ObjectType;
 use error::prelude::*;
+use events::{self, Observable};
+use history::History;
 use messages::{
     get_message::Message,
     payload::Payloads,
 };
 use messages::proofs::proof_request::ProofRequestMessage;
+use serialization;
 use settings;
 use settings::indy_mocks_enabled;
 use utils::constants::GET_MESSAGES_DECRYPTED_RESPONSE;
@@ -28,10 +34,29 @@ lazy_static! {
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(tag = "version", content = "data")]
 enum DisclosedProofs {
-    #[serde(rename = "2.0")]
+    #[serde(rename = "4.0")]
     V3(Prover),
 }
 
+impl Observable for Prover {
+    fn source_id(&self) -> String {
+        self.get_source_id()
+    }
+
+    fn thread_id(&self) -> Option<String> {
+        Some(self.get_thread_id())
+    }
+
+    fn history(&self) -> &History {
+        self.get_history()
+    }
+
+    fn serialize(&self) -> VcxResult<String> {
+        serde_json::to_string(&DisclosedProofs::V3(self.clone()))
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidState, format!("cannot serialize DisclosedProof proofect: {:?}", err)))
+    }
+}
+
 fn handle_err(err: VcxError) -> VcxError {
     if err.kind() == VcxErrorKind::InvalidHandle {
         VcxError::from(VcxErrorKind::InvalidDisclosedProofHandle)
@@ -44,12 +69,30 @@ pub fn create_proof(source_id: &str, proof_req: &str) -> VcxResult<u32> {
     trace!("create_proof >>> source_id: {}, proof_req: {}", source_id, proof_req);
     debug!("creating disclosed proof with id: {}", source_id);
 
+    let proof = _create_prover(source_id, proof_req)?;
+    events::add(&HANDLE_MAP, ObjectType::DisclosedProof, proof)
+}
+
+fn _create_prover(source_id: &str, proof_req: &str) -> VcxResult<Prover> {
+    if let Ok(A2AMessage::PresentationRequestV2(presentation_request)) = serde_json::from_str::<A2AMessage>(proof_req) {
+        return Prover::create_v2(source_id, presentation_request);
+    }
+
     let presentation_request: PresentationRequest = serde_json::from_str(proof_req)
         .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson,
                                           format!("Strict `aries` protocol is enabled. Can not parse `aries` formatted Presentation Request: {}", err)))?;
 
-    let proof = Prover::create(source_id, presentation_request)?;
-    HANDLE_MAP.add(proof)
+    Prover::create(source_id, presentation_request)
+}
+
+pub fn create_proof_with_proposal(source_id: &str, presentation_preview: &str, comment: Option<String>) -> VcxResult<u32> {
+    trace!("create_proof_with_proposal >>> source_id: {}, presentation_preview: {}", source_id, presentation_preview);
+
+    let presentation_preview: PresentationPreview = serde_json::from_str(presentation_preview)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize Presentation Preview: {:?}", err)))?;
+
+    let proof = Prover::create_with_proposal(source_id, presentation_preview, comment)?;
+    events::add(&HANDLE_MAP, ObjectType::DisclosedProof, proof)
 }
 
 pub fn create_proof_with_msgid(source_id: &str, connection_handle: u32, msg_id: &str) -> VcxResult<(u32, String)> {
@@ -59,13 +102,9 @@ pub fn create_proof_with_msgid(source_id: &str, connection_handle: u32, msg_id:
 
     let proof_request = get_proof_request(connection_handle, &msg_id)?;
 
-    let presentation_request: PresentationRequest = serde_json::from_str(&proof_request)
-        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson,
-                                          format!("Strict `aries` protocol is enabled. Can not parse `aries` formatted Presentation Request: {}", err)))?;
-
-    let proof = Prover::create(source_id, presentation_request)?;
+    let proof = _create_prover(source_id, &proof_request)?;
 
-    let handle = HANDLE_MAP.add(proof)?;
+    let handle = events::add(&HANDLE_MAP, ObjectType::DisclosedProof, proof)?;
 
     debug!("inserting disclosed proof {} into handle map", source_id);
     Ok((handle, proof_request))
@@ -78,7 +117,7 @@ pub fn get_state(handle: u32) -> VcxResult<u32> {
 }
 
 pub fn update_state(handle: u32, message: Option<String>, connection_handle: Option<u32>) -> VcxResult<u32> {
-    HANDLE_MAP.get_mut(handle, |proof| {
+    events::update(&HANDLE_MAP, ObjectType::DisclosedProof, handle, |proof| {
         proof.update_state(message.as_ref().map(String::as_str), connection_handle)?;
         Ok(proof.state())
     })
@@ -86,13 +125,12 @@ pub fn update_state(handle: u32, message: Option<String>, connection_handle: Opt
 
 pub fn to_string(handle: u32) -> VcxResult<String> {
     HANDLE_MAP.get(handle, |proof| {
-        serde_json::to_string(&DisclosedProofs::V3(proof.clone()))
-            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidState, format!("cannot serialize DisclosedProof proofect: {:?}", err)))
+        proof.serialize()
     })
 }
 
 pub fn from_string(proof_data: &str) -> VcxResult<u32> {
-    let proof: DisclosedProofs = serde_json::from_str(proof_data)
+    let proof: DisclosedProofs = serde_json::from_value(serialization::upgrade(ObjectType::DisclosedProof, proof_data)?)
         .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("cannot deserialize DisclosedProofs object: {:?}", err)))?;
 
     match proof {
@@ -108,6 +146,20 @@ pub fn release_all() {
     HANDLE_MAP.drain().ok();
 }
 
+/**
+Returns thread ids of all disclosed proofs, they are used to route received messages.
+ */
+pub fn get_thread_ids() -> VcxResult<Vec<(u32, String)>> {
+    HANDLE_MAP.get_all(|proof| proof.get_thread_id())
+}
+
+/**
+Returns the connection the object exchanges messages over, if it is already known.
+ */
+pub fn get_connection_handle(handle: u32) -> VcxResult<Option<u32>> {
+    HANDLE_MAP.get(handle, |proof| Ok(proof.get_connection_handle()))
+}
+
 pub fn generate_proof_msg(handle: u32) -> VcxResult<String> {
     HANDLE_MAP.get(handle, |proof| {
         proof.generate_presentation_msg()
@@ -115,7 +167,7 @@ pub fn generate_proof_msg(handle: u32) -> VcxResult<String> {
 }
 
 pub fn send_proof(handle: u32, connection_handle: u32) -> VcxResult<u32> {
-    HANDLE_MAP.get_mut(handle, |proof| {
+    events::update(&HANDLE_MAP, ObjectType::DisclosedProof, handle, |proof| {
         proof.send_presentation(connection_handle)?;
         let new_proof = proof.clone();
         *proof = new_proof;
@@ -123,15 +175,22 @@ pub fn send_proof(handle: u32, connection_handle: u32) -> VcxResult<u32> {
     })
 }
 
+pub fn send_proposal(handle: u32, connection_handle: u32) -> VcxResult<u32> {
+    events::update(&HANDLE_MAP, ObjectType::DisclosedProof, handle, |proof| {
+        proof.send_proposal(connection_handle)?;
+        Ok(error::SUCCESS.code_num)
+    }).map_err(handle_err)
+}
+
 pub fn generate_reject_proof_msg(handle: u32) -> VcxResult<String> {
-    HANDLE_MAP.get_mut(handle, |_| {
+    events::update(&HANDLE_MAP, ObjectType::DisclosedProof, handle, |_| {
         Err(VcxError::from_msg(VcxErrorKind::ActionNotSupported,
                                "Action generate_reject_proof_msg is not implemented for V3 disclosed proof."))
     })
 }
 
 pub fn reject_proof(handle: u32, connection_handle: u32) -> VcxResult<u32> {
-    HANDLE_MAP.get_mut(handle, |proof| {
+    events::update(&HANDLE_MAP, ObjectType::DisclosedProof, handle, |proof| {
         proof.decline_presentation_request(connection_handle, Some(String::from("Presentation Request was rejected")), None)?;
         let new_proof = proof.clone();
         *proof = new_proof;
@@ -140,14 +199,14 @@ pub fn reject_proof(handle: u32, connection_handle: u32) -> VcxResult<u32> {
 }
 
 pub fn generate_proof(handle: u32, credentials: String, self_attested_attrs: String) -> VcxResult<u32> {
-    HANDLE_MAP.get_mut(handle, |proof| {
+    events::update(&HANDLE_MAP, ObjectType::DisclosedProof, handle, |proof| {
         proof.generate_presentation(credentials.clone(), self_attested_attrs.clone())?;
         Ok(error::SUCCESS.code_num)
     }).map(|_| error::SUCCESS.code_num)
 }
 
 pub fn decline_presentation_request(handle: u32, connection_handle: u32, reason: Option<String>, proposal: Option<String>) -> VcxResult<u32> {
-    HANDLE_MAP.get_mut(handle, |proof| {
+    events::update(&HANDLE_MAP, ObjectType::DisclosedProof, handle, |proof| {
         proof.decline_presentation_request(connection_handle, reason.clone(), proposal.clone())?;
         let new_proof = proof.clone();
         *proof = new_proof;
@@ -156,13 +215,13 @@ pub fn decline_presentation_request(handle: u32, connection_handle: u32, reason:
 }
 
 pub fn retrieve_credentials(handle: u32) -> VcxResult<String> {
-    HANDLE_MAP.get_mut(handle, |proof| {
+    events::update(&HANDLE_MAP, ObjectType::DisclosedProof, handle, |proof| {
         proof.retrieve_credentials()
     })
 }
 
 pub fn get_proof_request_data(handle: u32) -> VcxResult<String> {
-    HANDLE_MAP.get_mut(handle, |proof| {
+    events::update(&HANDLE_MAP, ObjectType::DisclosedProof, handle, |proof| {
         proof.presentation_request_data()
     })
 }
@@ -181,8 +240,12 @@ fn get_proof_request(connection_handle: u32, msg_id: &str) -> VcxResult<String>
         AgencyMockDecrypted::set_next_decrypted_message(ARIES_PROOF_REQUEST_PRESENTATION);
     }
 
-    let presentation_request = Prover::get_presentation_request(connection_handle, msg_id)?;
-    serde_json::to_string_pretty(&presentation_request)
+    let presentation_request = match Prover::get_presentation_request(connection_handle, msg_id)? {
+        A2AMessage::PresentationRequest(presentation_request) => serde_json::to_string_pretty(&presentation_request),
+        message => serde_json::to_string_pretty(&message)
+    };
+
+    presentation_request
         .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot serialize message: {}", err)))
 }
 
@@ -218,6 +281,12 @@ pub fn get_source_id(handle: u32) -> VcxResult<String> {
     }).map_err(handle_err)
 }
 
+pub fn get_history(handle: u32) -> VcxResult<History> {
+    HANDLE_MAP.get(handle, |proof| {
+        Ok(proof.get_history().clone())
+    }).map_err(handle_err)
+}
+
 pub fn get_presentation_status(handle: u32) -> VcxResult<u32> {
     HANDLE_MAP.get(handle, |proof| {
         Ok(proof.presentation_status())
@@ -245,6 +314,8 @@ mod tests {
     use utils::mockdata::mock_settings::MockBuilder;
     use utils::mockdata::mockdata_proof;
     use utils::mockdata::mockdata_proof::{ARIES_PROOF_PRESENTATION_ACK, ARIES_PROOF_REQUEST_PRESENTATION};
+    use aries::messages::proof_presentation::presentation_proposal::tests::_presentation_preview;
+    use aries::messages::proof_presentation::presentation_request::tests::_presentation_request;
 
     use super::*;
 
@@ -264,6 +335,23 @@ mod tests {
         assert!(create_proof("1", ARIES_PROOF_REQUEST_PRESENTATION).unwrap() > 0);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_create_proof_with_v2_request() {
+        use aries::messages::proof_presentation::v20::PresentationFormat;
+        use aries::messages::proof_presentation::v20::presentation_request::PresentationRequestV2;
+
+        let _setup = SetupAriesMocks::init();
+        settings::set_config_value(settings::CONFIG_PROTOCOL_TYPE, "4.0");
+
+        let request: PresentationRequest = serde_json::from_str(ARIES_PROOF_REQUEST_PRESENTATION).unwrap();
+        let request = PresentationRequestV2::from_v1(&request, &PresentationFormat::Indy).unwrap();
+
+        let handle = create_proof("1", &json!(request.to_a2a_message()).to_string()).unwrap();
+        assert_eq!(VcxStateType::VcxStateRequestReceived as u32, get_state(handle).unwrap());
+        assert_eq!(request.request_presentations_attach.content_by_id("indy").unwrap(), get_proof_request_data(handle).unwrap());
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_create_fails() {
@@ -300,6 +388,36 @@ mod tests {
         assert_eq!(VcxStateType::VcxStateAccepted as u32, get_state(handle_proof).unwrap());
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_proof_cycle_started_with_proposal() {
+        let _setup = SetupStrictAriesMocks::init();
+
+        let connection_h = connection::tests::build_test_connection_inviter_requested();
+
+        let handle = create_proof_with_proposal("TEST_PROPOSAL", &json!(_presentation_preview()).to_string(), Some(String::from("comment"))).unwrap();
+        assert_eq!(VcxStateType::VcxStateInitialized as u32, get_state(handle).unwrap());
+
+        send_proposal(handle, connection_h).unwrap();
+        assert_eq!(VcxStateType::VcxStateProposalSent as u32, get_state(handle).unwrap());
+
+        let serialized: Value = serde_json::from_str(&to_string(handle).unwrap()).unwrap();
+        let thread_id = serialized["data"]["prover_sm"]["thread_id"].as_str().unwrap().to_string();
+
+        let request = _presentation_request().set_thread_id(&thread_id).to_a2a_message();
+        update_state(handle, Some(json!(request).to_string()), Some(connection_h)).unwrap();
+        assert_eq!(VcxStateType::VcxStateRequestReceived as u32, get_state(handle).unwrap());
+        assert_eq!(_presentation_request().request_presentations_attach.content().unwrap(), get_proof_request_data(handle).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_create_proof_with_proposal_fails_for_invalid_preview() {
+        let _setup = SetupDefaults::init();
+
+        assert_eq!(create_proof_with_proposal("TEST_PROPOSAL", "{}", None).unwrap_err().kind(), VcxErrorKind::InvalidJson);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_proof_update_state_v2() {
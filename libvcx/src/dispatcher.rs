@@ -0,0 +1,560 @@
+use std::collections::HashMap;
+
+use serde_json;
+
+use api::VcxStateType;
+use aries::messages::a2a::A2AMessage;
+use aries::messages::error::ProblemReport;
+use aries::messages::issuance::credential_proposal::CredentialProposal;
+use connection;
+use credential;
+use credential_def;
+use disclosed_proof;
+use error::prelude::*;
+use issuer_credential;
+use messages::get_message::{download_messages, Message};
+use messages::MessageStatusCode;
+use messages::payload::PayloadV1;
+use messages::update_message::{UIDsByConn, update_messages};
+use outbox;
+use proof;
+use replay;
+
+/*
+    Dispatcher updates all the objects at once instead of polling the agency object by object.
+
+    Messages of all completed connections are downloaded with a single request, every message is routed
+    to the credential or proof object owning its thread (`~thread.thid`, or `@id` of the message starting a thread).
+    Credential offers and presentation requests starting a new thread create new holder and prover objects.
+    Presentation proposals starting a new thread create verifier objects.
+    Credential proposals starting a new thread create issuer objects when the proposed credential definition is loaded,
+    otherwise they are left on the connection for the application.
+    Remaining messages are passed to their connections. Handled messages are marked as reviewed in a single request.
+
+    Messages are routed only to objects exchanging messages over the same connection, objects not bound
+    to a connection yet are updated by the application. Messages which fail to be handled are rejected
+    with a problem report and marked as reviewed.
+
+    Messages already processed on their connection (see `replay`) are marked as reviewed without being handled again.
+
+    Connections still in the handshake are updated one by one, as their messages may arrive to bootstrap agents.
+    Messages picked up from the mediator are dispatched the same way once they are put into the inbox (see `mediation`).
+    With the outbox enabled, queued outbound messages which are due are retried before the update.
+*/
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ObjectType {
+    Connection,
+    Credential,
+    DisclosedProof,
+    IssuerCredential,
+    Proof,
+}
+
+/// Object whose state was updated by the dispatcher.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct UpdatedObject {
+    #[serde(rename = "type")]
+    pub type_: ObjectType,
+    pub handle: u32,
+    pub connection_handle: u32,
+    #[serde(skip_serializing_if = "::std::ops::Not::not")]
+    pub created: bool,
+}
+
+impl UpdatedObject {
+    fn new(type_: ObjectType, handle: u32, connection_handle: u32) -> UpdatedObject {
+        UpdatedObject { type_, handle, connection_handle, created: false }
+    }
+
+    fn created(type_: ObjectType, handle: u32, connection_handle: u32) -> UpdatedObject {
+        UpdatedObject { type_, handle, connection_handle, created: true }
+    }
+}
+
+/// Objects by their thread ids, with the connection they exchange messages over if it is known.
+type ThreadIndex = HashMap<String, (ObjectType, u32, Option<u32>)>;
+
+/**
+Updates state of all connections, credentials and proofs with the pending messages.
+Returns JSON array of updated objects: [{"type": "credential", "handle": 1, "connection_handle": 2, "created": true}]
+ */
+pub fn update_all() -> VcxResult<String> {
+    trace!("update_all >>>");
+
+    let updated = update_all_objects()?;
+
+    serde_json::to_string(&updated)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize updated objects: {:?}", err)))
+}
+
+fn update_all_objects() -> VcxResult<Vec<UpdatedObject>> {
+    if outbox::is_enabled() {
+        if let Err(err) = outbox::flush() {
+            warn!("update_all >>> cannot flush outbox: {}", err);
+        }
+    }
+
+    if let Err(err) = replay::prune_processed() {
+        warn!("update_all >>> cannot prune processed messages: {}", err);
+    }
+
+    update_connections(&connection::get_handles()?)
+}
+
+/**
+Updates the connections and the objects their pending messages belong to.
+ */
+pub fn update_connections(connection_handles: &[u32]) -> VcxResult<Vec<UpdatedObject>> {
+    trace!("update_connections >>> connection_handles: {:?}", connection_handles);
+
+    let mut updated = Vec::new();
+    let mut completed = Vec::new();
+
+    for &handle in connection_handles {
+        let state = connection::get_state(handle);
+
+        if state == VcxStateType::VcxStateAccepted as u32 {
+            completed.push(handle);
+            continue;
+        }
+
+        match connection::update_state(handle) {
+            Ok(_) if connection::get_state(handle) != state => updated.push(UpdatedObject::new(ObjectType::Connection, handle, handle)),
+            Ok(_) => {}
+            Err(err) => warn!("update_all >>> cannot update connection {}: {}", handle, err)
+        }
+    }
+
+    let (messages, pw_dids) = fetch_messages(&completed)?;
+
+    let mut threads = build_thread_index()?;
+    let mut reviewed = Vec::new();
+
+    for (connection_handle, messages) in messages {
+        let uids = dispatch(connection_handle, messages, &mut threads, &mut updated);
+        reviewed.extend(uids.into_iter().map(|uid| (connection_handle, uid)));
+    }
+
+    mark_reviewed(reviewed, &pw_dids)?;
+
+    Ok(updated)
+}
+
+/**
+Fetches pending messages of the connections.
+Messages of connections using the agency are downloaded at once, their pairwise DIDs are returned too.
+ */
+fn fetch_messages(connection_handles: &[u32]) -> VcxResult<(HashMap<u32, HashMap<String, A2AMessage>>, HashMap<u32, String>)> {
+    let mut messages = HashMap::new();
+    let mut pw_dids = HashMap::new();
+
+    for &handle in connection_handles {
+        if connection::uses_agency(handle)? {
+            pw_dids.insert(handle, connection::get_pw_did(handle)?);
+        } else {
+            messages.insert(handle, connection::get_messages(handle)?);
+        }
+    }
+
+    if pw_dids.is_empty() {
+        return Ok((messages, pw_dids));
+    }
+
+    let handles: HashMap<&String, u32> = pw_dids.iter().map(|(handle, pw_did)| (pw_did, *handle)).collect();
+
+    let downloaded = download_messages(Some(pw_dids.values().cloned().collect()),
+                                       Some(vec![MessageStatusCode::Received.to_string()]),
+                                       None)?;
+
+    for connection_messages in downloaded {
+        let handle = match handles.get(&connection_messages.pairwise_did) {
+            Some(handle) => *handle,
+            None => continue
+        };
+
+        let decoded = connection_messages.msgs.iter()
+            .filter_map(|message| match decode_message(message) {
+                Ok(a2a_message) => Some((message.uid.clone(), a2a_message)),
+                Err(err) => {
+                    warn!("update_all >>> cannot decode message {}: {}", message.uid, err);
+                    None
+                }
+            })
+            .collect();
+
+        messages.insert(handle, decoded);
+    }
+
+    Ok((messages, pw_dids))
+}
+
+fn decode_message(message: &Message) -> VcxResult<A2AMessage> {
+    let payload = message.decrypted_payload.as_ref()
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidMessages, "Message has no payload"))?;
+
+    let payload: PayloadV1 = serde_json::from_str(payload)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidMessages, format!("Cannot deserialize message payload: {:?}", err)))?;
+
+    serde_json::from_str(&payload.msg)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidMessages, format!("Cannot deserialize A2A message: {:?}", err)))
+}
+
+fn build_thread_index() -> VcxResult<ThreadIndex> {
+    let objects = vec![
+        (ObjectType::Credential, credential::get_thread_ids()?),
+        (ObjectType::DisclosedProof, disclosed_proof::get_thread_ids()?),
+        (ObjectType::IssuerCredential, issuer_credential::get_thread_ids()?),
+        (ObjectType::Proof, proof::get_thread_ids()?),
+    ];
+
+    let mut threads = ThreadIndex::new();
+
+    for (type_, thread_ids) in objects {
+        for (handle, thread_id) in thread_ids {
+            if thread_id.is_empty() { continue; }
+            threads.insert(thread_id, (type_, handle, get_connection_handle(type_, handle)?));
+        }
+    }
+
+    Ok(threads)
+}
+
+/**
+Routes messages of the connection to the objects owning their threads and passes the rest to the connection.
+Returns uids of the handled messages. Messages which failed to be handled are rejected (see `reject`)
+and returned too, so they are marked as reviewed and not retried on every update.
+Messages processed on the connection before are dropped and returned as well, whatever source they come from.
+ */
+fn dispatch(connection_handle: u32,
+            messages: HashMap<String, A2AMessage>,
+            threads: &mut ThreadIndex,
+            updated: &mut Vec<UpdatedObject>) -> Vec<String> {
+    trace!("dispatch >>> connection_handle: {}, messages: {:?}", connection_handle, messages);
+
+    let (messages, mut handled) = match connection::get_pw_did(connection_handle)
+        .and_then(|pw_did| replay::filter_processed(&pw_did, messages)) {
+        Ok(filtered) => filtered,
+        Err(err) => {
+            warn!("dispatch >>> cannot filter processed messages of connection {}: {}", connection_handle, err);
+            return Vec::new();
+        }
+    };
+
+    let mut remaining = HashMap::new();
+
+    for (uid, message) in messages {
+        match route(connection_handle, &message, threads) {
+            Ok(Some(object)) => {
+                if let Err(err) = connection::record_processed(connection_handle, &message) {
+                    warn!("dispatch >>> cannot record message {} as processed: {}", uid, err);
+                }
+                handled.push(uid);
+                updated.push(object);
+            }
+            Ok(None) => {
+                remaining.insert(uid, message);
+            }
+            Err(err) => {
+                warn!("dispatch >>> cannot handle message {}, rejecting it: {}", uid, err);
+                reject(connection_handle, &message, err);
+                handled.push(uid);
+            }
+        }
+    }
+
+    if remaining.is_empty() {
+        return handled;
+    }
+
+    match connection::update_state_with_messages(connection_handle, remaining) {
+        Ok(uids) => {
+            if !uids.is_empty() {
+                updated.push(UpdatedObject::new(ObjectType::Connection, connection_handle, connection_handle));
+            }
+            handled.extend(uids);
+        }
+        Err(err) => warn!("dispatch >>> cannot update connection {}: {}", connection_handle, err)
+    }
+
+    handled
+}
+
+fn route(connection_handle: u32, message: &A2AMessage, threads: &mut ThreadIndex) -> VcxResult<Option<UpdatedObject>> {
+    let thread_id = match get_thread_id(message) {
+        Some(thread_id) => thread_id,
+        None => return Ok(None)
+    };
+
+    let json = json!(message).to_string();
+
+    if let Some(&(type_, handle, owner)) = threads.get(&thread_id) {
+        match owner {
+            Some(owner) if owner == connection_handle => {}
+            Some(owner) => return Err(VcxError::from_msg(VcxErrorKind::InvalidMessages,
+                                                         format!("Thread {} belongs to connection {}, not to {}", thread_id, owner, connection_handle))),
+            // the object is not bound to a connection yet, the application passes it the messages itself
+            None => return Ok(None)
+        }
+
+        match type_ {
+            ObjectType::Credential => credential::update_state(handle, Some(json), Some(connection_handle))?,
+            ObjectType::DisclosedProof => disclosed_proof::update_state(handle, Some(json), Some(connection_handle))?,
+            ObjectType::IssuerCredential => issuer_credential::update_state(handle, Some(json), Some(connection_handle))?,
+            ObjectType::Proof => proof::update_state(handle, Some(json), Some(connection_handle))?,
+            ObjectType::Connection => return Ok(None)
+        };
+        return Ok(Some(UpdatedObject::new(type_, handle, connection_handle)));
+    }
+
+    let (type_, handle) = match message {
+        A2AMessage::CredentialOffer(_) | A2AMessage::CredentialOfferV2(_) =>
+            (ObjectType::Credential, credential::credential_create_with_offer(&thread_id, &json)?),
+        A2AMessage::PresentationRequest(_) | A2AMessage::PresentationRequestV2(_) =>
+            (ObjectType::DisclosedProof, disclosed_proof::create_proof(&thread_id, &json)?),
+        A2AMessage::PresentationProposal(_) | A2AMessage::PresentationProposalV2(_) =>
+            (ObjectType::Proof, proof::create_proof_from_proposal(&thread_id, connection_handle, &json)?),
+        A2AMessage::CredentialProposal(_) | A2AMessage::CredentialProposalV2(_) => match find_proposed_cred_def(message)? {
+            Some(cred_def_handle) =>
+                (ObjectType::IssuerCredential, issuer_credential::issuer_credential_create_from_proposal(&thread_id, cred_def_handle, connection_handle, &json)?),
+            None => return Ok(None)
+        },
+        _ => return Ok(None)
+    };
+
+    threads.insert(thread_id, (type_, handle, Some(connection_handle)));
+
+    Ok(Some(UpdatedObject::created(type_, handle, connection_handle)))
+}
+
+fn get_connection_handle(type_: ObjectType, handle: u32) -> VcxResult<Option<u32>> {
+    match type_ {
+        ObjectType::Credential => credential::get_connection_handle(handle),
+        ObjectType::DisclosedProof => disclosed_proof::get_connection_handle(handle),
+        ObjectType::IssuerCredential => issuer_credential::get_connection_handle(handle),
+        ObjectType::Proof => proof::get_connection_handle(handle),
+        ObjectType::Connection => Ok(Some(handle))
+    }
+}
+
+/**
+Records the message which failed to be handled as processed, so it is not handled again nor acknowledged,
+and reports the failure to the counterparty unless the message is a problem report itself.
+ */
+fn reject(connection_handle: u32, message: &A2AMessage, err: VcxError) {
+    if let Err(err) = connection::record_rejected(connection_handle, message) {
+        warn!("dispatch >>> cannot record rejected message as processed: {}", err);
+    }
+
+    if let A2AMessage::CommonProblemReport(_) = message {
+        return;
+    }
+
+    let problem_report = ProblemReport::create()
+        .set_description(u32::from(err.kind()))
+        .set_comment(err.to_string())
+        .set_thread_id(&get_thread_id(message).unwrap_or_default());
+
+    if let Err(err) = connection::send_message(connection_handle, problem_report.to_a2a_message()) {
+        warn!("dispatch >>> cannot send problem report to connection {}: {}", connection_handle, err);
+    }
+}
+
+fn find_proposed_cred_def(message: &A2AMessage) -> VcxResult<Option<u32>> {
+    let cred_def_id = match message {
+        A2AMessage::CredentialProposal(proposal) => proposal.cred_def_id.clone(),
+        A2AMessage::CredentialProposalV2(proposal) => CredentialProposal::from(proposal.clone()).cred_def_id,
+        _ => return Ok(None)
+    };
+
+    if cred_def_id.is_empty() {
+        return Ok(None);
+    }
+
+    credential_def::find_handle(&cred_def_id)
+}
+
+fn get_thread_id(message: &A2AMessage) -> Option<String> {
+    let message = json!(message);
+
+    message["~thread"]["thid"].as_str()
+        .or(message["@id"].as_str())
+        .map(String::from)
+}
+
+fn mark_reviewed(reviewed: Vec<(u32, String)>, pw_dids: &HashMap<u32, String>) -> VcxResult<()> {
+    let mut uids_by_conns: HashMap<String, Vec<String>> = HashMap::new();
+
+    for (connection_handle, uid) in reviewed {
+        match pw_dids.get(&connection_handle) {
+            Some(pw_did) => uids_by_conns.entry(pw_did.clone()).or_insert_with(Vec::new).push(uid),
+            None => connection::update_message_status(connection_handle, uid)?
+        }
+    }
+
+    if uids_by_conns.is_empty() {
+        return Ok(());
+    }
+
+    let uids_by_conns = uids_by_conns.into_iter()
+        .map(|(pairwise_did, uids)| UIDsByConn { pairwise_did, uids })
+        .collect();
+
+    update_messages(MessageStatusCode::Reviewed, uids_by_conns)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::a2a::MessageId;
+    use aries::messages::error::tests::_problem_report;
+    use aries::messages::issuance::credential_offer::tests::_credential_offer;
+    use aries::messages::issuance::credential_proposal::tests::_credential_proposal;
+    use aries::messages::proof_presentation::presentation_proposal::tests::_presentation_proposal;
+    use connection::tests::build_test_connection_inviter_requested;
+    use credential_def::tests::create_cred_def_fake;
+    use messages::thread::Thread;
+    use settings;
+    use utils::devsetup::{SetupAriesMocks, SetupLibraryWallet};
+    use utils::uuid;
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_get_thread_id() {
+        let _setup = SetupAriesMocks::init();
+
+        let mut offer = _credential_offer();
+        offer.thread = None;
+        assert_eq!(Some(offer.id.0.clone()), get_thread_id(&offer.clone().to_a2a_message()));
+
+        offer.thread = Some(Thread::new().set_thid(String::from("proposal_id")));
+        assert_eq!(Some(String::from("proposal_id")), get_thread_id(&offer.to_a2a_message()));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_dispatch_creates_holder_for_offer_and_routes_its_thread() {
+        let _setup = SetupAriesMocks::init();
+
+        let connection_handle = build_test_connection_inviter_requested();
+
+        let mut offer = _credential_offer();
+        offer.id = MessageId(uuid::uuid());
+        offer.thread = None;
+
+        let mut threads = ThreadIndex::new();
+        let mut updated = Vec::new();
+
+        let messages = map!("offer_uid".to_string() => offer.to_a2a_message());
+        assert_eq!(vec!["offer_uid".to_string()], dispatch(connection_handle, messages, &mut threads, &mut updated));
+
+        assert_eq!(1, updated.len());
+        let holder = updated.pop().unwrap();
+        assert_eq!(ObjectType::Credential, holder.type_);
+        assert!(holder.created);
+        assert_eq!(Some(&(ObjectType::Credential, holder.handle, Some(connection_handle))), threads.get(&offer.id.0));
+
+        let mut problem_report = _problem_report();
+        problem_report.thread = Thread::new().set_thid(offer.id.0.clone());
+
+        // message of the thread coming over another connection is rejected
+        let other_connection_handle = build_test_connection_inviter_requested();
+        let messages = map!("injected_uid".to_string() => A2AMessage::CommonProblemReport(problem_report.clone()));
+        assert_eq!(vec!["injected_uid".to_string()], dispatch(other_connection_handle, messages, &mut threads, &mut updated));
+        assert!(updated.is_empty());
+
+        let messages = map!("problem_report_uid".to_string() => A2AMessage::CommonProblemReport(problem_report));
+        assert_eq!(vec!["problem_report_uid".to_string()], dispatch(connection_handle, messages, &mut threads, &mut updated));
+        assert_eq!(vec![UpdatedObject::new(ObjectType::Credential, holder.handle, connection_handle)], updated);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_dispatch_drops_message_processed_before() {
+        let _setup = SetupLibraryWallet::init();
+        // the connection receives messages on inbound endpoint, so it is provisioned without agency agent
+        settings::set_config_value(settings::CONFIG_INBOUND_ENDPOINT, "http://localhost:8080/inbound");
+
+        let connection_handle = connection::create_connection("faber_to_alice").unwrap();
+        connection::connect(connection_handle).unwrap();
+
+        let mut offer = _credential_offer();
+        offer.id = MessageId(uuid::uuid());
+        offer.thread = None;
+        let offer = offer.to_a2a_message();
+
+        replay::record_processed(&connection::get_pw_did(connection_handle).unwrap(), &offer).unwrap();
+
+        let mut threads = ThreadIndex::new();
+        let mut updated = Vec::new();
+
+        let messages = map!("offer_uid".to_string() => offer);
+        assert_eq!(vec!["offer_uid".to_string()], dispatch(connection_handle, messages, &mut threads, &mut updated));
+        assert!(updated.is_empty());
+        assert!(threads.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_dispatch_creates_issuer_for_proposal_of_loaded_cred_def() {
+        let _setup = SetupAriesMocks::init();
+
+        let connection_handle = build_test_connection_inviter_requested();
+        let cred_def_handle = create_cred_def_fake();
+
+        let mut proposal = _credential_proposal();
+        proposal.id = MessageId(uuid::uuid());
+        proposal.thread = None;
+
+        let mut threads = ThreadIndex::new();
+        let mut updated = Vec::new();
+
+        let messages = map!("unknown_uid".to_string() => proposal.clone().set_cred_def_id("unknown".to_string()).to_a2a_message());
+        dispatch(connection_handle, messages, &mut threads, &mut updated);
+        assert!(updated.iter().all(|object| object.type_ == ObjectType::Connection));
+
+        proposal.cred_def_id = credential_def::get_cred_def_id(cred_def_handle).unwrap();
+
+        let messages = map!("proposal_uid".to_string() => proposal.to_a2a_message());
+        assert_eq!(vec!["proposal_uid".to_string()], dispatch(connection_handle, messages, &mut threads, &mut updated));
+
+        let issuer = updated.pop().unwrap();
+        assert_eq!(ObjectType::IssuerCredential, issuer.type_);
+        assert!(issuer.created);
+        assert_eq!(VcxStateType::VcxStateProposalReceived as u32, issuer_credential::get_state(issuer.handle).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_dispatch_creates_verifier_for_proposal() {
+        let _setup = SetupAriesMocks::init();
+
+        let connection_handle = build_test_connection_inviter_requested();
+
+        let mut proposal = _presentation_proposal();
+        proposal.id = MessageId(uuid::uuid());
+        proposal.thread = Thread::new();
+
+        let mut threads = ThreadIndex::new();
+        let mut updated = Vec::new();
+
+        let messages = map!("proposal_uid".to_string() => proposal.to_a2a_message());
+        assert_eq!(vec!["proposal_uid".to_string()], dispatch(connection_handle, messages, &mut threads, &mut updated));
+
+        let verifier = updated.pop().unwrap();
+        assert_eq!(ObjectType::Proof, verifier.type_);
+        assert!(verifier.created);
+        assert_eq!(VcxStateType::VcxStateProposalReceived as u32, proof::get_state(verifier.handle).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_update_all_works_without_messages() {
+        let _setup = SetupAriesMocks::init();
+
+        let updated: Vec<::serde_json::Value> = ::serde_json::from_str(&update_all().unwrap()).unwrap();
+        assert!(updated.iter().all(|object| object["type"] == json!("connection")));
+    }
+}
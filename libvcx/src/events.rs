@@ -0,0 +1,201 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex, RwLock};
+
+use dispatcher::ObjectType;
+use error::prelude::*;
+use history::History;
+use utils::object_cache::ObjectCache;
+
+/// Event emitted whenever the state machine of a connection, credential or proof handles a message.
+/// The old and the new states are equal when the message left the state unchanged.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct StateEvent {
+    #[serde(rename = "type")]
+    pub type_: ObjectType,
+    pub handle: u32,
+    pub source_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub thread_id: Option<String>,
+    pub old_state: u32,
+    pub new_state: u32,
+    /// Name of the state machine message which triggered the transition, e.g. `CredentialOffer`.
+    pub message_type: String,
+    /// Object serialized right after the transition, as returned by `to_string`.
+    #[serde(skip)]
+    pub object: String,
+}
+
+pub trait StateSubscriber: Send + Sync {
+    fn on_state_changed(&self, event: &StateEvent);
+}
+
+/// Object whose state transitions are recorded in its history and reported to subscribers.
+pub trait Observable {
+    fn source_id(&self) -> String;
+    fn thread_id(&self) -> Option<String>;
+    fn history(&self) -> &History;
+    /// Versioned json the object can be restored from by `from_string`.
+    fn serialize(&self) -> VcxResult<String>;
+}
+
+lazy_static! {
+    static ref SUBSCRIBERS: RwLock<HashMap<u32, Arc<dyn StateSubscriber>>> = RwLock::new(HashMap::new());
+    static ref NEXT_SUBSCRIPTION: Mutex<u32> = Mutex::new(1);
+}
+
+/**
+Subscribes for state transitions of all objects. Returns the subscription handle.
+Subscribers are notified synchronously once the object which emitted the event is unlocked,
+so they are free to query it.
+ */
+pub fn subscribe(subscriber: Arc<dyn StateSubscriber>) -> u32 {
+    let mut next_subscription = NEXT_SUBSCRIPTION.lock().unwrap();
+
+    let subscription = *next_subscription;
+    *next_subscription += 1;
+
+    SUBSCRIBERS.write().unwrap().insert(subscription, subscriber);
+
+    subscription
+}
+
+pub fn unsubscribe(subscription: u32) -> VcxResult<()> {
+    SUBSCRIBERS.write().unwrap()
+        .remove(&subscription)
+        .map(|_| ())
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidHandle, format!("Subscription not found: {}", subscription)))
+}
+
+pub fn unsubscribe_all() {
+    SUBSCRIBERS.write().unwrap().clear();
+}
+
+/**
+Stores the object in the cache and notifies subscribers about the transitions it went through on creation.
+ */
+pub fn add<T: Observable>(cache: &ObjectCache<T>, type_: ObjectType, object: T) -> VcxResult<u32> {
+    let events = _events(type_, 0, &object, 0);
+    let handle = cache.add(object)?;
+
+    _notify(events.into_iter()
+        .map(|event| StateEvent { handle, ..event })
+        .collect());
+
+    Ok(handle)
+}
+
+/**
+Applies the closure to the object stored in the cache under the handle and notifies subscribers
+about the transitions the closure recorded into the object history.
+ */
+pub fn update<T, F, R>(cache: &ObjectCache<T>, type_: ObjectType, handle: u32, closure: F) -> VcxResult<R>
+    where T: Observable, F: Fn(&mut T) -> VcxResult<R> {
+    let (result, events) = cache.get_mut(handle, |object| {
+        let recorded = object.history().transitions().len();
+        let result = closure(object);
+        Ok((result, _events(type_, handle, object, recorded)))
+    })?;
+
+    _notify(events);
+
+    result
+}
+
+fn _events<T: Observable>(type_: ObjectType, handle: u32, object: &T, recorded: usize) -> Vec<StateEvent> {
+    let transitions: Vec<_> = object.history().transitions().iter()
+        .skip(recorded)
+        .collect();
+
+    if transitions.is_empty() || SUBSCRIBERS.read().unwrap().is_empty() {
+        return Vec::new();
+    }
+
+    let serialized = match object.serialize() {
+        Ok(serialized) => serialized,
+        Err(err) => {
+            warn!("Cannot serialize {:?} {} for state event: {}", type_, handle, err);
+            return Vec::new();
+        }
+    };
+
+    let thread_id = object.thread_id().filter(|thread_id| !thread_id.is_empty());
+
+    transitions.into_iter()
+        .map(|transition| StateEvent {
+            type_,
+            handle,
+            source_id: object.source_id(),
+            thread_id: thread_id.clone(),
+            old_state: transition.old_state,
+            new_state: transition.new_state,
+            message_type: transition.message_type.clone(),
+            object: serialized.clone(),
+        })
+        .collect()
+}
+
+fn _notify(events: Vec<StateEvent>) {
+    if events.is_empty() {
+        return;
+    }
+
+    let subscribers: Vec<Arc<dyn StateSubscriber>> = SUBSCRIBERS.read().unwrap().values().cloned().collect();
+
+    for event in events {
+        trace!("_notify >>> event: {:?}", event);
+
+        for subscriber in subscribers.iter() {
+            subscriber.on_state_changed(&event);
+        }
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::mpsc::{channel, Sender};
+
+    use api::VcxStateType;
+    use connection::tests::build_test_connection_inviter_requested;
+    use issuer_credential;
+    use issuer_credential::tests::_issuer_credential_create;
+    use utils::devsetup::SetupStrictAriesMocks;
+    use utils::timeout::TimeoutUtils;
+
+    use super::*;
+
+    pub struct ChannelSubscriber(pub Mutex<Sender<StateEvent>>);
+
+    impl StateSubscriber for ChannelSubscriber {
+        fn on_state_changed(&self, event: &StateEvent) {
+            self.0.lock().unwrap().send(event.clone()).ok();
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_transition_is_emitted_to_subscribers() {
+        let _setup = SetupStrictAriesMocks::init();
+
+        let (sender, receiver) = channel();
+        let subscription = subscribe(Arc::new(ChannelSubscriber(Mutex::new(sender))));
+
+        let connection_handle = build_test_connection_inviter_requested();
+        let handle = _issuer_credential_create();
+        issuer_credential::send_credential_offer(handle, connection_handle, None).unwrap();
+
+        // subscribers are shared by tests running in parallel
+        let event = ::std::iter::repeat_with(|| receiver.recv_timeout(TimeoutUtils::medium_timeout()).unwrap())
+            .find(|event| event.handle == handle)
+            .unwrap();
+
+        assert_eq!(ObjectType::IssuerCredential, event.type_);
+        assert_eq!("1", event.source_id);
+        assert_eq!(VcxStateType::VcxStateInitialized as u32, event.old_state);
+        assert_eq!(VcxStateType::VcxStateOfferSent as u32, event.new_state);
+        assert_eq!("CredentialInit", event.message_type);
+        assert_eq!(issuer_credential::to_string(handle).unwrap(), event.object);
+
+        unsubscribe(subscription).unwrap();
+        assert_eq!(VcxErrorKind::InvalidHandle, unsubscribe(subscription).unwrap_err().kind());
+    }
+}
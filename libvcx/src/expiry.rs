@@ -0,0 +1,122 @@
+use serde_json;
+
+use dispatcher::ObjectType;
+use error::prelude::*;
+use issuer_credential;
+use proof;
+
+/*
+    Issuers and verifiers put `~timing.expires_time` on the offers and presentation requests they send
+    when a timeout is configured for the protocol family in `protocol_timeouts` config option.
+
+    An exchange whose counterparty did not answer in time is abandoned: a problem report is sent to the counterparty
+    and the object moves to the finished state with failed status. It happens on the next update of the object,
+    or for all objects at once when expired objects are swept.
+*/
+
+/// Object whose exchange was abandoned because it expired.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct ExpiredObject {
+    #[serde(rename = "type")]
+    pub type_: ObjectType,
+    pub handle: u32,
+}
+
+/**
+Abandons all expired exchanges of issuer credentials and proofs.
+Returns JSON array of abandoned objects: [{"type": "proof", "handle": 1}]
+ */
+pub fn sweep() -> VcxResult<String> {
+    trace!("sweep >>>");
+
+    let expired = sweep_objects()?;
+
+    serde_json::to_string(&expired)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize expired objects: {:?}", err)))
+}
+
+fn sweep_objects() -> VcxResult<Vec<ExpiredObject>> {
+    let mut expired = Vec::new();
+
+    for handle in issuer_credential::get_expired()? {
+        match issuer_credential::expire(handle) {
+            Ok(true) => expired.push(ExpiredObject { type_: ObjectType::IssuerCredential, handle }),
+            Ok(false) => {}
+            Err(err) => warn!("sweep >>> cannot expire issuer credential {}: {}", handle, err)
+        }
+    }
+
+    for handle in proof::get_expired()? {
+        match proof::expire(handle) {
+            Ok(true) => expired.push(ExpiredObject { type_: ObjectType::Proof, handle }),
+            Ok(false) => {}
+            Err(err) => warn!("sweep >>> cannot expire proof {}: {}", handle, err)
+        }
+    }
+
+    Ok(expired)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use api::{ProofStateType, VcxStateType};
+    use connection::tests::build_test_connection_inviter_requested;
+    use issuer_credential::tests::_issuer_credential_create;
+    use settings;
+    use utils::constants::{REQUESTED_ATTRS, REQUESTED_PREDICATES};
+    use utils::devsetup::SetupStrictAriesMocks;
+
+    use super::*;
+
+    fn _proof_create() -> u32 {
+        proof::create_proof("1".to_string(),
+                            REQUESTED_ATTRS.to_owned(),
+                            REQUESTED_PREDICATES.to_owned(),
+                            r#"{"support_revocation":false}"#.to_string(),
+                            "Optional".to_owned()).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_sweep_abandons_expired_exchanges() {
+        let _setup = SetupStrictAriesMocks::init();
+        settings::set_config_value(settings::CONFIG_PROTOCOL_TIMEOUTS, &json!({"issue-credential": 0, "present-proof": 0}).to_string());
+
+        let connection_handle = build_test_connection_inviter_requested();
+
+        let credential_handle = _issuer_credential_create();
+        issuer_credential::send_credential_offer(credential_handle, connection_handle, None).unwrap();
+
+        let proof_handle = _proof_create();
+        proof::send_proof_request(proof_handle, connection_handle).unwrap();
+
+        let expired: Vec<serde_json::Value> = serde_json::from_str(&sweep().unwrap()).unwrap();
+        assert_eq!(2, expired.len());
+        assert!(expired.contains(&json!({"type": "issuer_credential", "handle": credential_handle})));
+        assert!(expired.contains(&json!({"type": "proof", "handle": proof_handle})));
+
+        assert_eq!(VcxStateType::VcxStateNone as u32, issuer_credential::get_state(credential_handle).unwrap());
+        assert_eq!(ProofStateType::ProofInvalid as u32, proof::get_proof_state(proof_handle).unwrap());
+
+        assert_eq!("[]", sweep().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_sweep_keeps_exchanges_without_timeout() {
+        let _setup = SetupStrictAriesMocks::init();
+        settings::set_config_value(settings::CONFIG_PROTOCOL_TIMEOUTS, &json!({"issue-credential": 3600}).to_string());
+
+        let connection_handle = build_test_connection_inviter_requested();
+
+        let credential_handle = _issuer_credential_create();
+        issuer_credential::send_credential_offer(credential_handle, connection_handle, None).unwrap();
+
+        let proof_handle = _proof_create();
+        proof::send_proof_request(proof_handle, connection_handle).unwrap();
+
+        assert_eq!("[]", sweep().unwrap());
+        assert_eq!(VcxStateType::VcxStateOfferSent as u32, issuer_credential::get_state(credential_handle).unwrap());
+        assert_eq!(VcxStateType::VcxStateOfferSent as u32, proof::get_state(proof_handle).unwrap());
+    }
+}
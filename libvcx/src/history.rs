@@ -0,0 +1,253 @@
+use chrono::Utc;
+use serde_json;
+
+use connection;
+use credential;
+use dispatcher::ObjectType;
+use disclosed_proof;
+use error::prelude::*;
+use issuer_credential;
+use proof;
+use aries::messages::connection::problem_report::ProblemReport as ConnectionProblemReport;
+use aries::messages::did_exchange::problem_report::ProblemReport as DidExchangeProblemReport;
+use aries::messages::error::ProblemReport;
+use aries::messages::status::Status;
+
+/*
+    Append-only history of state transitions kept by every connection, credential and proof.
+
+    It is recorded by state machines for every handled message and it is a part of the serialized object,
+    so it survives `to_string` / `from_string` and can be used for audits of what was issued or requested.
+    Messages which left the state unchanged (e.g. ignored messages, pings) are recorded as transitions
+    with equal old and new states.
+*/
+
+#[derive(Debug, Clone, PartialEq, Default, Serialize, Deserialize)]
+pub struct History(Vec<Transition>);
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Transition {
+    /// RFC 3339 time of the transition.
+    pub timestamp: String,
+    pub old_state: u32,
+    pub new_state: u32,
+    /// Name of the state machine message which triggered the transition, e.g. `CredentialOffer`.
+    pub message_type: String,
+    /// `@id` of the received message which triggered the transition.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub message_id: Option<String>,
+    pub outcome: Outcome,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub problem_report: Option<Problem>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum Outcome {
+    InProgress,
+    Success,
+    Failed,
+    Declined,
+}
+
+/// Details of the problem report which was sent or received within the transition.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct Problem {
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub comment: Option<String>,
+}
+
+impl History {
+    pub fn record(&mut self, transition: Transition) {
+        self.0.push(transition);
+    }
+
+    pub fn transitions(&self) -> &[Transition] {
+        &self.0
+    }
+
+    /// Whether a transition was already triggered by the received message of the given type and `@id`.
+    pub fn contains_message(&self, message_type: &str, message_id: &str) -> bool {
+        self.0.iter().any(|transition|
+            transition.message_type == message_type && transition.message_id.as_ref().map(String::as_str) == Some(message_id)
+        )
+    }
+}
+
+impl Transition {
+    pub fn new(old_state: u32, new_state: u32, message_type: &str) -> Transition {
+        Transition {
+            timestamp: Utc::now().to_rfc3339(),
+            old_state,
+            new_state,
+            message_type: message_type.to_string(),
+            message_id: None,
+            outcome: Outcome::InProgress,
+            problem_report: None,
+        }
+    }
+
+    pub fn set_message_id(mut self, message_id: Option<String>) -> Transition {
+        self.message_id = message_id;
+        self
+    }
+
+    pub fn set_outcome(mut self, outcome: Outcome) -> Transition {
+        self.outcome = outcome;
+        self
+    }
+
+    /// Takes the outcome from the status of the finished exchange.
+    pub fn set_status(mut self, status: Option<&Status>) -> Transition {
+        match status {
+            Some(Status::Success) => self.outcome = Outcome::Success,
+            Some(Status::Declined) => self.outcome = Outcome::Declined,
+            Some(Status::Failed(problem_report)) => {
+                self.outcome = Outcome::Failed;
+                self.problem_report = Some(problem_report.into());
+            }
+            Some(Status::Undefined) | None => {}
+        }
+        self
+    }
+
+    pub fn set_problem_report(mut self, problem_report: Option<Problem>) -> Transition {
+        if problem_report.is_some() {
+            self.problem_report = problem_report;
+        }
+        self
+    }
+}
+
+impl<'a> From<&'a ProblemReport> for Problem {
+    fn from(problem_report: &'a ProblemReport) -> Problem {
+        Problem {
+            code: problem_report.description.as_ref().map(|description| description.code.to_string()),
+            comment: problem_report.comment.clone(),
+        }
+    }
+}
+
+impl<'a> From<&'a ConnectionProblemReport> for Problem {
+    fn from(problem_report: &'a ConnectionProblemReport) -> Problem {
+        Problem {
+            code: problem_report.problem_code.as_ref().and_then(|code| json!(code).as_str().map(String::from)),
+            comment: problem_report.explain.clone(),
+        }
+    }
+}
+
+impl<'a> From<&'a DidExchangeProblemReport> for Problem {
+    fn from(problem_report: &'a DidExchangeProblemReport) -> Problem {
+        Problem {
+            code: problem_report.problem_code.as_ref().and_then(|code| json!(code).as_str().map(String::from)),
+            comment: problem_report.explain.clone(),
+        }
+    }
+}
+
+/// Returns the history of transitions of the object as json array.
+pub fn get(type_: ObjectType, handle: u32) -> VcxResult<String> {
+    trace!("get >>> type: {:?}, handle: {}", type_, handle);
+
+    let history = match type_ {
+        ObjectType::Connection => connection::get_history(handle)?,
+        ObjectType::Credential => credential::get_history(handle)?,
+        ObjectType::DisclosedProof => disclosed_proof::get_history(handle)?,
+        ObjectType::IssuerCredential => issuer_credential::get_history(handle)?,
+        ObjectType::Proof => proof::get_history(handle)?,
+    };
+
+    serde_json::to_string(&history)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidState, format!("Cannot serialize history: {:?}", err)))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use api::VcxStateType;
+    use connection::tests::build_test_connection_inviter_requested;
+    use issuer_credential::tests::_issuer_credential_create;
+    use utils::devsetup::{SetupAriesMocks, SetupStrictAriesMocks};
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_history_records_every_message() {
+        let mut history = History::default();
+
+        history.record(Transition::new(1, 1, "SendPing"));
+        history.record(Transition::new(1, 2, "CredentialInit"));
+
+        assert_eq!(2, history.transitions().len());
+        assert_eq!("SendPing", history.transitions()[0].message_type);
+        assert_eq!("CredentialInit", history.transitions()[1].message_type);
+        assert_eq!(Outcome::InProgress, history.transitions()[1].outcome);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_history_contains_message() {
+        let mut history = History::default();
+
+        history.record(Transition::new(1, 2, "CredentialInit"));
+        history.record(Transition::new(2, 3, "CredentialRequest").set_message_id(Some(String::from("request_id"))));
+
+        assert!(history.contains_message("CredentialRequest", "request_id"));
+        assert!(!history.contains_message("CredentialRequest", "other_id"));
+        assert!(!history.contains_message("Credential", "request_id"));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_transition_set_status() {
+        let problem_report = ProblemReport::create().set_description(42).set_comment(String::from("Invalid offer"));
+
+        let transition = Transition::new(2, 0, "ProblemReport").set_status(Some(&Status::Failed(problem_report)));
+        assert_eq!(Outcome::Failed, transition.outcome);
+        assert_eq!(Some(Problem { code: Some(String::from("42")), comment: Some(String::from("Invalid offer")) }), transition.problem_report);
+
+        let transition = Transition::new(2, 4, "Credential").set_status(Some(&Status::Success));
+        assert_eq!(Outcome::Success, transition.outcome);
+        assert_eq!(None, transition.problem_report);
+
+        let transition = Transition::new(1, 2, "CredentialInit").set_status(None);
+        assert_eq!(Outcome::InProgress, transition.outcome);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_history_is_serialized_with_object() {
+        let _setup = SetupStrictAriesMocks::init();
+
+        let connection_handle = build_test_connection_inviter_requested();
+        let handle = _issuer_credential_create();
+        issuer_credential::send_credential_offer(handle, connection_handle, None).unwrap();
+
+        let handle = issuer_credential::from_string(&issuer_credential::to_string(handle).unwrap()).unwrap();
+
+        let history: Vec<Transition> = serde_json::from_str(&get(ObjectType::IssuerCredential, handle).unwrap()).unwrap();
+        assert_eq!(1, history.len());
+        assert_eq!(VcxStateType::VcxStateInitialized as u32, history[0].old_state);
+        assert_eq!(VcxStateType::VcxStateOfferSent as u32, history[0].new_state);
+        assert_eq!("CredentialInit", history[0].message_type);
+
+        let history: Vec<Transition> = serde_json::from_str(&get(ObjectType::Connection, connection_handle).unwrap()).unwrap();
+        assert!(!history.is_empty());
+
+        let restored_handle = connection::from_string(&connection::to_string(connection_handle).unwrap()).unwrap();
+        let restored_history: Vec<Transition> = serde_json::from_str(&get(ObjectType::Connection, restored_handle).unwrap()).unwrap();
+        assert_eq!(history, restored_history);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_get_fails_for_invalid_handle() {
+        let _setup = SetupAriesMocks::init();
+
+        assert!(get(ObjectType::Proof, 0).is_err());
+        assert!(get(ObjectType::Connection, 0).is_err());
+    }
+}
@@ -0,0 +1,396 @@
+use std::collections::{HashMap, HashSet};
+use std::sync::Mutex;
+
+use indy::SearchHandle;
+
+use aries::messages::a2a::A2AMessage;
+use aries::utils::encryption_envelope::EncryptionEnvelope;
+use connection;
+use dispatcher;
+use error::prelude::*;
+use settings;
+use utils::libindy::wallet;
+#[cfg(feature = "inbound_listener")]
+use utils::transport::listener::Listener;
+use utils::uuid;
+
+/*
+    Inbound endpoint lets the library receive messages without an agency.
+
+    Packed messages posted to the endpoint are unpacked and kept in the inbox under the recipient key,
+    messages for keys not used by any connection are rejected. Listening requires `inbound_listener` feature. The connection is updated right away
+    the same way `update_all` does, messages it cannot handle yet stay in the inbox.
+    Connections created while the endpoint is configured put its URL into their DIDDocs
+    and read their messages from the inbox on `update_state` as they would read them from the agency.
+    Mediated connections read the messages picked up from the mediator from the inbox too.
+
+    Every inbox message is also stored into a `vcx_inbox` wallet record tagged by the recipient key
+    and removed once it is handled, so pending messages are loaded back after restart.
+    At most `MAX_INBOX_SIZE` messages are kept per key.
+*/
+
+pub const INBOX_RECORD_TYPE: &str = "vcx_inbox";
+
+const MAX_INBOX_SIZE: usize = 100;
+const SEARCH_BATCH_SIZE: usize = 100;
+
+lazy_static! {
+    static ref INBOX: Mutex<HashMap<String, Vec<(String, A2AMessage)>>> = Mutex::new(HashMap::new());
+    static ref LOADED_KEYS: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+}
+
+#[cfg(feature = "inbound_listener")]
+lazy_static! {
+    static ref LISTENER: Mutex<Option<Listener>> = Mutex::new(None);
+}
+
+#[derive(Debug, Deserialize)]
+struct InboxRecord {
+    id: String,
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchRecords {
+    records: Option<Vec<InboxRecord>>,
+}
+
+/**
+Starts listening on `inbound_address` if it is set in the config.
+ */
+pub fn init() -> VcxResult<()> {
+    match settings::get_config_value(settings::CONFIG_INBOUND_ADDRESS) {
+        Ok(address) => start(&address),
+        Err(_) => Ok(())
+    }
+}
+
+/**
+Starts listening for inbound messages on the given address, e.g. `0.0.0.0:8080`.
+Does nothing if the listener is already running on this address.
+ */
+#[cfg(feature = "inbound_listener")]
+pub fn start(address: &str) -> VcxResult<()> {
+    trace!("inbound::start >>> address: {}", address);
+
+    let mut listener = LISTENER.lock().unwrap();
+
+    if let Some(ref running) = *listener {
+        if running.address() == address {
+            return Ok(());
+        }
+    }
+
+    if let Some(running) = listener.take() {
+        running.stop();
+    }
+
+    *listener = Some(Listener::start(address, receive_message)?);
+
+    Ok(())
+}
+
+#[cfg(not(feature = "inbound_listener"))]
+pub fn start(address: &str) -> VcxResult<()> {
+    trace!("inbound::start >>> address: {}", address);
+    Err(VcxError::from_msg(VcxErrorKind::ActionNotSupported, "Inbound listener is not supported, libvcx is built without inbound_listener feature"))
+}
+
+#[cfg(feature = "inbound_listener")]
+pub fn stop() {
+    trace!("inbound::stop >>>");
+
+    if let Some(listener) = LISTENER.lock().unwrap().take() {
+        listener.stop();
+    }
+}
+
+#[cfg(not(feature = "inbound_listener"))]
+pub fn stop() {
+    trace!("inbound::stop >>>");
+}
+
+/**
+Returns whether new connections should use the inbound endpoint instead of the agency.
+ */
+pub fn is_enabled() -> bool {
+    settings::get_config_value(settings::CONFIG_INBOUND_ENDPOINT).is_ok()
+}
+
+pub fn get_endpoint() -> VcxResult<String> {
+    settings::get_config_value(settings::CONFIG_INBOUND_ENDPOINT)
+        .map_err(|_| VcxError::from_msg(VcxErrorKind::NotReady, "Inbound endpoint is not set"))
+}
+
+/**
+Unpacks the message received on the inbound endpoint, puts it into the inbox of the recipient key
+and updates the connection using the key.
+ */
+pub fn receive_message(payload: &[u8]) -> VcxResult<()> {
+    trace!("inbound::receive_message >>>");
+
+    let unpacked = EncryptionEnvelope::unpack(payload)?;
+
+    let message: A2AMessage = ::serde_json::from_str(&unpacked.message)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize A2A message: {}", err)))?;
+
+    let connection_handle = connection::find_by_pw_verkey(&unpacked.recipient_verkey)?
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidMessages, format!("No connection uses recipient key: {}", unpacked.recipient_verkey)))?;
+
+    debug!("inbound::receive_message >>> received message for {}: {:?}", unpacked.recipient_verkey, message);
+
+    store_message(&unpacked.recipient_verkey, &uuid::uuid(), message)?;
+
+    if let Err(err) = dispatcher::update_connections(&[connection_handle]) {
+        warn!("inbound::receive_message >>> cannot update connection {}: {}", connection_handle, err);
+    }
+
+    Ok(())
+}
+
+/**
+Puts the message into the inbox of the recipient key unless a message with the same uid is already there.
+Fails if the inbox of the key is full.
+ */
+pub fn store_message(pw_vk: &str, uid: &str, message: A2AMessage) -> VcxResult<()> {
+    _load(pw_vk)?;
+
+    let mut inbox = INBOX.lock().unwrap();
+    let messages = inbox.entry(pw_vk.to_string()).or_insert_with(Vec::new);
+
+    if messages.iter().any(|(uid_, _)| uid_ == uid) {
+        return Ok(());
+    }
+
+    if messages.len() >= MAX_INBOX_SIZE {
+        return Err(VcxError::from_msg(VcxErrorKind::InvalidState, format!("Inbox of recipient key {} is full", pw_vk)));
+    }
+
+    let value = ::serde_json::to_string(&message)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize inbox message: {:?}", err)))?;
+
+    wallet::add_record(INBOX_RECORD_TYPE, uid, &value, Some(&json!({"recipient_verkey": pw_vk}).to_string()))?;
+
+    messages.push((uid.to_string(), message));
+
+    Ok(())
+}
+
+pub fn contains_message(pw_vk: &str, uid: &str) -> bool {
+    INBOX.lock().unwrap()
+        .get(pw_vk)
+        .map(|messages| messages.iter().any(|(uid_, _)| uid_ == uid))
+        .unwrap_or(false)
+}
+
+pub fn get_messages(pw_vk: &str) -> VcxResult<HashMap<String, A2AMessage>> {
+    _load(pw_vk)?;
+
+    Ok(INBOX.lock().unwrap()
+        .get(pw_vk)
+        .map(|messages| messages.iter().cloned().collect())
+        .unwrap_or_default())
+}
+
+pub fn get_message(pw_vk: &str, uid: &str) -> VcxResult<A2AMessage> {
+    _load(pw_vk)?;
+
+    INBOX.lock().unwrap()
+        .get(pw_vk)
+        .and_then(|messages| messages.iter().find(|(uid_, _)| uid_ == uid))
+        .map(|(_, message)| message.clone())
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidMessages, format!("Message not found for id: {:?}", uid)))
+}
+
+pub fn remove_message(pw_vk: &str, uid: &str) -> VcxResult<()> {
+    if let Some(messages) = INBOX.lock().unwrap().get_mut(pw_vk) {
+        messages.retain(|(uid_, _)| uid_ != uid);
+    }
+
+    _delete_record(uid)
+}
+
+pub fn remove_messages(pw_vk: &str) -> VcxResult<()> {
+    _load(pw_vk)?;
+
+    let messages = INBOX.lock().unwrap().remove(pw_vk).unwrap_or_default();
+
+    for (uid, _) in messages {
+        _delete_record(&uid)?;
+    }
+
+    Ok(())
+}
+
+/// Drops the messages kept in memory, they are loaded from the wallet again when needed.
+pub fn clear() {
+    INBOX.lock().unwrap().clear();
+    LOADED_KEYS.lock().unwrap().clear();
+}
+
+/// Loads messages stored in the wallet for the key, once per key.
+fn _load(pw_vk: &str) -> VcxResult<()> {
+    if LOADED_KEYS.lock().unwrap().contains(pw_vk) {
+        return Ok(());
+    }
+
+    let query = json!({"recipient_verkey": pw_vk}).to_string();
+    let search_handle = wallet::open_search(INBOX_RECORD_TYPE, &query, &json!({"retrieveRecords": true}).to_string())?;
+
+    let result = _fetch_all(search_handle);
+
+    wallet::close_search(search_handle)?;
+
+    let mut inbox = INBOX.lock().unwrap();
+    let messages = inbox.entry(pw_vk.to_string()).or_insert_with(Vec::new);
+
+    for record in result? {
+        let message: A2AMessage = ::serde_json::from_str(&record.value)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize inbox message: {:?}", err)))?;
+
+        if !messages.iter().any(|(uid, _)| uid == &record.id) {
+            messages.push((record.id, message));
+        }
+    }
+
+    LOADED_KEYS.lock().unwrap().insert(pw_vk.to_string());
+
+    Ok(())
+}
+
+fn _fetch_all(search_handle: SearchHandle) -> VcxResult<Vec<InboxRecord>> {
+    let mut records = Vec::new();
+
+    loop {
+        let batch = wallet::fetch_next_records(search_handle, SEARCH_BATCH_SIZE)?;
+
+        let batch: SearchRecords = ::serde_json::from_str(&batch)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize inbox records: {:?}", err)))?;
+
+        match batch.records {
+            Some(ref batch) if batch.is_empty() => break,
+            Some(batch) => records.extend(batch),
+            None => break
+        }
+    }
+
+    Ok(records)
+}
+
+fn _delete_record(uid: &str) -> VcxResult<()> {
+    match wallet::delete_record(INBOX_RECORD_TYPE, uid) {
+        Err(ref err) if err.kind() == VcxErrorKind::WalletRecordNotFound => Ok(()),
+        result => result
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::ack::tests::_ack;
+    use connection;
+    use utils::devsetup::SetupAriesMocks;
+
+    use super::*;
+
+    pub fn _packed(message: &A2AMessage, recipient_key: &str) -> Vec<u8> {
+        json!({
+            "message": json!(message).to_string(),
+            "sender_verkey": "Hezce2UWMZ3wUhVkh2LfKSs8nDzWwzs2Win7EzNN3YaR",
+            "recipient_verkey": recipient_key
+        }).to_string().into_bytes()
+    }
+
+    /// Connection using the inbound endpoint with its own key, the inbox is shared by tests running in parallel.
+    fn _inbound_connection(pw_vk: &str) -> u32 {
+        settings::set_config_value(settings::CONFIG_INBOUND_ENDPOINT, "https://issuer.example.com/inbound");
+
+        let handle = connection::create_connection("faber").unwrap();
+        connection::connect(handle).unwrap();
+
+        let mut serialized: ::serde_json::Value = ::serde_json::from_str(&connection::to_string(handle).unwrap()).unwrap();
+        serialized["data"]["pw_vk"] = json!(pw_vk);
+
+        connection::from_string(&serialized.to_string()).unwrap()
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_receive_message_puts_message_into_recipient_inbox() {
+        let _setup = SetupAriesMocks::init();
+
+        let recipient_key = uuid::uuid();
+        _inbound_connection(&recipient_key);
+
+        receive_message(&_packed(&_ack().to_a2a_message(), &recipient_key)).unwrap();
+
+        let messages = get_messages(&recipient_key).unwrap();
+        assert_eq!(1, messages.len());
+        assert!(get_messages(&uuid::uuid()).unwrap().is_empty());
+
+        let (uid, message) = messages.into_iter().next().unwrap();
+        assert_eq!(_ack().to_a2a_message(), message);
+        assert_eq!(message, get_message(&recipient_key, &uid).unwrap());
+
+        store_message(&recipient_key, &uid, _ack().to_a2a_message()).unwrap();
+        assert_eq!(1, get_messages(&recipient_key).unwrap().len());
+
+        remove_message(&recipient_key, &uid).unwrap();
+        assert!(get_messages(&recipient_key).unwrap().is_empty());
+        assert!(!contains_message(&recipient_key, &uid));
+        assert_eq!(VcxErrorKind::InvalidMessages, get_message(&recipient_key, &uid).unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_receive_message_rejects_unknown_recipient_key() {
+        let _setup = SetupAriesMocks::init();
+
+        let recipient_key = uuid::uuid();
+
+        assert_eq!(VcxErrorKind::InvalidMessages, receive_message(&_packed(&_ack().to_a2a_message(), &recipient_key)).unwrap_err().kind());
+        assert!(get_messages(&recipient_key).unwrap().is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_store_message_fails_for_full_inbox() {
+        let _setup = SetupAriesMocks::init();
+
+        let recipient_key = uuid::uuid();
+
+        for _ in 0..MAX_INBOX_SIZE {
+            store_message(&recipient_key, &uuid::uuid(), _ack().to_a2a_message()).unwrap();
+        }
+
+        assert_eq!(VcxErrorKind::InvalidState, store_message(&recipient_key, &uuid::uuid(), _ack().to_a2a_message()).unwrap_err().kind());
+
+        remove_messages(&recipient_key).unwrap();
+        assert!(get_messages(&recipient_key).unwrap().is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_receive_message_fails_for_invalid_message() {
+        let _setup = SetupAriesMocks::init();
+
+        assert_eq!(VcxErrorKind::InvalidJson, receive_message(b"not a json").unwrap_err().kind());
+
+        let payload = json!({"message": "not a json", "recipient_verkey": "GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL"}).to_string();
+        assert_eq!(VcxErrorKind::InvalidJson, receive_message(payload.as_bytes()).unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_connection_uses_inbound_endpoint() {
+        let _setup = SetupAriesMocks::init();
+
+        settings::set_config_value(settings::CONFIG_INBOUND_ENDPOINT, "https://issuer.example.com/inbound");
+
+        let connection_handle = connection::create_connection("faber").unwrap();
+        let details = connection::connect(connection_handle).unwrap().unwrap();
+        let details: ::serde_json::Value = ::serde_json::from_str(&details).unwrap();
+
+        assert_eq!("https://issuer.example.com/inbound", details["serviceEndpoint"]);
+        assert_eq!(json!([]), details["routingKeys"]);
+    }
+}
@@ -1,7 +1,12 @@
 use serde_json;
 
 use aries::handlers::issuance::issuer::issuer::Issuer;
+use aries::messages::a2a::A2AMessage;
+use dispatcher::ObjectType;
 use error::prelude::*;
+use events::{self, Observable};
+use history::History;
+use serialization;
 use utils::error;
 use utils::object_cache::ObjectCache;
 
@@ -12,10 +17,29 @@ lazy_static! {
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(tag = "version", content = "data")]
 enum IssuerCredentials {
-    #[serde(rename = "2.0")]
+    #[serde(rename = "4.0")]
     V3(Issuer),
 }
 
+impl Observable for Issuer {
+    fn source_id(&self) -> String {
+        self.get_source_id().unwrap_or_default()
+    }
+
+    fn thread_id(&self) -> Option<String> {
+        Some(self.get_thread_id())
+    }
+
+    fn history(&self) -> &History {
+        self.get_history()
+    }
+
+    fn serialize(&self) -> VcxResult<String> {
+        serde_json::to_string(&IssuerCredentials::V3(self.clone()))
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidState, format!("cannot serialize IssuerCredential credentialect: {:?}", err)))
+    }
+}
+
 pub fn issuer_credential_create(cred_def_handle: u32,
                                 source_id: String,
                                 issuer_did: String,
@@ -26,11 +50,26 @@ pub fn issuer_credential_create(cred_def_handle: u32,
            cred_def_handle, source_id, issuer_did, credential_name, secret!(&credential_data), price);
 
     let issuer = Issuer::create(cred_def_handle, &credential_data, &source_id)?;
-    ISSUER_CREDENTIAL_MAP.add(issuer)
+    events::add(&ISSUER_CREDENTIAL_MAP, ObjectType::IssuerCredential, issuer)
+}
+
+/**
+Creates issuer for Credential Proposal the holder sent without receiving an offer first.
+The proposal has to refer to the given credential definition.
+ */
+pub fn issuer_credential_create_from_proposal(source_id: &str, cred_def_handle: u32, connection_handle: u32, proposal: &str) -> VcxResult<u32> {
+    trace!("issuer_credential_create_from_proposal >>> source_id: {}, cred_def_handle: {}, connection_handle: {}, proposal: {}",
+           source_id, cred_def_handle, connection_handle, secret!(&proposal));
+
+    let proposal: A2AMessage = serde_json::from_str(proposal)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize Credential Proposal: {:?}", err)))?;
+
+    let issuer = Issuer::create_from_proposal(cred_def_handle, proposal, connection_handle, source_id)?;
+    events::add(&ISSUER_CREDENTIAL_MAP, ObjectType::IssuerCredential, issuer)
 }
 
 pub fn update_state(handle: u32, message: Option<String>, connection_handle: Option<u32>) -> VcxResult<u32> {
-    ISSUER_CREDENTIAL_MAP.get_mut(handle, |credential| {
+    events::update(&ISSUER_CREDENTIAL_MAP, ObjectType::IssuerCredential, handle, |credential| {
         credential.update_status(message.clone(), connection_handle)?;
         credential.get_state()
     })
@@ -63,13 +102,12 @@ pub fn is_valid_handle(handle: u32) -> bool {
 
 pub fn to_string(handle: u32) -> VcxResult<String> {
     ISSUER_CREDENTIAL_MAP.get(handle, |credential| {
-        serde_json::to_string(&IssuerCredentials::V3(credential.clone()))
-            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidState, format!("cannot serialize IssuerCredential credentialect: {:?}", err)))
+        credential.serialize()
     })
 }
 
 pub fn from_string(credential_data: &str) -> VcxResult<u32> {
-    let issuer_credential: IssuerCredentials = serde_json::from_str(credential_data)
+    let issuer_credential: IssuerCredentials = serde_json::from_value(serialization::upgrade(ObjectType::IssuerCredential, credential_data)?)
         .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize IssuerCredential: {:?}", err)))?;
 
     match issuer_credential {
@@ -77,14 +115,28 @@ pub fn from_string(credential_data: &str) -> VcxResult<u32> {
     }
 }
 
+/**
+Returns thread ids of all issuer credentials, they are used to route received messages.
+ */
+pub fn get_thread_ids() -> VcxResult<Vec<(u32, String)>> {
+    ISSUER_CREDENTIAL_MAP.get_all(|credential| credential.get_thread_id())
+}
+
+/**
+Returns the connection the object exchanges messages over, if it is already known.
+ */
+pub fn get_connection_handle(handle: u32) -> VcxResult<Option<u32>> {
+    ISSUER_CREDENTIAL_MAP.get(handle, |credential| Ok(credential.get_connection_handle()))
+}
+
 pub fn generate_credential_offer_msg(handle: u32) -> VcxResult<(String, String)> {
-    ISSUER_CREDENTIAL_MAP.get_mut(handle, |_| {
+    events::update(&ISSUER_CREDENTIAL_MAP, ObjectType::IssuerCredential, handle, |_| {
         Err(VcxError::from_msg(VcxErrorKind::ActionNotSupported, "Not implemented yet"))
     })
 }
 
 pub fn send_credential_offer(handle: u32, connection_handle: u32, comment: Option<String>) -> VcxResult<u32> {
-    ISSUER_CREDENTIAL_MAP.get_mut(handle, |credential| {
+    events::update(&ISSUER_CREDENTIAL_MAP, ObjectType::IssuerCredential, handle, |credential| {
         credential.send_credential_offer(connection_handle, comment.clone())?;
         let new_credential = credential.clone();
         *credential = new_credential;
@@ -92,14 +144,35 @@ pub fn send_credential_offer(handle: u32, connection_handle: u32, comment: Optio
     })
 }
 
+pub fn get_credential_proposal(handle: u32) -> VcxResult<String> {
+    ISSUER_CREDENTIAL_MAP.get(handle, |credential| {
+        let proposal = credential.get_proposal()?;
+        Ok(json!(proposal).to_string())
+    })
+}
+
+pub fn send_counter_offer(handle: u32, connection_handle: u32, comment: Option<String>) -> VcxResult<u32> {
+    events::update(&ISSUER_CREDENTIAL_MAP, ObjectType::IssuerCredential, handle, |credential| {
+        credential.send_counter_offer(connection_handle, comment.clone())?;
+        Ok(error::SUCCESS.code_num)
+    })
+}
+
+pub fn decline_credential_proposal(handle: u32, connection_handle: u32, reason: &str) -> VcxResult<u32> {
+    events::update(&ISSUER_CREDENTIAL_MAP, ObjectType::IssuerCredential, handle, |credential| {
+        credential.decline_proposal(connection_handle, reason)?;
+        Ok(error::SUCCESS.code_num)
+    })
+}
+
 pub fn generate_credential_msg(handle: u32, _my_pw_did: &str) -> VcxResult<String> {
-    ISSUER_CREDENTIAL_MAP.get_mut(handle, |_| {
+    events::update(&ISSUER_CREDENTIAL_MAP, ObjectType::IssuerCredential, handle, |_| {
         Err(VcxError::from_msg(VcxErrorKind::ActionNotSupported, "Not implemented yet")) // TODO: implement
     })
 }
 
 pub fn send_credential(handle: u32, connection_handle: u32) -> VcxResult<u32> {
-    ISSUER_CREDENTIAL_MAP.get_mut(handle, |credential| {
+    events::update(&ISSUER_CREDENTIAL_MAP, ObjectType::IssuerCredential, handle, |credential| {
         credential.send_credential(connection_handle)?;
         Ok(error::SUCCESS.code_num)
     })
@@ -107,13 +180,13 @@ pub fn send_credential(handle: u32, connection_handle: u32) -> VcxResult<u32> {
 
 pub fn revoke_credential(handle: u32) -> VcxResult<()> {
     trace!("revoke_credential >>> handle: {}", handle);
-    ISSUER_CREDENTIAL_MAP.get_mut(handle, |credential| {
+    events::update(&ISSUER_CREDENTIAL_MAP, ObjectType::IssuerCredential, handle, |credential| {
         credential.revoke_credential(true)
     })
 }
 
 pub fn revoke_credential_local(handle: u32) -> VcxResult<()> {
-    ISSUER_CREDENTIAL_MAP.get_mut(handle, |credential| {
+    events::update(&ISSUER_CREDENTIAL_MAP, ObjectType::IssuerCredential, handle, |credential| {
         credential.revoke_credential(false)
     })
 }
@@ -138,6 +211,28 @@ pub fn get_source_id(handle: u32) -> VcxResult<String> {
     })
 }
 
+pub fn get_history(handle: u32) -> VcxResult<History> {
+    ISSUER_CREDENTIAL_MAP.get(handle, |credential| {
+        Ok(credential.get_history().clone())
+    })
+}
+
+/**
+Returns handles of issuer credentials whose offers expired without an answer of the holder.
+ */
+pub fn get_expired() -> VcxResult<Vec<u32>> {
+    Ok(ISSUER_CREDENTIAL_MAP.get_all(|credential| credential.is_expired())?
+        .into_iter()
+        .filter_map(|(handle, expired)| if expired { Some(handle) } else { None })
+        .collect())
+}
+
+pub fn expire(handle: u32) -> VcxResult<bool> {
+    events::update(&ISSUER_CREDENTIAL_MAP, ObjectType::IssuerCredential, handle, |credential| {
+        credential.expire()
+    })
+}
+
 #[cfg(test)]
 pub mod tests {
     use ::{issuer_credential, settings};
@@ -157,6 +252,7 @@ pub mod tests {
     use utils::httpclient::HttpClientMockResponse;
     use utils::mockdata::mockdata_connection::ARIES_CONNECTION_ACK;
     use utils::mockdata::mockdata_credex::ARIES_CREDENTIAL_REQUEST;
+    use aries::messages::issuance::credential_proposal::tests::_credential_proposal;
 
     use super::*;
 
@@ -179,7 +275,7 @@ pub mod tests {
         libindy_create_and_store_credential_def(&issuer_did, SCHEMAS_JSON, tag, None, config).unwrap();
     }
 
-    fn _issuer_credential_create() -> u32 {
+    pub fn _issuer_credential_create() -> u32 {
         issuer_credential_create(create_cred_def_fake(),
                                  "1".to_string(),
                                  "8XFh8yBzrpJQmNyZzgoTqB".to_owned(),
@@ -314,6 +410,68 @@ pub mod tests {
         assert_eq!(get_state(handle_cred).unwrap(), VcxStateType::VcxStateRequestReceived as u32);
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_credential_proposal_can_be_answered_with_counter_offer() {
+        let _setup = SetupStrictAriesMocks::init();
+
+        let handle_conn = build_test_connection_inviter_requested();
+        let handle_cred = _issuer_credential_create();
+        send_credential_offer(handle_cred, handle_conn, None).unwrap();
+
+        let proposal = json!(_credential_proposal().to_a2a_message()).to_string();
+        issuer_credential::update_state(handle_cred, Some(proposal), Some(handle_conn)).unwrap();
+        assert_eq!(get_state(handle_cred).unwrap(), VcxStateType::VcxStateProposalReceived as u32);
+
+        let proposal: serde_json::Value = serde_json::from_str(&get_credential_proposal(handle_cred).unwrap()).unwrap();
+        assert_eq!(json!(_credential_proposal().credential_proposal), proposal["credential_proposal"]);
+
+        assert_eq!(send_counter_offer(handle_cred, handle_conn, None).unwrap(), error::SUCCESS.code_num);
+        assert_eq!(get_state(handle_cred).unwrap(), VcxStateType::VcxStateOfferSent as u32);
+        assert_eq!(VcxErrorKind::NotReady, get_credential_proposal(handle_cred).unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_issuer_credential_create_from_proposal() {
+        let _setup = SetupStrictAriesMocks::init();
+
+        let handle_conn = build_test_connection_inviter_requested();
+        let cred_def_handle = create_cred_def_fake();
+
+        let mut proposal = _credential_proposal();
+        proposal.cred_def_id = ::credential_def::get_cred_def_id(cred_def_handle).unwrap();
+        let proposal = json!(proposal.to_a2a_message()).to_string();
+
+        let handle_cred = issuer_credential_create_from_proposal("1", cred_def_handle, handle_conn, &proposal).unwrap();
+        assert_eq!(get_state(handle_cred).unwrap(), VcxStateType::VcxStateProposalReceived as u32);
+
+        assert_eq!(send_counter_offer(handle_cred, handle_conn, None).unwrap(), error::SUCCESS.code_num);
+        assert_eq!(get_state(handle_cred).unwrap(), VcxStateType::VcxStateOfferSent as u32);
+
+        let other_proposal = json!(_credential_proposal().set_cred_def_id("other".to_string()).to_a2a_message()).to_string();
+        assert_eq!(VcxErrorKind::InvalidCredDefHandle, issuer_credential_create_from_proposal("1", cred_def_handle, handle_conn, &other_proposal).unwrap_err().kind());
+
+        let offer = json!(::aries::messages::issuance::credential_offer::tests::_credential_offer().to_a2a_message()).to_string();
+        assert_eq!(VcxErrorKind::InvalidJson, issuer_credential_create_from_proposal("1", cred_def_handle, handle_conn, &offer).unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_credential_proposal_can_be_declined() {
+        let _setup = SetupStrictAriesMocks::init();
+
+        let handle_conn = build_test_connection_inviter_requested();
+        let handle_cred = _issuer_credential_create();
+        send_credential_offer(handle_cred, handle_conn, None).unwrap();
+
+        let proposal = json!(_credential_proposal().to_a2a_message()).to_string();
+        issuer_credential::update_state(handle_cred, Some(proposal), Some(handle_conn)).unwrap();
+
+        assert_eq!(decline_credential_proposal(handle_cred, handle_conn, "not issuing it").unwrap(), error::SUCCESS.code_num);
+        assert_eq!(get_state(handle_cred).unwrap(), VcxStateType::VcxStateNone as u32);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_update_state_with_bad_message() {
@@ -27,6 +27,10 @@ extern crate strum;
 #[macro_use]
 extern crate strum_macros;
 extern crate time;
+#[cfg(feature = "inbound_listener")]
+extern crate tiny_http;
+#[cfg(feature = "ws_transport")]
+extern crate tungstenite;
 extern crate url;
 extern crate uuid;
 
@@ -45,6 +49,19 @@ pub mod credential_def;
 pub mod error;
 pub mod credential;
 pub mod disclosed_proof;
+pub mod mediation;
+pub mod router;
+pub mod inbound;
+pub mod dispatcher;
+pub mod events;
+pub mod persistence;
+pub mod serialization;
+pub mod history;
+pub mod expiry;
+pub mod outbox;
+pub mod replay;
+pub mod acks;
+pub mod tracing;
 
 pub mod aries;
 mod proof_utils;
@@ -0,0 +1,203 @@
+use serde_json;
+
+use aries::handlers::mediation::client::MediatorClient;
+use aries::messages::a2a::A2AMessage;
+use connection;
+use dispatcher;
+use error::prelude::*;
+use inbound;
+use settings;
+
+/**
+Requests mediation over the given connection.
+Once granted, new pairwise keys are registered with the mediator and its endpoint and routing keys are put into our DIDDocs.
+The mediator is stored in `mediator` config value which can be passed to `vcx_init` to keep using it in the next session.
+ */
+pub fn request_mediation(connection_handle: u32) -> VcxResult<String> {
+    trace!("request_mediation >>> connection_handle: {}", connection_handle);
+
+    let pw_vk = connection::get_pw_verkey(connection_handle)?;
+    let did_doc = connection::get_their_did_doc(connection_handle)?;
+
+    let mediator = MediatorClient::request(&pw_vk, did_doc)?;
+
+    let mediator = serde_json::to_string(&mediator)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize mediator: {:?}", err)))?;
+
+    settings::set_config_value(settings::CONFIG_MEDIATOR, &mediator);
+
+    Ok(mediator)
+}
+
+pub fn is_mediation_active() -> bool {
+    settings::get_config_value(settings::CONFIG_MEDIATOR).is_ok()
+}
+
+pub fn get_mediator() -> VcxResult<MediatorClient> {
+    let mediator = settings::get_config_value(settings::CONFIG_MEDIATOR)
+        .map_err(|_| VcxError::from_msg(VcxErrorKind::NotReady, "Mediator is not set"))?;
+
+    serde_json::from_str(&mediator)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidConfiguration, format!("Cannot deserialize mediator: {:?}", err)))
+}
+
+pub fn get_message_count() -> VcxResult<u32> {
+    get_mediator()?
+        .status()
+        .map(|status| status.message_count)
+}
+
+/// Message picked up from the mediator, `connection_handle` is not set if no connection uses the recipient key.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct ReceivedMessage {
+    pub recipient_verkey: String,
+    pub connection_handle: Option<u32>,
+    pub message: A2AMessage,
+}
+
+/**
+Picks up queued messages from the mediator and updates the connections and objects they belong to, as `update_all` does.
+Messages go through the replay filter of their connection, the mediator is asked to remove only the processed ones.
+Returns JSON array of picked up messages: [{"recipient_verkey": "...", "connection_handle": 1, "message": {...}}]
+ */
+pub fn pickup_messages(batch_size: u32) -> VcxResult<String> {
+    trace!("pickup_messages >>> batch_size: {}", batch_size);
+
+    let mediator = get_mediator()?;
+    let picked_up = mediator.pickup(batch_size)?;
+
+    let mut received = Vec::new();
+    let mut connection_handles = Vec::new();
+
+    for message in picked_up.iter() {
+        if let Err(err) = inbound::store_message(&message.recipient_verkey, &message.id, message.message.clone()) {
+            warn!("pickup_messages >>> cannot store message {}, it is left at the mediator: {}", message.id, err);
+        }
+
+        let connection_handle = connection::find_by_pw_verkey(&message.recipient_verkey)?;
+
+        match connection_handle {
+            Some(handle) if !connection_handles.contains(&handle) => connection_handles.push(handle),
+            Some(_) => {}
+            None => warn!("pickup_messages >>> no connection uses recipient key {}", message.recipient_verkey)
+        }
+
+        received.push(ReceivedMessage {
+            recipient_verkey: message.recipient_verkey.clone(),
+            connection_handle,
+            message: message.message.clone(),
+        });
+    }
+
+    dispatcher::update_connections(&connection_handles)?;
+
+    let processed: Vec<String> = picked_up.iter()
+        .filter(|message| !inbound::contains_message(&message.recipient_verkey, &message.id))
+        .map(|message| message.id.clone())
+        .collect();
+
+    if !processed.is_empty() {
+        mediator.acknowledge(processed)?;
+    }
+
+    serde_json::to_string(&received)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize picked up messages: {:?}", err)))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::a2a::MessageId;
+    use aries::messages::ack::tests::_ack;
+    use aries::messages::mediation::keylist_update::KeylistAction;
+    use aries::messages::mediation::keylist_update_response::{KeylistUpdateResponse, KeylistUpdateResult};
+    use aries::messages::mediation::mediate_grant::MediateGrant;
+    use aries::messages::pickup::batch::Batch;
+    use connection::tests::build_test_connection_inviter_requested;
+    use utils::devsetup::SetupAriesMocks;
+    use utils::httpclient::AgencyMockDecrypted;
+
+    use super::*;
+
+    fn _grant() -> A2AMessage {
+        MediateGrant::create()
+            .set_endpoint("https://mediator.example.com".to_string())
+            .set_routing_keys(vec!["Hezce2UWMZ3wUhVkh2LfKSs8nDzWwzs2Win7EzNN3YaR".to_string()])
+            .set_thread_id(&MessageId::id().0)
+            .to_a2a_message()
+    }
+
+    fn _keylist_update_response() -> A2AMessage {
+        KeylistUpdateResponse::create()
+            .add_updated("GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL", KeylistAction::Add, KeylistUpdateResult::Success)
+            .to_a2a_message()
+    }
+
+    pub fn set_test_mediator() {
+        let connection_handle = build_test_connection_inviter_requested();
+        AgencyMockDecrypted::set_next_decrypted_message(&json!(_grant()).to_string());
+        request_mediation(connection_handle).unwrap();
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_request_mediation_works() {
+        let _setup = SetupAriesMocks::init();
+
+        assert!(!is_mediation_active());
+        assert_eq!(VcxErrorKind::NotReady, pickup_messages(10).unwrap_err().kind());
+
+        set_test_mediator();
+
+        assert!(is_mediation_active());
+        let mediator = get_mediator().unwrap();
+        assert_eq!("https://mediator.example.com", mediator.endpoint());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_request_mediation_fails_for_connection_without_counterparty() {
+        let _setup = SetupAriesMocks::init();
+
+        let connection_handle = connection::create_connection("mediator").unwrap();
+        assert_eq!(VcxErrorKind::NotReady, request_mediation(connection_handle).unwrap_err().kind());
+        assert!(!is_mediation_active());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_mediated_connection_uses_mediator_routing() {
+        let _setup = SetupAriesMocks::init();
+
+        set_test_mediator();
+
+        AgencyMockDecrypted::set_next_decrypted_message(&json!(_keylist_update_response()).to_string());
+
+        let connection_handle = connection::create_connection("faber").unwrap();
+        let details = connection::connect(connection_handle).unwrap().unwrap();
+        let details: serde_json::Value = serde_json::from_str(&details).unwrap();
+
+        assert_eq!("https://mediator.example.com", details["serviceEndpoint"]);
+        assert_eq!(json!(["Hezce2UWMZ3wUhVkh2LfKSs8nDzWwzs2Win7EzNN3YaR"]), details["routingKeys"]);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_pickup_messages_works() {
+        let _setup = SetupAriesMocks::init();
+
+        set_test_mediator();
+
+        // the inbox is shared by tests running in parallel
+        let recipient_key = ::utils::uuid::uuid();
+
+        let packed = json!({"message": json!(_ack().to_a2a_message()).to_string(), "recipient_verkey": recipient_key});
+        AgencyMockDecrypted::set_next_decrypted_message(&json!(Batch::create().add_message("1", packed).to_a2a_message()).to_string());
+
+        let messages: Vec<ReceivedMessage> = serde_json::from_str(&pickup_messages(10).unwrap()).unwrap();
+        assert_eq!(vec![ReceivedMessage { recipient_verkey: recipient_key.clone(), connection_handle: None, message: _ack().to_a2a_message() }], messages);
+
+        // not processed by any connection, so it is kept in the inbox and not acknowledged to the mediator
+        assert_eq!(_ack().to_a2a_message(), inbound::get_message(&recipient_key, "1").unwrap());
+        assert!(!AgencyMockDecrypted::has_decrypted_mock_messages());
+    }
+}
@@ -323,10 +323,10 @@ impl Message {
         let a2a_message = EncryptionEnvelope::open(self.payload()?)?;
 
         let (kind, msg) = match a2a_message.clone() {
-            A2AMessage::PresentationRequest(_) => (PayloadKinds::ProofRequest, json!(&a2a_message).to_string()),
-            A2AMessage::CredentialOffer(_) => (PayloadKinds::CredOffer, json!(&a2a_message).to_string()),
-            A2AMessage::Credential(_) => (PayloadKinds::Cred, json!(&a2a_message).to_string()),
-            A2AMessage::Presentation(_) => (PayloadKinds::Proof, json!(&a2a_message).to_string()),
+            A2AMessage::PresentationRequest(_) | A2AMessage::PresentationRequestV2(_) => (PayloadKinds::ProofRequest, json!(&a2a_message).to_string()),
+            A2AMessage::CredentialOffer(_) | A2AMessage::CredentialOfferV2(_) => (PayloadKinds::CredOffer, json!(&a2a_message).to_string()),
+            A2AMessage::Credential(_) | A2AMessage::CredentialV2(_) => (PayloadKinds::Cred, json!(&a2a_message).to_string()),
+            A2AMessage::Presentation(_) | A2AMessage::PresentationV2(_) => (PayloadKinds::Proof, json!(&a2a_message).to_string()),
             msg => {
                 let msg = json!(&msg).to_string();
                 (PayloadKinds::Other(String::from("aries")), msg)
@@ -8,6 +8,7 @@ use settings;
 
 pub const MESSAGE_VERSION_V1: &str = "1.0";
 pub const DID: &str = "did:sov:123456789abcdefghi1234";
+pub const DIDCOMM_ORG_PREFIX: &str = "https://didcomm.org";
 
 #[derive(Debug, Deserialize, Serialize, Clone, PartialEq)]
 #[serde(untagged)]
@@ -112,19 +113,22 @@ impl ::std::string::ToString for MessageFamilies {
 }
 
 
+/**
+Parses `@type` either in `<did>;spec/<family>/<version>/<type>` form or in `https://didcomm.org/<family>/<version>/<type>` form,
+the latter returns `DIDCOMM_ORG_PREFIX` in place of the DID.
+ */
 pub fn parse_message_type(message_type: &str) -> VcxResult<(String, String, String, String)> {
     lazy_static! {
         static ref RE: Regex = Regex::new(r"(?x)
-            (?P<did>[\d\w:]*);
-            (?P<spec>.*)/
-            (?P<family>.*)/
-            (?P<version>.*)/
+            ^(?:(?P<did>[\d\w:]*);(?P<spec>.*)|(?P<prefix>https://didcomm\.org))/
+            (?P<family>[^/]*)/
+            (?P<version>[^/]*)/
             (?P<type>.*)").unwrap();
     }
 
     RE.captures(message_type)
         .and_then(|cap| {
-            let did = cap.name("did").as_ref().map(Match::as_str);
+            let did = cap.name("did").or(cap.name("prefix")).as_ref().map(Match::as_str);
             let family = cap.name("family").as_ref().map(Match::as_str);
             let version = cap.name("version").as_ref().map(Match::as_str);
             let type_ = cap.name("type").as_ref().map(Match::as_str);
@@ -137,6 +141,15 @@ pub fn parse_message_type(message_type: &str) -> VcxResult<(String, String, Stri
         }).ok_or(VcxError::from_msg(VcxErrorKind::InvalidOption, "Cannot parse @type"))
 }
 
+/// Builds `@type` in the form it was parsed from (see `parse_message_type`).
+pub fn format_message_type(did: &str, family: &str, version: &str, type_: &str) -> String {
+    if did == DIDCOMM_ORG_PREFIX {
+        format!("{}/{}/{}/{}", did, family, version, type_)
+    } else {
+        format!("{};spec/{}/{}/{}", did, family, version, type_)
+    }
+}
+
 impl<'de> Deserialize<'de> for MessageTypeV2 {
     fn deserialize<D>(deserializer: D) -> Result<Self, D::Error> where D: Deserializer<'de> {
         let value = Value::deserialize(deserializer).map_err(de::Error::custom)?;
@@ -158,7 +171,26 @@ impl<'de> Deserialize<'de> for MessageTypeV2 {
 
 impl Serialize for MessageTypeV2 {
     fn serialize<S>(&self, serializer: S) -> Result<S::Ok, S::Error> where S: Serializer {
-        let value = Value::String(format!("{};spec/{}/{}/{}", self.did, self.family.to_string(), self.version, self.type_));
+        let value = Value::String(format_message_type(&self.did, &self.family.to_string(), &self.version, &self.type_));
         value.serialize(serializer)
     }
-}
\ No newline at end of file
+}
+#[cfg(test)]
+pub mod tests {
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_parse_message_type_with_did_and_didcomm_org_prefix() {
+        let (did, family, version, type_) = parse_message_type("did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/out-of-band/1.0/invitation").unwrap();
+        assert_eq!(("did:sov:BzCbsNYhMrjHiqZDTUASHg", "out-of-band", "1.0", "invitation"), (did.as_str(), family.as_str(), version.as_str(), type_.as_str()));
+        assert_eq!("did:sov:BzCbsNYhMrjHiqZDTUASHg;spec/out-of-band/1.0/invitation", format_message_type(&did, &family, &version, &type_));
+
+        let (did, family, version, type_) = parse_message_type("https://didcomm.org/out-of-band/1.0/invitation").unwrap();
+        assert_eq!((DIDCOMM_ORG_PREFIX, "out-of-band", "1.0", "invitation"), (did.as_str(), family.as_str(), version.as_str(), type_.as_str()));
+        assert_eq!("https://didcomm.org/out-of-band/1.0/invitation", format_message_type(&did, &family, &version, &type_));
+
+        assert!(parse_message_type("https://example.org/out-of-band/1.0/invitation").is_err());
+        assert!(parse_message_type("invitation").is_err());
+    }
+}
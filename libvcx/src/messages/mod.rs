@@ -5,6 +5,7 @@ use serde::{de, Deserialize, Deserializer, ser, Serialize, Serializer};
 use serde_json::Value;
 
 use error::prelude::*;
+use history::History;
 use settings;
 use settings::ProtocolTypes;
 use utils::httpclient::AgencyMockDecrypted;
@@ -745,6 +746,8 @@ pub enum SerializableObjectWithState<T, P> {
     V2 { data: T, state: P },
     #[serde(rename = "3.0")]
     V3 { data: T, state: P, source_id: String },
+    #[serde(rename = "4.0")]
+    V4 { data: T, state: P, source_id: String, history: History },
 }
 
 pub fn create_keys() -> CreateKeyBuilder { CreateKeyBuilder::create() }
@@ -22,6 +22,11 @@ impl Thread {
         self
     }
 
+    pub fn set_pthid(mut self, pthid: String) -> Thread {
+        self.pthid = Some(pthid);
+        self
+    }
+
     pub fn increment_receiver(&mut self, did: &str) {
         self.received_orders.entry(did.to_string())
             .and_modify(|e| *e += 1)
@@ -1,6 +1,8 @@
 extern crate openssl;
 extern crate rust_base58;
 
+use std::collections::HashMap;
+
 use url::Url;
 
 use error::prelude::*;
@@ -66,6 +68,11 @@ pub fn validate_actors(actors: &str) -> VcxResult<Vec<Actors>> {
         .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidOption, format!("Invalid actors: {:?}", err)))
 }
 
+pub fn validate_protocol_timeouts(timeouts: &str) -> VcxResult<HashMap<String, u64>> {
+    ::serde_json::from_str(&timeouts)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidOption, format!("Invalid protocol timeouts: {:?}", err)))
+}
+
 pub fn validate_phone_number(p_num: &str) -> VcxResult<String> {
     Ok(String::from(p_num))
 }
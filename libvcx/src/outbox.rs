@@ -0,0 +1,525 @@
+use std::cmp;
+
+use base64;
+use chrono::{DateTime, Duration, Utc};
+use indy::SearchHandle;
+use serde_json;
+
+use aries::messages::a2a::A2AMessage;
+use aries::utils::encryption_envelope::EncryptionEnvelope;
+use error::prelude::*;
+use replay;
+use settings;
+use utils::libindy::wallet;
+use utils::transport;
+
+/*
+    Opt-in outbox of messages sent to connection counterparties.
+
+    When `outbox` is enabled in the config, every sent message is recorded into a `vcx_outbox` wallet record
+    with id equal to `@id` of the message before it is sent, so a message whose `@id` was sent already
+    is not sent again. A message which cannot be delivered does not fail the sending, it is reported as queued
+    and its delivery is retried with exponential backoff every time the outbox is flushed (explicitly or by `update_all`).
+
+    After `MAX_ATTEMPTS` failed deliveries the message is marked as failed and kept until it is cancelled.
+    Records of delivered messages are kept for `DELIVERED_RETENTION_SECS` to recognize repeated sends.
+*/
+
+pub const OUTBOX_RECORD_TYPE: &str = "vcx_outbox";
+
+const MAX_ATTEMPTS: u32 = 10;
+const BASE_DELAY_SECS: i64 = 5;
+const MAX_DELAY_SECS: i64 = 3600;
+const DELIVERED_RETENTION_SECS: i64 = 7 * 24 * 3600;
+const SEARCH_BATCH_SIZE: usize = 100;
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum OutboxStatus {
+    Pending,
+    Failed,
+    Delivered,
+}
+
+/// Outcome of sending a message.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Delivery {
+    Delivered,
+    /// The message cannot be delivered now, it is queued in outbox for retries.
+    Queued,
+    /// The message with the same `@id` was sent already, it is not sent again.
+    Duplicate,
+}
+
+/// Packed message waiting for delivery.
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub struct OutboxMessage {
+    /// `@id` of the message.
+    pub id: String,
+    pub endpoint: String,
+    /// Base64 encoded packed message.
+    pub payload: String,
+    pub status: OutboxStatus,
+    pub attempts: u32,
+    /// RFC 3339 time when the message was queued.
+    pub created: String,
+    /// RFC 3339 time of the next delivery attempt.
+    pub next_attempt: String,
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub last_error: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
+struct OutboxRecord {
+    value: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchRecords {
+    records: Option<Vec<OutboxRecord>>,
+}
+
+impl OutboxMessage {
+    fn new(id: &str, envelope: &EncryptionEnvelope, endpoint: &str) -> OutboxMessage {
+        let now = Utc::now().to_rfc3339();
+
+        OutboxMessage {
+            id: id.to_string(),
+            endpoint: endpoint.to_string(),
+            payload: base64::encode(&envelope.0),
+            status: OutboxStatus::Pending,
+            attempts: 0,
+            created: now.clone(),
+            next_attempt: now,
+            last_error: None,
+        }
+    }
+
+    fn is_due(&self) -> bool {
+        self.status == OutboxStatus::Pending &&
+            DateTime::parse_from_rfc3339(&self.next_attempt)
+                .map(|next_attempt| next_attempt.with_timezone(&Utc) <= Utc::now())
+                .unwrap_or(true)
+    }
+
+    fn deliver(&self) -> VcxResult<()> {
+        let payload = base64::decode(&self.payload)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidState, format!("Cannot decode queued message {}: {:?}", self.id, err)))?;
+
+        transport::send(&payload, &self.endpoint)?;
+        Ok(())
+    }
+
+    fn is_expired(&self) -> bool {
+        self.status == OutboxStatus::Delivered &&
+            DateTime::parse_from_rfc3339(&self.created)
+                .map(|created| created.with_timezone(&Utc) + Duration::seconds(DELIVERED_RETENTION_SECS) <= Utc::now())
+                .unwrap_or(true)
+    }
+
+    fn delivered(&mut self) {
+        self.status = OutboxStatus::Delivered;
+        self.last_error = None;
+    }
+
+    fn failed_attempt(&mut self, err: &VcxError) {
+        self.attempts += 1;
+        self.last_error = Some(err.to_string());
+
+        if self.attempts >= MAX_ATTEMPTS {
+            self.status = OutboxStatus::Failed;
+        } else {
+            self.next_attempt = (Utc::now() + backoff(self.attempts)).to_rfc3339();
+        }
+    }
+
+    fn tags(&self) -> String {
+        json!({"status": self.status, "endpoint": self.endpoint}).to_string()
+    }
+}
+
+/// Delay before the next delivery attempt, doubled with every failed attempt.
+pub fn backoff(attempts: u32) -> Duration {
+    let exponent = cmp::min(attempts.saturating_sub(1), 16);
+    Duration::seconds(cmp::min(BASE_DELAY_SECS << exponent, MAX_DELAY_SECS))
+}
+
+pub fn is_enabled() -> bool {
+    settings::get_config_value(settings::CONFIG_OUTBOX)
+        .map(|value| value == "true")
+        .unwrap_or(false)
+}
+
+/**
+Delivers the packed message to the endpoint.
+With the outbox enabled a message which cannot be delivered is queued for retries instead of failing.
+ */
+pub fn send(message: &A2AMessage, envelope: EncryptionEnvelope, endpoint: &str) -> VcxResult<Delivery> {
+    trace!("outbox::send >>> endpoint: {}", endpoint);
+
+    let id = match replay::message_id(message) {
+        Some(ref id) if is_enabled() => id.clone(),
+        _ => {
+            transport::send(&envelope.0, endpoint)?;
+            return Ok(Delivery::Delivered);
+        }
+    };
+
+    let mut outgoing = OutboxMessage::new(&id, &envelope, endpoint);
+
+    // the message is not retried by a concurrent flush until its first delivery attempt is over
+    outgoing.next_attempt = (Utc::now() + backoff(1)).to_rfc3339();
+
+    match wallet::add_record(OUTBOX_RECORD_TYPE, &id, &_to_string(&outgoing)?, Some(&outgoing.tags())) {
+        Err(ref err) if err.kind() == VcxErrorKind::DuplicationWalletRecord => {
+            debug!("Message {} was already sent", id);
+            return Ok(Delivery::Duplicate);
+        }
+        result => result?
+    }
+
+    let delivery = match transport::send(&envelope.0, endpoint) {
+        Ok(_) => {
+            outgoing.delivered();
+            Delivery::Delivered
+        }
+        Err(err) => {
+            warn!("Cannot deliver message {} to {}, it is queued in outbox: {}", id, endpoint, err);
+            outgoing.failed_attempt(&err);
+            Delivery::Queued
+        }
+    };
+
+    _update(&outgoing)?;
+
+    Ok(delivery)
+}
+
+/**
+Retries delivery of the queued messages which are due, from the oldest one.
+Returns ids of the delivered messages.
+ */
+pub fn flush() -> VcxResult<Vec<String>> {
+    trace!("outbox::flush >>>");
+
+    let mut delivered = Vec::new();
+
+    for mut message in _search(&json!({"status": OutboxStatus::Pending}).to_string())? {
+        if !message.is_due() { continue; }
+
+        match message.deliver() {
+            Ok(()) => {
+                message.delivered();
+                delivered.push(message.id.clone());
+            }
+            Err(err) => {
+                warn!("Cannot deliver queued message {} to {}: {}", message.id, message.endpoint, err);
+                message.failed_attempt(&err);
+            }
+        }
+
+        _update(&message)?;
+    }
+
+    for message in _search(&json!({"status": OutboxStatus::Delivered}).to_string())? {
+        if message.is_expired() {
+            wallet::delete_record(OUTBOX_RECORD_TYPE, &message.id)?;
+        }
+    }
+
+    Ok(delivered)
+}
+
+/// Returns JSON array of the queued messages, both pending and failed.
+pub fn get_messages() -> VcxResult<String> {
+    trace!("outbox::get_messages >>>");
+
+    serde_json::to_string(&_search(&json!({"status": {"$neq": OutboxStatus::Delivered}}).to_string())?)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidState, format!("Cannot serialize outbox messages: {:?}", err)))
+}
+
+/// Removes the message from the outbox, it will not be delivered anymore.
+pub fn cancel(id: &str) -> VcxResult<()> {
+    trace!("outbox::cancel >>> id: {}", id);
+
+    wallet::delete_record(OUTBOX_RECORD_TYPE, id)
+}
+
+fn _update(message: &OutboxMessage) -> VcxResult<()> {
+    wallet::update_record_value(OUTBOX_RECORD_TYPE, &message.id, &_to_string(message)?)?;
+    wallet::update_record_tags(OUTBOX_RECORD_TYPE, &message.id, &message.tags())
+}
+
+fn _search(query: &str) -> VcxResult<Vec<OutboxMessage>> {
+    let search_handle = wallet::open_search(OUTBOX_RECORD_TYPE, query, &json!({"retrieveRecords": true}).to_string())?;
+
+    let result = _fetch_all(search_handle);
+
+    wallet::close_search(search_handle)?;
+
+    let mut messages = result?.into_iter()
+        .map(|record| serde_json::from_str::<OutboxMessage>(&record.value)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize outbox message: {:?}", err))))
+        .collect::<VcxResult<Vec<OutboxMessage>>>()?;
+
+    messages.sort_by(|a, b| a.created.cmp(&b.created));
+
+    Ok(messages)
+}
+
+fn _fetch_all(search_handle: SearchHandle) -> VcxResult<Vec<OutboxRecord>> {
+    let mut records = Vec::new();
+
+    loop {
+        let batch = wallet::fetch_next_records(search_handle, SEARCH_BATCH_SIZE)?;
+
+        let batch: SearchRecords = serde_json::from_str(&batch)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize outbox records: {:?}", err)))?;
+
+        match batch.records {
+            Some(ref batch) if batch.is_empty() => break,
+            Some(batch) => records.extend(batch),
+            None => break
+        }
+    }
+
+    Ok(records)
+}
+
+fn _to_string(message: &OutboxMessage) -> VcxResult<String> {
+    serde_json::to_string(message)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidState, format!("Cannot serialize outbox message: {:?}", err)))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::collections::HashSet;
+    use std::sync::{Arc, Mutex};
+
+    use aries::messages::basic_message::message::BasicMessage;
+    use utils::devsetup::{SetupAriesMocks, SetupLibraryWallet};
+    use utils::transport::Transport;
+
+    use super::*;
+
+    lazy_static! {
+        static ref OFFLINE_ENDPOINTS: Mutex<HashSet<String>> = Mutex::new(HashSet::new());
+    }
+
+    /// Transport failing to deliver to the endpoints set offline.
+    struct OfflineTransport;
+
+    impl Transport for OfflineTransport {
+        fn schemes(&self) -> Vec<String> {
+            vec![String::from("vcx-outbox-test")]
+        }
+
+        fn send(&self, _message: &[u8], endpoint: &str) -> VcxResult<Vec<u8>> {
+            if OFFLINE_ENDPOINTS.lock().unwrap().contains(endpoint) {
+                return Err(VcxError::from_msg(VcxErrorKind::PostMessageFailed, format!("{} is offline", endpoint)));
+            }
+            Ok(Vec::new())
+        }
+    }
+
+    fn _set_offline(endpoint: &str, offline: bool) {
+        let mut endpoints = OFFLINE_ENDPOINTS.lock().unwrap();
+
+        if offline {
+            endpoints.insert(endpoint.to_string());
+        } else {
+            endpoints.remove(endpoint);
+        }
+    }
+
+    fn _enable_outbox() {
+        settings::set_config_value(settings::CONFIG_OUTBOX, "true");
+        transport::register_transport(Arc::new(OfflineTransport));
+    }
+
+    fn _send_offline(endpoint: &str) -> (A2AMessage, String) {
+        _set_offline(endpoint, true);
+
+        let message = BasicMessage::create()
+            .set_content(String::from("Hello"))
+            .to_a2a_message();
+        let id = replay::message_id(&message).unwrap();
+
+        assert_eq!(Delivery::Queued, send(&message, EncryptionEnvelope(vec![1, 2, 3]), endpoint).unwrap());
+
+        (message, id)
+    }
+
+    fn _queued_messages() -> Vec<OutboxMessage> {
+        serde_json::from_str(&get_messages().unwrap()).unwrap()
+    }
+
+    fn _make_due(message: &OutboxMessage) {
+        let mut message = message.clone();
+        message.next_attempt = Utc::now().to_rfc3339();
+        _update(&message).unwrap();
+    }
+
+    fn _outbox_message() -> OutboxMessage {
+        OutboxMessage::new("testid", &EncryptionEnvelope(vec![1, 2, 3]), "http://localhost:8080")
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_backoff_is_exponential_up_to_limit() {
+        assert_eq!(Duration::seconds(5), backoff(1));
+        assert_eq!(Duration::seconds(10), backoff(2));
+        assert_eq!(Duration::seconds(40), backoff(4));
+        assert_eq!(Duration::seconds(MAX_DELAY_SECS), backoff(MAX_ATTEMPTS));
+        assert_eq!(Duration::seconds(MAX_DELAY_SECS), backoff(u32::max_value()));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_failed_attempts_postpone_delivery() {
+        let mut message = _outbox_message();
+        assert!(message.is_due());
+
+        message.failed_attempt(&VcxError::from_msg(VcxErrorKind::PostMessageFailed, "offline"));
+        assert_eq!(1, message.attempts);
+        assert_eq!(OutboxStatus::Pending, message.status);
+        assert!(message.last_error.as_ref().unwrap().contains("offline"));
+        assert!(!message.is_due());
+
+        for _ in 1..MAX_ATTEMPTS {
+            message.failed_attempt(&VcxError::from(VcxErrorKind::PostMessageFailed));
+        }
+        assert_eq!(OutboxStatus::Failed, message.status);
+        assert!(!message.is_due());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_delivered_messages_expire_after_retention() {
+        let mut message = _outbox_message();
+        assert!(!message.is_expired());
+
+        message.delivered();
+        assert_eq!(OutboxStatus::Delivered, message.status);
+        assert!(!message.is_due());
+        assert!(!message.is_expired());
+
+        message.created = (Utc::now() - Duration::seconds(DELIVERED_RETENTION_SECS + 1)).to_rfc3339();
+        assert!(message.is_expired());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_outbox_message_serialization() {
+        let message = _outbox_message();
+
+        let serialized = _to_string(&message).unwrap();
+        assert_eq!(message, serde_json::from_str::<OutboxMessage>(&serialized).unwrap());
+        assert_eq!(json!({"status": "pending", "endpoint": "http://localhost:8080"}).to_string(), message.tags());
+        assert_eq!(vec![1, 2, 3], base64::decode(&message.payload).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_outbox_without_queued_messages() {
+        let _setup = SetupAriesMocks::init();
+        settings::set_config_value(settings::CONFIG_OUTBOX, "true");
+
+        assert!(is_enabled());
+        assert_eq!("[]", get_messages().unwrap());
+        assert_eq!(Vec::<String>::new(), flush().unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_queued_message_is_delivered_by_flush() {
+        let _setup = SetupLibraryWallet::init();
+        _enable_outbox();
+
+        let endpoint = "vcx-outbox-test://flush";
+        let (message, id) = _send_offline(endpoint);
+
+        let queued = _queued_messages();
+        assert_eq!(1, queued.len());
+        assert_eq!(id, queued[0].id);
+        assert_eq!(endpoint, queued[0].endpoint);
+        assert_eq!(OutboxStatus::Pending, queued[0].status);
+        assert_eq!(1, queued[0].attempts);
+
+        _set_offline(endpoint, false);
+        _make_due(&queued[0]);
+
+        assert_eq!(vec![id.clone()], flush().unwrap());
+        assert_eq!(Vec::<OutboxMessage>::new(), _queued_messages());
+        assert_eq!(Vec::<String>::new(), flush().unwrap());
+
+        assert_eq!(Delivery::Duplicate, send(&message, EncryptionEnvelope(vec![1, 2, 3]), endpoint).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_flush_removes_delivered_message_after_retention() {
+        let _setup = SetupLibraryWallet::init();
+        _enable_outbox();
+
+        let endpoint = "vcx-outbox-test://retention";
+        let (message, id) = _send_offline(endpoint);
+
+        let mut delivered = _queued_messages().remove(0);
+        delivered.delivered();
+        delivered.created = (Utc::now() - Duration::seconds(DELIVERED_RETENTION_SECS + 1)).to_rfc3339();
+        _update(&delivered).unwrap();
+
+        assert_eq!(Vec::<String>::new(), flush().unwrap());
+        assert_eq!(VcxErrorKind::WalletRecordNotFound, wallet::get_record(OUTBOX_RECORD_TYPE, &id, "{}").unwrap_err().kind());
+
+        _set_offline(endpoint, false);
+        assert_eq!(Delivery::Delivered, send(&message, EncryptionEnvelope(vec![1, 2, 3]), endpoint).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_flush_backs_off_failed_deliveries() {
+        let _setup = SetupLibraryWallet::init();
+        _enable_outbox();
+
+        let (_, id) = _send_offline("vcx-outbox-test://backoff");
+
+        let queued = _queued_messages().remove(0);
+        let next_attempt = DateTime::parse_from_rfc3339(&queued.next_attempt).unwrap().with_timezone(&Utc);
+        assert!(next_attempt > Utc::now());
+        assert!(next_attempt <= Utc::now() + backoff(1));
+
+        // not due yet, so it is not attempted
+        assert_eq!(Vec::<String>::new(), flush().unwrap());
+        assert_eq!(1, _queued_messages()[0].attempts);
+
+        _make_due(&queued);
+        assert_eq!(Vec::<String>::new(), flush().unwrap());
+
+        let queued = _queued_messages().remove(0);
+        assert_eq!(id, queued.id);
+        assert_eq!(2, queued.attempts);
+        assert_eq!(OutboxStatus::Pending, queued.status);
+        assert!(queued.last_error.unwrap().contains("offline"));
+        assert!(DateTime::parse_from_rfc3339(&queued.next_attempt).unwrap().with_timezone(&Utc) > Utc::now() + backoff(1));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_cancel_removes_queued_message() {
+        let _setup = SetupLibraryWallet::init();
+        _enable_outbox();
+
+        let endpoint = "vcx-outbox-test://cancel";
+        let (_, id) = _send_offline(endpoint);
+
+        cancel(&id).unwrap();
+        assert_eq!(Vec::<OutboxMessage>::new(), _queued_messages());
+
+        _set_offline(endpoint, false);
+        assert_eq!(Vec::<String>::new(), flush().unwrap());
+
+        assert_eq!(VcxErrorKind::WalletRecordNotFound, cancel(&id).unwrap_err().kind());
+    }
+}
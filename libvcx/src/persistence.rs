@@ -0,0 +1,413 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+use serde_json;
+
+use connection;
+use credential;
+use dispatcher::ObjectType;
+use disclosed_proof;
+use error::prelude::*;
+use events::{self, StateEvent, StateSubscriber};
+use indy::SearchHandle;
+use issuer_credential;
+use proof;
+use settings;
+use utils::libindy::wallet;
+
+/*
+    Opt-in persistence of connections, credentials and proofs in the wallet.
+
+    When `persist_objects` is enabled in the config, every message handled by the object stores the object
+    serialized right after handling it into a `vcx_object` wallet record with id `<type>:<key>`, where the key is pairwise DID for connections
+    and thread id for credentials and proofs. Objects without a key yet (e.g. not sent offers) are stored
+    after their first transition which assigns it. Records are written synchronously before the call
+    which changed the object returns.
+
+    Records are tagged by `type`, `source_id`, `thread_id` and `state`, so they can be loaded back
+    after restart either by record id or by WQL query over these tags.
+
+    The record an object was last stored to is remembered, so the record stored under the previous key is deleted
+    when the key changes (the inviter provisions new pairwise DID when handling connection request).
+    Releasing the connection deletes its record as well.
+*/
+
+pub const OBJECT_RECORD_TYPE: &str = "vcx_object";
+
+const SEARCH_BATCH_SIZE: usize = 100;
+
+lazy_static! {
+    static ref SUBSCRIPTION: Mutex<Option<u32>> = Mutex::new(None);
+    static ref RECORD_IDS: Mutex<HashMap<(ObjectType, u32), String>> = Mutex::new(HashMap::new());
+}
+
+/// Object loaded from the wallet.
+#[derive(Debug, Clone, PartialEq, Serialize)]
+pub struct LoadedObject {
+    pub id: String,
+    #[serde(rename = "type")]
+    pub type_: ObjectType,
+    pub handle: u32,
+}
+
+#[derive(Debug, Deserialize)]
+struct ObjectRecord {
+    id: String,
+    value: String,
+    tags: Option<ObjectTags>,
+}
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ObjectTags {
+    #[serde(rename = "type")]
+    type_: ObjectType,
+    source_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    thread_id: Option<String>,
+    state: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchRecords {
+    records: Option<Vec<ObjectRecord>>,
+}
+
+struct WalletPersistence;
+
+impl StateSubscriber for WalletPersistence {
+    fn on_state_changed(&self, event: &StateEvent) {
+        let result = _record_key(event.type_, event.handle, event.thread_id.clone())
+            .and_then(|key| match key {
+                Some(key) => {
+                    let id = record_id(event.type_, &key);
+                    _store(&id, &event.object, event.type_, &event.source_id, event.thread_id.clone(), event.new_state)?;
+                    _track(event.type_, event.handle, &id)
+                }
+                None => Ok(())
+            });
+
+        if let Err(err) = result {
+            warn!("Cannot persist {:?} {}: {}", event.type_, event.handle, err);
+        }
+    }
+}
+
+pub fn is_enabled() -> bool {
+    settings::get_config_value(settings::CONFIG_PERSIST_OBJECTS)
+        .map(|value| value == "true")
+        .unwrap_or(false)
+}
+
+/// Starts persisting transitions if it is enabled in the config.
+pub fn init() {
+    let mut subscription = SUBSCRIPTION.lock().unwrap();
+
+    if is_enabled() && subscription.is_none() {
+        *subscription = Some(events::subscribe(Arc::new(WalletPersistence)));
+    }
+}
+
+pub fn stop() {
+    if let Some(subscription) = SUBSCRIPTION.lock().unwrap().take() {
+        events::unsubscribe(subscription).ok();
+    }
+}
+
+/// Stores the current state of the object regardless of transitions. Returns the record id.
+pub fn persist(type_: ObjectType, handle: u32) -> VcxResult<String> {
+    trace!("persist >>> type: {:?}, handle: {}", type_, handle);
+
+    let thread_id = _thread_id(type_, handle)?;
+    _persist(type_, handle, thread_id)?
+        .ok_or(VcxError::from_msg(VcxErrorKind::NotReady, format!("{:?} {} has no thread id to be persisted with yet", type_, handle)))
+}
+
+/// Creates the object from the record with the given id and returns its handle.
+pub fn load(id: &str) -> VcxResult<LoadedObject> {
+    trace!("load >>> id: {}", id);
+
+    let record = wallet::get_record(OBJECT_RECORD_TYPE, id, &json!({"retrieveValue": true, "retrieveTags": true}).to_string())?;
+
+    let record: ObjectRecord = serde_json::from_str(&record)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize object record: {:?}", err)))?;
+
+    _load(record)
+}
+
+/// Creates objects from all the records matching WQL query over `type`, `source_id`, `thread_id` and `state` tags.
+pub fn load_all(query: &str) -> VcxResult<Vec<LoadedObject>> {
+    trace!("load_all >>> query: {}", query);
+
+    let search_handle = wallet::open_search(OBJECT_RECORD_TYPE, query, &json!({"retrieveRecords": true, "retrieveTags": true}).to_string())?;
+
+    let result = _fetch_all(search_handle);
+
+    wallet::close_search(search_handle)?;
+
+    result?.into_iter().map(_load).collect()
+}
+
+pub fn delete(id: &str) -> VcxResult<()> {
+    trace!("delete >>> id: {}", id);
+
+    wallet::delete_record(OBJECT_RECORD_TYPE, id)
+}
+
+/// Deletes the record the object was last stored to or loaded from, if any.
+pub fn remove(type_: ObjectType, handle: u32) -> VcxResult<()> {
+    trace!("remove >>> type: {:?}, handle: {}", type_, handle);
+
+    let id = RECORD_IDS.lock().unwrap().remove(&(type_, handle));
+
+    match id {
+        Some(id) => _delete_record(&id),
+        None => Ok(())
+    }
+}
+
+/// Forgets records of all objects of the type without deleting them (e.g. objects are released on shutdown).
+pub fn forget_all(type_: ObjectType) {
+    RECORD_IDS.lock().unwrap().retain(|&(object_type, _), _| object_type != type_);
+}
+
+pub fn record_id(type_: ObjectType, key: &str) -> String {
+    format!("{}:{}", _type_name(type_), key)
+}
+
+fn _fetch_all(search_handle: SearchHandle) -> VcxResult<Vec<ObjectRecord>> {
+    let mut records = Vec::new();
+
+    loop {
+        let batch = wallet::fetch_next_records(search_handle, SEARCH_BATCH_SIZE)?;
+
+        let batch: SearchRecords = serde_json::from_str(&batch)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize object records: {:?}", err)))?;
+
+        match batch.records {
+            Some(ref batch) if batch.is_empty() => break,
+            Some(batch) => records.extend(batch),
+            None => break
+        }
+    }
+
+    Ok(records)
+}
+
+fn _load(record: ObjectRecord) -> VcxResult<LoadedObject> {
+    let type_ = record.tags
+        .map(|tags| tags.type_)
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Object record {} has no type tag", record.id)))?;
+
+    let handle = match type_ {
+        ObjectType::Connection => connection::from_string(&record.value)?,
+        ObjectType::Credential => credential::from_string(&record.value)?,
+        ObjectType::DisclosedProof => disclosed_proof::from_string(&record.value)?,
+        ObjectType::IssuerCredential => issuer_credential::from_string(&record.value)?,
+        ObjectType::Proof => proof::from_string(&record.value)?,
+    };
+
+    RECORD_IDS.lock().unwrap().insert((type_, handle), record.id.clone());
+
+    Ok(LoadedObject { id: record.id, type_, handle })
+}
+
+fn _persist(type_: ObjectType, handle: u32, thread_id: Option<String>) -> VcxResult<Option<String>> {
+    let id = match _record_key(type_, handle, thread_id.clone())? {
+        Some(key) => record_id(type_, &key),
+        None => return Ok(None)
+    };
+
+    let (value, source_id, state) = match type_ {
+        ObjectType::Connection => (connection::to_string(handle)?, connection::get_source_id(handle)?, connection::get_state(handle)),
+        ObjectType::Credential => (credential::to_string(handle)?, credential::get_source_id(handle)?, credential::get_state(handle)?),
+        ObjectType::DisclosedProof => (disclosed_proof::to_string(handle)?, disclosed_proof::get_source_id(handle)?, disclosed_proof::get_state(handle)?),
+        ObjectType::IssuerCredential => (issuer_credential::to_string(handle)?, issuer_credential::get_source_id(handle)?, issuer_credential::get_state(handle)?),
+        ObjectType::Proof => (proof::to_string(handle)?, proof::get_source_id(handle)?, proof::get_state(handle)?),
+    };
+
+    _store(&id, &value, type_, &source_id, thread_id, state)?;
+    _track(type_, handle, &id)?;
+
+    Ok(Some(id))
+}
+
+/// Key of the record is pairwise DID for connections and thread id for credentials and proofs, objects without it are not stored.
+fn _record_key(type_: ObjectType, handle: u32, thread_id: Option<String>) -> VcxResult<Option<String>> {
+    match type_ {
+        ObjectType::Connection => connection::get_pw_did(handle).map(|pw_did| Some(pw_did).filter(|pw_did| !pw_did.is_empty())),
+        _ => Ok(thread_id.filter(|thread_id| !thread_id.is_empty()))
+    }
+}
+
+fn _store(id: &str, value: &str, type_: ObjectType, source_id: &str, thread_id: Option<String>, state: u32) -> VcxResult<()> {
+    let thread_id = thread_id.filter(|thread_id| !thread_id.is_empty());
+    let tags = json!(ObjectTags { type_, source_id: source_id.to_string(), thread_id, state: state.to_string() }).to_string();
+
+    match wallet::add_record(OBJECT_RECORD_TYPE, id, value, Some(&tags)) {
+        Err(ref err) if err.kind() == VcxErrorKind::DuplicationWalletRecord => {
+            wallet::update_record_value(OBJECT_RECORD_TYPE, id, value)?;
+            wallet::update_record_tags(OBJECT_RECORD_TYPE, id, &tags)
+        }
+        result => result
+    }
+}
+
+/// Remembers the record the object is stored to and deletes the one stored under its previous key.
+fn _track(type_: ObjectType, handle: u32, id: &str) -> VcxResult<()> {
+    let previous = RECORD_IDS.lock().unwrap().insert((type_, handle), id.to_string());
+
+    match previous {
+        Some(ref previous) if previous != id => _delete_record(previous),
+        _ => Ok(())
+    }
+}
+
+fn _delete_record(id: &str) -> VcxResult<()> {
+    match wallet::delete_record(OBJECT_RECORD_TYPE, id) {
+        Err(ref err) if err.kind() == VcxErrorKind::WalletRecordNotFound => Ok(()),
+        result => result
+    }
+}
+
+fn _thread_id(type_: ObjectType, handle: u32) -> VcxResult<Option<String>> {
+    let thread_ids = match type_ {
+        ObjectType::Connection => return Ok(None),
+        ObjectType::Credential => credential::get_thread_ids()?,
+        ObjectType::DisclosedProof => disclosed_proof::get_thread_ids()?,
+        ObjectType::IssuerCredential => issuer_credential::get_thread_ids()?,
+        ObjectType::Proof => proof::get_thread_ids()?,
+    };
+
+    thread_ids.into_iter()
+        .find(|(handle_, _)| *handle_ == handle)
+        .map(|(_, thread_id)| Some(thread_id))
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidHandle, format!("{:?} not found: {}", type_, handle)))
+}
+
+fn _type_name(type_: ObjectType) -> String {
+    json!(type_).as_str().unwrap_or_default().to_string()
+}
+
+#[cfg(test)]
+pub mod tests {
+    use api::VcxStateType;
+    use aries::messages::a2a::A2AMessage;
+    use connection::tests::build_test_connection_inviter_requested;
+    use issuer_credential::tests::_issuer_credential_create;
+    use utils::devsetup::{SetupAriesMocks, SetupLibraryWallet, SetupStrictAriesMocks};
+    use utils::mockdata::mockdata_connection::ARIES_CONNECTION_REQUEST;
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_record_id() {
+        assert_eq!("issuer_credential:thid", record_id(ObjectType::IssuerCredential, "thid"));
+        assert_eq!("disclosed_proof:thid", record_id(ObjectType::DisclosedProof, "thid"));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_persist() {
+        let _setup = SetupStrictAriesMocks::init();
+
+        let handle = _issuer_credential_create();
+        assert_eq!(VcxErrorKind::NotReady, persist(ObjectType::IssuerCredential, handle).unwrap_err().kind());
+
+        let connection_handle = build_test_connection_inviter_requested();
+        issuer_credential::send_credential_offer(handle, connection_handle, None).unwrap();
+
+        let thread_id = issuer_credential::get_thread_ids().unwrap().into_iter()
+            .find(|(handle_, _)| *handle_ == handle)
+            .unwrap().1;
+        assert_eq!(record_id(ObjectType::IssuerCredential, &thread_id), persist(ObjectType::IssuerCredential, handle).unwrap());
+
+        let pw_did = connection::get_pw_did(connection_handle).unwrap();
+        assert_eq!(record_id(ObjectType::Connection, &pw_did), persist(ObjectType::Connection, connection_handle).unwrap());
+
+        assert_eq!(VcxErrorKind::InvalidHandle, persist(ObjectType::Proof, 0).unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_load_all_without_records() {
+        let _setup = SetupAriesMocks::init();
+
+        assert_eq!(Vec::<LoadedObject>::new(), load_all(&json!({"type": "connection"}).to_string()).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_init_subscribes_when_enabled() {
+        let _setup = SetupAriesMocks::init();
+
+        init();
+        assert!(SUBSCRIPTION.lock().unwrap().is_none());
+
+        settings::set_config_value(settings::CONFIG_PERSIST_OBJECTS, "true");
+        init();
+        assert!(SUBSCRIPTION.lock().unwrap().is_some());
+
+        stop();
+        assert!(SUBSCRIPTION.lock().unwrap().is_none());
+    }
+
+    fn _enable_persistence() {
+        // messages go over memory transport and connections are provisioned without agency agent
+        settings::set_config_value(settings::CONFIG_ENABLE_TEST_MODE, "agency");
+        settings::set_config_value(settings::CONFIG_INBOUND_ENDPOINT, "http://localhost:8080/inbound");
+        settings::set_config_value(settings::CONFIG_PERSIST_OBJECTS, "true");
+        init();
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_connection_is_loaded_once_after_pw_did_changes() {
+        let _setup = SetupLibraryWallet::init();
+        _enable_persistence();
+
+        let handle = connection::create_connection("faber_to_alice").unwrap();
+        connection::connect(handle).unwrap();
+        let invited_pw_did = connection::get_pw_did(handle).unwrap();
+
+        let request: A2AMessage = serde_json::from_str(ARIES_CONNECTION_REQUEST).unwrap();
+        connection::update_state_with_message(handle, request).unwrap();
+        let responded_pw_did = connection::get_pw_did(handle).unwrap();
+        assert_ne!(invited_pw_did, responded_pw_did);
+
+        stop();
+        connection::release_all();
+
+        let loaded = load_all(&json!({"type": "connection"}).to_string()).unwrap();
+        assert_eq!(1, loaded.len());
+        assert_eq!(record_id(ObjectType::Connection, &responded_pw_did), loaded[0].id);
+        assert_eq!(VcxStateType::VcxStateRequestReceived as u32, connection::get_state(loaded[0].handle));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_release_and_delete_connection_delete_record() {
+        let _setup = SetupLibraryWallet::init();
+        _enable_persistence();
+
+        let released = connection::create_connection("faber_to_alice").unwrap();
+        connection::connect(released).unwrap();
+        let released_id = record_id(ObjectType::Connection, &connection::get_pw_did(released).unwrap());
+
+        let deleted = connection::create_connection("faber_to_bob").unwrap();
+        connection::connect(deleted).unwrap();
+        let deleted_id = record_id(ObjectType::Connection, &connection::get_pw_did(deleted).unwrap());
+
+        stop();
+        assert_eq!(2, load_all(&json!({"type": "connection"}).to_string()).unwrap().len());
+        connection::release_all();
+
+        let loaded = load(&released_id).unwrap();
+        connection::release(loaded.handle).unwrap();
+        assert_eq!(VcxErrorKind::WalletRecordNotFound, load(&released_id).unwrap_err().kind());
+
+        let loaded = load(&deleted_id).unwrap();
+        connection::delete_connection(loaded.handle).unwrap();
+        assert_eq!(VcxErrorKind::WalletRecordNotFound, load(&deleted_id).unwrap_err().kind());
+    }
+}
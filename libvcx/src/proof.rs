@@ -1,7 +1,12 @@
 use serde_json;
 
 use aries::handlers::proof_presentation::verifier::verifier::Verifier;
+use aries::messages::a2a::A2AMessage;
+use dispatcher::ObjectType;
 use error::prelude::*;
+use events::{self, Observable};
+use history::History;
+use serialization;
 use utils::error;
 use utils::object_cache::ObjectCache;
 
@@ -12,17 +17,50 @@ lazy_static! {
 #[derive(Serialize, Deserialize, Debug)]
 #[serde(tag = "version", content = "data")]
 enum Proofs {
-    #[serde(rename = "2.0")]
+    #[serde(rename = "4.0")]
     V3(Verifier),
 }
 
+impl Observable for Verifier {
+    fn source_id(&self) -> String {
+        self.get_source_id()
+    }
+
+    fn thread_id(&self) -> Option<String> {
+        Some(self.get_thread_id())
+    }
+
+    fn history(&self) -> &History {
+        self.get_history()
+    }
+
+    fn serialize(&self) -> VcxResult<String> {
+        serde_json::to_string(&Proofs::V3(self.clone()))
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidState, format!("cannot serialize Proof proofect: {:?}", err)))
+    }
+}
+
 pub fn create_proof(source_id: String,
                     requested_attrs: String,
                     requested_predicates: String,
                     revocation_details: String,
                     name: String) -> VcxResult<u32> {
     let verifier = Verifier::create(source_id, requested_attrs, requested_predicates, revocation_details, name)?;
-    PROOF_MAP.add(verifier)
+    events::add(&PROOF_MAP, ObjectType::Proof, verifier)
+        .or(Err(VcxError::from(VcxErrorKind::CreateProof)))
+}
+
+/**
+Creates verifier for Presentation Proposal the prover sent without receiving a request first.
+ */
+pub fn create_proof_from_proposal(source_id: &str, connection_handle: u32, presentation_proposal: &str) -> VcxResult<u32> {
+    trace!("create_proof_from_proposal >>> source_id: {}, connection_handle: {}, presentation_proposal: {}", source_id, connection_handle, presentation_proposal);
+
+    let presentation_proposal: A2AMessage = serde_json::from_str(presentation_proposal)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize Presentation Proposal: {:?}", err)))?;
+
+    let verifier = Verifier::create_from_proposal(source_id.to_string(), presentation_proposal, connection_handle)?;
+    events::add(&PROOF_MAP, ObjectType::Proof, verifier)
         .or(Err(VcxError::from(VcxErrorKind::CreateProof)))
 }
 
@@ -31,7 +69,7 @@ pub fn is_valid_handle(handle: u32) -> bool {
 }
 
 pub fn update_state(handle: u32, message: Option<String>, connection_handle: Option<u32>) -> VcxResult<u32> {
-    PROOF_MAP.get_mut(handle, |proof| {
+    events::update(&PROOF_MAP, ObjectType::Proof, handle, |proof| {
         proof.update_state(message.as_ref().map(String::as_str), connection_handle)?;
         Ok(proof.state())
     })
@@ -59,8 +97,7 @@ pub fn release_all() {
 
 pub fn to_string(handle: u32) -> VcxResult<String> {
     PROOF_MAP.get(handle, |proof| {
-        serde_json::to_string(&Proofs::V3(proof.clone()))
-            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidState, format!("cannot serialize Proof proofect: {:?}", err)))
+        proof.serialize()
     })
 }
 
@@ -70,8 +107,30 @@ pub fn get_source_id(handle: u32) -> VcxResult<String> {
     })
 }
 
+pub fn get_history(handle: u32) -> VcxResult<History> {
+    PROOF_MAP.get(handle, |proof| {
+        Ok(proof.get_history().clone())
+    })
+}
+
+/**
+Returns handles of proofs whose presentation requests expired without an answer of the prover.
+ */
+pub fn get_expired() -> VcxResult<Vec<u32>> {
+    Ok(PROOF_MAP.get_all(|proof| proof.is_expired())?
+        .into_iter()
+        .filter_map(|(handle, expired)| if expired { Some(handle) } else { None })
+        .collect())
+}
+
+pub fn expire(handle: u32) -> VcxResult<bool> {
+    events::update(&PROOF_MAP, ObjectType::Proof, handle, |proof| {
+        proof.expire()
+    })
+}
+
 pub fn from_string(proof_data: &str) -> VcxResult<u32> {
-    let proof: Proofs = serde_json::from_str(proof_data)
+    let proof: Proofs = serde_json::from_value(serialization::upgrade(ObjectType::Proof, proof_data)?)
         .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("cannot deserialize Proofs proofect: {:?}", err)))?;
 
     match proof {
@@ -79,14 +138,28 @@ pub fn from_string(proof_data: &str) -> VcxResult<u32> {
     }
 }
 
+/**
+Returns thread ids of all proofs, they are used to route received messages.
+ */
+pub fn get_thread_ids() -> VcxResult<Vec<(u32, String)>> {
+    PROOF_MAP.get_all(|proof| proof.get_thread_id())
+}
+
+/**
+Returns the connection the object exchanges messages over, if it is already known.
+ */
+pub fn get_connection_handle(handle: u32) -> VcxResult<Option<u32>> {
+    PROOF_MAP.get(handle, |proof| Ok(proof.get_connection_handle()))
+}
+
 pub fn generate_proof_request_msg(handle: u32) -> VcxResult<String> {
-    PROOF_MAP.get_mut(handle, |proof| {
+    events::update(&PROOF_MAP, ObjectType::Proof, handle, |proof| {
         proof.generate_presentation_request_msg()
     })
 }
 
 pub fn send_proof_request(handle: u32, connection_handle: u32) -> VcxResult<u32> {
-    PROOF_MAP.get_mut(handle, |proof| {
+    events::update(&PROOF_MAP, ObjectType::Proof, handle, |proof| {
         proof.send_presentation_request(connection_handle)?;
         Ok(error::SUCCESS.code_num)
     })
@@ -98,6 +171,38 @@ pub fn get_proof(handle: u32) -> VcxResult<String> {
     })
 }
 
+pub fn get_proof_proposal(handle: u32) -> VcxResult<String> {
+    PROOF_MAP.get(handle, |proof| {
+        proof.get_presentation_proposal()
+    })
+}
+
+pub fn accept_proof_proposal(handle: u32, connection_handle: u32) -> VcxResult<u32> {
+    events::update(&PROOF_MAP, ObjectType::Proof, handle, |proof| {
+        proof.accept_presentation_proposal(connection_handle)?;
+        Ok(error::SUCCESS.code_num)
+    })
+}
+
+pub fn send_counter_proof_request(handle: u32,
+                                  connection_handle: u32,
+                                  requested_attrs: String,
+                                  requested_predicates: String,
+                                  revocation_details: String,
+                                  name: String) -> VcxResult<u32> {
+    events::update(&PROOF_MAP, ObjectType::Proof, handle, |proof| {
+        proof.send_counter_presentation_request(connection_handle, requested_attrs.clone(), requested_predicates.clone(), revocation_details.clone(), name.clone())?;
+        Ok(error::SUCCESS.code_num)
+    })
+}
+
+pub fn reject_proof_proposal(handle: u32, connection_handle: u32, reason: &str) -> VcxResult<u32> {
+    events::update(&PROOF_MAP, ObjectType::Proof, handle, |proof| {
+        proof.reject_presentation_proposal(connection_handle, reason)?;
+        Ok(error::SUCCESS.code_num)
+    })
+}
+
 #[cfg(test)]
 pub mod tests {
     use serde_json::Value;
@@ -146,6 +251,24 @@ pub mod tests {
                      "Optional".to_owned()).unwrap();
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_create_proof_from_proposal() {
+        let _setup = SetupStrictAriesMocks::init();
+
+        let connection_handle = build_test_connection_inviter_requested();
+        let proposal = ::aries::messages::proof_presentation::presentation_proposal::tests::_presentation_proposal();
+
+        let handle = create_proof_from_proposal("1", connection_handle, &json!(proposal.to_a2a_message()).to_string()).unwrap();
+        assert_eq!(get_state(handle).unwrap(), VcxStateType::VcxStateProposalReceived as u32);
+
+        accept_proof_proposal(handle, connection_handle).unwrap();
+        assert_eq!(get_state(handle).unwrap(), VcxStateType::VcxStateOfferSent as u32);
+
+        let request = json!(::aries::messages::proof_presentation::presentation_request::tests::_presentation_request().to_a2a_message()).to_string();
+        assert_eq!(create_proof_from_proposal("1", connection_handle, &request).unwrap_err().kind(), VcxErrorKind::InvalidJson);
+    }
+
     #[test]
     #[cfg(feature = "general_test")]
     fn test_revocation_details() {
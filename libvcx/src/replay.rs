@@ -0,0 +1,299 @@
+use std::collections::{HashMap, HashSet};
+
+use chrono::{Duration, Utc};
+use indy::SearchHandle;
+use serde_json;
+
+use aries::messages::a2a::A2AMessage;
+use error::prelude::*;
+use utils::libindy::wallet;
+
+/*
+    Replay protection of inbound messages.
+
+    Every message handled on a connection leaves a `vcx_processed_message` wallet record tagged by pairwise DID
+    of the connection and `@id` of the message. Messages fetched later with an `@id` already processed on the connection,
+    either returned again by the agency or replayed by someone else, are dropped and marked as reviewed.
+    The dispatcher filters messages of every source (agency, inbound endpoint, mediator pickup and router) before routing them,
+    and so does the connection fetching its own messages.
+
+    The records are pruned by `update_all` once they are older than `PROCESSED_RETENTION_SECS`.
+    On top of that state machines ignore messages whose `@id` is in their history, where every handled message
+    is recorded whether it changed the state or not, so a duplicate passed directly to `update_state_with_message`
+    or replayed after the record expired does not run the exchange step (e.g. storing of a credential) again.
+*/
+
+pub const PROCESSED_RECORD_TYPE: &str = "vcx_processed_message";
+
+const PROCESSED_RETENTION_SECS: i64 = 30 * 24 * 3600;
+const SEARCH_BATCH_SIZE: usize = 100;
+
+#[derive(Debug, Serialize, Deserialize)]
+struct ProcessedTags {
+    connection: String,
+    message_id: String,
+    /// Unix time when the message was processed, unencrypted to be compared in queries.
+    #[serde(rename = "~processed_at")]
+    processed_at: String,
+}
+
+#[derive(Debug, Deserialize)]
+struct ProcessedRecord {
+    id: String,
+    tags: Option<ProcessedTags>,
+}
+
+#[derive(Debug, Deserialize)]
+struct SearchRecords {
+    records: Option<Vec<ProcessedRecord>>,
+}
+
+/// `@id` of the message.
+pub fn message_id(message: &A2AMessage) -> Option<String> {
+    json!(message)["@id"].as_str()
+        .filter(|id| !id.is_empty())
+        .map(String::from)
+}
+
+/// Records that the message was handled on the connection with the given pairwise DID.
+pub fn record_processed(pw_did: &str, message: &A2AMessage) -> VcxResult<()> {
+    trace!("replay::record_processed >>> pw_did: {}", pw_did);
+
+    let message_id = match message_id(message) {
+        Some(message_id) => message_id,
+        None => return Ok(())
+    };
+
+    let now = Utc::now();
+    let tags = json!(ProcessedTags {
+        connection: pw_did.to_string(),
+        message_id: message_id.clone(),
+        processed_at: _unix_time(now.timestamp()),
+    }).to_string();
+
+    match wallet::add_record(PROCESSED_RECORD_TYPE, &_record_id(pw_did, &message_id), &now.to_rfc3339(), Some(&tags)) {
+        Ok(()) => Ok(()),
+        Err(ref err) if err.kind() == VcxErrorKind::DuplicationWalletRecord => Ok(()),
+        Err(err) => Err(err)
+    }
+}
+
+/**
+Splits messages fetched for the connection into the ones to be handled and uids of the duplicates,
+which were either processed before or repeat `@id` of another fetched message.
+ */
+pub fn filter_processed(pw_did: &str, messages: HashMap<String, A2AMessage>) -> VcxResult<(HashMap<String, A2AMessage>, Vec<String>)> {
+    trace!("replay::filter_processed >>> pw_did: {}", pw_did);
+
+    if messages.is_empty() {
+        return Ok((messages, Vec::new()));
+    }
+
+    let message_ids: Vec<String> = messages.values().filter_map(message_id).collect();
+    let processed = _find_processed(pw_did, &message_ids)?;
+
+    Ok(_split(pw_did, messages, &processed))
+}
+
+/// Removes records of the messages processed more than `PROCESSED_RETENTION_SECS` ago.
+pub fn prune_processed() -> VcxResult<()> {
+    trace!("replay::prune_processed >>>");
+
+    let expired = _unix_time((Utc::now() - Duration::seconds(PROCESSED_RETENTION_SECS)).timestamp());
+
+    let query = json!({"~processed_at": {"$lt": expired}}).to_string();
+    let options = json!({"retrieveRecords": true, "retrieveValue": false, "retrieveTags": false}).to_string();
+
+    let search_handle = wallet::open_search(PROCESSED_RECORD_TYPE, &query, &options)?;
+
+    let result = _fetch_all(search_handle);
+
+    wallet::close_search(search_handle)?;
+
+    for record in result? {
+        wallet::delete_record(PROCESSED_RECORD_TYPE, &record.id)?;
+    }
+
+    Ok(())
+}
+
+fn _split(pw_did: &str, messages: HashMap<String, A2AMessage>, processed: &HashSet<String>) -> (HashMap<String, A2AMessage>, Vec<String>) {
+    let mut pending = HashMap::new();
+    let mut duplicates = Vec::new();
+    let mut seen = HashSet::new();
+
+    for (uid, message) in messages {
+        match message_id(&message) {
+            Some(ref id) if processed.contains(id) || !seen.insert(id.clone()) => {
+                warn!("Message {} with @id {} was already received on connection {}, it is ignored", uid, id, pw_did);
+                duplicates.push(uid);
+            }
+            _ => {
+                pending.insert(uid, message);
+            }
+        }
+    }
+
+    (pending, duplicates)
+}
+
+fn _record_id(pw_did: &str, message_id: &str) -> String {
+    format!("{}:{}", pw_did, message_id)
+}
+
+// zero padded, so the tag values compare as numbers
+fn _unix_time(timestamp: i64) -> String {
+    format!("{:012}", timestamp)
+}
+
+fn _find_processed(pw_did: &str, message_ids: &[String]) -> VcxResult<HashSet<String>> {
+    if message_ids.is_empty() {
+        return Ok(HashSet::new());
+    }
+
+    let query = json!({"connection": pw_did, "message_id": {"$in": message_ids}}).to_string();
+    let options = json!({"retrieveRecords": true, "retrieveValue": false, "retrieveTags": true}).to_string();
+
+    let search_handle = wallet::open_search(PROCESSED_RECORD_TYPE, &query, &options)?;
+
+    let result = _fetch_all(search_handle);
+
+    wallet::close_search(search_handle)?;
+
+    Ok(result?.into_iter()
+        .filter_map(|record| record.tags.map(|tags| tags.message_id))
+        .collect())
+}
+
+fn _fetch_all(search_handle: SearchHandle) -> VcxResult<Vec<ProcessedRecord>> {
+    let mut records = Vec::new();
+
+    loop {
+        let batch = wallet::fetch_next_records(search_handle, SEARCH_BATCH_SIZE)?;
+
+        let batch: SearchRecords = serde_json::from_str(&batch)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize processed message records: {:?}", err)))?;
+
+        match batch.records {
+            Some(ref batch) if batch.is_empty() => break,
+            Some(batch) => records.extend(batch),
+            None => break
+        }
+    }
+
+    Ok(records)
+}
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::ack::tests::_ack;
+    use aries::messages::a2a::MessageId;
+    use utils::devsetup::SetupLibraryWallet;
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_split_drops_processed_and_repeated_messages() {
+        let processed_ack = _ack().to_a2a_message();
+
+        let mut ack = _ack();
+        ack.id = MessageId(String::from("other"));
+        let ack = ack.to_a2a_message();
+
+        let mut messages = HashMap::new();
+        messages.insert(String::from("uid_1"), processed_ack);
+        messages.insert(String::from("uid_2"), ack.clone());
+        messages.insert(String::from("uid_3"), ack);
+
+        let processed = vec![String::from("testid")].into_iter().collect();
+        let (pending, mut duplicates) = _split("pw_did", messages, &processed);
+
+        assert_eq!(1, pending.len());
+        assert_eq!(Some(String::from("other")), message_id(pending.values().next().unwrap()));
+
+        duplicates.sort();
+        assert_eq!(2, duplicates.len());
+        assert_eq!(String::from("uid_1"), duplicates[0]);
+        assert!(pending.contains_key("uid_2") || pending.contains_key("uid_3"));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_message_id() {
+        assert_eq!(Some(String::from("testid")), message_id(&_ack().to_a2a_message()));
+    }
+
+    fn _messages(message: A2AMessage) -> HashMap<String, A2AMessage> {
+        let mut messages = HashMap::new();
+        messages.insert(String::from("uid_1"), message);
+        messages
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_filter_processed_drops_replayed_message() {
+        let _setup = SetupLibraryWallet::init();
+
+        let ack = _ack().to_a2a_message();
+
+        let (pending, duplicates) = filter_processed("pw_did", _messages(ack.clone())).unwrap();
+        assert_eq!(1, pending.len());
+        assert!(duplicates.is_empty());
+
+        record_processed("pw_did", &ack).unwrap();
+        record_processed("pw_did", &ack).unwrap();
+
+        let (pending, duplicates) = filter_processed("pw_did", _messages(ack.clone())).unwrap();
+        assert!(pending.is_empty());
+        assert_eq!(vec![String::from("uid_1")], duplicates);
+
+        // the same @id on another connection is not a replay
+        let (pending, duplicates) = filter_processed("other_pw_did", _messages(ack)).unwrap();
+        assert_eq!(1, pending.len());
+        assert!(duplicates.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_prune_processed_removes_expired_records() {
+        let _setup = SetupLibraryWallet::init();
+
+        let ack = _ack().to_a2a_message();
+        record_processed("pw_did", &ack).unwrap();
+
+        let mut expired_ack = _ack();
+        expired_ack.id = MessageId(String::from("expired"));
+        let expired_ack = expired_ack.to_a2a_message();
+
+        let processed = Utc::now() - Duration::seconds(PROCESSED_RETENTION_SECS + 60);
+        let tags = json!(ProcessedTags {
+            connection: String::from("pw_did"),
+            message_id: String::from("expired"),
+            processed_at: _unix_time(processed.timestamp()),
+        }).to_string();
+        wallet::add_record(PROCESSED_RECORD_TYPE, &_record_id("pw_did", "expired"), &processed.to_rfc3339(), Some(&tags)).unwrap();
+
+        let (pending, _) = filter_processed("pw_did", _messages(expired_ack.clone())).unwrap();
+        assert!(pending.is_empty());
+
+        prune_processed().unwrap();
+
+        assert_eq!(VcxErrorKind::WalletRecordNotFound,
+                   wallet::get_record(PROCESSED_RECORD_TYPE, &_record_id("pw_did", "expired"), "{}").unwrap_err().kind());
+        wallet::get_record(PROCESSED_RECORD_TYPE, &_record_id("pw_did", "testid"), "{}").unwrap();
+
+        let (pending, _) = filter_processed("pw_did", _messages(expired_ack)).unwrap();
+        assert_eq!(1, pending.len());
+
+        let (pending, _) = filter_processed("pw_did", _messages(ack)).unwrap();
+        assert!(pending.is_empty());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_processed_time_tag_compares_as_number() {
+        assert_eq!("000000000042", _unix_time(42));
+        assert!(_unix_time(999_999_999) < _unix_time(1_000_000_000));
+    }
+}
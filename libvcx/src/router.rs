@@ -0,0 +1,140 @@
+use aries::handlers::mediation::router::Router;
+use connection;
+use error::prelude::*;
+use messages::ObjectWithVersion;
+use utils::constants::DEFAULT_SERIALIZE_VERSION;
+use utils::object_cache::ObjectCache;
+
+lazy_static! {
+    static ref ROUTER_MAP: ObjectCache<Router> = ObjectCache::<Router>::new("routers-cache");
+}
+
+pub fn create_router(source_id: &str, endpoint: &str) -> VcxResult<u32> {
+    trace!("create_router >>> source_id: {}, endpoint: {}", source_id, endpoint);
+
+    let router = Router::create(source_id, endpoint)?;
+    ROUTER_MAP.add(router)
+}
+
+pub fn is_valid_handle(handle: u32) -> bool {
+    ROUTER_MAP.has_handle(handle)
+}
+
+pub fn get_source_id(handle: u32) -> VcxResult<String> {
+    ROUTER_MAP.get(handle, |router| {
+        Ok(router.get_source_id())
+    }).or(Err(VcxError::from(VcxErrorKind::InvalidHandle)))
+}
+
+/**
+Allows the counterparty of the connection to request mediation from the router.
+ */
+pub fn add_connection(handle: u32, connection_handle: u32) -> VcxResult<()> {
+    trace!("add_connection >>> handle: {}, connection_handle: {}", handle, connection_handle);
+
+    let pw_vk = connection::get_pw_verkey(connection_handle)?;
+    let did_doc = connection::get_their_did_doc(connection_handle)?;
+
+    ROUTER_MAP.get_mut(handle, |router| {
+        router.add_client(&pw_vk, did_doc.clone())
+    })
+}
+
+/**
+Processes the packed message received on the router's endpoint.
+Returns the packed answer which must be sent back in the response, empty if there is nothing to return.
+ */
+pub fn receive_message(handle: u32, payload: &[u8]) -> VcxResult<Vec<u8>> {
+    trace!("receive_message >>> handle: {}", handle);
+
+    ROUTER_MAP.get_mut(handle, |router| {
+        router.receive(payload)
+            .map(Option::unwrap_or_default)
+    })
+}
+
+pub fn get_queued_count(handle: u32) -> VcxResult<u32> {
+    ROUTER_MAP.get(handle, |router| {
+        Ok(router.queued_count() as u32)
+    })
+}
+
+pub fn to_string(handle: u32) -> VcxResult<String> {
+    ROUTER_MAP.get(handle, |router| {
+        ObjectWithVersion::new(DEFAULT_SERIALIZE_VERSION, router.to_owned())
+            .serialize()
+            .map_err(|err| err.extend("Cannot serialize Router"))
+    })
+}
+
+pub fn from_string(router_data: &str) -> VcxResult<u32> {
+    let router: Router = ObjectWithVersion::deserialize(router_data)
+        .map(|obj: ObjectWithVersion<Router>| obj.data)
+        .map_err(|err| err.extend("Cannot deserialize Router"))?;
+
+    ROUTER_MAP.add(router)
+}
+
+pub fn release(handle: u32) -> VcxResult<()> {
+    ROUTER_MAP.release(handle)
+        .or(Err(VcxError::from(VcxErrorKind::InvalidHandle)))
+}
+
+pub fn release_all() {
+    ROUTER_MAP.drain().ok();
+}
+
+#[cfg(test)]
+pub mod tests {
+    use aries::messages::a2a::A2AMessage;
+    use aries::messages::mediation::mediate_request::MediateRequest;
+    use connection::tests::build_test_connection_inviter_requested;
+    use utils::devsetup::SetupAriesMocks;
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_router_grants_mediation_over_registered_connection() {
+        let _setup = SetupAriesMocks::init();
+
+        let handle = create_router("router", "https://router.example.com").unwrap();
+        let connection_handle = build_test_connection_inviter_requested();
+
+        let request = json!({
+            "message": json!(MediateRequest::create().request_return_route().to_a2a_message()).to_string(),
+            "sender_verkey": connection::get_their_pw_verkey(connection_handle).unwrap(),
+            "recipient_verkey": connection::get_pw_verkey(connection_handle).unwrap()
+        }).to_string();
+
+        assert_eq!(VcxErrorKind::InvalidMessages, receive_message(handle, request.as_bytes()).unwrap_err().kind());
+
+        add_connection(handle, connection_handle).unwrap();
+        assert_eq!(VcxErrorKind::InvalidState, add_connection(handle, connection_handle).unwrap_err().kind());
+
+        let answer: A2AMessage = ::serde_json::from_slice(&receive_message(handle, request.as_bytes()).unwrap()).unwrap();
+        match answer {
+            A2AMessage::MediateGrant(grant) => assert_eq!("https://router.example.com", grant.endpoint),
+            message => panic!("Unexpected message: {:?}", message)
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_router_serialize_deserialize() {
+        let _setup = SetupAriesMocks::init();
+
+        let handle = create_router("router", "https://router.example.com").unwrap();
+        let connection_handle = build_test_connection_inviter_requested();
+        add_connection(handle, connection_handle).unwrap();
+
+        let serialized = to_string(handle).unwrap();
+        release(handle).unwrap();
+        assert!(!is_valid_handle(handle));
+
+        let handle = from_string(&serialized).unwrap();
+        assert_eq!("router", get_source_id(handle).unwrap());
+        assert_eq!(0, get_queued_count(handle).unwrap());
+        assert_eq!(serialized, to_string(handle).unwrap());
+    }
+}
@@ -0,0 +1,246 @@
+use std::convert::TryInto;
+
+use serde_json;
+use serde_json::Value;
+
+use api::VcxStateType;
+use error::prelude::*;
+use messages::proofs::proof_message::ProofMessage;
+use messages::proofs::proof_request::ProofRequestMessage;
+use aries::messages::connection::did_doc::DidDoc;
+use aries::messages::error::ProblemReport;
+use aries::messages::issuance::credential::Credential;
+use aries::messages::proof_presentation::presentation::Presentation;
+use aries::messages::proof_presentation::presentation_request::PresentationRequest;
+use aries::messages::status::Status;
+use utils::uuid;
+
+/// Version of pre-Aries objects, it is also assumed for objects serialized before versioning was introduced.
+pub const VERSION: &str = "1.0";
+
+/*
+    Pre-Aries objects were driven by the proprietary protocol which is not supported anymore,
+    so they cannot continue an unfinished exchange. Only objects which can be represented by
+    Aries state machines are migrated:
+        * connections which were not used yet and accepted connections of the invitee,
+          which stored agency details of the inviter needed to build its DID Doc
+        * credentials and proofs in the accepted state
+*/
+
+/// Pending credentials received an offer, but did not choose the protocol to answer it yet.
+pub fn pending_credential(object: Value) -> VcxResult<Value> {
+    Err(_not_supported("credential", _state(&object["data"])?))
+}
+
+/// Pending disclosed proofs received a request, but did not choose the protocol to answer it yet.
+pub fn pending_disclosed_proof(object: Value) -> VcxResult<Value> {
+    Err(_not_supported("disclosed proof", _state(&object["data"])?))
+}
+
+pub fn connection(object: Value) -> VcxResult<Value> {
+    let data = &object["data"];
+
+    let agent_info = json!({
+        "pw_did": _str(data, "pw_did")?,
+        "pw_vk": _str(data, "pw_verkey")?,
+        "agent_did": _str(data, "agent_did")?,
+        "agent_vk": _str(data, "agent_vk")?,
+    });
+
+    let state = match _state(data)? {
+        state if state == VcxStateType::VcxStateInitialized as u64 => json!({"Inviter": {"Null": {}}}),
+        state if state == VcxStateType::VcxStateAccepted as u64 && _is_invitee(data) => {
+            let invite_detail = &data["invite_detail"];
+
+            let mut did_doc = DidDoc::default();
+            did_doc.set_id(_str(data, "their_pw_did")?);
+            did_doc.set_service_endpoint(_str(&invite_detail["senderAgencyDetail"], "endpoint")?);
+            did_doc.set_keys(vec![_str(data, "their_pw_verkey")?],
+                             vec![_str(&invite_detail["senderDetail"]["agentKeyDlgProof"], "agentDelegatedKey")?,
+                                  _str(&invite_detail["senderAgencyDetail"], "verKey")?]);
+
+            json!({"Invitee": {"Completed": {"did_doc": did_doc, "protocols": null}}})
+        }
+        state => return Err(_not_supported("connection", state))
+    };
+
+    Ok(json!({
+        "source_id": _str(data, "source_id")?,
+        "data": agent_info,
+        "state": state,
+    }))
+}
+
+pub fn credential(object: Value) -> VcxResult<Value> {
+    let data = &object["data"];
+
+    _check_accepted("credential", data)?;
+
+    let thread_id = _thread_id(data);
+
+    let credential_message: Value = serde_json::from_str(&_str(data, "credential")?)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize legacy credential message: {:?}", err)))?;
+
+    let credential = Credential::create()
+        .set_credential(_str(&credential_message, "libindy_cred")?)?
+        .set_thread_id(&thread_id);
+
+    let rev_reg_def_json = credential_message["rev_reg_def_json"].as_str()
+        .filter(|rev_reg_def_json| !rev_reg_def_json.is_empty());
+
+    Ok(json!({
+        "data": {
+            "holder_sm": {
+                "state": {
+                    "Finished": {
+                        "cred_id": data["cred_id"].as_str(),
+                        "credential": credential,
+                        "status": Status::Success,
+                        "rev_reg_def_json": rev_reg_def_json,
+                    }
+                },
+                "source_id": _str(data, "source_id")?,
+                "thread_id": thread_id,
+            }
+        }
+    }))
+}
+
+pub fn disclosed_proof(object: Value) -> VcxResult<Value> {
+    let data = &object["data"];
+
+    _check_accepted("disclosed proof", data)?;
+
+    let thread_id = _thread_id(data);
+
+    let proof: ProofMessage = serde_json::from_value(data["proof"].clone())
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize legacy proof message: {:?}", err)))?;
+
+    let presentation: Presentation = proof.try_into()?;
+
+    Ok(json!({
+        "data": {
+            "prover_sm": {
+                "source_id": _str(data, "source_id")?,
+                "thread_id": thread_id,
+                "state": {
+                    "Finished": {
+                        "connection_handle": 0,
+                        "presentation_request": _presentation_request(data, &thread_id)?,
+                        "presentation": presentation.set_thread_id(&thread_id),
+                        "status": Status::Success,
+                    }
+                }
+            }
+        }
+    }))
+}
+
+pub fn issuer_credential(object: Value) -> VcxResult<Value> {
+    let data = &object["data"];
+
+    _check_accepted("issuer credential", data)?;
+
+    Ok(json!({
+        "data": {
+            "issuer_sm": {
+                "state": {
+                    "Finished": {
+                        "cred_id": null,
+                        "thread_id": _thread_id(data),
+                        "revocation_info_v1": {
+                            "cred_rev_id": data["cred_rev_id"].as_str(),
+                            "rev_reg_id": data["rev_reg_id"].as_str(),
+                            "tails_file": data["tails_file"].as_str(),
+                        },
+                        "status": Status::Success,
+                    }
+                },
+                "source_id": _str(data, "source_id")?,
+            }
+        }
+    }))
+}
+
+pub fn proof(object: Value) -> VcxResult<Value> {
+    let data = &object["data"];
+
+    _check_accepted("proof", data)?;
+
+    let thread_id = _thread_id(data);
+
+    let proof: ProofMessage = serde_json::from_value(data["proof"].clone())
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize legacy proof message: {:?}", err)))?;
+
+    let presentation: Presentation = proof.try_into()?;
+
+    // legacy proof state: 1 - validated, 2 - invalid
+    let status = match data["proof_state"].as_u64() {
+        Some(1) => Status::Success,
+        Some(2) => Status::Failed(ProblemReport::create().set_comment(String::from("Proof is invalid"))),
+        _ => Status::Undefined,
+    };
+
+    Ok(json!({
+        "data": {
+            "verifier_sm": {
+                "source_id": _str(data, "source_id")?,
+                "state": {
+                    "Finished": {
+                        "connection_handle": 0,
+                        "presentation_request": _presentation_request(data, &thread_id)?,
+                        "presentation": presentation.set_thread_id(&thread_id),
+                        "status": status,
+                        "revocation_status": null,
+                    }
+                }
+            }
+        }
+    }))
+}
+
+fn _presentation_request(data: &Value, thread_id: &str) -> VcxResult<PresentationRequest> {
+    let proof_request: ProofRequestMessage = serde_json::from_value(data["proof_request"].clone())
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize legacy proof request: {:?}", err)))?;
+
+    let presentation_request: PresentationRequest = proof_request.try_into()?;
+
+    Ok(presentation_request.set_id(thread_id.to_string()))
+}
+
+/// Whether the connection was established by accepting the invitation of the counterparty.
+fn _is_invitee(data: &Value) -> bool {
+    let their_verkey = data["their_pw_verkey"].as_str().unwrap_or_default();
+    !their_verkey.is_empty() && data["invite_detail"]["senderDetail"]["verKey"].as_str() == Some(their_verkey)
+}
+
+fn _thread_id(data: &Value) -> String {
+    data["thread"]["thid"].as_str()
+        .or(data["msg_uid"].as_str())
+        .filter(|thread_id| !thread_id.is_empty())
+        .map(String::from)
+        .unwrap_or_else(uuid::uuid)
+}
+
+fn _check_accepted(object: &str, data: &Value) -> VcxResult<()> {
+    match _state(data)? {
+        state if state == VcxStateType::VcxStateAccepted as u64 => Ok(()),
+        state => Err(_not_supported(object, state))
+    }
+}
+
+fn _state(data: &Value) -> VcxResult<u64> {
+    data["state"].as_u64()
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, "Legacy object has no state"))
+}
+
+fn _str(data: &Value, field: &str) -> VcxResult<String> {
+    data[field].as_str()
+        .map(String::from)
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Legacy object has no `{}` field", field)))
+}
+
+fn _not_supported(object: &str, state: u64) -> VcxError {
+    VcxError::from_msg(VcxErrorKind::ActionNotSupported,
+                       format!("Pre-Aries {} in state {} cannot be migrated, the exchange has to be started again", object, state))
+}
@@ -0,0 +1,347 @@
+use std::collections::HashMap;
+
+use serde_json;
+use serde_json::Value;
+
+use aries::handlers::issuance::protocol::IssuanceProtocol;
+use aries::handlers::proof_presentation::protocol::PresentationProtocol;
+use dispatcher::ObjectType;
+use error::prelude::*;
+
+mod legacy;
+
+/*
+    Every serialized connection, credential and proof carries the version of its schema in the `version` field.
+    When the state structs change, the version of the object type is bumped and a migration upgrading
+    the previous JSON form is registered here, so blobs stored by applications keep loading.
+
+    Migrations operate on plain JSON and are chained until the current version is reached.
+    Pre-Aries objects (`1.0`, pending `3.0` and blobs without version) are converted by `legacy` migrations.
+
+    Version `4.0` of all the types adds the object history, the negotiated protocol of credentials and proofs
+    and the multi-use flag of connection invitations. Credentials and proofs skip `3.0`, it is taken by pending
+    pre-Aries objects.
+*/
+
+pub type Migrate = fn(Value) -> VcxResult<Value>;
+
+struct Migration {
+    from: &'static str,
+    to: &'static str,
+    migrate: Migrate,
+}
+
+/// Schema of the serialized object type: its current version and migrations from the previous ones.
+pub struct Schema {
+    pub version: &'static str,
+    migrations: Vec<Migration>,
+}
+
+impl Schema {
+    fn new(version: &'static str) -> Schema {
+        Schema { version, migrations: Vec::new() }
+    }
+
+    fn migration(mut self, from: &'static str, to: &'static str, migrate: Migrate) -> Schema {
+        self.migrations.push(Migration { from, to, migrate });
+        self
+    }
+
+    fn find(&self, version: &str) -> Option<&Migration> {
+        self.migrations.iter().find(|migration| migration.from == version)
+    }
+}
+
+lazy_static! {
+    static ref REGISTRY: HashMap<ObjectType, Schema> = {
+        let mut registry = HashMap::new();
+
+        registry.insert(ObjectType::Connection, Schema::new("4.0")
+            .migration("1.0", "3.0", legacy::connection)
+            .migration("2.0", "3.0", _connection_v2)
+            .migration("3.0", "4.0", _connection_v3));
+
+        registry.insert(ObjectType::Credential, Schema::new("4.0")
+            .migration("3.0", "4.0", legacy::pending_credential)
+            .migration("1.0", "2.0", legacy::credential)
+            .migration("2.0", "4.0", _holder_v2));
+
+        registry.insert(ObjectType::DisclosedProof, Schema::new("4.0")
+            .migration("3.0", "4.0", legacy::pending_disclosed_proof)
+            .migration("1.0", "2.0", legacy::disclosed_proof)
+            .migration("2.0", "4.0", _prover_v2));
+
+        registry.insert(ObjectType::IssuerCredential, Schema::new("4.0")
+            .migration("1.0", "2.0", legacy::issuer_credential)
+            .migration("2.0", "4.0", _issuer_v2));
+
+        registry.insert(ObjectType::Proof, Schema::new("4.0")
+            .migration("1.0", "2.0", legacy::proof)
+            .migration("2.0", "4.0", _verifier_v2));
+
+        registry
+    };
+}
+
+pub fn schema(type_: ObjectType) -> &'static Schema {
+    &REGISTRY[&type_]
+}
+
+/// Current schema version of the serialized object type.
+pub fn current_version(type_: ObjectType) -> &'static str {
+    schema(type_).version
+}
+
+/**
+Parses serialized object and upgrades it to the current version of its schema.
+Objects without version are considered to be pre-Aries objects serialized before versioning was introduced.
+ */
+pub fn upgrade(type_: ObjectType, serialized: &str) -> VcxResult<Value> {
+    let mut object: Value = serde_json::from_str(serialized)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot deserialize {:?}: {:?}", type_, err)))?;
+
+    if object.get("version").is_none() {
+        object = json!({"version": legacy::VERSION, "data": object});
+    }
+
+    let schema = schema(type_);
+
+    // every migration is applied at most once, which guards against cycles in the registry
+    for _ in 0..=schema.migrations.len() {
+        let version = object["version"].as_str()
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Invalid version of serialized {:?}: {}", type_, object["version"])))?
+            .to_string();
+
+        if version == schema.version {
+            return Ok(object);
+        }
+
+        let migration = schema.find(&version)
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Unsupported version of serialized {:?}: {}", type_, version)))?;
+
+        debug!("upgrading serialized {:?} from version {} to {}", type_, migration.from, migration.to);
+
+        object = (migration.migrate)(object)?;
+        object["version"] = json!(migration.to);
+    }
+
+    Err(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Cannot upgrade serialized {:?} to version {}", type_, schema.version)))
+}
+
+/// Aries connection serialized before `source_id` was added.
+fn _connection_v2(mut object: Value) -> VcxResult<Value> {
+    object["source_id"] = json!(object["source_id"].as_str().unwrap_or_default());
+    Ok(object)
+}
+
+/// Aries connection serialized before history and multi-use invitations were added.
+fn _connection_v3(mut object: Value) -> VcxResult<Value> {
+    _set_default(&mut object, "history", json!([]));
+
+    for state in vec!["/state/Inviter/Null", "/state/Inviter/Invited"] {
+        if let Some(state) = object.pointer_mut(state) {
+            _set_default(state, "multi_use", json!(false));
+        }
+    }
+
+    Ok(object)
+}
+
+fn _holder_v2(mut object: Value) -> VcxResult<Value> {
+    _state_machine_v2(&mut object, "holder_sm", json!(IssuanceProtocol::default()))?;
+    Ok(object)
+}
+
+fn _issuer_v2(mut object: Value) -> VcxResult<Value> {
+    _state_machine_v2(&mut object, "issuer_sm", json!(IssuanceProtocol::default()))?;
+
+    if let Some(state) = object.pointer_mut("/data/issuer_sm/state/OfferSent") {
+        _set_default(state, "expires_time", Value::Null);
+    }
+
+    Ok(object)
+}
+
+fn _prover_v2(mut object: Value) -> VcxResult<Value> {
+    _state_machine_v2(&mut object, "prover_sm", json!(PresentationProtocol::default()))?;
+    Ok(object)
+}
+
+fn _verifier_v2(mut object: Value) -> VcxResult<Value> {
+    _state_machine_v2(&mut object, "verifier_sm", json!(PresentationProtocol::default()))?;
+    Ok(object)
+}
+
+/// Aries state machines serialized before history and protocol versions were added used 1.0 protocols only.
+fn _state_machine_v2(object: &mut Value, name: &str, protocol: Value) -> VcxResult<()> {
+    let state_machine = object.pointer_mut(&format!("/data/{}", name))
+        .filter(|state_machine| state_machine.is_object())
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, format!("Serialized object has no `{}`", name)))?;
+
+    _set_default(state_machine, "protocol", protocol);
+    _set_default(state_machine, "history", json!([]));
+    Ok(())
+}
+
+fn _set_default(object: &mut Value, field: &str, value: Value) {
+    if let Some(object) = object.as_object_mut() {
+        object.entry(field).or_insert(value);
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use api::{ProofStateType, VcxStateType};
+    use connection;
+    use credential;
+    use disclosed_proof;
+    use issuer_credential;
+    use proof;
+    use utils::constants::{DEFAULT_CONNECTION, PROOF_OFFER_SENT};
+    use utils::devsetup::{SetupAriesMocks, SetupEmpty};
+    use utils::mockdata::mockdata_connection::CONNECTION_SM_INVITEE_COMPLETED;
+    use utils::mockdata::mockdata_credex::{CREDENTIAL_ISSUER_SM_OFFER_SENT, CREDENTIAL_SM_OFFER_RECEIVED};
+    use utils::mockdata::mockdata_proof::SERIALIZIED_PROOF_INITIATED;
+    use utils::mockdata::mockdata_legacy::*;
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_upgrade_keeps_current_version() {
+        let _setup = SetupAriesMocks::init();
+
+        let handle = connection::from_string(CONNECTION_SM_INVITEE_COMPLETED).unwrap();
+        let serialized = connection::to_string(handle).unwrap();
+
+        let object = upgrade(ObjectType::Connection, &serialized).unwrap();
+        assert_eq!(serde_json::from_str::<Value>(&serialized).unwrap(), object);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_upgrade_fails_for_unknown_version() {
+        let _setup = SetupEmpty::init();
+
+        let object = json!({"version": "9.0", "data": {}}).to_string();
+        assert_eq!(VcxErrorKind::InvalidJson, upgrade(ObjectType::Proof, &object).unwrap_err().kind());
+
+        let object = json!({"version": 2, "data": {}}).to_string();
+        assert_eq!(VcxErrorKind::InvalidJson, upgrade(ObjectType::Proof, &object).unwrap_err().kind());
+
+        assert_eq!(VcxErrorKind::InvalidJson, upgrade(ObjectType::Proof, "invalid").unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_upgrade_connection_v2() {
+        let _setup = SetupEmpty::init();
+
+        let mut object: Value = serde_json::from_str(CONNECTION_SM_INVITEE_COMPLETED).unwrap();
+        object["version"] = json!("2.0");
+
+        let upgraded = upgrade(ObjectType::Connection, &object.to_string()).unwrap();
+        assert_eq!("4.0", upgraded["version"]);
+        assert_eq!(object["source_id"], upgraded["source_id"]);
+        assert_eq!(json!([]), upgraded["history"]);
+
+        object.as_object_mut().unwrap().remove("source_id");
+
+        let upgraded = upgrade(ObjectType::Connection, &object.to_string()).unwrap();
+        assert_eq!("", upgraded["source_id"]);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_upgrade_fills_fields_added_later() {
+        let _setup = SetupEmpty::init();
+
+        let object = upgrade(ObjectType::Credential, CREDENTIAL_SM_OFFER_RECEIVED).unwrap();
+        assert_eq!("4.0", object["version"]);
+        assert_eq!(json!(IssuanceProtocol::V1), object["data"]["holder_sm"]["protocol"]);
+        assert_eq!(json!([]), object["data"]["holder_sm"]["history"]);
+
+        let object = upgrade(ObjectType::IssuerCredential, CREDENTIAL_ISSUER_SM_OFFER_SENT).unwrap();
+        assert!(object["data"]["issuer_sm"]["state"]["OfferSent"].get("expires_time").is_some());
+
+        let object = upgrade(ObjectType::Proof, SERIALIZIED_PROOF_INITIATED).unwrap();
+        assert_eq!(json!(PresentationProtocol::V1), object["data"]["verifier_sm"]["protocol"]);
+
+        let object = json!({"version": "2.0", "data": {}}).to_string();
+        assert_eq!(VcxErrorKind::InvalidJson, upgrade(ObjectType::DisclosedProof, &object).unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_every_schema_is_registered() {
+        for type_ in vec![ObjectType::Connection, ObjectType::Credential, ObjectType::DisclosedProof, ObjectType::IssuerCredential, ObjectType::Proof] {
+            let schema = schema(type_);
+            assert!(schema.find(schema.version).is_none());
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_legacy_connection_is_migrated() {
+        let _setup = SetupAriesMocks::init();
+
+        let handle = connection::from_string(LEGACY_CONNECTION_INVITEE_ACCEPTED).unwrap();
+        assert_eq!(VcxStateType::VcxStateAccepted as u32, connection::get_state(handle));
+        assert_eq!("alice-legacy", connection::get_source_id(handle).unwrap());
+        assert_eq!("WRUzXXuFVTYkT8CjSZpFvT", connection::get_their_pw_did(handle).unwrap());
+
+        let serialized: Value = serde_json::from_str(&connection::to_string(handle).unwrap()).unwrap();
+        assert_eq!(current_version(ObjectType::Connection), serialized["version"]);
+
+        let handle = connection::from_string(DEFAULT_CONNECTION).unwrap();
+        assert_eq!(VcxStateType::VcxStateInitialized as u32, connection::get_state(handle));
+
+        let handle = connection::from_string(CONNECTION_V2_INVITER_NULL).unwrap();
+        assert_eq!(VcxStateType::VcxStateInitialized as u32, connection::get_state(handle));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_legacy_credentials_are_migrated() {
+        let _setup = SetupAriesMocks::init();
+
+        let handle = credential::from_string(LEGACY_CREDENTIAL_ACCEPTED).unwrap();
+        assert_eq!(VcxStateType::VcxStateAccepted as u32, credential::get_state(handle).unwrap());
+        assert_eq!("credential-legacy", credential::get_source_id(handle).unwrap());
+
+        let serialized: Value = serde_json::from_str(&credential::to_string(handle).unwrap()).unwrap();
+        assert_eq!(current_version(ObjectType::Credential), serialized["version"]);
+
+        let handle = issuer_credential::from_string(LEGACY_ISSUER_CREDENTIAL_ACCEPTED).unwrap();
+        assert_eq!(VcxStateType::VcxStateAccepted as u32, issuer_credential::get_state(handle).unwrap());
+        assert_eq!("issuer-credential-legacy", issuer_credential::get_source_id(handle).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_legacy_proofs_are_migrated() {
+        let _setup = SetupAriesMocks::init();
+
+        let handle = proof::from_string(LEGACY_PROOF_ACCEPTED).unwrap();
+        assert_eq!(VcxStateType::VcxStateAccepted as u32, proof::get_state(handle).unwrap());
+        assert_eq!(ProofStateType::ProofValidated as u32, proof::get_proof_state(handle).unwrap());
+
+        let serialized: Value = serde_json::from_str(&proof::to_string(handle).unwrap()).unwrap();
+        assert_eq!(current_version(ObjectType::Proof), serialized["version"]);
+
+        let handle = disclosed_proof::from_string(LEGACY_DISCLOSED_PROOF_ACCEPTED).unwrap();
+        assert_eq!(VcxStateType::VcxStateAccepted as u32, disclosed_proof::get_state(handle).unwrap());
+        assert_eq!("disclosed-proof-legacy", disclosed_proof::get_source_id(handle).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_unfinished_legacy_objects_are_not_migrated() {
+        let _setup = SetupAriesMocks::init();
+
+        assert_eq!(VcxErrorKind::ActionNotSupported, connection::from_string(LEGACY_CONNECTION_INVITER_ACCEPTED).unwrap_err().kind());
+        assert_eq!(VcxErrorKind::ActionNotSupported, credential::from_string(LEGACY_CREDENTIAL_PENDING).unwrap_err().kind());
+        assert_eq!(VcxErrorKind::ActionNotSupported, upgrade(ObjectType::DisclosedProof, LEGACY_CREDENTIAL_PENDING).unwrap_err().kind());
+        assert_eq!(VcxErrorKind::ActionNotSupported, proof::from_string(PROOF_OFFER_SENT).unwrap_err().kind());
+    }
+}
@@ -56,6 +56,17 @@ pub static CONFIG_DID_METHOD: &str = "did_method";
 pub static COMMUNICATION_METHOD: &str = "communication_method";
 // proprietary or aries
 pub static CONFIG_ACTORS: &str = "actors";
+pub static CONFIG_MEDIATOR: &str = "mediator";
+pub static CONFIG_INBOUND_ENDPOINT: &str = "inbound_endpoint";
+pub static CONFIG_INBOUND_ADDRESS: &str = "inbound_address";
+pub static CONFIG_PERSIST_OBJECTS: &str = "persist_objects";
+// seconds by protocol family, e.g. {"issue-credential": 86400}
+pub static CONFIG_PROTOCOL_TIMEOUTS: &str = "protocol_timeouts";
+pub static CONFIG_OUTBOX: &str = "outbox";
+// maximum number of messages a router queues for a single recipient key
+pub static CONFIG_ROUTER_QUEUE_LIMIT: &str = "router_queue_limit";
+// sink of `~trace` reports: "log", "file://<path>" or http(s) URL
+pub static CONFIG_TRACE_TARGET: &str = "trace_target";
 
 pub static DEFAULT_PROTOCOL_VERSION: usize = 2;
 pub static MAX_SUPPORTED_PROTOCOL_VERSION: usize = 2;
@@ -66,6 +77,7 @@ pub static DEFAULT_WALLET_NAME: &str = "LIBVCX_SDK_WALLET";
 pub static DEFAULT_POOL_NAME: &str = "pool1";
 pub static DEFAULT_LINK_SECRET_ALIAS: &str = "main";
 pub static DEFAULT_DEFAULT: &str = "default";
+pub static DEFAULT_TRACE_TARGET: &str = "log";
 pub static DEFAULT_URL: &str = "http://127.0.0.1:8080";
 pub static DEFAULT_DID: &str = "2hoqvcwupRTUNkXn6ArYzs";
 pub static DEFAULT_VERKEY: &str = "FuN98eH2eZybECWkofW6A9BKJxxnTatBCopfUiNxo6ZB";
@@ -162,8 +174,12 @@ pub fn validate_config(config: &HashMap<String, String>) -> VcxResult<u32> {
     validate_optional_config_val(config.get(CONFIG_INSTITUTION_LOGO_URL), VcxErrorKind::InvalidUrl, Url::parse)?;
 
     validate_optional_config_val(config.get(CONFIG_WEBHOOK_URL), VcxErrorKind::InvalidUrl, Url::parse)?;
+    validate_optional_config_val(config.get(CONFIG_INBOUND_ENDPOINT), VcxErrorKind::InvalidUrl, Url::parse)?;
 
     validate_optional_config_val(config.get(CONFIG_ACTORS), VcxErrorKind::InvalidOption, validation::validate_actors)?;
+    validate_optional_config_val(config.get(CONFIG_PROTOCOL_TIMEOUTS), VcxErrorKind::InvalidOption, validation::validate_protocol_timeouts)?;
+    validate_optional_config_val(config.get(CONFIG_TRACE_TARGET), VcxErrorKind::InvalidOption, ::tracing::TraceTarget::parse)?;
+    validate_optional_config_val(config.get(CONFIG_ROUTER_QUEUE_LIMIT), VcxErrorKind::InvalidOption, str::parse::<usize>)?;
 
     Ok(error::SUCCESS.code_num)
 }
@@ -402,6 +418,18 @@ pub fn get_actors() -> Vec<Actors> {
         ).unwrap_or_else(|_| Actors::iter().collect())
 }
 
+/// Seconds after which exchanges of the protocol family (e.g. `issue-credential`) expire, if configured.
+pub fn get_protocol_timeout(protocol: &str) -> Option<u64> {
+    get_config_value(CONFIG_PROTOCOL_TIMEOUTS).ok()
+        .and_then(|timeouts| validation::validate_protocol_timeouts(&timeouts).ok())
+        .and_then(|timeouts| timeouts.get(protocol).cloned())
+}
+
+/// Target of trace reports of messages traced without a target set on their connection.
+pub fn get_trace_target() -> String {
+    get_config_value(CONFIG_TRACE_TARGET).unwrap_or(DEFAULT_TRACE_TARGET.to_string())
+}
+
 #[derive(Clone, Serialize, Deserialize, Debug, Eq, PartialEq, EnumIter)]
 #[serde(rename_all = "lowercase")]
 pub enum Actors {
@@ -413,6 +441,8 @@ pub enum Actors {
     Verifier,
     Sender,
     Receiver,
+    Mediator,
+    Recipient,
 }
 
 pub const ARIES_COMMUNICATION_METHOD: &str = "aries";
@@ -716,4 +746,21 @@ pub mod tests {
         config["actors"] = json!(["wrong"]);
         assert_eq!(process_config_string(&config.to_string(), true).unwrap_err().kind(), VcxErrorKind::InvalidOption);
     }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_process_config_str_for_protocol_timeouts() {
+        let _setup = SetupDefaults::init();
+
+        let mut config = base_config();
+        config["protocol_timeouts"] = json!({"issue-credential": 86400});
+
+        process_config_string(&config.to_string(), true).unwrap();
+
+        assert_eq!(Some(86400), get_protocol_timeout("issue-credential"));
+        assert_eq!(None, get_protocol_timeout("present-proof"));
+
+        config["protocol_timeouts"] = json!({"issue-credential": -1});
+        assert_eq!(process_config_string(&config.to_string(), true).unwrap_err().kind(), VcxErrorKind::InvalidOption);
+    }
 }
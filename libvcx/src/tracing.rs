@@ -0,0 +1,363 @@
+use std::collections::HashMap;
+use std::fs::OpenOptions;
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::Mutex;
+
+use chrono::{DateTime, SecondsFormat, Utc};
+use reqwest;
+use reqwest::header::CONTENT_TYPE;
+use serde_json::{self, Value};
+use url::Url;
+
+use aries::messages::a2a::A2AMessage;
+use aries::messages::trace::{Trace, TraceReport};
+use error::prelude::*;
+use replay;
+use settings;
+use utils::threadpool::spawn;
+use utils::timeout::TimeoutUtils;
+
+/*
+    Message tracing (`~trace` decorator, RFC 0034).
+
+    A message is traced when tracing is enabled on its connection or when it carries `~trace` decorator.
+    Every hop of a traced message (pack, forward wrap, send, receive, state transition) is reported
+    to the target set on the connection, or to the one configured by `trace_target` for messages traced on their own.
+    The target is "log", "file://<absolute path>" (JSON line per report appended) or http(s) URL (JSON report posted).
+
+    Outbound messages of a traced connection are decorated with `~trace` to ask the counterparty to trace them too.
+    Only http(s) target is shared with the counterparty, a local one is replaced by "log", so the counterparty
+    reports to its own log. The target in `~trace` of an inbound message is never written to nor called,
+    the reports go to our own target.
+
+    A received message is remembered until it is handled, so its transition report carries time spent since receipt.
+*/
+
+pub const TRACE: &str = "~trace";
+
+const MAX_PENDING_TRANSITIONS: usize = 1000;
+
+lazy_static! {
+    // target and time of receipt of traced messages waiting to be handled, by `@id`
+    static ref PENDING_TRANSITIONS: Mutex<HashMap<String, (String, DateTime<Utc>)>> = Default::default();
+}
+
+#[derive(Debug, Clone, PartialEq)]
+pub enum TraceTarget {
+    Log,
+    File(PathBuf),
+    Http(String),
+}
+
+impl TraceTarget {
+    pub fn parse(target: &str) -> VcxResult<TraceTarget> {
+        if target == "log" {
+            return Ok(TraceTarget::Log);
+        }
+
+        let url = Url::parse(target)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidOption, format!("Invalid trace target {:?}: {}", target, err)))?;
+
+        match url.scheme() {
+            "file" => url.to_file_path()
+                .map(TraceTarget::File)
+                .map_err(|_| VcxError::from_msg(VcxErrorKind::InvalidOption, format!("Trace target {:?} is not absolute file path", target))),
+            "http" | "https" => Ok(TraceTarget::Http(target.to_string())),
+            scheme => Err(VcxError::from_msg(VcxErrorKind::InvalidOption, format!("Unsupported scheme of trace target: {:?}", scheme)))
+        }
+    }
+
+    fn send(&self, report: &TraceReport) -> VcxResult<()> {
+        let report = serde_json::to_string(report)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::SerializationError, format!("Cannot serialize trace report: {:?}", err)))?;
+
+        match self {
+            TraceTarget::Log => {
+                info!("Trace report: {}", report);
+                Ok(())
+            }
+            TraceTarget::File(path) => {
+                OpenOptions::new().create(true).append(true).open(path)
+                    .and_then(|mut file| writeln!(file, "{}", report))
+                    .map_err(|err| VcxError::from_msg(VcxErrorKind::IOError, format!("Cannot write trace report to {:?}: {}", path, err)))
+            }
+            TraceTarget::Http(url) => {
+                let url = url.clone();
+
+                spawn(move || {
+                    if let Err(err) = _post(&url, report) {
+                        warn!("Cannot post trace report to {}: {}", url, err);
+                    }
+                    Ok(())
+                });
+
+                Ok(())
+            }
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Hop {
+    Pack,
+    Forward,
+    Send,
+    Receive,
+    Transition,
+}
+
+impl Hop {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            Hop::Pack => "pack",
+            Hop::Forward => "forward",
+            Hop::Send => "send",
+            Hop::Receive => "receive",
+            Hop::Transition => "transition",
+        }
+    }
+}
+
+/// `~trace` decorator of the message.
+pub fn get_trace(message: &Value) -> Option<Trace> {
+    message.get(TRACE).and_then(|trace| serde_json::from_value(trace.clone()).ok())
+}
+
+/**
+Target of reports of the message: the one set on its connection,
+or the configured one if the message carries `~trace` decorator.
+ */
+pub fn target(connection_target: Option<&str>, message: &Value) -> Option<String> {
+    connection_target.map(String::from)
+        .or_else(|| get_trace(message).map(|_| settings::get_trace_target()))
+}
+
+/**
+Adds `~trace` decorator to the message, unless it is traced already.
+The target is shared only if it is http(s) URL, otherwise the decorator asks for reports to "log".
+ */
+pub fn decorate(message: &A2AMessage, target: &str) -> VcxResult<A2AMessage> {
+    let target = match TraceTarget::parse(target) {
+        Ok(TraceTarget::Http(_)) => target,
+        _ => "log"
+    };
+
+    let mut message = json!(message);
+
+    {
+        let object = message.as_object_mut()
+            .ok_or(VcxError::from_msg(VcxErrorKind::InvalidJson, "Message is not JSON object"))?;
+
+        if !object.contains_key(TRACE) {
+            object.insert(TRACE.to_string(), json!(Trace::create(target)));
+        }
+    }
+
+    Ok(A2AMessage::Generic(message))
+}
+
+/// Runs the hop of the message and reports its duration and outcome if the message is traced.
+pub fn traced<T, F>(target: Option<&str>, message: &A2AMessage, hop: Hop, hop_fn: F) -> VcxResult<T>
+    where F: FnOnce() -> VcxResult<T> {
+    traced_with_outcome(target, message, hop, hop_fn, |_| String::from("OK"))
+}
+
+/// Same as `traced`, with the outcome of successful hop taken from its result.
+pub fn traced_with_outcome<T, F, O>(target: Option<&str>, message: &A2AMessage, hop: Hop, hop_fn: F, outcome_fn: O) -> VcxResult<T>
+    where F: FnOnce() -> VcxResult<T>, O: FnOnce(&T) -> String {
+    let target = match target {
+        Some(target) => target,
+        None => return hop_fn()
+    };
+
+    let started = Utc::now();
+    let result = hop_fn();
+
+    let outcome = match result {
+        Ok(ref value) => outcome_fn(value),
+        Err(ref err) => format!("FAIL: {}", err)
+    };
+
+    report(target, &json!(message), hop, started, &outcome);
+
+    result
+}
+
+/// Sends report of the hop started at the given time to the target. Failures are only logged.
+pub fn report(target: &str, message: &Value, hop: Hop, started: DateTime<Utc>, outcome: &str) {
+    let report = build_report(message, hop, started, outcome);
+
+    if let Err(err) = TraceTarget::parse(target).and_then(|target| target.send(&report)) {
+        warn!("Cannot report {} of message {} to {}: {}", report.hop, report.msg_id, target, err);
+    }
+}
+
+/// Remembers the received traced message, so its transition is reported once it is handled.
+pub fn received(target: &str, message: &Value, received: DateTime<Utc>) {
+    let id = match message["@id"].as_str() {
+        Some(id) => id.to_string(),
+        None => return
+    };
+
+    let mut pending = PENDING_TRANSITIONS.lock().unwrap();
+
+    if pending.len() >= MAX_PENDING_TRANSITIONS {
+        let oldest = pending.iter()
+            .min_by_key(|(_, (_, time))| *time)
+            .map(|(id, _)| id.clone());
+
+        if let Some(oldest) = oldest {
+            pending.remove(&oldest);
+        }
+    }
+
+    pending.insert(id, (target.to_string(), received));
+}
+
+/// Reports transition of the state machine handling the message, if the message or its connection is traced.
+pub fn processed(connection_target: Option<&str>, message: &A2AMessage) {
+    let id = match replay::message_id(message) {
+        Some(id) => id,
+        None => return
+    };
+
+    let pending = PENDING_TRANSITIONS.lock().unwrap().remove(&id);
+
+    let (target, started) = match (pending, connection_target) {
+        (Some(pending), _) => pending,
+        (None, Some(target)) => (target.to_string(), Utc::now()),
+        (None, None) => return
+    };
+
+    report(&target, &json!(message), Hop::Transition, started, "OK");
+}
+
+fn build_report(message: &Value, hop: Hop, started: DateTime<Utc>, outcome: &str) -> TraceReport {
+    let msg_id = message["@id"].as_str().unwrap_or_default();
+    let thread_id = message["~thread"]["thid"].as_str().unwrap_or(msg_id);
+
+    let mut report = TraceReport::create(msg_id, thread_id);
+    report.traced_type = message["@type"].as_str().map(String::from);
+    report.handler = settings::get_config_value(settings::CONFIG_INSTITUTION_NAME).unwrap_or(String::from("libvcx"));
+    report.hop = hop.as_str().to_string();
+    report.ellapsed_milli = (Utc::now() - started).num_milliseconds().max(0) as u64;
+    report.timestamp = started.timestamp_millis().to_string();
+    report.str_time = started.to_rfc3339_opts(SecondsFormat::Millis, true);
+    report.outcome = outcome.to_string();
+    report
+}
+
+fn _post(url: &str, report: String) -> VcxResult<()> {
+    let client = reqwest::ClientBuilder::new().timeout(TimeoutUtils::short_timeout()).build()
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::PostMessageFailed, format!("Building reqwest client failed: {:?}", err)))?;
+
+    let response = client.post(url)
+        .body(report)
+        .header(CONTENT_TYPE, "application/json")
+        .send()
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::PostMessageFailed, format!("Could not connect {:?}", err)))?;
+
+    if !response.status().is_success() {
+        return Err(VcxError::from_msg(VcxErrorKind::PostMessageFailed, format!("POST failed with status {}", response.status())));
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::fs;
+
+    use aries::messages::ack::tests::_ack;
+    use utils::devsetup::SetupDefaults;
+    use utils::get_temp_dir_path;
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_parse_trace_target() {
+        assert_eq!(TraceTarget::Log, TraceTarget::parse("log").unwrap());
+        assert_eq!(TraceTarget::File(PathBuf::from("/tmp/trace.log")), TraceTarget::parse("file:///tmp/trace.log").unwrap());
+        assert_eq!(TraceTarget::Http(String::from("https://example.org/trace")), TraceTarget::parse("https://example.org/trace").unwrap());
+
+        assert_eq!(VcxErrorKind::InvalidOption, TraceTarget::parse("console").unwrap_err().kind());
+        assert_eq!(VcxErrorKind::InvalidOption, TraceTarget::parse("ws://example.org/trace").unwrap_err().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_decorate_and_resolve_target() {
+        let _setup = SetupDefaults::init();
+
+        let message = _ack().to_a2a_message();
+        assert_eq!(None, target(None, &json!(message)));
+        assert_eq!(Some(String::from("file:///tmp/trace.log")), target(Some("file:///tmp/trace.log"), &json!(message)));
+
+        let message = decorate(&message, "https://example.org/trace").unwrap();
+        assert_eq!(Some(Trace::create("https://example.org/trace")), get_trace(&json!(message)));
+        assert_eq!(Some(String::from("log")), target(None, &json!(message)));
+
+        let message = decorate(&message, "log").unwrap();
+        assert_eq!(Some(Trace::create("https://example.org/trace")), get_trace(&json!(message)));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_decorate_does_not_share_local_target() {
+        let message = _ack().to_a2a_message();
+
+        let decorated = decorate(&message, "file:///tmp/trace.log").unwrap();
+        assert_eq!(Some(Trace::create("log")), get_trace(&json!(decorated)));
+
+        let decorated = decorate(&message, "log").unwrap();
+        assert_eq!(Some(Trace::create("log")), get_trace(&json!(decorated)));
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_build_report() {
+        let _setup = SetupDefaults::init();
+
+        let message = json!(_ack().to_a2a_message());
+        let report = build_report(&message, Hop::Send, Utc::now(), "OK");
+
+        assert_eq!("testid", report.msg_id);
+        assert_eq!(message["~thread"]["thid"].as_str().unwrap(), report.thread_id);
+        assert_eq!(message["@type"].as_str().map(String::from), report.traced_type);
+        assert_eq!("send", report.hop);
+        assert_eq!("OK", report.outcome);
+        assert_eq!(Some(String::from("testid")), report.thread.thid);
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_reports_are_appended_to_file() {
+        let _setup = SetupDefaults::init();
+
+        let path = get_temp_dir_path("test_trace_reports.log");
+        fs::remove_file(&path).ok();
+        let target = Url::from_file_path(&path).unwrap().to_string();
+
+        let message = _ack().to_a2a_message();
+        traced(Some(&target), &message, Hop::Pack, || Ok(())).unwrap();
+        traced(Some(&target), &message, Hop::Send, || Err::<(), _>(VcxError::from(VcxErrorKind::PostMessageFailed))).unwrap_err();
+        traced_with_outcome(Some(&target), &message, Hop::Send, || Ok(false), |delivered| if *delivered { String::from("OK") } else { String::from("QUEUED") }).unwrap();
+
+        received(&target, &json!(message), Utc::now());
+        processed(None, &message);
+        processed(None, &message);
+
+        let reports: Vec<TraceReport> = fs::read_to_string(&path).unwrap()
+            .lines()
+            .map(|line| serde_json::from_str(line).unwrap())
+            .collect();
+        fs::remove_file(&path).ok();
+
+        assert_eq!(vec!["pack", "send", "send", "transition"], reports.iter().map(|report| report.hop.as_str()).collect::<Vec<&str>>());
+        assert_eq!("OK", reports[0].outcome);
+        assert!(reports[1].outcome.starts_with("FAIL"));
+        assert_eq!("QUEUED", reports[2].outcome);
+    }
+}
@@ -14,7 +14,7 @@ pub static DEFAULT_ACK_CONNECTION_VERSION: ::settings::ProtocolTypes = ::setting
 pub static DEFAULT_CREATE_KEYS_VERSION: ::settings::ProtocolTypes = ::settings::ProtocolTypes::V2;
 pub static DEFAULT_GET_MSG_VERSION: ::settings::ProtocolTypes = ::settings::ProtocolTypes::V2;
 pub static V1_OBJECT_SERIALIZE_VERSION: &str = "1.0";
-pub static V3_OBJECT_SERIALIZE_VERSION: &str = "2.0";
+pub static V3_OBJECT_SERIALIZE_VERSION: &str = "4.0";
 pub static PENDING_OBJECT_SERIALIZE_VERSION: &str = "3.0";
 pub static SCHEMA_TXN: &str = r#"{"seqNo":344,"reqId":1522866729726860308,"identifier":"VsKV7grR1BUE29mG2Fm2kX","txnTime":1516284381,"type":"101","data":{"name":"get schema attrs","version":"1.0","attr_names":["test","get","schema","attrs"]}}"#;
 pub static DEFAULT_SCHEMA_ATTRS: &str = r#"["address1","address2","zip","city","state"]"#;
@@ -170,6 +170,8 @@ pub static REV_STATE_JSON: &str = r#"{"V4SGRU86Z58d6TV7PBUe6f:4:V4SGRU86Z58d6TV7
 pub static REV_REG_JSON: &str = r#"{"ver":"1.0","value":{"accum":"2 0204F2D2B1F2B705A11AAFEEE73C9BA084C12AF1179294529AC4D14CA54E87F3 2 222BAE38FAF2673F7BCBB86D8DE1A327F5065BDC892E9A122164260C97BC0C63 2 1565105F8BA53037978B66E0CC9F53205F189DEEB6B7168744456DD98D2F4E88 2 1AC9E76B2868141A42329778831C14AEAAF7A9981209C1D96AECA4E69CAFB243 2 095E45DDF417D05FB10933FFC63D474548B7FFFF7888802F07FFFFFF7D07A8A8 1 0000000000000000000000000000000000000000000000000000000000000000"}}"#;
 pub static TEST_TAILS_FILE: &str = r#"tails_file"#;
 pub static REQUEST_WITH_ENDORSER: &str = r#"{"seqNo":344,"reqId":1522866729726860308,"identifier":"VsKV7grR1BUE29mG2Fm2kX","txnTime":1516284381,"type":"101","endorser":"V4SGRU86Z58d6TV7PBUe6f"}"#;
+pub static GET_NYM_RESPONSE: &str = r#"{"op":"REPLY","result":{"type":"105","identifier":"V4SGRU86Z58d6TV7PBUe6f","reqId":1580000000000000000,"dest":"V4SGRU86Z58d6TV7PBUe6f","data":"{\"dest\":\"V4SGRU86Z58d6TV7PBUe6f\",\"identifier\":\"V4SGRU86Z58d6TV7PBUe6f\",\"role\":\"0\",\"seqNo\":11,\"txnTime\":1580000000,\"verkey\":\"~CoRER63DVYnWZtK8uAzNbx\"}","seqNo":11,"txnTime":1580000000}}"#;
+pub static GET_ATTRIB_ENDPOINT_RESPONSE: &str = r#"{"op":"REPLY","result":{"type":"104","identifier":"V4SGRU86Z58d6TV7PBUe6f","reqId":1580000000000000001,"dest":"V4SGRU86Z58d6TV7PBUe6f","raw":"endpoint","data":"{\"endpoint\":{\"endpoint\":\"http://localhost:8080\",\"routingKeys\":[]}}","seqNo":12,"txnTime":1580000000}}"#;
 
 pub fn rev_def_json() -> String {
     use utils::get_temp_dir_path;
@@ -18,6 +18,7 @@ use utils::libindy::wallet::init_wallet;
 use utils::logger::LibvcxDefaultLogger;
 use utils::object_cache::ObjectCache;
 use utils::plugins::init_plugin;
+use utils::transport::memory::MemoryTransport;
 use rand::Rng;
 
 pub struct SetupEmpty; // clears settings, setups up logging
@@ -74,6 +75,7 @@ fn tear_down() {
     reset_wallet_handle();
     reset_pool_handle();
     AgencyMockDecrypted::clear_mocks();
+    MemoryTransport::clear();
 }
 
 impl SetupEmpty {
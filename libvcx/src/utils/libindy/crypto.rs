@@ -75,6 +75,8 @@ pub fn unpack_message(msg: &[u8]) -> VcxResult<Vec<u8>> {
 }
 
 pub fn create_key(seed: Option<&str>) -> VcxResult<String> {
+    if settings::indy_mocks_enabled() { return Ok(::utils::constants::VERKEY.to_string()); }
+
     let key_json = json!({"seed": seed}).to_string();
 
     crypto::create_key(::utils::libindy::wallet::get_wallet_handle(), Some(&key_json))
@@ -1,3 +1,5 @@
+extern crate rust_base58;
+
 use std::collections::HashMap;
 
 use futures::Future;
@@ -10,6 +12,11 @@ use settings;
 use utils::libindy::pool::get_pool_handle;
 use utils::libindy::wallet::get_wallet_handle;
 
+use self::rust_base58::{FromBase58, ToBase58};
+
+const DID_SOV_PREFIX: &str = "did:sov:";
+const ENDPOINT_ATTRIB: &str = "endpoint";
+
 pub fn multisign_request(did: &str, request: &str) -> VcxResult<String> {
     ledger::multi_sign_request(get_wallet_handle(), did, request)
         .wait()
@@ -119,6 +126,12 @@ pub fn libindy_build_get_nym_request(submitter_did: Option<&str>, did: &str) ->
         .map_err(VcxError::from)
 }
 
+pub fn libindy_build_get_attrib_request(submitter_did: Option<&str>, target_did: &str, raw: Option<&str>) -> VcxResult<String> {
+    ledger::build_get_attrib_request(submitter_did, target_did, raw, None, None)
+        .wait()
+        .map_err(VcxError::from)
+}
+
 pub mod auth_rule {
     use std::collections::HashMap;
     use std::sync::Mutex;
@@ -360,6 +373,73 @@ pub fn get_role(did: &str) -> VcxResult<String> {
     Ok(role)
 }
 
+pub fn get_attr(did: &str, attr_name: &str) -> VcxResult<String> {
+    if settings::indy_mocks_enabled() { return Ok(::utils::constants::GET_ATTRIB_ENDPOINT_RESPONSE.to_string()); }
+
+    let submitter_did = settings::get_config_value(settings::CONFIG_INSTITUTION_DID)?;
+    let get_attr_req = libindy_build_get_attrib_request(Some(&submitter_did), did, Some(attr_name))?;
+    libindy_submit_request(&get_attr_req)
+}
+
+/// Resolves the verkey of a public DID (either `did:sov:` qualified or not) from its NYM transaction.
+pub fn get_verkey(did: &str) -> VcxResult<String> {
+    let did = unqualified_did(did);
+
+    let get_nym_resp = if settings::indy_mocks_enabled() { ::utils::constants::GET_NYM_RESPONSE.to_string() } else { get_nym(did)? };
+    let data = _parse_get_reply_data(&get_nym_resp)?;
+
+    let verkey = data["verkey"].as_str()
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidLedgerResponse, format!("Ledger NYM for DID {} does not contain verkey", did)))?;
+
+    expand_verkey(did, verkey)
+}
+
+/// Resolves the agent endpoint and routing keys a public DID published in its `endpoint` ATTRIB.
+pub fn get_service_endpoint(did: &str) -> VcxResult<(String, Vec<String>)> {
+    let did = unqualified_did(did);
+
+    let data = _parse_get_reply_data(&get_attr(did, ENDPOINT_ATTRIB)?)?;
+
+    let endpoint = data[ENDPOINT_ATTRIB][ENDPOINT_ATTRIB].as_str()
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidLedgerResponse, format!("Ledger ATTRIB for DID {} does not contain endpoint", did)))?;
+
+    let routing_keys: Vec<String> = data[ENDPOINT_ATTRIB]["routingKeys"].as_array()
+        .map(|keys| keys.iter().filter_map(|key| key.as_str().map(String::from)).collect())
+        .unwrap_or_default();
+
+    Ok((endpoint.to_string(), routing_keys))
+}
+
+pub fn unqualified_did(did: &str) -> &str {
+    did.trim_start_matches(DID_SOV_PREFIX)
+}
+
+/// Abbreviated verkey (`~` prefixed) holds only the second half of the key, the first one is the DID itself.
+fn expand_verkey(did: &str, verkey: &str) -> VcxResult<String> {
+    if !verkey.starts_with('~') {
+        return Ok(verkey.to_string());
+    }
+
+    let mut key = did.from_base58()
+        .map_err(|_| VcxError::from_msg(VcxErrorKind::NotBase58, format!("Invalid DID: {}", did)))?;
+
+    key.extend(verkey[1..].from_base58()
+        .map_err(|_| VcxError::from_msg(VcxErrorKind::NotBase58, format!("Invalid verkey: {}", verkey)))?);
+
+    Ok(key.to_base58())
+}
+
+fn _parse_get_reply_data(response: &str) -> VcxResult<serde_json::Value> {
+    let response: serde_json::Value = serde_json::from_str(response)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidLedgerResponse, format!("{:?}", err)))?;
+
+    let data = response["result"]["data"].as_str()
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidLedgerResponse, format!("Ledger does not contain requested data: {}", response)))?;
+
+    serde_json::from_str(data)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidLedgerResponse, format!("{:?}", err)))
+}
+
 pub fn parse_response(response: &str) -> VcxResult<Response> {
     serde_json::from_str::<Response>(response)
         .to_vcx(VcxErrorKind::InvalidJson, "Cannot deserialize transaction response")
@@ -457,6 +537,28 @@ mod test {
         assert!(_verify_transaction_can_be_endorsed(transaction, "EbP4aYNeTHL6q385GuVpRV").is_err());
     }
 
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_expand_verkey() {
+        assert_eq!("GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL", expand_verkey("V4SGRU86Z58d6TV7PBUe6f", "~CoRER63DVYnWZtK8uAzNbx").unwrap());
+        assert_eq!("GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL", expand_verkey("V4SGRU86Z58d6TV7PBUe6f", "GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL").unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_resolve_public_did_works() {
+        let _setup = SetupMocks::init();
+
+        assert_eq!("GJ1SzoWzavQYfNL9XkaJdrQejfztN4XqdsiV4ct3LXKL", get_verkey("did:sov:V4SGRU86Z58d6TV7PBUe6f").unwrap());
+        assert_eq!((String::from("http://localhost:8080"), vec![]), get_service_endpoint("V4SGRU86Z58d6TV7PBUe6f").unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_resolve_public_did_fails_for_unknown_did() {
+        assert_eq!(VcxErrorKind::InvalidLedgerResponse, _parse_get_reply_data(r#"{"op":"REPLY","result":{"data":null}}"#).unwrap_err().kind());
+    }
+
     #[cfg(feature = "pool_tests")]
     #[test]
     fn test_endorse_transaction() {
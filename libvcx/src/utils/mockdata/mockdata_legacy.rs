@@ -0,0 +1,308 @@
+// Objects serialized by previous releases, cryptographic payloads are shortened.
+
+// Pre-Aries connection of the invitee which accepted the invitation
+pub const LEGACY_CONNECTION_INVITEE_ACCEPTED: &str = r#"
+{
+    "version": "1.0",
+    "data": {
+        "source_id": "alice-legacy",
+        "pw_did": "8XFh8yBzrpJQmNyZzgoTqB",
+        "pw_verkey": "EkVTa7SCJ5SntpYyX7CSb2pcBhiVGT9kWSagA8a9T69A",
+        "state": 4,
+        "uuid": "",
+        "endpoint": "",
+        "invite_detail": {
+            "statusCode": "MS-101",
+            "connReqId": "NjcwOWU",
+            "senderDetail": {
+                "name": "ent-name",
+                "agentKeyDlgProof": {
+                    "agentDID": "U5LXs4U7P9msh647kToezy",
+                    "agentDelegatedKey": "FktSZg8idAVzyQZrdUppK6FTrfAzW3wWVzAjJAfdUvJq",
+                    "signature": "gkVDhwe2/FEtFqJYBm2wbEvqGlBwAGGaC19Oebj/3ZtZ/KpZs7K2JFMgTqTb29xTTAad04AjfNa76931eRa6BA=="
+                },
+                "DID": "WRUzXXuFVTYkT8CjSZpFvT",
+                "logoUrl": "ent-logo-url",
+                "verKey": "ESE6MnqAyjRigduPG454vfLvKhMbmaZjy9vqxCnSKQnp"
+            },
+            "senderAgencyDetail": {
+                "DID": "BDSmVkzxRYGE4HKyMKxd1H",
+                "verKey": "HsaWDKnJtgoBsyqG2zKa5xRvKZzZHhkiCDH7eU3iqRsv",
+                "endpoint": "http://localhost:9001/agency/msg"
+            },
+            "targetName": "there",
+            "statusMsg": "message created"
+        },
+        "invite_url": null,
+        "agent_did": "Gqw6t57yDgzaG79h4HUVCf",
+        "agent_vk": "9drH4FZk79Y4bx5jzPBaJEmB4woEGG1XQSfgF7NkyKvV",
+        "their_pw_did": "WRUzXXuFVTYkT8CjSZpFvT",
+        "their_pw_verkey": "ESE6MnqAyjRigduPG454vfLvKhMbmaZjy9vqxCnSKQnp",
+        "public_did": null,
+        "their_public_did": null,
+        "version": "1.0"
+    }
+}"#;
+
+// Pre-Aries connection of the inviter which accepted the connection request
+pub const LEGACY_CONNECTION_INVITER_ACCEPTED: &str = r#"
+{
+    "version": "1.0",
+    "data": {
+        "source_id": "faber-legacy",
+        "pw_did": "WRUzXXuFVTYkT8CjSZpFvT",
+        "pw_verkey": "ESE6MnqAyjRigduPG454vfLvKhMbmaZjy9vqxCnSKQnp",
+        "state": 4,
+        "uuid": "",
+        "endpoint": "",
+        "invite_detail": {
+            "statusCode": "MS-101",
+            "connReqId": "NjcwOWU",
+            "senderDetail": {
+                "name": "ent-name",
+                "agentKeyDlgProof": {
+                    "agentDID": "U5LXs4U7P9msh647kToezy",
+                    "agentDelegatedKey": "FktSZg8idAVzyQZrdUppK6FTrfAzW3wWVzAjJAfdUvJq",
+                    "signature": "gkVDhwe2/FEtFqJYBm2wbEvqGlBwAGGaC19Oebj/3ZtZ/KpZs7K2JFMgTqTb29xTTAad04AjfNa76931eRa6BA=="
+                },
+                "DID": "WRUzXXuFVTYkT8CjSZpFvT",
+                "logoUrl": "ent-logo-url",
+                "verKey": "ESE6MnqAyjRigduPG454vfLvKhMbmaZjy9vqxCnSKQnp"
+            },
+            "senderAgencyDetail": {
+                "DID": "BDSmVkzxRYGE4HKyMKxd1H",
+                "verKey": "HsaWDKnJtgoBsyqG2zKa5xRvKZzZHhkiCDH7eU3iqRsv",
+                "endpoint": "http://localhost:9001/agency/msg"
+            },
+            "targetName": "there",
+            "statusMsg": "message created"
+        },
+        "invite_url": null,
+        "agent_did": "U5LXs4U7P9msh647kToezy",
+        "agent_vk": "FktSZg8idAVzyQZrdUppK6FTrfAzW3wWVzAjJAfdUvJq",
+        "their_pw_did": "8XFh8yBzrpJQmNyZzgoTqB",
+        "their_pw_verkey": "EkVTa7SCJ5SntpYyX7CSb2pcBhiVGT9kWSagA8a9T69A",
+        "public_did": null,
+        "their_public_did": null,
+        "version": "1.0"
+    }
+}"#;
+
+// Aries connection serialized before source id was stored
+pub const CONNECTION_V2_INVITER_NULL: &str = r#"
+{
+    "version": "2.0",
+    "data": {
+        "pw_did": "2ZHFFhzA2XtTD6hJqzL7ux",
+        "pw_vk": "rCw3x5h1jS6gPo7rRrt3EYbXXe5nNjnGbdf1jAwUxuj",
+        "agent_did": "EZrZyu4bfydm4ByNm56kPP",
+        "agent_vk": "8Ps2WosJ9AV1eXPoJKsEJdM3NchPhSyS8qFt6LQUTKv2"
+    },
+    "state": {
+        "Inviter": {
+            "Null": {}
+        }
+    }
+}"#;
+
+// Pre-Aries credential which was received and stored in the wallet
+pub const LEGACY_CREDENTIAL_ACCEPTED: &str = r#"
+{
+    "version": "1.0",
+    "data": {
+        "source_id": "credential-legacy",
+        "state": 4,
+        "credential_name": "credential_name",
+        "credential_request": null,
+        "credential_offer": null,
+        "msg_uid": "ntc2ytb",
+        "agent_did": "U5LXs4U7P9msh647kToezy",
+        "agent_vk": "FktSZg8idAVzyQZrdUppK6FTrfAzW3wWVzAjJAfdUvJq",
+        "my_did": "8XFh8yBzrpJQmNyZzgoTqB",
+        "my_vk": "EkVTa7SCJ5SntpYyX7CSb2pcBhiVGT9kWSagA8a9T69A",
+        "their_did": "WRUzXXuFVTYkT8CjSZpFvT",
+        "their_vk": "ESE6MnqAyjRigduPG454vfLvKhMbmaZjy9vqxCnSKQnp",
+        "credential": "{\"libindy_cred\":\"{\\\"schema_id\\\":\\\"2hoqvcwupRTUNkXn6ArYzs:2:schema_name:0.0.11\\\",\\\"cred_def_id\\\":\\\"2hoqvcwupRTUNkXn6ArYzs:3:CL:1766\\\",\\\"rev_reg_id\\\":null,\\\"values\\\":{\\\"age\\\":{\\\"raw\\\":\\\"111\\\",\\\"encoded\\\":\\\"111\\\"},\\\"name\\\":{\\\"raw\\\":\\\"Bob\\\",\\\"encoded\\\":\\\"93006290325627508022776103386395994712401809437930957652111221015872244345185\\\"}},\\\"signature\\\":{\\\"p_credential\\\":{\\\"m_2\\\":\\\"31700338570294708736115754102769589522052428093121126330650183539696104868123\\\"},\\\"r_credential\\\":null},\\\"signature_correctness_proof\\\":{\\\"c\\\":\\\"7209681799349211936664438159371869376664205836919843273264901864121026304303\\\"},\\\"rev_reg\\\":null,\\\"witness\\\":null}\",\"rev_reg_def_json\":\"\",\"cred_def_id\":\"2hoqvcwupRTUNkXn6ArYzs:3:CL:1766\",\"msg_type\":\"CLAIM\",\"claim_offer_id\":\"1234\",\"version\":\"0.1\",\"from_did\":\"WRUzXXuFVTYkT8CjSZpFvT\"}",
+        "cred_id": "cred_id",
+        "payment_info": null,
+        "payment_txn": null,
+        "thread": {
+            "thid": "cCanHnpFAD",
+            "pthid": null,
+            "sender_order": 0,
+            "received_orders": {}
+        }
+    }
+}"#;
+
+// Pre-Aries credential offer which was received but not answered, the protocol was not chosen yet
+pub const LEGACY_CREDENTIAL_PENDING: &str = r#"
+{
+    "version": "3.0",
+    "data": {
+        "source_id": "credential-pending",
+        "state": 3,
+        "credential_name": null,
+        "credential_request": null,
+        "credential_offer": {
+            "msg_type": "CRED_OFFER",
+            "version": "0.1",
+            "to_did": "8XFh8yBzrpJQmNyZzgoTqB",
+            "from_did": "WRUzXXuFVTYkT8CjSZpFvT",
+            "libindy_offer": "{}",
+            "cred_def_id": "2hoqvcwupRTUNkXn6ArYzs:3:CL:1766",
+            "credential_attrs": {"age": "111"},
+            "schema_seq_no": 0,
+            "claim_name": "credential_name",
+            "claim_id": "1234",
+            "msg_ref_id": null
+        },
+        "msg_uid": "",
+        "agent_did": "",
+        "agent_vk": "",
+        "my_did": "",
+        "my_vk": "",
+        "their_did": "",
+        "their_vk": "",
+        "credential": null,
+        "cred_id": null,
+        "payment_info": null,
+        "payment_txn": null,
+        "thread": null
+    }
+}"#;
+
+// Pre-Aries credential issued by the issuer
+pub const LEGACY_ISSUER_CREDENTIAL_ACCEPTED: &str = r#"
+{
+    "version": "1.0",
+    "data": {
+        "source_id": "issuer-credential-legacy",
+        "credential_attributes": "{\"age\":[\"111\"],\"name\":[\"Bob\"]}",
+        "msg_uid": "mmi3yze",
+        "schema_seq_no": 0,
+        "issuer_did": "2hoqvcwupRTUNkXn6ArYzs",
+        "state": 4,
+        "credential_request": null,
+        "credential_offer": null,
+        "credential_name": "credential_name",
+        "credential_id": "3622610954",
+        "ref_msg_id": null,
+        "cred_def_id": "2hoqvcwupRTUNkXn6ArYzs:3:CL:1766",
+        "cred_def_handle": 0,
+        "rev_reg_id": "2hoqvcwupRTUNkXn6ArYzs:4:2hoqvcwupRTUNkXn6ArYzs:3:CL:1766:tag1:CL_ACCUM:tag1",
+        "tails_file": "/tmp/tails",
+        "cred_rev_id": "1",
+        "price": 0,
+        "payment_address": null,
+        "remote_did": "8XFh8yBzrpJQmNyZzgoTqB",
+        "remote_vk": "EkVTa7SCJ5SntpYyX7CSb2pcBhiVGT9kWSagA8a9T69A",
+        "agent_did": "U5LXs4U7P9msh647kToezy",
+        "agent_vk": "FktSZg8idAVzyQZrdUppK6FTrfAzW3wWVzAjJAfdUvJq",
+        "thread": {
+            "thid": "cCanHnpFAD",
+            "pthid": null,
+            "sender_order": 0,
+            "received_orders": {}
+        }
+    }
+}"#;
+
+// Pre-Aries proof which was received and validated by the verifier
+pub const LEGACY_PROOF_ACCEPTED: &str = r#"
+{
+    "version": "1.0",
+    "data": {
+        "source_id": "proof-legacy",
+        "requested_attrs": "[{\"name\":\"age\"}]",
+        "requested_predicates": "[]",
+        "msg_uid": "1234",
+        "ref_msg_id": "",
+        "prover_did": "8XFh8yBzrpJQmNyZzgoTqB",
+        "prover_vk": "EkVTa7SCJ5SntpYyX7CSb2pcBhiVGT9kWSagA8a9T69A",
+        "state": 4,
+        "proof_state": 1,
+        "name": "proof_req_1",
+        "version": "1.0",
+        "nonce": "123432421212",
+        "proof": {
+            "version": null,
+            "to_did": null,
+            "from_did": "8XFh8yBzrpJQmNyZzgoTqB",
+            "proof_request_id": null,
+            "libindy_proof": "{\"proof\":{\"proofs\":[],\"aggregated_proof\":{\"c_hash\":\"53950352519396118727110126559791411098081490918154654750464505328600997082590\",\"c_list\":[]}},\"requested_proof\":{\"revealed_attrs\":{\"age_1\":{\"sub_proof_index\":0,\"raw\":\"111\",\"encoded\":\"111\"}},\"self_attested_attrs\":{},\"unrevealed_attrs\":{},\"predicates\":{}},\"identifiers\":[{\"schema_id\":\"2hoqvcwupRTUNkXn6ArYzs:2:schema_name:0.0.11\",\"cred_def_id\":\"2hoqvcwupRTUNkXn6ArYzs:3:CL:1766\",\"rev_reg_id\":null,\"timestamp\":null}]}"
+        },
+        "proof_request": {
+            "@type": {"name": "PROOF_REQUEST", "version": "1.0"},
+            "@topic": {"mid": 0, "tid": 0},
+            "proof_request_data": {
+                "nonce": "123432421212",
+                "name": "proof_req_1",
+                "version": "0.1",
+                "requested_attributes": {
+                    "age_1": {
+                        "name": "age",
+                        "restrictions": [{"schema_id": null, "schema_issuer_did": null, "schema_name": null, "schema_version": null, "issuer_did": "2hoqvcwupRTUNkXn6ArYzs", "cred_def_id": null}]
+                    }
+                },
+                "requested_predicates": {}
+            },
+            "msg_ref_id": null
+        },
+        "remote_did": "8XFh8yBzrpJQmNyZzgoTqB",
+        "remote_vk": "EkVTa7SCJ5SntpYyX7CSb2pcBhiVGT9kWSagA8a9T69A",
+        "agent_did": "U5LXs4U7P9msh647kToezy",
+        "agent_vk": "FktSZg8idAVzyQZrdUppK6FTrfAzW3wWVzAjJAfdUvJq",
+        "revocation_interval": {},
+        "thread": {
+            "thid": "ef3b9b3d-d446-4d82-a3f2-a9a0d4e58d53",
+            "pthid": null,
+            "sender_order": 0,
+            "received_orders": {}
+        }
+    }
+}"#;
+
+// Pre-Aries proof which was generated and sent by the prover
+pub const LEGACY_DISCLOSED_PROOF_ACCEPTED: &str = r#"
+{
+    "version": "1.0",
+    "data": {
+        "source_id": "disclosed-proof-legacy",
+        "my_did": "8XFh8yBzrpJQmNyZzgoTqB",
+        "my_vk": "EkVTa7SCJ5SntpYyX7CSb2pcBhiVGT9kWSagA8a9T69A",
+        "state": 4,
+        "proof_request": {
+            "@type": {"name": "PROOF_REQUEST", "version": "1.0"},
+            "@topic": {"mid": 0, "tid": 0},
+            "proof_request_data": {
+                "nonce": "123432421212",
+                "name": "proof_req_1",
+                "version": "0.1",
+                "requested_attributes": {
+                    "age_1": {"name": "age"}
+                },
+                "requested_predicates": {}
+            },
+            "msg_ref_id": "ymy5nth"
+        },
+        "proof": {
+            "version": null,
+            "to_did": null,
+            "from_did": "8XFh8yBzrpJQmNyZzgoTqB",
+            "proof_request_id": null,
+            "libindy_proof": "{\"proof\":{\"proofs\":[],\"aggregated_proof\":{\"c_hash\":\"53950352519396118727110126559791411098081490918154654750464505328600997082590\",\"c_list\":[]}},\"requested_proof\":{\"revealed_attrs\":{\"age_1\":{\"sub_proof_index\":0,\"raw\":\"111\",\"encoded\":\"111\"}},\"self_attested_attrs\":{},\"unrevealed_attrs\":{},\"predicates\":{}},\"identifiers\":[]}"
+        },
+        "link_secret_alias": "main",
+        "their_did": "WRUzXXuFVTYkT8CjSZpFvT",
+        "their_vk": "ESE6MnqAyjRigduPG454vfLvKhMbmaZjy9vqxCnSKQnp",
+        "agent_did": "U5LXs4U7P9msh647kToezy",
+        "agent_vk": "FktSZg8idAVzyQZrdUppK6FTrfAzW3wWVzAjJAfdUvJq",
+        "thread": {
+            "thid": "ef3b9b3d-d446-4d82-a3f2-a9a0d4e58d53",
+            "pthid": null,
+            "sender_order": 0,
+            "received_orders": {}
+        }
+    }
+}"#;
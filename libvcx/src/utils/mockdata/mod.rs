@@ -2,4 +2,5 @@ pub mod mockdata_credex;
 pub mod mockdata_connection;
 pub mod mockdata_proof;
 pub mod mockdata_agency;
+pub mod mockdata_legacy;
 pub mod mock_settings;
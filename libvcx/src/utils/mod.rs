@@ -41,6 +41,7 @@ macro_rules! map (
 
 pub mod error;
 pub mod httpclient;
+pub mod transport;
 pub mod constants;
 pub mod timeout;
 pub mod openssl;
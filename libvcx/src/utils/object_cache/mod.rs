@@ -63,6 +63,17 @@ impl<T> ObjectCache<T> {
         }
     }
 
+    /**
+    Applies the closure to every stored object, objects which cannot be locked are skipped.
+     */
+    pub fn get_all<F, R>(&self, closure: F) -> VcxResult<Vec<(u32, R)>>
+        where F: Fn(&T) -> R {
+        let store = self._lock_store()?;
+        Ok(store.iter()
+            .filter_map(|(handle, m)| m.lock().ok().map(|obj| (*handle, closure(obj.deref()))))
+            .collect())
+    }
+
     pub fn add(&self, obj: T) -> VcxResult<u32> {
         let mut store = self._lock_store()?;
 
@@ -164,4 +175,20 @@ mod tests {
 
         assert_eq!("TEST", string);
     }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn get_all_test() {
+        let _setup = SetupDefaults::init();
+
+        let test: ObjectCache<u32> = ObjectCache::new("cache-get-all-u32");
+        let handle1 = test.add(1111).unwrap();
+        let handle2 = test.add(2222).unwrap();
+
+        let mut all = test.get_all(|obj| obj + 1).unwrap();
+        all.sort();
+        let mut expected = vec![(handle1, 1112), (handle2, 2223)];
+        expected.sort();
+        assert_eq!(expected, all);
+    }
 }
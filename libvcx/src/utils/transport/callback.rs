@@ -0,0 +1,95 @@
+use std::collections::HashMap;
+use std::sync::Mutex;
+use std::sync::mpsc::{channel, Sender};
+
+use indy_sys::CommandHandle;
+use libc::c_char;
+
+use error::prelude::*;
+use utils::cstring::CStringUtils;
+use utils::libindy::next_command_handle;
+use utils::timeout::TimeoutUtils;
+use utils::transport::Transport;
+
+pub type TransportSendCB = extern fn(xcommand_handle: CommandHandle, send_handle: CommandHandle,
+                                     endpoint: *const c_char, message: *const u8, message_len: u32);
+
+lazy_static! {
+    static ref PENDING_SENDS: Mutex<HashMap<CommandHandle, Sender<VcxResult<Vec<u8>>>>> = Mutex::new(HashMap::new());
+}
+
+/// Transport implemented by the application.
+/// Messages are passed to its callback and the answer is awaited until the application sets it with `set_response`.
+pub struct CallbackTransport {
+    scheme: String,
+    command_handle: CommandHandle,
+    cb: TransportSendCB,
+}
+
+impl CallbackTransport {
+    pub fn new(scheme: &str, command_handle: CommandHandle, cb: TransportSendCB) -> CallbackTransport {
+        CallbackTransport { scheme: scheme.to_string(), command_handle, cb }
+    }
+}
+
+impl Transport for CallbackTransport {
+    fn schemes(&self) -> Vec<String> {
+        vec![self.scheme.clone()]
+    }
+
+    fn send(&self, message: &[u8], endpoint: &str) -> VcxResult<Vec<u8>> {
+        let (sender, receiver) = channel();
+
+        let send_handle = next_command_handle();
+        PENDING_SENDS.lock().unwrap().insert(send_handle, sender);
+
+        let endpoint = CStringUtils::string_to_cstring(endpoint.to_string());
+        (self.cb)(self.command_handle, send_handle, endpoint.as_ptr(), message.as_ptr(), message.len() as u32);
+
+        let response = receiver.recv_timeout(TimeoutUtils::long_timeout());
+
+        PENDING_SENDS.lock().unwrap().remove(&send_handle);
+
+        response.map_err(|_| VcxError::from_msg(VcxErrorKind::PostMessageFailed, format!("Transport of scheme {} did not answer in time", self.scheme)))?
+    }
+}
+
+/**
+Completes the send started by `CallbackTransport` with the answer of the endpoint or with the error.
+ */
+pub fn set_response(send_handle: CommandHandle, response: VcxResult<Vec<u8>>) -> VcxResult<()> {
+    let sender = PENDING_SENDS.lock().unwrap()
+        .remove(&send_handle)
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidHandle, format!("No message is being sent with handle {}", send_handle)))?;
+
+    sender.send(response)
+        .map_err(|_| VcxError::from_msg(VcxErrorKind::InvalidHandle, format!("Sending of message with handle {} has already finished", send_handle)))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Arc;
+    use std::thread;
+
+    use utils::devsetup::SetupEmpty;
+    use utils::libindy::callback::build_buf;
+    use utils::transport;
+
+    use super::*;
+
+    extern fn _echo_cb(_command_handle: CommandHandle, send_handle: CommandHandle, _endpoint: *const c_char, message: *const u8, message_len: u32) {
+        let message = build_buf(message, message_len);
+        thread::spawn(move || set_response(send_handle, Ok(message)).unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_callback_transport_works() {
+        let _setup = SetupEmpty::init();
+
+        transport::register_transport(Arc::new(CallbackTransport::new("vcx-callback", 1, _echo_cb)));
+
+        assert_eq!(b"message".to_vec(), transport::send(b"message", "vcx-callback://endpoint").unwrap());
+        assert_eq!(VcxErrorKind::InvalidHandle, set_response(0, Ok(Vec::new())).unwrap_err().kind());
+    }
+}
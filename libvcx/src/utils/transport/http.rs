@@ -0,0 +1,70 @@
+use std::io::Read;
+
+use reqwest;
+use reqwest::header::CONTENT_TYPE;
+
+use error::prelude::*;
+use utils::transport::Transport;
+
+pub struct HttpTransport;
+
+impl Transport for HttpTransport {
+    fn schemes(&self) -> Vec<String> {
+        vec![String::from("http"), String::from("https")]
+    }
+
+    fn send(&self, message: &[u8], endpoint: &str) -> VcxResult<Vec<u8>> {
+        //Setting SSL Certs location. This is needed on android platform. Or openssl will fail to verify the certs
+        #[cfg(target_os = "android")]
+        set_ssl_cert_location()?;
+
+        let client = reqwest::ClientBuilder::new().timeout(::utils::timeout::TimeoutUtils::long_timeout()).build().map_err(|err| {
+            error!("error: {}", err);
+            VcxError::from_msg(VcxErrorKind::PostMessageFailed, format!("Building reqwest client failed: {:?}", err))
+        })?;
+        debug!("Posting encrypted bundle to: \"{}\"", endpoint);
+
+        let mut response =
+            client.post(endpoint)
+                .body(message.to_vec())
+                .header(CONTENT_TYPE, "application/ssi-agent-wire")
+                .send()
+                .map_err(|err| {
+                    error!("error: {}", err);
+                    VcxError::from_msg(VcxErrorKind::PostMessageFailed, format!("Could not connect {:?}", err))
+                })?;
+
+        trace!("Response Header: {:?}", response);
+        if !response.status().is_success() {
+            let mut content = String::new();
+            match response.read_to_string(&mut content) {
+                Ok(_) => info!("Request failed: {}", content),
+                Err(_) => info!("could not read response"),
+            };
+            return Err(VcxError::from_msg(VcxErrorKind::PostMessageFailed, format!("POST failed with: {}", content)));
+        }
+
+        let mut content = Vec::new();
+        response.read_to_end(&mut content)
+            .or(Err(VcxError::from_msg(VcxErrorKind::PostMessageFailed, "could not read response")))?;
+
+        Ok(content)
+    }
+}
+
+#[cfg(target_os = "android")]
+fn set_ssl_cert_location() -> VcxResult<()> {
+    use std::env;
+
+    info!("::Android code");
+
+    let external_storage = env::var("EXTERNAL_STORAGE")
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::PostMessageFailed,
+                                          format!("EXTERNAL_STORAGE is not set, it is needed on Android to make https calls: {:?}. \
+                                                   See https://github.com/seanmonstar/reqwest/issues/70 for more info", err)))?;
+
+    env::set_var("SSL_CERT_FILE", external_storage + "/cacert.pem");
+    info!("::SSL_CERT_FILE has been set");
+
+    Ok(())
+}
@@ -0,0 +1,119 @@
+use std::io::Read;
+use std::sync::Arc;
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::thread;
+use std::time::Duration;
+
+use tiny_http::{Method, Request, Response, Server, StatusCode};
+
+use error::prelude::*;
+
+const POLL_INTERVAL: Duration = Duration::from_millis(200);
+
+/// Inbound HTTP transport accepting packed messages posted to the given address.
+/// Every request body is passed to the handler, the request is answered with 202 on success and 400 otherwise.
+pub struct Listener {
+    address: String,
+    stopped: Arc<AtomicBool>,
+    thread: thread::JoinHandle<()>,
+}
+
+impl Listener {
+    pub fn start<F>(address: &str, handler: F) -> VcxResult<Listener>
+        where F: Fn(&[u8]) -> VcxResult<()> + Send + 'static {
+        trace!("Listener::start >>> address: {}", address);
+
+        let server = Server::http(address)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidConfiguration, format!("Cannot listen on {}: {}", address, err)))?;
+
+        let stopped = Arc::new(AtomicBool::new(false));
+        let stopped_ = stopped.clone();
+
+        let thread = thread::Builder::new()
+            .name(format!("vcx-inbound-{}", address))
+            .spawn(move || {
+                while !stopped_.load(Ordering::SeqCst) {
+                    match server.recv_timeout(POLL_INTERVAL) {
+                        Ok(Some(request)) => Listener::handle(request, &handler),
+                        Ok(None) => {}
+                        Err(err) => error!("Listener >>> cannot receive request: {}", err)
+                    }
+                }
+            })
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::UnknownError, format!("Cannot start listener thread: {}", err)))?;
+
+        Ok(Listener { address: address.to_string(), stopped, thread })
+    }
+
+    pub fn address(&self) -> &str {
+        &self.address
+    }
+
+    pub fn stop(self) {
+        trace!("Listener::stop >>> address: {}", self.address);
+
+        self.stopped.store(true, Ordering::SeqCst);
+        self.thread.join().ok();
+    }
+
+    fn handle<F>(mut request: Request, handler: &F) where F: Fn(&[u8]) -> VcxResult<()> {
+        if *request.method() != Method::Post {
+            request.respond(Response::empty(StatusCode(405))).ok();
+            return;
+        }
+
+        let mut body = Vec::new();
+
+        let status = match request.as_reader().read_to_end(&mut body) {
+            Ok(_) => match handler(&body) {
+                Ok(()) => 202,
+                Err(err) => {
+                    warn!("Listener >>> cannot process inbound message: {}", err);
+                    400
+                }
+            },
+            Err(err) => {
+                warn!("Listener >>> cannot read request body: {}", err);
+                400
+            }
+        };
+
+        request.respond(Response::empty(StatusCode(status))).ok();
+    }
+}
+
+#[cfg(test)]
+pub mod tests {
+    use std::sync::Mutex;
+
+    use utils::devsetup::SetupEmpty;
+    use utils::transport::http::HttpTransport;
+    use utils::transport::Transport;
+
+    use super::*;
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_listener_passes_posted_messages_to_handler() {
+        let _setup = SetupEmpty::init();
+
+        let received = Arc::new(Mutex::new(Vec::new()));
+        let received_ = received.clone();
+
+        let listener = Listener::start("127.0.0.1:18543", move |message| {
+            if message == b"invalid" {
+                return Err(VcxError::from(VcxErrorKind::InvalidJson));
+            }
+            received_.lock().unwrap().push(message.to_vec());
+            Ok(())
+        }).unwrap();
+
+        HttpTransport.send(b"message", "http://127.0.0.1:18543").unwrap();
+        assert_eq!(VcxErrorKind::PostMessageFailed, HttpTransport.send(b"invalid", "http://127.0.0.1:18543").unwrap_err().kind());
+
+        listener.stop();
+
+        assert_eq!(vec![b"message".to_vec()], *received.lock().unwrap());
+        assert!(HttpTransport.send(b"message", "http://127.0.0.1:18543").is_err());
+    }
+}
@@ -0,0 +1,48 @@
+use std::sync::Mutex;
+
+use error::prelude::*;
+use utils::httpclient::{AgencyMock, AgencyMockDecrypted, HttpClientMockResponse};
+use utils::transport::Transport;
+
+pub const MEMORY_SCHEME: &str = "memory";
+
+lazy_static! {
+    static ref SENT_MESSAGES: Mutex<Vec<(String, Vec<u8>)>> = Mutex::new(Vec::new());
+}
+
+/// In-memory transport used in tests, registered for `memory://` endpoints.
+/// It keeps sent messages and answers with responses mocked with `HttpClientMockResponse` and `AgencyMock`.
+pub struct MemoryTransport;
+
+impl MemoryTransport {
+    /// Returns endpoints and messages sent since the last call.
+    pub fn take_sent_messages() -> Vec<(String, Vec<u8>)> {
+        SENT_MESSAGES.lock().unwrap().drain(..).collect()
+    }
+
+    pub fn clear() {
+        SENT_MESSAGES.lock().unwrap().clear();
+    }
+}
+
+impl Transport for MemoryTransport {
+    fn schemes(&self) -> Vec<String> {
+        vec![String::from(MEMORY_SCHEME)]
+    }
+
+    fn send(&self, message: &[u8], endpoint: &str) -> VcxResult<Vec<u8>> {
+        SENT_MESSAGES.lock().unwrap().push((endpoint.to_string(), message.to_vec()));
+
+        if HttpClientMockResponse::has_response() {
+            warn!("HttpClient has mocked response");
+            return HttpClientMockResponse::get_response();
+        }
+        if AgencyMockDecrypted::has_decrypted_mock_responses() {
+            warn!("Agency requests returns empty response, decrypted mock response is available");
+            return Ok(vec!());
+        }
+        let mocked_response = AgencyMock::get_response();
+        debug!("Agency returns mocked response of length {}", mocked_response.len());
+        Ok(mocked_response)
+    }
+}
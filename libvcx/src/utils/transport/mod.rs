@@ -0,0 +1,151 @@
+use std::collections::HashMap;
+use std::sync::{Arc, Once, RwLock};
+
+use url::Url;
+
+use error::prelude::*;
+use settings;
+
+pub mod callback;
+pub mod http;
+#[cfg(feature = "inbound_listener")]
+pub mod listener;
+pub mod memory;
+#[cfg(feature = "ws_transport")]
+pub mod ws;
+
+use self::http::HttpTransport;
+use self::memory::{MEMORY_SCHEME, MemoryTransport};
+#[cfg(feature = "ws_transport")]
+use self::ws::WsTransport;
+
+/// Outbound transport delivering packed messages to an endpoint.
+/// The transport is chosen by the scheme of the endpoint, i.e. DIDDoc service endpoint or agency endpoint.
+pub trait Transport: Send + Sync {
+    /// URL schemes the transport is able to deliver to.
+    fn schemes(&self) -> Vec<String>;
+
+    /// Sends the message and returns the response body, empty if the endpoint did not answer.
+    fn send(&self, message: &[u8], endpoint: &str) -> VcxResult<Vec<u8>>;
+}
+
+static BUILTIN_INIT: Once = Once::new();
+
+lazy_static! {
+    static ref TRANSPORTS: RwLock<HashMap<String, Arc<dyn Transport>>> = RwLock::new(HashMap::new());
+}
+
+/**
+Registers built-in HTTP(S), in-memory and, with `ws_transport` feature, WebSocket transports.
+Transports registered before for the same schemes are kept.
+ */
+pub fn init() {
+    #[allow(unused_mut)]
+    let mut builtin: Vec<Arc<dyn Transport>> = vec![Arc::new(HttpTransport), Arc::new(MemoryTransport)];
+
+    #[cfg(feature = "ws_transport")]
+    builtin.push(Arc::new(WsTransport));
+
+    let mut transports = TRANSPORTS.write().unwrap();
+
+    for transport in builtin {
+        for scheme in transport.schemes() {
+            transports.entry(scheme).or_insert(transport.clone());
+        }
+    }
+}
+
+/**
+Registers the transport for all the schemes it supports replacing the transports registered before.
+ */
+pub fn register_transport(transport: Arc<dyn Transport>) {
+    let mut transports = TRANSPORTS.write().unwrap();
+
+    for scheme in transport.schemes() {
+        transports.insert(scheme, transport.clone());
+    }
+}
+
+/**
+Sends the message to the endpoint using the transport registered for its scheme.
+ */
+pub fn send(message: &[u8], endpoint: &str) -> VcxResult<Vec<u8>> {
+    get_transport(endpoint)?.send(message, endpoint)
+}
+
+/**
+Returns the transport registered for the scheme of the endpoint.
+If agency mocks are enabled, HTTP(S) and WebSocket endpoints are served by the in-memory transport.
+ */
+fn get_transport(endpoint: &str) -> VcxResult<Arc<dyn Transport>> {
+    let url = Url::parse(endpoint)
+        .map_err(|err| VcxError::from_msg(VcxErrorKind::InvalidUrl, format!("Invalid endpoint {}: {:?}", endpoint, err)))?;
+
+    BUILTIN_INIT.call_once(init);
+
+    let scheme = match url.scheme() {
+        "http" | "https" | "ws" | "wss" if settings::agency_mocks_enabled() => MEMORY_SCHEME,
+        scheme => scheme
+    };
+
+    TRANSPORTS.read().unwrap()
+        .get(scheme)
+        .cloned()
+        .ok_or(VcxError::from_msg(VcxErrorKind::InvalidUrl, format!("No transport is registered for scheme: {}", scheme)))
+}
+
+#[cfg(test)]
+pub mod tests {
+    use utils::devsetup::{SetupAriesMocks, SetupEmpty};
+
+    use super::*;
+
+    struct TestTransport;
+
+    impl Transport for TestTransport {
+        fn schemes(&self) -> Vec<String> {
+            vec![String::from("vcx-test")]
+        }
+
+        fn send(&self, message: &[u8], _endpoint: &str) -> VcxResult<Vec<u8>> {
+            Ok(message.to_vec())
+        }
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_transport_is_chosen_by_scheme() {
+        let _setup = SetupEmpty::init();
+
+        init();
+
+        assert_eq!(vec!["http", "https"], get_transport("https://agency.example.com/agency/msg").unwrap().schemes());
+        #[cfg(feature = "ws_transport")]
+        assert_eq!(vec!["ws", "wss"], get_transport("ws://localhost:8080").unwrap().schemes());
+        #[cfg(not(feature = "ws_transport"))]
+        assert_eq!(VcxErrorKind::InvalidUrl, get_transport("ws://localhost:8080").err().unwrap().kind());
+        assert_eq!(vec!["memory"], get_transport("memory://agent").unwrap().schemes());
+        assert_eq!(VcxErrorKind::InvalidUrl, get_transport("ftp://localhost:8080").err().unwrap().kind());
+        assert_eq!(VcxErrorKind::InvalidUrl, get_transport("localhost").err().unwrap().kind());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_register_transport_works() {
+        let _setup = SetupEmpty::init();
+
+        register_transport(Arc::new(TestTransport));
+
+        assert_eq!(b"message".to_vec(), send(b"message", "vcx-test://endpoint").unwrap());
+    }
+
+    #[test]
+    #[cfg(feature = "general_test")]
+    fn test_send_uses_memory_transport_when_mocks_enabled() {
+        let _setup = SetupAriesMocks::init();
+
+        send(b"message", "ws://localhost:8080").unwrap();
+
+        assert_eq!(vec![(String::from("ws://localhost:8080"), b"message".to_vec())], MemoryTransport::take_sent_messages());
+    }
+}
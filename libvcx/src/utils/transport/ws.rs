@@ -0,0 +1,53 @@
+use std::io;
+use std::net::TcpStream;
+
+use tungstenite;
+use tungstenite::Message;
+use tungstenite::client::AutoStream;
+use tungstenite::stream::Stream;
+
+use error::prelude::*;
+use utils::timeout::TimeoutUtils;
+use utils::transport::Transport;
+
+pub struct WsTransport;
+
+impl Transport for WsTransport {
+    fn schemes(&self) -> Vec<String> {
+        vec![String::from("ws"), String::from("wss")]
+    }
+
+    fn send(&self, message: &[u8], endpoint: &str) -> VcxResult<Vec<u8>> {
+        debug!("Sending encrypted bundle over WebSocket to: \"{}\"", endpoint);
+
+        let (mut socket, _) = tungstenite::connect(endpoint)
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::PostMessageFailed, format!("Could not connect {:?}", err)))?;
+
+        _tcp_stream(socket.get_ref())
+            .set_read_timeout(Some(TimeoutUtils::short_timeout()))
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::PostMessageFailed, format!("Could not set read timeout {:?}", err)))?;
+
+        socket.write_message(Message::Binary(message.to_vec()))
+            .map_err(|err| VcxError::from_msg(VcxErrorKind::PostMessageFailed, format!("Could not send message {:?}", err)))?;
+
+        // the endpoint answers over the socket only when return route is requested
+        let response = match socket.read_message() {
+            Ok(Message::Binary(data)) => data,
+            Ok(Message::Text(data)) => data.into_bytes(),
+            Ok(_) => Vec::new(),
+            Err(tungstenite::Error::Io(ref err)) if err.kind() == io::ErrorKind::WouldBlock || err.kind() == io::ErrorKind::TimedOut => Vec::new(),
+            Err(err) => return Err(VcxError::from_msg(VcxErrorKind::PostMessageFailed, format!("Could not read response {:?}", err)))
+        };
+
+        socket.close(None).ok();
+
+        Ok(response)
+    }
+}
+
+fn _tcp_stream(stream: &AutoStream) -> &TcpStream {
+    match stream {
+        Stream::Plain(stream) => stream,
+        Stream::Tls(stream) => stream.get_ref(),
+    }
+}
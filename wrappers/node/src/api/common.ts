@@ -115,7 +115,9 @@ export enum StateType {
     Expired = 6,
     Revoked = 7,
     Redirected = 8,
-    Rejected = 9
+    Rejected = 9,
+    ProposalReceived = 10,
+    ProposalSent = 11
 }
 
 export interface IInitVCXOptions {
@@ -12,6 +12,8 @@ class State(IntEnum):
     Revoked = 7,
     Redirected = 8,
     Rejected = 9,
+    ProposalReceived = 10,
+    ProposalSent = 11,
 
 
 class ProofState(IntEnum):